@@ -63,6 +63,10 @@ pub struct WebCommArgs {
     #[clap(long = "db_url", env = "DATABASE_URL")]
     pub database_path: Option<String>,
 
+    /// Metric database directory [default: /root/.landscape-router/metric]
+    #[clap(long = "metric_path", env = "LANDSCAPE_METRIC_PATH")]
+    pub metric_path: Option<PathBuf>,
+
     /// ebpf map space
     /// [default: default]
     #[clap(long, env = "LANDSCAPE_EBPF_MAP_SPACE", default_value = "default")]
@@ -72,7 +76,7 @@ pub struct WebCommArgs {
     #[clap(long = "user", env = "LANDSCAPE_ADMIN_USER")]
     pub admin_user: Option<String>,
 
-    /// Manager pass [default: root]
+    /// Manager pass, only used to create the admin user on first start [default: root]
     #[clap(long = "pass", env = "LANDSCAPE_ADMIN_PASS")]
     pub admin_pass: Option<String>,
 
@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::database::repository::LandscapeDBStore;
+use crate::utils::id::gen_database_uuid;
+use crate::utils::time::get_f64_timestamp;
+
+/// 保留的审计日志数量上限
+pub const MAX_AUDIT_LOGS: u64 = 20000;
+
+/// 单次查询返回的默认条数
+pub const DEFAULT_AUDIT_QUERY_LIMIT: u64 = 200;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum AuditLogKind {
+    Login,
+    LoginFailed,
+    /// 修改类 API 调用
+    Api,
+}
+
+impl AuditLogKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditLogKind::Login => "login",
+            AuditLogKind::LoginFailed => "login_failed",
+            AuditLogKind::Api => "api",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AuditLog {
+    #[serde(default = "gen_database_uuid")]
+    #[cfg_attr(feature = "openapi", schema(required = false))]
+    pub id: Uuid,
+    pub kind: AuditLogKind,
+    pub username: String,
+    pub source_ip: Option<String>,
+    pub method: String,
+    pub route: String,
+    /// 路径中的配置 ID
+    pub config_id: Option<String>,
+    pub status: u16,
+    /// 修改前的配置快照
+    pub before: Option<Uuid>,
+    /// 修改后的配置快照
+    pub after: Option<Uuid>,
+    /// 变更摘要或登录失败原因
+    pub summary: String,
+    /// 记录时间
+    #[serde(default = "get_f64_timestamp")]
+    #[cfg_attr(feature = "openapi", schema(required = false))]
+    pub update_at: f64,
+}
+
+impl LandscapeDBStore<Uuid> for AuditLog {
+    fn get_id(&self) -> Uuid {
+        self.id
+    }
+    fn get_update_at(&self) -> f64 {
+        self.update_at
+    }
+    fn set_update_at(&mut self, ts: f64) {
+        self.update_at = ts;
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema, utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct AuditLogQuery {
+    pub username: Option<String>,
+    pub kind: Option<AuditLogKind>,
+    pub source_ip: Option<String>,
+    /// 起始时间 (ms)
+    pub start: Option<f64>,
+    /// 结束时间 (ms)
+    pub end: Option<f64>,
+    pub limit: Option<u64>,
+}
@@ -1,10 +1,16 @@
 use serde::{Deserialize, Serialize};
 
+pub mod audit;
+pub mod user;
+
 #[derive(Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct LoginInfo {
     pub username: String,
     pub password: String,
+    /// 启用两步验证的用户需要提供 TOTP 验证码
+    #[serde(default)]
+    pub totp_code: Option<String>,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -0,0 +1,233 @@
+use landscape_macro::LdApiError;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::database::repository::LandscapeDBStore;
+use crate::utils::id::gen_database_uuid;
+use crate::utils::time::get_f64_timestamp;
+
+/// 密码最短长度
+pub const MIN_PASSWORD_LENGTH: usize = 6;
+
+/// API Token 的前缀, 用于与 JWT 区分
+pub const API_TOKEN_PREFIX: &str = "ldt_";
+
+#[derive(thiserror::Error, Debug, LdApiError)]
+#[api_error(crate_path = "crate")]
+pub enum UserError {
+    #[error("User '{0}' not found")]
+    #[api_error(id = "user.not_found", status = 404)]
+    NotFound(Uuid),
+
+    #[error("Username '{0}' already exists")]
+    #[api_error(id = "user.username_exists", status = 409)]
+    UsernameExists(String),
+
+    #[error("Invalid username '{0}'")]
+    #[api_error(id = "user.invalid_username", status = 400)]
+    InvalidUsername(String),
+
+    #[error("Password must be at least {0} characters")]
+    #[api_error(id = "user.password_too_short", status = 400)]
+    PasswordTooShort(usize),
+
+    #[error("Current password is incorrect")]
+    #[api_error(id = "user.wrong_password", status = 400)]
+    WrongPassword,
+
+    #[error("TOTP has not been enrolled")]
+    #[api_error(id = "user.totp_not_enrolled", status = 400)]
+    TotpNotEnrolled,
+
+    #[error("Invalid TOTP code")]
+    #[api_error(id = "user.invalid_totp_code", status = 400)]
+    InvalidTotpCode,
+
+    #[error("At least one enabled admin must remain")]
+    #[api_error(id = "user.last_admin", status = 409)]
+    LastAdmin,
+
+    #[error("API token '{0}' not found")]
+    #[api_error(id = "user.token_not_found", status = 404)]
+    TokenNotFound(Uuid),
+
+    #[error("Token scope '{0:?}' exceeds the role of its owner")]
+    #[api_error(id = "user.token_scope_exceeded", status = 403)]
+    TokenScopeExceeded(UserRole),
+}
+
+/// 用户角色, 按权限从低到高排序
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum UserRole {
+    /// 只能查看
+    #[default]
+    ReadOnly,
+    /// 可以修改配置
+    Operator,
+    /// 可以修改配置并管理用户
+    Admin,
+}
+
+impl UserRole {
+    pub fn can_write(&self) -> bool {
+        *self >= UserRole::Operator
+    }
+
+    pub fn is_admin(&self) -> bool {
+        *self == UserRole::Admin
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LandscapeUser {
+    #[serde(default = "gen_database_uuid")]
+    #[cfg_attr(feature = "openapi", schema(required = false))]
+    pub id: Uuid,
+    pub username: String,
+    /// 密码哈希, 不通过 API 输出
+    #[serde(default, skip_serializing)]
+    #[cfg_attr(feature = "openapi", schema(ignore))]
+    pub password_hash: String,
+    pub role: UserRole,
+    pub enable: bool,
+    /// TOTP 密钥 (Base32), 登记后需验证一次才会启用
+    #[serde(default, skip_serializing)]
+    #[cfg_attr(feature = "openapi", schema(ignore))]
+    pub totp_secret: Option<String>,
+    /// 是否已启用两步验证
+    #[serde(default)]
+    pub totp_enable: bool,
+    /// 修改密码后递增, 之前签发的登录会话随之失效
+    #[serde(default, skip_serializing)]
+    #[cfg_attr(feature = "openapi", schema(ignore))]
+    pub token_version: u32,
+    #[serde(default = "get_f64_timestamp")]
+    #[cfg_attr(feature = "openapi", schema(required = false))]
+    pub update_at: f64,
+}
+
+impl LandscapeDBStore<Uuid> for LandscapeUser {
+    fn get_id(&self) -> Uuid {
+        self.id
+    }
+    fn get_update_at(&self) -> f64 {
+        self.update_at
+    }
+    fn set_update_at(&mut self, ts: f64) {
+        self.update_at = ts;
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateUserRequest {
+    pub username: String,
+    pub password: String,
+    pub role: UserRole,
+}
+
+/// 未提供的字段保持不变
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UpdateUserRequest {
+    #[serde(default)]
+    pub role: Option<UserRole>,
+    #[serde(default)]
+    pub enable: Option<bool>,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ChangePasswordRequest {
+    pub old_password: String,
+    pub new_password: String,
+}
+
+/// 登记 TOTP 时返回, 用于在验证器中添加账号
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TotpEnrollment {
+    pub secret: String,
+    /// `otpauth://` 链接, 可生成二维码
+    pub uri: String,
+}
+
+/// 已启用 TOTP 时重新登记需要提供当前验证码
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TotpEnrollRequest {
+    #[serde(default)]
+    pub code: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TotpCodeRequest {
+    pub code: String,
+}
+
+/// 长期有效的 API Token, 权限范围不超过所属用户的角色
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ApiToken {
+    #[serde(default = "gen_database_uuid")]
+    #[cfg_attr(feature = "openapi", schema(required = false))]
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    /// Token 的权限范围
+    pub role: UserRole,
+    /// Token 的 SHA-256 摘要, 原文仅在创建时返回一次
+    #[serde(default, skip_serializing)]
+    #[cfg_attr(feature = "openapi", schema(ignore))]
+    pub token_hash: String,
+    /// 过期时间 (ms), 为空时永不过期
+    pub expire_at: Option<u64>,
+    pub revoked: bool,
+    #[serde(default = "get_f64_timestamp")]
+    #[cfg_attr(feature = "openapi", schema(required = false))]
+    pub update_at: f64,
+}
+
+impl ApiToken {
+    pub fn is_valid(&self, now_ms: u64) -> bool {
+        !self.revoked && self.expire_at.map_or(true, |expire_at| now_ms < expire_at)
+    }
+}
+
+impl LandscapeDBStore<Uuid> for ApiToken {
+    fn get_id(&self) -> Uuid {
+        self.id
+    }
+    fn get_update_at(&self) -> f64 {
+        self.update_at
+    }
+    fn set_update_at(&mut self, ts: f64) {
+        self.update_at = ts;
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateApiTokenRequest {
+    pub name: String,
+    /// 默认与所属用户的角色相同
+    #[serde(default)]
+    pub role: Option<UserRole>,
+    /// 有效期 (秒), 为空时永不过期
+    #[serde(default)]
+    pub expire_secs: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateApiTokenResult {
+    pub token: ApiToken,
+    /// Token 原文, 仅返回这一次
+    pub secret: String,
+}
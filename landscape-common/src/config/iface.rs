@@ -1,4 +1,5 @@
 use crate::database::repository::LandscapeDBStore;
+use crate::service::ServiceConfigError;
 use crate::utils::time::get_f64_timestamp;
 use crate::{store::storev2::LandscapeStore, LANDSCAPE_DEFAULT_LAN_NAME};
 use sea_orm::{prelude::StringLen, DeriveActiveEnum, EnumIter};
@@ -15,6 +16,11 @@ pub struct NetworkIfaceConfig {
     #[cfg_attr(feature = "openapi", schema(required = true))]
     pub create_dev_type: CreateDevType,
 
+    /// VLAN / Bond / MACVLAN 等虚拟设备的创建参数
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub dev_params: Option<CreateDevParams>,
+
     // 是否有 master 使用 name 因为 Linux 中名称是唯一的
     pub controller_name: Option<String>,
 
@@ -78,6 +84,7 @@ impl NetworkIfaceConfig {
         NetworkIfaceConfig {
             name,
             create_dev_type: CreateDevType::Bridge,
+            dev_params: None,
             controller_name: None,
             enable_in_boot: true,
             zone_type: zone_type.unwrap_or_default(),
@@ -86,6 +93,20 @@ impl NetworkIfaceConfig {
             update_at: get_f64_timestamp(),
         }
     }
+
+    pub fn crate_virtual_dev(name: String, params: CreateDevParams) -> NetworkIfaceConfig {
+        NetworkIfaceConfig {
+            name,
+            create_dev_type: params.dev_type(),
+            dev_params: Some(params),
+            controller_name: None,
+            enable_in_boot: true,
+            zone_type: IfaceZoneType::default(),
+            wifi_mode: WifiMode::default(),
+            xps_rps: None,
+            update_at: get_f64_timestamp(),
+        }
+    }
 }
 
 /// 需要创建的设备类型
@@ -98,6 +119,156 @@ pub enum CreateDevType {
     #[default]
     NoNeedToCreate,
     Bridge,
+    Vlan,
+    Bond,
+    Macvlan,
+    Wireguard,
+}
+
+/// 虚拟设备创建参数
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(tag = "t", rename_all = "snake_case")]
+pub enum CreateDevParams {
+    /// 802.1Q VLAN 子接口
+    Vlan { parent: String, vlan_id: u16 },
+    /// 链路聚合, 成员网卡通过 controller_name 加入
+    Bond {
+        mode: BondMode,
+        #[serde(default = "default_bond_miimon")]
+        miimon: u32,
+        #[serde(default)]
+        #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+        xmit_hash_policy: Option<BondXmitHashPolicy>,
+    },
+    /// 基于父网卡的 MACVLAN
+    Macvlan { parent: String, mode: MacvlanMode },
+    /// WireGuard 隧道, 密钥与对端由 WireGuard 服务配置
+    Wireguard,
+}
+
+fn default_bond_miimon() -> u32 {
+    100
+}
+
+impl CreateDevParams {
+    pub fn dev_type(&self) -> CreateDevType {
+        match self {
+            CreateDevParams::Vlan { .. } => CreateDevType::Vlan,
+            CreateDevParams::Bond { .. } => CreateDevType::Bond,
+            CreateDevParams::Macvlan { .. } => CreateDevType::Macvlan,
+            CreateDevParams::Wireguard => CreateDevType::Wireguard,
+        }
+    }
+
+    /// 依赖的父网卡
+    pub fn parent(&self) -> Option<&str> {
+        match self {
+            CreateDevParams::Vlan { parent, .. } | CreateDevParams::Macvlan { parent, .. } => {
+                Some(parent)
+            }
+            CreateDevParams::Bond { .. } | CreateDevParams::Wireguard => None,
+        }
+    }
+
+    pub fn validate(&self, name: &str) -> Result<(), ServiceConfigError> {
+        // IFNAMSIZ 16, 包含结尾的 \0
+        if name.is_empty() || name.len() > 15 {
+            return Err(ServiceConfigError::InvalidConfig {
+                reason: format!("invalid interface name: '{name}'"),
+            });
+        }
+        if let Some(parent) = self.parent() {
+            if parent.is_empty() || parent == name {
+                return Err(ServiceConfigError::InvalidConfig {
+                    reason: format!("invalid parent interface: '{parent}'"),
+                });
+            }
+        }
+        match self {
+            CreateDevParams::Vlan { vlan_id, .. } => {
+                if *vlan_id == 0 || *vlan_id > 4094 {
+                    return Err(ServiceConfigError::InvalidConfig {
+                        reason: format!("vlan id must be in 1-4094, got {vlan_id}"),
+                    });
+                }
+            }
+            CreateDevParams::Bond { mode, xmit_hash_policy, .. } => {
+                if xmit_hash_policy.is_some() && matches!(mode, BondMode::ActiveBackup) {
+                    return Err(ServiceConfigError::InvalidConfig {
+                        reason: "xmit_hash_policy is not used in active-backup mode".into(),
+                    });
+                }
+            }
+            CreateDevParams::Macvlan { .. } | CreateDevParams::Wireguard => {}
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum BondMode {
+    ActiveBackup,
+    #[serde(rename = "802.3ad")]
+    Ieee8023ad,
+    BalanceXor,
+}
+
+impl BondMode {
+    /// 内核 / iproute2 使用的名称
+    pub fn kernel_name(&self) -> &'static str {
+        match self {
+            BondMode::ActiveBackup => "active-backup",
+            BondMode::Ieee8023ad => "802.3ad",
+            BondMode::BalanceXor => "balance-xor",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum BondXmitHashPolicy {
+    #[serde(rename = "layer2")]
+    Layer2,
+    #[serde(rename = "layer2+3")]
+    Layer23,
+    #[serde(rename = "layer3+4")]
+    Layer34,
+}
+
+impl BondXmitHashPolicy {
+    pub fn kernel_name(&self) -> &'static str {
+        match self {
+            BondXmitHashPolicy::Layer2 => "layer2",
+            BondXmitHashPolicy::Layer23 => "layer2+3",
+            BondXmitHashPolicy::Layer34 => "layer3+4",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum MacvlanMode {
+    Private,
+    Vepa,
+    #[default]
+    Bridge,
+    Passthru,
+}
+
+impl MacvlanMode {
+    /// MACVLAN_MODE_* in linux/if_link.h
+    pub fn kernel_value(&self) -> u32 {
+        match self {
+            MacvlanMode::Private => 1,
+            MacvlanMode::Vepa => 2,
+            MacvlanMode::Bridge => 4,
+            MacvlanMode::Passthru => 8,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, Default)]
@@ -142,6 +313,19 @@ pub enum ZoneRequirement {
     WanOrPpp,
 }
 
+impl ZoneRequirement {
+    /// 网卡区域是否满足要求, PPP 设备需调用方另行判断
+    pub fn allows(&self, zone: &IfaceZoneType) -> bool {
+        match self {
+            ZoneRequirement::WanOnly | ZoneRequirement::WanOrPpp => {
+                matches!(zone, IfaceZoneType::Wan)
+            }
+            ZoneRequirement::LanOnly => matches!(zone, IfaceZoneType::Lan),
+            ZoneRequirement::WanOrLan => matches!(zone, IfaceZoneType::Wan | IfaceZoneType::Lan),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum ServiceKind {
@@ -158,6 +342,9 @@ pub enum ServiceKind {
     Icmpv6Ra,
     RouteLan,
     WiFi,
+    WifiStation,
+    WireGuard,
+    Sqm,
 }
 
 impl std::fmt::Display for ServiceKind {
@@ -174,6 +361,9 @@ impl std::fmt::Display for ServiceKind {
             Self::Icmpv6Ra => write!(f, "ICMPv6 RA"),
             Self::RouteLan => write!(f, "Route LAN"),
             Self::WiFi => write!(f, "WiFi"),
+            Self::WifiStation => write!(f, "WiFi Station"),
+            Self::WireGuard => write!(f, "WireGuard"),
+            Self::Sqm => write!(f, "SQM"),
         }
     }
 }
@@ -183,3 +373,50 @@ pub trait ZoneAwareConfig {
     fn zone_requirement() -> ZoneRequirement;
     fn service_kind() -> ServiceKind;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dev_params_validate() {
+        let vlan = |parent: &str, vlan_id| CreateDevParams::Vlan { parent: parent.into(), vlan_id };
+        assert!(vlan("eth0", 100).validate("eth0.100").is_ok());
+        assert!(vlan("eth0", 0).validate("eth0.0").is_err());
+        assert!(vlan("eth0", 4095).validate("eth0.4095").is_err());
+        assert!(vlan("eth0", 100).validate("eth0").is_err());
+        assert!(vlan("", 100).validate("vlan100").is_err());
+        assert!(vlan("eth0", 100).validate("a-very-long-name").is_err());
+
+        let bond =
+            |mode, xmit_hash_policy| CreateDevParams::Bond { mode, miimon: 100, xmit_hash_policy };
+        assert!(bond(BondMode::Ieee8023ad, Some(BondXmitHashPolicy::Layer34))
+            .validate("bond0")
+            .is_ok());
+        assert!(bond(BondMode::ActiveBackup, Some(BondXmitHashPolicy::Layer2))
+            .validate("bond0")
+            .is_err());
+    }
+
+    #[test]
+    fn test_dev_params_serde() {
+        let params: CreateDevParams =
+            serde_json::from_str(r#"{"t":"bond","mode":"802.3ad","xmit_hash_policy":"layer3+4"}"#)
+                .unwrap();
+        assert_eq!(
+            params,
+            CreateDevParams::Bond {
+                mode: BondMode::Ieee8023ad,
+                miimon: 100,
+                xmit_hash_policy: Some(BondXmitHashPolicy::Layer34),
+            }
+        );
+        assert_eq!(params.dev_type(), CreateDevType::Bond);
+        assert_eq!(params.parent(), None);
+
+        let params: CreateDevParams =
+            serde_json::from_str(r#"{"t":"macvlan","parent":"eth1","mode":"bridge"}"#).unwrap();
+        assert_eq!(params.parent(), Some("eth1"));
+        assert_eq!(MacvlanMode::Bridge.kernel_value(), 4);
+    }
+}
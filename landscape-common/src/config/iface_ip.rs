@@ -6,6 +6,7 @@ use super::iface::NetworkIfaceConfig;
 use crate::config::iface::IfaceZoneType;
 use crate::database::repository::LandscapeDBStore;
 use crate::net_proto::udp::dhcp::DhcpV4Options;
+use crate::service::ServiceConfigError;
 use crate::store::storev2::LandscapeStore;
 use crate::utils::time::get_f64_timestamp;
 
@@ -70,9 +71,17 @@ pub enum IfaceIpModelConfig {
         #[serde(default)]
         #[cfg_attr(feature = "openapi", schema(required = true))]
         ipv4_mask: u8,
+        /// 兼容旧配置, 视为 /64 地址
         #[serde(default)]
         #[cfg_attr(feature = "openapi", schema(required = true, nullable = true, value_type = Option<String>))]
         ipv6: Option<Ipv6Addr>,
+        #[serde(default)]
+        #[cfg_attr(feature = "openapi", schema(required = true))]
+        ipv6_addrs: Vec<StaticIpv6Addr>,
+        /// IPv6 网关, 可为链路本地地址
+        #[serde(default)]
+        #[cfg_attr(feature = "openapi", schema(required = true, nullable = true, value_type = Option<String>))]
+        ipv6_gateway: Option<Ipv6Addr>,
     },
     PPPoE {
         #[serde(default)]
@@ -94,7 +103,69 @@ pub enum IfaceIpModelConfig {
     },
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct StaticIpv6Addr {
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
+    pub ip: Ipv6Addr,
+    pub prefix: u8,
+}
+
+impl IfaceIpServiceConfig {
+    pub fn validate(&self) -> Result<(), ServiceConfigError> {
+        let IfaceIpModelConfig::Static { ipv4, ipv4_mask, ipv6_gateway, .. } = &self.ip_model
+        else {
+            return Ok(());
+        };
+
+        if ipv4.is_some() && *ipv4_mask > 32 {
+            return Err(ServiceConfigError::InvalidConfig {
+                reason: format!("ipv4_mask ({}) must be between 0 and 32", ipv4_mask),
+            });
+        }
+
+        for addr in self.ip_model.static_ipv6_addrs() {
+            if addr.prefix == 0 || addr.prefix > 128 {
+                return Err(ServiceConfigError::InvalidConfig {
+                    reason: format!(
+                        "ipv6 prefix ({}) of {} must be between 1 and 128",
+                        addr.prefix, addr.ip
+                    ),
+                });
+            }
+            if addr.ip.is_unspecified() || addr.ip.is_multicast() || addr.ip.is_loopback() {
+                return Err(ServiceConfigError::InvalidConfig {
+                    reason: format!("{} can not be used as interface address", addr.ip),
+                });
+            }
+        }
+
+        if let Some(gateway) = ipv6_gateway {
+            if gateway.is_unspecified() || gateway.is_multicast() || gateway.is_loopback() {
+                return Err(ServiceConfigError::InvalidConfig {
+                    reason: format!("{} can not be used as ipv6 gateway", gateway),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
 impl IfaceIpModelConfig {
+    /// 静态模式下需要设置的全部 IPv6 地址 (包含旧的 ipv6 字段)
+    pub fn static_ipv6_addrs(&self) -> Vec<StaticIpv6Addr> {
+        let IfaceIpModelConfig::Static { ipv6, ipv6_addrs, .. } = self else {
+            return vec![];
+        };
+        let mut result = ipv6_addrs.clone();
+        if let Some(ip) = ipv6 {
+            if !result.iter().any(|a| a.ip == *ip) {
+                result.push(StaticIpv6Addr { ip: *ip, prefix: 64 });
+            }
+        }
+        result
+    }
+
     /// 检查当前的 zone 设置是否满足 IP 配置的要求
     pub fn check_iface_status(&self, iface_config: &NetworkIfaceConfig) -> bool {
         match self {
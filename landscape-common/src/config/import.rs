@@ -0,0 +1,545 @@
+use std::collections::{HashMap, HashSet};
+
+use landscape_macro::LdApiError;
+use serde::{Deserialize, Serialize};
+
+use super::iface::{NetworkIfaceConfig, ZoneAwareConfig, ZoneRequirement};
+use super::ppp::PPPDServiceConfig;
+use super::InitConfig;
+use crate::database::repository::LandscapeDBStore;
+use crate::ip_mark::check_flow_port_rule_limit;
+use crate::netns_target::NetnsTargetError;
+use crate::service::ServiceConfigError;
+
+#[derive(thiserror::Error, Debug, LdApiError)]
+#[api_error(crate_path = "crate")]
+pub enum ConfigImportError {
+    #[error("Failed to parse config file: {0}")]
+    #[api_error(id = "config_import.parse_failed", status = 400)]
+    ParseFailed(String),
+
+    #[error("Invalid config in section '{section}' ({id}): {reason}")]
+    #[api_error(id = "config_import.invalid_section", status = 422)]
+    InvalidSection { section: &'static str, id: String, reason: String },
+
+    #[error("Failed to reload '{0}' after import, the previous config has been restored")]
+    #[api_error(id = "config_import.reload_failed", status = 500)]
+    ReloadFailed(String),
+}
+
+/// 配置导入方式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigImportMode {
+    /// 清空现有配置后写入导入内容
+    #[default]
+    Replace,
+    /// 按 ID 写入或更新, 保留导入内容中未出现的配置
+    Merge,
+}
+
+/// 导入参数, 文件中的 `config` (landscape.toml) 部分不参与导入
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema, utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct ConfigImportQuery {
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = false))]
+    pub mode: ConfigImportMode,
+    /// 仅校验并返回差异, 不写入
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = false))]
+    pub dry_run: bool,
+}
+
+/// 单个配置分区与数据库的差异, 以配置 ID 标识
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ConfigSectionDiff {
+    pub section: String,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+    pub unchanged: usize,
+}
+
+impl ConfigSectionDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ConfigImportResult {
+    pub mode: ConfigImportMode,
+    pub dry_run: bool,
+    /// 仅包含存在变更的分区
+    pub sections: Vec<ConfigSectionDiff>,
+}
+
+impl InitConfig {
+    pub fn from_toml(content: &str) -> Result<Self, ConfigImportError> {
+        toml::from_str(content).map_err(|e| ConfigImportError::ParseFailed(e.to_string()))
+    }
+}
+
+/// 忽略 update_at 后比较两份配置是否一致
+fn same_content<T: Serialize>(a: &T, b: &T) -> bool {
+    let strip = |v: &T| {
+        let mut value = serde_json::to_value(v).unwrap_or_default();
+        if let Some(obj) = value.as_object_mut() {
+            obj.remove("update_at");
+        }
+        value
+    };
+    strip(a) == strip(b)
+}
+
+fn diff_section<Id: ToString, T: LandscapeDBStore<Id> + Serialize>(
+    section: &str,
+    current: &[T],
+    incoming: &[T],
+    mode: ConfigImportMode,
+) -> ConfigSectionDiff {
+    let current: HashMap<String, &T> =
+        current.iter().map(|c| (c.get_id().to_string(), c)).collect();
+    let mut diff = ConfigSectionDiff { section: section.to_string(), ..Default::default() };
+    for config in incoming {
+        let id = config.get_id().to_string();
+        match current.get(&id) {
+            None => diff.added.push(id),
+            Some(old) if !same_content(*old, config) => diff.changed.push(id),
+            Some(_) => diff.unchanged += 1,
+        }
+    }
+    if mode == ConfigImportMode::Replace {
+        let incoming_ids: Vec<String> = incoming.iter().map(|c| c.get_id().to_string()).collect();
+        diff.removed =
+            current.into_keys().filter(|id| !incoming_ids.contains(id)).collect::<Vec<_>>();
+        diff.removed.sort();
+    }
+    diff
+}
+
+fn merge_section<Id: ToString, T: LandscapeDBStore<Id>>(current: &mut Vec<T>, incoming: Vec<T>) {
+    for config in incoming {
+        let id = config.get_id().to_string();
+        if let Some(exist) = current.iter_mut().find(|c| c.get_id().to_string() == id) {
+            *exist = config;
+        } else {
+            current.push(config);
+        }
+    }
+}
+
+macro_rules! init_config_sections {
+    ( $( $field:ident ),* $(,)? ) => {
+        impl InitConfig {
+            /// 以 `self` 为数据库现状, 计算导入 `incoming` 后各分区的差异
+            pub fn diff(&self, incoming: &InitConfig, mode: ConfigImportMode) -> Vec<ConfigSectionDiff> {
+                let mut result = vec![];
+                $(
+                    let diff = diff_section(stringify!($field), &self.$field, &incoming.$field, mode);
+                    if !diff.is_empty() {
+                        result.push(diff);
+                    }
+                )*
+                result
+            }
+
+            /// 导入完成后的完整配置, 用于整体校验
+            pub fn apply_import(&self, incoming: InitConfig, mode: ConfigImportMode) -> InitConfig {
+                match mode {
+                    ConfigImportMode::Replace => incoming,
+                    ConfigImportMode::Merge => {
+                        let mut result = self.clone();
+                        $( merge_section(&mut result.$field, incoming.$field); )*
+                        result
+                    }
+                }
+            }
+        }
+    };
+}
+
+init_config_sections!(
+    ifaces,
+    ipconfigs,
+    nats,
+    marks,
+    pppds,
+    flow_rules,
+    dns_rules,
+    dst_ip_mark,
+    dhcpv6pds,
+    icmpras,
+    firewalls,
+    firewall_rules,
+    firewall_blacklists,
+    wifi_configs,
+    wifi_stations,
+    wireguards,
+    dhcpv4_services,
+    mss_clamps,
+    sqms,
+    geo_ips,
+    geo_sites,
+    route_lans,
+    route_wans,
+    static_nat_mappings,
+    dns_redirects,
+    dns_upstream_configs,
+    enrolled_devices,
+    traffic_quotas,
+    rate_limits,
+    netns_targets,
+    managed_containers,
+);
+
+/// 基于导入后的网卡与 PPP 配置进行区域校验, 规则同 API 写入时的校验
+fn check_zone<C: ZoneAwareConfig>(
+    config: &C,
+    ifaces: &HashMap<&str, &NetworkIfaceConfig>,
+    pppds: &[PPPDServiceConfig],
+) -> Result<(), ServiceConfigError> {
+    let iface_name = config.iface_name();
+    let requirement = C::zone_requirement();
+
+    if matches!(requirement, ZoneRequirement::WanOrPpp) {
+        let ppp = pppds.iter().find(|p| p.iface_name == iface_name);
+        if ppp.is_some_and(|p| ifaces.contains_key(p.attach_iface_name.as_str())) {
+            return Ok(());
+        }
+    }
+
+    if iface_name == "docker0" && matches!(requirement, ZoneRequirement::LanOnly) {
+        return Ok(());
+    }
+
+    let iface = ifaces
+        .get(iface_name)
+        .ok_or_else(|| ServiceConfigError::IfaceNotFound { iface_name: iface_name.to_string() })?;
+
+    if requirement.allows(&iface.zone_type) {
+        Ok(())
+    } else {
+        Err(ServiceConfigError::ZoneMismatch {
+            service_name: C::service_kind(),
+            iface_name: iface_name.to_string(),
+        })
+    }
+}
+
+fn invalid<E: ToString>(section: &'static str, id: String) -> impl FnOnce(E) -> ConfigImportError {
+    move |e| ConfigImportError::InvalidSection { section, id, reason: e.to_string() }
+}
+
+macro_rules! check_zone_section {
+    ($cfg:expr, $ifaces:expr, $( $field:ident $( => |$c:ident| $validate:expr )? ),* $(,)?) => {
+        $(
+            for config in $cfg.$field.iter() {
+                let id = config.iface_name().to_string();
+                check_zone(config, $ifaces, &$cfg.pppds)
+                    .map_err(invalid(stringify!($field), id.clone()))?;
+                $(
+                    let $c = config;
+                    $validate.map_err(invalid(stringify!($field), id))?;
+                )?
+            }
+        )*
+    };
+}
+
+impl InitConfig {
+    /// 校验完整配置, 包括各服务自身的参数以及所在网卡的区域
+    pub fn validate(&self) -> Result<(), ConfigImportError> {
+        for iface in self.ifaces.iter() {
+            if let Some(params) = &iface.dev_params {
+                params.validate(&iface.name).map_err(invalid("ifaces", iface.name.clone()))?;
+            }
+        }
+
+        let ifaces: HashMap<&str, &NetworkIfaceConfig> =
+            self.ifaces.iter().map(|i| (i.name.as_str(), i)).collect();
+
+        check_zone_section!(
+            self,
+            &ifaces,
+            ipconfigs => |c| c.validate(),
+            nats => |c| c.nat_config.validate(),
+            pppds => |c| c.pppd_config.validate(),
+            dhcpv6pds => |c| c.config.validate(),
+            icmpras => |c| c.config.validate(),
+            firewalls,
+            wifi_configs => |c| c.validate(),
+            wifi_stations => |c| c.validate(),
+            wireguards => |c| c.validate(),
+            dhcpv4_services => |c| c.config.validate(),
+            mss_clamps => |c| c.validate(),
+            sqms => |c| c.validate(),
+            route_lans,
+            route_wans,
+        );
+
+        for mark in self.marks.iter() {
+            let is_ppp = self.pppds.iter().any(|p| p.iface_name == mark.iface_name);
+            if !is_ppp && !ifaces.contains_key(mark.iface_name.as_str()) {
+                let e = ServiceConfigError::IfaceNotFound { iface_name: mark.iface_name.clone() };
+                return Err(invalid("marks", mark.iface_name.clone())(e));
+            }
+        }
+
+        // 与 API 写入时一致: MAC 与 IPv4 不可重复, IPv4 需位于所在网卡的 DHCP 网段内
+        let mut macs = HashSet::new();
+        let mut ipv4s = HashSet::new();
+        for device in self.enrolled_devices.iter() {
+            let id = device.id.to_string();
+            if !macs.insert(device.mac) {
+                return Err(invalid("enrolled_devices", id)(format!(
+                    "duplicate mac {}",
+                    device.mac
+                )));
+            }
+            let Some(ipv4) = device.ipv4 else { continue };
+            if !ipv4s.insert(ipv4) {
+                return Err(invalid("enrolled_devices", id)(format!("duplicate ipv4 {ipv4}")));
+            }
+            if let Some(iface_name) = &device.iface_name {
+                let in_range = self.dhcpv4_services.iter().any(|service| {
+                    let config = &service.config;
+                    let mask = u32::MAX.checked_shl(32 - config.network_mask as u32).unwrap_or(0);
+                    service.iface_name == *iface_name
+                        && u32::from(ipv4) & mask == u32::from(config.server_ip_addr) & mask
+                });
+                if !in_range {
+                    return Err(invalid("enrolled_devices", id)(format!(
+                        "{ipv4} is not in the dhcp range of {iface_name}"
+                    )));
+                }
+            }
+        }
+
+        for mapping in self.static_nat_mappings.iter() {
+            mapping.validate().map_err(invalid("static_nat_mappings", mapping.id.to_string()))?;
+        }
+        for quota in self.traffic_quotas.iter() {
+            quota.validate().map_err(invalid("traffic_quotas", quota.id.to_string()))?;
+        }
+        for limit in self.rate_limits.iter() {
+            limit.validate().map_err(invalid("rate_limits", limit.id.to_string()))?;
+        }
+        let mut netns_names = HashSet::new();
+        for target in self.netns_targets.iter() {
+            target.validate().map_err(invalid("netns_targets", target.id.to_string()))?;
+            if !netns_names.insert(target.name.as_str())
+                || self.ifaces.iter().any(|iface| iface.name == target.name)
+                || self.pppds.iter().any(|ppp| ppp.iface_name == target.name)
+            {
+                return Err(invalid("netns_targets", target.id.to_string())(
+                    NetnsTargetError::DuplicateName(target.name.clone()),
+                ));
+            }
+        }
+        for container in self.managed_containers.iter() {
+            container
+                .validate()
+                .map_err(invalid("managed_containers", container.id.to_string()))?;
+        }
+        for flow in self.flow_rules.iter() {
+            for rule in flow.flow_match_rules.iter() {
+                rule.mode.validate().map_err(invalid("flow_rules", flow.flow_id.to_string()))?;
+                if let Some(qos) = &rule.qos {
+                    qos.validate().map_err(invalid("flow_rules", flow.flow_id.to_string()))?;
+                }
+            }
+            for target in flow.flow_targets.iter() {
+                target.validate().map_err(invalid("flow_rules", flow.flow_id.to_string()))?;
+            }
+            if let Some(health_check) = &flow.health_check {
+                health_check.validate().map_err(invalid("flow_rules", flow.flow_id.to_string()))?;
+            }
+        }
+        for rule in self.dns_rules.iter() {
+            rule.mark.validate().map_err(invalid("dns_rules", rule.id.to_string()))?;
+        }
+        for rule in self.dst_ip_mark.iter() {
+            rule.mark.validate().map_err(invalid("dst_ip_mark", rule.index.to_string()))?;
+            rule.validate().map_err(invalid("dst_ip_mark", rule.index.to_string()))?;
+        }
+        let flow_ids: HashSet<u32> = self.dst_ip_mark.iter().map(|rule| rule.flow_id).collect();
+        for flow_id in flow_ids {
+            check_flow_port_rule_limit(flow_id, self.dst_ip_mark.iter())
+                .map_err(invalid("dst_ip_mark", flow_id.to_string()))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::config::iface::IfaceZoneType;
+    use crate::config::mss_clamp::MSSClampServiceConfig;
+    use crate::flow::mark::FlowMark;
+    use crate::ip_mark::{PortRange, WanIPRuleSource, WanIpMatchConfig, WanIpRuleConfig};
+    use crate::netns_target::{NetnsSource, NetnsTargetConfig};
+    use crate::network::LandscapeIpProtocolCode;
+
+    fn clamp(iface_name: &str, clamp_size: u16) -> MSSClampServiceConfig {
+        MSSClampServiceConfig {
+            iface_name: iface_name.to_string(),
+            enable: true,
+            clamp_size,
+            update_at: 0.0,
+        }
+    }
+
+    fn netns(name: &str) -> NetnsTargetConfig {
+        NetnsTargetConfig {
+            id: Uuid::new_v4(),
+            enable: true,
+            name: name.to_string(),
+            source: NetnsSource::Named { netns: name.to_string() },
+            remark: String::new(),
+            update_at: 0.0,
+        }
+    }
+
+    fn port_rule(index: u32, flow_id: u32, count: usize) -> WanIpRuleConfig {
+        let port = WanIpMatchConfig {
+            ip: "0.0.0.0".parse().unwrap(),
+            prefix: 0,
+            l4_protocol: Some(LandscapeIpProtocolCode::TCP),
+            dst_port: Some(PortRange { start: 443, end: 443 }),
+        };
+        WanIpRuleConfig {
+            id: Some(Uuid::new_v4()),
+            index,
+            enable: true,
+            mark: FlowMark::default(),
+            source: vec![WanIPRuleSource::Config(port); count],
+            remark: String::new(),
+            flow_id,
+            override_dns: false,
+            update_at: 0.0,
+        }
+    }
+
+    fn with_wan() -> InitConfig {
+        InitConfig {
+            ifaces: vec![
+                NetworkIfaceConfig::crate_bridge("wan0".into(), Some(IfaceZoneType::Wan)),
+                NetworkIfaceConfig::crate_bridge("lan0".into(), Some(IfaceZoneType::Lan)),
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn section_name(result: &Result<(), ConfigImportError>) -> Option<&'static str> {
+        match result {
+            Err(ConfigImportError::InvalidSection { section, .. }) => Some(*section),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn diff_section_reports_changes_by_id() {
+        let current = vec![clamp("eth0", 1492), clamp("eth1", 1492), clamp("eth2", 1492)];
+        let mut touched = clamp("eth0", 1492);
+        touched.update_at = 1.0;
+        let incoming = vec![touched, clamp("eth1", 1400), clamp("eth3", 1492)];
+
+        // 只有 update_at 不同时视为未变更
+        let diff = diff_section("mss_clamps", &current, &incoming, ConfigImportMode::Replace);
+        assert_eq!(diff.unchanged, 1);
+        assert_eq!(diff.changed, vec!["eth1"]);
+        assert_eq!(diff.added, vec!["eth3"]);
+        assert_eq!(diff.removed, vec!["eth2"]);
+
+        // Merge 模式不删除导入内容中未出现的配置
+        let diff = diff_section("mss_clamps", &current, &incoming, ConfigImportMode::Merge);
+        assert!(diff.removed.is_empty());
+        assert!(!diff.is_empty());
+
+        assert!(
+            diff_section("mss_clamps", &current, &current, ConfigImportMode::Replace).is_empty()
+        );
+    }
+
+    #[test]
+    fn merge_section_replaces_by_id_and_keeps_others() {
+        let mut current = vec![clamp("eth0", 1492), clamp("eth1", 1492)];
+        merge_section(&mut current, vec![clamp("eth1", 1400), clamp("eth2", 1300)]);
+        let result: Vec<_> =
+            current.iter().map(|c| (c.iface_name.as_str(), c.clamp_size)).collect();
+        assert_eq!(result, vec![("eth0", 1492), ("eth1", 1400), ("eth2", 1300)]);
+    }
+
+    #[test]
+    fn diff_and_apply_import_cover_all_sections() {
+        let current = InitConfig {
+            mss_clamps: vec![clamp("eth0", 1492)],
+            netns_targets: vec![netns("ns1")],
+            ..Default::default()
+        };
+        let incoming = InitConfig {
+            mss_clamps: vec![clamp("eth1", 1492)],
+            ..Default::default()
+        };
+
+        let sections: Vec<_> = current
+            .diff(&incoming, ConfigImportMode::Replace)
+            .into_iter()
+            .map(|diff| diff.section)
+            .collect();
+        assert_eq!(sections, vec!["mss_clamps", "netns_targets"]);
+        assert_eq!(current.diff(&incoming, ConfigImportMode::Merge).len(), 1);
+
+        let merged = current.apply_import(incoming.clone(), ConfigImportMode::Merge);
+        assert_eq!(merged.mss_clamps.len(), 2);
+        assert_eq!(merged.netns_targets.len(), 1);
+
+        let replaced = current.apply_import(incoming, ConfigImportMode::Replace);
+        assert_eq!(replaced.mss_clamps.len(), 1);
+        assert!(replaced.netns_targets.is_empty());
+    }
+
+    #[test]
+    fn validate_checks_zone_and_service_params() {
+        let mut config = with_wan();
+        config.mss_clamps = vec![clamp("wan0", 1492)];
+        assert!(config.validate().is_ok());
+
+        for invalid in [clamp("lan0", 1492), clamp("missing", 1492), clamp("wan0", 100)] {
+            config.mss_clamps = vec![invalid];
+            assert_eq!(section_name(&config.validate()), Some("mss_clamps"));
+        }
+    }
+
+    #[test]
+    fn validate_rejects_conflicting_netns_names() {
+        let mut config = with_wan();
+        config.netns_targets = vec![netns("ns1")];
+        assert!(config.validate().is_ok());
+
+        config.netns_targets.push(netns("ns1"));
+        assert_eq!(section_name(&config.validate()), Some("netns_targets"));
+
+        config.netns_targets = vec![netns("wan0")];
+        assert_eq!(section_name(&config.validate()), Some("netns_targets"));
+    }
+
+    #[test]
+    fn validate_limits_port_rules_per_flow() {
+        let mut config = with_wan();
+        config.dst_ip_mark = vec![port_rule(1, 1, 20), port_rule(2, 2, 20)];
+        assert!(config.validate().is_ok());
+
+        config.dst_ip_mark.push(port_rule(3, 1, 20));
+        assert_eq!(section_name(&config.validate()), Some("dst_ip_mark"));
+    }
+}
@@ -4,23 +4,32 @@ pub mod flow;
 pub mod geo;
 pub mod iface;
 pub mod iface_ip;
+pub mod import;
 pub mod mss_clamp;
 pub mod nat;
 pub mod ppp;
 pub mod ra;
+pub mod snapshot;
+pub mod sqm;
 pub mod wifi;
+pub mod wifi_station;
+pub mod wireguard;
 
 pub mod route_lan;
 pub mod route_wan;
 
 use std::{
-    net::{IpAddr, Ipv6Addr},
+    net::{IpAddr, Ipv6Addr, SocketAddr},
     path::PathBuf,
 };
 
 use crate::dhcp::v4_server::config::DHCPv4ServiceConfig;
 use crate::dhcp::v6_client::config::IPV6PDServiceConfig;
+use crate::docker::managed::ManagedContainerConfig;
 use crate::enrolled_device::EnrolledDevice;
+use crate::netns_target::NetnsTargetConfig;
+use crate::quota::TrafficQuotaConfig;
+use crate::rate_limit::RateLimitConfig;
 use dns::DNSRuleConfig;
 use firewall::FirewallServiceConfig;
 use flow::FlowWanServiceConfig;
@@ -31,8 +40,11 @@ use nat::NatServiceConfig;
 use ppp::PPPDServiceConfig;
 use ra::IPV6RAServiceConfig;
 use serde::{Deserialize, Serialize};
+use sqm::SqmServiceConfig;
 use uuid::Uuid;
 use wifi::WifiServiceConfig;
+use wifi_station::WifiStationServiceConfig;
+use wireguard::WireGuardServiceConfig;
 
 use crate::{
     args::WebCommArgs,
@@ -47,7 +59,7 @@ use crate::{
     flow::config::FlowConfig,
     ip_mark::WanIpRuleConfig,
     LANDSCAPE_CONFIG_DIR_NAME, LANDSCAPE_DB_SQLITE_NAME, LANDSCAPE_LOG_DIR_NAME,
-    LANDSCAPE_WEBROOT_DIR_NAME, LAND_CONFIG,
+    LANDSCAPE_METRIC_DIR_NAME, LANDSCAPE_WEBROOT_DIR_NAME, LAND_CONFIG,
 };
 
 pub type FlowId = u32;
@@ -93,10 +105,16 @@ pub struct InitConfig {
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub wifi_configs: Vec<WifiServiceConfig>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub wifi_stations: Vec<WifiStationServiceConfig>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub wireguards: Vec<WireGuardServiceConfig>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub dhcpv4_services: Vec<DHCPv4ServiceConfig>,
 
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub mss_clamps: Vec<MSSClampServiceConfig>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub sqms: Vec<SqmServiceConfig>,
 
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub geo_ips: Vec<GeoIpSourceConfig>,
@@ -119,6 +137,18 @@ pub struct InitConfig {
 
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub enrolled_devices: Vec<EnrolledDevice>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub traffic_quotas: Vec<TrafficQuotaConfig>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rate_limits: Vec<RateLimitConfig>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub netns_targets: Vec<NetnsTargetConfig>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub managed_containers: Vec<ManagedContainerConfig>,
 }
 
 /// auth realte config
@@ -206,6 +236,71 @@ pub struct LandscapeMetricConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[cfg_attr(feature = "openapi", schema(required = false, nullable = false))]
     pub max_threads: Option<usize>,
+    /// 指标数据库目录, 未设置时使用 home 下的 metric 目录
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<String>, required = false, nullable = false))]
+    pub db_path: Option<PathBuf>,
+
+    /// Prometheus `/metrics` 的访问令牌, 未设置时不在 Web 端口上提供
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "openapi", schema(required = false, nullable = false))]
+    pub prometheus_token: Option<String>,
+    /// 单独提供 `/metrics` 的 HTTP 监听地址, 如 `127.0.0.1:9100`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<String>, required = false, nullable = false))]
+    pub prometheus_listen: Option<SocketAddr>,
+
+    /// 结束连接的 IPFIX / NetFlow v9 导出目标
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(feature = "openapi", schema(required = false))]
+    pub flow_collectors: Vec<FlowExportCollector>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum FlowExportProtocol {
+    #[default]
+    Ipfix,
+    NetflowV9,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FlowExportCollector {
+    /// 采集器 UDP 地址
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
+    pub address: SocketAddr,
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = true))]
+    pub protocol: FlowExportProtocol,
+    /// 模板重发间隔
+    #[serde(default = "default_template_refresh_secs")]
+    #[cfg_attr(feature = "openapi", schema(required = true))]
+    pub template_refresh_secs: u64,
+    /// 每 N 条连接导出 1 条, 1 表示全部导出
+    #[serde(default = "default_sampling_interval")]
+    #[cfg_attr(feature = "openapi", schema(required = true))]
+    pub sampling_interval: u32,
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = true))]
+    pub observation_domain_id: u32,
+    /// flow_id / WAN 企业字段使用的 PEN, 默认为 RFC 5612 的示例编号
+    #[serde(default = "default_enterprise_number")]
+    #[cfg_attr(feature = "openapi", schema(required = true))]
+    pub enterprise_number: u32,
+}
+
+fn default_template_refresh_secs() -> u64 {
+    crate::DEFAULT_FLOW_EXPORT_TEMPLATE_REFRESH_SECS
+}
+
+fn default_sampling_interval() -> u32 {
+    1
+}
+
+fn default_enterprise_number() -> u32 {
+    crate::DEFAULT_FLOW_EXPORT_ENTERPRISE_NUMBER
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -430,6 +525,14 @@ impl RuntimeConfig {
                 .unwrap_or(crate::DEFAULT_METRIC_FLUSH_INTERVAL_SECS),
             max_memory: config.metric.max_memory.unwrap_or(crate::DEFAULT_METRIC_MAX_MEMORY),
             max_threads: config.metric.max_threads.unwrap_or(crate::DEFAULT_METRIC_MAX_THREADS),
+            db_path: read_value(
+                &args.metric_path,
+                &config.metric.db_path,
+                home_path.join(LANDSCAPE_METRIC_DIR_NAME),
+            ),
+            prometheus_token: config.metric.prometheus_token.clone(),
+            prometheus_listen: config.metric.prometheus_listen,
+            flow_collectors: config.metric.flow_collectors.clone(),
         };
         let dns = DnsRuntimeConfig {
             cache_capacity: config.dns.cache_capacity.unwrap_or(crate::DEFAULT_DNS_CACHE_CAPACITY),
@@ -496,7 +599,8 @@ impl RuntimeConfig {
          Batch Size: {}\n\
          Flush Interval: {}s\n\
          Max Memory: {}MB\n\
-         Max Threads: {}\n",
+         Max Threads: {}\n\
+         Metric DB Path: {}\n",
             self.home_path.display(),
             self.auth.admin_user,
             self.auth.admin_pass,
@@ -517,6 +621,7 @@ impl RuntimeConfig {
             self.metric.flush_interval_secs,
             self.metric.max_memory,
             self.metric.max_threads,
+            self.metric.db_path.display(),
         )
     }
 }
@@ -569,6 +674,11 @@ pub struct MetricRuntimeConfig {
     pub flush_interval_secs: u64,
     pub max_memory: usize,
     pub max_threads: usize,
+    /// 指标数据库目录
+    pub db_path: PathBuf,
+    pub prometheus_token: Option<String>,
+    pub prometheus_listen: Option<SocketAddr>,
+    pub flow_collectors: Vec<FlowExportCollector>,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -607,6 +717,8 @@ impl MetricRuntimeConfig {
         if let Some(v) = config.max_threads {
             self.max_threads = v;
         }
+        // 令牌允许被清除
+        self.prometheus_token = config.prometheus_token.clone();
     }
 }
 
@@ -0,0 +1,128 @@
+use landscape_macro::LdApiError;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::import::ConfigSectionDiff;
+use crate::database::repository::LandscapeDBStore;
+use crate::utils::id::gen_database_uuid;
+use crate::utils::time::get_f64_timestamp;
+
+/// 保留的快照数量上限
+pub const MAX_CONFIG_SNAPSHOTS: u64 = 200;
+
+/// 确认超时的上限 (秒)
+pub const MAX_COMMIT_CONFIRM_SECS: u64 = 3600;
+
+/// 请求头: 本次修改需在指定秒数内确认, 否则自动回滚
+pub const COMMIT_CONFIRM_HEADER: &str = "X-Commit-Confirm";
+/// 响应头: 待确认修改的 ID
+pub const COMMIT_CONFIRM_ID_HEADER: &str = "X-Commit-Confirm-Id";
+
+#[derive(thiserror::Error, Debug, LdApiError)]
+#[api_error(crate_path = "crate")]
+pub enum ConfigSnapshotError {
+    #[error("Config snapshot '{0}' not found")]
+    #[api_error(id = "config_snapshot.not_found", status = 404)]
+    NotFound(Uuid),
+
+    #[error("Pending confirmation '{0}' not found or already expired")]
+    #[api_error(id = "config_snapshot.confirm_not_found", status = 404)]
+    ConfirmNotFound(Uuid),
+
+    #[error("Invalid commit confirm timeout: {0}")]
+    #[api_error(id = "config_snapshot.invalid_timeout", status = 400)]
+    InvalidTimeout(String),
+}
+
+/// 数据库配置 (InitConfig 结构) 的一次快照
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ConfigSnapshot {
+    #[serde(default = "gen_database_uuid")]
+    #[cfg_attr(feature = "openapi", schema(required = false))]
+    pub id: Uuid,
+    /// 触发变更的用户
+    pub author: String,
+    /// 触发变更的请求, 如 `PUT /services/nat`
+    pub summary: String,
+    /// 与上一个快照的差异
+    pub diff: Vec<ConfigSectionDiff>,
+    /// InitConfig 格式的 TOML 内容
+    pub content: String,
+    /// 快照时间
+    #[serde(default = "get_f64_timestamp")]
+    #[cfg_attr(feature = "openapi", schema(required = false))]
+    pub update_at: f64,
+}
+
+impl LandscapeDBStore<Uuid> for ConfigSnapshot {
+    fn get_id(&self) -> Uuid {
+        self.id
+    }
+    fn get_update_at(&self) -> f64 {
+        self.update_at
+    }
+    fn set_update_at(&mut self, ts: f64) {
+        self.update_at = ts;
+    }
+}
+
+/// 快照列表项, 不含完整内容
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ConfigSnapshotInfo {
+    pub id: Uuid,
+    pub author: String,
+    pub summary: String,
+    pub diff: Vec<ConfigSectionDiff>,
+    pub update_at: f64,
+}
+
+impl From<ConfigSnapshot> for ConfigSnapshotInfo {
+    fn from(value: ConfigSnapshot) -> Self {
+        ConfigSnapshotInfo {
+            id: value.id,
+            author: value.author,
+            summary: value.summary,
+            diff: value.diff,
+            update_at: value.update_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema, utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct ConfigSnapshotCompareQuery {
+    pub from: Uuid,
+    pub to: Uuid,
+}
+
+/// 等待确认的修改, 超时后回滚到 `revert_to`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PendingConfigConfirm {
+    pub id: Uuid,
+    pub revert_to: Uuid,
+    /// 本次修改后的快照, 之后若有新快照则不再自动回滚
+    pub applied: Uuid,
+    pub author: String,
+    pub summary: String,
+    /// 截止时间 (ms), 重启后按剩余时间继续等待
+    pub deadline: u64,
+    #[serde(default = "get_f64_timestamp")]
+    #[cfg_attr(feature = "openapi", schema(required = false))]
+    pub update_at: f64,
+}
+
+impl LandscapeDBStore<Uuid> for PendingConfigConfirm {
+    fn get_id(&self) -> Uuid {
+        self.id
+    }
+    fn get_update_at(&self) -> f64 {
+        self.update_at
+    }
+    fn set_update_at(&mut self, ts: f64) {
+        self.update_at = ts;
+    }
+}
@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+
+use crate::database::repository::LandscapeDBStore;
+use crate::service::ServiceConfigError;
+use crate::store::storev2::LandscapeStore;
+use crate::utils::time::get_f64_timestamp;
+
+/// 带宽上限 (kbit/s), 10 Gbit/s
+pub const MAX_SQM_BANDWIDTH_KBIT: u32 = 10_000_000;
+/// 每个包的额外开销上限 (字节)
+pub const MAX_SQM_OVERHEAD: u16 = 256;
+
+/// 队列算法
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum SqmQdiscKind {
+    #[default]
+    Cake,
+    /// HTB 限速 + fq_codel 队列
+    FqCodel,
+}
+
+/// 链路层, 用于计算每个包的实际占用
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum SqmLinkLayer {
+    #[default]
+    Ethernet,
+    /// ADSL 等 ATM 链路, 按 53 字节信元对齐
+    Atm,
+    /// VDSL2 等 PTM 链路, 按 64/65 编码计算
+    Ptm,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SqmServiceConfig {
+    pub iface_name: String,
+    pub enable: bool,
+
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = true))]
+    pub qdisc: SqmQdiscKind,
+    /// 上行带宽 (kbit/s), 0 表示不整形
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = true))]
+    pub egress_bandwidth: u32,
+    /// 下行带宽 (kbit/s), 0 表示不整形, 通过 IFB 设备实现
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = true))]
+    pub ingress_bandwidth: u32,
+    /// 每个包的额外开销 (字节), 如 PPPoE over Ethernet 可设为 34
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = true))]
+    pub overhead: u16,
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = true))]
+    pub link_layer: SqmLinkLayer,
+    #[serde(default = "get_f64_timestamp")]
+    #[cfg_attr(feature = "openapi", schema(required = true))]
+    pub update_at: f64,
+}
+
+impl LandscapeStore for SqmServiceConfig {
+    fn get_store_key(&self) -> String {
+        self.iface_name.clone()
+    }
+}
+
+impl LandscapeDBStore<String> for SqmServiceConfig {
+    fn get_id(&self) -> String {
+        self.iface_name.clone()
+    }
+    fn get_update_at(&self) -> f64 {
+        self.update_at
+    }
+    fn set_update_at(&mut self, ts: f64) {
+        self.update_at = ts;
+    }
+}
+
+impl super::iface::ZoneAwareConfig for SqmServiceConfig {
+    fn iface_name(&self) -> &str {
+        &self.iface_name
+    }
+    fn zone_requirement() -> super::iface::ZoneRequirement {
+        super::iface::ZoneRequirement::WanOrPpp
+    }
+    fn service_kind() -> super::iface::ServiceKind {
+        super::iface::ServiceKind::Sqm
+    }
+}
+
+impl SqmServiceConfig {
+    pub fn validate(&self) -> Result<(), ServiceConfigError> {
+        for (name, bandwidth) in [
+            ("egress_bandwidth", self.egress_bandwidth),
+            ("ingress_bandwidth", self.ingress_bandwidth),
+        ] {
+            if bandwidth > MAX_SQM_BANDWIDTH_KBIT {
+                return Err(ServiceConfigError::InvalidConfig {
+                    reason: format!(
+                        "{name} ({bandwidth}) must not exceed {MAX_SQM_BANDWIDTH_KBIT} kbit/s"
+                    ),
+                });
+            }
+        }
+        if self.egress_bandwidth == 0 && self.ingress_bandwidth == 0 {
+            return Err(ServiceConfigError::InvalidConfig {
+                reason: "at least one of egress_bandwidth and ingress_bandwidth must be set"
+                    .to_string(),
+            });
+        }
+        if self.overhead > MAX_SQM_OVERHEAD {
+            return Err(ServiceConfigError::InvalidConfig {
+                reason: format!(
+                    "overhead ({}) must not exceed {MAX_SQM_OVERHEAD} bytes",
+                    self.overhead
+                ),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// 单个方向上根队列的统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SqmQdiscStats {
+    /// 队列类型, 如 `cake`, `htb`
+    pub kind: String,
+    pub bytes: u64,
+    pub packets: u32,
+    pub drops: u32,
+    pub overlimits: u32,
+    /// 当前排队的字节数
+    pub backlog: u32,
+    /// 当前排队的包数
+    pub qlen: u32,
+}
+
+/// 网卡当前的 SQM 队列统计, 未整形的方向为空
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SqmStats {
+    pub iface_name: String,
+    pub egress: Option<SqmQdiscStats>,
+    pub ingress: Option<SqmQdiscStats>,
+}
@@ -1,22 +1,207 @@
 use serde::{Deserialize, Serialize};
 
 use crate::database::repository::LandscapeDBStore;
+use crate::iface::dev_wifi::WifiPhyCapability;
 use crate::service::ServiceConfigError;
 use crate::store::storev2::LandscapeStore;
 use crate::utils::time::get_f64_timestamp;
 
+/// 单个无线接口上最多的 BSS 数量
+pub const MAX_WIFI_BSS: usize = 8;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct WifiServiceConfig {
     pub iface_name: String,
     pub enable: bool,
-    /// hostapd config file
-    pub config: String,
+    /// AP 配置, 启动时生成 hostapd 配置文件
+    #[serde(default)]
+    pub ap: WifiApConfig,
     #[serde(default = "get_f64_timestamp")]
     #[cfg_attr(feature = "openapi", schema(required = true))]
     pub update_at: f64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WifiApConfig {
+    /// ISO 3166-1 国家代码
+    pub country: String,
+    pub band: WifiBand,
+    /// 0 为自动选择信道
+    #[serde(default)]
+    pub channel: u8,
+    pub width: WifiChannelWidth,
+    /// 第一个 BSS 使用当前接口, 其余 BSS 创建名称为 `{iface_name}-{index}` 的接口
+    #[serde(default)]
+    pub bss: Vec<WifiBssConfig>,
+}
+
+impl Default for WifiApConfig {
+    fn default() -> Self {
+        Self {
+            country: "CN".to_string(),
+            band: WifiBand::Band2G,
+            channel: 0,
+            width: WifiChannelWidth::Mhz20,
+            bss: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WifiBssConfig {
+    pub ssid: String,
+    pub security: WifiSecurity,
+    /// 不广播 SSID
+    #[serde(default)]
+    pub hidden: bool,
+    /// 客户端隔离
+    #[serde(default)]
+    pub isolate: bool,
+    /// 加入的网桥, 不同 VLAN 可通过加入 VLAN 设备所在的网桥区分
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub bridge: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(tag = "t", rename_all = "snake_case")]
+pub enum WifiSecurity {
+    Open,
+    Wpa2Psk {
+        passphrase: String,
+    },
+    Wpa3Sae {
+        passphrase: String,
+    },
+    /// WPA2 / WPA3 混合模式
+    Wpa2Wpa3 {
+        passphrase: String,
+    },
+}
+
+impl WifiSecurity {
+    pub fn passphrase(&self) -> Option<&str> {
+        match self {
+            WifiSecurity::Open => None,
+            WifiSecurity::Wpa2Psk { passphrase }
+            | WifiSecurity::Wpa3Sae { passphrase }
+            | WifiSecurity::Wpa2Wpa3 { passphrase } => Some(passphrase),
+        }
+    }
+
+    /// 8-63 位可打印 ASCII 字符, 或 64 位十六进制 PSK
+    pub fn validate(&self, ssid: &str) -> Result<(), ServiceConfigError> {
+        let Some(passphrase) = self.passphrase() else {
+            return Ok(());
+        };
+        let is_psk_hex =
+            passphrase.len() == 64 && passphrase.chars().all(|c| c.is_ascii_hexdigit());
+        if !is_psk_hex
+            && (!(8..=63).contains(&passphrase.len())
+                || !passphrase.chars().all(|c| c.is_ascii() && !c.is_ascii_control()))
+        {
+            return Err(invalid(format!(
+                "passphrase of {ssid} must be 8-63 printable ASCII characters"
+            )));
+        }
+        Ok(())
+    }
+}
+
+pub fn check_ssid(ssid: &str) -> Result<(), ServiceConfigError> {
+    if ssid.is_empty() || ssid.len() > 32 {
+        return Err(invalid(format!("SSID length must be in 1-32 bytes: {ssid}")));
+    }
+    if ssid.chars().any(|c| c.is_control()) {
+        return Err(invalid(format!("SSID contains control characters: {ssid}")));
+    }
+    Ok(())
+}
+
+/// 接口名会原样写入 hostapd 配置, 不允许出现换行等控制字符
+fn check_iface_name(kind: &str, name: &str) -> Result<(), ServiceConfigError> {
+    if name.is_empty() || name.len() > 15 {
+        return Err(invalid(format!("{kind} name length must be in 1-15 bytes: {name}")));
+    }
+    if name.chars().any(|c| c.is_control() || c.is_whitespace() || c == '/' || c == ':') {
+        return Err(invalid(format!("{kind} name contains invalid characters: {name:?}")));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum WifiBand {
+    #[serde(rename = "2g")]
+    Band2G,
+    #[serde(rename = "5g")]
+    Band5G,
+    #[serde(rename = "6g")]
+    Band6G,
+}
+
+impl WifiBand {
+    /// 信道中心频率 (MHz)
+    pub fn channel_to_freq(&self, channel: u8) -> Option<u32> {
+        let channel = channel as u32;
+        match self {
+            WifiBand::Band2G => match channel {
+                1..=13 => Some(2407 + channel * 5),
+                14 => Some(2484),
+                _ => None,
+            },
+            WifiBand::Band5G => match channel {
+                32..=177 => Some(5000 + channel * 5),
+                _ => None,
+            },
+            WifiBand::Band6G => match channel {
+                2 => Some(5935),
+                1..=233 if channel % 4 == 1 => Some(5950 + channel * 5),
+                _ => None,
+            },
+        }
+    }
+
+    pub fn freq_to_channel(freq: u32) -> Option<(WifiBand, u8)> {
+        match freq {
+            2484 => Some((WifiBand::Band2G, 14)),
+            2412..=2472 => Some((WifiBand::Band2G, ((freq - 2407) / 5) as u8)),
+            5935 => Some((WifiBand::Band6G, 2)),
+            5160..=5885 => Some((WifiBand::Band5G, ((freq - 5000) / 5) as u8)),
+            5955..=7115 => Some((WifiBand::Band6G, ((freq - 5950) / 5) as u8)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum WifiChannelWidth {
+    #[serde(rename = "20")]
+    Mhz20,
+    #[serde(rename = "40")]
+    Mhz40,
+    #[serde(rename = "80")]
+    Mhz80,
+    #[serde(rename = "160")]
+    Mhz160,
+}
+
+impl WifiChannelWidth {
+    pub fn mhz(&self) -> u32 {
+        match self {
+            WifiChannelWidth::Mhz20 => 20,
+            WifiChannelWidth::Mhz40 => 40,
+            WifiChannelWidth::Mhz80 => 80,
+            WifiChannelWidth::Mhz160 => 160,
+        }
+    }
+}
+
 impl LandscapeStore for WifiServiceConfig {
     fn get_store_key(&self) -> String {
         self.iface_name.clone()
@@ -35,17 +220,106 @@ impl LandscapeDBStore<String> for WifiServiceConfig {
     }
 }
 
+fn invalid(reason: String) -> ServiceConfigError {
+    ServiceConfigError::InvalidConfig { reason }
+}
+
 impl WifiServiceConfig {
+    /// 额外 BSS 的接口名称
+    pub fn bss_iface_name(&self, index: usize) -> String {
+        if index == 0 {
+            self.iface_name.clone()
+        } else {
+            format!("{}-{}", self.iface_name, index)
+        }
+    }
+
     pub fn validate(&self) -> Result<(), ServiceConfigError> {
-        if self.enable && self.config.trim().is_empty() {
-            return Err(ServiceConfigError::InvalidConfig {
-                reason: "config must not be empty when enabled".to_string(),
-            });
+        let ap = &self.ap;
+        if self.enable && ap.bss.is_empty() {
+            return Err(invalid("at least one BSS is required when enabled".to_string()));
+        }
+        if ap.bss.len() > MAX_WIFI_BSS {
+            return Err(invalid(format!("BSS count ({}) exceeds {MAX_WIFI_BSS}", ap.bss.len())));
+        }
+        if ap.country.len() != 2 || !ap.country.chars().all(|c| c.is_ascii_uppercase()) {
+            return Err(invalid(format!("invalid country code: {}", ap.country)));
+        }
+
+        if ap.channel != 0 && ap.band.channel_to_freq(ap.channel).is_none() {
+            return Err(invalid(format!("channel {} is not in band {:?}", ap.channel, ap.band)));
+        }
+        match (ap.band, ap.width) {
+            (WifiBand::Band2G, WifiChannelWidth::Mhz80 | WifiChannelWidth::Mhz160) => {
+                return Err(invalid("2.4GHz band supports up to 40MHz".to_string()));
+            }
+            (WifiBand::Band2G, WifiChannelWidth::Mhz40) if ap.channel > 13 => {
+                return Err(invalid("channel 14 does not support 40MHz".to_string()));
+            }
+            _ => {}
+        }
+
+        check_iface_name("interface", &self.iface_name)?;
+        let mut ssids = std::collections::HashSet::new();
+        for (index, bss) in ap.bss.iter().enumerate() {
+            check_iface_name("BSS interface", &self.bss_iface_name(index))?;
+            check_ssid(&bss.ssid)?;
+            if !ssids.insert(bss.ssid.as_str()) {
+                return Err(invalid(format!("duplicate SSID: {}", bss.ssid)));
+            }
+            bss.security.validate(&bss.ssid)?;
+            // 6GHz 仅允许 WPA3
+            if ap.band == WifiBand::Band6G && !matches!(bss.security, WifiSecurity::Wpa3Sae { .. })
+            {
+                return Err(invalid(format!("6GHz band requires WPA3-SAE: {}", bss.ssid)));
+            }
+            if let Some(bridge) = &bss.bridge {
+                check_iface_name("bridge", bridge)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 检查网卡是否支持所选的频段 / 信道 / 频宽
+    pub fn validate_capability(
+        &self,
+        capability: &WifiPhyCapability,
+    ) -> Result<(), ServiceConfigError> {
+        let ap = &self.ap;
+        let Some(band) = capability.bands.iter().find(|band| band.band == ap.band) else {
+            return Err(invalid(format!(
+                "band {:?} is not supported by {}",
+                ap.band, self.iface_name
+            )));
+        };
+
+        if ap.channel != 0 {
+            let Some(channel) = band.channels.iter().find(|c| c.channel == ap.channel) else {
+                return Err(invalid(format!("channel {} is not supported", ap.channel)));
+            };
+            if channel.disabled || channel.no_ir {
+                return Err(invalid(format!(
+                    "channel {} is not allowed to start an AP",
+                    ap.channel
+                )));
+            }
         }
-        if self.config.len() > 8192 {
-            return Err(ServiceConfigError::InvalidConfig {
-                reason: format!("config length ({}) exceeds 8192", self.config.len()),
-            });
+
+        let supported = match ap.width {
+            WifiChannelWidth::Mhz20 => true,
+            WifiChannelWidth::Mhz40 => band.ht40 || band.he,
+            WifiChannelWidth::Mhz80 => band.vht || band.he,
+            WifiChannelWidth::Mhz160 => band.vht160 || band.he160,
+        };
+        if !supported {
+            return Err(invalid(format!(
+                "{}MHz width is not supported in band {:?}",
+                ap.width.mhz(),
+                ap.band
+            )));
+        }
+        if ap.band == WifiBand::Band6G && !band.he {
+            return Err(invalid("6GHz band requires 802.11ax".to_string()));
         }
         Ok(())
     }
@@ -62,3 +336,57 @@ impl super::iface::ZoneAwareConfig for WifiServiceConfig {
         super::iface::ServiceKind::WiFi
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(bss: WifiBssConfig) -> WifiServiceConfig {
+        WifiServiceConfig {
+            iface_name: "wlan0".to_string(),
+            enable: true,
+            ap: WifiApConfig { bss: vec![bss], ..Default::default() },
+            update_at: 0.0,
+        }
+    }
+
+    fn bss() -> WifiBssConfig {
+        WifiBssConfig {
+            ssid: "Home".to_string(),
+            security: WifiSecurity::Wpa2Psk { passphrase: "12345678".to_string() },
+            hidden: false,
+            isolate: false,
+            bridge: Some("br0".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_validate_accepts_plain_config() {
+        assert!(config(bss()).validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_line_breaks() {
+        // 任一写入 hostapd 配置的字段含有换行都会注入额外指令
+        let mut value = bss();
+        value.ssid = "Home\nctrl_interface=/tmp".to_string();
+        assert!(config(value).validate().is_err());
+
+        let mut value = bss();
+        value.security = WifiSecurity::Wpa3Sae { passphrase: "12345678\nwps_state=2".to_string() };
+        assert!(config(value).validate().is_err());
+
+        let mut value = bss();
+        value.bridge = Some("br0\nwps=1".to_string());
+        assert!(config(value).validate().is_err());
+
+        let mut value = config(bss());
+        value.iface_name = "wl\n0".to_string();
+        assert!(value.validate().is_err());
+
+        let mut value = config(bss());
+        value.ap.bss.push(WifiBssConfig { ssid: "Guest".to_string(), ..bss() });
+        value.iface_name = "wlan0\r".to_string();
+        assert!(value.validate().is_err());
+    }
+}
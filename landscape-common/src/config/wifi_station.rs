@@ -0,0 +1,181 @@
+use serde::{Deserialize, Serialize};
+
+use super::wifi::{check_ssid, WifiSecurity};
+use crate::database::repository::LandscapeDBStore;
+use crate::net::MacAddr;
+use crate::service::ServiceConfigError;
+use crate::store::storev2::LandscapeStore;
+use crate::utils::time::get_f64_timestamp;
+
+/// 连接已有热点, 作为 WAN 上行使用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WifiStationServiceConfig {
+    pub iface_name: String,
+    pub enable: bool,
+    /// 已保存的网络, 按 priority 从高到低尝试
+    #[serde(default)]
+    pub networks: Vec<WifiStationNetwork>,
+    #[serde(default = "get_f64_timestamp")]
+    #[cfg_attr(feature = "openapi", schema(required = true))]
+    pub update_at: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WifiStationNetwork {
+    pub ssid: String,
+    pub security: WifiSecurity,
+    /// 目标网络不广播 SSID
+    #[serde(default)]
+    pub hidden: bool,
+    #[serde(default)]
+    pub priority: u32,
+    /// 仅连接指定的 AP
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub bssid: Option<MacAddr>,
+}
+
+impl LandscapeStore for WifiStationServiceConfig {
+    fn get_store_key(&self) -> String {
+        self.iface_name.clone()
+    }
+}
+
+impl LandscapeDBStore<String> for WifiStationServiceConfig {
+    fn get_id(&self) -> String {
+        self.iface_name.clone()
+    }
+    fn get_update_at(&self) -> f64 {
+        self.update_at
+    }
+    fn set_update_at(&mut self, ts: f64) {
+        self.update_at = ts;
+    }
+}
+
+impl super::iface::ZoneAwareConfig for WifiStationServiceConfig {
+    fn iface_name(&self) -> &str {
+        &self.iface_name
+    }
+    fn zone_requirement() -> super::iface::ZoneRequirement {
+        super::iface::ZoneRequirement::WanOnly
+    }
+    fn service_kind() -> super::iface::ServiceKind {
+        super::iface::ServiceKind::WifiStation
+    }
+}
+
+impl WifiStationServiceConfig {
+    pub fn validate(&self) -> Result<(), ServiceConfigError> {
+        if self.enable && self.networks.is_empty() {
+            return Err(ServiceConfigError::InvalidConfig {
+                reason: "at least one network is required when enabled".to_string(),
+            });
+        }
+        let mut ssids = std::collections::HashSet::new();
+        for network in self.networks.iter() {
+            check_ssid(&network.ssid)?;
+            network.security.validate(&network.ssid)?;
+            if !ssids.insert(network.ssid.as_str()) {
+                return Err(ServiceConfigError::InvalidConfig {
+                    reason: format!("duplicate SSID: {}", network.ssid),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 当前连接状态
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WifiStationStatus {
+    pub connected: bool,
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub ssid: Option<String>,
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub bssid: Option<MacAddr>,
+    /// MHz
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub freq: Option<u32>,
+    /// dBm
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub signal: Option<i8>,
+    /// 最近的连接事件, 新的在后
+    pub events: Vec<WifiStationEvent>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WifiStationEvent {
+    pub time: f64,
+    pub kind: WifiStationEventKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(tag = "t", rename_all = "snake_case")]
+pub enum WifiStationEventKind {
+    Connected {
+        ssid: String,
+        #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+        bssid: Option<MacAddr>,
+    },
+    Disconnected {
+        ssid: String,
+    },
+    /// wpa_supplicant 异常退出
+    SupplicantExit,
+}
+
+/// 扫描到的网络
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WifiScanResult {
+    pub bssid: MacAddr,
+    pub ssid: String,
+    /// MHz
+    pub freq: u32,
+    /// dBm
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub signal: Option<i32>,
+    /// 是否加密
+    pub privacy: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(networks: Vec<WifiStationNetwork>) -> WifiStationServiceConfig {
+        WifiStationServiceConfig {
+            iface_name: "wlan0".to_string(),
+            enable: true,
+            networks,
+            update_at: 0.0,
+        }
+    }
+
+    fn network(ssid: &str, passphrase: &str) -> WifiStationNetwork {
+        WifiStationNetwork {
+            ssid: ssid.to_string(),
+            security: WifiSecurity::Wpa2Psk { passphrase: passphrase.to_string() },
+            hidden: false,
+            priority: 0,
+            bssid: None,
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(config(vec![network("Home", "password")]).validate().is_ok());
+        assert!(config(vec![]).validate().is_err());
+        assert!(config(vec![network("Home", "short")]).validate().is_err());
+        assert!(config(vec![network("", "password")]).validate().is_err());
+        assert!(config(vec![network("Home", "password"), network("Home", "password2")])
+            .validate()
+            .is_err());
+    }
+}
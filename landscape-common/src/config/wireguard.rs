@@ -0,0 +1,238 @@
+use std::net::IpAddr;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+
+use crate::database::repository::LandscapeDBStore;
+use crate::service::ServiceConfigError;
+use crate::store::storev2::LandscapeStore;
+use crate::utils::time::get_f64_timestamp;
+
+/// WireGuard key 长度
+pub const WG_KEY_LEN: usize = 32;
+
+/// 解析 base64 编码的 WireGuard key
+pub fn decode_wg_key(key: &str) -> Option<[u8; WG_KEY_LEN]> {
+    let data = STANDARD.decode(key.trim()).ok()?;
+    data.try_into().ok()
+}
+
+pub fn encode_wg_key(key: &[u8; WG_KEY_LEN]) -> String {
+    STANDARD.encode(key)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WireGuardServiceConfig {
+    pub iface_name: String,
+    pub enable: bool,
+    /// base64 编码的私钥
+    pub private_key: String,
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub listen_port: Option<u16>,
+    /// 隧道接口地址
+    #[serde(default)]
+    pub addresses: Vec<WireGuardCidr>,
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub mtu: Option<u32>,
+    /// 对端连接本机使用的地址 (域名或 IP), 用于生成对端配置
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub public_endpoint: Option<String>,
+    #[serde(default)]
+    pub peers: Vec<WireGuardPeerConfig>,
+    #[serde(default = "get_f64_timestamp")]
+    #[cfg_attr(feature = "openapi", schema(required = true))]
+    pub update_at: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WireGuardPeerConfig {
+    /// 对端名称, 同一接口下唯一
+    pub name: String,
+    pub public_key: String,
+    /// 由 Landscape 生成密钥时保存, 用于生成完整的对端配置
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub private_key: Option<String>,
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub preshared_key: Option<String>,
+    /// host:port
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub endpoint: Option<String>,
+    #[serde(default)]
+    pub allowed_ips: Vec<WireGuardCidr>,
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub persistent_keepalive: Option<u16>,
+    /// 生成对端配置时 [Peer] 中的 AllowedIPs, 为空时使用全部流量
+    #[serde(default)]
+    pub client_allowed_ips: Vec<WireGuardCidr>,
+    /// 生成对端配置时的 DNS
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(value_type = Vec<String>))]
+    pub client_dns: Vec<IpAddr>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WireGuardCidr {
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
+    pub ip: IpAddr,
+    pub prefix: u8,
+}
+
+impl WireGuardCidr {
+    pub fn is_default_route(&self) -> bool {
+        self.prefix == 0
+    }
+
+    fn is_valid(&self) -> bool {
+        match self.ip {
+            IpAddr::V4(_) => self.prefix <= 32,
+            IpAddr::V6(_) => self.prefix <= 128,
+        }
+    }
+}
+
+impl std::fmt::Display for WireGuardCidr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.ip, self.prefix)
+    }
+}
+
+impl LandscapeStore for WireGuardServiceConfig {
+    fn get_store_key(&self) -> String {
+        self.iface_name.clone()
+    }
+}
+
+impl LandscapeDBStore<String> for WireGuardServiceConfig {
+    fn get_id(&self) -> String {
+        self.iface_name.clone()
+    }
+    fn get_update_at(&self) -> f64 {
+        self.update_at
+    }
+    fn set_update_at(&mut self, ts: f64) {
+        self.update_at = ts;
+    }
+}
+
+impl super::iface::ZoneAwareConfig for WireGuardServiceConfig {
+    fn iface_name(&self) -> &str {
+        &self.iface_name
+    }
+    fn zone_requirement() -> super::iface::ZoneRequirement {
+        super::iface::ZoneRequirement::WanOrLan
+    }
+    fn service_kind() -> super::iface::ServiceKind {
+        super::iface::ServiceKind::WireGuard
+    }
+}
+
+fn invalid(reason: String) -> ServiceConfigError {
+    ServiceConfigError::InvalidConfig { reason }
+}
+
+impl WireGuardServiceConfig {
+    pub fn get_peer(&self, name: &str) -> Option<&WireGuardPeerConfig> {
+        self.peers.iter().find(|peer| peer.name == name)
+    }
+
+    pub fn validate(&self) -> Result<(), ServiceConfigError> {
+        if decode_wg_key(&self.private_key).is_none() {
+            return Err(invalid("invalid private key".to_string()));
+        }
+        if matches!(self.listen_port, Some(0)) {
+            return Err(invalid("listen port must not be 0".to_string()));
+        }
+        if let Some(mtu) = self.mtu {
+            if !(1280..=9000).contains(&mtu) {
+                return Err(invalid(format!("mtu must be in 1280-9000, got {mtu}")));
+            }
+        }
+        for addr in self.addresses.iter() {
+            if !addr.is_valid() || addr.is_default_route() {
+                return Err(invalid(format!("invalid interface address: {addr}")));
+            }
+        }
+
+        let mut names = std::collections::HashSet::new();
+        let mut keys = std::collections::HashSet::new();
+        for peer in self.peers.iter() {
+            if peer.name.trim().is_empty() {
+                return Err(invalid("peer name must not be empty".to_string()));
+            }
+            if !names.insert(peer.name.as_str()) {
+                return Err(invalid(format!("duplicate peer name: {}", peer.name)));
+            }
+            let Some(public_key) = decode_wg_key(&peer.public_key) else {
+                return Err(invalid(format!("invalid public key of peer: {}", peer.name)));
+            };
+            if !keys.insert(public_key) {
+                return Err(invalid(format!("duplicate public key of peer: {}", peer.name)));
+            }
+            for key in [&peer.private_key, &peer.preshared_key].into_iter().flatten() {
+                if decode_wg_key(key).is_none() {
+                    return Err(invalid(format!("invalid key of peer: {}", peer.name)));
+                }
+            }
+            if let Some(endpoint) = &peer.endpoint {
+                if endpoint
+                    .rsplit_once(':')
+                    .and_then(|(_, port)| port.parse::<u16>().ok())
+                    .is_none()
+                {
+                    return Err(invalid(format!("invalid endpoint of peer: {}", peer.name)));
+                }
+            }
+            for cidr in peer.allowed_ips.iter().chain(peer.client_allowed_ips.iter()) {
+                if !cidr.is_valid() {
+                    return Err(invalid(format!(
+                        "invalid allowed ip of peer {}: {cidr}",
+                        peer.name
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 生成的密钥对
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WireGuardKeyPair {
+    pub private_key: String,
+    pub public_key: String,
+}
+
+/// 从内核读取的运行状态
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WireGuardRuntimeInfo {
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub public_key: Option<String>,
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub listen_port: Option<u16>,
+    pub peers: Vec<WireGuardPeerRuntimeInfo>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WireGuardPeerRuntimeInfo {
+    pub public_key: String,
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub endpoint: Option<String>,
+    /// 最近一次握手时间 (秒), 未握手时为空
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub last_handshake: Option<f64>,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+}
@@ -1,6 +1,6 @@
 use crate::{
-    database::repository::LandscapeDBStore, store::storev2::LandscapeStore,
-    utils::time::get_f64_timestamp,
+    database::repository::LandscapeDBStore, service::ServiceConfigError,
+    store::storev2::LandscapeStore, utils::time::get_f64_timestamp,
 };
 use serde::{Deserialize, Serialize};
 
@@ -22,6 +22,39 @@ pub struct IPV6PDServiceConfig {
 pub struct IPV6PDConfig {
     #[cfg_attr(feature = "openapi", schema(value_type = String))]
     pub mac: MacAddr,
+    /// 请求前缀委派 (IA_PD)
+    #[serde(default = "default_request_pd")]
+    #[cfg_attr(feature = "openapi", schema(required = true))]
+    pub request_pd: bool,
+    /// 在同一次 DHCPv6 交互中为 WAN 口请求地址 (IA_NA)
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = true))]
+    pub request_na: bool,
+    /// 处理上游 RA: SLAAC 地址 / 默认路由 / RDNSS
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = true))]
+    pub accept_ra: bool,
+}
+
+const fn default_request_pd() -> bool {
+    true
+}
+
+impl IPV6PDConfig {
+    /// 是否需要运行 DHCPv6 客户端
+    pub fn need_dhcp_client(&self) -> bool {
+        self.request_pd || self.request_na
+    }
+
+    pub fn validate(&self) -> Result<(), ServiceConfigError> {
+        if !self.request_pd && !self.request_na && !self.accept_ra {
+            return Err(ServiceConfigError::InvalidConfig {
+                reason: "at least one of request_pd, request_na or accept_ra must be enabled"
+                    .to_string(),
+            });
+        }
+        Ok(())
+    }
 }
 
 impl LandscapeDBStore<String> for IPV6PDServiceConfig {
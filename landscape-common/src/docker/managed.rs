@@ -0,0 +1,221 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use landscape_macro::LdApiError;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::config::ConfigId;
+use crate::database::repository::LandscapeDBStore;
+use crate::docker::DockerCmd;
+use crate::utils::id::gen_database_uuid;
+use crate::utils::time::get_f64_timestamp;
+
+/// 被 Landscape 管理的容器标签, 值为配置 ID
+pub const LAND_MANAGED_CONTAINER_LABEL: &str = "ld_managed_container";
+
+#[derive(thiserror::Error, Debug, LdApiError)]
+#[api_error(crate_path = "crate")]
+pub enum ManagedContainerError {
+    #[error("Managed container '{0}' not found")]
+    #[api_error(id = "managed_container.not_found", status = 404)]
+    NotFound(ConfigId),
+
+    #[error("Managed container name '{0}' is already used")]
+    #[api_error(id = "managed_container.duplicate_name", status = 400)]
+    DuplicateName(String),
+
+    #[error("Invalid managed container: {0}")]
+    #[api_error(id = "managed_container.invalid", status = 400)]
+    InvalidConfig(String),
+
+    #[error("Container '{0}' exists but is not managed by this definition")]
+    #[api_error(id = "managed_container.not_managed", status = 409)]
+    NotManaged(String),
+
+    #[error("Docker error: {0}")]
+    #[api_error(id = "managed_container.docker", status = 500)]
+    Docker(String),
+}
+
+/// redirect_pkg_handler 的处理模式, 对应 `LAND_PROXY_HANDLE_MODE`
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum RedirectHandleMode {
+    Tproxy,
+    Route,
+    MultipleTproxy,
+}
+
+impl RedirectHandleMode {
+    pub fn as_env_value(&self) -> &'static str {
+        match self {
+            RedirectHandleMode::Tproxy => "tproxy",
+            RedirectHandleMode::Route => "route",
+            RedirectHandleMode::MultipleTproxy => "multiple_tproxy",
+        }
+    }
+}
+
+fn default_server_addr() -> Ipv4Addr {
+    Ipv4Addr::UNSPECIFIED
+}
+
+fn default_server_addr_v6() -> Ipv6Addr {
+    Ipv6Addr::UNSPECIFIED
+}
+
+fn default_server_port() -> u16 {
+    12345
+}
+
+/// 容器内 redirect_pkg_handler 的参数
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ManagedRedirectConfig {
+    pub mode: RedirectHandleMode,
+    #[serde(default = "default_server_addr")]
+    #[cfg_attr(feature = "openapi", schema(value_type = String, required = false))]
+    pub server_addr: Ipv4Addr,
+    #[serde(default = "default_server_addr_v6")]
+    #[cfg_attr(feature = "openapi", schema(value_type = String, required = false))]
+    pub server_addr_v6: Ipv6Addr,
+    #[serde(default = "default_server_port")]
+    #[cfg_attr(feature = "openapi", schema(required = false))]
+    pub server_port: u16,
+}
+
+impl ManagedRedirectConfig {
+    /// 传递给 redirect_pkg_handler 的环境变量
+    pub fn envs(&self) -> Vec<(String, String)> {
+        vec![
+            ("LAND_PROXY_SERVER_ADDR".to_string(), self.server_addr.to_string()),
+            ("LAND_PROXY_SERVER_ADDR_V6".to_string(), self.server_addr_v6.to_string()),
+            ("LAND_PROXY_SERVER_PORT".to_string(), self.server_port.to_string()),
+            ("LAND_PROXY_HANDLE_MODE".to_string(), self.mode.as_env_value().to_string()),
+        ]
+    }
+}
+
+/// 由 Landscape 负责创建 / 启停 / 重建的代理容器
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ManagedContainerConfig {
+    #[serde(default = "gen_database_uuid")]
+    #[cfg_attr(feature = "openapi", schema(required = false))]
+    pub id: Uuid,
+    /// 关闭时停止容器
+    pub enable: bool,
+    /// 容器名称, 同时也是 Flow 出口名称
+    pub name: String,
+    /// 容器定义, 其中的 container_name 以 name 为准
+    pub cmd: DockerCmd,
+    /// 设置后容器作为 Flow 出口运行 redirect_pkg_handler
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub redirect: Option<ManagedRedirectConfig>,
+    /// 定期检查镜像是否有更新
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = false))]
+    pub check_update: bool,
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = false))]
+    pub remark: String,
+    #[serde(default = "get_f64_timestamp")]
+    #[cfg_attr(feature = "openapi", schema(required = false))]
+    pub update_at: f64,
+}
+
+impl ManagedContainerConfig {
+    pub fn validate(&self) -> Result<(), ManagedContainerError> {
+        let valid_name = !self.name.is_empty()
+            && self.name.chars().all(|c| c.is_ascii_alphanumeric() || "_.-".contains(c))
+            && self.name.chars().next().is_some_and(|c| c.is_ascii_alphanumeric());
+        if !valid_name {
+            return Err(ManagedContainerError::InvalidConfig(format!(
+                "invalid name: {}",
+                self.name
+            )));
+        }
+        if self.cmd.image_name.trim().is_empty() {
+            return Err(ManagedContainerError::InvalidConfig("image is empty".to_string()));
+        }
+        if let Some(redirect) = &self.redirect {
+            if redirect.server_port == 0 {
+                return Err(ManagedContainerError::InvalidConfig(
+                    "redirect server port is 0".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// 是否需要重建容器才能使配置生效
+    pub fn need_recreate(&self, old: &ManagedContainerConfig) -> bool {
+        self.name != old.name || self.cmd != old.cmd || self.redirect != old.redirect
+    }
+}
+
+impl LandscapeDBStore<Uuid> for ManagedContainerConfig {
+    fn get_id(&self) -> Uuid {
+        self.id
+    }
+    fn get_update_at(&self) -> f64 {
+        self.update_at
+    }
+    fn set_update_at(&mut self, ts: f64) {
+        self.update_at = ts;
+    }
+}
+
+/// 托管容器及其运行状态
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ManagedContainerStatus {
+    pub config: ManagedContainerConfig,
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub container_id: Option<String>,
+    /// Docker 中的容器状态, 如 running / exited
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub state: Option<String>,
+    /// 仓库中有比本地更新的镜像
+    pub update_available: bool,
+    /// 最近一次操作失败的原因
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub error: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_managed_container_config() {
+        let config: ManagedContainerConfig = serde_json::from_str(
+            r#"{
+                "enable": true,
+                "name": "proxy-a",
+                "cmd": { "image_name": "ghcr.io/example/proxy:latest" },
+                "redirect": { "mode": "multiple_tproxy" }
+            }"#,
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+
+        let redirect = config.redirect.clone().unwrap();
+        assert_eq!(redirect.server_port, 12345);
+        assert!(redirect
+            .envs()
+            .contains(&("LAND_PROXY_HANDLE_MODE".to_string(), "multiple_tproxy".to_string())));
+
+        let mut renamed = config.clone();
+        renamed.name = "-proxy".to_string();
+        assert!(renamed.validate().is_err());
+
+        let mut changed = config.clone();
+        changed.remark = "remark".to_string();
+        assert!(!changed.need_recreate(&config));
+        changed.cmd.image_name = "ghcr.io/example/proxy:v2".to_string();
+        assert!(changed.need_recreate(&config));
+    }
+}
@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 use crate::{NAMESPACE_REGISTER_SOCK_PATH, NAMESPACE_REGISTER_SOCK_PATH_IN_DOCKER};
 
 pub mod image;
+pub mod managed;
 /// This file is to prepare for the future migration
 /// of the docker api library to avoid large-scale modification of the API
 ///
@@ -13,13 +14,16 @@ pub mod network;
 
 pub const DOCKER_NETWORK_BRIDGE_NAME_OPTION_KEY: &str = "com.docker.network.bridge.name";
 
+/// 作为 Flow 出口的容器标签
+pub const LAND_FLOW_EDGE_LABEL: &str = "ld_flow_edge";
+
 #[derive(Serialize, Deserialize, Debug, Default)]
 pub struct DockerTargetEnroll {
     pub id: String,
     pub ifindex: u32,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct DockerCmd {
     pub image_name: String,
@@ -90,7 +94,7 @@ impl DockerCmd {
         let mut accept_local = false;
         if let Some(labels) = &self.labels {
             for label in labels {
-                if label.key == "ld_flow_edge" {
+                if label.key == LAND_FLOW_EDGE_LABEL {
                     accept_local = true;
                 }
                 command.push("--label".to_string());
@@ -144,7 +148,7 @@ impl DockerCmd {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct KeyValuePair {
     pub key: String,
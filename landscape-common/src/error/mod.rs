@@ -20,6 +20,9 @@ pub enum LdError {
     #[error("setting cpu balance error: {0}")]
     SettingCpuBalanceError(String),
 
+    #[error("network device error: {0}")]
+    NetDevError(String),
+
     #[error("Database error: {0}")]
     DatabaseError(#[from] DbErr),
 
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::database::repository::LandscapeDBStore;
+use crate::flow::health::FlowHealthCheck;
 use crate::flow::{FlowEntryRule, FlowTarget};
 use crate::utils::id::gen_database_uuid;
 use crate::utils::time::get_f64_timestamp;
@@ -22,6 +23,10 @@ pub struct FlowConfig {
     /// 处理流量目标网卡, 目前只取第一个
     /// 暂定, 可能会移动到具体的网卡上进行设置
     pub flow_targets: Vec<FlowTarget>,
+    /// 出口健康检查, 不可用的出口会被跳过
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = false, nullable = true))]
+    pub health_check: Option<FlowHealthCheck>,
     /// 备注
     pub remark: String,
 
@@ -30,6 +35,13 @@ pub struct FlowConfig {
     pub update_at: f64,
 }
 
+impl FlowConfig {
+    /// 已启用的健康检查配置
+    pub fn active_health_check(&self) -> Option<&FlowHealthCheck> {
+        self.health_check.as_ref().filter(|check| check.enable)
+    }
+}
+
 impl LandscapeDBStore<Uuid> for FlowConfig {
     fn get_id(&self) -> Uuid {
         self.id
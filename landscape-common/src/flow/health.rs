@@ -0,0 +1,176 @@
+use serde::{Deserialize, Serialize};
+
+use crate::flow::{FlowRuleError, FlowTarget};
+
+/// Flow 出口健康检查配置
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FlowHealthCheck {
+    pub enable: bool,
+    /// 检查间隔 (秒)
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u32,
+    /// 单次探测超时 (毫秒)
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u32,
+    /// 连续失败多少次后判定出口不可用
+    #[serde(default = "default_failure_threshold")]
+    pub failure_threshold: u32,
+    /// 探测项, 全部通过才视为健康
+    #[serde(default)]
+    pub probes: Vec<FlowTargetProbe>,
+    /// 所有出口均不可用时改走默认 WAN, 否则丢弃该 Flow 的流量
+    #[serde(default)]
+    pub fallback_direct: bool,
+}
+
+fn default_interval_secs() -> u32 {
+    10
+}
+
+fn default_timeout_ms() -> u32 {
+    3000
+}
+
+fn default_failure_threshold() -> u32 {
+    3
+}
+
+impl Default for FlowHealthCheck {
+    fn default() -> Self {
+        FlowHealthCheck {
+            enable: false,
+            interval_secs: default_interval_secs(),
+            timeout_ms: default_timeout_ms(),
+            failure_threshold: default_failure_threshold(),
+            probes: vec![FlowTargetProbe::ContainerRunning],
+            fallback_direct: false,
+        }
+    }
+}
+
+impl FlowHealthCheck {
+    pub fn validate(&self) -> Result<(), FlowRuleError> {
+        if self.interval_secs == 0 {
+            return Err(FlowRuleError::InvalidHealthCheck("interval must be positive".into()));
+        }
+        if self.timeout_ms == 0 || self.timeout_ms as u64 >= self.interval_secs as u64 * 1000 {
+            return Err(FlowRuleError::InvalidHealthCheck(
+                "timeout must be positive and shorter than interval".into(),
+            ));
+        }
+        if self.failure_threshold == 0 {
+            return Err(FlowRuleError::InvalidHealthCheck(
+                "failure threshold must be positive".into(),
+            ));
+        }
+        for probe in self.probes.iter() {
+            probe.validate()?;
+        }
+        Ok(())
+    }
+}
+
+/// 出口探测方式
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(tag = "t")]
+#[serde(rename_all = "snake_case")]
+pub enum FlowTargetProbe {
+    /// 容器处于运行状态 / 命名空间存在 / 本机 TProxy 端口可连接, 对网卡出口不生效
+    ContainerRunning,
+    /// 容器、命名空间或本机 127.0.0.1 上的端口可连接 (如 TProxy 端口), 对网卡出口不生效
+    TcpPort { port: u16 },
+    /// 经由该出口发起 HTTP 请求, 返回 2xx / 3xx 视为成功, 对本机 TProxy 出口不生效
+    Http { url: String },
+}
+
+impl FlowTargetProbe {
+    pub fn validate(&self) -> Result<(), FlowRuleError> {
+        match self {
+            FlowTargetProbe::TcpPort { port: 0 } => {
+                Err(FlowRuleError::InvalidHealthCheck("probe port must not be 0".into()))
+            }
+            FlowTargetProbe::Http { url }
+                if !url.starts_with("http://") && !url.starts_with("https://") =>
+            {
+                Err(FlowRuleError::InvalidHealthCheck(format!("invalid probe url: {url}")))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// 单个出口的健康状态
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FlowTargetHealth {
+    pub target: FlowTarget,
+    pub healthy: bool,
+    pub consecutive_failures: u32,
+    /// 最近一次检查时间 (毫秒时间戳)
+    pub last_check: u64,
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub last_error: Option<String>,
+}
+
+/// 出口切换记录
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FlowTargetSwitchEvent {
+    pub flow_id: u32,
+    /// 切换前的出口, 为空表示直连或丢弃
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub from: Option<FlowTarget>,
+    /// 切换后的出口, 为空表示直连或丢弃
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub to: Option<FlowTarget>,
+    /// 切换后是否回退到默认 WAN
+    pub direct: bool,
+    pub reason: String,
+    pub time: u64,
+}
+
+/// Flow 健康检查状态
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FlowHealthStatus {
+    pub flow_id: u32,
+    /// 当前使用的出口
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub active_target: Option<FlowTarget>,
+    /// 是否已回退到默认 WAN
+    pub direct: bool,
+    pub targets: Vec<FlowTargetHealth>,
+    /// 最近的切换记录, 新的在前
+    pub switch_events: Vec<FlowTargetSwitchEvent>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        assert!(FlowHealthCheck::default().validate().is_ok());
+
+        let check = FlowHealthCheck {
+            interval_secs: 2,
+            timeout_ms: 2000,
+            ..Default::default()
+        };
+        assert!(check.validate().is_err());
+
+        let check = FlowHealthCheck {
+            probes: vec![FlowTargetProbe::Http { url: "example.com".into() }],
+            ..Default::default()
+        };
+        assert!(check.validate().is_err());
+
+        let check = FlowHealthCheck {
+            probes: vec![FlowTargetProbe::TcpPort { port: 0 }],
+            ..Default::default()
+        };
+        assert!(check.validate().is_err());
+    }
+}
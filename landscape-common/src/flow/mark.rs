@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
 
+use crate::flow::{qos::FlowQos, FlowRuleError};
+
 const FLOW_ID_MASK: u32 = 0x000000FF;
-const FLOW_ACTION_MASK: u32 = 0x00007F00;
+const FLOW_ACTION_MASK: u32 = 0x00000F00;
 const FLOW_ALLOW_REUSE_PORT_MASK: u32 = 0x00008000;
 
 const FLOW_KEEP_GOING: u8 = 0;
@@ -20,12 +22,17 @@ pub struct FlowMark {
 
     /// Flow Id
     flow_id: u8,
+
+    /// 出口 QoS, 为空时沿用 Flow 入口规则的设置
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    qos: Option<FlowQos>,
 }
 
 impl FlowMark {
     pub fn need_insert_in_ebpf_map(&self) -> bool {
         match self.action {
-            FlowMarkAction::KeepGoing => self.allow_reuse_port,
+            FlowMarkAction::KeepGoing => self.allow_reuse_port || self.qos.is_some(),
             _ => true,
         }
     }
@@ -50,6 +57,10 @@ impl FlowMark {
     pub fn set_reuseport(&mut self, value: bool) {
         self.allow_reuse_port = value;
     }
+
+    pub fn validate(&self) -> Result<(), FlowRuleError> {
+        self.qos.as_ref().map_or(Ok(()), FlowQos::validate)
+    }
 }
 
 impl From<u32> for FlowMark {
@@ -59,7 +70,8 @@ impl From<u32> for FlowMark {
         let allow_reuse_port = (value & FLOW_ALLOW_REUSE_PORT_MASK) != 0;
 
         let action: FlowMarkAction = raw_action.into();
-        FlowMark { action, allow_reuse_port, flow_id }
+        let qos = FlowQos::from_mark_bits(value);
+        FlowMark { action, allow_reuse_port, flow_id, qos }
     }
 }
 
@@ -76,6 +88,10 @@ impl Into<u32> for FlowMark {
             value |= FLOW_ALLOW_REUSE_PORT_MASK;
         }
 
+        if let Some(qos) = self.qos {
+            value |= qos.to_mark_bits();
+        }
+
         value
     }
 }
@@ -123,6 +139,7 @@ impl Into<u8> for FlowMarkAction {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::flow::qos::FlowPriorityClass;
 
     #[test]
     fn test_from_u32() {
@@ -131,7 +148,8 @@ mod tests {
             FlowMark {
                 action: FlowMarkAction::Direct,
                 allow_reuse_port: false,
-                flow_id: 0
+                flow_id: 0,
+                qos: None
             }
         );
         assert_eq!(
@@ -139,7 +157,8 @@ mod tests {
             FlowMark {
                 action: FlowMarkAction::Redirect,
                 allow_reuse_port: false,
-                flow_id: 5
+                flow_id: 5,
+                qos: None
             }
         );
         assert_eq!(
@@ -147,7 +166,8 @@ mod tests {
             FlowMark {
                 action: FlowMarkAction::Redirect,
                 allow_reuse_port: true,
-                flow_id: 0
+                flow_id: 0,
+                qos: None
             }
         );
     }
@@ -158,6 +178,7 @@ mod tests {
             action: FlowMarkAction::Direct,
             allow_reuse_port: false,
             flow_id: 0,
+            qos: None,
         }
         .into();
         assert_eq!(mark, 0x0100);
@@ -166,6 +187,7 @@ mod tests {
             action: FlowMarkAction::Redirect,
             allow_reuse_port: false,
             flow_id: 5,
+            qos: None,
         }
         .into();
         assert_eq!(mark, 0x0305);
@@ -174,8 +196,26 @@ mod tests {
             action: FlowMarkAction::Redirect,
             allow_reuse_port: true,
             flow_id: 0,
+            qos: None,
         }
         .into();
         assert_eq!(mark, 0x8000 | 0x0300); // 0x8000 | 0x0300
     }
+
+    #[test]
+    fn test_qos_u32() {
+        let flow_mark = FlowMark {
+            action: FlowMarkAction::KeepGoing,
+            allow_reuse_port: false,
+            flow_id: 0,
+            qos: Some(FlowQos {
+                dscp: Some(46),
+                priority: Some(FlowPriorityClass::Voice),
+            }),
+        };
+        let mark: u32 = flow_mark.into();
+        assert_eq!(mark, 0x00400000 | (46 << 16) | 0x4000);
+        assert_eq!(FlowMark::from(mark), flow_mark);
+        assert!(flow_mark.need_insert_in_ebpf_map());
+    }
 }
@@ -4,10 +4,15 @@ use landscape_macro::LdApiError;
 use serde::{Deserialize, Serialize};
 
 use crate::config::ConfigId;
-use crate::{flow::mark::FlowMark, net::MacAddr};
+use crate::{
+    flow::{mark::FlowMark, qos::FlowQos},
+    net::MacAddr,
+};
 
 pub mod config;
+pub mod health;
 pub mod mark;
+pub mod qos;
 pub mod target;
 
 #[derive(thiserror::Error, Debug, LdApiError)]
@@ -24,16 +29,46 @@ pub enum FlowRuleError {
     #[error("Entry rule '{rule}' conflicts with flow '{flow_remark}' (ID: {flow_id})")]
     #[api_error(id = "flow_rule.conflict_entry", status = 400)]
     ConflictEntryRule { rule: String, flow_remark: String, flow_id: u32 },
+
+    #[error("Invalid DSCP value {0}, must be 0-63")]
+    #[api_error(id = "flow_rule.invalid_dscp", status = 400)]
+    InvalidDscp(u8),
+
+    #[error("Invalid VLAN ID {0}, must be 1-4094")]
+    #[api_error(id = "flow_rule.invalid_vlan", status = 400)]
+    InvalidVlanId(u16),
+
+    #[error("Entry rule requires an interface name")]
+    #[api_error(id = "flow_rule.empty_iface", status = 400)]
+    EmptyIfaceName,
+
+    #[error("Invalid health check: {0}")]
+    #[api_error(id = "flow_rule.invalid_health_check", status = 400)]
+    InvalidHealthCheck(String),
+
+    #[error("Invalid flow target: {0}")]
+    #[api_error(id = "flow_rule.invalid_target", status = 400)]
+    InvalidTarget(String),
+
+    #[error("Bridge port '{0}' ifindex {1} exceeds 16 bits")]
+    #[api_error(id = "flow_rule.bridge_port_ifindex", status = 400)]
+    BridgePortIfindexTooLarge(String, u32),
+}
+
+/// 网桥端口标记保存在 16 位的 tc_index, ifindex 超出时无法匹配
+pub fn check_bridge_port_ifindex(iface_name: &str, ifindex: u32) -> Result<u16, FlowRuleError> {
+    u16::try_from(ifindex)
+        .map_err(|_| FlowRuleError::BridgePortIfindexTooLarge(iface_name.to_string(), ifindex))
 }
 
 /// Flow 入口匹配规则
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct FlowEntryRule {
-    // pub vlan_id: Option<u32>,
+    /// 匹配的流量在出口设置的 DSCP / 优先级
     #[serde(default)]
     #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
-    pub qos: Option<u32>,
+    pub qos: Option<FlowQos>,
     pub mode: FlowEntryMatchMode,
 }
 
@@ -53,6 +88,38 @@ pub enum FlowEntryMatchMode {
         #[cfg_attr(feature = "openapi", schema(required = true))]
         prefix_len: u8,
     },
+    /// 从指定网卡进入的流量, 如 VLAN 子接口或访客网桥
+    Iface { iface_name: String },
+    /// 携带指定 802.1Q 标签进入的流量
+    Vlan { vlan_id: u16 },
+    /// 从网桥的指定端口进入的流量
+    BridgePort { iface_name: String },
+}
+
+impl FlowEntryMatchMode {
+    pub fn validate(&self) -> Result<(), FlowRuleError> {
+        match self {
+            FlowEntryMatchMode::Vlan { vlan_id } if !(1..=4094).contains(vlan_id) => {
+                Err(FlowRuleError::InvalidVlanId(*vlan_id))
+            }
+            FlowEntryMatchMode::Iface { iface_name }
+            | FlowEntryMatchMode::BridgePort { iface_name }
+                if iface_name.is_empty() =>
+            {
+                Err(FlowRuleError::EmptyIfaceName)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// 按网卡名称匹配时使用的网卡, ifindex 需要在写入 eBPF 时解析
+    pub fn iface_name(&self) -> Option<&str> {
+        match self {
+            FlowEntryMatchMode::Iface { iface_name }
+            | FlowEntryMatchMode::BridgePort { iface_name } => Some(iface_name),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for FlowEntryMatchMode {
@@ -60,6 +127,11 @@ impl fmt::Display for FlowEntryMatchMode {
         match self {
             FlowEntryMatchMode::Mac { mac_addr } => write!(f, "MAC {}", mac_addr),
             FlowEntryMatchMode::Ip { ip, prefix_len } => write!(f, "IP {}/{}", ip, prefix_len),
+            FlowEntryMatchMode::Iface { iface_name } => write!(f, "Iface {}", iface_name),
+            FlowEntryMatchMode::Vlan { vlan_id } => write!(f, "VLAN {}", vlan_id),
+            FlowEntryMatchMode::BridgePort { iface_name } => {
+                write!(f, "Bridge port {}", iface_name)
+            }
         }
     }
 }
@@ -80,13 +152,57 @@ fn default_prefix_len() -> u8 {
     32
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(tag = "t")]
 #[serde(rename_all = "snake_case")]
 pub enum FlowTarget {
-    Interface { name: String },
-    Netns { container_name: String },
+    Interface {
+        name: String,
+    },
+    Netns {
+        container_name: String,
+    },
+    /// 非 Docker 的命名空间出口, 引用命名空间出口配置的名称
+    Namespace {
+        name: String,
+    },
+    /// 交给宿主机上监听 127.0.0.1 / ::1 (或通配地址) 的 TProxy 进程
+    /// 未启用的 IP 版本继续选择下一个出口, 未启用的协议按默认路由转发
+    Tproxy {
+        port: u16,
+        #[serde(default = "default_true")]
+        ipv4: bool,
+        #[serde(default = "default_true")]
+        ipv6: bool,
+        #[serde(default = "default_true")]
+        tcp: bool,
+        #[serde(default = "default_true")]
+        udp: bool,
+    },
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl FlowTarget {
+    pub fn validate(&self) -> Result<(), FlowRuleError> {
+        match self {
+            FlowTarget::Tproxy { port, ipv4, ipv6, tcp, udp } => {
+                if *port == 0 {
+                    return Err(FlowRuleError::InvalidTarget("tproxy port must not be 0".into()));
+                }
+                if !(*ipv4 || *ipv6) || !(*tcp || *udp) {
+                    return Err(FlowRuleError::InvalidTarget(
+                        "tproxy needs at least one ip version and one protocol".into(),
+                    ));
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
 }
 
 /// 用于 Flow ebpf DNS Map 记录操作
@@ -102,3 +218,63 @@ pub struct DnsRuntimeMarkInfo {
     pub mark: FlowMark,
     pub priority: u16,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_entry_mode() {
+        assert!(FlowEntryMatchMode::Vlan { vlan_id: 100 }.validate().is_ok());
+        assert!(FlowEntryMatchMode::Vlan { vlan_id: 0 }.validate().is_err());
+        assert!(FlowEntryMatchMode::Vlan { vlan_id: 4095 }.validate().is_err());
+        assert!(FlowEntryMatchMode::Iface { iface_name: "br-guest".into() }.validate().is_ok());
+        assert!(FlowEntryMatchMode::BridgePort { iface_name: String::new() }.validate().is_err());
+    }
+
+    #[test]
+    fn test_bridge_port_ifindex() {
+        assert_eq!(check_bridge_port_ifindex("eth1", 3).unwrap(), 3);
+        assert_eq!(check_bridge_port_ifindex("eth1", 0xFFFF).unwrap(), 0xFFFF);
+        assert!(matches!(
+            check_bridge_port_ifindex("eth1", 0x10000),
+            Err(FlowRuleError::BridgePortIfindexTooLarge(_, 0x10000))
+        ));
+        let mode = FlowEntryMatchMode::BridgePort { iface_name: "eth1".into() };
+        assert_eq!(mode.iface_name(), Some("eth1"));
+        assert_eq!(FlowEntryMatchMode::Vlan { vlan_id: 10 }.iface_name(), None);
+    }
+
+    #[test]
+    fn test_deserialize_entry_mode() {
+        let mode: FlowEntryMatchMode =
+            serde_json::from_str(r#"{"t":"bridge_port","iface_name":"eth1"}"#).unwrap();
+        assert_eq!(mode, FlowEntryMatchMode::BridgePort { iface_name: "eth1".into() });
+    }
+
+    #[test]
+    fn test_tproxy_target() {
+        let target: FlowTarget =
+            serde_json::from_str(r#"{"t":"tproxy","port":12345,"udp":false}"#).unwrap();
+        assert_eq!(
+            target,
+            FlowTarget::Tproxy {
+                port: 12345,
+                ipv4: true,
+                ipv6: true,
+                tcp: true,
+                udp: false
+            }
+        );
+        assert!(target.validate().is_ok());
+
+        let target = FlowTarget::Tproxy {
+            port: 12345,
+            ipv4: true,
+            ipv6: true,
+            tcp: false,
+            udp: false,
+        };
+        assert!(target.validate().is_err());
+    }
+}
@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+
+use crate::flow::FlowRuleError;
+
+/// DSCP 取值上限 (6 bit)
+pub const MAX_DSCP: u8 = 63;
+
+const FLOW_QOS_CLASS_MASK: u32 = 0x00007000;
+const FLOW_QOS_DSCP_MASK: u32 = 0x003F0000;
+const FLOW_QOS_DSCP_SET_MASK: u32 = 0x00400000;
+
+const FLOW_QOS_CLASS_BULK: u8 = 1;
+const FLOW_QOS_CLASS_BEST_EFFORT: u8 = 2;
+const FLOW_QOS_CLASS_VIDEO: u8 = 3;
+const FLOW_QOS_CLASS_VOICE: u8 = 4;
+
+/// 优先级分类, eBPF 按分类将 skb->priority 设为 SQM 队列中对应的 classid
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum FlowPriorityClass {
+    /// 1:4, cake Voice tin
+    Voice,
+    /// 1:3, cake Video tin
+    Video,
+    /// 1:2, cake Best Effort tin
+    BestEffort,
+    /// 1:1, cake Bulk tin
+    Bulk,
+}
+
+/// 出口 QoS 设置
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FlowQos {
+    /// 改写的 DSCP 值 (0-63), 为空时保持原值
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub dscp: Option<u8>,
+    /// 优先级分类, 为空时不修改 skb->priority
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub priority: Option<FlowPriorityClass>,
+}
+
+impl FlowQos {
+    pub fn validate(&self) -> Result<(), FlowRuleError> {
+        match self.dscp {
+            Some(dscp) if dscp > MAX_DSCP => Err(FlowRuleError::InvalidDscp(dscp)),
+            _ => Ok(()),
+        }
+    }
+
+    /// 编码到 mark 中的 QoS 位
+    pub fn to_mark_bits(&self) -> u32 {
+        let mut value = 0;
+        if let Some(dscp) = self.dscp {
+            value |= ((dscp as u32) << 16) & FLOW_QOS_DSCP_MASK;
+            value |= FLOW_QOS_DSCP_SET_MASK;
+        }
+        let raw_class = match self.priority {
+            None => 0,
+            Some(FlowPriorityClass::Bulk) => FLOW_QOS_CLASS_BULK,
+            Some(FlowPriorityClass::BestEffort) => FLOW_QOS_CLASS_BEST_EFFORT,
+            Some(FlowPriorityClass::Video) => FLOW_QOS_CLASS_VIDEO,
+            Some(FlowPriorityClass::Voice) => FLOW_QOS_CLASS_VOICE,
+        };
+        value | ((raw_class as u32) << 12)
+    }
+
+    /// 从 mark 中解析 QoS, 未设置任何项时返回 None
+    pub fn from_mark_bits(value: u32) -> Option<Self> {
+        let dscp = if value & FLOW_QOS_DSCP_SET_MASK != 0 {
+            Some(((value & FLOW_QOS_DSCP_MASK) >> 16) as u8)
+        } else {
+            None
+        };
+        let priority = match ((value & FLOW_QOS_CLASS_MASK) >> 12) as u8 {
+            FLOW_QOS_CLASS_BULK => Some(FlowPriorityClass::Bulk),
+            FLOW_QOS_CLASS_BEST_EFFORT => Some(FlowPriorityClass::BestEffort),
+            FLOW_QOS_CLASS_VIDEO => Some(FlowPriorityClass::Video),
+            FLOW_QOS_CLASS_VOICE => Some(FlowPriorityClass::Voice),
+            _ => None,
+        };
+        if dscp.is_none() && priority.is_none() {
+            None
+        } else {
+            Some(FlowQos { dscp, priority })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mark_bits() {
+        let qos = FlowQos {
+            dscp: Some(46),
+            priority: Some(FlowPriorityClass::Voice),
+        };
+        assert_eq!(qos.to_mark_bits(), 0x00400000 | (46 << 16) | 0x4000);
+        assert_eq!(FlowQos::from_mark_bits(qos.to_mark_bits()), Some(qos));
+
+        let qos = FlowQos { dscp: Some(0), priority: None };
+        assert_eq!(qos.to_mark_bits(), 0x00400000);
+        assert_eq!(FlowQos::from_mark_bits(qos.to_mark_bits()), Some(qos));
+
+        assert_eq!(FlowQos::default().to_mark_bits(), 0);
+        assert_eq!(FlowQos::from_mark_bits(0x0305), None);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(FlowQos { dscp: Some(63), priority: None }.validate().is_ok());
+        assert!(FlowQos { dscp: Some(64), priority: None }.validate().is_err());
+    }
+}
@@ -56,6 +56,17 @@ impl DefaultRouterManager {
         self.gen_and_set_ecmp_route().await;
     }
 
+    /// 仅移除接口的 IPv6 默认路由, 保留其他类型
+    pub async fn del_ipv6_route_by_iface(&self, iface_name: &str) {
+        tracing::info!("del: {:#?} ipv6 default router", iface_name);
+        let mut infos = self.infos.write().await;
+        infos.retain(|info| {
+            !(info.iface_name == iface_name && matches!(info.route, RouteType::Ipv6(_)))
+        });
+        drop(infos);
+        self.gen_and_set_ecmp_route().await;
+    }
+
     /// gen cmd like this
     /// ip route add default \
     /// nexthop via 192.168.1.1 dev eth0 weight 2 \
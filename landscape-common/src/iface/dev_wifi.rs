@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 
+use crate::config::wifi::WifiBand;
+use crate::net::MacAddr;
+
 /// 无线接口类型
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
@@ -28,4 +31,64 @@ pub struct LandscapeWifiInterface {
     pub name: String,
     pub index: u32,
     pub wifi_type: WLANType,
+    /// 所属的物理网卡 (wiphy) 编号
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub wiphy: Option<u32>,
+}
+
+/// 物理网卡能力
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WifiPhyCapability {
+    pub wiphy: u32,
+    pub bands: Vec<WifiBandCapability>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WifiBandCapability {
+    pub band: WifiBand,
+    /// 802.11n
+    pub ht: bool,
+    pub ht40: bool,
+    /// 802.11ac
+    pub vht: bool,
+    pub vht160: bool,
+    /// 802.11ax
+    pub he: bool,
+    pub he160: bool,
+    pub channels: Vec<WifiChannelCapability>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WifiChannelCapability {
+    pub channel: u8,
+    pub freq: u32,
+    pub disabled: bool,
+    /// 不允许主动发射, 无法作为 AP 使用
+    pub no_ir: bool,
+    /// 需要 DFS 雷达检测
+    pub radar: bool,
+    /// 最大发射功率 (0.01 dBm)
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub max_tx_power: Option<u32>,
+}
+
+/// 已连接的客户端
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WifiStationInfo {
+    /// 客户端所连接的 BSS 接口
+    pub iface_name: String,
+    pub mac: MacAddr,
+    /// dBm
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub signal: Option<i8>,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    /// 已连接时长 (秒)
+    pub connected_time: u32,
+    /// 空闲时长 (毫秒)
+    pub inactive_time: u32,
 }
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::config::iface::{IfaceZoneType, NetworkIfaceConfig};
+use crate::config::iface::{CreateDevParams, IfaceZoneType, NetworkIfaceConfig};
 use crate::dev::LandscapeInterface;
 use dev_wifi::LandscapeWifiInterface;
 
@@ -12,6 +12,14 @@ pub struct BridgeCreate {
     pub name: String,
 }
 
+/// 创建 VLAN / Bond / MACVLAN / WireGuard 设备
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct VirtualDevCreate {
+    pub name: String,
+    pub params: CreateDevParams,
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct AddController {
@@ -4,17 +4,29 @@ use landscape_macro::LdApiError;
 
 use crate::config::geo::GeoConfigKey;
 use crate::config::ConfigId;
+use crate::network::LandscapeIpProtocolCode;
 use crate::utils::time::get_f64_timestamp;
 use crate::{database::repository::LandscapeDBStore, flow::mark::FlowMark};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+/// 每个 Flow 中同一 IP 版本带 L4 条件的规则上限, 与 eBPF 中 FLOW_PORT_RULE_MAX 保持一致
+pub const FLOW_PORT_RULE_MAX: usize = 32;
+
 #[derive(thiserror::Error, Debug, LdApiError)]
 #[api_error(crate_path = "crate")]
 pub enum DstIpRuleError {
     #[error("Destination IP rule '{0}' not found")]
     #[api_error(id = "dst_ip_rule.not_found", status = 404)]
     NotFound(ConfigId),
+
+    #[error("Invalid destination IP rule: {0}")]
+    #[api_error(id = "dst_ip_rule.invalid", status = 400)]
+    InvalidRule(String),
+
+    #[error("Flow {0} exceeds the limit of {1} protocol / port rules per IP version")]
+    #[api_error(id = "dst_ip_rule.port_rule_limit", status = 400)]
+    PortRuleLimit(u32, usize),
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -55,6 +67,51 @@ fn default_flow_id() -> u32 {
     0_u32
 }
 
+impl WanIpRuleConfig {
+    pub fn validate(&self) -> Result<(), DstIpRuleError> {
+        for source in self.source.iter() {
+            if let WanIPRuleSource::Config(config) = source {
+                config.validate()?;
+            }
+        }
+        check_flow_port_rule_limit(self.flow_id, [self])
+    }
+
+    /// 带 L4 条件的手动配置, 按 (IPv4, IPv6) 计数
+    fn port_rule_count(&self) -> (usize, usize) {
+        let mut count = (0, 0);
+        for source in self.source.iter() {
+            if let WanIPRuleSource::Config(config) = source {
+                if config.l4_protocol.is_none() && config.dst_port.is_none() {
+                    continue;
+                }
+                if config.ip.is_ipv4() {
+                    count.0 += 1;
+                } else {
+                    count.1 += 1;
+                }
+            }
+        }
+        count
+    }
+}
+
+/// 检查同一 Flow 下已启用规则中带 L4 条件的数量是否超出 eBPF 的上限
+pub fn check_flow_port_rule_limit<'a>(
+    flow_id: u32,
+    rules: impl IntoIterator<Item = &'a WanIpRuleConfig>,
+) -> Result<(), DstIpRuleError> {
+    let (v4, v6) = rules
+        .into_iter()
+        .filter(|rule| rule.enable && rule.flow_id == flow_id)
+        .map(WanIpRuleConfig::port_rule_count)
+        .fold((0, 0), |acc, count| (acc.0 + count.0, acc.1 + count.1));
+    if v4 > FLOW_PORT_RULE_MAX || v6 > FLOW_PORT_RULE_MAX {
+        return Err(DstIpRuleError::PortRuleLimit(flow_id, FLOW_PORT_RULE_MAX));
+    }
+    Ok(())
+}
+
 impl LandscapeDBStore<Uuid> for WanIpRuleConfig {
     fn get_id(&self) -> Uuid {
         self.id.unwrap_or(Uuid::new_v4())
@@ -73,7 +130,65 @@ impl LandscapeDBStore<Uuid> for WanIpRuleConfig {
 #[serde(rename_all = "snake_case")]
 pub enum WanIPRuleSource {
     GeoKey(GeoConfigKey),
-    Config(IpConfig),
+    Config(WanIpMatchConfig),
+}
+
+/// 目标 IP 规则中手动配置的 CIDR, 可附加 L4 协议与目标端口范围
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WanIpMatchConfig {
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
+    pub ip: IpAddr,
+    pub prefix: u32,
+    /// 为空时匹配任意协议
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub l4_protocol: Option<LandscapeIpProtocolCode>,
+    /// 目标端口范围, 为空时匹配任意端口
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub dst_port: Option<PortRange>,
+}
+
+impl WanIpMatchConfig {
+    pub fn validate(&self) -> Result<(), DstIpRuleError> {
+        let max_prefix = if self.ip.is_ipv4() { 32 } else { 128 };
+        if self.prefix > max_prefix {
+            return Err(DstIpRuleError::InvalidRule(format!(
+                "invalid prefix length {} for {}",
+                self.prefix, self.ip
+            )));
+        }
+        if let Some(port) = &self.dst_port {
+            if port.start == 0 || port.start > port.end {
+                return Err(DstIpRuleError::InvalidRule(format!(
+                    "invalid port range {}-{}",
+                    port.start, port.end
+                )));
+            }
+            if !matches!(
+                self.l4_protocol,
+                None | Some(LandscapeIpProtocolCode::TCP) | Some(LandscapeIpProtocolCode::UDP)
+            ) {
+                return Err(DstIpRuleError::InvalidRule(
+                    "port range requires TCP, UDP or any protocol".into(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn cidr(&self) -> IpConfig {
+        IpConfig { ip: self.ip, prefix: self.prefix }
+    }
+}
+
+/// 端口范围, 包含两端
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PortRange {
+    pub start: u16,
+    pub end: u16,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
@@ -90,6 +205,100 @@ pub struct IpConfig {
 pub struct IpMarkInfo {
     pub mark: FlowMark,
     pub cidr: IpConfig,
+    /// 附加的 L4 匹配条件, 有值时不写入 LPM 而写入端口规则表
+    pub l4_protocol: Option<LandscapeIpProtocolCode>,
+    pub dst_port: Option<PortRange>,
     // pub override_dns: bool,
     pub priority: u16,
 }
+
+impl IpMarkInfo {
+    /// 是否带有 L4 协议或端口条件
+    pub fn has_l4_match(&self) -> bool {
+        self.l4_protocol.is_some() || self.dst_port.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn match_config(
+        l4_protocol: Option<LandscapeIpProtocolCode>,
+        start: u16,
+        end: u16,
+    ) -> WanIpMatchConfig {
+        WanIpMatchConfig {
+            ip: "0.0.0.0".parse().unwrap(),
+            prefix: 0,
+            l4_protocol,
+            dst_port: Some(PortRange { start, end }),
+        }
+    }
+
+    #[test]
+    fn test_validate_port_range() {
+        assert!(match_config(Some(LandscapeIpProtocolCode::UDP), 3478, 3497).validate().is_ok());
+        assert!(match_config(None, 443, 443).validate().is_ok());
+        assert!(match_config(Some(LandscapeIpProtocolCode::UDP), 3497, 3478).validate().is_err());
+        assert!(match_config(Some(LandscapeIpProtocolCode::TCP), 0, 80).validate().is_err());
+        assert!(match_config(Some(LandscapeIpProtocolCode::ICMP), 1, 80).validate().is_err());
+    }
+
+    fn rule(flow_id: u32, sources: Vec<WanIpMatchConfig>) -> WanIpRuleConfig {
+        WanIpRuleConfig {
+            id: Some(Uuid::new_v4()),
+            index: 0,
+            enable: true,
+            mark: FlowMark::default(),
+            source: sources.into_iter().map(WanIPRuleSource::Config).collect(),
+            remark: String::new(),
+            flow_id,
+            override_dns: false,
+            update_at: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_port_rule_limit() {
+        let port_rules =
+            |count| vec![match_config(Some(LandscapeIpProtocolCode::TCP), 443, 443); count];
+        assert!(rule(1, port_rules(FLOW_PORT_RULE_MAX)).validate().is_ok());
+        assert!(matches!(
+            rule(1, port_rules(FLOW_PORT_RULE_MAX + 1)).validate(),
+            Err(DstIpRuleError::PortRuleLimit(1, FLOW_PORT_RULE_MAX))
+        ));
+
+        // 上限按 Flow 内所有已启用的规则累计, 不同 IP 版本分别计算
+        let a = rule(1, port_rules(20));
+        let b = rule(1, port_rules(20));
+        assert!(check_flow_port_rule_limit(1, [&a, &b]).is_err());
+        assert!(check_flow_port_rule_limit(2, [&a, &b]).is_ok());
+        let mut disabled = b.clone();
+        disabled.enable = false;
+        assert!(check_flow_port_rule_limit(1, [&a, &disabled]).is_ok());
+        let mut v6 = match_config(Some(LandscapeIpProtocolCode::TCP), 443, 443);
+        v6.ip = "::".parse().unwrap();
+        let c = rule(1, vec![v6; 20]);
+        assert!(check_flow_port_rule_limit(1, [&a, &c]).is_ok());
+
+        // 不带 L4 条件的规则不计入
+        let plain = WanIpMatchConfig {
+            l4_protocol: None,
+            dst_port: None,
+            ..match_config(None, 1, 1)
+        };
+        assert!(rule(1, vec![plain; FLOW_PORT_RULE_MAX + 1]).validate().is_ok());
+    }
+
+    #[test]
+    fn test_deserialize_legacy_config() {
+        let source: WanIPRuleSource =
+            serde_json::from_str(r#"{"t":"config","ip":"10.0.0.0","prefix":8}"#).unwrap();
+        let WanIPRuleSource::Config(config) = source else {
+            panic!("expect config source");
+        };
+        assert_eq!(config.l4_protocol, None);
+        assert_eq!(config.dst_port, None);
+    }
+}
@@ -83,3 +83,99 @@ impl IAPrefixMap {
         }
     }
 }
+
+/// WAN 侧 IPv6 地址来源
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum LDWanIpv6AddrSource {
+    /// 通过上游 RA 的 Prefix Information 自动配置
+    Slaac,
+    /// 通过 DHCPv6 IA_NA 获取
+    Dhcpv6,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LDWanIpv6Addr {
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
+    pub ip: Ipv6Addr,
+    pub prefix_len: u8,
+    /// unit: s
+    pub preferred_lifetime: u32,
+    /// unit: s
+    pub valid_lifetime: u32,
+    pub source: LDWanIpv6AddrSource,
+    pub last_update_time: f64,
+}
+
+/// WAN 口通过 RA / DHCPv6 获得的运行时 IPv6 信息
+#[derive(Debug, Clone, Default, serde::Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LDWanIpv6Info {
+    pub addrs: Vec<LDWanIpv6Addr>,
+    /// RA 中通告的默认路由 (链路本地地址)
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<String>))]
+    pub gateway: Option<Ipv6Addr>,
+    /// unit: s
+    pub router_lifetime: u16,
+    /// RA 中的 RDNSS, 仅供查看, 不会作为 DNS 上游使用
+    #[cfg_attr(feature = "openapi", schema(value_type = Vec<String>))]
+    pub rdnss: Vec<Ipv6Addr>,
+    pub mtu: Option<u32>,
+}
+
+impl LDWanIpv6Info {
+    /// 按 IP 与来源更新地址, valid_lifetime 为 0 时移除
+    pub fn upsert_addr(&mut self, addr: LDWanIpv6Addr) {
+        self.addrs.retain(|a| !(a.ip == addr.ip && a.source == addr.source));
+        if addr.valid_lifetime > 0 {
+            self.addrs.push(addr);
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct WanIpv6InfoMap {
+    infos: Arc<RwLock<HashMap<String, LDWanIpv6Info>>>,
+}
+
+impl WanIpv6InfoMap {
+    pub fn new() -> Self {
+        WanIpv6InfoMap { infos: Arc::new(RwLock::new(HashMap::new())) }
+    }
+
+    pub async fn get_info(&self) -> HashMap<String, LDWanIpv6Info> {
+        self.infos.read().await.clone()
+    }
+
+    pub async fn get_iface_info(&self, iface_name: &str) -> Option<LDWanIpv6Info> {
+        self.infos.read().await.get(iface_name).cloned()
+    }
+
+    /// 修改指定接口的信息, 不存在时先创建默认值
+    pub async fn modify<F>(&self, iface_name: &str, f: F)
+    where
+        F: FnOnce(&mut LDWanIpv6Info),
+    {
+        let mut infos = self.infos.write().await;
+        f(infos.entry(iface_name.to_string()).or_default());
+    }
+
+    /// 清除指定来源的地址, 当接口没有任何信息时移除整个条目
+    pub async fn clean_source(&self, iface_name: &str, source: LDWanIpv6AddrSource) {
+        let mut infos = self.infos.write().await;
+        if let Some(info) = infos.get_mut(iface_name) {
+            info.addrs.retain(|a| a.source != source);
+            if source == LDWanIpv6AddrSource::Slaac {
+                info.gateway = None;
+                info.router_lifetime = 0;
+                info.rdnss.clear();
+                info.mtu = None;
+            }
+            if info.addrs.is_empty() && info.gateway.is_none() {
+                infos.remove(iface_name);
+            }
+        }
+    }
+}
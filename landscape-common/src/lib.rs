@@ -40,6 +40,9 @@ pub mod auth;
 pub mod pty;
 
 pub mod dns;
+pub mod netns_target;
+pub mod quota;
+pub mod rate_limit;
 
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -49,6 +52,9 @@ pub const LANDSCAPE_CONFIG_DIR_NAME: &str = ".landscape-router";
 /// sys token
 pub const LANDSCAPE_SYS_TOKEN_FILE_ANME: &str = "landscape_api_token";
 
+/// 上次写入的配置文件管理员密码哈希, 配置文件中的密码变化后重置管理员账号
+pub const LANDSCAPE_ADMIN_SEED_FILE_NAME: &str = "landscape_admin_seed";
+
 /// Config file
 pub const LAND_CONFIG: &str = "landscape.toml";
 /// init lock file name
@@ -64,13 +70,17 @@ pub const NAMESPACE_REGISTER_SOCK_PATH: &str = "unix_link";
 pub const NAMESPACE_REGISTER_SOCK_PATH_IN_DOCKER: &str = "ld_unix_link";
 pub const NAMESPACE_REGISTER_SOCK: &str = "register.sock";
 
+/// 本机 TProxy 出口的数据包标记位与策略路由表
+pub const LANDSCAPE_TPROXY_MARK: u32 = 0x0080_0000;
+pub const LANDSCAPE_TPROXY_ROUTE_TABLE: u32 = 233;
+
 /// LOG Path
 pub const LANDSCAPE_LOG_DIR_NAME: &str = "logs";
 /// web resource
 pub const LANDSCAPE_WEBROOT_DIR_NAME: &str = "static";
 // --- Metric Settings ---
 pub const LANDSCAPE_METRIC_DIR_NAME: &str = "metric";
-pub const LANDSCAPE_METRIC_DB_VERSION: u32 = 10;
+pub const LANDSCAPE_METRIC_DB_VERSION: u32 = 11;
 
 // Metric Retention Defaults
 pub const DEFAULT_CONN_METRIC_RETENTION_MINS: u64 = 5;
@@ -84,6 +94,9 @@ pub const DEFAULT_METRIC_BATCH_SIZE: usize = 20_000;
 pub const DEFAULT_METRIC_FLUSH_INTERVAL_SECS: u64 = 30;
 pub const DEFAULT_METRIC_MAX_MEMORY: usize = 256;
 pub const DEFAULT_METRIC_MAX_THREADS: usize = 4;
+pub const DEFAULT_FLOW_EXPORT_TEMPLATE_REFRESH_SECS: u64 = 60;
+/// RFC 5612 中用于示例的 Private Enterprise Number
+pub const DEFAULT_FLOW_EXPORT_ENTERPRISE_NUMBER: u32 = 32473;
 
 // --- DNS Settings ---
 pub const DEFAULT_DNS_CACHE_CAPACITY: u32 = 4096;
@@ -99,6 +112,8 @@ pub const DEFAULT_METRIC_CLEANUP_INTERVAL_SECS: u64 = 300;
 pub const LANDSCAPE_DB_SQLITE_NAME: &str = "landscape_db.sqlite";
 /// LOG Path
 pub const LANDSCAPE_HOSTAPD_TMP_DIR: &str = "hostapd_tmp";
+/// wpa_supplicant 配置目录
+pub const LANDSCAPE_WPA_SUPPLICANT_TMP_DIR: &str = "wpa_supplicant_tmp";
 /// GEO_CACHE Path
 pub const LANDSCAPE_GEO_CACHE_TMP_DIR: &str = "geo_tmp";
 
@@ -76,6 +76,12 @@ pub struct ConnectMetric {
     pub flow_id: u8,
     pub trace_id: u8,
     pub gress: u8,
+    /// 连接所在的 WAN 网卡
+    #[serde(default)]
+    pub ifindex: u32,
+    /// 同一客户端最近一次解析到目标 IP 的域名
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
 
     pub report_time: u64,
 
@@ -128,6 +134,9 @@ pub struct ConnectRealtimeStatus {
     pub flow_id: u8,
     pub trace_id: u8,
     pub gress: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "openapi", schema(required = false, nullable = false))]
+    pub domain: Option<String>,
 
     pub create_time_ms: u64,
 
@@ -160,6 +169,7 @@ pub enum ConnectSortKey {
     Ingress,
     Egress,
     Duration,
+    Domain,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
@@ -203,6 +213,8 @@ pub struct ConnectHistoryQueryParams {
     pub status: Option<u8>, // 0: Active, 1: Closed
     #[cfg_attr(feature = "openapi", schema(nullable = false))]
     pub gress: Option<u8>,
+    #[cfg_attr(feature = "openapi", schema(nullable = false))]
+    pub domain: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -223,6 +235,9 @@ pub struct ConnectHistoryStatus {
     pub flow_id: u8,
     pub trace_id: u8,
     pub gress: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "openapi", schema(required = false, nullable = false))]
+    pub domain: Option<String>,
 
     pub create_time_ms: u64,
 
@@ -266,6 +281,18 @@ pub struct IpHistoryStat {
     pub connect_count: u32,
 }
 
+/// 按域名聚合的历史流量
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DomainHistoryStat {
+    pub domain: String,
+    pub total_ingress_bytes: u64,
+    pub total_egress_bytes: u64,
+    pub total_ingress_pkts: u64,
+    pub total_egress_pkts: u64,
+    pub connect_count: u32,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct MetricChartRequest {
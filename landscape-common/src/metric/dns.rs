@@ -28,6 +28,14 @@ pub struct DnsMetric {
     #[cfg_attr(feature = "openapi", schema(value_type = String))]
     pub src_ip: IpAddr,
     pub answers: Vec<String>,
+    /// 处理该查询的解析规则名称
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "openapi", schema(required = false, nullable = false))]
+    pub rule: Option<String>,
+    /// 处理该查询的上游名称
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "openapi", schema(required = false, nullable = false))]
+    pub upstream: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
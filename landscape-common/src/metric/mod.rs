@@ -1,2 +1,3 @@
 pub mod connect;
 pub mod dns;
+pub mod traffic;
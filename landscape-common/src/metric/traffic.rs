@@ -0,0 +1,145 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::metric::connect::{MetricResolution, SortOrder};
+
+/// 流量统计对象类型
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum TrafficTargetKind {
+    /// 网卡收发统计
+    #[default]
+    Iface,
+    /// 内网主机 IP, 由连接统计累计
+    Ip,
+    /// 按 MAC 聚合后关联到已登记设备, 仅用于查询
+    Device,
+    /// 按 Flow 统计, 由连接统计累计
+    Flow,
+}
+
+impl TrafficTargetKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrafficTargetKind::Iface => "iface",
+            TrafficTargetKind::Ip => "ip",
+            TrafficTargetKind::Device => "device",
+            TrafficTargetKind::Flow => "flow",
+        }
+    }
+}
+
+/// 一个采样周期内的流量增量
+/// 对于网卡 rx / tx 为收 / 发, 对于内网主机 rx 为下载, tx 为上传
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrafficSample {
+    pub kind: TrafficTargetKind,
+    pub target: String,
+    pub mac: Option<String>,
+    pub report_time: u64,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    pub rx_packets: u64,
+    pub tx_packets: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TrafficPoint {
+    pub report_time: u64,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    pub rx_packets: u64,
+    pub tx_packets: u64,
+}
+
+/// 某个统计对象在时间范围内的累计流量
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TrafficTotal {
+    pub kind: TrafficTargetKind,
+    /// 网卡名 / IP / MAC
+    pub target: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "openapi", schema(required = false, nullable = false))]
+    pub mac: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "openapi", schema(required = false, nullable = false))]
+    pub device_id: Option<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "openapi", schema(required = false, nullable = false))]
+    pub device_name: Option<String>,
+    /// 设备聚合时包含的 IP
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(feature = "openapi", schema(required = false))]
+    pub ips: Vec<String>,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    pub rx_packets: u64,
+    pub tx_packets: u64,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum TrafficSortKey {
+    #[default]
+    Total,
+    Rx,
+    Tx,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema, utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct TrafficHistoryQueryParams {
+    pub kind: TrafficTargetKind,
+    /// 网卡名 / IP / MAC
+    pub target: String,
+    #[cfg_attr(feature = "openapi", schema(nullable = false))]
+    pub start_time: Option<u64>,
+    #[cfg_attr(feature = "openapi", schema(nullable = false))]
+    pub end_time: Option<u64>,
+    #[cfg_attr(feature = "openapi", schema(nullable = false))]
+    pub resolution: Option<MetricResolution>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema, utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct TrafficTopQueryParams {
+    pub kind: TrafficTargetKind,
+    #[cfg_attr(feature = "openapi", schema(nullable = false))]
+    pub start_time: Option<u64>,
+    #[cfg_attr(feature = "openapi", schema(nullable = false))]
+    pub end_time: Option<u64>,
+    #[cfg_attr(feature = "openapi", schema(nullable = false))]
+    pub limit: Option<usize>,
+    #[cfg_attr(feature = "openapi", schema(nullable = false))]
+    pub sort_key: Option<TrafficSortKey>,
+    #[cfg_attr(feature = "openapi", schema(nullable = false))]
+    pub sort_order: Option<SortOrder>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema, utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct TrafficMonthlyQueryParams {
+    pub kind: TrafficTargetKind,
+    /// 统计月份, 格式为 `YYYY-MM`, 默认为当前计费周期
+    #[cfg_attr(feature = "openapi", schema(nullable = false))]
+    pub month: Option<String>,
+    /// 每月流量重置日 (1-28), 默认为 1
+    #[cfg_attr(feature = "openapi", schema(nullable = false))]
+    pub reset_day: Option<u8>,
+}
+
+/// 按月 (计费周期) 汇总的流量
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TrafficMonthlyReport {
+    pub start_time: u64,
+    pub end_time: u64,
+    pub items: Vec<TrafficTotal>,
+}
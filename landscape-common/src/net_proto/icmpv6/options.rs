@@ -56,8 +56,19 @@ define_options!(IcmpV6Option, u8, u8, {
 impl dhcproto::Decodable for IcmpV6Options {
     fn decode(decoder: &mut dhcproto::Decoder<'_>) -> dhcproto::error::DecodeResult<Self> {
         let mut opts = Vec::new();
-        while let Ok(opt) = IcmpV6Option::decode(decoder) {
-            opts.push(opt);
+        loop {
+            // RDNSS 选项中可能携带多个地址, 展开为多个选项
+            if decoder.peek_u8().ok() == Some(IcmpV6OptionCode::RecursiveDNSServer.into()) {
+                match decode_rdnss(decoder) {
+                    Ok(servers) => opts.extend(servers),
+                    Err(_) => break,
+                }
+                continue;
+            }
+            match IcmpV6Option::decode(decoder) {
+                Ok(opt) => opts.push(opt),
+                Err(_) => break,
+            }
         }
         // sorts by OptionCode
         opts.sort_unstable();
@@ -75,6 +86,9 @@ impl dhcproto::Decodable for IcmpV6Option {
     fn decode(decoder: &mut dhcproto::Decoder<'_>) -> dhcproto::error::DecodeResult<Self> {
         let code = decoder.read_u8()?.into();
         let len = (decoder.read_u8()? as usize) * 8;
+        if len < 8 {
+            return Err(dhcproto::error::DecodeError::NotEnoughBytes);
+        }
 
         let result = match code {
             IcmpV6OptionCode::SourceLinkLayerAddress => {
@@ -83,12 +97,50 @@ impl dhcproto::Decodable for IcmpV6Option {
             IcmpV6OptionCode::TargetLinkLayerAddress => IcmpV6Option::TargetLinkLayerAddress(
                 MacAddr::from_arry(decoder.read_slice(len - 2)?).unwrap(),
             ),
-            code => IcmpV6Option::UnknownOption(code.into(), decoder.read_slice(len)?.to_vec()),
+            IcmpV6OptionCode::PrefixInformation => {
+                let data = decoder.read_slice(len - 2)?;
+                IcmpV6Option::PrefixInformation(PrefixInformation::decode(
+                    &mut dhcproto::Decoder::new(data),
+                )?)
+            }
+            IcmpV6OptionCode::MTU => {
+                let data = decoder.read_slice(len - 2)?;
+                let mut data = dhcproto::Decoder::new(data);
+                let _reserved = data.read_u16()?;
+                IcmpV6Option::MTU(data.read_u32()?)
+            }
+            IcmpV6OptionCode::RecursiveDNSServer => {
+                // 单独解码时只保留第一个地址
+                let data = decoder.read_slice(len - 2)?;
+                let mut data = dhcproto::Decoder::new(data);
+                let _reserved = data.read_u16()?;
+                let lifetime = data.read_u32()?;
+                let ip = data.read_ipv6s(16)?.get(0).cloned().unwrap_or(Ipv6Addr::UNSPECIFIED);
+                IcmpV6Option::RecursiveDNSServer((lifetime, ip))
+            }
+            code => IcmpV6Option::UnknownOption(code.into(), decoder.read_slice(len - 2)?.to_vec()),
         };
         Ok(result)
     }
 }
 
+/// https://www.rfc-editor.org/rfc/rfc8106.html#section-5.1
+fn decode_rdnss(
+    decoder: &mut dhcproto::Decoder<'_>,
+) -> dhcproto::error::DecodeResult<Vec<IcmpV6Option>> {
+    let _code = decoder.read_u8()?;
+    let len = (decoder.read_u8()? as usize) * 8;
+    if len < 24 {
+        return Err(dhcproto::error::DecodeError::NotEnoughBytes);
+    }
+    let data = decoder.read_slice(len - 2)?;
+    let mut data = dhcproto::Decoder::new(data);
+    let _reserved = data.read_u16()?;
+    let lifetime = data.read_u32()?;
+    let servers = data.read_ipv6s(len - 8)?;
+    Ok(servers.into_iter().map(|ip| IcmpV6Option::RecursiveDNSServer((lifetime, ip))).collect())
+}
+
 impl dhcproto::Encodable for IcmpV6Option {
     fn encode(&self, e: &mut dhcproto::Encoder<'_>) -> dhcproto::v6::EncodeResult<()> {
         let code: IcmpV6OptionCode = self.into();
@@ -217,6 +269,31 @@ impl PrefixInformation {
     }
 }
 
+impl PrefixInformation {
+    /// L: on-link 标志
+    pub fn on_link(&self) -> bool {
+        self.flags & 0x80 != 0
+    }
+
+    /// A: autonomous 标志, 可用于 SLAAC
+    pub fn autonomous(&self) -> bool {
+        self.flags & 0x40 != 0
+    }
+}
+
+impl dhcproto::Decodable for PrefixInformation {
+    fn decode(decoder: &mut dhcproto::Decoder<'_>) -> dhcproto::error::DecodeResult<Self> {
+        Ok(Self {
+            prefix_length: decoder.read_u8()?,
+            flags: decoder.read_u8()?,
+            valid_lifetime: decoder.read_u32()?,
+            preferred_lifetime: decoder.read_u32()?,
+            reserved2: decoder.read_u32()?,
+            prefix: decoder.read_ipv6s(16)?.get(0).unwrap().clone(),
+        })
+    }
+}
+
 impl dhcproto::Encodable for PrefixInformation {
     fn encode(&self, e: &mut dhcproto::Encoder<'_>) -> dhcproto::v6::EncodeResult<()> {
         e.write_u8(self.prefix_length)?;
@@ -488,6 +565,59 @@ mod tests {
         assert!(matches!(options.0[2], AOption::A2(_)));
     }
 
+    #[test]
+    fn test_decode_ra_options() {
+        use dhcproto::Decodable;
+
+        let prefix: Ipv6Addr = "2001:db8:1:2::".parse().unwrap();
+        let dns1: Ipv6Addr = "2001:db8::53".parse().unwrap();
+        let dns2: Ipv6Addr = "2001:db8::54".parse().unwrap();
+
+        let mut data = vec![];
+        // Prefix Information
+        data.extend_from_slice(&[3, 4, 64, 0xc0]);
+        data.extend_from_slice(&7200u32.to_be_bytes());
+        data.extend_from_slice(&3600u32.to_be_bytes());
+        data.extend_from_slice(&[0; 4]);
+        data.extend_from_slice(&prefix.octets());
+        // MTU
+        data.extend_from_slice(&[5, 1, 0, 0]);
+        data.extend_from_slice(&1492u32.to_be_bytes());
+        // RDNSS with two servers
+        data.extend_from_slice(&[25, 5, 0, 0]);
+        data.extend_from_slice(&600u32.to_be_bytes());
+        data.extend_from_slice(&dns1.octets());
+        data.extend_from_slice(&dns2.octets());
+        // unknown option
+        data.extend_from_slice(&[31, 1, 0, 0, 0, 0, 0, 0]);
+
+        let opts = IcmpV6Options::decode(&mut dhcproto::Decoder::new(&data)).unwrap();
+
+        let Some(IcmpV6Option::PrefixInformation(info)) =
+            opts.get(IcmpV6OptionCode::PrefixInformation)
+        else {
+            panic!("prefix information not found");
+        };
+        assert_eq!(info.prefix, prefix);
+        assert_eq!(info.prefix_length, 64);
+        assert!(info.autonomous());
+        assert_eq!(info.valid_lifetime, 7200);
+        assert_eq!(info.preferred_lifetime, 3600);
+
+        assert!(matches!(opts.get(IcmpV6OptionCode::MTU), Some(IcmpV6Option::MTU(1492))));
+
+        let servers: Vec<_> = opts
+            .get_all(IcmpV6OptionCode::RecursiveDNSServer)
+            .unwrap()
+            .into_iter()
+            .filter_map(|opt| match opt {
+                IcmpV6Option::RecursiveDNSServer((600, ip)) => Some(*ip),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(servers, vec![dns1, dns2]);
+    }
+
     #[test]
     fn test_options_into_iterator() {
         let options = create_test_data().into_iter().collect::<AOptions>();
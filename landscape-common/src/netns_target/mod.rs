@@ -0,0 +1,115 @@
+use landscape_macro::LdApiError;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::config::ConfigId;
+use crate::database::repository::LandscapeDBStore;
+use crate::utils::id::gen_database_uuid;
+use crate::utils::time::get_f64_timestamp;
+
+#[derive(thiserror::Error, Debug, LdApiError)]
+#[api_error(crate_path = "crate")]
+pub enum NetnsTargetError {
+    #[error("Namespace target '{0}' not found")]
+    #[api_error(id = "netns_target.not_found", status = 404)]
+    NotFound(ConfigId),
+
+    #[error("Namespace target name '{0}' is already used")]
+    #[api_error(id = "netns_target.duplicate_name", status = 400)]
+    DuplicateName(String),
+
+    #[error("Invalid namespace target: {0}")]
+    #[api_error(id = "netns_target.invalid", status = 400)]
+    InvalidConfig(String),
+}
+
+fn default_containerd_namespace() -> String {
+    "default".to_string()
+}
+
+/// 网络命名空间来源
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(tag = "t", rename_all = "snake_case")]
+pub enum NetnsSource {
+    /// `ip netns` 创建的命名空间 (/run/netns/<netns>)
+    Named { netns: String },
+    /// 指定进程所在的命名空间
+    Pid { pid: u32 },
+    /// Podman 容器, 通过 `podman inspect` 获取 PID
+    Podman { container: String },
+    /// containerd 容器, 通过 `ctr task ls` 获取 PID
+    Containerd {
+        container: String,
+        #[serde(default = "default_containerd_namespace")]
+        namespace: String,
+    },
+}
+
+/// 非 Docker 的命名空间出口
+/// 命名空间内需运行 redirect_pkg_handler 等处理程序, 宿主机侧使用其 veth 对端
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct NetnsTargetConfig {
+    #[serde(default = "gen_database_uuid")]
+    #[cfg_attr(feature = "openapi", schema(required = false))]
+    pub id: Uuid,
+    pub enable: bool,
+    /// Flow 出口中引用的名称, 不能与容器或网卡同名
+    pub name: String,
+    pub source: NetnsSource,
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = false))]
+    pub remark: String,
+    #[serde(default = "get_f64_timestamp")]
+    #[cfg_attr(feature = "openapi", schema(required = false))]
+    pub update_at: f64,
+}
+
+impl NetnsTargetConfig {
+    pub fn validate(&self) -> Result<(), NetnsTargetError> {
+        if self.name.is_empty() || self.name.contains('/') {
+            return Err(NetnsTargetError::InvalidConfig(format!("invalid name: {}", self.name)));
+        }
+        let valid = match &self.source {
+            NetnsSource::Named { netns } => !netns.is_empty() && !netns.contains('/'),
+            NetnsSource::Pid { pid } => *pid > 0,
+            NetnsSource::Podman { container } => !container.is_empty(),
+            NetnsSource::Containerd { container, namespace } => {
+                !container.is_empty() && !namespace.is_empty()
+            }
+        };
+        if !valid {
+            return Err(NetnsTargetError::InvalidConfig(format!(
+                "invalid source: {:?}",
+                self.source
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl LandscapeDBStore<Uuid> for NetnsTargetConfig {
+    fn get_id(&self) -> Uuid {
+        self.id
+    }
+    fn get_update_at(&self) -> f64 {
+        self.update_at
+    }
+    fn set_update_at(&mut self, ts: f64) {
+        self.update_at = ts;
+    }
+}
+
+/// 命名空间出口及其注册状态
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct NetnsTargetStatus {
+    pub config: NetnsTargetConfig,
+    /// 已注册的宿主机侧 veth ifindex
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub ifindex: Option<u32>,
+    /// 最近一次解析失败的原因
+    #[cfg_attr(feature = "openapi", schema(required = true, nullable = true))]
+    pub error: Option<String>,
+}
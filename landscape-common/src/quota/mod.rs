@@ -0,0 +1,184 @@
+use landscape_macro::LdApiError;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::config::{ConfigId, FlowId};
+use crate::database::repository::LandscapeDBStore;
+use crate::net::MacAddr;
+use crate::utils::id::gen_database_uuid;
+use crate::utils::time::get_f64_timestamp;
+
+#[derive(thiserror::Error, Debug, LdApiError)]
+#[api_error(crate_path = "crate")]
+pub enum TrafficQuotaError {
+    #[error("Traffic quota '{0}' not found")]
+    #[api_error(id = "traffic_quota.not_found", status = 404)]
+    NotFound(ConfigId),
+
+    #[error("Invalid traffic quota: {0}")]
+    #[api_error(id = "traffic_quota.invalid", status = 400)]
+    InvalidConfig(String),
+}
+
+/// 配额统计对象
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(tag = "t", rename_all = "snake_case")]
+pub enum TrafficQuotaTarget {
+    /// 已登记设备, 统计其所有 IP 的上下行流量
+    Device { device_id: Uuid },
+    /// 某个 Flow 内所有连接的流量
+    Flow { flow_id: FlowId },
+    /// WAN 网卡收发流量
+    Wan { iface_name: String },
+}
+
+/// 配额重置周期
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(tag = "t", rename_all = "snake_case")]
+pub enum TrafficQuotaPeriod {
+    /// 每日 0 点 (本地时间) 重置
+    Daily,
+    /// 每月 `reset_day` 日 0 点 (本地时间) 重置, 取值 1-28
+    Monthly { reset_day: u8 },
+}
+
+/// 超出配额后的处理动作
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(tag = "t", rename_all = "snake_case")]
+pub enum TrafficQuotaAction {
+    /// 仅记录并提示
+    Notify,
+    /// 限速, 单位 kbit/s
+    Throttle { rate_kbps: u32 },
+    /// 将流量切换到另一个 Flow
+    SwitchFlow { flow_id: FlowId },
+    /// 丢弃
+    Drop,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TrafficQuotaConfig {
+    #[serde(default = "gen_database_uuid")]
+    #[cfg_attr(feature = "openapi", schema(required = false))]
+    pub id: Uuid,
+    pub enable: bool,
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = false))]
+    pub remark: String,
+    pub target: TrafficQuotaTarget,
+    pub period: TrafficQuotaPeriod,
+    /// 周期内允许的上下行总字节数
+    pub limit_bytes: u64,
+    pub action: TrafficQuotaAction,
+    #[serde(default = "get_f64_timestamp")]
+    #[cfg_attr(feature = "openapi", schema(required = false))]
+    pub update_at: f64,
+}
+
+impl TrafficQuotaConfig {
+    pub fn validate(&self) -> Result<(), TrafficQuotaError> {
+        if self.limit_bytes == 0 {
+            return Err(TrafficQuotaError::InvalidConfig("limit_bytes must be positive".into()));
+        }
+        if let TrafficQuotaPeriod::Monthly { reset_day } = self.period {
+            if !(1..=28).contains(&reset_day) {
+                return Err(TrafficQuotaError::InvalidConfig(
+                    "reset_day must be between 1 and 28".into(),
+                ));
+            }
+        }
+        match (&self.target, &self.action) {
+            (_, TrafficQuotaAction::Throttle { rate_kbps: 0 }) => {
+                Err(TrafficQuotaError::InvalidConfig("throttle rate must be positive".into()))
+            }
+            (TrafficQuotaTarget::Wan { .. }, TrafficQuotaAction::SwitchFlow { .. }) => {
+                Err(TrafficQuotaError::InvalidConfig(
+                    "switch_flow is only available for device or flow quotas".into(),
+                ))
+            }
+            (
+                TrafficQuotaTarget::Flow { flow_id },
+                TrafficQuotaAction::SwitchFlow { flow_id: to },
+            ) if flow_id == to => Err(TrafficQuotaError::InvalidConfig(
+                "switch_flow target must differ from the quota flow".into(),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl LandscapeDBStore<Uuid> for TrafficQuotaConfig {
+    fn get_id(&self) -> Uuid {
+        self.id
+    }
+    fn get_update_at(&self) -> f64 {
+        self.update_at
+    }
+    fn set_update_at(&mut self, ts: f64) {
+        self.update_at = ts;
+    }
+}
+
+/// 配额当前周期的用量, 持久化以便重启后继续计数
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TrafficQuotaUsage {
+    /// 对应配额的 ID
+    pub id: Uuid,
+    /// 当前周期开始时间 (ms)
+    pub period_start: u64,
+    pub used_bytes: u64,
+    /// 超出配额的时间 (ms)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "openapi", schema(required = false, nullable = false))]
+    pub exceeded_at: Option<u64>,
+    #[serde(default = "get_f64_timestamp")]
+    #[cfg_attr(feature = "openapi", schema(required = false))]
+    pub update_at: f64,
+}
+
+impl LandscapeDBStore<Uuid> for TrafficQuotaUsage {
+    fn get_id(&self) -> Uuid {
+        self.id
+    }
+    fn get_update_at(&self) -> f64 {
+        self.update_at
+    }
+    fn set_update_at(&mut self, ts: f64) {
+        self.update_at = ts;
+    }
+}
+
+/// 配额及其当前周期用量
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TrafficQuotaStatus {
+    pub config: TrafficQuotaConfig,
+    pub period_start: u64,
+    pub period_end: u64,
+    pub used_bytes: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "openapi", schema(required = false, nullable = false))]
+    pub exceeded_at: Option<u64>,
+}
+
+/// 写入 eBPF 的配额限制对象
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum QuotaLimitScope {
+    Mac(MacAddr),
+    Flow(FlowId),
+    Ifindex(u32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaLimitAction {
+    Drop,
+    /// 限速, 单位 字节/秒
+    Throttle {
+        rate_bytes: u64,
+    },
+}
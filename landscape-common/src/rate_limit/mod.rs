@@ -0,0 +1,164 @@
+use std::net::IpAddr;
+
+use landscape_macro::LdApiError;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::config::{ConfigId, FlowId};
+use crate::database::repository::LandscapeDBStore;
+use crate::net::MacAddr;
+use crate::utils::id::gen_database_uuid;
+use crate::utils::time::get_f64_timestamp;
+
+/// 限速上限 (kbit/s), 10 Gbit/s
+pub const MAX_RATE_LIMIT_KBPS: u32 = 10_000_000;
+
+#[derive(thiserror::Error, Debug, LdApiError)]
+#[api_error(crate_path = "crate")]
+pub enum RateLimitError {
+    #[error("Rate limit '{0}' not found")]
+    #[api_error(id = "rate_limit.not_found", status = 404)]
+    NotFound(ConfigId),
+
+    #[error("Invalid rate limit: {0}")]
+    #[api_error(id = "rate_limit.invalid", status = 400)]
+    InvalidConfig(String),
+}
+
+/// 限速对象
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(tag = "t", rename_all = "snake_case")]
+pub enum RateLimitTarget {
+    /// 已登记设备, 按其 MAC 限速
+    Device { device_id: Uuid },
+    Mac {
+        #[cfg_attr(feature = "openapi", schema(value_type = String))]
+        mac: MacAddr,
+    },
+    Ip {
+        #[cfg_attr(feature = "openapi", schema(value_type = String))]
+        ip: IpAddr,
+    },
+    /// 某个 Flow 内所有主机的总速率
+    Flow { flow_id: FlowId },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RateLimitConfig {
+    #[serde(default = "gen_database_uuid")]
+    #[cfg_attr(feature = "openapi", schema(required = false))]
+    pub id: Uuid,
+    pub enable: bool,
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = false))]
+    pub remark: String,
+    pub target: RateLimitTarget,
+    /// 上传限速 (kbit/s), 0 表示不限制
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = true))]
+    pub upload_kbps: u32,
+    /// 下载限速 (kbit/s), 0 表示不限制
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = true))]
+    pub download_kbps: u32,
+    #[serde(default = "get_f64_timestamp")]
+    #[cfg_attr(feature = "openapi", schema(required = false))]
+    pub update_at: f64,
+}
+
+impl RateLimitConfig {
+    pub fn validate(&self) -> Result<(), RateLimitError> {
+        if self.upload_kbps == 0 && self.download_kbps == 0 {
+            return Err(RateLimitError::InvalidConfig(
+                "at least one of upload_kbps and download_kbps must be set".into(),
+            ));
+        }
+        if self.upload_kbps > MAX_RATE_LIMIT_KBPS || self.download_kbps > MAX_RATE_LIMIT_KBPS {
+            return Err(RateLimitError::InvalidConfig(format!(
+                "rate must not exceed {MAX_RATE_LIMIT_KBPS} kbit/s"
+            )));
+        }
+        if let RateLimitTarget::Flow { flow_id: 0 } = self.target {
+            return Err(RateLimitError::InvalidConfig(
+                "the default flow can not be rate limited".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl LandscapeDBStore<Uuid> for RateLimitConfig {
+    fn get_id(&self) -> Uuid {
+        self.id
+    }
+    fn get_update_at(&self) -> f64 {
+        self.update_at
+    }
+    fn set_update_at(&mut self, ts: f64) {
+        self.update_at = ts;
+    }
+}
+
+/// 限速及其当前速率
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RateLimitStatus {
+    pub config: RateLimitConfig,
+    /// 当前上传速率 (bit/s)
+    pub upload_bps: u64,
+    /// 当前下载速率 (bit/s)
+    pub download_bps: u64,
+    /// 排队过长被丢弃的包数
+    pub dropped_packets: u64,
+}
+
+/// 写入 eBPF 的限速对象
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RateLimitScope {
+    Mac(MacAddr),
+    Ip(IpAddr),
+    Flow(FlowId),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RateLimitDirection {
+    Upload,
+    Download,
+}
+
+/// eBPF 中限速记录的累计计数
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RateLimitCounter {
+    pub bytes: u64,
+    pub drops: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(target: RateLimitTarget, upload_kbps: u32, download_kbps: u32) -> RateLimitConfig {
+        RateLimitConfig {
+            id: Uuid::nil(),
+            enable: true,
+            remark: String::new(),
+            target,
+            upload_kbps,
+            download_kbps,
+            update_at: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let mac = RateLimitTarget::Mac { mac: MacAddr(0, 0x11, 0x22, 0x33, 0x44, 0x55) };
+        assert!(config(mac.clone(), 1000, 0).validate().is_ok());
+        assert!(config(mac.clone(), 0, 0).validate().is_err());
+        assert!(config(mac, 0, MAX_RATE_LIMIT_KBPS + 1).validate().is_err());
+
+        assert!(config(RateLimitTarget::Flow { flow_id: 1 }, 1000, 1000).validate().is_ok());
+        assert!(config(RateLimitTarget::Flow { flow_id: 0 }, 1000, 1000).validate().is_err());
+    }
+}
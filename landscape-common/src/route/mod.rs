@@ -11,6 +11,10 @@ pub struct RouteTargetInfo {
     pub mac: Option<MacAddr>,
     pub default_route: bool,
     pub is_docker: bool,
+    /// 非 Docker 的命名空间出口, 转发方式与 Docker 相同
+    pub is_namespace: bool,
+    /// 本机 TProxy 出口, 不经由任何网卡转发
+    pub tproxy: Option<RouteTproxyInfo>,
 
     pub iface_name: String,
 
@@ -27,6 +31,8 @@ impl RouteTargetInfo {
                 mac: Some(MacAddr::dummy()),
                 default_route: false,
                 is_docker: true,
+                is_namespace: false,
+                tproxy: None,
                 iface_name: iface_name.to_string(),
                 iface_ip: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
                 gateway_ip: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
@@ -37,6 +43,8 @@ impl RouteTargetInfo {
                 mac: Some(MacAddr::dummy()),
                 default_route: false,
                 is_docker: true,
+                is_namespace: false,
+                tproxy: None,
                 iface_name: iface_name.to_string(),
                 iface_ip: IpAddr::V6(Ipv6Addr::UNSPECIFIED),
                 gateway_ip: IpAddr::V6(Ipv6Addr::UNSPECIFIED),
@@ -44,8 +52,46 @@ impl RouteTargetInfo {
         )
     }
 
+    pub fn netns_new(ifindex: u32, name: &str) -> (Self, Self) {
+        let (mut ipv4, mut ipv6) = Self::docker_new(ifindex, name);
+        ipv4.is_namespace = true;
+        ipv6.is_namespace = true;
+        (ipv4, ipv6)
+    }
+
+    /// 单个 IP 版本的本机 TProxy 出口
+    pub fn tproxy_new(ipv6: bool, tproxy: RouteTproxyInfo) -> Self {
+        let addr = if ipv6 {
+            IpAddr::V6(Ipv6Addr::UNSPECIFIED)
+        } else {
+            IpAddr::V4(Ipv4Addr::UNSPECIFIED)
+        };
+        RouteTargetInfo {
+            weight: 0,
+            ifindex: 0,
+            mac: None,
+            default_route: false,
+            is_docker: false,
+            is_namespace: false,
+            tproxy: Some(tproxy),
+            iface_name: String::new(),
+            iface_ip: addr,
+            gateway_ip: addr,
+        }
+    }
+
     pub fn get_flow_target(&self) -> FlowTarget {
-        if self.is_docker {
+        if let Some(tproxy) = &self.tproxy {
+            FlowTarget::Tproxy {
+                port: tproxy.port,
+                ipv4: self.iface_ip.is_ipv4(),
+                ipv6: self.iface_ip.is_ipv6(),
+                tcp: tproxy.tcp,
+                udp: tproxy.udp,
+            }
+        } else if self.is_namespace {
+            FlowTarget::Namespace { name: self.iface_name.clone() }
+        } else if self.is_docker {
             FlowTarget::Netns { container_name: self.iface_name.clone() }
         } else {
             FlowTarget::Interface { name: self.iface_name.clone() }
@@ -53,6 +99,13 @@ impl RouteTargetInfo {
     }
 }
 
+#[derive(Eq, Hash, PartialEq, Debug, Clone)]
+pub struct RouteTproxyInfo {
+    pub port: u16,
+    pub tcp: bool,
+    pub udp: bool,
+}
+
 #[derive(Eq, Hash, PartialEq, Debug, Default, Clone)]
 pub enum LanRouteMode {
     #[default]
@@ -2,7 +2,7 @@ use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
 use serde::{Deserialize, Serialize};
 
-use crate::{flow::mark::FlowMark, net::MacAddr};
+use crate::{flow::mark::FlowMark, net::MacAddr, network::LandscapeIpProtocolCode};
 
 // ===== Step 1: Flow Match =====
 
@@ -41,6 +41,13 @@ pub struct FlowVerdictRequest {
     pub src_ipv6: Option<Ipv6Addr>,
     #[cfg_attr(feature = "openapi", schema(value_type = Vec<String>))]
     pub dst_ips: Vec<IpAddr>,
+    /// 用于匹配带 L4 条件的目标 IP 规则
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = false, nullable = false))]
+    pub l4_protocol: Option<LandscapeIpProtocolCode>,
+    #[serde(default)]
+    #[cfg_attr(feature = "openapi", schema(required = false, nullable = false))]
+    pub dst_port: Option<u16>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -56,6 +63,8 @@ pub struct SingleVerdictResult {
     pub dst_ip: IpAddr,
     pub ip_rule_match: Option<FlowRuleMatchResult>,
     pub dns_rule_match: Option<FlowRuleMatchResult>,
+    /// 命中的带 L4 条件的目标 IP 规则
+    pub port_rule_match: Option<FlowRuleMatchResult>,
     pub effective_mark: FlowMark,
     pub has_cache: bool,
     pub cached_mark: Option<u32>,
@@ -1,8 +1,10 @@
 use std::collections::HashMap;
 
+use crate::config::import::ConfigSectionDiff;
 use crate::config::FlowId;
 use crate::database::{LandscapeFlowStore, LandscapeStore};
 use crate::error::LdError;
+use crate::store::storev2::LandscapeStore as _;
 
 use super::{
     manager::{ServiceManager, ServiceStarterTrait},
@@ -53,6 +55,23 @@ pub trait ControllerService {
     async fn get_config_by_name(&self, iface_name: Self::Id) -> Option<Self::Config> {
         self.get_repository().find_by_id(iface_name).await.unwrap()
     }
+
+    /// 数据库被整体改写后 (如配置导入) 仅重启差异中新增或修改的服务, 并停止已被移除的服务;
+    /// 失败时返回对应服务的名称
+    async fn reload_from_repository(&self, diff: &ConfigSectionDiff) -> Result<(), String> {
+        for name in diff.removed.iter() {
+            self.get_service().stop_service(name.clone()).await;
+        }
+        let configs: Vec<<Self::H as ServiceStarterTrait>::Config> =
+            self.get_repository().list().await.unwrap_or_default();
+        for config in configs {
+            let key = config.get_store_key();
+            if diff.added.contains(&key) || diff.changed.contains(&key) {
+                self.get_service().update_service(config).await.map_err(|_| key)?;
+            }
+        }
+        Ok(())
+    }
 }
 
 #[async_trait::async_trait]
@@ -130,6 +149,13 @@ pub trait ConfigController {
         self.get_repository().find_by_ids(ids).await
     }
 
+    /// 数据库被整体改写后 (如配置导入) 通知配置变更
+    async fn reload_from_repository(&self, old_configs: Vec<Self::Config>) {
+        let new_configs = self.list().await;
+        self.after_update_config(new_configs.clone(), old_configs).await;
+        self.update_many_config(new_configs).await;
+    }
+
     async fn delete(&self, id: Self::Id) {
         if let Some(config) = self.find_by_id(id.clone()).await {
             let old_configs = self.list().await;
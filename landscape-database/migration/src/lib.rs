@@ -33,6 +33,21 @@ mod m20260126_111753_enrolled_device;
 mod m20260222_154411_geo_source_type;
 mod m20260222_171753_firewall_blacklist;
 mod m20260226_001739_pppd_plugin;
+mod m20260305_093112_wan_ipv6_acquire;
+mod m20260309_141502_iface_dev_params;
+mod m20260312_102447_wireguard;
+mod m20260316_090412_wifi_ap_config;
+mod m20260318_031520_wifi_station;
+mod m20260322_104518_traffic_quota;
+mod m20260406_081522_config_snapshot;
+mod m20260411_140318_user_account;
+mod m20260415_093027_login_hardening;
+mod m20260420_101245_sqm;
+mod m20260425_143612_rate_limit;
+mod m20260502_091736_flow_health_check;
+mod m20260508_150224_netns_target;
+mod m20260512_103518_managed_container;
+mod m20260516_092347_config_pending_confirm;
 mod tables;
 
 pub struct Migrator;
@@ -74,6 +89,21 @@ impl MigratorTrait for Migrator {
             Box::new(m20260222_154411_geo_source_type::Migration),
             Box::new(m20260222_171753_firewall_blacklist::Migration),
             Box::new(m20260226_001739_pppd_plugin::Migration),
+            Box::new(m20260305_093112_wan_ipv6_acquire::Migration),
+            Box::new(m20260309_141502_iface_dev_params::Migration),
+            Box::new(m20260312_102447_wireguard::Migration),
+            Box::new(m20260316_090412_wifi_ap_config::Migration),
+            Box::new(m20260318_031520_wifi_station::Migration),
+            Box::new(m20260322_104518_traffic_quota::Migration),
+            Box::new(m20260406_081522_config_snapshot::Migration),
+            Box::new(m20260411_140318_user_account::Migration),
+            Box::new(m20260415_093027_login_hardening::Migration),
+            Box::new(m20260420_101245_sqm::Migration),
+            Box::new(m20260425_143612_rate_limit::Migration),
+            Box::new(m20260502_091736_flow_health_check::Migration),
+            Box::new(m20260508_150224_netns_target::Migration),
+            Box::new(m20260512_103518_managed_container::Migration),
+            Box::new(m20260516_092347_config_pending_confirm::Migration),
         ]
     }
 }
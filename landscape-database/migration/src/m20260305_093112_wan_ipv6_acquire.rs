@@ -0,0 +1,67 @@
+use sea_orm_migration::prelude::*;
+
+use crate::tables::dhcp_v6_client::DHCPv6ClientConfigs;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite 不支持在一条 ALTER 中添加多列
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(DHCPv6ClientConfigs::Table)
+                    .add_column(
+                        ColumnDef::new(DHCPv6ClientConfigs::RequestPd)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(DHCPv6ClientConfigs::Table)
+                    .add_column(
+                        ColumnDef::new(DHCPv6ClientConfigs::RequestNa)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(DHCPv6ClientConfigs::Table)
+                    .add_column(
+                        ColumnDef::new(DHCPv6ClientConfigs::AcceptRa)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for col in [
+            DHCPv6ClientConfigs::RequestPd,
+            DHCPv6ClientConfigs::RequestNa,
+            DHCPv6ClientConfigs::AcceptRa,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter().table(DHCPv6ClientConfigs::Table).drop_column(col).to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,31 @@
+use sea_orm_migration::prelude::*;
+
+use crate::tables::iface::NetIfaceConfigs;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(NetIfaceConfigs::Table)
+                    .add_column(ColumnDef::new(NetIfaceConfigs::DevParams).json().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(NetIfaceConfigs::Table)
+                    .drop_column(NetIfaceConfigs::DevParams)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,43 @@
+use sea_orm_migration::prelude::*;
+
+use crate::tables::wireguard::WireGuardServiceConfigs;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(WireGuardServiceConfigs::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(WireGuardServiceConfigs::IfaceName)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(WireGuardServiceConfigs::Enable).boolean().not_null())
+                    .col(ColumnDef::new(WireGuardServiceConfigs::PrivateKey).string().not_null())
+                    .col(ColumnDef::new(WireGuardServiceConfigs::ListenPort).unsigned().null())
+                    .col(ColumnDef::new(WireGuardServiceConfigs::Addresses).json().not_null())
+                    .col(ColumnDef::new(WireGuardServiceConfigs::Mtu).unsigned().null())
+                    .col(ColumnDef::new(WireGuardServiceConfigs::PublicEndpoint).string().null())
+                    .col(ColumnDef::new(WireGuardServiceConfigs::Peers).json().not_null())
+                    .col(
+                        ColumnDef::new(WireGuardServiceConfigs::UpdateAt)
+                            .double()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(WireGuardServiceConfigs::Table).to_owned()).await
+    }
+}
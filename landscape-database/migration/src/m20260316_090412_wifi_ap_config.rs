@@ -0,0 +1,193 @@
+use sea_orm_migration::{prelude::*, schema::*, sea_orm::FromQueryResult};
+use serde_json::{json, Value};
+
+use crate::tables::wifi::WifiServiceConfigs;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        use sea_orm_migration::sea_orm::{ConnectionTrait, TransactionTrait};
+
+        let db = manager.get_connection();
+        let txn = db.begin().await?;
+        let builder = manager.get_database_backend();
+
+        let select = Query::select()
+            .columns([WifiServiceConfigs::IfaceName, WifiServiceConfigs::Config])
+            .from(WifiServiceConfigs::Table)
+            .to_owned();
+        let rows: Vec<OldWifiServiceConfigRow> =
+            OldWifiServiceConfigRow::find_by_statement(builder.build(&select)).all(&txn).await?;
+
+        txn.execute(
+            builder.build(
+                &Table::alter()
+                    .table(WifiServiceConfigs::Table)
+                    .add_column_if_not_exists(json(WifiServiceConfigs::ApConfig).default("{}"))
+                    .to_owned(),
+            ),
+        )
+        .await?;
+
+        // 将原有的 hostapd 配置文本转换为结构化配置, 启用状态保持不变
+        for row in rows {
+            let update = Query::update()
+                .table(WifiServiceConfigs::Table)
+                .value(WifiServiceConfigs::ApConfig, convert_hostapd_config(&row.config))
+                .and_where(Expr::col(WifiServiceConfigs::IfaceName).eq(row.iface_name))
+                .to_owned();
+            txn.execute(builder.build(&update)).await?;
+        }
+
+        txn.execute(
+            builder.build(
+                &Table::alter()
+                    .table(WifiServiceConfigs::Table)
+                    .drop_column(WifiServiceConfigs::Config)
+                    .to_owned(),
+            ),
+        )
+        .await?;
+
+        txn.commit().await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WifiServiceConfigs::Table)
+                    .add_column_if_not_exists(text(WifiServiceConfigs::Config).default(""))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WifiServiceConfigs::Table)
+                    .drop_column(WifiServiceConfigs::ApConfig)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(FromQueryResult)]
+struct OldWifiServiceConfigRow {
+    iface_name: String,
+    config: String,
+}
+
+#[derive(Default)]
+struct OldBss {
+    ssid: Option<String>,
+    wpa: u8,
+    key_mgmt: String,
+    passphrase: Option<String>,
+    hidden: bool,
+    isolate: bool,
+    bridge: Option<String>,
+}
+
+/// 解析 hostapd 配置文本, 生成 `WifiApConfig` 对应的 JSON
+fn convert_hostapd_config(config: &str) -> Value {
+    let mut country = "CN".to_string();
+    let mut hw_mode = "g".to_string();
+    let mut op_class = 0_u32;
+    let mut channel = 0_u8;
+    let mut width = "20";
+    let mut bss_list: Vec<OldBss> = vec![OldBss::default()];
+
+    for line in config.lines() {
+        let line = line.trim();
+        if line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        // `bss=` 开始一个新的 BSS 段
+        if key == "bss" {
+            bss_list.push(OldBss::default());
+            continue;
+        }
+        let bss = bss_list.last_mut().unwrap();
+        match key {
+            "country_code" => country = value.to_ascii_uppercase(),
+            "hw_mode" => hw_mode = value.to_string(),
+            "op_class" => op_class = value.parse().unwrap_or(0),
+            "channel" => channel = value.parse().unwrap_or(0),
+            "ht_capab" if value.contains("[HT40") && width == "20" => width = "40",
+            "vht_oper_chwidth" | "he_oper_chwidth" => match value {
+                "1" => width = "80",
+                "2" => width = "160",
+                _ => {}
+            },
+            "ssid" => bss.ssid = Some(value.to_string()),
+            "ssid2" => {
+                if let Some(ssid) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+                    bss.ssid = Some(ssid.to_string());
+                }
+            }
+            "wpa" => bss.wpa = value.parse().unwrap_or(0),
+            "wpa_key_mgmt" => bss.key_mgmt = value.to_string(),
+            "wpa_passphrase" | "sae_password" | "wpa_psk" => {
+                bss.passphrase.get_or_insert_with(|| value.to_string());
+            }
+            "ignore_broadcast_ssid" => bss.hidden = value != "0",
+            "ap_isolate" => bss.isolate = value == "1",
+            "bridge" => bss.bridge = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    let band = match hw_mode.as_str() {
+        "a" if (131..=137).contains(&op_class) => "6g",
+        "a" => "5g",
+        _ => "2g",
+    };
+    if band == "2g" && width != "20" {
+        width = "40";
+    }
+
+    let bss: Vec<Value> = bss_list
+        .into_iter()
+        .filter_map(|bss| {
+            let ssid = bss.ssid?;
+            let security = match bss.passphrase {
+                Some(passphrase) if bss.wpa != 0 => {
+                    let psk = bss.key_mgmt.contains("WPA-PSK");
+                    let sae = bss.key_mgmt.contains("SAE");
+                    let t = match (psk, sae) {
+                        (true, true) => "wpa2_wpa3",
+                        (false, true) => "wpa3_sae",
+                        _ => "wpa2_psk",
+                    };
+                    json!({ "t": t, "passphrase": passphrase })
+                }
+                _ => json!({ "t": "open" }),
+            };
+            Some(json!({
+                "ssid": ssid,
+                "security": security,
+                "hidden": bss.hidden,
+                "isolate": bss.isolate,
+                "bridge": bss.bridge,
+            }))
+        })
+        .collect();
+
+    json!({
+        "country": country,
+        "band": band,
+        "channel": channel,
+        "width": width,
+        "bss": bss,
+    })
+}
@@ -0,0 +1,38 @@
+use sea_orm_migration::prelude::*;
+
+use crate::tables::wifi_station::WifiStationServiceConfigs;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(WifiStationServiceConfigs::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(WifiStationServiceConfigs::IfaceName)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(WifiStationServiceConfigs::Enable).boolean().not_null())
+                    .col(ColumnDef::new(WifiStationServiceConfigs::Networks).json().not_null())
+                    .col(
+                        ColumnDef::new(WifiStationServiceConfigs::UpdateAt)
+                            .double()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(WifiStationServiceConfigs::Table).to_owned()).await
+    }
+}
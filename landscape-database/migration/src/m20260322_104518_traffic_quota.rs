@@ -0,0 +1,64 @@
+use sea_orm_migration::prelude::*;
+
+use crate::tables::traffic_quota::{TrafficQuotaConfigs, TrafficQuotaUsages};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TrafficQuotaConfigs::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(TrafficQuotaConfigs::Id).uuid().primary_key())
+                    .col(ColumnDef::new(TrafficQuotaConfigs::Enable).boolean().not_null())
+                    .col(
+                        ColumnDef::new(TrafficQuotaConfigs::Remark).string().not_null().default(""),
+                    )
+                    .col(ColumnDef::new(TrafficQuotaConfigs::Target).json().not_null())
+                    .col(ColumnDef::new(TrafficQuotaConfigs::Period).json().not_null())
+                    .col(ColumnDef::new(TrafficQuotaConfigs::LimitBytes).big_integer().not_null())
+                    .col(ColumnDef::new(TrafficQuotaConfigs::Action).json().not_null())
+                    .col(
+                        ColumnDef::new(TrafficQuotaConfigs::UpdateAt)
+                            .double()
+                            .not_null()
+                            .default(0.0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(TrafficQuotaUsages::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(TrafficQuotaUsages::Id).uuid().primary_key())
+                    .col(ColumnDef::new(TrafficQuotaUsages::PeriodStart).big_integer().not_null())
+                    .col(
+                        ColumnDef::new(TrafficQuotaUsages::UsedBytes)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(TrafficQuotaUsages::ExceededAt).big_integer().null())
+                    .col(
+                        ColumnDef::new(TrafficQuotaUsages::UpdateAt)
+                            .double()
+                            .not_null()
+                            .default(0.0),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(TrafficQuotaUsages::Table).to_owned()).await?;
+        manager.drop_table(Table::drop().table(TrafficQuotaConfigs::Table).to_owned()).await
+    }
+}
@@ -0,0 +1,40 @@
+use sea_orm_migration::prelude::*;
+
+use crate::tables::config_snapshot::ConfigSnapshots;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ConfigSnapshots::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(ConfigSnapshots::Id).uuid().primary_key())
+                    .col(ColumnDef::new(ConfigSnapshots::Author).string().not_null().default(""))
+                    .col(ColumnDef::new(ConfigSnapshots::Summary).string().not_null().default(""))
+                    .col(ColumnDef::new(ConfigSnapshots::Diff).json().not_null())
+                    .col(ColumnDef::new(ConfigSnapshots::Content).text().not_null())
+                    .col(ColumnDef::new(ConfigSnapshots::UpdateAt).double().not_null().default(0.0))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-config-snapshot-update-at")
+                    .table(ConfigSnapshots::Table)
+                    .col(ConfigSnapshots::UpdateAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(ConfigSnapshots::Table).to_owned()).await
+    }
+}
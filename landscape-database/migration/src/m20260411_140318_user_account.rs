@@ -0,0 +1,49 @@
+use sea_orm_migration::prelude::*;
+
+use crate::tables::user::{ApiTokens, Users};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Users::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Users::Id).uuid().primary_key())
+                    .col(ColumnDef::new(Users::Username).string().unique_key().not_null())
+                    .col(ColumnDef::new(Users::PasswordHash).string().not_null())
+                    .col(ColumnDef::new(Users::Role).json().not_null())
+                    .col(ColumnDef::new(Users::Enable).boolean().not_null())
+                    .col(ColumnDef::new(Users::TokenVersion).unsigned().not_null().default(0))
+                    .col(ColumnDef::new(Users::UpdateAt).double().not_null().default(0.0))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ApiTokens::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(ApiTokens::Id).uuid().primary_key())
+                    .col(ColumnDef::new(ApiTokens::UserId).uuid().not_null())
+                    .col(ColumnDef::new(ApiTokens::Name).string().not_null().default(""))
+                    .col(ColumnDef::new(ApiTokens::Role).json().not_null())
+                    .col(ColumnDef::new(ApiTokens::TokenHash).string().unique_key().not_null())
+                    .col(ColumnDef::new(ApiTokens::ExpireAt).big_integer().null())
+                    .col(ColumnDef::new(ApiTokens::Revoked).boolean().not_null().default(false))
+                    .col(ColumnDef::new(ApiTokens::UpdateAt).double().not_null().default(0.0))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(ApiTokens::Table).to_owned()).await?;
+        manager.drop_table(Table::drop().table(Users::Table).to_owned()).await
+    }
+}
@@ -0,0 +1,79 @@
+use sea_orm_migration::prelude::*;
+
+use crate::tables::audit_log::AuditLogs;
+use crate::tables::user::Users;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .add_column(ColumnDef::new(Users::TotpSecret).string().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .add_column(
+                        ColumnDef::new(Users::TotpEnable).boolean().not_null().default(false),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(AuditLogs::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(AuditLogs::Id).uuid().primary_key())
+                    .col(ColumnDef::new(AuditLogs::Kind).string().not_null())
+                    .col(ColumnDef::new(AuditLogs::Username).string().not_null().default(""))
+                    .col(ColumnDef::new(AuditLogs::SourceIp).string().null())
+                    .col(ColumnDef::new(AuditLogs::Method).string().not_null().default(""))
+                    .col(ColumnDef::new(AuditLogs::Route).string().not_null().default(""))
+                    .col(ColumnDef::new(AuditLogs::ConfigId).string().null())
+                    .col(ColumnDef::new(AuditLogs::Status).integer().not_null().default(0))
+                    .col(ColumnDef::new(AuditLogs::Before).uuid().null())
+                    .col(ColumnDef::new(AuditLogs::After).uuid().null())
+                    .col(ColumnDef::new(AuditLogs::Summary).text().not_null().default(""))
+                    .col(ColumnDef::new(AuditLogs::UpdateAt).double().not_null().default(0.0))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-audit-log-update-at")
+                    .table(AuditLogs::Table)
+                    .col(AuditLogs::UpdateAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(AuditLogs::Table).to_owned()).await?;
+
+        manager
+            .alter_table(
+                Table::alter().table(Users::Table).drop_column(Users::TotpEnable).to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter().table(Users::Table).drop_column(Users::TotpSecret).to_owned(),
+            )
+            .await
+    }
+}
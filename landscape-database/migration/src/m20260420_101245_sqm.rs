@@ -0,0 +1,37 @@
+use sea_orm_migration::prelude::*;
+
+use crate::tables::sqm::SqmServiceConfigs;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SqmServiceConfigs::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SqmServiceConfigs::IfaceName)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(SqmServiceConfigs::Enable).boolean().not_null())
+                    .col(ColumnDef::new(SqmServiceConfigs::Qdisc).json().not_null())
+                    .col(ColumnDef::new(SqmServiceConfigs::EgressBandwidth).unsigned().not_null())
+                    .col(ColumnDef::new(SqmServiceConfigs::IngressBandwidth).unsigned().not_null())
+                    .col(ColumnDef::new(SqmServiceConfigs::Overhead).unsigned().not_null())
+                    .col(ColumnDef::new(SqmServiceConfigs::LinkLayer).json().not_null())
+                    .col(ColumnDef::new(SqmServiceConfigs::UpdateAt).double().not_null().default(0))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(SqmServiceConfigs::Table).to_owned()).await
+    }
+}
@@ -0,0 +1,31 @@
+use sea_orm_migration::prelude::*;
+
+use crate::tables::rate_limit::RateLimitConfigs;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RateLimitConfigs::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(RateLimitConfigs::Id).uuid().primary_key())
+                    .col(ColumnDef::new(RateLimitConfigs::Enable).boolean().not_null())
+                    .col(ColumnDef::new(RateLimitConfigs::Remark).string().not_null())
+                    .col(ColumnDef::new(RateLimitConfigs::Target).json().not_null())
+                    .col(ColumnDef::new(RateLimitConfigs::UploadKbps).unsigned().not_null())
+                    .col(ColumnDef::new(RateLimitConfigs::DownloadKbps).unsigned().not_null())
+                    .col(ColumnDef::new(RateLimitConfigs::UpdateAt).double().not_null().default(0))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(RateLimitConfigs::Table).to_owned()).await
+    }
+}
@@ -0,0 +1,32 @@
+use sea_orm_migration::prelude::*;
+
+use crate::tables::netns_target::NetnsTargetConfigs;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(NetnsTargetConfigs::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(NetnsTargetConfigs::Id).uuid().primary_key())
+                    .col(ColumnDef::new(NetnsTargetConfigs::Enable).boolean().not_null())
+                    .col(ColumnDef::new(NetnsTargetConfigs::Name).string().not_null())
+                    .col(ColumnDef::new(NetnsTargetConfigs::Source).json().not_null())
+                    .col(ColumnDef::new(NetnsTargetConfigs::Remark).string().not_null())
+                    .col(
+                        ColumnDef::new(NetnsTargetConfigs::UpdateAt).double().not_null().default(0),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(NetnsTargetConfigs::Table).to_owned()).await
+    }
+}
@@ -0,0 +1,42 @@
+use sea_orm_migration::prelude::*;
+
+use crate::tables::managed_container::ManagedContainerConfigs;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ManagedContainerConfigs::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(ManagedContainerConfigs::Id).uuid().primary_key())
+                    .col(ColumnDef::new(ManagedContainerConfigs::Enable).boolean().not_null())
+                    .col(ColumnDef::new(ManagedContainerConfigs::Name).string().not_null())
+                    .col(ColumnDef::new(ManagedContainerConfigs::Cmd).json().not_null())
+                    .col(ColumnDef::new(ManagedContainerConfigs::Redirect).json().null())
+                    .col(
+                        ColumnDef::new(ManagedContainerConfigs::CheckUpdate)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(ColumnDef::new(ManagedContainerConfigs::Remark).string().not_null())
+                    .col(
+                        ColumnDef::new(ManagedContainerConfigs::UpdateAt)
+                            .double()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(ManagedContainerConfigs::Table).to_owned()).await
+    }
+}
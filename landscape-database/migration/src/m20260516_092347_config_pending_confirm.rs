@@ -0,0 +1,46 @@
+use sea_orm_migration::prelude::*;
+
+use crate::tables::config_snapshot::ConfigPendingConfirms;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ConfigPendingConfirms::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(ConfigPendingConfirms::Id).uuid().primary_key())
+                    .col(ColumnDef::new(ConfigPendingConfirms::RevertTo).uuid().not_null())
+                    .col(ColumnDef::new(ConfigPendingConfirms::Applied).uuid().not_null())
+                    .col(
+                        ColumnDef::new(ConfigPendingConfirms::Author)
+                            .string()
+                            .not_null()
+                            .default(""),
+                    )
+                    .col(
+                        ColumnDef::new(ConfigPendingConfirms::Summary)
+                            .string()
+                            .not_null()
+                            .default(""),
+                    )
+                    .col(ColumnDef::new(ConfigPendingConfirms::Deadline).big_integer().not_null())
+                    .col(
+                        ColumnDef::new(ConfigPendingConfirms::UpdateAt)
+                            .double()
+                            .not_null()
+                            .default(0.0),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(ConfigPendingConfirms::Table).to_owned()).await
+    }
+}
@@ -0,0 +1,18 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveIden)]
+pub enum AuditLogs {
+    Table,
+    Id,
+    Kind,
+    Username,
+    SourceIp,
+    Method,
+    Route,
+    ConfigId,
+    Status,
+    Before,
+    After,
+    Summary,
+    UpdateAt,
+}
@@ -0,0 +1,24 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveIden)]
+pub enum ConfigSnapshots {
+    Table,
+    Id,
+    Author,
+    Summary,
+    Diff,
+    Content,
+    UpdateAt,
+}
+
+#[derive(DeriveIden)]
+pub enum ConfigPendingConfirms {
+    Table,
+    Id,
+    RevertTo,
+    Applied,
+    Author,
+    Summary,
+    Deadline,
+    UpdateAt,
+}
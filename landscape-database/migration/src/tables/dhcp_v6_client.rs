@@ -7,5 +7,8 @@ pub enum DHCPv6ClientConfigs {
     IfaceName,
     Enable,
     Mac,
+    RequestPd,
+    RequestNa,
+    AcceptRa,
     UpdateAt,
 }
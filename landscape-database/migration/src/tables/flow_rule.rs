@@ -9,5 +9,6 @@ pub enum FlowConfigs {
     FlowMatchRules,
     PacketHandleIfaceName,
     Remark,
+    HealthCheck,
     UpdateAt,
 }
@@ -5,6 +5,7 @@ pub enum NetIfaceConfigs {
     Table,
     Name, // 主键
     CreateDevType,
+    DevParams,
     ControllerName,
     ZoneType,
     EnableInBoot,
@@ -0,0 +1,15 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveIden)]
+pub enum ManagedContainerConfigs {
+    #[sea_orm(iden = "managed_container_configs")]
+    Table,
+    Id,
+    Enable,
+    Name,
+    Cmd,
+    Redirect,
+    CheckUpdate,
+    Remark,
+    UpdateAt,
+}
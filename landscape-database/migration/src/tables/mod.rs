@@ -8,7 +8,10 @@ pub mod mss_clamp;
 pub mod nat;
 pub mod pppd;
 pub mod ra;
+pub mod sqm;
 pub mod wifi;
+pub mod wifi_station;
+pub mod wireguard;
 
 pub mod dns_rule;
 pub mod dst_ip_rule;
@@ -19,5 +22,12 @@ pub mod geo;
 
 pub mod route;
 
+pub mod audit_log;
+pub mod config_snapshot;
 pub mod enrolled_device;
 pub mod firewall_blacklist;
+pub mod managed_container;
+pub mod netns_target;
+pub mod rate_limit;
+pub mod traffic_quota;
+pub mod user;
@@ -0,0 +1,13 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveIden)]
+pub enum NetnsTargetConfigs {
+    #[sea_orm(iden = "netns_target_configs")]
+    Table,
+    Id,
+    Enable,
+    Name,
+    Source,
+    Remark,
+    UpdateAt,
+}
@@ -0,0 +1,14 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveIden)]
+pub enum RateLimitConfigs {
+    #[sea_orm(iden = "rate_limit_configs")]
+    Table,
+    Id,
+    Enable,
+    Remark,
+    Target,
+    UploadKbps,
+    DownloadKbps,
+    UpdateAt,
+}
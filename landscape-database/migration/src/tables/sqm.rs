@@ -0,0 +1,15 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveIden)]
+pub enum SqmServiceConfigs {
+    #[sea_orm(iden = "sqm_service_configs")]
+    Table,
+    IfaceName,
+    Enable,
+    Qdisc,
+    EgressBandwidth,
+    IngressBandwidth,
+    Overhead,
+    LinkLayer,
+    UpdateAt,
+}
@@ -0,0 +1,24 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveIden)]
+pub enum TrafficQuotaConfigs {
+    Table,
+    Id,
+    Enable,
+    Remark,
+    Target,
+    Period,
+    LimitBytes,
+    Action,
+    UpdateAt,
+}
+
+#[derive(DeriveIden)]
+pub enum TrafficQuotaUsages {
+    Table,
+    Id,
+    PeriodStart,
+    UsedBytes,
+    ExceededAt,
+    UpdateAt,
+}
@@ -0,0 +1,28 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveIden)]
+pub enum Users {
+    Table,
+    Id,
+    Username,
+    PasswordHash,
+    Role,
+    Enable,
+    TotpSecret,
+    TotpEnable,
+    TokenVersion,
+    UpdateAt,
+}
+
+#[derive(DeriveIden)]
+pub enum ApiTokens {
+    Table,
+    Id,
+    UserId,
+    Name,
+    Role,
+    TokenHash,
+    ExpireAt,
+    Revoked,
+    UpdateAt,
+}
@@ -6,5 +6,6 @@ pub enum WifiServiceConfigs {
     IfaceName,
     Enable,
     Config,
+    ApConfig,
     UpdateAt,
 }
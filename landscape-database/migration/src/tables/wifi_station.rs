@@ -0,0 +1,10 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveIden)]
+pub enum WifiStationServiceConfigs {
+    Table,
+    IfaceName,
+    Enable,
+    Networks,
+    UpdateAt,
+}
@@ -0,0 +1,16 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveIden)]
+pub enum WireGuardServiceConfigs {
+    #[sea_orm(iden = "wireguard_service_configs")]
+    Table,
+    IfaceName,
+    Enable,
+    PrivateKey,
+    ListenPort,
+    Addresses,
+    Mtu,
+    PublicEndpoint,
+    Peers,
+    UpdateAt,
+}
@@ -0,0 +1,67 @@
+use crate::repository::UpdateActiveModel;
+use landscape_common::auth::user::ApiToken;
+use sea_orm::{entity::prelude::*, ActiveValue::Set};
+use serde::{Deserialize, Serialize};
+
+use crate::{DBId, DBJson, DBTimestamp};
+
+pub type ApiTokenModel = Model;
+pub type ApiTokenEntity = Entity;
+pub type ApiTokenActiveModel = ActiveModel;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "api_tokens")]
+#[cfg_attr(feature = "postgres", sea_orm(schema_name = "public"))]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: DBId,
+    pub user_id: DBId,
+    pub name: String,
+    #[sea_orm(column_type = "Json")]
+    pub role: DBJson,
+    #[sea_orm(unique)]
+    pub token_hash: String,
+    pub expire_at: Option<i64>,
+    pub revoked: bool,
+    pub update_at: DBTimestamp,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<Model> for ApiToken {
+    fn from(entity: Model) -> Self {
+        ApiToken {
+            id: entity.id,
+            user_id: entity.user_id,
+            name: entity.name,
+            role: serde_json::from_value(entity.role).unwrap_or_default(),
+            token_hash: entity.token_hash,
+            expire_at: entity.expire_at.map(|t| t as u64),
+            revoked: entity.revoked,
+            update_at: entity.update_at,
+        }
+    }
+}
+
+impl Into<ActiveModel> for ApiToken {
+    fn into(self) -> ActiveModel {
+        let mut active = ActiveModel { id: Set(self.id), ..Default::default() };
+        self.update(&mut active);
+        active
+    }
+}
+
+impl UpdateActiveModel<ActiveModel> for ApiToken {
+    fn update(self, active: &mut ActiveModel) {
+        active.user_id = Set(self.user_id);
+        active.name = Set(self.name);
+        active.role = Set(serde_json::to_value(&self.role).unwrap());
+        active.token_hash = Set(self.token_hash);
+        active.expire_at = Set(self.expire_at.map(|t| t as i64));
+        active.revoked = Set(self.revoked);
+        active.update_at = Set(self.update_at);
+    }
+}
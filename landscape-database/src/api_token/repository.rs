@@ -0,0 +1,46 @@
+use landscape_common::auth::user::ApiToken;
+use landscape_common::error::LdError;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+
+use super::entity::{ApiTokenActiveModel, ApiTokenEntity, ApiTokenModel, Column};
+use crate::DBId;
+
+#[derive(Clone)]
+pub struct ApiTokenRepository {
+    db: DatabaseConnection,
+}
+
+impl ApiTokenRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn find_by_token_hash(&self, token_hash: &str) -> Result<Option<ApiToken>, LdError> {
+        let model =
+            ApiTokenEntity::find().filter(Column::TokenHash.eq(token_hash)).one(&self.db).await?;
+        Ok(model.map(From::from))
+    }
+
+    pub async fn list_by_user(&self, user_id: DBId) -> Result<Vec<ApiToken>, LdError> {
+        let models = ApiTokenEntity::find()
+            .filter(Column::UserId.eq(user_id))
+            .order_by_desc(Column::UpdateAt)
+            .all(&self.db)
+            .await?;
+        Ok(models.into_iter().map(From::from).collect())
+    }
+
+    pub async fn delete_by_user(&self, user_id: DBId) -> Result<(), LdError> {
+        ApiTokenEntity::delete_many().filter(Column::UserId.eq(user_id)).exec(&self.db).await?;
+        Ok(())
+    }
+}
+
+crate::impl_repository!(
+    ApiTokenRepository,
+    ApiTokenModel,
+    ApiTokenEntity,
+    ApiTokenActiveModel,
+    ApiToken,
+    DBId
+);
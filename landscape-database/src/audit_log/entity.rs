@@ -0,0 +1,78 @@
+use crate::repository::UpdateActiveModel;
+use landscape_common::auth::audit::AuditLog;
+use sea_orm::{entity::prelude::*, ActiveValue::Set};
+use serde::{Deserialize, Serialize};
+
+use crate::{DBId, DBTimestamp};
+
+pub type AuditLogModel = Model;
+pub type AuditLogEntity = Entity;
+pub type AuditLogActiveModel = ActiveModel;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "audit_logs")]
+#[cfg_attr(feature = "postgres", sea_orm(schema_name = "public"))]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: DBId,
+    pub kind: String,
+    pub username: String,
+    pub source_ip: Option<String>,
+    pub method: String,
+    pub route: String,
+    pub config_id: Option<String>,
+    pub status: i32,
+    pub before: Option<DBId>,
+    pub after: Option<DBId>,
+    #[sea_orm(column_type = "Text")]
+    pub summary: String,
+    pub update_at: DBTimestamp,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<Model> for AuditLog {
+    fn from(entity: Model) -> Self {
+        AuditLog {
+            id: entity.id,
+            kind: serde_json::from_value(serde_json::Value::String(entity.kind)).unwrap(),
+            username: entity.username,
+            source_ip: entity.source_ip,
+            method: entity.method,
+            route: entity.route,
+            config_id: entity.config_id,
+            status: entity.status as u16,
+            before: entity.before,
+            after: entity.after,
+            summary: entity.summary,
+            update_at: entity.update_at,
+        }
+    }
+}
+
+impl Into<ActiveModel> for AuditLog {
+    fn into(self) -> ActiveModel {
+        let mut active = ActiveModel { id: Set(self.id), ..Default::default() };
+        self.update(&mut active);
+        active
+    }
+}
+
+impl UpdateActiveModel<ActiveModel> for AuditLog {
+    fn update(self, active: &mut ActiveModel) {
+        active.kind = Set(self.kind.as_str().to_string());
+        active.username = Set(self.username);
+        active.source_ip = Set(self.source_ip);
+        active.method = Set(self.method);
+        active.route = Set(self.route);
+        active.config_id = Set(self.config_id);
+        active.status = Set(self.status as i32);
+        active.before = Set(self.before);
+        active.after = Set(self.after);
+        active.summary = Set(self.summary);
+        active.update_at = Set(self.update_at);
+    }
+}
@@ -0,0 +1,71 @@
+use landscape_common::auth::audit::{AuditLog, AuditLogQuery, DEFAULT_AUDIT_QUERY_LIMIT};
+use landscape_common::error::LdError;
+use sea_orm::{
+    ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
+};
+
+use super::entity::{AuditLogActiveModel, AuditLogEntity, AuditLogModel, Column};
+use crate::DBId;
+
+#[derive(Clone)]
+pub struct AuditLogRepository {
+    db: DatabaseConnection,
+}
+
+impl AuditLogRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 按时间倒序查询
+    pub async fn query(&self, query: AuditLogQuery) -> Result<Vec<AuditLog>, LdError> {
+        let mut condition = Condition::all();
+        if let Some(username) = query.username {
+            condition = condition.add(Column::Username.eq(username));
+        }
+        if let Some(kind) = query.kind {
+            condition = condition.add(Column::Kind.eq(kind.as_str()));
+        }
+        if let Some(source_ip) = query.source_ip {
+            condition = condition.add(Column::SourceIp.eq(source_ip));
+        }
+        if let Some(start) = query.start {
+            condition = condition.add(Column::UpdateAt.gte(start));
+        }
+        if let Some(end) = query.end {
+            condition = condition.add(Column::UpdateAt.lte(end));
+        }
+        let result = AuditLogEntity::find()
+            .filter(condition)
+            .order_by_desc(Column::UpdateAt)
+            .limit(query.limit.unwrap_or(DEFAULT_AUDIT_QUERY_LIMIT))
+            .all(&self.db)
+            .await?;
+        Ok(result.into_iter().map(From::from).collect())
+    }
+
+    /// 仅保留最近的 `keep` 条记录
+    pub async fn prune(&self, keep: u64) -> Result<(), LdError> {
+        let expired: Vec<DBId> = AuditLogEntity::find()
+            .select_only()
+            .column(Column::Id)
+            .order_by_desc(Column::UpdateAt)
+            .offset(keep)
+            .into_tuple()
+            .all(&self.db)
+            .await?;
+        if !expired.is_empty() {
+            AuditLogEntity::delete_many().filter(Column::Id.is_in(expired)).exec(&self.db).await?;
+        }
+        Ok(())
+    }
+}
+
+crate::impl_repository!(
+    AuditLogRepository,
+    AuditLogModel,
+    AuditLogEntity,
+    AuditLogActiveModel,
+    AuditLog,
+    DBId
+);
@@ -0,0 +1,62 @@
+use crate::repository::UpdateActiveModel;
+use landscape_common::config::snapshot::PendingConfigConfirm;
+use sea_orm::{entity::prelude::*, ActiveValue::Set};
+use serde::{Deserialize, Serialize};
+
+use crate::{DBId, DBTimestamp};
+
+pub type ConfigPendingConfirmModel = Model;
+pub type ConfigPendingConfirmEntity = Entity;
+pub type ConfigPendingConfirmActiveModel = ActiveModel;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "config_pending_confirms")]
+#[cfg_attr(feature = "postgres", sea_orm(schema_name = "public"))]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: DBId,
+    pub revert_to: DBId,
+    pub applied: DBId,
+    pub author: String,
+    pub summary: String,
+    pub deadline: i64,
+    pub update_at: DBTimestamp,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<Model> for PendingConfigConfirm {
+    fn from(entity: Model) -> Self {
+        PendingConfigConfirm {
+            id: entity.id,
+            revert_to: entity.revert_to,
+            applied: entity.applied,
+            author: entity.author,
+            summary: entity.summary,
+            deadline: entity.deadline as u64,
+            update_at: entity.update_at,
+        }
+    }
+}
+
+impl Into<ActiveModel> for PendingConfigConfirm {
+    fn into(self) -> ActiveModel {
+        let mut active = ActiveModel { id: Set(self.id), ..Default::default() };
+        self.update(&mut active);
+        active
+    }
+}
+
+impl UpdateActiveModel<ActiveModel> for PendingConfigConfirm {
+    fn update(self, active: &mut ActiveModel) {
+        active.revert_to = Set(self.revert_to);
+        active.applied = Set(self.applied);
+        active.author = Set(self.author);
+        active.summary = Set(self.summary);
+        active.deadline = Set(self.deadline as i64);
+        active.update_at = Set(self.update_at);
+    }
+}
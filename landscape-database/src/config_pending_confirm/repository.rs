@@ -0,0 +1,37 @@
+use landscape_common::config::snapshot::PendingConfigConfirm;
+use landscape_common::error::LdError;
+use sea_orm::{DatabaseConnection, EntityTrait};
+
+use super::entity::{
+    ConfigPendingConfirmActiveModel, ConfigPendingConfirmEntity, ConfigPendingConfirmModel,
+};
+use crate::DBId;
+
+#[derive(Clone)]
+pub struct ConfigPendingConfirmRepository {
+    db: DatabaseConnection,
+}
+
+impl ConfigPendingConfirmRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 删除并返回待确认修改, 确认与超时回滚同时发生时只有一方能取到
+    pub async fn take(&self, id: DBId) -> Result<Option<PendingConfigConfirm>, LdError> {
+        let Some(model) = ConfigPendingConfirmEntity::find_by_id(id).one(&self.db).await? else {
+            return Ok(None);
+        };
+        let result = ConfigPendingConfirmEntity::delete_by_id(id).exec(&self.db).await?;
+        Ok((result.rows_affected > 0).then(|| model.into()))
+    }
+}
+
+crate::impl_repository!(
+    ConfigPendingConfirmRepository,
+    ConfigPendingConfirmModel,
+    ConfigPendingConfirmEntity,
+    ConfigPendingConfirmActiveModel,
+    PendingConfigConfirm,
+    DBId
+);
@@ -0,0 +1,83 @@
+use crate::repository::UpdateActiveModel;
+use landscape_common::config::snapshot::{ConfigSnapshot, ConfigSnapshotInfo};
+use sea_orm::{entity::prelude::*, ActiveValue::Set, FromQueryResult};
+use serde::{Deserialize, Serialize};
+
+use crate::{DBId, DBJson, DBTimestamp};
+
+pub type ConfigSnapshotModel = Model;
+pub type ConfigSnapshotEntity = Entity;
+pub type ConfigSnapshotActiveModel = ActiveModel;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "config_snapshots")]
+#[cfg_attr(feature = "postgres", sea_orm(schema_name = "public"))]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: DBId,
+    pub author: String,
+    pub summary: String,
+    #[sea_orm(column_type = "Json")]
+    pub diff: DBJson,
+    #[sea_orm(column_type = "Text")]
+    pub content: String,
+    pub update_at: DBTimestamp,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// 列表查询时不读取完整内容
+#[derive(Debug, FromQueryResult)]
+pub struct ConfigSnapshotInfoModel {
+    pub id: DBId,
+    pub author: String,
+    pub summary: String,
+    pub diff: DBJson,
+    pub update_at: DBTimestamp,
+}
+
+impl From<ConfigSnapshotInfoModel> for ConfigSnapshotInfo {
+    fn from(entity: ConfigSnapshotInfoModel) -> Self {
+        ConfigSnapshotInfo {
+            id: entity.id,
+            author: entity.author,
+            summary: entity.summary,
+            diff: serde_json::from_value(entity.diff).unwrap_or_default(),
+            update_at: entity.update_at,
+        }
+    }
+}
+
+impl From<Model> for ConfigSnapshot {
+    fn from(entity: Model) -> Self {
+        ConfigSnapshot {
+            id: entity.id,
+            author: entity.author,
+            summary: entity.summary,
+            diff: serde_json::from_value(entity.diff).unwrap_or_default(),
+            content: entity.content,
+            update_at: entity.update_at,
+        }
+    }
+}
+
+impl Into<ActiveModel> for ConfigSnapshot {
+    fn into(self) -> ActiveModel {
+        let mut active = ActiveModel { id: Set(self.id), ..Default::default() };
+        self.update(&mut active);
+        active
+    }
+}
+
+impl UpdateActiveModel<ActiveModel> for ConfigSnapshot {
+    fn update(self, active: &mut ActiveModel) {
+        active.author = Set(self.author);
+        active.summary = Set(self.summary);
+        active.diff = Set(serde_json::to_value(&self.diff).unwrap());
+        active.content = Set(self.content);
+        active.update_at = Set(self.update_at);
+    }
+}
@@ -0,0 +1,78 @@
+use landscape_common::config::snapshot::{ConfigSnapshot, ConfigSnapshotInfo};
+use landscape_common::error::LdError;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+
+use super::entity::{
+    Column, ConfigSnapshotActiveModel, ConfigSnapshotEntity, ConfigSnapshotInfoModel,
+    ConfigSnapshotModel,
+};
+use crate::DBId;
+
+#[derive(Clone)]
+pub struct ConfigSnapshotRepository {
+    db: DatabaseConnection,
+}
+
+impl ConfigSnapshotRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 按时间倒序列出快照摘要
+    pub async fn list_infos(&self) -> Result<Vec<ConfigSnapshotInfo>, LdError> {
+        let result = ConfigSnapshotEntity::find()
+            .select_only()
+            .columns([Column::Id, Column::Author, Column::Summary, Column::Diff, Column::UpdateAt])
+            .order_by_desc(Column::UpdateAt)
+            .into_model::<ConfigSnapshotInfoModel>()
+            .all(&self.db)
+            .await?;
+        Ok(result.into_iter().map(From::from).collect())
+    }
+
+    pub async fn latest(&self) -> Result<Option<ConfigSnapshot>, LdError> {
+        let result =
+            ConfigSnapshotEntity::find().order_by_desc(Column::UpdateAt).one(&self.db).await?;
+        Ok(result.map(From::from))
+    }
+
+    /// 只读取最新快照的 ID, 不加载完整内容
+    pub async fn latest_id(&self) -> Result<Option<DBId>, LdError> {
+        let result = ConfigSnapshotEntity::find()
+            .select_only()
+            .column(Column::Id)
+            .order_by_desc(Column::UpdateAt)
+            .into_tuple()
+            .one(&self.db)
+            .await?;
+        Ok(result)
+    }
+
+    /// 仅保留最近的 `keep` 个快照
+    pub async fn prune(&self, keep: u64) -> Result<(), LdError> {
+        let expired: Vec<DBId> = ConfigSnapshotEntity::find()
+            .select_only()
+            .column(Column::Id)
+            .order_by_desc(Column::UpdateAt)
+            .offset(keep)
+            .into_tuple()
+            .all(&self.db)
+            .await?;
+        if !expired.is_empty() {
+            ConfigSnapshotEntity::delete_many()
+                .filter(Column::Id.is_in(expired))
+                .exec(&self.db)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+crate::impl_repository!(
+    ConfigSnapshotRepository,
+    ConfigSnapshotModel,
+    ConfigSnapshotEntity,
+    ConfigSnapshotActiveModel,
+    ConfigSnapshot,
+    DBId
+);
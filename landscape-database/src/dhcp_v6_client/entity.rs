@@ -21,6 +21,9 @@ pub struct Model {
     pub enable: bool,
 
     pub mac: String,
+    pub request_pd: bool,
+    pub request_na: bool,
+    pub accept_ra: bool,
     pub update_at: DBTimestamp,
 }
 
@@ -32,7 +35,12 @@ impl ActiveModelBehavior for ActiveModel {}
 
 impl From<Model> for IPV6PDServiceConfig {
     fn from(entity: Model) -> Self {
-        let config = IPV6PDConfig { mac: MacAddr::from_str(&entity.mac).unwrap() };
+        let config = IPV6PDConfig {
+            mac: MacAddr::from_str(&entity.mac).unwrap(),
+            request_pd: entity.request_pd,
+            request_na: entity.request_na,
+            accept_ra: entity.accept_ra,
+        };
         IPV6PDServiceConfig {
             iface_name: entity.iface_name,
             enable: entity.enable,
@@ -57,6 +65,9 @@ impl UpdateActiveModel<ActiveModel> for IPV6PDServiceConfig {
     fn update(self, active: &mut ActiveModel) {
         active.enable = Set(self.enable);
         active.mac = Set(self.config.mac.to_string());
+        active.request_pd = Set(self.config.request_pd);
+        active.request_na = Set(self.config.request_na);
+        active.accept_ra = Set(self.config.accept_ra);
         active.update_at = Set(self.update_at);
     }
 }
@@ -45,6 +45,18 @@ impl FlowConfigRepository {
                     sea_orm::Value::Int(Some(*prefix_len as i32)),
                 ],
             ),
+            FlowEntryMatchMode::Iface { iface_name } => (
+                "json_extract(json_each.value, '$.mode.t') = 'iface' AND json_extract(json_each.value, '$.mode.iface_name') = ?",
+                vec![sea_orm::Value::String(Some(Box::new(iface_name.clone())))],
+            ),
+            FlowEntryMatchMode::Vlan { vlan_id } => (
+                "json_extract(json_each.value, '$.mode.t') = 'vlan' AND json_extract(json_each.value, '$.mode.vlan_id') = ?",
+                vec![sea_orm::Value::Int(Some(*vlan_id as i32))],
+            ),
+            FlowEntryMatchMode::BridgePort { iface_name } => (
+                "json_extract(json_each.value, '$.mode.t') = 'bridge_port' AND json_extract(json_each.value, '$.mode.iface_name') = ?",
+                vec![sea_orm::Value::String(Some(Box::new(iface_name.clone())))],
+            ),
         };
 
         let full_sql = format!(
@@ -77,6 +89,12 @@ impl FlowConfigRepository {
             "json_extract(json_each.value, '$.t') = 'netns' AND json_extract(json_each.value, '$.container_name') = ?",
             container_name,
         ),
+        FlowTarget::Namespace { name } => (
+            "json_extract(json_each.value, '$.t') = 'namespace' AND json_extract(json_each.value, '$.name') = ?",
+            name,
+        ),
+        // 本机 TProxy 出口不随网卡注册变化
+        FlowTarget::Tproxy { .. } => return Ok(vec![]),
     };
 
         let full_sql = format!(
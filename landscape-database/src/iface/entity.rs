@@ -19,6 +19,7 @@ pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub name: String,
     pub create_dev_type: CreateDevType,
+    pub dev_params: Option<DBJson>,
     pub controller_name: Option<String>,
     pub zone_type: IfaceZoneType,
     pub enable_in_boot: bool,
@@ -38,6 +39,7 @@ impl From<Model> for NetworkIfaceConfig {
         NetworkIfaceConfig {
             name: entity.name,
             create_dev_type: entity.create_dev_type,
+            dev_params: entity.dev_params.and_then(|val| serde_json::from_value(val).ok()),
             controller_name: entity.controller_name,
             zone_type: entity.zone_type,
             enable_in_boot: entity.enable_in_boot,
@@ -59,6 +61,7 @@ impl Into<ActiveModel> for NetworkIfaceConfig {
 impl UpdateActiveModel<ActiveModel> for NetworkIfaceConfig {
     fn update(self, active: &mut ActiveModel) {
         active.create_dev_type = Set(self.create_dev_type);
+        active.dev_params = Set(self.dev_params.and_then(|val| serde_json::to_value(&val).ok()));
         active.controller_name = Set(self.controller_name);
         active.zone_type = Set(self.zone_type);
         active.enable_in_boot = Set(self.enable_in_boot);
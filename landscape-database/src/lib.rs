@@ -2,6 +2,10 @@ use sea_orm::prelude::Uuid;
 
 pub mod repository;
 
+pub mod api_token;
+pub mod audit_log;
+pub mod config_pending_confirm;
+pub mod config_snapshot;
 pub mod dhcp_v4_server;
 pub mod dhcp_v6_client;
 pub mod enrolled_device;
@@ -14,12 +18,21 @@ pub mod mss_clamp;
 pub mod pppd;
 pub mod provider;
 pub mod ra;
+pub mod sqm;
+pub mod user;
 pub mod wifi;
+pub mod wifi_station;
+pub mod wireguard;
 
 pub mod dst_ip_rule;
 pub mod firewall_blacklist;
 pub mod firewall_rule;
 pub mod flow_rule;
+pub mod managed_container;
+pub mod netns_target;
+pub mod rate_limit;
+pub mod traffic_quota;
+pub mod traffic_quota_usage;
 
 pub mod geo_ip;
 pub mod geo_site;
@@ -0,0 +1,77 @@
+use crate::repository::UpdateActiveModel;
+use landscape_common::docker::managed::ManagedContainerConfig;
+use sea_orm::{entity::prelude::*, ActiveValue::Set};
+use serde::{Deserialize, Serialize};
+
+use crate::{DBId, DBJson, DBTimestamp};
+
+pub type ManagedContainerConfigModel = Model;
+pub type ManagedContainerConfigEntity = Entity;
+pub type ManagedContainerConfigActiveModel = ActiveModel;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "managed_container_configs")]
+#[cfg_attr(feature = "postgres", sea_orm(schema_name = "public"))]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: DBId,
+    pub enable: bool,
+    pub name: String,
+    #[sea_orm(column_type = "Json")]
+    pub cmd: DBJson,
+    pub redirect: Option<DBJson>,
+    pub check_update: bool,
+    pub remark: String,
+    pub update_at: DBTimestamp,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert && self.id.is_not_set() {
+            self.id = Set(Uuid::new_v4());
+        }
+        Ok(self)
+    }
+}
+
+impl From<Model> for ManagedContainerConfig {
+    fn from(entity: Model) -> Self {
+        ManagedContainerConfig {
+            id: entity.id,
+            enable: entity.enable,
+            name: entity.name,
+            cmd: serde_json::from_value(entity.cmd).unwrap(),
+            redirect: entity.redirect.and_then(|val| serde_json::from_value(val).ok()),
+            check_update: entity.check_update,
+            remark: entity.remark,
+            update_at: entity.update_at,
+        }
+    }
+}
+
+impl Into<ActiveModel> for ManagedContainerConfig {
+    fn into(self) -> ActiveModel {
+        let mut active = ActiveModel { id: Set(self.id), ..Default::default() };
+        self.update(&mut active);
+        active
+    }
+}
+
+impl UpdateActiveModel<ActiveModel> for ManagedContainerConfig {
+    fn update(self, active: &mut ActiveModel) {
+        active.enable = Set(self.enable);
+        active.name = Set(self.name);
+        active.cmd = Set(serde_json::to_value(&self.cmd).unwrap());
+        active.redirect = Set(self.redirect.and_then(|val| serde_json::to_value(&val).ok()));
+        active.check_update = Set(self.check_update);
+        active.remark = Set(self.remark);
+        active.update_at = Set(self.update_at);
+    }
+}
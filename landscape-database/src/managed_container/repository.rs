@@ -0,0 +1,27 @@
+use landscape_common::docker::managed::ManagedContainerConfig;
+use sea_orm::DatabaseConnection;
+
+use super::entity::{
+    ManagedContainerConfigActiveModel, ManagedContainerConfigEntity, ManagedContainerConfigModel,
+};
+use crate::DBId;
+
+#[derive(Clone)]
+pub struct ManagedContainerRepository {
+    db: DatabaseConnection,
+}
+
+impl ManagedContainerRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+crate::impl_repository!(
+    ManagedContainerRepository,
+    ManagedContainerConfigModel,
+    ManagedContainerConfigEntity,
+    ManagedContainerConfigActiveModel,
+    ManagedContainerConfig,
+    DBId
+);
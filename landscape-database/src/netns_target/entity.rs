@@ -0,0 +1,71 @@
+use crate::repository::UpdateActiveModel;
+use landscape_common::netns_target::NetnsTargetConfig;
+use sea_orm::{entity::prelude::*, ActiveValue::Set};
+use serde::{Deserialize, Serialize};
+
+use crate::{DBId, DBJson, DBTimestamp};
+
+pub type NetnsTargetConfigModel = Model;
+pub type NetnsTargetConfigEntity = Entity;
+pub type NetnsTargetConfigActiveModel = ActiveModel;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "netns_target_configs")]
+#[cfg_attr(feature = "postgres", sea_orm(schema_name = "public"))]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: DBId,
+    pub enable: bool,
+    pub name: String,
+    #[sea_orm(column_type = "Json")]
+    pub source: DBJson,
+    pub remark: String,
+    pub update_at: DBTimestamp,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert && self.id.is_not_set() {
+            self.id = Set(Uuid::new_v4());
+        }
+        Ok(self)
+    }
+}
+
+impl From<Model> for NetnsTargetConfig {
+    fn from(entity: Model) -> Self {
+        NetnsTargetConfig {
+            id: entity.id,
+            enable: entity.enable,
+            name: entity.name,
+            source: serde_json::from_value(entity.source).unwrap(),
+            remark: entity.remark,
+            update_at: entity.update_at,
+        }
+    }
+}
+
+impl Into<ActiveModel> for NetnsTargetConfig {
+    fn into(self) -> ActiveModel {
+        let mut active = ActiveModel { id: Set(self.id), ..Default::default() };
+        self.update(&mut active);
+        active
+    }
+}
+
+impl UpdateActiveModel<ActiveModel> for NetnsTargetConfig {
+    fn update(self, active: &mut ActiveModel) {
+        active.enable = Set(self.enable);
+        active.name = Set(self.name);
+        active.source = Set(serde_json::to_value(&self.source).unwrap());
+        active.remark = Set(self.remark);
+        active.update_at = Set(self.update_at);
+    }
+}
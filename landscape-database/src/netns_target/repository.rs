@@ -0,0 +1,27 @@
+use landscape_common::netns_target::NetnsTargetConfig;
+use sea_orm::DatabaseConnection;
+
+use super::entity::{
+    NetnsTargetConfigActiveModel, NetnsTargetConfigEntity, NetnsTargetConfigModel,
+};
+use crate::DBId;
+
+#[derive(Clone)]
+pub struct NetnsTargetRepository {
+    db: DatabaseConnection,
+}
+
+impl NetnsTargetRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+crate::impl_repository!(
+    NetnsTargetRepository,
+    NetnsTargetConfigModel,
+    NetnsTargetConfigEntity,
+    NetnsTargetConfigActiveModel,
+    NetnsTargetConfig,
+    DBId
+);
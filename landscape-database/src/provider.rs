@@ -1,12 +1,17 @@
 use std::time::Duration;
 
 use crate::repository::Repository;
+use landscape_common::config::import::ConfigImportMode;
 use landscape_common::config::{InitConfig, StoreRuntimeConfig};
-use sea_orm::{Database, DatabaseConnection};
+use landscape_common::error::LdError;
+use sea_orm::{Database, DatabaseConnection, TransactionTrait};
 
 use migration::{Migrator, MigratorTrait};
 
 use crate::{
+    api_token::repository::ApiTokenRepository, audit_log::repository::AuditLogRepository,
+    config_pending_confirm::repository::ConfigPendingConfirmRepository,
+    config_snapshot::repository::ConfigSnapshotRepository,
     dhcp_v4_server::repository::DHCPv4ServerRepository,
     dhcp_v6_client::repository::DHCPv6ClientRepository,
     dns_redirect::repository::DNSRedirectRuleRepository, dns_rule::repository::DNSRuleRepository,
@@ -18,12 +23,18 @@ use crate::{
     flow_wan::repository::FlowWanServiceRepository,
     geo_ip::repository::GeoIpSourceConfigRepository, geo_site::repository::GeoSiteConfigRepository,
     iface::repository::NetIfaceRepository, iface_ip::repository::IfaceIpServiceRepository,
+    managed_container::repository::ManagedContainerRepository,
     mss_clamp::repository::MssClampServiceRepository, nat::repository::NatServiceRepository,
-    pppd::repository::PPPDServiceRepository, ra::repository::IPV6RAServiceRepository,
+    netns_target::repository::NetnsTargetRepository, pppd::repository::PPPDServiceRepository,
+    ra::repository::IPV6RAServiceRepository, rate_limit::repository::RateLimitRepository,
     route_lan::repository::RouteLanServiceRepository,
-    route_wan::repository::RouteWanServiceRepository,
+    route_wan::repository::RouteWanServiceRepository, sqm::repository::SqmServiceRepository,
     static_nat_mapping::repository::StaticNatMappingConfigRepository,
+    traffic_quota::repository::TrafficQuotaRepository,
+    traffic_quota_usage::repository::TrafficQuotaUsageRepository, user::repository::UserRepository,
     wifi::repository::WifiServiceRepository,
+    wifi_station::repository::WifiStationServiceRepository,
+    wireguard::repository::WireGuardServiceRepository,
 };
 
 pub async fn db_action(config: &StoreRuntimeConfig, rollback: &bool, steps: &u32) {
@@ -84,6 +95,20 @@ macro_rules! define_store {
                     )*
                 }
             }
+
+            /// 在单个事务中写入导入的配置, 任一分区失败则整体回滚
+            pub async fn import_init_config(
+                &self,
+                config: InitConfig,
+                mode: ConfigImportMode,
+            ) -> Result<(), LdError> {
+                let txn = self.database.begin().await?;
+                $(
+                    self.$store_name().import_with_txn(&txn, config.$init_field, mode).await?;
+                )*
+                txn.commit().await?;
+                Ok(())
+            }
         }
     }
 }
@@ -92,6 +117,8 @@ define_store!(
     iface_store: (NetIfaceRepository, ifaces),
     dhcp_v4_server_store: (DHCPv4ServerRepository, dhcpv4_services),
     wifi_service_store: (WifiServiceRepository, wifi_configs),
+    wifi_station_service_store: (WifiStationServiceRepository, wifi_stations),
+    wireguard_service_store: (WireGuardServiceRepository, wireguards),
     firewall_service_store: (FirewallServiceRepository, firewalls),
     firewall_rule_store: (FirewallRuleRepository, firewall_rules),
     firewall_blacklist_store: (FirewallBlacklistRepository, firewall_blacklists),
@@ -105,6 +132,7 @@ define_store!(
     dhcp_v6_client_store: (DHCPv6ClientRepository, dhcpv6pds),
     ra_service_store: (IPV6RAServiceRepository, icmpras),
     mss_clamp_service_store: (MssClampServiceRepository, mss_clamps),
+    sqm_service_store: (SqmServiceRepository, sqms),
     geo_ip_rule_store: (GeoIpSourceConfigRepository, geo_ips),
     geo_site_rule_store: (GeoSiteConfigRepository, geo_sites),
     route_lan_service_store: (RouteLanServiceRepository, route_lans),
@@ -113,8 +141,41 @@ define_store!(
     dns_redirect_rule_store: (DNSRedirectRuleRepository, dns_redirects),
     dns_upstream_config_store: (DnsUpstreamRepository, dns_upstream_configs),
     enrolled_device_store: (EnrolledDeviceRepository, enrolled_devices),
+    traffic_quota_store: (TrafficQuotaRepository, traffic_quotas),
+    rate_limit_store: (RateLimitRepository, rate_limits),
+    netns_target_store: (NetnsTargetRepository, netns_targets),
+    managed_container_store: (ManagedContainerRepository, managed_containers),
 );
 
+impl LandscapeDBServiceProvider {
+    /// 配额用量属于运行数据, 不参与配置导入导出
+    pub fn traffic_quota_usage_store(&self) -> TrafficQuotaUsageRepository {
+        TrafficQuotaUsageRepository::new(self.database.clone())
+    }
+
+    /// 配置快照本身不参与配置导入导出
+    pub fn config_snapshot_store(&self) -> ConfigSnapshotRepository {
+        ConfigSnapshotRepository::new(self.database.clone())
+    }
+
+    pub fn config_pending_confirm_store(&self) -> ConfigPendingConfirmRepository {
+        ConfigPendingConfirmRepository::new(self.database.clone())
+    }
+
+    /// 用户与 API Token 不参与配置导入导出
+    pub fn user_store(&self) -> UserRepository {
+        UserRepository::new(self.database.clone())
+    }
+
+    pub fn api_token_store(&self) -> ApiTokenRepository {
+        ApiTokenRepository::new(self.database.clone())
+    }
+
+    pub fn audit_log_store(&self) -> AuditLogRepository {
+        AuditLogRepository::new(self.database.clone())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use landscape_common::config::StoreRuntimeConfig;
@@ -0,0 +1,74 @@
+use crate::repository::UpdateActiveModel;
+use landscape_common::rate_limit::RateLimitConfig;
+use sea_orm::{entity::prelude::*, ActiveValue::Set};
+use serde::{Deserialize, Serialize};
+
+use crate::{DBId, DBJson, DBTimestamp};
+
+pub type RateLimitConfigModel = Model;
+pub type RateLimitConfigEntity = Entity;
+pub type RateLimitConfigActiveModel = ActiveModel;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "rate_limit_configs")]
+#[cfg_attr(feature = "postgres", sea_orm(schema_name = "public"))]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: DBId,
+    pub enable: bool,
+    pub remark: String,
+    #[sea_orm(column_type = "Json")]
+    pub target: DBJson,
+    pub upload_kbps: u32,
+    pub download_kbps: u32,
+    pub update_at: DBTimestamp,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert && self.id.is_not_set() {
+            self.id = Set(Uuid::new_v4());
+        }
+        Ok(self)
+    }
+}
+
+impl From<Model> for RateLimitConfig {
+    fn from(entity: Model) -> Self {
+        RateLimitConfig {
+            id: entity.id,
+            enable: entity.enable,
+            remark: entity.remark,
+            target: serde_json::from_value(entity.target).unwrap(),
+            upload_kbps: entity.upload_kbps,
+            download_kbps: entity.download_kbps,
+            update_at: entity.update_at,
+        }
+    }
+}
+
+impl Into<ActiveModel> for RateLimitConfig {
+    fn into(self) -> ActiveModel {
+        let mut active = ActiveModel { id: Set(self.id), ..Default::default() };
+        self.update(&mut active);
+        active
+    }
+}
+
+impl UpdateActiveModel<ActiveModel> for RateLimitConfig {
+    fn update(self, active: &mut ActiveModel) {
+        active.enable = Set(self.enable);
+        active.remark = Set(self.remark);
+        active.target = Set(serde_json::to_value(&self.target).unwrap());
+        active.upload_kbps = Set(self.upload_kbps);
+        active.download_kbps = Set(self.download_kbps);
+        active.update_at = Set(self.update_at);
+    }
+}
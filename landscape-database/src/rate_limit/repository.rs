@@ -0,0 +1,25 @@
+use landscape_common::rate_limit::RateLimitConfig;
+use sea_orm::DatabaseConnection;
+
+use super::entity::{RateLimitConfigActiveModel, RateLimitConfigEntity, RateLimitConfigModel};
+use crate::DBId;
+
+#[derive(Clone)]
+pub struct RateLimitRepository {
+    db: DatabaseConnection,
+}
+
+impl RateLimitRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+crate::impl_repository!(
+    RateLimitRepository,
+    RateLimitConfigModel,
+    RateLimitConfigEntity,
+    RateLimitConfigActiveModel,
+    RateLimitConfig,
+    DBId
+);
@@ -1,12 +1,13 @@
 use std::fmt::Debug;
 
 use async_trait::async_trait;
+use landscape_common::config::import::ConfigImportMode;
 use landscape_common::config::FlowId;
 use landscape_common::database::repository::LandscapeDBStore;
 use landscape_common::error::LdError;
 use sea_orm::{
-    ActiveModelBehavior, ActiveModelTrait, DatabaseConnection, EntityTrait, FromQueryResult,
-    IntoActiveModel, PrimaryKeyTrait,
+    ActiveModelBehavior, ActiveModelTrait, DatabaseConnection, DatabaseTransaction, EntityTrait,
+    FromQueryResult, IntoActiveModel, PrimaryKeyTrait,
 };
 
 /// 用于将 domain data 映射到 Sea-ORM ActiveModel 的 trait
@@ -145,6 +146,32 @@ where
             }
         }
     }
+
+    /// 在事务中导入配置, Replace 模式先清空整表
+    async fn import_with_txn(
+        &self,
+        txn: &DatabaseTransaction,
+        configs: Vec<Self::Data>,
+        mode: ConfigImportMode,
+    ) -> Result<(), LdError> {
+        if mode == ConfigImportMode::Replace {
+            <Self::Entity as EntityTrait>::delete_many().exec(txn).await?;
+        }
+        for config in configs {
+            let pk_value = config.get_id().into();
+            if let Some(model) =
+                <Self::Entity as EntityTrait>::find_by_id(pk_value).one(txn).await?
+            {
+                let mut active: Self::ActiveModel = Self::Data::from(model).into();
+                config.update(&mut active);
+                active.update(txn).await?;
+            } else {
+                let active: Self::ActiveModel = config.into();
+                active.insert(txn).await?;
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Flow 过滤表达式（Sea-ORM 特定）
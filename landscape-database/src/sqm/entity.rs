@@ -0,0 +1,71 @@
+use crate::repository::UpdateActiveModel;
+use landscape_common::config::sqm::SqmServiceConfig;
+use sea_orm::{entity::prelude::*, ActiveValue::Set};
+use serde::{Deserialize, Serialize};
+
+use crate::{DBJson, DBTimestamp};
+
+pub type SqmServiceConfigModel = Model;
+pub type SqmServiceConfigEntity = Entity;
+pub type SqmServiceConfigActiveModel = ActiveModel;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "sqm_service_configs")]
+#[cfg_attr(feature = "postgres", sea_orm(schema_name = "public"))]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub iface_name: String,
+    pub enable: bool,
+    #[sea_orm(column_type = "Json")]
+    pub qdisc: DBJson,
+    pub egress_bandwidth: u32,
+    pub ingress_bandwidth: u32,
+    pub overhead: u16,
+    #[sea_orm(column_type = "Json")]
+    pub link_layer: DBJson,
+    pub update_at: DBTimestamp,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<Model> for SqmServiceConfig {
+    fn from(entity: Model) -> Self {
+        SqmServiceConfig {
+            iface_name: entity.iface_name,
+            enable: entity.enable,
+            qdisc: serde_json::from_value(entity.qdisc).unwrap_or_default(),
+            egress_bandwidth: entity.egress_bandwidth,
+            ingress_bandwidth: entity.ingress_bandwidth,
+            overhead: entity.overhead,
+            link_layer: serde_json::from_value(entity.link_layer).unwrap_or_default(),
+            update_at: entity.update_at,
+        }
+    }
+}
+
+impl Into<ActiveModel> for SqmServiceConfig {
+    fn into(self) -> ActiveModel {
+        let mut active = ActiveModel {
+            iface_name: Set(self.iface_name.clone()),
+            ..Default::default()
+        };
+        self.update(&mut active);
+        active
+    }
+}
+
+impl UpdateActiveModel<ActiveModel> for SqmServiceConfig {
+    fn update(self, active: &mut ActiveModel) {
+        active.enable = Set(self.enable);
+        active.qdisc = Set(serde_json::to_value(self.qdisc).unwrap());
+        active.egress_bandwidth = Set(self.egress_bandwidth);
+        active.ingress_bandwidth = Set(self.ingress_bandwidth);
+        active.overhead = Set(self.overhead);
+        active.link_layer = Set(serde_json::to_value(self.link_layer).unwrap());
+        active.update_at = Set(self.update_at);
+    }
+}
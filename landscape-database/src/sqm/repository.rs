@@ -0,0 +1,24 @@
+use landscape_common::config::sqm::SqmServiceConfig;
+use sea_orm::DatabaseConnection;
+
+use super::entity::{SqmServiceConfigActiveModel, SqmServiceConfigEntity, SqmServiceConfigModel};
+
+#[derive(Clone)]
+pub struct SqmServiceRepository {
+    db: DatabaseConnection,
+}
+
+impl SqmServiceRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+crate::impl_repository!(
+    SqmServiceRepository,
+    SqmServiceConfigModel,
+    SqmServiceConfigEntity,
+    SqmServiceConfigActiveModel,
+    SqmServiceConfig,
+    String
+);
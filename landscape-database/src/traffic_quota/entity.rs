@@ -0,0 +1,79 @@
+use crate::repository::UpdateActiveModel;
+use landscape_common::quota::TrafficQuotaConfig;
+use sea_orm::{entity::prelude::*, ActiveValue::Set};
+use serde::{Deserialize, Serialize};
+
+use crate::{DBId, DBJson, DBTimestamp};
+
+pub type TrafficQuotaConfigModel = Model;
+pub type TrafficQuotaConfigEntity = Entity;
+pub type TrafficQuotaConfigActiveModel = ActiveModel;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "traffic_quota_configs")]
+#[cfg_attr(feature = "postgres", sea_orm(schema_name = "public"))]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: DBId,
+    pub enable: bool,
+    pub remark: String,
+    #[sea_orm(column_type = "Json")]
+    pub target: DBJson,
+    #[sea_orm(column_type = "Json")]
+    pub period: DBJson,
+    pub limit_bytes: i64,
+    #[sea_orm(column_type = "Json")]
+    pub action: DBJson,
+    pub update_at: DBTimestamp,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert && self.id.is_not_set() {
+            self.id = Set(Uuid::new_v4());
+        }
+        Ok(self)
+    }
+}
+
+impl From<Model> for TrafficQuotaConfig {
+    fn from(entity: Model) -> Self {
+        TrafficQuotaConfig {
+            id: entity.id,
+            enable: entity.enable,
+            remark: entity.remark,
+            target: serde_json::from_value(entity.target).unwrap(),
+            period: serde_json::from_value(entity.period).unwrap(),
+            limit_bytes: entity.limit_bytes as u64,
+            action: serde_json::from_value(entity.action).unwrap(),
+            update_at: entity.update_at,
+        }
+    }
+}
+
+impl Into<ActiveModel> for TrafficQuotaConfig {
+    fn into(self) -> ActiveModel {
+        let mut active = ActiveModel { id: Set(self.id), ..Default::default() };
+        self.update(&mut active);
+        active
+    }
+}
+
+impl UpdateActiveModel<ActiveModel> for TrafficQuotaConfig {
+    fn update(self, active: &mut ActiveModel) {
+        active.enable = Set(self.enable);
+        active.remark = Set(self.remark);
+        active.target = Set(serde_json::to_value(&self.target).unwrap());
+        active.period = Set(serde_json::to_value(&self.period).unwrap());
+        active.limit_bytes = Set(self.limit_bytes as i64);
+        active.action = Set(serde_json::to_value(&self.action).unwrap());
+        active.update_at = Set(self.update_at);
+    }
+}
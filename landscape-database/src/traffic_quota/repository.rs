@@ -0,0 +1,27 @@
+use landscape_common::quota::TrafficQuotaConfig;
+use sea_orm::DatabaseConnection;
+
+use super::entity::{
+    TrafficQuotaConfigActiveModel, TrafficQuotaConfigEntity, TrafficQuotaConfigModel,
+};
+use crate::DBId;
+
+#[derive(Clone)]
+pub struct TrafficQuotaRepository {
+    db: DatabaseConnection,
+}
+
+impl TrafficQuotaRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+crate::impl_repository!(
+    TrafficQuotaRepository,
+    TrafficQuotaConfigModel,
+    TrafficQuotaConfigEntity,
+    TrafficQuotaConfigActiveModel,
+    TrafficQuotaConfig,
+    DBId
+);
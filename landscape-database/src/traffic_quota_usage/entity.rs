@@ -0,0 +1,57 @@
+use crate::repository::UpdateActiveModel;
+use landscape_common::quota::TrafficQuotaUsage;
+use sea_orm::{entity::prelude::*, ActiveValue::Set};
+use serde::{Deserialize, Serialize};
+
+use crate::{DBId, DBTimestamp};
+
+pub type TrafficQuotaUsageModel = Model;
+pub type TrafficQuotaUsageEntity = Entity;
+pub type TrafficQuotaUsageActiveModel = ActiveModel;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "traffic_quota_usages")]
+#[cfg_attr(feature = "postgres", sea_orm(schema_name = "public"))]
+pub struct Model {
+    /// 对应 traffic_quota_configs 的 id
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: DBId,
+    pub period_start: i64,
+    pub used_bytes: i64,
+    pub exceeded_at: Option<i64>,
+    pub update_at: DBTimestamp,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<Model> for TrafficQuotaUsage {
+    fn from(entity: Model) -> Self {
+        TrafficQuotaUsage {
+            id: entity.id,
+            period_start: entity.period_start as u64,
+            used_bytes: entity.used_bytes as u64,
+            exceeded_at: entity.exceeded_at.map(|t| t as u64),
+            update_at: entity.update_at,
+        }
+    }
+}
+
+impl Into<ActiveModel> for TrafficQuotaUsage {
+    fn into(self) -> ActiveModel {
+        let mut active = ActiveModel { id: Set(self.id), ..Default::default() };
+        self.update(&mut active);
+        active
+    }
+}
+
+impl UpdateActiveModel<ActiveModel> for TrafficQuotaUsage {
+    fn update(self, active: &mut ActiveModel) {
+        active.period_start = Set(self.period_start as i64);
+        active.used_bytes = Set(self.used_bytes as i64);
+        active.exceeded_at = Set(self.exceeded_at.map(|t| t as i64));
+        active.update_at = Set(self.update_at);
+    }
+}
@@ -0,0 +1,27 @@
+use landscape_common::quota::TrafficQuotaUsage;
+use sea_orm::DatabaseConnection;
+
+use super::entity::{
+    TrafficQuotaUsageActiveModel, TrafficQuotaUsageEntity, TrafficQuotaUsageModel,
+};
+use crate::DBId;
+
+#[derive(Clone)]
+pub struct TrafficQuotaUsageRepository {
+    db: DatabaseConnection,
+}
+
+impl TrafficQuotaUsageRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+crate::impl_repository!(
+    TrafficQuotaUsageRepository,
+    TrafficQuotaUsageModel,
+    TrafficQuotaUsageEntity,
+    TrafficQuotaUsageActiveModel,
+    TrafficQuotaUsage,
+    DBId
+);
@@ -0,0 +1,70 @@
+use crate::repository::UpdateActiveModel;
+use landscape_common::auth::user::LandscapeUser;
+use sea_orm::{entity::prelude::*, ActiveValue::Set};
+use serde::{Deserialize, Serialize};
+
+use crate::{DBId, DBJson, DBTimestamp};
+
+pub type UserModel = Model;
+pub type UserEntity = Entity;
+pub type UserActiveModel = ActiveModel;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "users")]
+#[cfg_attr(feature = "postgres", sea_orm(schema_name = "public"))]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: DBId,
+    #[sea_orm(unique)]
+    pub username: String,
+    pub password_hash: String,
+    #[sea_orm(column_type = "Json")]
+    pub role: DBJson,
+    pub enable: bool,
+    pub totp_secret: Option<String>,
+    pub totp_enable: bool,
+    pub token_version: u32,
+    pub update_at: DBTimestamp,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<Model> for LandscapeUser {
+    fn from(entity: Model) -> Self {
+        LandscapeUser {
+            id: entity.id,
+            username: entity.username,
+            password_hash: entity.password_hash,
+            role: serde_json::from_value(entity.role).unwrap_or_default(),
+            enable: entity.enable,
+            totp_secret: entity.totp_secret,
+            totp_enable: entity.totp_enable,
+            token_version: entity.token_version,
+            update_at: entity.update_at,
+        }
+    }
+}
+
+impl Into<ActiveModel> for LandscapeUser {
+    fn into(self) -> ActiveModel {
+        let mut active = ActiveModel { id: Set(self.id), ..Default::default() };
+        self.update(&mut active);
+        active
+    }
+}
+
+impl UpdateActiveModel<ActiveModel> for LandscapeUser {
+    fn update(self, active: &mut ActiveModel) {
+        active.username = Set(self.username);
+        active.password_hash = Set(self.password_hash);
+        active.role = Set(serde_json::to_value(&self.role).unwrap());
+        active.enable = Set(self.enable);
+        active.totp_secret = Set(self.totp_secret);
+        active.totp_enable = Set(self.totp_enable);
+        active.token_version = Set(self.token_version);
+        active.update_at = Set(self.update_at);
+    }
+}
@@ -0,0 +1,31 @@
+use landscape_common::auth::user::LandscapeUser;
+use landscape_common::error::LdError;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+
+use super::entity::{Column, UserActiveModel, UserEntity, UserModel};
+use crate::DBId;
+
+#[derive(Clone)]
+pub struct UserRepository {
+    db: DatabaseConnection,
+}
+
+impl UserRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn find_by_username(&self, username: &str) -> Result<Option<LandscapeUser>, LdError> {
+        let model = UserEntity::find().filter(Column::Username.eq(username)).one(&self.db).await?;
+        Ok(model.map(From::from))
+    }
+}
+
+crate::impl_repository!(
+    UserRepository,
+    UserModel,
+    UserEntity,
+    UserActiveModel,
+    LandscapeUser,
+    DBId
+);
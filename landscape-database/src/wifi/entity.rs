@@ -3,7 +3,7 @@ use landscape_common::config::wifi::WifiServiceConfig;
 use sea_orm::{entity::prelude::*, ActiveValue::Set};
 use serde::{Deserialize, Serialize};
 
-use crate::DBTimestamp;
+use crate::{DBJson, DBTimestamp};
 
 pub type WifiServiceConfigModel = Model;
 pub type WifiServiceConfigEntity = Entity;
@@ -17,9 +17,9 @@ pub struct Model {
     pub iface_name: String,
     pub enable: bool,
 
-    /// hostapd config file
-    #[sea_orm(column_type = "Text")]
-    pub config: String,
+    /// AP 配置
+    #[sea_orm(column_type = "Json")]
+    pub ap_config: DBJson,
 
     pub update_at: DBTimestamp,
 }
@@ -35,7 +35,7 @@ impl From<Model> for WifiServiceConfig {
         WifiServiceConfig {
             iface_name: entity.iface_name,
             enable: entity.enable,
-            config: entity.config,
+            ap: serde_json::from_value(entity.ap_config).unwrap_or_default(),
             update_at: entity.update_at,
         }
     }
@@ -55,7 +55,7 @@ impl Into<ActiveModel> for WifiServiceConfig {
 impl UpdateActiveModel<ActiveModel> for WifiServiceConfig {
     fn update(self, active: &mut ActiveModel) {
         active.enable = Set(self.enable);
-        active.config = Set(self.config);
+        active.ap_config = Set(serde_json::to_value(&self.ap).unwrap_or_default());
         active.update_at = Set(self.update_at);
     }
 }
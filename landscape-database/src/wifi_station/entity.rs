@@ -0,0 +1,58 @@
+use crate::repository::UpdateActiveModel;
+use landscape_common::config::wifi_station::WifiStationServiceConfig;
+use sea_orm::{entity::prelude::*, ActiveValue::Set};
+use serde::{Deserialize, Serialize};
+
+use crate::{DBJson, DBTimestamp};
+
+pub type WifiStationServiceConfigModel = Model;
+pub type WifiStationServiceConfigEntity = Entity;
+pub type WifiStationServiceConfigActiveModel = ActiveModel;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "wifi_station_service_configs")]
+#[cfg_attr(feature = "postgres", sea_orm(schema_name = "public"))]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub iface_name: String,
+    pub enable: bool,
+    #[sea_orm(column_type = "Json")]
+    pub networks: DBJson,
+    pub update_at: DBTimestamp,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<Model> for WifiStationServiceConfig {
+    fn from(entity: Model) -> Self {
+        WifiStationServiceConfig {
+            iface_name: entity.iface_name,
+            enable: entity.enable,
+            networks: serde_json::from_value(entity.networks).unwrap_or_default(),
+            update_at: entity.update_at,
+        }
+    }
+}
+
+impl Into<ActiveModel> for WifiStationServiceConfig {
+    fn into(self) -> ActiveModel {
+        let mut active = ActiveModel {
+            iface_name: Set(self.iface_name.clone()),
+            ..Default::default()
+        };
+        self.update(&mut active);
+        active
+    }
+}
+
+impl UpdateActiveModel<ActiveModel> for WifiStationServiceConfig {
+    fn update(self, active: &mut ActiveModel) {
+        active.enable = Set(self.enable);
+        active.networks = Set(serde_json::to_value(self.networks).unwrap());
+        active.update_at = Set(self.update_at);
+    }
+}
@@ -0,0 +1,27 @@
+use landscape_common::config::wifi_station::WifiStationServiceConfig;
+use sea_orm::DatabaseConnection;
+
+use super::entity::{
+    WifiStationServiceConfigActiveModel, WifiStationServiceConfigEntity,
+    WifiStationServiceConfigModel,
+};
+
+#[derive(Clone)]
+pub struct WifiStationServiceRepository {
+    db: DatabaseConnection,
+}
+
+impl WifiStationServiceRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+crate::impl_repository!(
+    WifiStationServiceRepository,
+    WifiStationServiceConfigModel,
+    WifiStationServiceConfigEntity,
+    WifiStationServiceConfigActiveModel,
+    WifiStationServiceConfig,
+    String
+);
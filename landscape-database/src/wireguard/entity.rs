@@ -0,0 +1,74 @@
+use crate::repository::UpdateActiveModel;
+use landscape_common::config::wireguard::WireGuardServiceConfig;
+use sea_orm::{entity::prelude::*, ActiveValue::Set};
+use serde::{Deserialize, Serialize};
+
+use crate::{DBJson, DBTimestamp};
+
+pub type WireGuardServiceConfigModel = Model;
+pub type WireGuardServiceConfigEntity = Entity;
+pub type WireGuardServiceConfigActiveModel = ActiveModel;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "wireguard_service_configs")]
+#[cfg_attr(feature = "postgres", sea_orm(schema_name = "public"))]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub iface_name: String,
+    pub enable: bool,
+    pub private_key: String,
+    pub listen_port: Option<u16>,
+    #[sea_orm(column_type = "Json")]
+    pub addresses: DBJson,
+    pub mtu: Option<u32>,
+    pub public_endpoint: Option<String>,
+    #[sea_orm(column_type = "Json")]
+    pub peers: DBJson,
+    pub update_at: DBTimestamp,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<Model> for WireGuardServiceConfig {
+    fn from(entity: Model) -> Self {
+        WireGuardServiceConfig {
+            iface_name: entity.iface_name,
+            enable: entity.enable,
+            private_key: entity.private_key,
+            listen_port: entity.listen_port,
+            addresses: serde_json::from_value(entity.addresses).unwrap_or_default(),
+            mtu: entity.mtu,
+            public_endpoint: entity.public_endpoint,
+            peers: serde_json::from_value(entity.peers).unwrap_or_default(),
+            update_at: entity.update_at,
+        }
+    }
+}
+
+impl Into<ActiveModel> for WireGuardServiceConfig {
+    fn into(self) -> ActiveModel {
+        let mut active = ActiveModel {
+            iface_name: Set(self.iface_name.clone()),
+            ..Default::default()
+        };
+        self.update(&mut active);
+        active
+    }
+}
+
+impl UpdateActiveModel<ActiveModel> for WireGuardServiceConfig {
+    fn update(self, active: &mut ActiveModel) {
+        active.enable = Set(self.enable);
+        active.private_key = Set(self.private_key);
+        active.listen_port = Set(self.listen_port);
+        active.addresses = Set(serde_json::to_value(self.addresses).unwrap());
+        active.mtu = Set(self.mtu);
+        active.public_endpoint = Set(self.public_endpoint);
+        active.peers = Set(serde_json::to_value(self.peers).unwrap());
+        active.update_at = Set(self.update_at);
+    }
+}
@@ -0,0 +1,26 @@
+use landscape_common::config::wireguard::WireGuardServiceConfig;
+use sea_orm::DatabaseConnection;
+
+use super::entity::{
+    WireGuardServiceConfigActiveModel, WireGuardServiceConfigEntity, WireGuardServiceConfigModel,
+};
+
+#[derive(Clone)]
+pub struct WireGuardServiceRepository {
+    db: DatabaseConnection,
+}
+
+impl WireGuardServiceRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+crate::impl_repository!(
+    WireGuardServiceRepository,
+    WireGuardServiceConfigModel,
+    WireGuardServiceConfigEntity,
+    WireGuardServiceConfigActiveModel,
+    WireGuardServiceConfig,
+    String
+);
@@ -291,6 +291,7 @@ impl DnsRequestHandler {
         start_time: Instant,
         src_ip: std::net::IpAddr,
         answers: Vec<String>,
+        rule: Option<&ResolutionRule>,
     ) {
         if let Some(msg_tx) = &self.msg_tx {
             let dns_metric = DnsMetric {
@@ -304,6 +305,8 @@ impl DnsRequestHandler {
                 duration_ms: start_time.elapsed().as_millis() as u32,
                 src_ip,
                 answers,
+                rule: rule.map(|rule| rule.rule_name().to_string()),
+                upstream: rule.map(|rule| rule.upstream_name()),
             };
             let _ = msg_tx.try_send(DnsMetricMessage::Metric(dns_metric));
         }
@@ -356,6 +359,8 @@ impl RequestHandler for DnsRequestHandler {
 
         let mut records = vec![];
         let mut status = DnsResultStatus::Normal;
+        let resolves = self.resolves.load();
+        let mut matched_rule = None;
 
         // 1. Redirects
         if let Some((redirect_records, redirect_status, _)) =
@@ -378,11 +383,11 @@ impl RequestHandler for DnsRequestHandler {
         }
         // 3. Resolution Rules (with Early Filter check)
         else {
-            let resolves = self.resolves.load();
             let mut resolved = false;
             for (_index, resolver) in resolves.iter() {
                 if resolver.is_match(&domain) {
                     resolved = true;
+                    matched_rule = Some(resolver);
                     let filter = resolver.filter_mode();
 
                     // Early return if current query type is filtered by rule
@@ -433,6 +438,7 @@ impl RequestHandler for DnsRequestHandler {
                                 start_time,
                                 src_ip,
                                 vec![],
+                                Some(resolver),
                             );
                             return self.send_error_response(request, response_handle, code).await;
                         }
@@ -469,6 +475,7 @@ impl RequestHandler for DnsRequestHandler {
             start_time,
             src_ip,
             answers,
+            matched_rule,
         );
 
         match result {
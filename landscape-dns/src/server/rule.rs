@@ -115,6 +115,20 @@ impl ResolutionRule {
         self.config.id
     }
 
+    pub fn rule_name(&self) -> &str {
+        &self.config.name
+    }
+
+    /// 上游名称, 未填写备注时使用上游 ID
+    pub fn upstream_name(&self) -> String {
+        let upstream = &self.config.resolve_mode;
+        if upstream.remark.is_empty() {
+            upstream.id.to_string()
+        } else {
+            upstream.remark.clone()
+        }
+    }
+
     /// 确定是不是当前规则进行处理
     pub fn is_match(&self, domain: &str) -> bool {
         let match_result = if self.config.source.is_empty() {
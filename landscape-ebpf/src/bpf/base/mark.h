@@ -15,10 +15,33 @@
 #define FLOW_FROM_WAN 4
 
 #define FLOW_SOURCE_MASK 0xFF000000
-#define FLOW_ACTION_MASK 0x00007F00
+#define FLOW_ACTION_MASK 0x00000F00
 #define FLOW_ALLOW_REUSE_PORT_MASK 0x00008000
 #define FLOW_ID_MASK 0x000000FF
 
+// QoS: 12~14 位为优先级分类, 16~21 位为 DSCP, 22 位表示需要改写 DSCP
+#define FLOW_QOS_CLASS_MASK 0x00007000
+#define FLOW_QOS_DSCP_MASK 0x003F0000
+#define FLOW_QOS_DSCP_SET_MASK 0x00400000
+#define FLOW_QOS_MASK (FLOW_QOS_CLASS_MASK | FLOW_QOS_DSCP_MASK | FLOW_QOS_DSCP_SET_MASK)
+
+// 23 位表示已交给本机 TProxy, 与 LANDSCAPE_TPROXY_MARK 一致
+#define FLOW_TPROXY_MASK 0x00800000
+
+#define FLOW_QOS_CLASS_UNSET 0
+#define FLOW_QOS_CLASS_BULK 1
+#define FLOW_QOS_CLASS_BEST_EFFORT 2
+#define FLOW_QOS_CLASS_VIDEO 3
+#define FLOW_QOS_CLASS_VOICE 4
+
+// SQM 根队列的 handle (1:) 与各分类的 minor, 与 landscape/src/sqm/tc.rs 一致
+// skb->priority 为 1:minor 时 HTB 直接选择对应子分类, cake diffserv4 选择第 minor - 1 个 tin
+#define SQM_QDISC_HANDLE 0x00010000
+#define SQM_CLASS_BULK 1
+#define SQM_CLASS_BEST_EFFORT 2
+#define SQM_CLASS_VIDEO 3
+#define SQM_CLASS_VOICE 4
+
 // 替换 FLOW_ID_MASK 对应的 0~7 位
 static __always_inline u32 replace_flow_id(u32 original, u8 new_id) {
     original &= ~FLOW_ID_MASK;         // 清除原来的 ID 部分
@@ -26,10 +49,10 @@ static __always_inline u32 replace_flow_id(u32 original, u8 new_id) {
     return original;
 }
 
-// 替换 FLOW_ACTION_MASK 对应的 8~14 位
+// 替换 FLOW_ACTION_MASK 对应的 8~11 位
 static __always_inline u32 replace_flow_action(u32 original, u8 new_action) {
     original &= ~FLOW_ACTION_MASK;              // 清除原来的 Action 部分
-    original |= ((u32)new_action & 0x7F) << 8;  // 只取低 7 bit，写入 8~14 位
+    original |= ((u32)new_action & 0x0F) << 8;  // 只取低 4 bit，写入 8~11 位
     return original;
 }
 
@@ -53,7 +76,7 @@ static __always_inline u8 get_flow_id(u32 original) { return (original & FLOW_ID
 
 // 获取 action
 static __always_inline u8 get_flow_action(u32 original) {
-    return (original & FLOW_ACTION_MASK) >> 8;  // 返回 0–127
+    return (original & FLOW_ACTION_MASK) >> 8;  // 返回 0–15
 }
 
 // 获取 reuse port 标志
@@ -65,6 +88,45 @@ static __always_inline u8 get_flow_source(u32 original) {
     return (original & FLOW_SOURCE_MASK) >> 24;
 }
 
+static __always_inline u8 get_flow_qos_class(u32 original) {
+    return (original & FLOW_QOS_CLASS_MASK) >> 12;
+}
+
+static __always_inline bool get_flow_qos_has_dscp(u32 original) {
+    return (original & FLOW_QOS_DSCP_SET_MASK) != 0;
+}
+
+static __always_inline u8 get_flow_qos_dscp(u32 original) {
+    return (original & FLOW_QOS_DSCP_MASK) >> 16;
+}
+
+// 规则未设置的 QoS 项沿用 Flow 入口规则的设置
+static __always_inline u32 merge_flow_qos(u32 original, u32 entry_qos) {
+    if (!get_flow_qos_has_dscp(original)) {
+        original |= entry_qos & (FLOW_QOS_DSCP_MASK | FLOW_QOS_DSCP_SET_MASK);
+    }
+    if (get_flow_qos_class(original) == FLOW_QOS_CLASS_UNSET) {
+        original |= entry_qos & FLOW_QOS_CLASS_MASK;
+    }
+    return original;
+}
+
+// 优先级分类对应的 skb->priority (SQM 的 classid), 未设置时返回 -1
+static __always_inline int flow_qos_class_to_priority(u8 qos_class) {
+    switch (qos_class) {
+    case FLOW_QOS_CLASS_VOICE:
+        return SQM_QDISC_HANDLE | SQM_CLASS_VOICE;
+    case FLOW_QOS_CLASS_VIDEO:
+        return SQM_QDISC_HANDLE | SQM_CLASS_VIDEO;
+    case FLOW_QOS_CLASS_BEST_EFFORT:
+        return SQM_QDISC_HANDLE | SQM_CLASS_BEST_EFFORT;
+    case FLOW_QOS_CLASS_BULK:
+        return SQM_QDISC_HANDLE | SQM_CLASS_BULK;
+    default:
+        return -1;
+    }
+}
+
 // INGRESS MARK
 #define INGRESS_NO_MARK 0
 #define INGRESS_STATIC_MARK 1
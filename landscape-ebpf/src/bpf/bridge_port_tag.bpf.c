@@ -0,0 +1,15 @@
+#include <vmlinux.h>
+
+#include <bpf/bpf_helpers.h>
+
+#include "landscape.h"
+
+char LICENSE[] SEC("license") = "Dual BSD/GPL";
+
+// 挂载在网桥端口的入口, 将端口的 ifindex 写入 tc_index.
+// 进入网桥后 ingress_ifindex 变为网桥自身, 网桥上的 route_lan 依靠 tc_index 区分端口
+SEC("tc/ingress")
+int bridge_port_tag(struct __sk_buff *skb) {
+    skb->tc_index = skb->ifindex & 0xFFFF;
+    return TC_ACT_UNSPEC;
+}
@@ -6,6 +6,9 @@
 
 #define FLOW_ENTRY_MODE_MAC 0
 #define FLOW_ENTRY_MODE_IP 1
+#define FLOW_ENTRY_MODE_IFACE 2
+#define FLOW_ENTRY_MODE_VLAN 3
+#define FLOW_ENTRY_MODE_BRIDGE_PORT 4
 
 // IPV4 32 + 32 = 64
 #define FLOW_IP_IPV4_MATCH_LEN 64
@@ -15,6 +18,9 @@
 // IPV4 32 + 48 = 80
 #define FLOW_MAC_MATCH_LEN 80
 
+// 32 + 32 = 64, ifindex / VLAN ID / 网桥端口标记 存放于 src_addr.ip
+#define FLOW_INDEX_MATCH_LEN 64
+
 struct imac_addr {
     u8 mac[6];
 };
@@ -35,7 +41,7 @@ struct flow_match_key {
 
     u8 _pad[2];
     union {
-        // 源 IP 地址
+        // 源 IP 地址, 或 ifindex / VLAN ID / 网桥端口标记
         union u_inet_addr src_addr;
         // MAC
         struct imac_addr mac;
@@ -45,18 +51,47 @@ struct flow_match_key {
 struct {
     __uint(type, BPF_MAP_TYPE_LPM_TRIE);
     __type(key, struct flow_match_key);
+    // flow_id | 入口规则的 QoS 位
     __type(value, u32);
     __uint(max_entries, 65536);
     __uint(pinning, LIBBPF_PIN_BY_NAME);
     __uint(map_flags, BPF_F_NO_PREALLOC | BPF_F_RDONLY_PROG);
 } flow_match_map SEC(".maps");
 
+static __always_inline void lookup_flow_index(struct flow_match_key *match_key, u8 mode,
+                                              u32 index, u32 *flow_id_) {
+    match_key->is_match_ip = mode;
+    match_key->src_addr.ip = index;
+    u32 *flow_id_ptr = bpf_map_lookup_elem(&flow_match_map, match_key);
+    if (flow_id_ptr != NULL) {
+        *flow_id_ = *flow_id_ptr;
+    }
+}
+
+// 按入口网卡 / VLAN / 网桥端口匹配, 范围更小的规则覆盖范围更大的规则,
+// 之后的 MAC / IP 规则会再次覆盖这里的结果
+static __always_inline void match_flow_id_by_ingress(struct __sk_buff *skb, u32 *flow_id_) {
+    struct flow_match_key match_key = {0};
+    match_key.prefixlen = FLOW_INDEX_MATCH_LEN;
+
+    lookup_flow_index(&match_key, FLOW_ENTRY_MODE_IFACE, skb->ingress_ifindex, flow_id_);
+    if (skb->vlan_present) {
+        lookup_flow_index(&match_key, FLOW_ENTRY_MODE_VLAN, skb->vlan_tci & 0x0FFF, flow_id_);
+    }
+    // 由网桥端口上的 bridge_port_tag 写入
+    if (skb->tc_index != 0) {
+        lookup_flow_index(&match_key, FLOW_ENTRY_MODE_BRIDGE_PORT, skb->tc_index, flow_id_);
+    }
+}
+
 static __always_inline int match_flow_id_v4(struct __sk_buff *skb, u32 current_l3_offset,
                                             __be32 saddr, u32 *default_flow_id_) {
 #define BPF_LOG_TOPIC "match_flow_id_v4"
     struct flow_match_key match_key = {0};
     u32 ret_flow_id = *default_flow_id_;
 
+    match_flow_id_by_ingress(skb, &ret_flow_id);
+
     if (current_l3_offset != 0) {
         u8 *mac;
         if (VALIDATE_READ_DATA(skb, &mac, 6, 6)) {
@@ -109,6 +144,8 @@ static __always_inline int match_flow_id_v6(struct __sk_buff *skb, u32 current_l
     struct flow_match_key match_key = {0};
     u32 ret_flow_id = *default_flow_id_;
 
+    match_flow_id_by_ingress(skb, &ret_flow_id);
+
     if (current_l3_offset != 0) {
         u8 *mac;
         if (VALIDATE_READ_DATA(skb, &mac, 6, 6)) {
@@ -35,7 +35,7 @@ int reuseport_dns_dispatcher(struct sk_reuseport_md *reuse_md) {
 
         u32 *flow_id_ptr = bpf_map_lookup_elem(&flow_match_map, &match_key);
         if (flow_id_ptr != NULL) {
-            flow_id = *flow_id_ptr;
+            flow_id = *flow_id_ptr & FLOW_ID_MASK;
         }
     }
 
@@ -65,7 +65,7 @@ int reuseport_dns_dispatcher(struct sk_reuseport_md *reuse_md) {
 
     u32 *flow_id_ptr = bpf_map_lookup_elem(&flow_match_map, &match_key);
     if (flow_id_ptr != NULL) {
-        flow_id = *flow_id_ptr;
+        flow_id = *flow_id_ptr & FLOW_ID_MASK;
     }
 
     // bpf_log_info("find flow_id: %d", flow_id);
@@ -96,6 +96,8 @@ struct nat_timer_value_v4 {
     u64 egress_bytes;
     u64 egress_packets;
     u32 cpu_id;
+    // 连接所在的 WAN 网卡
+    u32 ifindex;
 };
 
 //
@@ -124,6 +126,7 @@ struct nat_timer_value_v6 {
     u64 egress_bytes;
     u64 egress_packets;
     u32 cpu_id;
+    u32 ifindex;
     u8 client_prefix[8];
 };
 
@@ -240,6 +240,7 @@ static __always_inline int nat_metric_try_report_v4(struct nat_timer_key_v4 *tim
     event->egress_bytes = timer_value->egress_bytes;
     event->egress_packets = timer_value->egress_packets;
     event->cpu_id = timer_value->cpu_id;
+    event->ifindex = timer_value->ifindex;
     event->status = status;
     event->gress = timer_value->gress;
     bpf_ringbuf_submit(event, 0);
@@ -475,6 +476,7 @@ static __always_inline int lookup_or_new_ct(struct __sk_buff *skb, u8 l4proto, b
     timer_value_new.create_time = bpf_ktime_get_ns();
     timer_value_new.flow_id = flow_id;
     timer_value_new.cpu_id = bpf_get_smp_processor_id();
+    timer_value_new.ifindex = skb->ifindex;
     timer_value = insert_new_nat_timer(l4proto, &timer_key, &timer_value_new);
     if (timer_value == NULL) {
         return TIMER_ERROR;
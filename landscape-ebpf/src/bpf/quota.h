@@ -0,0 +1,126 @@
+#ifndef __LD_QUOTA_H__
+#define __LD_QUOTA_H__
+#include <vmlinux.h>
+#include <bpf/bpf_helpers.h>
+#include "landscape.h"
+
+#define QUOTA_SCOPE_MAC 1
+#define QUOTA_SCOPE_FLOW 2
+#define QUOTA_SCOPE_IFINDEX 3
+
+#define QUOTA_ACTION_DROP 1
+#define QUOTA_ACTION_THROTTLE 2
+
+#define QUOTA_NS_PER_SEC 1000000000ULL
+
+struct quota_limit_key {
+    u8 scope;
+    u8 _pad[3];
+    // flow_id 或 ifindex
+    u32 id;
+    u8 mac[6];
+    u8 _pad2[2];
+};
+
+struct quota_limit_value {
+    struct bpf_spin_lock lock;
+    u8 action;
+    u8 _pad[3];
+    // 限速值, 字节每秒, 同时作为令牌桶容量
+    u64 rate;
+    u64 tokens;
+    u64 last_ns;
+};
+
+// 超出配额后由用户态写入的处理动作
+struct {
+    __uint(type, BPF_MAP_TYPE_HASH);
+    __type(key, struct quota_limit_key);
+    __type(value, struct quota_limit_value);
+    __uint(max_entries, 1024);
+    __uint(map_flags, BPF_F_NO_PREALLOC);
+    __uint(pinning, LIBBPF_PIN_BY_NAME);
+} quota_limit_map SEC(".maps");
+
+static __always_inline int quota_limit_check(const struct quota_limit_key *key, u32 len) {
+    struct quota_limit_value *value = bpf_map_lookup_elem(&quota_limit_map, key);
+    if (value == NULL) {
+        return TC_ACT_OK;
+    }
+
+    if (value->action == QUOTA_ACTION_DROP) {
+        return TC_ACT_SHOT;
+    }
+
+    if (value->action != QUOTA_ACTION_THROTTLE) {
+        return TC_ACT_OK;
+    }
+
+    int ret = TC_ACT_OK;
+    u64 now = bpf_ktime_get_ns();
+
+    bpf_spin_lock(&value->lock);
+    u64 elapsed = now - value->last_ns;
+    if (elapsed > QUOTA_NS_PER_SEC) {
+        elapsed = QUOTA_NS_PER_SEC;
+    }
+    u64 tokens = value->tokens + elapsed * value->rate / QUOTA_NS_PER_SEC;
+    if (tokens > value->rate) {
+        tokens = value->rate;
+    }
+    if (tokens < len) {
+        ret = TC_ACT_SHOT;
+    } else {
+        tokens -= len;
+    }
+    value->tokens = tokens;
+    value->last_ns = now;
+    bpf_spin_unlock(&value->lock);
+
+    return ret;
+}
+
+static __always_inline int quota_check_mac_at(struct __sk_buff *skb, u32 current_l3_offset,
+                                              u32 mac_offset) {
+    if (current_l3_offset == 0) {
+        return TC_ACT_OK;
+    }
+
+    struct quota_limit_key key = {0};
+    u8 *mac;
+    if (VALIDATE_READ_DATA(skb, &mac, mac_offset, 6)) {
+        return TC_ACT_OK;
+    }
+    key.scope = QUOTA_SCOPE_MAC;
+    __builtin_memcpy(key.mac, mac, 6);
+    return quota_limit_check(&key, skb->len);
+}
+
+// 上传方向, 源 MAC 位于以太网头第 6 字节
+static __always_inline int quota_check_src_mac(struct __sk_buff *skb, u32 current_l3_offset) {
+    return quota_check_mac_at(skb, current_l3_offset, 6);
+}
+
+// 下载方向, 在 LAN 出口按目的 MAC 匹配设备
+static __always_inline int quota_check_dst_mac(struct __sk_buff *skb, u32 current_l3_offset) {
+    return quota_check_mac_at(skb, current_l3_offset, 0);
+}
+
+static __always_inline int quota_check_flow(struct __sk_buff *skb, u32 flow_id) {
+    if (flow_id == 0) {
+        return TC_ACT_OK;
+    }
+    struct quota_limit_key key = {0};
+    key.scope = QUOTA_SCOPE_FLOW;
+    key.id = flow_id;
+    return quota_limit_check(&key, skb->len);
+}
+
+static __always_inline int quota_check_ifindex(struct __sk_buff *skb) {
+    struct quota_limit_key key = {0};
+    key.scope = QUOTA_SCOPE_IFINDEX;
+    key.id = skb->ifindex;
+    return quota_limit_check(&key, skb->len);
+}
+
+#endif /* __LD_QUOTA_H__ */
@@ -0,0 +1,213 @@
+#ifndef __LD_RATE_LIMIT_H__
+#define __LD_RATE_LIMIT_H__
+#include <vmlinux.h>
+#include <bpf/bpf_helpers.h>
+#include "landscape.h"
+#include "flow_match.h"
+
+#define RATE_LIMIT_SCOPE_MAC 1
+#define RATE_LIMIT_SCOPE_IPV4 2
+#define RATE_LIMIT_SCOPE_IPV6 3
+#define RATE_LIMIT_SCOPE_FLOW 4
+
+#define RATE_LIMIT_UPLOAD 0
+#define RATE_LIMIT_DOWNLOAD 1
+
+#define RATE_LIMIT_NS_PER_SEC 1000000000ULL
+// 发送时间超出当前时间该值的包直接丢弃, 避免 fq 中排队过长
+#define RATE_LIMIT_DROP_HORIZON_NS (2 * RATE_LIMIT_NS_PER_SEC)
+
+struct rate_limit_key {
+    u8 scope;
+    // RATE_LIMIT_UPLOAD | RATE_LIMIT_DOWNLOAD
+    u8 direction;
+    u8 _pad[2];
+    u32 flow_id;
+    union u_inet_addr addr;
+    u8 mac[6];
+    u8 _pad2[2];
+};
+
+struct rate_limit_value {
+    struct bpf_spin_lock lock;
+    u32 _pad;
+    // 限速值, 字节每秒
+    u64 rate;
+    // 上一个包的发送时间
+    u64 t_last;
+    // 累计字节数与丢包数, 由用户态采样计算速率
+    u64 bytes;
+    u64 drops;
+};
+
+struct {
+    __uint(type, BPF_MAP_TYPE_HASH);
+    __type(key, struct rate_limit_key);
+    __type(value, struct rate_limit_value);
+    __uint(max_entries, 4096);
+    __uint(map_flags, BPF_F_NO_PREALLOC);
+    __uint(pinning, LIBBPF_PIN_BY_NAME);
+} rate_limit_map SEC(".maps");
+
+// EDT: 按速率计算每个包的最早发送时间, 写入 skb->tstamp 后由 fq qdisc 完成排队
+static __always_inline int rate_limit_edt(struct __sk_buff *skb,
+                                          const struct rate_limit_key *key) {
+    struct rate_limit_value *value = bpf_map_lookup_elem(&rate_limit_map, key);
+    if (value == NULL) {
+        return TC_ACT_OK;
+    }
+
+    u64 now = bpf_ktime_get_ns();
+    u64 t = now;
+    // 已被前一个限速规则推迟的包从推迟后的时间开始计算
+    if (skb->tstamp_type == BPF_SKB_TSTAMP_DELIVERY_MONO && skb->tstamp > now) {
+        t = skb->tstamp;
+    }
+
+    int ret = TC_ACT_OK;
+    u64 next = t;
+
+    bpf_spin_lock(&value->lock);
+    if (value->rate == 0) {
+        value->bytes += skb->len;
+        bpf_spin_unlock(&value->lock);
+        return TC_ACT_OK;
+    }
+    u64 delay = (u64)skb->len * RATE_LIMIT_NS_PER_SEC / value->rate;
+    u64 candidate = value->t_last + delay;
+    if (candidate <= t) {
+        value->t_last = t;
+        value->bytes += skb->len;
+    } else if (candidate - now > RATE_LIMIT_DROP_HORIZON_NS) {
+        value->drops += 1;
+        ret = TC_ACT_SHOT;
+    } else {
+        value->t_last = candidate;
+        value->bytes += skb->len;
+        next = candidate;
+    }
+    bpf_spin_unlock(&value->lock);
+
+    if (ret == TC_ACT_OK && next > now) {
+        bpf_skb_set_tstamp(skb, next, BPF_SKB_TSTAMP_DELIVERY_MONO);
+    }
+    return ret;
+}
+
+static __always_inline int rate_limit_check_mac(struct __sk_buff *skb, u32 current_l3_offset,
+                                                u8 direction) {
+    if (current_l3_offset == 0) {
+        return TC_ACT_OK;
+    }
+
+    struct rate_limit_key key = {0};
+    u8 *mac;
+    // 上传按源 MAC (第 6 字节), 下载按目的 MAC (第 0 字节)
+    u32 mac_offset = direction == RATE_LIMIT_UPLOAD ? 6 : 0;
+    if (VALIDATE_READ_DATA(skb, &mac, mac_offset, 6)) {
+        return TC_ACT_OK;
+    }
+    key.scope = RATE_LIMIT_SCOPE_MAC;
+    key.direction = direction;
+    __builtin_memcpy(key.mac, mac, 6);
+    return rate_limit_edt(skb, &key);
+}
+
+static __always_inline int rate_limit_check_flow(struct __sk_buff *skb, u32 flow_id,
+                                                 u8 direction) {
+    if (flow_id == 0) {
+        return TC_ACT_OK;
+    }
+    struct rate_limit_key key = {0};
+    key.scope = RATE_LIMIT_SCOPE_FLOW;
+    key.direction = direction;
+    key.flow_id = flow_id;
+    return rate_limit_edt(skb, &key);
+}
+
+static __always_inline int rate_limit_check_v4(struct __sk_buff *skb, u32 current_l3_offset,
+                                               __be32 addr, u8 direction) {
+    int ret = rate_limit_check_mac(skb, current_l3_offset, direction);
+    if (ret != TC_ACT_OK) {
+        return ret;
+    }
+
+    struct rate_limit_key key = {0};
+    key.scope = RATE_LIMIT_SCOPE_IPV4;
+    key.direction = direction;
+    key.addr.ip = addr;
+    return rate_limit_edt(skb, &key);
+}
+
+static __always_inline int rate_limit_check_v6(struct __sk_buff *skb, u32 current_l3_offset,
+                                               const union u_inet6_addr *addr, u8 direction) {
+    int ret = rate_limit_check_mac(skb, current_l3_offset, direction);
+    if (ret != TC_ACT_OK) {
+        return ret;
+    }
+
+    struct rate_limit_key key = {0};
+    key.scope = RATE_LIMIT_SCOPE_IPV6;
+    key.direction = direction;
+    COPY_ADDR_FROM(key.addr.all, addr->bytes);
+    return rate_limit_edt(skb, &key);
+}
+
+// 下载方向没有经过 flow_verdict, 按目的 MAC / IP 反查所属的 Flow
+static __always_inline u32 rate_limit_lookup_dst_flow(struct __sk_buff *skb,
+                                                      u32 current_l3_offset,
+                                                      struct flow_match_key *match_key) {
+    u32 flow_id = 0;
+    u32 *flow_id_ptr = bpf_map_lookup_elem(&flow_match_map, match_key);
+    if (flow_id_ptr != NULL) {
+        flow_id = *flow_id_ptr;
+    } else if (current_l3_offset != 0) {
+        struct flow_match_key mac_key = {0};
+        u8 *mac;
+        if (VALIDATE_READ_DATA(skb, &mac, 0, 6)) {
+            return 0;
+        }
+        __builtin_memcpy(mac_key.mac.mac, mac, 6);
+        mac_key.prefixlen = FLOW_MAC_MATCH_LEN;
+        mac_key.is_match_ip = FLOW_ENTRY_MODE_MAC;
+        flow_id_ptr = bpf_map_lookup_elem(&flow_match_map, &mac_key);
+        if (flow_id_ptr != NULL) {
+            flow_id = *flow_id_ptr;
+        }
+    }
+    return get_flow_id(flow_id);
+}
+
+static __always_inline int rate_limit_download_v4(struct __sk_buff *skb, u32 current_l3_offset,
+                                                  __be32 daddr) {
+    int ret = rate_limit_check_v4(skb, current_l3_offset, daddr, RATE_LIMIT_DOWNLOAD);
+    if (ret != TC_ACT_OK) {
+        return ret;
+    }
+
+    struct flow_match_key match_key = {0};
+    match_key.l3_protocol = LANDSCAPE_IPV4_TYPE;
+    match_key.is_match_ip = FLOW_ENTRY_MODE_IP;
+    match_key.prefixlen = FLOW_IP_IPV4_MATCH_LEN;
+    match_key.src_addr.ip = daddr;
+    u32 flow_id = rate_limit_lookup_dst_flow(skb, current_l3_offset, &match_key);
+    return rate_limit_check_flow(skb, flow_id, RATE_LIMIT_DOWNLOAD);
+}
+
+static __always_inline int rate_limit_download_v6(struct __sk_buff *skb, u32 current_l3_offset,
+                                                  const union u_inet6_addr *daddr) {
+    int ret = rate_limit_check_v6(skb, current_l3_offset, daddr, RATE_LIMIT_DOWNLOAD);
+    if (ret != TC_ACT_OK) {
+        return ret;
+    }
+
+    struct flow_match_key match_key = {0};
+    match_key.l3_protocol = LANDSCAPE_IPV6_TYPE;
+    match_key.is_match_ip = FLOW_ENTRY_MODE_IP;
+    match_key.prefixlen = FLOW_IP_IPV6_MATCH_LEN;
+    COPY_ADDR_FROM(match_key.src_addr.all, daddr->bytes);
+    u32 flow_id = rate_limit_lookup_dst_flow(skb, current_l3_offset, &match_key);
+    return rate_limit_check_flow(skb, flow_id, RATE_LIMIT_DOWNLOAD);
+}
+
+#endif /* __LD_RATE_LIMIT_H__ */
@@ -2,10 +2,14 @@
 #define __LD_ROUTE_INDEX_H__
 #include <vmlinux.h>
 #include <bpf/bpf_endian.h>
+#include <bpf/bpf_helpers.h>
 
-#define WAN_CACHE 0
 #define LAN_CACHE 1
 
+// 入站连接回程记录的空闲超时
+#define REPLY_TCP_TIMEOUT_NS (1800ULL * 1000000000ULL)
+#define REPLY_OTHER_TIMEOUT_NS (300ULL * 1000000000ULL)
+
 struct route_context_v4 {
     __be32 saddr;
     __be32 daddr;
@@ -13,9 +17,12 @@ struct route_context_v4 {
     u8 l4_protocol;
     // tos value
     u8 tos;
-    // TODO
-    // u16 dst_port;
+    // TCP / UDP 源端口与目标端口, 其他协议或非首个分片为 0
+    __be16 src_port;
+    __be16 dst_port;
     u8 smac[6];
+    // 命中了端口相关的目标 IP 规则, 结果不能按 IP 对缓存
+    u8 skip_cache;
 };
 
 struct route_context_v6 {
@@ -25,11 +32,50 @@ struct route_context_v6 {
     u8 l4_protocol;
     // tos value
     u8 tos;
-    // TODO
-    // u16 dst_port;
+    // TCP / UDP 源端口与目标端口, 其他协议或非首个分片为 0
+    __be16 src_port;
+    __be16 dst_port;
     u8 smac[6];
+    // 命中了端口相关的目标 IP 规则, 结果不能按 IP 对缓存
+    u8 skip_cache;
 };
 
+static __always_inline u64 reply_timeout_ns(u8 l4_protocol) {
+    return l4_protocol == IPPROTO_TCP ? REPLY_TCP_TIMEOUT_NS : REPLY_OTHER_TIMEOUT_NS;
+}
+
+// 读取 L4 端口, TCP / UDP 的源端口与目标端口都位于首部前 4 字节
+static __always_inline void load_l4_ports(struct __sk_buff *skb, u32 l4_offset, u8 l4_protocol,
+                                          __be16 *src_port, __be16 *dst_port) {
+    if (l4_protocol != IPPROTO_TCP && l4_protocol != IPPROTO_UDP) {
+        return;
+    }
+    __be16 ports[2];
+    if (bpf_skb_load_bytes(skb, l4_offset, ports, sizeof(ports))) {
+        return;
+    }
+    *src_port = ports[0];
+    *dst_port = ports[1];
+}
+
+static __always_inline void load_ports_v4(struct __sk_buff *skb, u32 current_l3_offset,
+                                          const struct iphdr *iph, __be16 *src_port,
+                                          __be16 *dst_port) {
+    // 非首个分片不携带 L4 首部
+    if (iph->frag_off & bpf_htons(0x1FFF)) {
+        return;
+    }
+    load_l4_ports(skb, current_l3_offset + (iph->ihl * 4), iph->protocol, src_port, dst_port);
+}
+
+// 仅处理没有扩展头的 IPv6 包
+static __always_inline void load_ports_v6(struct __sk_buff *skb, u32 current_l3_offset,
+                                          const struct ipv6hdr *ip6h, __be16 *src_port,
+                                          __be16 *dst_port) {
+    load_l4_ports(skb, current_l3_offset + sizeof(struct ipv6hdr), ip6h->nexthdr, src_port,
+                  dst_port);
+}
+
 #define IP_MULTICAST_MASK_NBO bpf_ntohl(0xF0000000)
 #define IP_MULTICAST_BASE_NBO bpf_ntohl(0xE0000000)
 
@@ -3,6 +3,11 @@
 #include <vmlinux.h>
 #include <bpf/bpf_helpers.h>
 
+#ifndef FLOW_PORT_RULE_MAX
+// 每个 flow 的端口规则上限
+#define FLOW_PORT_RULE_MAX 32
+#endif
+
 struct lan_route_key_v4 {
     __u32 prefixlen;
     __be32 addr;
@@ -85,6 +90,35 @@ struct {
     __array(values, struct each_flow_ip_trie_v4);
 } flow4_ip_map SEC(".maps");
 
+// 带 L4 协议 / 目标端口条件的目标 IP 规则, 数量较少, 按顺序遍历
+struct flow_port_rule_v4 {
+    __be32 addr;
+    __be32 mask;
+    u32 mark;
+    u16 priority;
+    // 0 表示任意协议
+    u8 l4_protocol;
+    u8 _pad;
+    // 主机字节序, port_start 为 0 表示任意端口
+    u16 port_start;
+    u16 port_end;
+};
+
+struct flow_port_rules_v4 {
+    u32 count;
+    struct flow_port_rule_v4 rules[FLOW_PORT_RULE_MAX];
+};
+
+// flow <-> 端口规则
+struct {
+    __uint(type, BPF_MAP_TYPE_HASH);
+    __type(key, u32);
+    __type(value, struct flow_port_rules_v4);
+    __uint(max_entries, 256);
+    __uint(map_flags, BPF_F_NO_PREALLOC);
+    __uint(pinning, LIBBPF_PIN_BY_NAME);
+} flow4_port_map SEC(".maps");
+
 struct route_target_key_v4 {
     __u32 flow_id;
 };
@@ -95,6 +129,9 @@ struct route_target_info_v4 {
     u8 has_mac;
     u8 is_docker;
     u8 mac[6];
+    // 本机 TProxy 出口处理的协议 (TPROXY_PROTOCOL_*), 0 表示不是 TProxy 出口
+    u8 tproxy_protocols;
+    __be16 tproxy_port;
 };
 
 struct {
@@ -136,4 +173,31 @@ struct {
     __array(values, struct each_v4_cache);
 } rt4_cache_map SEC(".maps");
 
+// 入站连接的回程记录, local 为 LAN 侧地址 (NAT 之后), remote 为外部地址
+struct rt_reply_key_v4 {
+    __be32 local_addr;
+    __be32 remote_addr;
+    __be16 local_port;
+    __be16 remote_port;
+    u8 l4_protocol;
+    u8 _pad[3];
+} _rt_reply_key_v4;
+
+struct rt_reply_value_v4 {
+    // 连接进入的 WAN
+    u32 ifindex;
+    u8 has_mac;
+    u8 _pad[3];
+    u64 active_time;
+} _rt_reply_value_v4;
+
+// 按连接记录入站 WAN, 回复报文从同一 WAN 发出, 不受 LAN 主机 Flow 的影响
+struct {
+    __uint(type, BPF_MAP_TYPE_LRU_HASH);
+    __type(key, struct rt_reply_key_v4);
+    __type(value, struct rt_reply_value_v4);
+    __uint(max_entries, 65536);
+    __uint(pinning, LIBBPF_PIN_BY_NAME);
+} rt4_reply_map SEC(".maps");
+
 #endif /* __LD_ROUTE_MAP_v4_H__ */
\ No newline at end of file
@@ -4,6 +4,11 @@
 #include <bpf/bpf_helpers.h>
 #include "../landscape.h"
 
+#ifndef FLOW_PORT_RULE_MAX
+// 每个 flow 的端口规则上限
+#define FLOW_PORT_RULE_MAX 32
+#endif
+
 struct lan_route_key_v6 {
     __u32 prefixlen;
     union u_inet6_addr addr;
@@ -86,6 +91,35 @@ struct {
     __array(values, struct each_flow_ip_trie_v6);
 } flow6_ip_map SEC(".maps");
 
+// 带 L4 协议 / 目标端口条件的目标 IP 规则, 数量较少, 按顺序遍历
+struct flow_port_rule_v6 {
+    union u_inet6_addr addr;
+    union u_inet6_addr mask;
+    u32 mark;
+    u16 priority;
+    // 0 表示任意协议
+    u8 l4_protocol;
+    u8 _pad;
+    // 主机字节序, port_start 为 0 表示任意端口
+    u16 port_start;
+    u16 port_end;
+};
+
+struct flow_port_rules_v6 {
+    u32 count;
+    struct flow_port_rule_v6 rules[FLOW_PORT_RULE_MAX];
+};
+
+// flow <-> 端口规则
+struct {
+    __uint(type, BPF_MAP_TYPE_HASH);
+    __type(key, u32);
+    __type(value, struct flow_port_rules_v6);
+    __uint(max_entries, 256);
+    __uint(map_flags, BPF_F_NO_PREALLOC);
+    __uint(pinning, LIBBPF_PIN_BY_NAME);
+} flow6_port_map SEC(".maps");
+
 struct route_target_key_v6 {
     __u32 flow_id;
 };
@@ -96,6 +130,9 @@ struct route_target_info_v6 {
     u8 has_mac;
     u8 is_docker;
     u8 mac[6];
+    // 本机 TProxy 出口处理的协议 (TPROXY_PROTOCOL_*), 0 表示不是 TProxy 出口
+    u8 tproxy_protocols;
+    __be16 tproxy_port;
 };
 
 struct {
@@ -136,4 +173,31 @@ struct {
     __array(values, struct each_v6_cache);
 } rt6_cache_map SEC(".maps");
 
+// 入站连接的回程记录, local 为 LAN 侧地址 (NAT 之后), remote 为外部地址
+struct rt_reply_key_v6 {
+    union u_inet6_addr local_addr;
+    union u_inet6_addr remote_addr;
+    __be16 local_port;
+    __be16 remote_port;
+    u8 l4_protocol;
+    u8 _pad[3];
+} _rt_reply_key_v6;
+
+struct rt_reply_value_v6 {
+    // 连接进入的 WAN
+    u32 ifindex;
+    u8 has_mac;
+    u8 _pad[3];
+    u64 active_time;
+} _rt_reply_value_v6;
+
+// 按连接记录入站 WAN, 回复报文从同一 WAN 发出, 不受 LAN 主机 Flow 的影响
+struct {
+    __uint(type, BPF_MAP_TYPE_LRU_HASH);
+    __type(key, struct rt_reply_key_v6);
+    __type(value, struct rt_reply_value_v6);
+    __uint(max_entries, 65536);
+    __uint(pinning, LIBBPF_PIN_BY_NAME);
+} rt6_reply_map SEC(".maps");
+
 #endif /* __LD_ROUTE_MAP_V6_H__ */
\ No newline at end of file
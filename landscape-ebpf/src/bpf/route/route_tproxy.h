@@ -0,0 +1,130 @@
+#ifndef __LD_ROUTE_TPROXY_H__
+#define __LD_ROUTE_TPROXY_H__
+#include <vmlinux.h>
+#include <bpf/bpf_endian.h>
+#include <bpf/bpf_helpers.h>
+
+#include "landscape.h"
+#include "route/route_index.h"
+
+#ifndef BPF_F_CURRENT_NETNS
+#define BPF_F_CURRENT_NETNS (-1L)
+#endif
+
+#ifndef PACKET_HOST
+#define PACKET_HOST 0
+#endif
+
+// 本机 TProxy 出口处理的协议
+#define TPROXY_PROTOCOL_TCP 1
+#define TPROXY_PROTOCOL_UDP 2
+
+// bpf_sk_assign 只能在 ingress 上使用, egress 上 (本机发出的流量) 直接按当前路由转发
+static __always_inline bool tproxy_can_assign(struct __sk_buff *skb) {
+    return skb->ingress_ifindex == skb->ifindex;
+}
+
+static __always_inline bool tproxy_protocol_enabled(u8 protocols, u8 l4_protocol) {
+    if (l4_protocol == IPPROTO_TCP) {
+        return protocols & TPROXY_PROTOCOL_TCP;
+    }
+    if (l4_protocol == IPPROTO_UDP) {
+        return protocols & TPROXY_PROTOCOL_UDP;
+    }
+    return false;
+}
+
+// tuple 中为原始的目标地址, 已建立的 TCP 连接直接复用, 否则查找本机的监听 socket
+// 成功后打上 FLOW_TPROXY_MASK, 由策略路由将数据包投递到本机
+static __always_inline int tproxy_sk_assign(struct __sk_buff *skb, struct bpf_sock_tuple *tuple,
+                                            u32 tuple_len, u8 l4_protocol,
+                                            struct bpf_sock_tuple *listen) {
+#define BPF_LOG_TOPIC "tproxy_sk_assign"
+    struct bpf_sock *sk = NULL;
+    int ret;
+
+    if (l4_protocol == IPPROTO_TCP) {
+        sk = bpf_skc_lookup_tcp(skb, tuple, tuple_len, BPF_F_CURRENT_NETNS, 0);
+        if (sk) {
+            if (sk->state != BPF_TCP_LISTEN) {
+                goto assign;
+            }
+            bpf_sk_release(sk);
+            sk = NULL;
+        }
+        sk = bpf_skc_lookup_tcp(skb, listen, tuple_len, BPF_F_CURRENT_NETNS, 0);
+    } else {
+        sk = bpf_sk_lookup_udp(skb, listen, tuple_len, BPF_F_CURRENT_NETNS, 0);
+    }
+
+    if (!sk) {
+        bpf_log_info("tproxy listener not found, l4_protocol: %u", l4_protocol);
+        return TC_ACT_SHOT;
+    }
+
+    if (l4_protocol == IPPROTO_TCP && sk->state != BPF_TCP_LISTEN) {
+        bpf_sk_release(sk);
+        bpf_log_info("tproxy sk not ready");
+        return TC_ACT_SHOT;
+    }
+
+assign:
+    ret = bpf_sk_assign(skb, sk, 0);
+    bpf_sk_release(sk);
+    if (ret) {
+        bpf_log_info("bpf_sk_assign ret %d", ret);
+        return TC_ACT_SHOT;
+    }
+    skb->mark = skb->mark | FLOW_TPROXY_MASK;
+    bpf_skb_change_type(skb, PACKET_HOST);
+    return TC_ACT_OK;
+#undef BPF_LOG_TOPIC
+}
+
+static __always_inline int tproxy_assign_v4(struct __sk_buff *skb,
+                                            const struct route_context_v4 *context,
+                                            u8 protocols, __be16 port) {
+    if (!tproxy_can_assign(skb) || !tproxy_protocol_enabled(protocols, context->l4_protocol)) {
+        // 未启用的协议按默认路由转发
+        return TC_ACT_UNSPEC;
+    }
+
+    struct bpf_sock_tuple tuple = {0};
+    struct bpf_sock_tuple listen = {0};
+    tuple.ipv4.saddr = context->saddr;
+    tuple.ipv4.daddr = context->daddr;
+    tuple.ipv4.sport = context->src_port;
+    tuple.ipv4.dport = context->dst_port;
+
+    listen.ipv4.saddr = context->saddr;
+    listen.ipv4.sport = context->src_port;
+    listen.ipv4.daddr = bpf_htonl(0x7F000001);
+    listen.ipv4.dport = port;
+
+    return tproxy_sk_assign(skb, &tuple, sizeof(tuple.ipv4), context->l4_protocol, &listen);
+}
+
+static __always_inline int tproxy_assign_v6(struct __sk_buff *skb,
+                                            const struct route_context_v6 *context,
+                                            u8 protocols, __be16 port) {
+    if (!tproxy_can_assign(skb) || !tproxy_protocol_enabled(protocols, context->l4_protocol)) {
+        return TC_ACT_UNSPEC;
+    }
+
+    struct bpf_sock_tuple tuple = {0};
+    struct bpf_sock_tuple listen = {0};
+    COPY_ADDR_FROM(tuple.ipv6.saddr, context->saddr.all);
+    COPY_ADDR_FROM(tuple.ipv6.daddr, context->daddr.all);
+    tuple.ipv6.sport = context->src_port;
+    tuple.ipv6.dport = context->dst_port;
+
+    COPY_ADDR_FROM(listen.ipv6.saddr, context->saddr.all);
+    listen.ipv6.sport = context->src_port;
+    // ::1
+    listen.ipv6.daddr[3] = bpf_htonl(1);
+    listen.ipv6.dport = port;
+
+    return tproxy_sk_assign(skb, &tuple, sizeof(tuple.ipv6), context->l4_protocol, &listen);
+}
+
+#endif /* __LD_ROUTE_TPROXY_H__ */
@@ -43,11 +43,21 @@ int rt4_lan_ingress(struct __sk_buff *skb) {
     context.l4_protocol = iph->protocol;
     context.daddr = iph->daddr;
     context.saddr = iph->saddr;
+    load_ports_v4(skb, current_l3_offset, iph, &context.src_port, &context.dst_port);
 
     if (should_not_forward(context.daddr)) {
         return TC_ACT_UNSPEC;
     }
 
+    ret = quota_check_src_mac(skb, current_l3_offset);
+    if (ret != TC_ACT_OK) {
+        return ret;
+    }
+
+    ret = rate_limit_check_v4(skb, current_l3_offset, context.saddr, RATE_LIMIT_UPLOAD);
+    if (ret != TC_ACT_OK) {
+        return ret;
+    }
 
     ret = search_route_in_lan_v4(skb, current_l3_offset, &context, &flow_mark);
     if (ret != TC_ACT_OK) {
@@ -70,7 +80,7 @@ int rt4_lan_ingress(struct __sk_buff *skb) {
 
     ret = pick_wan_and_send_by_flow_id_v4(skb, current_l3_offset, &context, flow_mark);
 
-    if (ret == TC_ACT_REDIRECT) {
+    if (ret == TC_ACT_REDIRECT && !context.skip_cache) {
         setting_cache_in_lan_v4(&context, flow_mark);
     }
     return ret;
@@ -94,11 +104,23 @@ int rt6_lan_ingress(struct __sk_buff *skb) {
 
     COPY_ADDR_FROM(context.saddr.all, ip6h->saddr.in6_u.u6_addr32);
     COPY_ADDR_FROM(context.daddr.all, ip6h->daddr.in6_u.u6_addr32);
+    context.l4_protocol = ip6h->nexthdr;
+    load_ports_v6(skb, current_l3_offset, ip6h, &context.src_port, &context.dst_port);
 
     if (is_broadcast_ip6(context.daddr.bytes)) {
         return TC_ACT_UNSPEC;
     }
 
+    ret = quota_check_src_mac(skb, current_l3_offset);
+    if (ret != TC_ACT_OK) {
+        return ret;
+    }
+
+    ret = rate_limit_check_v6(skb, current_l3_offset, &context.saddr, RATE_LIMIT_UPLOAD);
+    if (ret != TC_ACT_OK) {
+        return ret;
+    }
+
     ret = search_route_in_lan_v6(skb, current_l3_offset, &context, &flow_mark);
     if (ret != TC_ACT_OK) {
         skb->mark = replace_flow_source(flow_mark, FLOW_FROM_LAN);
@@ -179,6 +201,40 @@ SEC("tc/egress")
 int route_lan_egress(struct __sk_buff *skb) {
 #define BPF_LOG_TOPIC "<<< route_lan_egress <<<"
 
+    bool is_ipv4;
+    int ret;
+
+    ret = current_pkg_type(skb, current_l3_offset, &is_ipv4);
+    if (unlikely(ret != TC_ACT_OK)) {
+        return TC_ACT_UNSPEC;
+    }
+
+    // 设备超出配额后的下载方向丢弃 / 限速
+    ret = quota_check_dst_mac(skb, current_l3_offset);
+    if (ret != TC_ACT_OK) {
+        return ret;
+    }
+
+    // 下载方向限速, 由 LAN 口上的 fq qdisc 按 skb->tstamp 发送
+    if (is_ipv4) {
+        struct iphdr *iph;
+        if (VALIDATE_READ_DATA(skb, &iph, current_l3_offset, sizeof(struct iphdr))) {
+            return TC_ACT_UNSPEC;
+        }
+        ret = rate_limit_download_v4(skb, current_l3_offset, iph->daddr);
+    } else {
+        struct ipv6hdr *ip6h;
+        if (VALIDATE_READ_DATA(skb, &ip6h, current_l3_offset, sizeof(struct ipv6hdr))) {
+            return TC_ACT_UNSPEC;
+        }
+        union u_inet6_addr daddr;
+        COPY_ADDR_FROM(daddr.all, ip6h->daddr.in6_u.u6_addr32);
+        ret = rate_limit_download_v6(skb, current_l3_offset, &daddr);
+    }
+
+    if (ret != TC_ACT_OK) {
+        return ret;
+    }
     return TC_ACT_UNSPEC;
 #undef BPF_LOG_TOPIC
 }
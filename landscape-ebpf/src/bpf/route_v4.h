@@ -9,9 +9,12 @@
 
 #include "route/route_index.h"
 #include "route/route_maps_v4.h"
+#include "route/route_tproxy.h"
 
 #include "flow_match.h"
 #include "neigh_ip.h"
+#include "quota.h"
+#include "rate_limit.h"
 
 static __always_inline int lan_redirect_check_v4(struct __sk_buff *skb, u32 current_l3_offset,
                                                  struct route_context_v4 *context, bool is_lan) {
@@ -111,6 +114,39 @@ static __always_inline int lan_redirect_check_v4(struct __sk_buff *skb, u32 curr
 #undef BPF_LOG_TOPIC
 }
 
+// 遍历 flow 的端口规则, 返回优先级最高的匹配项
+static __always_inline struct flow_port_rule_v4 *
+match_flow_port_rule_v4(u32 flow_id, struct route_context_v4 *context) {
+    struct flow_port_rules_v4 *rules = bpf_map_lookup_elem(&flow4_port_map, &flow_id);
+    if (rules == NULL) {
+        return NULL;
+    }
+
+    struct flow_port_rule_v4 *result = NULL;
+    u16 dst_port = bpf_ntohs(context->dst_port);
+    for (int i = 0; i < FLOW_PORT_RULE_MAX; i++) {
+        if (i >= rules->count) {
+            break;
+        }
+        struct flow_port_rule_v4 *rule = &rules->rules[i];
+        if ((context->daddr & rule->mask) != rule->addr) {
+            continue;
+        }
+        // 目标 IP 存在端口规则时, 同一 IP 对的不同端口可能走向不同出口
+        context->skip_cache = 1;
+        if (rule->l4_protocol != 0 && rule->l4_protocol != context->l4_protocol) {
+            continue;
+        }
+        if (rule->port_start != 0 && (dst_port < rule->port_start || dst_port > rule->port_end)) {
+            continue;
+        }
+        if (result == NULL || rule->priority < result->priority) {
+            result = rule;
+        }
+    }
+    return result;
+}
+
 static __always_inline int flow_verdict_v4(struct __sk_buff *skb, u32 current_l3_offset,
                                            struct route_context_v4 *context, u32 *init_flow_id_) {
 #define BPF_LOG_TOPIC "flow_verdict_v4"
@@ -123,6 +159,10 @@ static __always_inline int flow_verdict_v4(struct __sk_buff *skb, u32 current_l3
         return TC_ACT_SHOT;
     }
 
+    // 入口规则的 QoS 与 flow_id 一同保存在匹配结果中
+    u32 entry_qos = flow_id & FLOW_QOS_MASK;
+    flow_id = flow_id & FLOW_ID_MASK;
+
     volatile u32 flow_mark_action = *init_flow_id_;
     volatile u16 priority = 0xFFFF;
 
@@ -150,6 +190,12 @@ static __always_inline int flow_verdict_v4(struct __sk_buff *skb, u32 current_l3
         // bpf_log_info("flow_id: %d, ip map is empty", flow_id);
     }
 
+    struct flow_port_rule_v4 *port_rule = match_flow_port_rule_v4(flow_id, context);
+    if (port_rule != NULL && port_rule->priority <= priority) {
+        flow_mark_action = port_rule->mark;
+        priority = port_rule->priority;
+    }
+
     struct flow_dns_match_key_v4 key = {0};
     struct flow_dns_match_value_v4 *dns_rule_value = NULL;
     key.addr = context->daddr;
@@ -202,7 +248,46 @@ keep_going:
     //     bpf_log_info("get_flow_id value is : %u", get_flow_id(flow_mark_action));
     //     bpf_log_info("dst ip: %pI4", context->daddr.in6_u.u6_addr32);
     // }
-    *init_flow_id_ = flow_mark_action;
+    *init_flow_id_ = merge_flow_qos(flow_mark_action, entry_qos);
+    return TC_ACT_OK;
+#undef BPF_LOG_TOPIC
+}
+
+// 按 mark 中的 QoS 设置 skb->priority 并改写 DSCP
+static __always_inline int apply_flow_qos_v4(struct __sk_buff *skb, u32 current_l3_offset,
+                                             u32 flow_mark) {
+#define BPF_LOG_TOPIC "apply_flow_qos_v4"
+    int priority = flow_qos_class_to_priority(get_flow_qos_class(flow_mark));
+    if (priority >= 0) {
+        skb->priority = priority;
+    }
+
+    if (!get_flow_qos_has_dscp(flow_mark)) {
+        return TC_ACT_OK;
+    }
+
+    __be16 old_word;
+    if (bpf_skb_load_bytes(skb, current_l3_offset, &old_word, sizeof(old_word))) {
+        return TC_ACT_OK;
+    }
+
+    // 首部的第一个 16 位字为 版本/首部长度 + TOS, 保留 TOS 低 2 位的 ECN
+    u16 word = bpf_ntohs(old_word);
+    word = (word & 0xFF03) | ((u16)get_flow_qos_dscp(flow_mark) << 2);
+    __be16 new_word = bpf_htons(word);
+    if (new_word == old_word) {
+        return TC_ACT_OK;
+    }
+
+    if (bpf_skb_store_bytes(skb, current_l3_offset, &new_word, sizeof(new_word), 0)) {
+        bpf_log_info("store dscp error");
+        return TC_ACT_OK;
+    }
+    if (bpf_l3_csum_replace(skb, current_l3_offset + offsetof(struct iphdr, check), old_word,
+                            new_word, sizeof(new_word))) {
+        bpf_log_info("l3_csum_replace error");
+        return TC_ACT_SHOT;
+    }
     return TC_ACT_OK;
 #undef BPF_LOG_TOPIC
 }
@@ -217,6 +302,19 @@ static __always_inline int pick_wan_and_send_by_flow_id_v4(struct __sk_buff *skb
     struct route_target_key_v4 wan_key = {0};
     wan_key.flow_id = get_flow_id(flow_id);
 
+    // Flow 超出配额后的丢弃 / 限速
+    if (quota_check_flow(skb, wan_key.flow_id) != TC_ACT_OK) {
+        return TC_ACT_SHOT;
+    }
+
+    if (rate_limit_check_flow(skb, wan_key.flow_id, RATE_LIMIT_UPLOAD) != TC_ACT_OK) {
+        return TC_ACT_SHOT;
+    }
+
+    if (apply_flow_qos_v4(skb, current_l3_offset, flow_id) != TC_ACT_OK) {
+        return TC_ACT_SHOT;
+    }
+
     struct route_target_info_v4 *target_info = bpf_map_lookup_elem(&rt4_target_map, &wan_key);
 
     // 找不到转发的 target 按照原有计划进行处理
@@ -231,6 +329,11 @@ static __always_inline int pick_wan_and_send_by_flow_id_v4(struct __sk_buff *skb
         }
     }
 
+    if (target_info->tproxy_protocols) {
+        return tproxy_assign_v4(skb, context, target_info->tproxy_protocols,
+                                target_info->tproxy_port);
+    }
+
     if (target_info->ifindex == skb->ifindex) {
         // Belongs to the current ifindex No redirection required
         return TC_ACT_UNSPEC;
@@ -306,65 +409,109 @@ static __always_inline int is_current_wan_packet_v4(struct __sk_buff *skb, u32 c
 #undef BPF_LOG_TOPIC
 }
 
+// TCP / UDP 的非首个分片没有端口, 回复路由只能按地址对匹配
+static __always_inline bool is_port_less_fragment_v4(const struct route_context_v4 *context) {
+    return (context->l4_protocol == IPPROTO_TCP || context->l4_protocol == IPPROTO_UDP) &&
+           context->src_port == 0 && context->dst_port == 0;
+}
+
+static __always_inline void set_reply_key_addr_only_v4(struct rt_reply_key_v4 *reply_key) {
+    reply_key->local_port = 0;
+    reply_key->remote_port = 0;
+    reply_key->l4_protocol = 0;
+}
+
+static __always_inline void update_reply_target_v4(const struct rt_reply_key_v4 *reply_key,
+                                                   u32 ifindex, u8 has_mac, u64 now) {
+    struct rt_reply_value_v4 *target = bpf_map_lookup_elem(&rt4_reply_map, reply_key);
+    if (target && target->ifindex == ifindex) {
+        target->active_time = now;
+        return;
+    }
+
+    struct rt_reply_value_v4 new_target = {0};
+    new_target.ifindex = ifindex;
+    new_target.has_mac = has_mac;
+    new_target.active_time = now;
+    bpf_map_update_elem(&rt4_reply_map, reply_key, &new_target, BPF_ANY);
+}
+
+// 入站连接的回复报文, 从连接进入的 WAN 发出
+static __always_inline int search_reply_route_v4(struct __sk_buff *skb,
+                                                 const struct route_context_v4 *context) {
+#define BPF_LOG_TOPIC "search_reply_route_v4"
+    struct rt_reply_key_v4 reply_key = {0};
+    struct mac_value_v4 *mac_value = NULL;
+    reply_key.local_addr = context->saddr;
+    reply_key.remote_addr = context->daddr;
+    reply_key.local_port = context->src_port;
+    reply_key.remote_port = context->dst_port;
+    reply_key.l4_protocol = context->l4_protocol;
+
+    struct rt_reply_value_v4 *target = bpf_map_lookup_elem(&rt4_reply_map, &reply_key);
+    if (target == NULL && is_port_less_fragment_v4(context)) {
+        set_reply_key_addr_only_v4(&reply_key);
+        target = bpf_map_lookup_elem(&rt4_reply_map, &reply_key);
+    }
+    if (target == NULL) {
+        return TC_ACT_OK;
+    }
+
+    u64 now = bpf_ktime_get_ns();
+    if (now - target->active_time > reply_timeout_ns(reply_key.l4_protocol)) {
+        bpf_map_delete_elem(&rt4_reply_map, &reply_key);
+        return TC_ACT_OK;
+    }
+    target->active_time = now;
+
+    u32 ifindex = target->ifindex;
+    struct wan_ip_info_key wan_search_key = {0};
+    wan_search_key.ifindex = ifindex;
+    wan_search_key.l3_protocol = LANDSCAPE_IPV4_TYPE;
+
+    struct wan_ip_info_value *wan_ip_info = bpf_map_lookup_elem(&wan_ip_binding, &wan_search_key);
+    if (wan_ip_info == NULL) {
+        // 入口 WAN 已不存在, 按 LAN 主机的 Flow 发送
+        return TC_ACT_OK;
+    }
+
+    if (!target->has_mac) {
+        return bpf_redirect(ifindex, 0);
+    }
+
+    mac_value = bpf_map_lookup_elem(&ip_mac_v4, &reply_key.remote_addr);
+    if (mac_value == NULL) {
+        mac_value = bpf_map_lookup_elem(&ip_mac_v4, &wan_ip_info->gateway.ip);
+    }
+    if (mac_value) {
+        if (!bpf_skb_store_bytes(skb, 0, &mac_value->mac, 14, 0)) {
+            return bpf_redirect(ifindex, 0);
+        }
+    }
+
+    struct bpf_redir_neigh param;
+    param.nh_family = AF_INET;
+
+    COPY_ADDR_FROM(param.ipv6_nh, wan_ip_info->gateway.bits);
+    return bpf_redirect_neigh(ifindex, &param, sizeof(param), 0);
+#undef BPF_LOG_TOPIC
+}
+
 static __always_inline int search_route_in_lan_v4(struct __sk_buff *skb,
                                                   const u32 current_l3_offset,
                                                   const struct route_context_v4 *context,
                                                   u32 *flow_mark) {
 #define BPF_LOG_TOPIC "search_route_in_lan_v4"
-    int ret = 0;
-    u32 key = WAN_CACHE;
+    int ret = search_reply_route_v4(skb, context);
+    if (ret != TC_ACT_OK) {
+        return ret;
+    }
+
+    u32 key = LAN_CACHE;
     struct rt_cache_key_v4 search_key = {0};
-    struct mac_value_v4 *mac_value = NULL;
     search_key.local_addr = context->saddr;
     search_key.remote_addr = context->daddr;
 
-    // Fist WAN
-    void *wan_cache = bpf_map_lookup_elem(&rt4_cache_map, &key);
-    if (wan_cache) {
-        struct rt_cache_value_v4 *target = bpf_map_lookup_elem(wan_cache, &search_key);
-        if (target) {
-            struct wan_ip_info_key wan_search_key = {0};
-            wan_search_key.ifindex = target->ifindex;
-            wan_search_key.l3_protocol = LANDSCAPE_IPV4_TYPE;
-
-            struct wan_ip_info_value *wan_ip_info =
-                bpf_map_lookup_elem(&wan_ip_binding, &wan_search_key);
-            if (wan_ip_info != NULL) {
-                bool target_has_mac = target->has_mac;
-
-                if (!target_has_mac) {
-                    return bpf_redirect(target->ifindex, 0);
-                } else {
-                    mac_value = bpf_map_lookup_elem(&ip_mac_v4, &search_key.remote_addr);
-                    if (mac_value) {
-                        bpf_log_info("find ip: %pI4 mac, send to ifindex: %d",
-                                     &search_key.remote_addr, target->ifindex);
-                        if (!bpf_skb_store_bytes(skb, 0, &mac_value->mac, 14, 0)) {
-                            return bpf_redirect(target->ifindex, 0);
-                        }
-                    } else {
-                        mac_value = bpf_map_lookup_elem(&ip_mac_v4, &wan_ip_info->gateway.ip);
-                        if (mac_value) {
-                            bpf_log_info("find ip: %pI4 mac, send to ifindex: %d",
-                                         &wan_ip_info->gateway.ip, target->ifindex);
-                            if (!bpf_skb_store_bytes(skb, 0, &mac_value->mac, 14, 0)) {
-                                return bpf_redirect(target->ifindex, 0);
-                            }
-                        }
-                    }
-                }
-
-                struct bpf_redir_neigh param;
-                param.nh_family = AF_INET;
-
-                COPY_ADDR_FROM(param.ipv6_nh, wan_ip_info->gateway.bits);
-                ret = bpf_redirect_neigh(target->ifindex, &param, sizeof(param), 0);
-                return ret;
-            }
-        }
-    }
-
-    key = LAN_CACHE;
     void *lan_cache = bpf_map_lookup_elem(&rt4_cache_map, &key);
     if (lan_cache) {
         struct rt_cache_value_v4 *target = bpf_map_lookup_elem(lan_cache, &search_key);
@@ -379,63 +526,30 @@ static __always_inline int search_route_in_lan_v4(struct __sk_buff *skb,
 #undef BPF_LOG_TOPIC
 }
 
-static __always_inline int setting_cache_in_wan_v4(const struct route_context_v4 *context,
+// 记录入站连接的入口 WAN, 每个入站报文刷新活跃时间
+static __always_inline int setting_reply_in_wan_v4(const struct route_context_v4 *context,
                                                    u32 current_l3_offset, u32 ifindex) {
-#define BPF_LOG_TOPIC "setting_cache_in_wan_v4"
-    struct rt_cache_key_v4 search_key = {0};
-    struct rt_cache_value_v4 *target = NULL;
-
-    u32 key = LAN_CACHE;
-    search_key.local_addr = context->daddr;
-    search_key.remote_addr = context->saddr;
-
-    void *lan_cache = bpf_map_lookup_elem(&rt4_cache_map, &key);
-    if (lan_cache != NULL) {
-        target = bpf_map_lookup_elem(lan_cache, &search_key);
-        if (target) {
-            // if (context->l3_protocol == LANDSCAPE_IPV4_TYPE) {
-            //     bpf_log_info("Already cached %pI4 -> %pI4", search_key.local_addr.in6_u.u6_addr8,
-            //                 search_key.remote_addr.in6_u.u6_addr8);
-            // } else {
-            //     bpf_log_info("Already cached %pI6 -> %pI6", search_key.local_addr.in6_u.u6_addr8,
-            //                 search_key.remote_addr.in6_u.u6_addr8);
-            // }
-            return TC_ACT_OK;
-        }
+#define BPF_LOG_TOPIC "setting_reply_in_wan_v4"
+    struct rt_reply_key_v4 reply_key = {0};
+    reply_key.local_addr = context->daddr;
+    reply_key.remote_addr = context->saddr;
+    reply_key.local_port = context->dst_port;
+    reply_key.remote_port = context->src_port;
+    reply_key.l4_protocol = context->l4_protocol;
+
+    u64 now = bpf_ktime_get_ns();
+    u8 has_mac = current_l3_offset > 0;
+    if (is_port_less_fragment_v4(context)) {
+        set_reply_key_addr_only_v4(&reply_key);
+        update_reply_target_v4(&reply_key, ifindex, has_mac, now);
+        return TC_ACT_OK;
     }
 
-    key = WAN_CACHE;
-    void *wan_cache = bpf_map_lookup_elem(&rt4_cache_map, &key);
-    if (wan_cache) {
-        target = bpf_map_lookup_elem(wan_cache, &search_key);
-        if (target) {
-            if (target->ifindex != ifindex) {
-                bpf_map_delete_elem(wan_cache, &search_key);
-            }
-        } else {
-            struct rt_cache_value_v4 new_target_cache = {0};
-            new_target_cache.has_mac = current_l3_offset > 0;
-            new_target_cache.ifindex = ifindex;
-            // if (new_target_cache.has_mac) {
-            //     struct wan_ip_info_value *wan_ip = bpf_map_lookup_elem(wan_cache, &search_key);
-            //     if (wan_ip) {
-
-            //         __builtin_memcpy(match_key.mac.mac, mac, 6);
-            //     }
-            // }
-            bpf_map_update_elem(wan_cache, &search_key, &new_target_cache, BPF_ANY);
-            // bpf_log_info("wan_cache new new_target_cache.ifindex: %d", new_target_cache.ifindex);
-        }
-
-        // if (context->l3_protocol == LANDSCAPE_IPV4_TYPE) {
-        //     bpf_log_info("cache %pI4 -> %pI4", search_key.local_addr.in6_u.u6_addr8,
-        //                  search_key.remote_addr.in6_u.u6_addr8);
-        // } else {
-        //     bpf_log_info("cache %pI6 -> %pI6", search_key.local_addr.in6_u.u6_addr8,
-        //                  search_key.remote_addr.in6_u.u6_addr8);
-        // }
-    } else {
-        bpf_log_info("could not find wan_cache: %d", key);
+    update_reply_target_v4(&reply_key, ifindex, has_mac, now);
+    if (context->src_port != 0 || context->dst_port != 0) {
+        // 同时记录地址对, 供后续不带端口的分片使用
+        set_reply_key_addr_only_v4(&reply_key);
+        update_reply_target_v4(&reply_key, ifindex, has_mac, now);
     }
 
     return TC_ACT_OK;
@@ -447,20 +561,11 @@ static __always_inline int setting_cache_in_lan_v4(const struct route_context_v4
 #define BPF_LOG_TOPIC "setting_cache_in_lan_v4"
     struct rt_cache_key_v4 search_key = {0};
     struct rt_cache_value_v4 *target = NULL;
-    u32 key = WAN_CACHE;
+    u32 key = LAN_CACHE;
 
     search_key.local_addr = context->saddr;
     search_key.remote_addr = context->daddr;
 
-    void *wan_cache = bpf_map_lookup_elem(&rt4_cache_map, &key);
-    if (wan_cache) {
-        target = bpf_map_lookup_elem(wan_cache, &search_key);
-        if (target) {
-            return TC_ACT_OK;
-        }
-    }
-
-    key = LAN_CACHE;
     void *lan_cache = bpf_map_lookup_elem(&rt4_cache_map, &key);
     if (lan_cache) {
         target = bpf_map_lookup_elem(lan_cache, &search_key);
@@ -8,6 +8,7 @@
 #include "landscape.h"
 #include "route_v4.h"
 #include "route_v6.h"
+#include "sqm.h"
 
 char LICENSE[] SEC("license") = "Dual BSD/GPL";
 
@@ -42,6 +43,7 @@ int rt4_wan_ingress(struct __sk_buff *skb) {
     context.l4_protocol = iph->protocol;
     context.daddr = iph->daddr;
     context.saddr = iph->saddr;
+    load_ports_v4(skb, current_l3_offset, iph, &context.src_port, &context.dst_port);
 
     if (should_not_forward(context.daddr)) {
         return TC_ACT_UNSPEC;
@@ -52,12 +54,19 @@ int rt4_wan_ingress(struct __sk_buff *skb) {
         return ret;
     }
 
+    if (sqm_ingress_enabled(skb->ifindex)) {
+        if (get_cache_mask(skb->mark) == INGRESS_STATIC_MARK) {
+            setting_reply_in_wan_v4(&context, current_l3_offset, skb->ifindex);
+        }
+        return TC_ACT_UNSPEC;
+    }
+
     ret = lan_redirect_check_v4(skb, current_l3_offset, &context, false);
     if (ret == TC_ACT_REDIRECT) {
         u8 mark = get_cache_mask(skb->mark);
         if (mark == INGRESS_STATIC_MARK) {
             // bpf_log_info("get wan ingress mark: %u", mark);
-            setting_cache_in_wan_v4(&context, current_l3_offset, skb->ifindex);
+            setting_reply_in_wan_v4(&context, current_l3_offset, skb->ifindex);
         }
     }
 
@@ -89,6 +98,7 @@ int rt4_wan_egress(struct __sk_buff *skb) {
     context.l4_protocol = iph->protocol;
     context.daddr = iph->daddr;
     context.saddr = iph->saddr;
+    load_ports_v4(skb, current_l3_offset, iph, &context.src_port, &context.dst_port);
 
     if (should_not_forward(context.daddr)) {
         return TC_ACT_UNSPEC;
@@ -128,6 +138,8 @@ int rt6_wan_ingress(struct __sk_buff *skb) {
 
     COPY_ADDR_FROM(context.saddr.all, ip6h->saddr.in6_u.u6_addr32);
     COPY_ADDR_FROM(context.daddr.all, ip6h->daddr.in6_u.u6_addr32);
+    context.l4_protocol = ip6h->nexthdr;
+    load_ports_v6(skb, current_l3_offset, ip6h, &context.src_port, &context.dst_port);
 
     if (is_broadcast_ip6(context.daddr.bytes)) {
         bpf_log_info("is_broadcast_ip6: %pI6", context.daddr.bytes);
@@ -140,12 +152,19 @@ int rt6_wan_ingress(struct __sk_buff *skb) {
         return ret;
     }
 
+    if (sqm_ingress_enabled(skb->ifindex)) {
+        if (get_cache_mask(skb->mark) == INGRESS_STATIC_MARK) {
+            setting_reply_in_wan_v6(&context, current_l3_offset, skb->ifindex);
+        }
+        return TC_ACT_UNSPEC;
+    }
+
     ret = lan_redirect_check_v6(skb, current_l3_offset, &context);
     if (ret == TC_ACT_REDIRECT) {
         u8 mark = get_cache_mask(skb->mark);
         if (mark == INGRESS_STATIC_MARK) {
             // bpf_log_info("get wan ingress mark: %u", mark);
-            setting_cache_in_wan_v6(&context, current_l3_offset, skb->ifindex);
+            setting_reply_in_wan_v6(&context, current_l3_offset, skb->ifindex);
         }
     }
 
@@ -171,6 +190,8 @@ int rt6_wan_egress(struct __sk_buff *skb) {
 
     COPY_ADDR_FROM(context.saddr.all, ip6h->saddr.in6_u.u6_addr32);
     COPY_ADDR_FROM(context.daddr.all, ip6h->daddr.in6_u.u6_addr32);
+    context.l4_protocol = ip6h->nexthdr;
+    load_ports_v6(skb, current_l3_offset, ip6h, &context.src_port, &context.dst_port);
 
     if (is_broadcast_ip6(context.daddr.bytes)) {
         return TC_ACT_UNSPEC;
@@ -230,6 +251,12 @@ int route_wan_ingress(struct __sk_buff *skb) {
     bool is_ipv4;
     int ret;
 
+    // WAN 超出配额后的丢弃 / 限速
+    ret = quota_check_ifindex(skb);
+    if (unlikely(ret != TC_ACT_OK)) {
+        return ret;
+    }
+
     if (likely(current_l3_offset > 0)) {
         ret = is_broadcast_mac(skb);
         if (unlikely(ret != TC_ACT_OK)) {
@@ -258,14 +285,19 @@ SEC("tc/egress")
 int route_wan_egress(struct __sk_buff *skb) {
 #define BPF_LOG_TOPIC "<<< route_wan_egress <<<"
 
+    bool is_ipv4;
+    int ret;
+
+    ret = quota_check_ifindex(skb);
+    if (unlikely(ret != TC_ACT_OK)) {
+        return ret;
+    }
+
     if (likely(skb->ingress_ifindex != 0)) {
         // 端口转发数据, 相对于是已经决定使用这个出口, 所以直接发送
         return TC_ACT_UNSPEC;
     }
 
-    bool is_ipv4;
-    int ret;
-
     if (likely(current_l3_offset > 0)) {
         ret = is_broadcast_mac(skb);
         if (unlikely(ret != TC_ACT_OK)) {
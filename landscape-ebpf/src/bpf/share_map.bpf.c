@@ -12,6 +12,9 @@
 #include "route/route_maps_v6.h"
 
 #include "neigh_ip.h"
+#include "quota.h"
+#include "rate_limit.h"
+#include "sqm.h"
 
 char LICENSE[] SEC("license") = "Dual BSD/GPL";
 
@@ -0,0 +1,23 @@
+#ifndef __LD_SQM_H__
+#define __LD_SQM_H__
+#include <vmlinux.h>
+#include <bpf/bpf_helpers.h>
+#include "landscape.h"
+
+// 开启下行 SQM 的 WAN ifindex, 由用户态写入
+struct {
+    __uint(type, BPF_MAP_TYPE_HASH);
+    __type(key, u32);
+    __type(value, u8);
+    __uint(max_entries, 64);
+    __uint(map_flags, BPF_F_NO_PREALLOC);
+    __uint(pinning, LIBBPF_PIN_BY_NAME);
+} sqm_ingress_map SEC(".maps");
+
+// 下行整形的 IFB 重新注入报文时会跳过 tc ingress, 需由后续的 mirred 过滤器送入 IFB,
+// 整形后交给内核转发
+static __always_inline bool sqm_ingress_enabled(u32 ifindex) {
+    return bpf_map_lookup_elem(&sqm_ingress_map, &ifindex) != NULL;
+}
+
+#endif /* __LD_SQM_H__ */
@@ -0,0 +1,32 @@
+pub(crate) mod bridge_port_tag {
+    include!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/bpf_rs/bridge_port_tag.skel.rs"));
+}
+
+use std::mem::MaybeUninit;
+
+use bridge_port_tag::*;
+use libbpf_rs::{
+    skel::{OpenSkel, SkelBuilder},
+    TC_INGRESS,
+};
+use tokio::sync::oneshot;
+
+use crate::{landscape::TcHookProxy, BRIDGE_PORT_TAG_INGRESS_PRIORITY};
+
+/// 在网桥端口上标记入口端口, 供 Flow 的网桥端口入口规则使用
+pub fn run_bridge_port_tag(ifindex: i32, service_status: oneshot::Receiver<()>) {
+    let landscape_builder = BridgePortTagSkelBuilder::default();
+
+    let mut open_object = MaybeUninit::uninit();
+    let landscape_open = landscape_builder.open(&mut open_object).unwrap();
+    let landscape_skel = landscape_open.load().unwrap();
+
+    let bridge_port_tag = landscape_skel.progs.bridge_port_tag;
+    let mut bridge_port_tag_hook =
+        TcHookProxy::new(&bridge_port_tag, ifindex, TC_INGRESS, BRIDGE_PORT_TAG_INGRESS_PRIORITY);
+    bridge_port_tag_hook.attach();
+
+    let _ = service_status.blocking_recv();
+
+    drop(bridge_port_tag_hook);
+}
@@ -5,6 +5,7 @@ use once_cell::sync::Lazy;
 
 pub mod base;
 pub mod bpf_error;
+pub mod bridge_port;
 pub mod firewall;
 pub mod landscape;
 pub mod map_setting;
@@ -64,17 +65,26 @@ static MAP_PATHS: Lazy<LandscapeMapPath> = Lazy::new(|| {
         rt4_target_map: PathBuf::from(format!("{}/rt4_target_map", ebpf_map_path)),
         flow4_dns_map: PathBuf::from(format!("{}/flow4_dns_map", ebpf_map_path)),
         flow4_ip_map: PathBuf::from(format!("{}/flow4_ip_map", ebpf_map_path)),
+        flow4_port_map: PathBuf::from(format!("{}/flow4_port_map", ebpf_map_path)),
 
         rt6_lan_map: PathBuf::from(format!("{}/rt6_lan_map", ebpf_map_path)),
         rt6_target_map: PathBuf::from(format!("{}/rt6_target_map", ebpf_map_path)),
         flow6_dns_map: PathBuf::from(format!("{}/flow6_dns_map", ebpf_map_path)),
         flow6_ip_map: PathBuf::from(format!("{}/flow6_ip_map", ebpf_map_path)),
+        flow6_port_map: PathBuf::from(format!("{}/flow6_port_map", ebpf_map_path)),
 
         rt4_cache_map: PathBuf::from(format!("{}/rt4_cache_map", ebpf_map_path)),
         rt6_cache_map: PathBuf::from(format!("{}/rt6_cache_map", ebpf_map_path)),
 
+        rt4_reply_map: PathBuf::from(format!("{}/rt4_reply_map", ebpf_map_path)),
+        rt6_reply_map: PathBuf::from(format!("{}/rt6_reply_map", ebpf_map_path)),
+
         ip_mac_v4: PathBuf::from(format!("{}/ip_mac_v4", ebpf_map_path)),
         ip_mac_v6: PathBuf::from(format!("{}/ip_mac_v6", ebpf_map_path)),
+
+        quota_limit_map: PathBuf::from(format!("{}/quota_limit_map", ebpf_map_path)),
+        rate_limit_map: PathBuf::from(format!("{}/rate_limit_map", ebpf_map_path)),
+        sqm_ingress_map: PathBuf::from(format!("{}/sqm_ingress_map", ebpf_map_path)),
     };
     tracing::info!("ebpf map paths is: {paths:#?}");
     map_setting::init_path(&paths);
@@ -111,18 +121,31 @@ pub(crate) struct LandscapeMapPath {
     pub rt4_target_map: PathBuf,
     pub flow4_dns_map: PathBuf,
     pub flow4_ip_map: PathBuf,
+    pub flow4_port_map: PathBuf,
 
     pub rt6_lan_map: PathBuf,
     pub rt6_target_map: PathBuf,
     pub flow6_dns_map: PathBuf,
     pub flow6_ip_map: PathBuf,
+    pub flow6_port_map: PathBuf,
 
     pub rt4_cache_map: PathBuf,
     pub rt6_cache_map: PathBuf,
 
+    /// 入站连接回程的 WAN
+    pub rt4_reply_map: PathBuf,
+    pub rt6_reply_map: PathBuf,
+
     // IP MAC
     pub ip_mac_v4: PathBuf,
     pub ip_mac_v6: PathBuf,
+
+    // 流量配额
+    pub quota_limit_map: PathBuf,
+    // 设备 / Flow 限速
+    pub rate_limit_map: PathBuf,
+    // 开启下行 SQM 的 WAN
+    pub sqm_ingress_map: PathBuf,
 }
 
 // pppoe -> Fire wall -> nat -> route
@@ -131,7 +154,9 @@ const PPPOE_INGRESS_PRIORITY: u32 = 3;
 const FIREWALL_INGRESS_PRIORITY: u32 = 4;
 // const MARK_INGRESS_PRIORITY: u32 = 5;
 const NAT_INGRESS_PRIORITY: u32 = 6;
-const WAN_ROUTE_INGRESS_PRIORITY: u32 = 7;
+const WAN_ROUTE_INGRESS_PRIORITY: u32 = 8;
+/// SQM 下行整形的 mirred 过滤器, 接收 WAN 路由放行的报文
+pub const SQM_INGRESS_PRIORITY: u32 = 9;
 
 // Fire wall -> nat -> pppoe
 // const PPPOE_MTU_FILTER_EGRESS_PRIORITY: u32 = 1;
@@ -144,6 +169,8 @@ const FIREWALL_EGRESS_PRIORITY: u32 = 7;
 const PPPOE_EGRESS_PRIORITY: u32 = 8;
 
 // lAN PRIORITY
+// 网桥端口标记需在网桥上的 route_lan 之前完成
+const BRIDGE_PORT_TAG_INGRESS_PRIORITY: u32 = 1;
 const LAN_ROUTE_INGRESS_PRIORITY: u32 = 2;
 
 const LAN_ROUTE_EGRESS_PRIORITY: u32 = 2;
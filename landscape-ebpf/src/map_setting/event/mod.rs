@@ -27,6 +27,8 @@ impl From<&nat_conn_metric_event> for ConnectMetric {
             flow_id: ev.flow_id,
             trace_id: ev.trace_id,
             gress: ev.gress,
+            ifindex: ev.ifindex,
+            domain: None,
             report_time: ev.time,
             create_time_ms: 0,
             ingress_bytes: ev.ingress_bytes,
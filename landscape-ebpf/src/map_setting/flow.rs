@@ -1,4 +1,7 @@
-use landscape_common::flow::{FlowEbpfMatchPair, FlowEntryMatchMode, FlowEntryRule};
+use landscape_common::dev::get_interface_index_by_name;
+use landscape_common::flow::{
+    check_bridge_port_ifindex, FlowEbpfMatchPair, FlowEntryMatchMode, FlowEntryRule,
+};
 use libbpf_rs::{MapCore, MapFlags};
 
 use crate::{
@@ -8,53 +11,91 @@ use crate::{
 
 const FLOW_ENTRY_MODE_MAC: u8 = 0;
 const FLOW_ENTRY_MODE_IP: u8 = 1;
+const FLOW_ENTRY_MODE_IFACE: u8 = 2;
+const FLOW_ENTRY_MODE_VLAN: u8 = 3;
+const FLOW_ENTRY_MODE_BRIDGE_PORT: u8 = 4;
+// 32 + 32, 与 eBPF 中 FLOW_INDEX_MATCH_LEN 一致
+const FLOW_INDEX_MATCH_LEN: u32 = 64;
 
-impl Into<flow_match_key> for FlowEntryRule {
-    fn into(self) -> flow_match_key {
-        let mut match_key = flow_match_key::default();
-        match self.mode {
-            FlowEntryMatchMode::Mac { mac_addr } => {
-                match_key.prefixlen = 80;
-                match_key.l3_protocol = 0;
-                match_key.is_match_ip = FLOW_ENTRY_MODE_MAC;
-                match_key.__anon_flow_match_key_1.mac.mac = mac_addr.octets();
-            }
-            FlowEntryMatchMode::Ip { ip, prefix_len } => {
-                match_key.prefixlen = 32 + prefix_len as u32;
-                match_key.is_match_ip = FLOW_ENTRY_MODE_IP;
-                match ip {
-                    std::net::IpAddr::V4(ipv4_addr) => {
-                        match_key.l3_protocol = LANDSCAPE_IPV4_TYPE;
-                        match_key.__anon_flow_match_key_1.src_addr.ip = ipv4_addr.to_bits().to_be();
-                    }
-                    std::net::IpAddr::V6(ipv6_addr) => {
-                        match_key.l3_protocol = LANDSCAPE_IPV6_TYPE;
-                        match_key.__anon_flow_match_key_1.src_addr.bits =
-                            ipv6_addr.to_bits().to_be_bytes();
-                    }
+/// 生成匹配键, 按网卡匹配的规则通过 `resolve_ifindex` 取得 ifindex
+///
+/// 网卡不存在或网桥端口的 ifindex 超出 16 位时返回 None, 不写入无法匹配的键
+fn to_match_key(
+    rule: &FlowEntryRule,
+    resolve_ifindex: &impl Fn(&str) -> Option<u32>,
+) -> Option<flow_match_key> {
+    let mut match_key = flow_match_key::default();
+    match &rule.mode {
+        FlowEntryMatchMode::Mac { mac_addr } => {
+            match_key.prefixlen = 80;
+            match_key.l3_protocol = 0;
+            match_key.is_match_ip = FLOW_ENTRY_MODE_MAC;
+            match_key.__anon_flow_match_key_1.mac.mac = mac_addr.octets();
+        }
+        FlowEntryMatchMode::Ip { ip, prefix_len } => {
+            match_key.prefixlen = 32 + *prefix_len as u32;
+            match_key.is_match_ip = FLOW_ENTRY_MODE_IP;
+            match ip {
+                std::net::IpAddr::V4(ipv4_addr) => {
+                    match_key.l3_protocol = LANDSCAPE_IPV4_TYPE;
+                    match_key.__anon_flow_match_key_1.src_addr.ip = ipv4_addr.to_bits().to_be();
+                }
+                std::net::IpAddr::V6(ipv6_addr) => {
+                    match_key.l3_protocol = LANDSCAPE_IPV6_TYPE;
+                    match_key.__anon_flow_match_key_1.src_addr.bits =
+                        ipv6_addr.to_bits().to_be_bytes();
                 }
             }
         }
-        match_key
+        FlowEntryMatchMode::Iface { iface_name } => {
+            match_key.prefixlen = FLOW_INDEX_MATCH_LEN;
+            match_key.is_match_ip = FLOW_ENTRY_MODE_IFACE;
+            match_key.__anon_flow_match_key_1.src_addr.ip = resolve_ifindex(iface_name)?;
+        }
+        FlowEntryMatchMode::Vlan { vlan_id } => {
+            match_key.prefixlen = FLOW_INDEX_MATCH_LEN;
+            match_key.is_match_ip = FLOW_ENTRY_MODE_VLAN;
+            match_key.__anon_flow_match_key_1.src_addr.ip = *vlan_id as u32;
+        }
+        FlowEntryMatchMode::BridgePort { iface_name } => {
+            let ifindex = resolve_ifindex(iface_name)?;
+            let tag = match check_bridge_port_ifindex(iface_name, ifindex) {
+                Ok(tag) => tag,
+                Err(e) => {
+                    tracing::warn!("{e}, entry rule is not active");
+                    return None;
+                }
+            };
+            match_key.prefixlen = FLOW_INDEX_MATCH_LEN;
+            match_key.is_match_ip = FLOW_ENTRY_MODE_BRIDGE_PORT;
+            match_key.__anon_flow_match_key_1.src_addr.ip = tag as u32;
+        }
     }
+    Some(match_key)
 }
 
-/// 更新匹配规则到 flow 的映射
+/// 更新匹配规则到 flow 的映射, 按网卡匹配的规则使用网卡当前的 ifindex
 pub fn update_flow_match_rule(rules: Vec<FlowEbpfMatchPair>) {
-    if rules.is_empty() {
-        return;
-    }
-
-    let flow_match_map = libbpf_rs::MapHandle::from_pinned_path(&MAP_PATHS.flow_match_map).unwrap();
     let mut keys = vec![];
     let mut values = vec![];
-    let counts = rules.len() as u32;
+    let mut counts = 0;
 
     for FlowEbpfMatchPair { entry_rule, flow_id } in rules.into_iter() {
-        let match_key: flow_match_key = entry_rule.into();
+        let Some(match_key) = to_match_key(&entry_rule, &get_interface_index_by_name) else {
+            tracing::warn!("entry rule {} is not active", entry_rule.mode);
+            continue;
+        };
+        // 入口规则的 QoS 与 flow_id 一同写入
+        let value = flow_id | entry_rule.qos.map(|qos| qos.to_mark_bits()).unwrap_or(0);
         keys.extend_from_slice(unsafe { plain::as_bytes(&match_key) });
-        values.extend_from_slice(unsafe { plain::as_bytes(&flow_id) });
+        values.extend_from_slice(unsafe { plain::as_bytes(&value) });
+        counts += 1;
+    }
+    if counts == 0 {
+        return;
     }
+
+    let flow_match_map = libbpf_rs::MapHandle::from_pinned_path(&MAP_PATHS.flow_match_map).unwrap();
     if let Err(e) =
         flow_match_map.update_batch(&keys, &values, counts, MapFlags::ANY, MapFlags::ANY)
     {
@@ -63,20 +104,31 @@ pub fn update_flow_match_rule(rules: Vec<FlowEbpfMatchPair>) {
     crate::map_setting::route::cache::recreate_route_lan_cache_inner_map();
 }
 
-/// 删除匹配规则到 flow 的映射
+/// 删除匹配规则到 flow 的映射, 按网卡匹配的规则使用网卡当前的 ifindex
 pub fn del_flow_match_rule(rules: Vec<FlowEntryRule>) {
-    if rules.is_empty() {
-        return;
-    }
+    del_flow_match_rule_with(rules, get_interface_index_by_name);
+}
 
-    let flow_match_map = libbpf_rs::MapHandle::from_pinned_path(&MAP_PATHS.flow_match_map).unwrap();
+/// 删除匹配规则到 flow 的映射, 按网卡匹配的规则使用 `resolve_ifindex` 给出的 ifindex,
+/// 用于网卡重建后删除按旧 ifindex 写入的键
+pub fn del_flow_match_rule_with(
+    rules: Vec<FlowEntryRule>,
+    resolve_ifindex: impl Fn(&str) -> Option<u32>,
+) {
     let mut keys = vec![];
-    let counts = rules.len() as u32;
+    let mut counts = 0;
 
-    for entry_rule in rules.into_iter() {
-        let match_key: flow_match_key = entry_rule.into();
-        keys.extend_from_slice(unsafe { plain::as_bytes(&match_key) });
+    for entry_rule in rules.iter() {
+        if let Some(match_key) = to_match_key(entry_rule, &resolve_ifindex) {
+            keys.extend_from_slice(unsafe { plain::as_bytes(&match_key) });
+            counts += 1;
+        }
+    }
+    if counts == 0 {
+        return;
     }
+
+    let flow_match_map = libbpf_rs::MapHandle::from_pinned_path(&MAP_PATHS.flow_match_map).unwrap();
     if let Err(e) = flow_match_map.delete_batch(&keys, counts, MapFlags::ANY, MapFlags::ANY) {
         tracing::error!("del_flow_match_rule error:{e:?}");
     }
@@ -1,11 +1,13 @@
 use std::os::fd::{AsFd, AsRawFd};
 
-use landscape_common::ip_mark::IpMarkInfo;
+use landscape_common::ip_mark::{IpMarkInfo, FLOW_PORT_RULE_MAX};
 use libbpf_rs::{libbpf_sys, MapCore, MapFlags, MapHandle, MapType};
 
 use crate::{
     bpf_error::LdEbpfResult,
-    map_setting::share_map::types::{flow_ip_trie_key_v6, flow_ip_trie_value_v6},
+    map_setting::share_map::types::{
+        flow_ip_trie_key_v6, flow_ip_trie_value_v6, flow_port_rules_v4, flow_port_rules_v6,
+    },
     route::lan_v2::route_lan::types::{flow_ip_trie_key_v4, flow_ip_trie_value_v4},
     MAP_PATHS,
 };
@@ -65,7 +67,7 @@ where
     let mut values = vec![];
 
     let mut count = 0;
-    for IpMarkInfo { mark, cidr, priority } in ips.iter() {
+    for IpMarkInfo { mark, cidr, priority, .. } in ips.iter().filter(|ip| !ip.has_l4_match()) {
         let mark: u32 = mark.clone().into();
         let mut value = flow_ip_trie_value_v4::default();
 
@@ -153,7 +155,7 @@ where
     let mut values = vec![];
 
     let mut count = 0;
-    for IpMarkInfo { mark, cidr, priority } in ips.iter() {
+    for IpMarkInfo { mark, cidr, priority, .. } in ips.iter().filter(|ip| !ip.has_l4_match()) {
         let mark: u32 = mark.clone().into();
         let mut value = flow_ip_trie_value_v6::default();
 
@@ -189,5 +191,92 @@ fn add_wan_ip_mark_inner(flow_id: u32, ips: Vec<IpMarkInfo>) -> LdEbpfResult<()>
 
     let flow_ip_match_map = libbpf_rs::MapHandle::from_pinned_path(&MAP_PATHS.flow6_ip_map)?;
     create_inner_flow_match_map_v6(&flow_ip_match_map, flow_id, &ips)?;
+
+    let flow_port_map = libbpf_rs::MapHandle::from_pinned_path(&MAP_PATHS.flow4_port_map)?;
+    update_flow_port_rules_v4(&flow_port_map, flow_id, &ips)?;
+
+    let flow_port_map = libbpf_rs::MapHandle::from_pinned_path(&MAP_PATHS.flow6_port_map)?;
+    update_flow_port_rules_v6(&flow_port_map, flow_id, &ips)?;
+    Ok(())
+}
+
+/// 带 L4 条件的规则, 按 IP 版本筛选并截断到 eBPF 的上限
+fn port_rules<'a>(flow_id: u32, ips: &'a [IpMarkInfo], is_v4: bool) -> Vec<&'a IpMarkInfo> {
+    let rules: Vec<_> =
+        ips.iter().filter(|ip| ip.has_l4_match() && ip.cidr.ip.is_ipv4() == is_v4).collect();
+    if rules.len() > FLOW_PORT_RULE_MAX {
+        tracing::warn!(
+            "flow {flow_id}: {} port rules exceed the limit {FLOW_PORT_RULE_MAX}, extra rules are ignored",
+            rules.len()
+        );
+    }
+    rules.into_iter().take(FLOW_PORT_RULE_MAX).collect()
+}
+
+fn update_flow_port_rules_v4<T>(map: &T, flow_id: u32, ips: &[IpMarkInfo]) -> LdEbpfResult<()>
+where
+    T: MapCore,
+{
+    let key = unsafe { plain::as_bytes(&flow_id) };
+    let rules = port_rules(flow_id, ips, true);
+    if rules.is_empty() {
+        let _ = map.delete(key);
+        return Ok(());
+    }
+
+    let mut value = flow_port_rules_v4::default();
+    for (index, info) in rules.iter().enumerate() {
+        let std::net::IpAddr::V4(addr) = info.cidr.ip else {
+            continue;
+        };
+        let mask = u32::MAX.checked_shl(32 - info.cidr.prefix).unwrap_or(0);
+        let rule = &mut value.rules[index];
+        rule.addr = (addr.to_bits() & mask).to_be();
+        rule.mask = mask.to_be();
+        rule.mark = info.mark.clone().into();
+        rule.priority = info.priority;
+        rule.l4_protocol = info.l4_protocol.clone().map(|p| p as u8).unwrap_or(0);
+        if let Some(range) = info.dst_port {
+            rule.port_start = range.start;
+            rule.port_end = range.end;
+        }
+    }
+    value.count = rules.len() as u32;
+
+    map.update(key, unsafe { plain::as_bytes(&value) }, MapFlags::ANY)?;
+    Ok(())
+}
+
+fn update_flow_port_rules_v6<T>(map: &T, flow_id: u32, ips: &[IpMarkInfo]) -> LdEbpfResult<()>
+where
+    T: MapCore,
+{
+    let key = unsafe { plain::as_bytes(&flow_id) };
+    let rules = port_rules(flow_id, ips, false);
+    if rules.is_empty() {
+        let _ = map.delete(key);
+        return Ok(());
+    }
+
+    let mut value = flow_port_rules_v6::default();
+    for (index, info) in rules.iter().enumerate() {
+        let std::net::IpAddr::V6(addr) = info.cidr.ip else {
+            continue;
+        };
+        let mask = u128::MAX.checked_shl(128 - info.cidr.prefix).unwrap_or(0);
+        let rule = &mut value.rules[index];
+        rule.addr.bytes = (addr.to_bits() & mask).to_be_bytes();
+        rule.mask.bytes = mask.to_be_bytes();
+        rule.mark = info.mark.clone().into();
+        rule.priority = info.priority;
+        rule.l4_protocol = info.l4_protocol.clone().map(|p| p as u8).unwrap_or(0);
+        if let Some(range) = info.dst_port {
+            rule.port_start = range.start;
+            rule.port_end = range.end;
+        }
+    }
+    value.count = rules.len() as u32;
+
+    map.update(key, unsafe { plain::as_bytes(&value) }, MapFlags::ANY)?;
     Ok(())
 }
@@ -46,7 +46,10 @@ pub mod flow_dns;
 pub mod flow_wanip;
 pub mod metric;
 pub mod nat;
+pub mod quota;
+pub mod rate_limit;
 pub mod route;
+pub mod sqm;
 
 pub mod event;
 
@@ -100,20 +103,33 @@ pub(crate) fn init_path(paths: &LandscapeMapPath) {
     reuse_pinned_map_or_recreate(&mut landscape_open.maps.rt4_target_map, &paths.rt4_target_map);
     reuse_pinned_map_or_recreate(&mut landscape_open.maps.flow4_dns_map, &paths.flow4_dns_map);
     reuse_pinned_map_or_recreate(&mut landscape_open.maps.flow4_ip_map, &paths.flow4_ip_map);
+    reuse_pinned_map_or_recreate(&mut landscape_open.maps.flow4_port_map, &paths.flow4_port_map);
 
     reuse_pinned_map_or_recreate(&mut landscape_open.maps.rt6_lan_map, &paths.rt6_lan_map);
     reuse_pinned_map_or_recreate(&mut landscape_open.maps.rt6_target_map, &paths.rt6_target_map);
     reuse_pinned_map_or_recreate(&mut landscape_open.maps.flow6_dns_map, &paths.flow6_dns_map);
     reuse_pinned_map_or_recreate(&mut landscape_open.maps.flow6_ip_map, &paths.flow6_ip_map);
+    reuse_pinned_map_or_recreate(&mut landscape_open.maps.flow6_port_map, &paths.flow6_port_map);
 
     reuse_pinned_map_or_recreate(&mut landscape_open.maps.rt4_cache_map, &paths.rt4_cache_map);
     reuse_pinned_map_or_recreate(&mut landscape_open.maps.rt6_cache_map, &paths.rt6_cache_map);
 
+    reuse_pinned_map_or_recreate(&mut landscape_open.maps.rt4_reply_map, &paths.rt4_reply_map);
+    reuse_pinned_map_or_recreate(&mut landscape_open.maps.rt6_reply_map, &paths.rt6_reply_map);
+
     reuse_pinned_map_or_recreate(&mut landscape_open.maps.ip_mac_v4, &paths.ip_mac_v4);
     reuse_pinned_map_or_recreate(&mut landscape_open.maps.ip_mac_v6, &paths.ip_mac_v6);
 
+    // quota
+    reuse_pinned_map_or_recreate(&mut landscape_open.maps.quota_limit_map, &paths.quota_limit_map);
+
+    // rate limit
+    reuse_pinned_map_or_recreate(&mut landscape_open.maps.rate_limit_map, &paths.rate_limit_map);
+
+    // sqm
+    reuse_pinned_map_or_recreate(&mut landscape_open.maps.sqm_ingress_map, &paths.sqm_ingress_map);
+
     let _landscape_skel = landscape_open.load().unwrap();
-    route::cache::init_route_wan_cache_inner_map(paths);
     route::cache::init_route_lan_cache_inner_map(paths);
 }
 
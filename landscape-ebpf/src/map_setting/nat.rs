@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
 use landscape_common::config::nat::StaticNatMappingItem;
@@ -326,3 +327,33 @@ where
         tracing::error!("update static_nat_mappings error:{e:?}");
     }
 }
+
+unsafe impl plain::Plain for nat_mapping_value_v4 {}
+
+/// 统计 IPv4 动态 NAT 已占用的 WAN 端口数量, 按 L4 协议分组
+pub fn get_nat4_dynamic_port_usage() -> HashMap<u8, u64> {
+    let mut result = HashMap::new();
+    let Ok(nat4_mappings) = libbpf_rs::MapHandle::from_pinned_path(&MAP_PATHS.nat4_mappings) else {
+        return result;
+    };
+
+    for key in nat4_mappings.keys() {
+        let Ok(mapping_key) = plain::from_bytes::<NatMappingKeyV4>(&key) else {
+            continue;
+        };
+        // 入向映射以 WAN 端口为键, 每个条目对应一个被占用的端口
+        if mapping_key.gress != NAT_MAPPING_INGRESS {
+            continue;
+        }
+        let Ok(Some(value)) = nat4_mappings.lookup(&key, MapFlags::ANY) else {
+            continue;
+        };
+        let Ok(mapping_value) = plain::from_bytes::<nat_mapping_value_v4>(&value) else {
+            continue;
+        };
+        if mapping_value.is_static == 0 {
+            *result.entry(mapping_key.l4proto).or_default() += 1;
+        }
+    }
+    result
+}
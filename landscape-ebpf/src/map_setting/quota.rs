@@ -0,0 +1,91 @@
+use landscape_common::quota::{QuotaLimitAction, QuotaLimitScope};
+use libbpf_rs::{MapCore, MapFlags};
+
+use crate::{
+    map_setting::share_map::types::{quota_limit_key, quota_limit_value},
+    MAP_PATHS,
+};
+
+const QUOTA_SCOPE_MAC: u8 = 1;
+const QUOTA_SCOPE_FLOW: u8 = 2;
+const QUOTA_SCOPE_IFINDEX: u8 = 3;
+
+const QUOTA_ACTION_DROP: u8 = 1;
+const QUOTA_ACTION_THROTTLE: u8 = 2;
+
+impl From<&QuotaLimitScope> for quota_limit_key {
+    fn from(scope: &QuotaLimitScope) -> Self {
+        let mut key = quota_limit_key::default();
+        match scope {
+            QuotaLimitScope::Mac(mac) => {
+                key.scope = QUOTA_SCOPE_MAC;
+                key.mac = mac.octets();
+            }
+            QuotaLimitScope::Flow(flow_id) => {
+                key.scope = QUOTA_SCOPE_FLOW;
+                key.id = *flow_id;
+            }
+            QuotaLimitScope::Ifindex(ifindex) => {
+                key.scope = QUOTA_SCOPE_IFINDEX;
+                key.id = *ifindex;
+            }
+        }
+        key
+    }
+}
+
+impl From<QuotaLimitAction> for quota_limit_value {
+    fn from(action: QuotaLimitAction) -> Self {
+        let mut value = quota_limit_value::default();
+        match action {
+            QuotaLimitAction::Drop => {
+                value.action = QUOTA_ACTION_DROP;
+            }
+            QuotaLimitAction::Throttle { rate_bytes } => {
+                value.action = QUOTA_ACTION_THROTTLE;
+                value.rate = rate_bytes;
+                value.tokens = rate_bytes;
+            }
+        }
+        value
+    }
+}
+
+/// 设置超出配额后的处理动作
+pub fn update_quota_limit(scope: &QuotaLimitScope, action: QuotaLimitAction) {
+    let quota_limit_map =
+        libbpf_rs::MapHandle::from_pinned_path(&MAP_PATHS.quota_limit_map).unwrap();
+
+    let key: quota_limit_key = scope.into();
+    let value: quota_limit_value = action.into();
+
+    let key = unsafe { plain::as_bytes(&key) };
+    let value = unsafe { plain::as_bytes(&value) };
+    if let Err(e) = quota_limit_map.update(key, value, MapFlags::ANY) {
+        tracing::error!("update quota limit {scope:?} error: {e:?}");
+    }
+}
+
+pub fn del_quota_limit(scope: &QuotaLimitScope) {
+    let quota_limit_map =
+        libbpf_rs::MapHandle::from_pinned_path(&MAP_PATHS.quota_limit_map).unwrap();
+
+    let key: quota_limit_key = scope.into();
+    let key = unsafe { plain::as_bytes(&key) };
+    if let Err(e) = quota_limit_map.delete(key) {
+        tracing::debug!("delete quota limit {scope:?} error: {e:?}");
+    }
+}
+
+/// 清空所有配额限制, 用于服务启动时丢弃上次运行遗留的记录
+pub fn clear_quota_limits() {
+    let quota_limit_map =
+        libbpf_rs::MapHandle::from_pinned_path(&MAP_PATHS.quota_limit_map).unwrap();
+
+    let keys: Vec<Vec<u8>> = quota_limit_map.keys().collect();
+    for key in keys {
+        if let Err(e) = quota_limit_map.delete(&key) {
+            tracing::error!("clear quota limit error: {e:?}");
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use std::net::IpAddr;
+
+use landscape_common::rate_limit::{RateLimitCounter, RateLimitDirection, RateLimitScope};
+use libbpf_rs::{MapCore, MapFlags};
+
+use crate::{
+    map_setting::share_map::types::{rate_limit_key, rate_limit_value},
+    MAP_PATHS,
+};
+
+const RATE_LIMIT_SCOPE_MAC: u8 = 1;
+const RATE_LIMIT_SCOPE_IPV4: u8 = 2;
+const RATE_LIMIT_SCOPE_IPV6: u8 = 3;
+const RATE_LIMIT_SCOPE_FLOW: u8 = 4;
+
+const RATE_LIMIT_UPLOAD: u8 = 0;
+const RATE_LIMIT_DOWNLOAD: u8 = 1;
+
+unsafe impl plain::Plain for rate_limit_value {}
+
+fn to_rate_limit_key(scope: &RateLimitScope, direction: RateLimitDirection) -> rate_limit_key {
+    let mut key = rate_limit_key::default();
+    key.direction = match direction {
+        RateLimitDirection::Upload => RATE_LIMIT_UPLOAD,
+        RateLimitDirection::Download => RATE_LIMIT_DOWNLOAD,
+    };
+    match scope {
+        RateLimitScope::Mac(mac) => {
+            key.scope = RATE_LIMIT_SCOPE_MAC;
+            key.mac = mac.octets();
+        }
+        RateLimitScope::Ip(IpAddr::V4(ipv4)) => {
+            key.scope = RATE_LIMIT_SCOPE_IPV4;
+            key.addr.ip = ipv4.to_bits().to_be();
+        }
+        RateLimitScope::Ip(IpAddr::V6(ipv6)) => {
+            key.scope = RATE_LIMIT_SCOPE_IPV6;
+            key.addr.bits = ipv6.to_bits().to_be_bytes();
+        }
+        RateLimitScope::Flow(flow_id) => {
+            key.scope = RATE_LIMIT_SCOPE_FLOW;
+            key.flow_id = *flow_id;
+        }
+    }
+    key
+}
+
+/// 设置限速值 (字节每秒), 已存在的记录保留累计计数
+pub fn update_rate_limit(scope: &RateLimitScope, direction: RateLimitDirection, rate_bytes: u64) {
+    let rate_limit_map = libbpf_rs::MapHandle::from_pinned_path(&MAP_PATHS.rate_limit_map).unwrap();
+
+    let key = to_rate_limit_key(scope, direction);
+    let key = unsafe { plain::as_bytes(&key) };
+
+    let mut value = match rate_limit_map.lookup(key, MapFlags::ANY) {
+        Ok(Some(old)) => plain::from_bytes::<rate_limit_value>(&old).copied().unwrap_or_default(),
+        _ => rate_limit_value::default(),
+    };
+    value.rate = rate_bytes;
+
+    let value = unsafe { plain::as_bytes(&value) };
+    if let Err(e) = rate_limit_map.update(key, value, MapFlags::ANY) {
+        tracing::error!("update rate limit {scope:?} {direction:?} error: {e:?}");
+    }
+}
+
+pub fn del_rate_limit(scope: &RateLimitScope, direction: RateLimitDirection) {
+    let rate_limit_map = libbpf_rs::MapHandle::from_pinned_path(&MAP_PATHS.rate_limit_map).unwrap();
+
+    let key = to_rate_limit_key(scope, direction);
+    let key = unsafe { plain::as_bytes(&key) };
+    if let Err(e) = rate_limit_map.delete(key) {
+        tracing::debug!("delete rate limit {scope:?} {direction:?} error: {e:?}");
+    }
+}
+
+/// 读取累计的字节数与丢包数
+pub fn read_rate_limit_counter(
+    scope: &RateLimitScope,
+    direction: RateLimitDirection,
+) -> Option<RateLimitCounter> {
+    let rate_limit_map = libbpf_rs::MapHandle::from_pinned_path(&MAP_PATHS.rate_limit_map).unwrap();
+
+    let key = to_rate_limit_key(scope, direction);
+    let key = unsafe { plain::as_bytes(&key) };
+    let value = rate_limit_map.lookup(key, MapFlags::ANY).ok().flatten()?;
+    let value = plain::from_bytes::<rate_limit_value>(&value).ok()?;
+    Some(RateLimitCounter { bytes: value.bytes, drops: value.drops })
+}
+
+/// 清空所有限速记录, 用于服务启动时丢弃上次运行遗留的记录
+pub fn clear_rate_limits() {
+    let rate_limit_map = libbpf_rs::MapHandle::from_pinned_path(&MAP_PATHS.rate_limit_map).unwrap();
+
+    let keys: Vec<Vec<u8>> = rate_limit_map.keys().collect();
+    for key in keys {
+        if let Err(e) = rate_limit_map.delete(&key) {
+            tracing::error!("clear rate limit error: {e:?}");
+        }
+    }
+}
@@ -10,7 +10,8 @@ use crate::{
 };
 
 const DNS_MATCH_MAX_ENTRIES: u32 = 65536;
-const WAN_CACHE: u32 = 0;
+/// 旧版本 WAN 缓存所在的位置, 回程改由 rt4/rt6_reply_map 记录后不再使用
+const LEGACY_WAN_CACHE: u32 = 0;
 const LAN_CACHE: u32 = 1;
 
 fn create_inner_map_generic<P, K, V>(path: P, name: String, cache_type: u32)
@@ -53,22 +54,21 @@ where
     }
 }
 
-pub(crate) fn init_route_wan_cache_inner_map(path: &LandscapeMapPath) {
-    // IPv4
-    create_inner_map_generic::<_, rt_cache_key_v4, rt_cache_value_v4>(
-        &path.rt4_cache_map,
-        "rt4_cache_wan".into(),
-        WAN_CACHE,
-    );
-    // IPv6
-    create_inner_map_generic::<_, rt_cache_key_v6, rt_cache_value_v6>(
-        &path.rt6_cache_map,
-        "rt6_cache_wan".into(),
-        WAN_CACHE,
-    );
+/// 删除外层 map 中旧版本遗留的 WAN 缓存
+fn remove_legacy_wan_cache<P: AsRef<std::path::Path>>(path: P) {
+    let Ok(outer_map) = libbpf_rs::MapHandle::from_pinned_path(path) else {
+        return;
+    };
+    let key = unsafe { plain::as_bytes(&LEGACY_WAN_CACHE) };
+    if outer_map.delete(key).is_ok() {
+        tracing::info!("removed legacy route wan cache");
+    }
 }
 
 pub(crate) fn init_route_lan_cache_inner_map(path: &LandscapeMapPath) {
+    remove_legacy_wan_cache(&path.rt4_cache_map);
+    remove_legacy_wan_cache(&path.rt6_cache_map);
+
     // IPv4
     create_inner_map_generic::<_, rt_cache_key_v4, rt_cache_value_v4>(
         &path.rt4_cache_map,
@@ -83,20 +83,6 @@ pub(crate) fn init_route_lan_cache_inner_map(path: &LandscapeMapPath) {
     );
 }
 
-// 修改了 静态 NAT 需要清理
-pub fn recreate_route_wan_cache_inner_map() {
-    create_inner_map_generic::<_, rt_cache_key_v4, rt_cache_value_v4>(
-        &MAP_PATHS.rt4_cache_map,
-        "rt4_cache_wan".into(),
-        WAN_CACHE,
-    );
-    create_inner_map_generic::<_, rt_cache_key_v6, rt_cache_value_v6>(
-        &MAP_PATHS.rt6_cache_map,
-        "rt6_cache_wan".into(),
-        WAN_CACHE,
-    );
-}
-
 /// 在修改了 DNS 规则， DST IP 规则。 Flow Match 规则后调用
 /// 使缓存失效
 pub fn recreate_route_lan_cache_inner_map() {
@@ -1,14 +1,17 @@
+use std::collections::HashSet;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::Path;
 
 use landscape_common::{
     config::FlowId,
     flow::mark::FlowMark,
+    network::LandscapeIpProtocolCode,
     route::{
         trace::{
             FlowMatchRequest, FlowMatchResult, FlowRuleMatchResult, FlowVerdictRequest,
             FlowVerdictResult, SingleVerdictResult,
         },
-        LanRouteInfo, RouteTargetInfo,
+        LanRouteInfo, RouteTargetInfo, RouteTproxyInfo,
     },
 };
 use libbpf_rs::{MapCore, MapFlags};
@@ -17,8 +20,10 @@ use crate::{
     map_setting::share_map::types::{
         flow_dns_match_key_v4, flow_dns_match_key_v6, flow_dns_match_value_v4,
         flow_dns_match_value_v6, flow_ip_trie_key_v4, flow_ip_trie_key_v6, flow_ip_trie_value_v4,
-        flow_ip_trie_value_v6, flow_match_key, route_target_info_v6, route_target_key_v6,
-        rt_cache_key_v4, rt_cache_key_v6, rt_cache_value_v4, rt_cache_value_v6,
+        flow_ip_trie_value_v6, flow_match_key, flow_port_rules_v4, flow_port_rules_v6,
+        route_target_info_v6, route_target_key_v6, rt_cache_key_v4, rt_cache_key_v6,
+        rt_cache_value_v4, rt_cache_value_v6, rt_reply_key_v4, rt_reply_key_v6, rt_reply_value_v4,
+        rt_reply_value_v6,
     },
     route::lan_v2::route_lan::types::{
         lan_route_info_v4, lan_route_info_v6, lan_route_key_v4, lan_route_key_v6,
@@ -32,6 +37,11 @@ pub mod cache;
 const FLOW_ENTRY_MODE_MAC: u8 = 0;
 const FLOW_ENTRY_MODE_IP: u8 = 1;
 const LAN_CACHE: u32 = 1;
+/// flow_match_map 的值中高位为入口规则的 QoS
+const FLOW_ID_MASK: u32 = 0xFF;
+/// 与 route_tproxy.h 中的 TPROXY_PROTOCOL_* 一致
+const TPROXY_PROTOCOL_TCP: u8 = 1;
+const TPROXY_PROTOCOL_UDP: u8 = 2;
 
 /// Step 1: Match source client → flow_id
 pub fn trace_flow_match(req: FlowMatchRequest) -> FlowMatchResult {
@@ -57,7 +67,7 @@ pub fn trace_flow_match(req: FlowMatchRequest) -> FlowMatchResult {
 
         let key_bytes = unsafe { plain::as_bytes(&key) };
         match flow_match_map.lookup(key_bytes, MapFlags::ANY) {
-            Ok(Some(val)) => plain::from_bytes::<u32>(&val).ok().copied(),
+            Ok(Some(val)) => plain::from_bytes::<u32>(&val).ok().map(|v| v & FLOW_ID_MASK),
             _ => None,
         }
     } else {
@@ -74,7 +84,7 @@ pub fn trace_flow_match(req: FlowMatchRequest) -> FlowMatchResult {
 
         let key_bytes = unsafe { plain::as_bytes(&key) };
         match flow_match_map.lookup(key_bytes, MapFlags::ANY) {
-            Ok(Some(val)) => plain::from_bytes::<u32>(&val).ok().copied(),
+            Ok(Some(val)) => plain::from_bytes::<u32>(&val).ok().map(|v| v & FLOW_ID_MASK),
             _ => None,
         }
     } else {
@@ -91,7 +101,7 @@ pub fn trace_flow_match(req: FlowMatchRequest) -> FlowMatchResult {
 
         let key_bytes = unsafe { plain::as_bytes(&key) };
         match flow_match_map.lookup(key_bytes, MapFlags::ANY) {
-            Ok(Some(val)) => plain::from_bytes::<u32>(&val).ok().copied(),
+            Ok(Some(val)) => plain::from_bytes::<u32>(&val).ok().map(|v| v & FLOW_ID_MASK),
             _ => None,
         }
     } else {
@@ -107,13 +117,17 @@ pub fn trace_flow_match(req: FlowMatchRequest) -> FlowMatchResult {
 
 /// Step 2: Flow verdict on multiple dst_ips (supports both IPv4 and IPv6)
 pub fn trace_flow_verdict(req: FlowVerdictRequest) -> FlowVerdictResult {
+    let l4_match = TraceL4Match {
+        l4_protocol: req.l4_protocol.clone().map(|p| p as u8).unwrap_or(0),
+        dst_port: req.dst_port.unwrap_or(0),
+    };
     let verdicts = req
         .dst_ips
         .iter()
         .map(|dst_ip| match dst_ip {
             IpAddr::V4(v4) => {
-                let (ip_rule_match, dns_rule_match, effective_mark) =
-                    trace_flow_verdict_single_v4(req.flow_id, *v4);
+                let (ip_rule_match, port_rule_match, dns_rule_match, effective_mark) =
+                    trace_flow_verdict_single_v4(req.flow_id, *v4, &l4_match);
                 let (has_cache, cached_mark, cache_consistent) = if let Some(src) = req.src_ipv4 {
                     trace_cache_check_v4(src, *v4, &effective_mark)
                 } else {
@@ -124,6 +138,7 @@ pub fn trace_flow_verdict(req: FlowVerdictRequest) -> FlowVerdictResult {
                     dst_ip: *dst_ip,
                     ip_rule_match,
                     dns_rule_match,
+                    port_rule_match,
                     effective_mark,
                     has_cache,
                     cached_mark,
@@ -131,8 +146,8 @@ pub fn trace_flow_verdict(req: FlowVerdictRequest) -> FlowVerdictResult {
                 }
             }
             IpAddr::V6(v6) => {
-                let (ip_rule_match, dns_rule_match, effective_mark) =
-                    trace_flow_verdict_single_v6(req.flow_id, *v6);
+                let (ip_rule_match, port_rule_match, dns_rule_match, effective_mark) =
+                    trace_flow_verdict_single_v6(req.flow_id, *v6, &l4_match);
                 let (has_cache, cached_mark, cache_consistent) = if let Some(src) = req.src_ipv6 {
                     trace_cache_check_v6(src, *v6, &effective_mark)
                 } else {
@@ -143,6 +158,7 @@ pub fn trace_flow_verdict(req: FlowVerdictRequest) -> FlowVerdictResult {
                     dst_ip: *dst_ip,
                     ip_rule_match,
                     dns_rule_match,
+                    port_rule_match,
                     effective_mark,
                     has_cache,
                     cached_mark,
@@ -168,28 +184,42 @@ fn lookup_inner_map(
     }
 }
 
+/// 与 eBPF 一致: 依次比较 IP 规则、端口规则、DNS 规则, 优先级相同时后者生效
 fn compute_effective_mark(
     ip_rule_match: &Option<FlowRuleMatchResult>,
+    port_rule_match: &Option<FlowRuleMatchResult>,
     dns_rule_match: &Option<FlowRuleMatchResult>,
 ) -> FlowMark {
-    match (ip_rule_match, dns_rule_match) {
-        (Some(ip), Some(dns)) => {
-            if dns.priority <= ip.priority {
-                dns.mark
-            } else {
-                ip.mark
-            }
+    let mut result: Option<&FlowRuleMatchResult> = ip_rule_match.as_ref();
+    for candidate in [port_rule_match, dns_rule_match].into_iter().flatten() {
+        if result.map_or(true, |current| candidate.priority <= current.priority) {
+            result = Some(candidate);
+        }
+    }
+    result.map(|rule| rule.mark).unwrap_or_default()
+}
+
+/// 追踪时使用的 L4 信息, 未提供时为 0
+struct TraceL4Match {
+    l4_protocol: u8,
+    dst_port: u16,
+}
+
+impl TraceL4Match {
+    fn matches(&self, l4_protocol: u8, port_start: u16, port_end: u16) -> bool {
+        if l4_protocol != 0 && l4_protocol != self.l4_protocol {
+            return false;
         }
-        (Some(ip), None) => ip.mark,
-        (None, Some(dns)) => dns.mark,
-        (None, None) => FlowMark::default(),
+        port_start == 0 || (port_start <= self.dst_port && self.dst_port <= port_end)
     }
 }
 
 fn trace_flow_verdict_single_v4(
     flow_id: u32,
     dst_ip: Ipv4Addr,
-) -> (Option<FlowRuleMatchResult>, Option<FlowRuleMatchResult>, FlowMark) {
+    l4_match: &TraceL4Match,
+) -> (Option<FlowRuleMatchResult>, Option<FlowRuleMatchResult>, Option<FlowRuleMatchResult>, FlowMark)
+{
     let flow_id_key = unsafe { plain::as_bytes(&flow_id) };
 
     // IP trie lookup
@@ -214,6 +244,30 @@ fn trace_flow_verdict_single_v4(
         })
     })();
 
+    // 带 L4 条件的规则
+    let port_rule_match = (|| -> Option<FlowRuleMatchResult> {
+        let map = libbpf_rs::MapHandle::from_pinned_path(&MAP_PATHS.flow4_port_map).ok()?;
+        let val_bytes = map.lookup(flow_id_key, MapFlags::ANY).ok()??;
+        if val_bytes.len() < size_of::<flow_port_rules_v4>() {
+            return None;
+        }
+        let val =
+            unsafe { std::ptr::read_unaligned(val_bytes.as_ptr() as *const flow_port_rules_v4) };
+        let addr = dst_ip.to_bits();
+        val.rules
+            .iter()
+            .take(val.count as usize)
+            .filter(|rule| {
+                addr & u32::from_be(rule.mask) == u32::from_be(rule.addr)
+                    && l4_match.matches(rule.l4_protocol, rule.port_start, rule.port_end)
+            })
+            .min_by_key(|rule| rule.priority)
+            .map(|rule| FlowRuleMatchResult {
+                mark: FlowMark::from(rule.mark),
+                priority: rule.priority,
+            })
+    })();
+
     // DNS hash lookup
     let dns_rule_match = (|| -> Option<FlowRuleMatchResult> {
         let outer = libbpf_rs::MapHandle::from_pinned_path(&MAP_PATHS.flow4_dns_map).ok()?;
@@ -236,14 +290,16 @@ fn trace_flow_verdict_single_v4(
         })
     })();
 
-    let effective_mark = compute_effective_mark(&ip_rule_match, &dns_rule_match);
-    (ip_rule_match, dns_rule_match, effective_mark)
+    let effective_mark = compute_effective_mark(&ip_rule_match, &port_rule_match, &dns_rule_match);
+    (ip_rule_match, port_rule_match, dns_rule_match, effective_mark)
 }
 
 fn trace_flow_verdict_single_v6(
     flow_id: u32,
     dst_ip: Ipv6Addr,
-) -> (Option<FlowRuleMatchResult>, Option<FlowRuleMatchResult>, FlowMark) {
+    l4_match: &TraceL4Match,
+) -> (Option<FlowRuleMatchResult>, Option<FlowRuleMatchResult>, Option<FlowRuleMatchResult>, FlowMark)
+{
     let flow_id_key = unsafe { plain::as_bytes(&flow_id) };
 
     // IP trie lookup
@@ -268,6 +324,31 @@ fn trace_flow_verdict_single_v6(
         })
     })();
 
+    // 带 L4 条件的规则
+    let port_rule_match = (|| -> Option<FlowRuleMatchResult> {
+        let map = libbpf_rs::MapHandle::from_pinned_path(&MAP_PATHS.flow6_port_map).ok()?;
+        let val_bytes = map.lookup(flow_id_key, MapFlags::ANY).ok()??;
+        if val_bytes.len() < size_of::<flow_port_rules_v6>() {
+            return None;
+        }
+        let val =
+            unsafe { std::ptr::read_unaligned(val_bytes.as_ptr() as *const flow_port_rules_v6) };
+        let addr = dst_ip.to_bits();
+        val.rules
+            .iter()
+            .take(val.count as usize)
+            .filter(|rule| {
+                addr & u128::from_be_bytes(unsafe { rule.mask.bytes })
+                    == u128::from_be_bytes(unsafe { rule.addr.bytes })
+                    && l4_match.matches(rule.l4_protocol, rule.port_start, rule.port_end)
+            })
+            .min_by_key(|rule| rule.priority)
+            .map(|rule| FlowRuleMatchResult {
+                mark: FlowMark::from(rule.mark),
+                priority: rule.priority,
+            })
+    })();
+
     // DNS hash lookup
     let dns_rule_match = (|| -> Option<FlowRuleMatchResult> {
         let outer = libbpf_rs::MapHandle::from_pinned_path(&MAP_PATHS.flow6_dns_map).ok()?;
@@ -290,8 +371,8 @@ fn trace_flow_verdict_single_v6(
         })
     })();
 
-    let effective_mark = compute_effective_mark(&ip_rule_match, &dns_rule_match);
-    (ip_rule_match, dns_rule_match, effective_mark)
+    let effective_mark = compute_effective_mark(&ip_rule_match, &port_rule_match, &dns_rule_match);
+    (ip_rule_match, port_rule_match, dns_rule_match, effective_mark)
 }
 
 fn trace_cache_check_v4(
@@ -536,6 +617,17 @@ pub fn add_wan_route(flow_id: FlowId, wan_info: RouteTargetInfo) {
     add_wan_route_inner_v6(&rt_target_map, flow_id, &wan_info);
 }
 
+fn tproxy_protocols(tproxy: &RouteTproxyInfo) -> u8 {
+    let mut protocols = 0;
+    if tproxy.tcp {
+        protocols |= TPROXY_PROTOCOL_TCP;
+    }
+    if tproxy.udp {
+        protocols |= TPROXY_PROTOCOL_UDP;
+    }
+    protocols
+}
+
 pub(crate) fn add_wan_route_inner_v4<'obj, T>(
     rt_target_map: &T,
     flow_id: FlowId,
@@ -553,6 +645,10 @@ pub(crate) fn add_wan_route_inner_v4<'obj, T>(
     } else {
         value.is_docker = 0;
     };
+    if let Some(tproxy) = &wan_info.tproxy {
+        value.tproxy_protocols = tproxy_protocols(tproxy);
+        value.tproxy_port = tproxy.port.to_be();
+    }
 
     match wan_info.gateway_ip {
         std::net::IpAddr::V4(ipv4_addr) => value.gate_addr = ipv4_addr.to_bits().to_be(),
@@ -596,6 +692,10 @@ pub(crate) fn add_wan_route_inner_v6<'obj, T>(
     } else {
         value.is_docker = 0;
     };
+    if let Some(tproxy) = &wan_info.tproxy {
+        value.tproxy_protocols = tproxy_protocols(tproxy);
+        value.tproxy_port = tproxy.port.to_be();
+    }
 
     match wan_info.gateway_ip {
         std::net::IpAddr::V4(_) => {
@@ -661,3 +761,109 @@ where
         tracing::error!("del wan config error:{e:?}");
     }
 }
+
+/// 回程记录中 LAN 主机一端的地址, 端口与协议,
+/// 与 route_v4.h 中 setting_reply_in_wan_v4 的构造一致 (本端为入站报文的目的地址)
+fn reply_local_v4(key: &rt_reply_key_v4) -> (IpAddr, u16, u8) {
+    let addr = Ipv4Addr::from(u32::from_be(key.local_addr));
+    (IpAddr::V4(addr), u16::from_be(key.local_port), key.l4_protocol)
+}
+
+fn reply_local_v6(key: &rt_reply_key_v6) -> (IpAddr, u16, u8) {
+    let addr = Ipv6Addr::from(unsafe { key.local_addr.bytes });
+    (IpAddr::V6(addr), u16::from_be(key.local_port), key.l4_protocol)
+}
+
+/// 删除回程记录中满足条件的项
+fn remove_reply_entries<K, V>(path: &Path, mut should_remove: impl FnMut(&K, &V) -> bool) {
+    let reply_map = match libbpf_rs::MapHandle::from_pinned_path(path) {
+        Ok(map) => map,
+        Err(e) => {
+            tracing::error!("open reply map {path:?} error: {e:?}");
+            return;
+        }
+    };
+
+    let keys: Vec<Vec<u8>> = reply_map.keys().collect();
+    for key_bytes in keys {
+        let Ok(Some(value_bytes)) = reply_map.lookup(&key_bytes, MapFlags::ANY) else {
+            continue;
+        };
+        if key_bytes.len() < size_of::<K>() || value_bytes.len() < size_of::<V>() {
+            continue;
+        }
+        let key = unsafe { std::ptr::read_unaligned(key_bytes.as_ptr() as *const K) };
+        let value = unsafe { std::ptr::read_unaligned(value_bytes.as_ptr() as *const V) };
+        if should_remove(&key, &value) {
+            if let Err(e) = reply_map.delete(&key_bytes) {
+                tracing::debug!("delete reply entry error: {e:?}");
+            }
+        }
+    }
+}
+
+/// WAN 移除或变更后, 清除经该 WAN 进入的连接的回程记录
+pub fn del_ipv4_route_reply_by_wan(ifindex: u32) {
+    remove_reply_entries::<rt_reply_key_v4, rt_reply_value_v4>(
+        &MAP_PATHS.rt4_reply_map,
+        |_, value| value.ifindex == ifindex,
+    );
+}
+
+pub fn del_ipv6_route_reply_by_wan(ifindex: u32) {
+    remove_reply_entries::<rt_reply_key_v6, rt_reply_value_v6>(
+        &MAP_PATHS.rt6_reply_map,
+        |_, value| value.ifindex == ifindex,
+    );
+}
+
+/// 静态映射移除后, 清除映射目标 (LAN 地址, 端口, 协议) 的回程记录
+pub fn del_route_reply_by_local<I>(locals: I)
+where
+    I: IntoIterator<Item = (IpAddr, u16, u8)>,
+{
+    let locals: HashSet<(IpAddr, u16, u8)> = locals.into_iter().collect();
+    if locals.is_empty() {
+        return;
+    }
+    if locals.iter().any(|(addr, _, _)| addr.is_ipv4()) {
+        remove_reply_entries::<rt_reply_key_v4, rt_reply_value_v4>(
+            &MAP_PATHS.rt4_reply_map,
+            |key, _| locals.contains(&reply_local_v4(key)),
+        );
+    }
+    if locals.iter().any(|(addr, _, _)| addr.is_ipv6()) {
+        remove_reply_entries::<rt_reply_key_v6, rt_reply_value_v6>(
+            &MAP_PATHS.rt6_reply_map,
+            |key, _| locals.contains(&reply_local_v6(key)),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reply_key_local_side() {
+        // 入站报文 203.0.113.7:40000 -> 192.168.1.10:8080 (TCP), 本端为 LAN 主机
+        let mut key = rt_reply_key_v4::default();
+        key.local_addr = Ipv4Addr::new(192, 168, 1, 10).to_bits().to_be();
+        key.remote_addr = Ipv4Addr::new(203, 0, 113, 7).to_bits().to_be();
+        key.local_port = 8080u16.to_be();
+        key.remote_port = 40000u16.to_be();
+        key.l4_protocol = 6;
+        assert_eq!(size_of::<rt_reply_key_v4>(), 16);
+        assert_eq!(reply_local_v4(&key), (IpAddr::V4(Ipv4Addr::new(192, 168, 1, 10)), 8080, 6));
+
+        let local: Ipv6Addr = "fd00::10".parse().unwrap();
+        let mut key = rt_reply_key_v6::default();
+        key.local_addr.bytes = local.to_bits().to_be_bytes();
+        key.remote_addr.bytes = "2001:db8::7".parse::<Ipv6Addr>().unwrap().to_bits().to_be_bytes();
+        key.local_port = 443u16.to_be();
+        key.remote_port = 40000u16.to_be();
+        key.l4_protocol = 17;
+        assert_eq!(size_of::<rt_reply_key_v6>(), 40);
+        assert_eq!(reply_local_v6(&key), (IpAddr::V6(local), 443, 17));
+    }
+}
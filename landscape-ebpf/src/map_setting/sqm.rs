@@ -0,0 +1,20 @@
+use libbpf_rs::{MapCore, MapFlags};
+
+use crate::MAP_PATHS;
+
+/// 标记 WAN 开启下行 SQM, WAN 路由程序会放行报文交给 IFB 整形
+pub fn add_sqm_ingress(ifindex: u32) {
+    let sqm_ingress_map =
+        libbpf_rs::MapHandle::from_pinned_path(&MAP_PATHS.sqm_ingress_map).unwrap();
+    if let Err(e) = sqm_ingress_map.update(&ifindex.to_ne_bytes(), &[1], MapFlags::ANY) {
+        tracing::error!("add sqm ingress {ifindex} error: {e:?}");
+    }
+}
+
+pub fn del_sqm_ingress(ifindex: u32) {
+    let sqm_ingress_map =
+        libbpf_rs::MapHandle::from_pinned_path(&MAP_PATHS.sqm_ingress_map).unwrap();
+    if let Err(e) = sqm_ingress_map.delete(&ifindex.to_ne_bytes()) {
+        tracing::debug!("delete sqm ingress {ifindex} error: {e:?}");
+    }
+}
@@ -52,9 +52,13 @@ pub fn route_wan(
 
     open_skel.maps.flow4_ip_map.set_pin_path(&MAP_PATHS.flow4_ip_map).unwrap();
     open_skel.maps.flow4_ip_map.reuse_pinned_map(&MAP_PATHS.flow4_ip_map).unwrap();
+    open_skel.maps.flow4_port_map.set_pin_path(&MAP_PATHS.flow4_port_map).unwrap();
+    open_skel.maps.flow4_port_map.reuse_pinned_map(&MAP_PATHS.flow4_port_map).unwrap();
 
     open_skel.maps.flow6_ip_map.set_pin_path(&MAP_PATHS.flow6_ip_map).unwrap();
     open_skel.maps.flow6_ip_map.reuse_pinned_map(&MAP_PATHS.flow6_ip_map).unwrap();
+    open_skel.maps.flow6_port_map.set_pin_path(&MAP_PATHS.flow6_port_map).unwrap();
+    open_skel.maps.flow6_port_map.reuse_pinned_map(&MAP_PATHS.flow6_port_map).unwrap();
 
     open_skel.maps.rt4_cache_map.set_pin_path(&MAP_PATHS.rt4_cache_map).unwrap();
     open_skel.maps.rt4_cache_map.reuse_pinned_map(&MAP_PATHS.rt4_cache_map).unwrap();
@@ -62,12 +66,27 @@ pub fn route_wan(
     open_skel.maps.rt6_cache_map.set_pin_path(&MAP_PATHS.rt6_cache_map).unwrap();
     open_skel.maps.rt6_cache_map.reuse_pinned_map(&MAP_PATHS.rt6_cache_map).unwrap();
 
+    open_skel.maps.rt4_reply_map.set_pin_path(&MAP_PATHS.rt4_reply_map).unwrap();
+    open_skel.maps.rt4_reply_map.reuse_pinned_map(&MAP_PATHS.rt4_reply_map).unwrap();
+
+    open_skel.maps.rt6_reply_map.set_pin_path(&MAP_PATHS.rt6_reply_map).unwrap();
+    open_skel.maps.rt6_reply_map.reuse_pinned_map(&MAP_PATHS.rt6_reply_map).unwrap();
+
     open_skel.maps.ip_mac_v4.set_pin_path(&MAP_PATHS.ip_mac_v4).unwrap();
     open_skel.maps.ip_mac_v4.reuse_pinned_map(&MAP_PATHS.ip_mac_v4).unwrap();
 
     open_skel.maps.ip_mac_v6.set_pin_path(&MAP_PATHS.ip_mac_v6).unwrap();
     open_skel.maps.ip_mac_v6.reuse_pinned_map(&MAP_PATHS.ip_mac_v6).unwrap();
 
+    open_skel.maps.quota_limit_map.set_pin_path(&MAP_PATHS.quota_limit_map).unwrap();
+    open_skel.maps.quota_limit_map.reuse_pinned_map(&MAP_PATHS.quota_limit_map).unwrap();
+
+    open_skel.maps.rate_limit_map.set_pin_path(&MAP_PATHS.rate_limit_map).unwrap();
+    open_skel.maps.rate_limit_map.reuse_pinned_map(&MAP_PATHS.rate_limit_map).unwrap();
+
+    open_skel.maps.sqm_ingress_map.set_pin_path(&MAP_PATHS.sqm_ingress_map).unwrap();
+    open_skel.maps.sqm_ingress_map.reuse_pinned_map(&MAP_PATHS.sqm_ingress_map).unwrap();
+
     let rodata_data =
         open_skel.maps.rodata_data.as_deref_mut().expect("`rodata` is not memery mapped");
 
@@ -1,5 +1,7 @@
 mod lan;
 mod package;
+mod quota;
+mod reply;
 
 #[cfg(test)]
 pub mod tests {
@@ -35,6 +37,8 @@ pub mod tests {
                 ip: IpAddr::V4(Ipv4Addr::new(74, 125, 131, 27)),
                 prefix: 24,
             },
+            l4_protocol: None,
+            dst_port: None,
             priority: 100,
         }];
         crate::map_setting::flow_wanip::create_inner_flow_match_map_v4(
@@ -53,6 +57,8 @@ pub mod tests {
                 mac: Some(MacAddr::dummy()),
                 default_route: false,
                 is_docker: false,
+                is_namespace: false,
+                tproxy: None,
                 iface_name: "test".to_string(),
                 iface_ip: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
                 gateway_ip: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
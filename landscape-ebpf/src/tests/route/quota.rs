@@ -0,0 +1,67 @@
+#[cfg(test)]
+pub mod tests {
+    use std::mem::MaybeUninit;
+
+    use landscape_common::net::MacAddr;
+    use landscape_common::quota::{QuotaLimitAction, QuotaLimitScope};
+    use libbpf_rs::{
+        skel::{OpenSkel, SkelBuilder as _},
+        MapCore, MapFlags, ProgramInput,
+    };
+
+    use crate::map_setting::share_map::types::{quota_limit_key, quota_limit_value};
+    use crate::route::lan_v2::route_lan::RouteLanSkelBuilder;
+    use crate::tests::route::package::simple_tcp_syn;
+
+    const TC_ACT_SHOT: i32 = 2;
+
+    // simple_tcp_syn 的目的 / 源 MAC
+    fn dst_mac() -> MacAddr {
+        MacAddr::new(0x00, 0x1f, 0x29, 0x5e, 0x4d, 0x26)
+    }
+
+    fn src_mac() -> MacAddr {
+        MacAddr::new(0x00, 0x50, 0x56, 0xbb, 0x3a, 0xa0)
+    }
+
+    #[test]
+    fn quota_drop_by_device_mac() {
+        let mut open_object = MaybeUninit::zeroed();
+        let open_skel = RouteLanSkelBuilder::default().open(&mut open_object).unwrap();
+        let skel = open_skel.load().unwrap();
+        let quota_limit_map = &skel.maps.quota_limit_map;
+
+        macro_rules! run {
+            ($prog:expr) => {{
+                let input = ProgramInput {
+                    data_in: Some(&mut simple_tcp_syn()),
+                    repeat: 1,
+                    ..Default::default()
+                };
+                $prog.test_run(input).expect("test_run failed").return_value as i32
+            }};
+        }
+        let set_drop = |mac: MacAddr| {
+            let key: quota_limit_key = (&QuotaLimitScope::Mac(mac)).into();
+            let value: quota_limit_value = QuotaLimitAction::Drop.into();
+            let key = unsafe { plain::as_bytes(&key) }.to_vec();
+            let value = unsafe { plain::as_bytes(&value) };
+            quota_limit_map.update(&key, value, MapFlags::ANY).unwrap();
+            key
+        };
+
+        // 下载方向: LAN 出口按目的 MAC 匹配
+        assert_ne!(run!(skel.progs.route_lan_egress), TC_ACT_SHOT);
+        let key = set_drop(dst_mac());
+        assert_eq!(run!(skel.progs.route_lan_egress), TC_ACT_SHOT);
+        // 入口只按源 MAC 匹配
+        assert_ne!(run!(skel.progs.route_lan_ingress), TC_ACT_SHOT);
+        quota_limit_map.delete(&key).unwrap();
+
+        // 上传方向: LAN 入口按源 MAC 匹配
+        let key = set_drop(src_mac());
+        assert_eq!(run!(skel.progs.route_lan_ingress), TC_ACT_SHOT);
+        assert_ne!(run!(skel.progs.route_lan_egress), TC_ACT_SHOT);
+        quota_limit_map.delete(&key).unwrap();
+    }
+}
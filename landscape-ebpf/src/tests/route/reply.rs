@@ -0,0 +1,114 @@
+#[cfg(test)]
+pub mod tests {
+    use std::{
+        mem::MaybeUninit,
+        net::{IpAddr, Ipv4Addr},
+        os::fd::AsFd,
+    };
+
+    use etherparse::PacketBuilder;
+    use landscape_common::net::MacAddr;
+    use libbpf_rs::{
+        skel::{OpenSkel, SkelBuilder as _},
+        MapCore, MapFlags, ProgramInput,
+    };
+    use zerocopy::IntoBytes;
+
+    use crate::{
+        map_setting::add_wan_ip,
+        route::{lan_v2::route_lan::RouteLanSkelBuilder, wan_v2::route_wan::RouteWanSkelBuilder},
+        tests::{route::package::simple_tcp_syn, TestSkb},
+    };
+
+    const TC_ACT_UNSPEC: i32 = -1;
+    const TC_ACT_REDIRECT: i32 = 7;
+    const INGRESS_STATIC_MARK: u32 = 1;
+    // test_run 只接受已存在的网卡, 使用 lo 作为入口 WAN
+    const WAN_IFINDEX: u32 = 1;
+
+    // simple_tcp_syn 的反向报文: 74.125.131.27:25 -> 192.168.20.70:54557
+    fn inbound_tcp_syn() -> Vec<u8> {
+        let builder = PacketBuilder::ethernet2(
+            [0x00, 0x1f, 0x29, 0x5e, 0x4d, 0x26],
+            [0x00, 0x50, 0x56, 0xbb, 0x3a, 0xa0],
+        )
+        .ipv4([74, 125, 131, 27], [192, 168, 20, 70], 64)
+        .tcp(25, 54557, 1, 4000);
+        let mut payload = Vec::with_capacity(builder.size(0));
+        builder.write(&mut payload, &[]).unwrap();
+        payload
+    }
+
+    // simple_tcp_syn 改为非首个分片, 不携带 TCP 首部的端口
+    fn reply_fragment() -> Vec<u8> {
+        let mut packet = simple_tcp_syn();
+        packet[20] = 0x00;
+        packet[21] = 0x10;
+        packet
+    }
+
+    // 另一个连接的报文, 只有源端口不同
+    fn other_connection() -> Vec<u8> {
+        let mut packet = simple_tcp_syn();
+        packet[34] = 0xd5;
+        packet[35] = 0x1e;
+        packet
+    }
+
+    #[test]
+    fn reply_leaves_from_ingress_wan() {
+        let mut wan_open_object = MaybeUninit::zeroed();
+        let wan_skel =
+            RouteWanSkelBuilder::default().open(&mut wan_open_object).unwrap().load().unwrap();
+
+        // LAN 与 WAN 共用回复路由记录
+        let mut lan_open_object = MaybeUninit::zeroed();
+        let mut lan_open_skel = RouteLanSkelBuilder::default().open(&mut lan_open_object).unwrap();
+        lan_open_skel.maps.rt4_reply_map.reuse_fd(wan_skel.maps.rt4_reply_map.as_fd()).unwrap();
+        let lan_skel = lan_open_skel.load().unwrap();
+
+        add_wan_ip(
+            &lan_skel.maps.wan_ip_binding,
+            WAN_IFINDEX,
+            IpAddr::V4(Ipv4Addr::new(192, 168, 20, 70)),
+            Some(IpAddr::V4(Ipv4Addr::new(192, 168, 20, 1))),
+            24,
+            Some(MacAddr::dummy()),
+        );
+        // 开启入口 SQM 后, WAN 入口只记录回复路由
+        wan_skel
+            .maps
+            .sqm_ingress_map
+            .update(&WAN_IFINDEX.to_ne_bytes(), &[1], MapFlags::ANY)
+            .unwrap();
+
+        let run_lan = |mut packet: Vec<u8>| {
+            let input = ProgramInput {
+                data_in: Some(&mut packet),
+                repeat: 1,
+                ..Default::default()
+            };
+            lan_skel.progs.route_lan_ingress.test_run(input).expect("test_run failed").return_value
+                as i32
+        };
+
+        assert_ne!(run_lan(simple_tcp_syn()), TC_ACT_REDIRECT);
+
+        let mut ctx = TestSkb::default();
+        ctx.ifindex = WAN_IFINDEX;
+        ctx.mark = INGRESS_STATIC_MARK;
+        let mut packet = inbound_tcp_syn();
+        let input = ProgramInput {
+            data_in: Some(&mut packet),
+            context_in: Some(ctx.as_mut_bytes()),
+            repeat: 1,
+            ..Default::default()
+        };
+        let result = wan_skel.progs.route_wan_ingress.test_run(input).expect("test_run failed");
+        assert_eq!(result.return_value as i32, TC_ACT_UNSPEC);
+
+        assert_eq!(run_lan(simple_tcp_syn()), TC_ACT_REDIRECT);
+        assert_eq!(run_lan(reply_fragment()), TC_ACT_REDIRECT);
+        assert_ne!(run_lan(other_connection()), TC_ACT_REDIRECT);
+    }
+}
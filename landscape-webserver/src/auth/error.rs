@@ -18,10 +18,30 @@ pub enum AuthError {
     #[api_error(id = "auth.unauthorized", status = 401)]
     UnauthorizedUser,
 
+    #[error("Permission denied for current role")]
+    #[api_error(id = "auth.forbidden", status = 403)]
+    Forbidden,
+
+    #[error("Account settings can only be changed from a login session")]
+    #[api_error(id = "auth.session_required", status = 403)]
+    SessionRequired,
+
     #[error("Invalid username or password")]
     #[api_error(id = "auth.invalid_credentials", status = 401)]
     InvalidUsernameOrPassword,
 
+    #[error("Two-factor authentication code required")]
+    #[api_error(id = "auth.totp_required", status = 401)]
+    TotpRequired,
+
+    #[error("Invalid two-factor authentication code")]
+    #[api_error(id = "auth.invalid_totp_code", status = 401)]
+    InvalidTotpCode,
+
+    #[error("Too many failed login attempts, retry in {0} seconds")]
+    #[api_error(id = "auth.too_many_attempts", status = 429)]
+    TooManyAttempts(u64),
+
     #[error("Token creation failed: {0}")]
     #[api_error(id = "auth.token_creation_failed", status = 500)]
     JwtCreationFailed(#[from] jsonwebtoken::errors::Error),
@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// 不触发锁定的连续失败次数
+const FREE_ATTEMPTS: u32 = 5;
+const BASE_LOCK_SECS: u64 = 30;
+const MAX_LOCK_SECS: u64 = 60 * 60;
+/// 超过该时间没有失败的记录会被清理
+const ENTRY_TTL_SECS: u64 = 60 * 60 * 24;
+/// 记录数上限, 超过后淘汰最久未失败的记录
+const MAX_ENTRIES: usize = 4096;
+
+/// 同一用户名不触发延迟的连续失败次数
+const USER_FREE_ATTEMPTS: u32 = 5;
+const USER_BASE_DELAY_MS: u64 = 1000;
+const USER_MAX_DELAY_MS: u64 = 30 * 1000;
+/// 排队等待超过该时间的登录请求直接拒绝
+const USER_MAX_WAIT_MS: u64 = 60 * 1000;
+
+#[derive(Debug, Clone, Copy, Default)]
+struct FailureEntry {
+    failures: u32,
+    locked_until: u64,
+    last_failure: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct UserBackoffEntry {
+    failures: u32,
+    /// 下一次允许尝试的时间 (毫秒)
+    next_attempt_ms: u64,
+    last_failure: u64,
+}
+
+/// 按来源 IP 统计登录失败次数, 超过 `FREE_ATTEMPTS` 后每次失败的锁定时间翻倍;
+/// 同时按用户名退避: 超过 `USER_FREE_ATTEMPTS` 后同一用户名的登录请求依次间隔
+/// 翻倍的延迟执行, 更换来源也无法加快猜测, 且只延迟不锁定, 他人无法锁住管理员
+#[derive(Clone, Default)]
+pub struct LoginLimiter {
+    entries: Arc<Mutex<HashMap<String, FailureEntry>>>,
+    users: Arc<Mutex<HashMap<String, UserBackoffEntry>>>,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64
+}
+
+fn normalize_username(username: &str) -> String {
+    username.trim().to_lowercase()
+}
+
+/// 清理过期记录, 并在达到上限时淘汰最久未失败的记录
+fn prune<T>(
+    entries: &mut HashMap<String, T>,
+    key: &str,
+    now: u64,
+    last_failure: impl Fn(&T) -> u64,
+) {
+    entries.retain(|_, entry| now.saturating_sub(last_failure(entry)) < ENTRY_TTL_SECS);
+    if entries.len() >= MAX_ENTRIES && !entries.contains_key(key) {
+        let oldest =
+            entries.iter().min_by_key(|(_, entry)| last_failure(entry)).map(|(key, _)| key.clone());
+        if let Some(oldest) = oldest {
+            entries.remove(&oldest);
+        }
+    }
+}
+
+impl LoginLimiter {
+    /// 返回剩余锁定秒数
+    pub fn locked_for(&self, ip: &str) -> Option<u64> {
+        let now = now_secs();
+        let entries = self.entries.lock().unwrap();
+        entries
+            .get(ip)
+            .map(|entry| entry.locked_until.saturating_sub(now))
+            .filter(|remain| *remain > 0)
+    }
+
+    /// 为该用户名的本次登录预留尝试时间, 返回需要等待的时长;
+    /// 排队时间超过 `USER_MAX_WAIT_MS` 时返回需要稍后重试的秒数
+    pub fn user_delay(&self, username: &str) -> Result<Duration, u64> {
+        let now = now_ms();
+        let mut users = self.users.lock().unwrap();
+        let Some(entry) = users.get_mut(&normalize_username(username)) else {
+            return Ok(Duration::ZERO);
+        };
+        if entry.failures <= USER_FREE_ATTEMPTS {
+            return Ok(Duration::ZERO);
+        }
+
+        let wait = entry.next_attempt_ms.saturating_sub(now);
+        if wait > USER_MAX_WAIT_MS {
+            return Err((wait - USER_MAX_WAIT_MS).div_ceil(1000));
+        }
+        let exp = (entry.failures - USER_FREE_ATTEMPTS - 1).min(16);
+        let delay = (USER_BASE_DELAY_MS << exp).min(USER_MAX_DELAY_MS);
+        entry.next_attempt_ms = now.max(entry.next_attempt_ms) + delay;
+        Ok(Duration::from_millis(wait))
+    }
+
+    pub fn fail(&self, ip: &str, username: &str) {
+        let now = now_secs();
+        {
+            let mut entries = self.entries.lock().unwrap();
+            prune(&mut entries, ip, now, |entry| entry.last_failure);
+            let entry = entries.entry(ip.to_string()).or_default();
+            entry.failures += 1;
+            entry.last_failure = now;
+            if entry.failures > FREE_ATTEMPTS {
+                let exp = (entry.failures - FREE_ATTEMPTS - 1).min(16);
+                entry.locked_until = now + (BASE_LOCK_SECS << exp).min(MAX_LOCK_SECS);
+            }
+        }
+
+        let username = normalize_username(username);
+        let mut users = self.users.lock().unwrap();
+        prune(&mut users, &username, now, |entry| entry.last_failure);
+        let entry = users.entry(username).or_default();
+        entry.failures += 1;
+        entry.last_failure = now;
+    }
+
+    pub fn reset(&self, ip: &str, username: &str) {
+        self.entries.lock().unwrap().remove(ip);
+        self.users.lock().unwrap().remove(&normalize_username(username));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lock_after_free_attempts() {
+        let limiter = LoginLimiter::default();
+        for i in 0..FREE_ATTEMPTS {
+            limiter.fail("192.168.1.2", &format!("user{i}"));
+        }
+        assert_eq!(limiter.locked_for("192.168.1.2"), None);
+
+        limiter.fail("192.168.1.2", "admin");
+        assert!(limiter.locked_for("192.168.1.2").is_some_and(|secs| secs <= BASE_LOCK_SECS));
+        // 其他来源不受影响
+        assert_eq!(limiter.locked_for("192.168.1.3"), None);
+
+        limiter.reset("192.168.1.2", "admin");
+        assert_eq!(limiter.locked_for("192.168.1.2"), None);
+    }
+
+    #[test]
+    fn entries_are_capped() {
+        let limiter = LoginLimiter::default();
+        for i in 0..MAX_ENTRIES + 10 {
+            limiter.fail(&format!("10.0.{}.{}", i / 256, i % 256), &format!("user{i}"));
+        }
+        assert_eq!(limiter.entries.lock().unwrap().len(), MAX_ENTRIES);
+        assert_eq!(limiter.users.lock().unwrap().len(), MAX_ENTRIES);
+    }
+
+    #[test]
+    fn user_backoff_across_sources() {
+        let limiter = LoginLimiter::default();
+        // 每次失败来自不同的地址, 不会触发 IP 锁定
+        for i in 0..USER_FREE_ATTEMPTS {
+            limiter.fail(&format!("2001:db8::{i}"), "admin");
+        }
+        assert_eq!(limiter.user_delay("admin"), Ok(Duration::ZERO));
+
+        limiter.fail("2001:db8::ff", "Admin ");
+        assert_eq!(limiter.locked_for("2001:db8::ff"), None);
+        // 第一次尝试立即执行, 之后的尝试依次间隔延迟
+        assert_eq!(limiter.user_delay("admin"), Ok(Duration::ZERO));
+        let wait = limiter.user_delay("ADMIN").unwrap();
+        assert!(wait > Duration::from_millis(USER_BASE_DELAY_MS - 100));
+        assert!(wait <= Duration::from_millis(USER_BASE_DELAY_MS));
+        let wait = limiter.user_delay("admin").unwrap();
+        assert!(wait > Duration::from_millis(2 * USER_BASE_DELAY_MS - 100));
+        // 其他用户不受影响
+        assert_eq!(limiter.user_delay("operator"), Ok(Duration::ZERO));
+
+        limiter.reset("2001:db8::ff", "admin");
+        assert_eq!(limiter.user_delay("admin"), Ok(Duration::ZERO));
+    }
+
+    #[test]
+    fn user_backoff_grows_and_caps_waiting() {
+        let limiter = LoginLimiter::default();
+        for i in 0..USER_FREE_ATTEMPTS + 3 {
+            limiter.fail(&format!("10.0.0.{i}"), "admin");
+        }
+        // 第 3 次超出后每次间隔 4 秒
+        assert_eq!(limiter.user_delay("admin"), Ok(Duration::ZERO));
+        let wait = limiter.user_delay("admin").unwrap();
+        assert!(wait > Duration::from_millis(4 * USER_BASE_DELAY_MS - 100));
+
+        // 排队超过上限后直接拒绝, 不再继续累积等待时间
+        let mut rejected = None;
+        for _ in 0..100 {
+            if let Err(secs) = limiter.user_delay("admin") {
+                rejected = Some(secs);
+                break;
+            }
+        }
+        assert!(rejected.is_some_and(|secs| secs > 0));
+        let next = limiter.users.lock().unwrap().get("admin").unwrap().next_attempt_ms;
+        assert!(limiter.user_delay("admin").is_err());
+        assert_eq!(limiter.users.lock().unwrap().get("admin").unwrap().next_attempt_ms, next);
+    }
+}
@@ -1,17 +1,27 @@
+use std::collections::HashSet;
 use std::fs::Permissions;
+use std::net::SocketAddr;
 use std::os::unix::fs::PermissionsExt;
 use std::sync::Arc;
 use std::time::SystemTime;
 use std::time::UNIX_EPOCH;
 
-use axum::extract::State;
+use axum::extract::{ConnectInfo, MatchedPath, State};
+use axum::http::Method;
 use axum::Router;
 use axum::{extract::Request, middleware::Next, response::Response};
+use landscape::sys_service::audit_service::AuditLogService;
+use landscape::sys_service::user_service::LandscapeUserService;
 use landscape_common::api_response::LandscapeApiResp as CommonApiResp;
 use landscape_common::args::LAND_HOME_PATH;
+use landscape_common::auth::audit::{AuditLog, AuditLogKind};
+use landscape_common::auth::user::{LandscapeUser, UserRole, API_TOKEN_PREFIX};
 use landscape_common::auth::LoginInfo;
 use landscape_common::auth::LoginResult;
-use landscape_common::config::AuthRuntimeConfig;
+use landscape_common::config::{AuthRuntimeConfig, ConfigId};
+use landscape_common::error::LdApiErrorInfo;
+use landscape_common::utils::id::gen_database_uuid;
+use landscape_common::utils::time::get_f64_timestamp;
 use landscape_common::LANDSCAPE_SYS_TOKEN_FILE_ANME;
 use once_cell::sync::Lazy;
 use utoipa_axum::router::OpenApiRouter;
@@ -26,15 +36,20 @@ use tokio::io::AsyncWriteExt;
 use crate::api::JsonBody;
 use crate::api::LandscapeApiResp;
 use crate::auth::error::AuthError;
+use crate::auth::lockout::LoginLimiter;
 use crate::error::LandscapeApiError;
 use crate::error::LandscapeApiResult;
 
 pub mod error;
+pub mod lockout;
 
 const SECRET_KEY_LENGTH: usize = 20;
 const DEFAULT_EXPIRE_TIME: usize = 60 * 60 * 1;
 const SYS_TOKEN_EXPIRE_TIME: usize = 60 * 60 * 24 * 365 * 30;
 
+/// 接口声明 `security(("bearer_auth" = ["read_only"]))` 后, 只读用户也可以调用
+pub const READ_ONLY_SCOPE: &str = "read_only";
+
 pub static SECRET_KEY: Lazy<String> = Lazy::new(|| {
     //
     rand::rng()
@@ -44,11 +59,13 @@ pub static SECRET_KEY: Lazy<String> = Lazy::new(|| {
         .collect()
 });
 
-pub async fn output_sys_token(auth: &AuthRuntimeConfig) {
+pub async fn output_sys_token(auth: &AuthRuntimeConfig, user_service: &LandscapeUserService) {
     let token_path = LAND_HOME_PATH.join(LANDSCAPE_SYS_TOKEN_FILE_ANME);
-    // 生成长期有效的系统 token
-    let sys_token =
-        create_jwt(&auth.admin_user, SYS_TOKEN_EXPIRE_TIME).expect("Failed to create system token");
+    // 生成长期有效的系统 token, 管理员修改密码后需重启才会重新生成
+    let token_version =
+        user_service.find_user_by_name(&auth.admin_user).await.map_or(0, |user| user.token_version);
+    let sys_token = create_jwt(&auth.admin_user, token_version, SYS_TOKEN_EXPIRE_TIME)
+        .expect("Failed to create system token");
 
     let mut file = OpenOptions::new()
         .write(true)
@@ -66,26 +83,164 @@ pub async fn output_sys_token(auth: &AuthRuntimeConfig) {
     file.set_permissions(perms).await.expect("Failed to set file permissions");
 }
 
+/// 认证中间件与登录接口共享的状态
+#[derive(Clone)]
+pub struct AuthState {
+    pub user_service: LandscapeUserService,
+    pub audit_service: AuditLogService,
+    pub limiter: LoginLimiter,
+    pub read_only_routes: ReadOnlyRoutes,
+}
+
+/// 只读用户可以调用的修改类请求 (查询类 POST 接口与账号自助接口),
+/// 从 OpenAPI 文档中带有 `READ_ONLY_SCOPE` 的接口收集
+#[derive(Clone, Default)]
+pub struct ReadOnlyRoutes(Arc<HashSet<(Method, String)>>);
+
+impl ReadOnlyRoutes {
+    pub fn from_openapi(spec: &utoipa::openapi::OpenApi) -> Self {
+        let mut routes = HashSet::new();
+        for (path, item) in spec.paths.paths.iter() {
+            let operations = [
+                (Method::POST, &item.post),
+                (Method::PUT, &item.put),
+                (Method::PATCH, &item.patch),
+                (Method::DELETE, &item.delete),
+            ];
+            for (method, operation) in operations {
+                let marked = operation
+                    .as_ref()
+                    .and_then(|operation| operation.security.as_ref())
+                    .is_some_and(|security| security.iter().any(has_read_only_scope));
+                if marked {
+                    routes.insert((method, path.clone()));
+                }
+            }
+        }
+        Self(Arc::new(routes))
+    }
+
+    pub(crate) fn allows(&self, method: &Method, path: &str) -> bool {
+        match *method {
+            Method::GET | Method::HEAD | Method::OPTIONS => true,
+            _ => self.0.contains(&(method.clone(), path.to_string())),
+        }
+    }
+}
+
+fn has_read_only_scope(requirement: &utoipa::openapi::security::SecurityRequirement) -> bool {
+    // SecurityRequirement 序列化为 { "<scheme>": ["<scope>", ...] }
+    let Ok(serde_json::Value::Object(schemes)) = serde_json::to_value(requirement) else {
+        return false;
+    };
+    schemes
+        .values()
+        .filter_map(|scopes| scopes.as_array())
+        .flatten()
+        .any(|scope| scope == READ_ONLY_SCOPE)
+}
+
+/// 请求不会修改数据 (`ReadOnlyRoutes::allows` 通过), 由认证中间件写入请求扩展
+#[derive(Debug, Clone, Copy)]
+pub struct ReadOnlyRoute;
+
+/// 已通过认证的用户, 由认证中间件写入请求扩展
+#[derive(Debug, Clone)]
+pub struct AuthUser {
+    pub user_id: ConfigId,
+    pub username: String,
+    /// 生效的角色, 使用 API Token 时为 Token 的权限范围
+    pub role: UserRole,
+    /// 通过 API Token 认证
+    pub via_token: bool,
+}
+
+impl AuthUser {
+    pub fn require_admin(&self) -> Result<(), AuthError> {
+        if self.role.is_admin() {
+            Ok(())
+        } else {
+            Err(AuthError::Forbidden)
+        }
+    }
+
+    /// 账号安全设置 (密码 / 两步验证 / API Token) 只能在登录会话中修改
+    pub fn require_session(&self) -> Result<(), AuthError> {
+        if self.via_token {
+            Err(AuthError::SessionRequired)
+        } else {
+            Ok(())
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct Claims {
     // 用户ID或标识
     sub: String,
     // 过期时间（Unix timestamp）
     exp: usize,
+    // 签发时用户的 token_version, 修改密码后旧的会话失效
+    #[serde(default)]
+    ver: u32,
 }
 
-fn create_jwt(user_id: &str, expiration: usize) -> Result<String, AuthError> {
+fn create_jwt(user_id: &str, version: u32, expiration: usize) -> Result<String, AuthError> {
     // 设置过期时间
     let expiration =
         SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as usize + expiration;
-    let claims = Claims { sub: user_id.to_owned(), exp: expiration };
+    let claims = Claims {
+        sub: user_id.to_owned(),
+        exp: expiration,
+        ver: version,
+    };
     // 使用一个足够复杂的密钥来签名
     Ok(encode(&Header::default(), &claims, &EncodingKey::from_secret(SECRET_KEY.as_bytes()))?)
 }
 
+/// 校验 JWT 或 API Token, 返回当前用户与 JWT 的声明
+async fn authenticate(
+    auth: &AuthState,
+    token: &str,
+) -> Result<(AuthUser, Option<Claims>), AuthError> {
+    if token.starts_with(API_TOKEN_PREFIX) {
+        let Some((user, role)) = auth.user_service.verify_token(token).await else {
+            return Err(AuthError::InvalidToken);
+        };
+        let user = AuthUser {
+            user_id: user.id,
+            username: user.username,
+            role,
+            via_token: true,
+        };
+        return Ok((user, None));
+    }
+
+    let Ok(token_data) = decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(SECRET_KEY.as_bytes()),
+        &Validation::default(),
+    ) else {
+        return Err(AuthError::InvalidToken);
+    };
+
+    match auth.user_service.find_user_by_name(&token_data.claims.sub).await {
+        Some(user) if user.enable && user.token_version == token_data.claims.ver => Ok((
+            AuthUser {
+                user_id: user.id,
+                username: user.username,
+                role: user.role,
+                via_token: false,
+            },
+            Some(token_data.claims),
+        )),
+        _ => Err(AuthError::UnauthorizedUser),
+    }
+}
+
 pub async fn auth_handler(
-    State(auth): State<Arc<AuthRuntimeConfig>>,
-    req: Request<axum::body::Body>,
+    State(auth): State<AuthState>,
+    mut req: Request<axum::body::Body>,
     next: Next,
 ) -> Result<Response, LandscapeApiError> {
     let Some(auth_header) =
@@ -98,39 +253,45 @@ pub async fn auth_handler(
         return Err(AuthError::InvalidAuthorizationHeaderFormat)?;
     };
 
-    let Ok(token_data) = decode::<Claims>(
-        token,
-        &DecodingKey::from_secret(SECRET_KEY.as_bytes()),
-        &Validation::default(),
-    ) else {
-        return Err(AuthError::InvalidToken)?;
-    };
+    let (user, claims) = authenticate(&auth, token).await?;
+    // 未匹配到路由时交给 404 处理
+    let read_only = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or(true, |path| auth.read_only_routes.allows(req.method(), path.as_str()));
+    if read_only {
+        req.extensions_mut().insert(ReadOnlyRoute);
+    } else if !user.role.can_write() {
+        return Err(AuthError::Forbidden)?;
+    }
 
-    if token_data.claims.sub == auth.admin_user {
-        let mut response = next.run(req).await;
-
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as usize;
-        if token_data.claims.exp.saturating_sub(now) < DEFAULT_EXPIRE_TIME / 2 {
-            if let Ok(new_token) = create_jwt(&token_data.claims.sub, DEFAULT_EXPIRE_TIME) {
-                if let Ok(value) = axum::http::HeaderValue::from_str(&new_token) {
-                    response.headers_mut().insert("X-Refresh-Token", value);
-                    response.headers_mut().append(
-                        axum::http::header::ACCESS_CONTROL_EXPOSE_HEADERS,
-                        axum::http::HeaderValue::from_static("X-Refresh-Token"),
-                    );
-                }
+    req.extensions_mut().insert(user);
+    let mut response = next.run(req).await;
+
+    // API Token 长期有效, 只刷新 JWT
+    let Some(claims) = claims else {
+        return Ok(response);
+    };
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as usize;
+    if claims.exp.saturating_sub(now) < DEFAULT_EXPIRE_TIME / 2 {
+        if let Ok(new_token) = create_jwt(&claims.sub, claims.ver, DEFAULT_EXPIRE_TIME) {
+            if let Ok(value) = axum::http::HeaderValue::from_str(&new_token) {
+                response.headers_mut().insert("X-Refresh-Token", value);
+                response.headers_mut().append(
+                    axum::http::header::ACCESS_CONTROL_EXPOSE_HEADERS,
+                    axum::http::HeaderValue::from_static("X-Refresh-Token"),
+                );
             }
         }
-
-        Ok(response)
-    } else {
-        Err(AuthError::UnauthorizedUser)?
     }
+
+    Ok(response)
 }
 
+/// WebSocket 接口 (终端, 镜像拉取, 抓包) 需要可写权限, 终端另外要求管理员
 pub async fn auth_handler_from_query(
-    State(auth): State<Arc<AuthRuntimeConfig>>,
-    req: Request<axum::body::Body>,
+    State(auth): State<AuthState>,
+    mut req: Request<axum::body::Body>,
     next: Next,
 ) -> Result<Response, LandscapeApiError> {
     let Some(query_str) = req.uri().query() else {
@@ -143,28 +304,21 @@ pub async fn auth_handler_from_query(
         return Err(AuthError::MissingAuthorizationHeader)?;
     };
 
-    let Ok(token_data) = decode::<Claims>(
-        token,
-        &DecodingKey::from_secret(SECRET_KEY.as_bytes()),
-        &Validation::default(),
-    ) else {
-        return Err(AuthError::InvalidToken)?;
-    };
-
-    if token_data.claims.sub == auth.admin_user {
-        Ok(next.run(req).await)
-    } else {
-        Err(AuthError::UnauthorizedUser)?
+    let (user, _) = authenticate(&auth, token).await?;
+    if !user.role.can_write() {
+        return Err(AuthError::Forbidden)?;
     }
+    req.extensions_mut().insert(user);
+    Ok(next.run(req).await)
 }
 
 /// Build the OpenApiRouter for auth (different state type from LandscapeApp).
 /// Used by openapi.rs to extract the spec, and by main.rs to serve.
-pub fn get_auth_openapi_router() -> OpenApiRouter<Arc<AuthRuntimeConfig>> {
+pub fn get_auth_openapi_router() -> OpenApiRouter<AuthState> {
     OpenApiRouter::new().routes(routes!(login_handler))
 }
 
-pub fn get_auth_route(auth: Arc<AuthRuntimeConfig>) -> Router {
+pub fn get_auth_route(auth: AuthState) -> Router {
     let (router, _) = get_auth_openapi_router().split_for_parts();
     router.with_state(auth)
 }
@@ -177,19 +331,78 @@ pub fn get_auth_route(auth: Arc<AuthRuntimeConfig>) -> Router {
     request_body = LoginInfo,
     responses(
         (status = 200, body = CommonApiResp<LoginResult>),
-        (status = 401, description = "Invalid credentials")
+        (status = 401, description = "Invalid credentials or two-factor code required"),
+        (status = 429, description = "Too many failed attempts")
     )
 )]
 async fn login_handler(
-    State(auth): State<Arc<AuthRuntimeConfig>>,
-    JsonBody(LoginInfo { username, password }): JsonBody<LoginInfo>,
+    State(auth): State<AuthState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    JsonBody(LoginInfo { username, password, totp_code }): JsonBody<LoginInfo>,
 ) -> LandscapeApiResult<LoginResult> {
-    let mut result = LoginResult { success: false, token: "".to_string() };
-    if username == auth.admin_user && password == auth.admin_pass {
-        result.success = true;
-        result.token = create_jwt(&username, DEFAULT_EXPIRE_TIME)?;
-    } else {
-        return Err(AuthError::InvalidUsernameOrPassword)?;
+    let source_ip = addr.ip().to_canonical().to_string();
+    let totp_code = totp_code.as_deref().filter(|code| !code.is_empty());
+    let result = check_login(&auth, &source_ip, &username, &password, totp_code).await;
+
+    // 等待输入验证码不算作失败
+    if !matches!(result, Err(AuthError::TotpRequired)) {
+        let (kind, summary) = match &result {
+            Ok(_) => (AuditLogKind::Login, String::new()),
+            Err(e) => (AuditLogKind::LoginFailed, e.to_string()),
+        };
+        auth.audit_service
+            .record(AuditLog {
+                id: gen_database_uuid(),
+                kind,
+                username: username.clone(),
+                source_ip: Some(source_ip),
+                method: "POST".to_string(),
+                route: "/auth/login".to_string(),
+                config_id: None,
+                status: result.as_ref().map_or_else(|e| e.http_status_code(), |_| 200),
+                before: None,
+                after: None,
+                summary,
+                update_at: get_f64_timestamp(),
+            })
+            .await;
     }
-    LandscapeApiResp::success(result)
+
+    let user = result?;
+    let token = create_jwt(&user.username, user.token_version, DEFAULT_EXPIRE_TIME)?;
+    LandscapeApiResp::success(LoginResult { success: true, token })
+}
+
+async fn check_login(
+    auth: &AuthState,
+    source_ip: &str,
+    username: &str,
+    password: &str,
+    totp_code: Option<&str>,
+) -> Result<LandscapeUser, AuthError> {
+    if let Some(secs) = auth.limiter.locked_for(source_ip) {
+        return Err(AuthError::TooManyAttempts(secs));
+    }
+    let delay = auth.limiter.user_delay(username).map_err(AuthError::TooManyAttempts)?;
+    if !delay.is_zero() {
+        tokio::time::sleep(delay).await;
+    }
+
+    let Some(user) = auth.user_service.verify_password(username, password).await else {
+        auth.limiter.fail(source_ip, username);
+        return Err(AuthError::InvalidUsernameOrPassword);
+    };
+
+    if user.totp_enable {
+        let Some(code) = totp_code else {
+            return Err(AuthError::TotpRequired);
+        };
+        if !auth.user_service.verify_totp(&user, code) {
+            auth.limiter.fail(source_ip, username);
+            return Err(AuthError::InvalidTotpCode);
+        }
+    }
+
+    auth.limiter.reset(source_ip, username);
+    Ok(user)
 }
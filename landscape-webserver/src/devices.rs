@@ -50,6 +50,7 @@ async fn check_iface_validity(
     post,
     path = "/validate_ip",
     tag = "Enrolled Devices",
+    security(("bearer_auth" = ["read_only"])),
     request_body = ValidateIpPayload,
     responses((status = 200, body = CommonApiResp<bool>))
 )]
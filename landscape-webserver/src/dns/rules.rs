@@ -82,6 +82,9 @@ async fn add_many_dns_rules(
     State(state): State<LandscapeApp>,
     JsonBody(dns_rules): JsonBody<Vec<DNSRuleConfig>>,
 ) -> LandscapeApiResult<()> {
+    for dns_rule in &dns_rules {
+        dns_rule.mark.validate()?;
+    }
     state.dns_rule_service.checked_set_list(dns_rules).await?;
     LandscapeApiResp::success(())
 }
@@ -97,6 +100,7 @@ async fn add_dns_rules(
     State(state): State<LandscapeApp>,
     JsonBody(dns_rule): JsonBody<DNSRuleConfig>,
 ) -> LandscapeApiResult<DNSRuleConfig> {
+    dns_rule.mark.validate()?;
     let result = state.dns_rule_service.checked_set(dns_rule).await?;
     LandscapeApiResp::success(result)
 }
@@ -0,0 +1,190 @@
+use axum::extract::{Path, State};
+use landscape_common::api_response::LandscapeApiResp as CommonApiResp;
+use landscape_common::config::ConfigId;
+use landscape_common::docker::managed::{
+    ManagedContainerConfig, ManagedContainerError, ManagedContainerStatus,
+};
+use landscape_common::service::controller::ConfigController;
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+
+use crate::api::{JsonBody, LandscapeApiResp};
+use crate::error::LandscapeApiResult;
+use crate::LandscapeApp;
+
+pub fn get_managed_container_paths() -> OpenApiRouter<LandscapeApp> {
+    OpenApiRouter::new()
+        .routes(routes!(get_managed_containers, add_managed_container))
+        .routes(routes!(get_managed_container_status))
+        .routes(routes!(get_managed_container, del_managed_container))
+        .routes(routes!(start_managed_container))
+        .routes(routes!(stop_managed_container))
+        .routes(routes!(recreate_managed_container))
+        .routes(routes!(update_managed_container))
+        .routes(routes!(check_managed_container_update))
+}
+
+#[utoipa::path(
+    get,
+    path = "/managed_containers",
+    tag = "Docker Managed Containers",
+    responses((status = 200, body = CommonApiResp<Vec<ManagedContainerConfig>>))
+)]
+async fn get_managed_containers(
+    State(state): State<LandscapeApp>,
+) -> LandscapeApiResult<Vec<ManagedContainerConfig>> {
+    let result = state.managed_container_service.list().await;
+    LandscapeApiResp::success(result)
+}
+
+#[utoipa::path(
+    get,
+    path = "/managed_containers/status",
+    tag = "Docker Managed Containers",
+    responses((status = 200, body = CommonApiResp<Vec<ManagedContainerStatus>>))
+)]
+async fn get_managed_container_status(
+    State(state): State<LandscapeApp>,
+) -> LandscapeApiResult<Vec<ManagedContainerStatus>> {
+    let result = state.managed_container_service.list_status().await;
+    LandscapeApiResp::success(result)
+}
+
+#[utoipa::path(
+    get,
+    path = "/managed_containers/{id}",
+    tag = "Docker Managed Containers",
+    params(("id" = Uuid, Path, description = "Managed container ID")),
+    responses(
+        (status = 200, body = CommonApiResp<ManagedContainerConfig>),
+        (status = 404, description = "Not found")
+    )
+)]
+async fn get_managed_container(
+    State(state): State<LandscapeApp>,
+    Path(id): Path<ConfigId>,
+) -> LandscapeApiResult<ManagedContainerConfig> {
+    let result = state.managed_container_service.find_by_id(id).await;
+    if let Some(config) = result {
+        LandscapeApiResp::success(config)
+    } else {
+        Err(ManagedContainerError::NotFound(id))?
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/managed_containers",
+    tag = "Docker Managed Containers",
+    request_body = ManagedContainerConfig,
+    responses(
+        (status = 200, body = CommonApiResp<ManagedContainerConfig>),
+        (status = 400, description = "Invalid managed container")
+    )
+)]
+async fn add_managed_container(
+    State(state): State<LandscapeApp>,
+    JsonBody(config): JsonBody<ManagedContainerConfig>,
+) -> LandscapeApiResult<ManagedContainerConfig> {
+    config.validate()?;
+    let configs = state.managed_container_service.list().await;
+    if configs.iter().any(|other| other.id != config.id && other.name == config.name) {
+        Err(ManagedContainerError::DuplicateName(config.name.clone()))?
+    }
+    let result = state.managed_container_service.checked_set(config).await?;
+    LandscapeApiResp::success(result)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/managed_containers/{id}",
+    tag = "Docker Managed Containers",
+    params(("id" = Uuid, Path, description = "Managed container ID")),
+    responses(
+        (status = 200, description = "Success"),
+        (status = 404, description = "Not found")
+    )
+)]
+async fn del_managed_container(
+    State(state): State<LandscapeApp>,
+    Path(id): Path<ConfigId>,
+) -> LandscapeApiResult<()> {
+    state.managed_container_service.delete(id).await;
+    LandscapeApiResp::success(())
+}
+
+#[utoipa::path(
+    post,
+    path = "/managed_containers/{id}/start",
+    tag = "Docker Managed Containers",
+    params(("id" = Uuid, Path, description = "Managed container ID")),
+    responses((status = 200, description = "Success"))
+)]
+async fn start_managed_container(
+    State(state): State<LandscapeApp>,
+    Path(id): Path<ConfigId>,
+) -> LandscapeApiResult<()> {
+    state.managed_container_service.start(id).await?;
+    LandscapeApiResp::success(())
+}
+
+#[utoipa::path(
+    post,
+    path = "/managed_containers/{id}/stop",
+    tag = "Docker Managed Containers",
+    params(("id" = Uuid, Path, description = "Managed container ID")),
+    responses((status = 200, description = "Success"))
+)]
+async fn stop_managed_container(
+    State(state): State<LandscapeApp>,
+    Path(id): Path<ConfigId>,
+) -> LandscapeApiResult<()> {
+    state.managed_container_service.stop(id).await?;
+    LandscapeApiResp::success(())
+}
+
+#[utoipa::path(
+    post,
+    path = "/managed_containers/{id}/recreate",
+    tag = "Docker Managed Containers",
+    params(("id" = Uuid, Path, description = "Managed container ID")),
+    responses((status = 200, description = "Success"))
+)]
+async fn recreate_managed_container(
+    State(state): State<LandscapeApp>,
+    Path(id): Path<ConfigId>,
+) -> LandscapeApiResult<()> {
+    state.managed_container_service.recreate(id, false).await?;
+    LandscapeApiResp::success(())
+}
+
+/// 拉取最新镜像后重建
+#[utoipa::path(
+    post,
+    path = "/managed_containers/{id}/update",
+    tag = "Docker Managed Containers",
+    params(("id" = Uuid, Path, description = "Managed container ID")),
+    responses((status = 200, description = "Success"))
+)]
+async fn update_managed_container(
+    State(state): State<LandscapeApp>,
+    Path(id): Path<ConfigId>,
+) -> LandscapeApiResult<()> {
+    state.managed_container_service.recreate(id, true).await?;
+    LandscapeApiResp::success(())
+}
+
+#[utoipa::path(
+    post,
+    path = "/managed_containers/{id}/check_update",
+    tag = "Docker Managed Containers",
+    params(("id" = Uuid, Path, description = "Managed container ID")),
+    responses((status = 200, body = CommonApiResp<bool>))
+)]
+async fn check_managed_container_update(
+    State(state): State<LandscapeApp>,
+    Path(id): Path<ConfigId>,
+) -> LandscapeApiResult<bool> {
+    let result = state.managed_container_service.check_update(id).await?;
+    LandscapeApiResp::success(result)
+}
@@ -25,6 +25,7 @@ use crate::{docker::error::DockerError, LandscapeApp};
 
 pub mod error;
 mod image;
+mod managed;
 mod network;
 
 pub fn get_docker_paths() -> OpenApiRouter<LandscapeApp> {
@@ -38,6 +39,7 @@ pub fn get_docker_paths() -> OpenApiRouter<LandscapeApp> {
         .routes(routes!(remove_container))
         .merge(get_docker_images_paths())
         .merge(get_docker_networks_paths())
+        .merge(managed::get_managed_container_paths())
 }
 
 #[utoipa::path(
@@ -3,18 +3,25 @@ use axum::http::StatusCode;
 use axum::response::IntoResponse;
 use axum::Json;
 use landscape_common::api_response::LandscapeApiResp as CommonLandscapeApiResp;
+use landscape_common::auth::user::UserError;
 use landscape_common::config::dns::DnsRuleError;
 use landscape_common::config::geo::{GeoIpError, GeoSiteError};
+use landscape_common::config::import::ConfigImportError;
 use landscape_common::config::nat::StaticNatError;
+use landscape_common::config::snapshot::ConfigSnapshotError;
 use landscape_common::dhcp::DhcpError;
 use landscape_common::dns::redirect::DnsRedirectError;
 use landscape_common::dns::upstream::DnsUpstreamError;
+use landscape_common::docker::managed::ManagedContainerError;
 use landscape_common::enrolled_device::EnrolledDeviceError;
 use landscape_common::error::{LdApiErrorInfo, LdError};
 use landscape_common::firewall::blacklist::FirewallBlacklistError;
 use landscape_common::firewall::FirewallRuleError;
 use landscape_common::flow::FlowRuleError;
 use landscape_common::ip_mark::DstIpRuleError;
+use landscape_common::netns_target::NetnsTargetError;
+use landscape_common::quota::TrafficQuotaError;
+use landscape_common::rate_limit::RateLimitError;
 use landscape_common::service::ServiceConfigError;
 
 use crate::api::LandscapeApiResp;
@@ -49,6 +56,20 @@ pub enum LandscapeApiError {
     #[error(transparent)]
     EnrolledDevice(#[from] EnrolledDeviceError),
     #[error(transparent)]
+    TrafficQuota(#[from] TrafficQuotaError),
+    #[error(transparent)]
+    RateLimit(#[from] RateLimitError),
+    #[error(transparent)]
+    NetnsTarget(#[from] NetnsTargetError),
+    #[error(transparent)]
+    ManagedContainer(#[from] ManagedContainerError),
+    #[error(transparent)]
+    ConfigImport(#[from] ConfigImportError),
+    #[error(transparent)]
+    ConfigSnapshot(#[from] ConfigSnapshotError),
+    #[error(transparent)]
+    User(#[from] UserError),
+    #[error(transparent)]
     ServiceConfig(#[from] ServiceConfigError),
     #[error(transparent)]
     Auth(#[from] AuthError),
@@ -79,6 +100,13 @@ impl LandscapeApiError {
             Self::StaticNat(e) => e.error_id(),
             Self::DstIpRule(e) => e.error_id(),
             Self::EnrolledDevice(e) => e.error_id(),
+            Self::TrafficQuota(e) => e.error_id(),
+            Self::RateLimit(e) => e.error_id(),
+            Self::NetnsTarget(e) => e.error_id(),
+            Self::ManagedContainer(e) => e.error_id(),
+            Self::ConfigImport(e) => e.error_id(),
+            Self::ConfigSnapshot(e) => e.error_id(),
+            Self::User(e) => e.error_id(),
             Self::ServiceConfig(e) => e.error_id(),
             Self::Auth(e) => e.error_id(),
             Self::Docker(e) => e.error_id(),
@@ -105,6 +133,13 @@ impl LandscapeApiError {
             Self::StaticNat(e) => StatusCode::from_u16(e.http_status_code()).unwrap(),
             Self::DstIpRule(e) => StatusCode::from_u16(e.http_status_code()).unwrap(),
             Self::EnrolledDevice(e) => StatusCode::from_u16(e.http_status_code()).unwrap(),
+            Self::TrafficQuota(e) => StatusCode::from_u16(e.http_status_code()).unwrap(),
+            Self::RateLimit(e) => StatusCode::from_u16(e.http_status_code()).unwrap(),
+            Self::NetnsTarget(e) => StatusCode::from_u16(e.http_status_code()).unwrap(),
+            Self::ManagedContainer(e) => StatusCode::from_u16(e.http_status_code()).unwrap(),
+            Self::ConfigImport(e) => StatusCode::from_u16(e.http_status_code()).unwrap(),
+            Self::ConfigSnapshot(e) => StatusCode::from_u16(e.http_status_code()).unwrap(),
+            Self::User(e) => StatusCode::from_u16(e.http_status_code()).unwrap(),
             Self::ServiceConfig(e) => StatusCode::from_u16(e.http_status_code()).unwrap(),
             Self::Auth(e) => StatusCode::from_u16(e.http_status_code()).unwrap(),
             Self::Docker(e) => StatusCode::from_u16(e.http_status_code()).unwrap(),
@@ -131,6 +166,13 @@ impl LandscapeApiError {
             Self::StaticNat(e) => e.error_args(),
             Self::DstIpRule(e) => e.error_args(),
             Self::EnrolledDevice(e) => e.error_args(),
+            Self::TrafficQuota(e) => e.error_args(),
+            Self::RateLimit(e) => e.error_args(),
+            Self::NetnsTarget(e) => e.error_args(),
+            Self::ManagedContainer(e) => e.error_args(),
+            Self::ConfigImport(e) => e.error_args(),
+            Self::ConfigSnapshot(e) => e.error_args(),
+            Self::User(e) => e.error_args(),
             Self::ServiceConfig(e) => e.error_args(),
             Self::Auth(e) => e.error_args(),
             Self::Docker(e) => e.error_args(),
@@ -84,6 +84,9 @@ async fn modify_dst_ip_rules(
     Path(_id): Path<ConfigId>,
     JsonBody(rule): JsonBody<WanIpRuleConfig>,
 ) -> LandscapeApiResult<WanIpRuleConfig> {
+    rule.mark.validate()?;
+    rule.validate()?;
+    state.dst_ip_rule_service.check_port_rule_limit(std::slice::from_ref(&rule)).await?;
     let result = state.dst_ip_rule_service.checked_set(rule).await?;
     LandscapeApiResp::success(result)
 }
@@ -99,6 +102,9 @@ async fn add_dst_ip_rules(
     State(state): State<LandscapeApp>,
     JsonBody(rule): JsonBody<WanIpRuleConfig>,
 ) -> LandscapeApiResult<WanIpRuleConfig> {
+    rule.mark.validate()?;
+    rule.validate()?;
+    state.dst_ip_rule_service.check_port_rule_limit(std::slice::from_ref(&rule)).await?;
     let result = state.dst_ip_rule_service.checked_set(rule).await?;
     LandscapeApiResp::success(result)
 }
@@ -114,6 +120,11 @@ async fn add_many_dst_ip_rules(
     State(state): State<LandscapeApp>,
     JsonBody(rules): JsonBody<Vec<WanIpRuleConfig>>,
 ) -> LandscapeApiResult<()> {
+    for rule in &rules {
+        rule.mark.validate()?;
+        rule.validate()?;
+    }
+    state.dst_ip_rule_service.check_port_rule_limit(&rules).await?;
     state.dst_ip_rule_service.checked_set_list(rules).await?;
     LandscapeApiResp::success(())
 }
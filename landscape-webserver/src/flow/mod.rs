@@ -1,2 +1,5 @@
 pub mod dst_ip_rules;
+pub mod netns_targets;
+pub mod quotas;
+pub mod rate_limits;
 pub mod rules;
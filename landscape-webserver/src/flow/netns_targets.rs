@@ -0,0 +1,104 @@
+use axum::extract::{Path, State};
+use landscape_common::api_response::LandscapeApiResp as CommonApiResp;
+use landscape_common::config::ConfigId;
+use landscape_common::netns_target::{NetnsTargetConfig, NetnsTargetError, NetnsTargetStatus};
+use landscape_common::service::controller::ConfigController;
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+
+use crate::api::JsonBody;
+use crate::LandscapeApp;
+use crate::{api::LandscapeApiResp, error::LandscapeApiResult};
+
+pub fn get_netns_target_config_paths() -> OpenApiRouter<LandscapeApp> {
+    OpenApiRouter::new()
+        .routes(routes!(get_netns_targets, add_netns_target))
+        .routes(routes!(get_netns_target_status))
+        .routes(routes!(get_netns_target, del_netns_target))
+}
+
+#[utoipa::path(
+    get,
+    path = "/netns_targets",
+    tag = "Namespace Targets",
+    responses((status = 200, body = CommonApiResp<Vec<NetnsTargetConfig>>))
+)]
+async fn get_netns_targets(
+    State(state): State<LandscapeApp>,
+) -> LandscapeApiResult<Vec<NetnsTargetConfig>> {
+    let result = state.netns_target_service.list().await;
+    LandscapeApiResp::success(result)
+}
+
+#[utoipa::path(
+    get,
+    path = "/netns_targets/status",
+    tag = "Namespace Targets",
+    responses((status = 200, body = CommonApiResp<Vec<NetnsTargetStatus>>))
+)]
+async fn get_netns_target_status(
+    State(state): State<LandscapeApp>,
+) -> LandscapeApiResult<Vec<NetnsTargetStatus>> {
+    let result = state.netns_target_service.list_status().await;
+    LandscapeApiResp::success(result)
+}
+
+#[utoipa::path(
+    get,
+    path = "/netns_targets/{id}",
+    tag = "Namespace Targets",
+    params(("id" = Uuid, Path, description = "Namespace target ID")),
+    responses(
+        (status = 200, body = CommonApiResp<NetnsTargetConfig>),
+        (status = 404, description = "Not found")
+    )
+)]
+async fn get_netns_target(
+    State(state): State<LandscapeApp>,
+    Path(id): Path<ConfigId>,
+) -> LandscapeApiResult<NetnsTargetConfig> {
+    let result = state.netns_target_service.find_by_id(id).await;
+    if let Some(config) = result {
+        LandscapeApiResp::success(config)
+    } else {
+        Err(NetnsTargetError::NotFound(id))?
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/netns_targets",
+    tag = "Namespace Targets",
+    request_body = NetnsTargetConfig,
+    responses(
+        (status = 200, body = CommonApiResp<NetnsTargetConfig>),
+        (status = 400, description = "Invalid namespace target")
+    )
+)]
+async fn add_netns_target(
+    State(state): State<LandscapeApp>,
+    JsonBody(config): JsonBody<NetnsTargetConfig>,
+) -> LandscapeApiResult<NetnsTargetConfig> {
+    config.validate()?;
+    state.netns_target_service.check_name(&config).await?;
+    let result = state.netns_target_service.checked_set(config).await?;
+    LandscapeApiResp::success(result)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/netns_targets/{id}",
+    tag = "Namespace Targets",
+    params(("id" = Uuid, Path, description = "Namespace target ID")),
+    responses(
+        (status = 200, description = "Success"),
+        (status = 404, description = "Not found")
+    )
+)]
+async fn del_netns_target(
+    State(state): State<LandscapeApp>,
+    Path(id): Path<ConfigId>,
+) -> LandscapeApiResult<()> {
+    state.netns_target_service.delete(id).await;
+    LandscapeApiResp::success(())
+}
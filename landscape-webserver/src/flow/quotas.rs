@@ -0,0 +1,103 @@
+use axum::extract::{Path, State};
+use landscape_common::api_response::LandscapeApiResp as CommonApiResp;
+use landscape_common::config::ConfigId;
+use landscape_common::quota::{TrafficQuotaConfig, TrafficQuotaError, TrafficQuotaStatus};
+use landscape_common::service::controller::ConfigController;
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+
+use crate::api::JsonBody;
+use crate::LandscapeApp;
+use crate::{api::LandscapeApiResp, error::LandscapeApiResult};
+
+pub fn get_traffic_quota_config_paths() -> OpenApiRouter<LandscapeApp> {
+    OpenApiRouter::new()
+        .routes(routes!(get_traffic_quotas, add_traffic_quota))
+        .routes(routes!(get_traffic_quota_status))
+        .routes(routes!(get_traffic_quota, del_traffic_quota))
+}
+
+#[utoipa::path(
+    get,
+    path = "/quotas",
+    tag = "Traffic Quotas",
+    responses((status = 200, body = CommonApiResp<Vec<TrafficQuotaConfig>>))
+)]
+async fn get_traffic_quotas(
+    State(state): State<LandscapeApp>,
+) -> LandscapeApiResult<Vec<TrafficQuotaConfig>> {
+    let result = state.traffic_quota_service.list().await;
+    LandscapeApiResp::success(result)
+}
+
+#[utoipa::path(
+    get,
+    path = "/quotas/status",
+    tag = "Traffic Quotas",
+    responses((status = 200, body = CommonApiResp<Vec<TrafficQuotaStatus>>))
+)]
+async fn get_traffic_quota_status(
+    State(state): State<LandscapeApp>,
+) -> LandscapeApiResult<Vec<TrafficQuotaStatus>> {
+    let result = state.traffic_quota_service.list_status().await;
+    LandscapeApiResp::success(result)
+}
+
+#[utoipa::path(
+    get,
+    path = "/quotas/{id}",
+    tag = "Traffic Quotas",
+    params(("id" = Uuid, Path, description = "Traffic quota ID")),
+    responses(
+        (status = 200, body = CommonApiResp<TrafficQuotaConfig>),
+        (status = 404, description = "Not found")
+    )
+)]
+async fn get_traffic_quota(
+    State(state): State<LandscapeApp>,
+    Path(id): Path<ConfigId>,
+) -> LandscapeApiResult<TrafficQuotaConfig> {
+    let result = state.traffic_quota_service.find_by_id(id).await;
+    if let Some(config) = result {
+        LandscapeApiResp::success(config)
+    } else {
+        Err(TrafficQuotaError::NotFound(id))?
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/quotas",
+    tag = "Traffic Quotas",
+    request_body = TrafficQuotaConfig,
+    responses(
+        (status = 200, body = CommonApiResp<TrafficQuotaConfig>),
+        (status = 400, description = "Invalid quota")
+    )
+)]
+async fn add_traffic_quota(
+    State(state): State<LandscapeApp>,
+    JsonBody(config): JsonBody<TrafficQuotaConfig>,
+) -> LandscapeApiResult<TrafficQuotaConfig> {
+    config.validate()?;
+    let result = state.traffic_quota_service.checked_set(config).await?;
+    LandscapeApiResp::success(result)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/quotas/{id}",
+    tag = "Traffic Quotas",
+    params(("id" = Uuid, Path, description = "Traffic quota ID")),
+    responses(
+        (status = 200, description = "Success"),
+        (status = 404, description = "Not found")
+    )
+)]
+async fn del_traffic_quota(
+    State(state): State<LandscapeApp>,
+    Path(id): Path<ConfigId>,
+) -> LandscapeApiResult<()> {
+    state.traffic_quota_service.delete(id).await;
+    LandscapeApiResp::success(())
+}
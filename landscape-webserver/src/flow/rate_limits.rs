@@ -0,0 +1,103 @@
+use axum::extract::{Path, State};
+use landscape_common::api_response::LandscapeApiResp as CommonApiResp;
+use landscape_common::config::ConfigId;
+use landscape_common::rate_limit::{RateLimitConfig, RateLimitError, RateLimitStatus};
+use landscape_common::service::controller::ConfigController;
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+
+use crate::api::JsonBody;
+use crate::LandscapeApp;
+use crate::{api::LandscapeApiResp, error::LandscapeApiResult};
+
+pub fn get_rate_limit_config_paths() -> OpenApiRouter<LandscapeApp> {
+    OpenApiRouter::new()
+        .routes(routes!(get_rate_limits, add_rate_limit))
+        .routes(routes!(get_rate_limit_status))
+        .routes(routes!(get_rate_limit, del_rate_limit))
+}
+
+#[utoipa::path(
+    get,
+    path = "/rate_limits",
+    tag = "Rate Limits",
+    responses((status = 200, body = CommonApiResp<Vec<RateLimitConfig>>))
+)]
+async fn get_rate_limits(
+    State(state): State<LandscapeApp>,
+) -> LandscapeApiResult<Vec<RateLimitConfig>> {
+    let result = state.rate_limit_service.list().await;
+    LandscapeApiResp::success(result)
+}
+
+#[utoipa::path(
+    get,
+    path = "/rate_limits/status",
+    tag = "Rate Limits",
+    responses((status = 200, body = CommonApiResp<Vec<RateLimitStatus>>))
+)]
+async fn get_rate_limit_status(
+    State(state): State<LandscapeApp>,
+) -> LandscapeApiResult<Vec<RateLimitStatus>> {
+    let result = state.rate_limit_service.list_status().await;
+    LandscapeApiResp::success(result)
+}
+
+#[utoipa::path(
+    get,
+    path = "/rate_limits/{id}",
+    tag = "Rate Limits",
+    params(("id" = Uuid, Path, description = "Rate limit ID")),
+    responses(
+        (status = 200, body = CommonApiResp<RateLimitConfig>),
+        (status = 404, description = "Not found")
+    )
+)]
+async fn get_rate_limit(
+    State(state): State<LandscapeApp>,
+    Path(id): Path<ConfigId>,
+) -> LandscapeApiResult<RateLimitConfig> {
+    let result = state.rate_limit_service.find_by_id(id).await;
+    if let Some(config) = result {
+        LandscapeApiResp::success(config)
+    } else {
+        Err(RateLimitError::NotFound(id))?
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/rate_limits",
+    tag = "Rate Limits",
+    request_body = RateLimitConfig,
+    responses(
+        (status = 200, body = CommonApiResp<RateLimitConfig>),
+        (status = 400, description = "Invalid rate limit")
+    )
+)]
+async fn add_rate_limit(
+    State(state): State<LandscapeApp>,
+    JsonBody(config): JsonBody<RateLimitConfig>,
+) -> LandscapeApiResult<RateLimitConfig> {
+    config.validate()?;
+    let result = state.rate_limit_service.checked_set(config).await?;
+    LandscapeApiResp::success(result)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/rate_limits/{id}",
+    tag = "Rate Limits",
+    params(("id" = Uuid, Path, description = "Rate limit ID")),
+    responses(
+        (status = 200, description = "Success"),
+        (status = 404, description = "Not found")
+    )
+)]
+async fn del_rate_limit(
+    State(state): State<LandscapeApp>,
+    Path(id): Path<ConfigId>,
+) -> LandscapeApiResult<()> {
+    state.rate_limit_service.delete(id).await;
+    LandscapeApiResp::success(())
+}
@@ -6,6 +6,7 @@ use landscape_common::{config::FlowId, service::controller::ConfigController};
 use utoipa_axum::router::OpenApiRouter;
 use utoipa_axum::routes;
 
+use landscape_common::flow::health::FlowHealthStatus;
 use landscape_common::flow::FlowRuleError;
 
 use crate::api::JsonBody;
@@ -17,6 +18,20 @@ pub fn get_flow_rule_config_paths() -> OpenApiRouter<LandscapeApp> {
         .routes(routes!(get_flow_rules, add_flow_rule))
         .routes(routes!(get_flow_rule, del_flow_rule))
         .routes(routes!(get_flow_rule_by_flow_id))
+        .routes(routes!(get_flow_health_status))
+}
+
+#[utoipa::path(
+    get,
+    path = "/rules/health",
+    tag = "Flow Rules",
+    responses((status = 200, body = CommonApiResp<Vec<FlowHealthStatus>>))
+)]
+async fn get_flow_health_status(
+    State(state): State<LandscapeApp>,
+) -> LandscapeApiResult<Vec<FlowHealthStatus>> {
+    let result = state.flow_health_service.list_status().await;
+    LandscapeApiResp::success(result)
 }
 
 #[utoipa::path(
@@ -93,8 +108,20 @@ async fn add_flow_rule(
             if !seen.insert(&rule.mode) {
                 Err(FlowRuleError::DuplicateEntryRule(rule.mode.to_string()))?;
             }
+            rule.mode.validate()?;
+            if let Some(qos) = &rule.qos {
+                qos.validate()?;
+            }
         }
     }
+    for target in &flow_rule.flow_targets {
+        target.validate()?;
+    }
+    if let Some(health_check) = &flow_rule.health_check {
+        health_check.validate()?;
+    }
+
+    state.flow_rule_service.check_entry_ifaces(&flow_rule).await?;
 
     // Check for overlap with other flows' entry rules via DB query
     for rule in &flow_rule.flow_match_rules {
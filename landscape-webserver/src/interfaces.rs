@@ -1,13 +1,15 @@
 use axum::extract::{Path, State};
 use landscape_common::api_response::LandscapeApiResp as CommonApiResp;
 use landscape_common::iface::{IfaceTopology, IfacesInfo};
+use landscape_common::service::controller::ControllerService;
+use landscape_common::service::ServiceConfigError;
 use landscape_common::{
     config::iface::WifiMode,
     iface::{AddController, ChangeZone},
 };
 use landscape_common::{
     config::iface::{IfaceCpuSoftBalance, NetworkIfaceConfig},
-    iface::BridgeCreate,
+    iface::{BridgeCreate, VirtualDevCreate},
 };
 use utoipa_axum::router::OpenApiRouter;
 use utoipa_axum::routes;
@@ -23,6 +25,8 @@ pub fn get_iface_paths() -> OpenApiRouter<LandscapeApp> {
         .routes(routes!(manage_ifaces))
         .routes(routes!(create_bridge))
         .routes(routes!(delete_bridge))
+        .routes(routes!(create_virtual_dev))
+        .routes(routes!(delete_virtual_dev))
         .routes(routes!(set_controller))
         .routes(routes!(change_zone))
         .routes(routes!(change_dev_status))
@@ -119,6 +123,46 @@ async fn delete_bridge(
     LandscapeApiResp::success(())
 }
 
+#[utoipa::path(
+    post,
+    path = "/virtual_dev",
+    tag = "Interfaces",
+    operation_id = "create_virtual_dev",
+    request_body = VirtualDevCreate,
+    responses((status = 200, description = "Success"))
+)]
+async fn create_virtual_dev(
+    State(state): State<LandscapeApp>,
+    JsonBody(create_request): JsonBody<VirtualDevCreate>,
+) -> LandscapeApiResult<()> {
+    create_request.params.validate(&create_request.name)?;
+    state.iface_config_service.create_virtual_dev(create_request).await?;
+    LandscapeApiResp::success(())
+}
+
+#[utoipa::path(
+    delete,
+    path = "/virtual_dev/{dev_name}",
+    tag = "Interfaces",
+    operation_id = "delete_virtual_dev",
+    params(("dev_name" = String, Path, description = "VLAN / Bond / MACVLAN device name")),
+    responses(
+        (status = 200, description = "Success"),
+        (status = 404, description = "Not found")
+    )
+)]
+async fn delete_virtual_dev(
+    State(state): State<LandscapeApp>,
+    Path(dev_name): Path<String>,
+) -> LandscapeApiResult<()> {
+    state.remove_all_iface_service(&dev_name).await;
+    state.wireguard_service.delete_and_stop_iface_service(dev_name.clone()).await;
+    if !state.iface_config_service.delete_virtual_dev(dev_name.clone()).await {
+        return Err(ServiceConfigError::IfaceNotFound { iface_name: dev_name }.into());
+    }
+    LandscapeApiResp::success(())
+}
+
 #[utoipa::path(
     post,
     path = "/controller",
@@ -148,8 +192,10 @@ async fn change_zone(
     State(state): State<LandscapeApp>,
     JsonBody(change_zone): JsonBody<ChangeZone>,
 ) -> LandscapeApiResult<()> {
-    state.remove_all_iface_service(&change_zone.iface_name).await;
+    let iface_name = change_zone.iface_name.clone();
+    state.remove_all_iface_service(&iface_name).await;
     state.iface_config_service.change_zone(change_zone).await;
+    state.wireguard_service.restart_iface_service(iface_name).await;
     LandscapeApiResp::success(())
 }
 
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, net::SocketAddr, path::PathBuf, sync::Arc, time::Duration};
+use std::{collections::HashMap, net::SocketAddr, path::PathBuf, time::Duration};
 
 use axum::{
     handler::HandlerWithoutStateExt, http::StatusCode, response::IntoResponse, routing::get, Router,
@@ -17,12 +17,16 @@ use landscape::{
         dst_ip_rule::DstIpRuleService,
         firewall_blacklist::FirewallBlacklistService,
         firewall_rule::FirewallRuleService,
+        flow_health::FlowHealthService,
         flow_rule::FlowRuleService,
         geo_ip_service::GeoIpService,
         geo_site_service::GeoSiteService,
+        netns_target::NetnsTargetService,
+        rate_limit::RateLimitService,
         static_nat_mapping::StaticNatMappingService,
+        traffic_quota::TrafficQuotaService,
     },
-    docker::LandscapeDockerService,
+    docker::{managed::ManagedContainerService, LandscapeDockerService},
     firewall::FirewallServiceManagerService,
     iface::IfaceManagerService,
     metric::MetricService,
@@ -34,17 +38,23 @@ use landscape::{
         ra::IPV6RAManagerService, route_lan::RouteLanServiceManagerService,
         route_wan::RouteWanServiceManagerService,
     },
+    sqm::SqmServiceManagerService,
     sys_service::{
-        config_service::LandscapeConfigService, dns_service::LandscapeDnsService,
-        ebpf_service::LandscapeEbpfService,
+        audit_service::AuditLogService, config_service::LandscapeConfigService,
+        dns_service::LandscapeDnsService, ebpf_service::LandscapeEbpfService,
+        snapshot_service::ConfigSnapshotService, user_service::LandscapeUserService,
     },
-    wifi::WifiServiceManagerService,
+    wifi::{station::WifiStationServiceManagerService, WifiServiceManagerService},
+    wireguard::WireGuardServiceManagerService,
 };
 use landscape_common::config::route_lan::RouteLanServiceConfig;
 use landscape_common::dhcp::v4_server::config::DHCPv4ServiceConfig;
 use landscape_common::{
     args::{LandscapeAction, LAND_ARGS, LAND_HOME_PATH},
-    config::RuntimeConfig,
+    config::{
+        import::{ConfigImportError, ConfigImportMode, ConfigImportResult, ConfigSectionDiff},
+        InitConfig, RuntimeConfig,
+    },
     error::LdResult,
     ipv6_pd::IAPrefixMap,
     service::controller::ControllerService,
@@ -90,12 +100,19 @@ pub struct LandscapeApp {
     pub dns_service: LandscapeDnsService,
     pub dns_rule_service: DNSRuleService,
     pub flow_rule_service: FlowRuleService,
+    pub flow_health_service: FlowHealthService,
+    pub netns_target_service: NetnsTargetService,
     pub geo_site_service: GeoSiteService,
     pub fire_wall_rule_service: FirewallRuleService,
     pub firewall_blacklist_service: FirewallBlacklistService,
     pub dst_ip_rule_service: DstIpRuleService,
     pub geo_ip_service: GeoIpService,
+    pub traffic_quota_service: TrafficQuotaService,
+    pub rate_limit_service: RateLimitService,
     pub config_service: LandscapeConfigService,
+    pub config_snapshot_service: ConfigSnapshotService,
+    pub user_service: LandscapeUserService,
+    pub audit_service: AuditLogService,
 
     pub dhcp_v4_server_service: DHCPv4ServerManagerService,
 
@@ -112,6 +129,7 @@ pub struct LandscapeApp {
     /// Iface IP Service
     wan_ip_service: IfaceIpServiceManagerService,
     docker_service: LandscapeDockerService,
+    pub managed_container_service: ManagedContainerService,
 
     /// pppd service
     pppd_service: PPPDServiceConfigManagerService,
@@ -130,8 +148,11 @@ pub struct LandscapeApp {
 
     /// Mss Clamp Service
     mss_clamp_service: MssClampServiceManagerService,
+    sqm_service: SqmServiceManagerService,
     firewall_service: FirewallServiceManagerService,
     wifi_service: WifiServiceManagerService,
+    wifi_station_service: WifiStationServiceManagerService,
+    wireguard_service: WireGuardServiceManagerService,
     nat_service: NatServiceManagerService,
 
     ebpf_service: LandscapeEbpfService,
@@ -143,7 +164,7 @@ impl LandscapeApp {
         &self,
         config: &C,
     ) -> Result<(), landscape_common::service::ServiceConfigError> {
-        use landscape_common::config::iface::{IfaceZoneType, ZoneRequirement};
+        use landscape_common::config::iface::ZoneRequirement;
         use landscape_common::service::ServiceConfigError;
 
         let iface_name = config.iface_name();
@@ -177,19 +198,7 @@ impl LandscapeApp {
                 || ServiceConfigError::IfaceNotFound { iface_name: iface_name.to_string() },
             )?;
 
-        let allowed = match requirement {
-            ZoneRequirement::WanOnly | ZoneRequirement::WanOrPpp => {
-                matches!(iface_config.zone_type, IfaceZoneType::Wan)
-            }
-            ZoneRequirement::LanOnly => {
-                matches!(iface_config.zone_type, IfaceZoneType::Lan)
-            }
-            ZoneRequirement::WanOrLan => {
-                matches!(iface_config.zone_type, IfaceZoneType::Wan | IfaceZoneType::Lan)
-            }
-        };
-
-        if allowed {
+        if requirement.allows(&iface_config.zone_type) {
             Ok(())
         } else {
             Err(ServiceConfigError::ZoneMismatch {
@@ -201,6 +210,7 @@ impl LandscapeApp {
 
     pub(crate) async fn remove_all_iface_service(&self, iface_name: &str) {
         self.mss_clamp_service.delete_and_stop_iface_service(iface_name.to_string()).await;
+        self.sqm_service.delete_and_stop_iface_service(iface_name.to_string()).await;
         self.wan_ip_service.delete_and_stop_iface_service(iface_name.to_string()).await;
         self.firewall_service.delete_and_stop_iface_service(iface_name.to_string()).await;
         self.nat_service.delete_and_stop_iface_service(iface_name.to_string()).await;
@@ -209,9 +219,113 @@ impl LandscapeApp {
         self.dhcp_v4_server_service.delete_and_stop_iface_service(iface_name.to_string()).await;
         self.ipv6_ra_service.delete_and_stop_iface_service(iface_name.to_string()).await;
         self.route_lan_service.delete_and_stop_iface_service(iface_name.to_string()).await;
+        self.wifi_station_service.delete_and_stop_iface_service(iface_name.to_string()).await;
         self.pppd_service.stop_pppds_by_attach_iface_name(iface_name.to_string()).await;
     }
 
+    /// 校验导入内容并计算差异, 非 dry run 且存在差异时写入数据库并重新加载服务;
+    /// 重新加载失败时恢复导入前的配置
+    pub(crate) async fn apply_init_config(
+        &self,
+        incoming: InitConfig,
+        mode: ConfigImportMode,
+        dry_run: bool,
+    ) -> Result<ConfigImportResult, error::LandscapeApiError> {
+        let current = self.config_service.export_init_config().await;
+        current.apply_import(incoming.clone(), mode).validate()?;
+
+        let sections = current.diff(&incoming, mode);
+        if !dry_run && !sections.is_empty() {
+            self.config_service.import_init_config(incoming, mode).await?;
+            if let Err(failed) = self.reload_imported_config(current.clone(), &sections).await {
+                tracing::error!("reload {failed} after import failed, restoring previous config");
+                let imported = self.config_service.export_init_config().await;
+                let revert = imported.diff(&current, ConfigImportMode::Replace);
+                self.config_service.import_init_config(current, ConfigImportMode::Replace).await?;
+                if let Err(e) = self.reload_imported_config(imported, &revert).await {
+                    tracing::error!("reload {e} while restoring previous config failed");
+                }
+                return Err(ConfigImportError::ReloadFailed(failed).into());
+            }
+        }
+        Ok(ConfigImportResult { mode, dry_run, sections })
+    }
+
+    /// 配置导入写入数据库后, 按依赖顺序重新加载存在差异的分区:
+    /// 网卡 -> 链路与地址 -> WAN 侧服务 -> LAN 侧服务 -> 规则类配置
+    /// 服务启动失败时返回 `分区/服务名`
+    pub(crate) async fn reload_imported_config(
+        &self,
+        old: InitConfig,
+        sections: &[ConfigSectionDiff],
+    ) -> Result<(), String> {
+        use landscape_common::service::controller::ConfigController;
+
+        let diff = |section: &str| sections.iter().find(|diff| diff.section == section);
+
+        macro_rules! reload_services {
+            ( $( $field:ident => $service:ident ),* $(,)? ) => {
+                $(
+                    if let Some(diff) = diff(stringify!($field)) {
+                        self.$service
+                            .reload_from_repository(diff)
+                            .await
+                            .map_err(|name| format!("{}/{name}", stringify!($field)))?;
+                    }
+                )*
+            };
+        }
+
+        macro_rules! reload_configs {
+            ( $( $field:ident => $service:ident ),* $(,)? ) => {
+                $(
+                    if diff(stringify!($field)).is_some() {
+                        self.$service.reload_from_repository(old.$field).await;
+                    }
+                )*
+            };
+        }
+
+        if diff("ifaces").is_some() {
+            self.iface_config_service.reapply_all().await;
+        }
+
+        reload_services!(
+            pppds => pppd_service,
+            ipconfigs => wan_ip_service,
+            wifi_configs => wifi_service,
+            wifi_stations => wifi_station_service,
+            wireguards => wireguard_service,
+            dhcpv6pds => ipv6_pd_service,
+            firewalls => firewall_service,
+            nats => nat_service,
+            mss_clamps => mss_clamp_service,
+            sqms => sqm_service,
+            route_wans => route_wan_service,
+            dhcpv4_services => dhcp_v4_server_service,
+            icmpras => ipv6_ra_service,
+            route_lans => route_lan_service,
+        );
+
+        reload_configs!(
+            geo_ips => geo_ip_service,
+            geo_sites => geo_site_service,
+            dns_upstream_configs => dns_upstream_service,
+            dns_rules => dns_rule_service,
+            dns_redirects => dns_redirect_service,
+            flow_rules => flow_rule_service,
+            netns_targets => netns_target_service,
+            managed_containers => managed_container_service,
+            dst_ip_mark => dst_ip_rule_service,
+            firewall_rules => fire_wall_rule_service,
+            firewall_blacklists => firewall_blacklist_service,
+            static_nat_mappings => static_nat_mapping_config_service,
+            traffic_quotas => traffic_quota_service,
+            rate_limits => rate_limit_service,
+        );
+        Ok(())
+    }
+
     pub async fn shutdown(&self) {
         tracing::info!("Shutting down all services...");
 
@@ -219,6 +333,7 @@ impl LandscapeApp {
         //     tracing::info!("Debug mode: keeping WAN IP and DHCP v4 services alive");
         tokio::join!(
             self.mss_clamp_service.get_service().stop_all(),
+            self.sqm_service.get_service().stop_all(),
             self.firewall_service.get_service().stop_all(),
             self.nat_service.get_service().stop_all(),
             self.route_wan_service.get_service().stop_all(),
@@ -227,6 +342,8 @@ impl LandscapeApp {
             self.ipv6_ra_service.get_service().stop_all(),
             self.pppd_service.get_service().stop_all(),
             self.wifi_service.get_service().stop_all(),
+            self.wifi_station_service.get_service().stop_all(),
+            self.wireguard_service.get_service().stop_all(),
         );
         // } else {
         //     tokio::join!(
@@ -293,13 +410,14 @@ async fn run(home_path: PathBuf, config: RuntimeConfig) -> LdResult<()> {
         db_store_provider.clone(),
         dns_service_tx.clone(),
         route_service_tx.clone(),
+        dev_obs.resubscribe(),
     )
     .await;
 
     let dns_redirect_service =
         DNSRedirectService::new(db_store_provider.clone(), dns_service_tx.clone()).await;
 
-    let metric_service = MetricService::new(home_path.clone(), config.metric.clone()).await;
+    let metric_service = MetricService::new(config.metric.clone()).await;
 
     let dns_service = LandscapeDnsService::new(
         dns_service_rx,
@@ -330,8 +448,16 @@ async fn run(home_path: PathBuf, config: RuntimeConfig) -> LdResult<()> {
 
     let config_service =
         LandscapeConfigService::new(config.clone(), db_store_provider.clone()).await;
+    let config_snapshot_service =
+        ConfigSnapshotService::new(config_service.clone(), db_store_provider.clone()).await;
+    let user_service = LandscapeUserService::new(db_store_provider.clone(), &config.auth).await;
+    let audit_service = AuditLogService::new(db_store_provider.clone());
 
     let route_service = IpRouteService::new(route_service_rx, db_store_provider.flow_rule_store());
+    let flow_health_service =
+        FlowHealthService::new(db_store_provider.clone(), route_service.clone());
+    let netns_target_service =
+        NetnsTargetService::new(db_store_provider.clone(), route_service.clone()).await;
     let ebpf_service = LandscapeEbpfService::new();
 
     let static_nat_mapping_config_service =
@@ -339,6 +465,15 @@ async fn run(home_path: PathBuf, config: RuntimeConfig) -> LdResult<()> {
 
     let enrolled_device_service = EnrolledDeviceService::new(db_store_provider.clone()).await;
 
+    let traffic_quota_service = TrafficQuotaService::new(
+        db_store_provider.clone(),
+        metric_service.data.traffic_metric.subscribe(),
+        flow_rule_service.subscribe_updated(),
+    )
+    .await;
+
+    let rate_limit_service = RateLimitService::new(db_store_provider.clone()).await;
+
     let route_lan_service = RouteLanServiceManagerService::new(
         db_store_provider.clone(),
         route_service.clone(),
@@ -351,6 +486,9 @@ async fn run(home_path: PathBuf, config: RuntimeConfig) -> LdResult<()> {
     let mss_clamp_service =
         MssClampServiceManagerService::new(db_store_provider.clone(), dev_obs.resubscribe()).await;
 
+    let sqm_service =
+        SqmServiceManagerService::new(db_store_provider.clone(), dev_obs.resubscribe()).await;
+
     let firewall_service =
         FirewallServiceManagerService::new(db_store_provider.clone(), dev_obs.resubscribe()).await;
 
@@ -375,7 +513,24 @@ async fn run(home_path: PathBuf, config: RuntimeConfig) -> LdResult<()> {
     )
     .await;
 
+    let wifi_station_service =
+        WifiStationServiceManagerService::new(wan_ip_service.clone(), db_store_provider.clone())
+            .await;
+
+    let wireguard_service = WireGuardServiceManagerService::new(
+        route_service.clone(),
+        db_store_provider.clone(),
+        dev_obs.resubscribe(),
+    )
+    .await;
+
     let docker_service = LandscapeDockerService::new(home_path.clone(), route_service.clone());
+    let managed_container_service = ManagedContainerService::new(
+        db_store_provider.clone(),
+        route_service.clone(),
+        home_path.clone(),
+    )
+    .await;
 
     let pppd_service =
         PPPDServiceConfigManagerService::new(db_store_provider.clone(), route_service.clone())
@@ -405,12 +560,19 @@ async fn run(home_path: PathBuf, config: RuntimeConfig) -> LdResult<()> {
         dns_service,
         dns_rule_service,
         flow_rule_service,
+        flow_health_service,
+        netns_target_service,
         geo_site_service,
         fire_wall_rule_service,
         firewall_blacklist_service,
         dst_ip_rule_service,
         geo_ip_service,
+        traffic_quota_service,
+        rate_limit_service,
         config_service,
+        config_snapshot_service,
+        user_service: user_service.clone(),
+        audit_service: audit_service.clone(),
         metric_service,
         route_service,
         dhcp_v4_server_service,
@@ -420,6 +582,7 @@ async fn run(home_path: PathBuf, config: RuntimeConfig) -> LdResult<()> {
         route_wan_service,
 
         docker_service,
+        managed_container_service,
 
         pppd_service,
 
@@ -431,13 +594,18 @@ async fn run(home_path: PathBuf, config: RuntimeConfig) -> LdResult<()> {
         dns_upstream_service,
         iface_config_service,
         mss_clamp_service,
+        sqm_service,
         firewall_service,
         wifi_service,
+        wifi_station_service,
+        wireguard_service,
         nat_service,
         // ebpf
         ebpf_service,
         enrolled_device_service,
     };
+    // 重启前未确认的修改继续计时, 超时后回滚
+    system::config_snapshot::resume_pending_confirms(landscape_app_status.clone()).await;
 
     // 初始化结束
     let tls_config = load_or_generate_cert(home_path.clone()).await;
@@ -451,11 +619,19 @@ async fn run(home_path: PathBuf, config: RuntimeConfig) -> LdResult<()> {
 
     let serve_dir = ServeDir::new(&config.web.web_root).not_found_service(service);
 
-    let auth_share = Arc::new(config.auth.clone());
-    auth::output_sys_token(&config.auth).await;
+    let openapi = openapi::build_full_openapi_spec();
+    let auth_share = auth::AuthState {
+        user_service,
+        audit_service,
+        limiter: auth::lockout::LoginLimiter::default(),
+        read_only_routes: auth::ReadOnlyRoutes::from_openapi(&openapi),
+    };
+    auth::output_sys_token(&config.auth, &auth_share.user_service).await;
     // Build OpenApiRouter for each domain, then split into plain Router + discard local spec
     let (interfaces_router, _) = openapi::build_interfaces_openapi_router().split_for_parts();
-    let (system_router, _) = openapi::build_system_openapi_router().split_for_parts();
+    let (sys_config_router, _) = system::config::get_sys_config_paths().split_for_parts();
+    let (sys_account_router, _) =
+        system::users::get_user_paths().merge(system::audit::get_audit_paths()).split_for_parts();
     let (services_router, _) = openapi::build_services_openapi_router().split_for_parts();
     let (dns_router, _) = openapi::build_dns_openapi_router().split_for_parts();
     let (firewall_router, _) = openapi::build_firewall_openapi_router().split_for_parts();
@@ -465,12 +641,19 @@ async fn run(home_path: PathBuf, config: RuntimeConfig) -> LdResult<()> {
     let (devices_router, _) = openapi::build_devices_openapi_router().split_for_parts();
     let (docker_router, _) = openapi::build_docker_openapi_router().split_for_parts();
     let (metrics_router, _) = openapi::build_metrics_openapi_router().split_for_parts();
-    let openapi = openapi::build_full_openapi_spec();
 
-    // /system combines two routers with different state types:
-    // - system_router (LandscapeApp state): /config/...
+    // 只在修改配置的路由上记录配置快照
+    let config_snapshot_layer = axum::middleware::from_fn_with_state(
+        landscape_app_status.clone(),
+        system::config_snapshot::config_snapshot_layer,
+    );
+
+    // /system combines routers with different state types:
+    // - sys_config_router / sys_account_router (LandscapeApp state): /config/..., /users/...
     // - sysinfo (WatchResource state): /info/...
-    let system_combined = system_router
+    let system_combined = sys_config_router
+        .route_layer(config_snapshot_layer.clone())
+        .merge(sys_account_router)
         .with_state(landscape_app_status.clone())
         .merge(system::info::get_sys_info_route());
 
@@ -484,15 +667,25 @@ async fn run(home_path: PathBuf, config: RuntimeConfig) -> LdResult<()> {
         .nest("/nat", nat_router)
         .nest("/geo", geo_router)
         .nest("/devices", devices_router)
+        .route_layer(config_snapshot_layer)
         .nest("/docker", docker_router)
         .nest("/metrics", metrics_router)
         .with_state(landscape_app_status.clone())
         .nest("/system", system_combined)
+        .route_layer(axum::middleware::from_fn_with_state(
+            landscape_app_status.clone(),
+            system::audit::audit_layer,
+        ))
         .route_layer(axum::middleware::from_fn_with_state(auth_share.clone(), auth::auth_handler));
 
     // /api/ws — WebSocket routes (query string token auth)
     let ws_route = Router::new()
-        .nest("/docker", websocket::docker_task::get_docker_images_socks_paths().await)
+        .nest(
+            "/docker",
+            websocket::docker_task::get_docker_images_socks_paths()
+                .await
+                .merge(websocket::container_log::get_container_log_socks_paths().await),
+        )
         .nest("/pty", websocket::web_pty::get_web_pty_socks_paths().await)
         .with_state(landscape_app_status.clone())
         .merge(dump::get_tump_router())
@@ -545,8 +738,18 @@ async fn run(home_path: PathBuf, config: RuntimeConfig) -> LdResult<()> {
 </body>
 </html>"#,
         ));
+    if let Some(addr) = config.metric.prometheus_listen {
+        tokio::spawn(metrics::prometheus::serve_prometheus_metrics(
+            addr,
+            landscape_app_status.clone(),
+        ));
+    }
+    let prometheus_route =
+        metrics::prometheus::get_prometheus_route().with_state(landscape_app_status.clone());
+
     let app = Router::new()
         .nest("/api", api_route)
+        .merge(prometheus_route)
         // .nest("/sock", sockets_route)
         .route("/foo", get(|| async { "Hi from /foo" }))
         .fallback_service(serve_dir)
@@ -555,7 +758,7 @@ async fn run(home_path: PathBuf, config: RuntimeConfig) -> LdResult<()> {
     let server_handle = axum_server::Handle::new();
     let server = axum_server::bind_rustls(addr, RustlsConfig::from_config(tls_config.into()))
         .handle(server_handle.clone())
-        .serve(app.into_make_service());
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>());
 
     tokio::select! {
         result = server => {
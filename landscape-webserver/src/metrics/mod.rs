@@ -1,13 +1,19 @@
 use axum::extract::{Query, State};
 use landscape_common::api_response::LandscapeApiResp as CommonApiResp;
+use landscape_common::error::LdError;
 use landscape_common::metric::connect::{
     ConnectGlobalStats, ConnectHistoryQueryParams, ConnectHistoryStatus, ConnectMetricPoint,
-    ConnectRealtimeStatus, IpHistoryStat, IpRealtimeStat, MetricChartRequest,
+    ConnectRealtimeStatus, DomainHistoryStat, IpHistoryStat, IpRealtimeStat, MetricChartRequest,
 };
 use landscape_common::metric::dns::{
     DnsHistoryQueryParams, DnsHistoryResponse, DnsLightweightSummaryResponse,
     DnsSummaryQueryParams, DnsSummaryResponse,
 };
+use landscape_common::metric::traffic::{
+    TrafficHistoryQueryParams, TrafficMonthlyQueryParams, TrafficMonthlyReport, TrafficPoint,
+    TrafficTopQueryParams, TrafficTotal,
+};
+use landscape_common::service::controller::ControllerService;
 use landscape_common::service::ServiceStatus;
 use utoipa_axum::router::OpenApiRouter;
 use utoipa_axum::routes;
@@ -17,6 +23,8 @@ use crate::error::LandscapeApiResult;
 
 use crate::LandscapeApp;
 
+pub mod prometheus;
+
 pub fn get_metric_paths() -> OpenApiRouter<LandscapeApp> {
     OpenApiRouter::new()
         .routes(routes!(get_metric_status))
@@ -28,9 +36,13 @@ pub fn get_metric_paths() -> OpenApiRouter<LandscapeApp> {
         .routes(routes!(get_dst_ip_stats))
         .routes(routes!(get_history_src_ip_stats))
         .routes(routes!(get_history_dst_ip_stats))
+        .routes(routes!(get_history_domain_stats))
         .routes(routes!(get_dns_history))
         .routes(routes!(get_dns_summary))
         .routes(routes!(get_dns_lightweight_summary))
+        .routes(routes!(get_traffic_history))
+        .routes(routes!(get_traffic_top_talkers))
+        .routes(routes!(get_traffic_monthly))
 }
 
 #[utoipa::path(
@@ -63,6 +75,7 @@ async fn get_connects_info(
     post,
     path = "/connections/chart",
     tag = "Metric",
+    security(("bearer_auth" = ["read_only"])),
     operation_id = "get_connect_metric_info",
     request_body = MetricChartRequest,
     responses((status = 200, body = CommonApiResp<Vec<ConnectMetricPoint>>))
@@ -166,6 +179,22 @@ async fn get_history_dst_ip_stats(
     LandscapeApiResp::success(data)
 }
 
+#[utoipa::path(
+    get,
+    path = "/connections/history/domain_stats",
+    tag = "Metric",
+    operation_id = "get_history_domain_stats",
+    params(ConnectHistoryQueryParams),
+    responses((status = 200, body = CommonApiResp<Vec<DomainHistoryStat>>))
+)]
+async fn get_history_domain_stats(
+    State(state): State<LandscapeApp>,
+    Query(params): Query<ConnectHistoryQueryParams>,
+) -> LandscapeApiResult<Vec<DomainHistoryStat>> {
+    let data = state.metric_service.data.connect_metric.history_domain_stats(params).await;
+    LandscapeApiResp::success(data)
+}
+
 #[utoipa::path(
     get,
     path = "/dns/history",
@@ -213,3 +242,60 @@ async fn get_dns_lightweight_summary(
     let data = state.metric_service.data.dns_metric.get_dns_lightweight_summary(params).await;
     LandscapeApiResp::success(data)
 }
+
+#[utoipa::path(
+    get,
+    path = "/traffic/history",
+    tag = "Metric",
+    operation_id = "get_traffic_history",
+    params(TrafficHistoryQueryParams),
+    responses((status = 200, body = CommonApiResp<Vec<TrafficPoint>>))
+)]
+async fn get_traffic_history(
+    State(state): State<LandscapeApp>,
+    Query(params): Query<TrafficHistoryQueryParams>,
+) -> LandscapeApiResult<Vec<TrafficPoint>> {
+    let data = state.metric_service.data.traffic_metric.query_history(params).await;
+    LandscapeApiResp::success(data)
+}
+
+#[utoipa::path(
+    get,
+    path = "/traffic/top",
+    tag = "Metric",
+    operation_id = "get_traffic_top_talkers",
+    params(TrafficTopQueryParams),
+    responses((status = 200, body = CommonApiResp<Vec<TrafficTotal>>))
+)]
+async fn get_traffic_top_talkers(
+    State(state): State<LandscapeApp>,
+    Query(params): Query<TrafficTopQueryParams>,
+) -> LandscapeApiResult<Vec<TrafficTotal>> {
+    let devices = state.enrolled_device_service.list().await;
+    let data = state.metric_service.data.traffic_metric.query_top(params, &devices).await;
+    LandscapeApiResp::success(data)
+}
+
+#[utoipa::path(
+    get,
+    path = "/traffic/monthly",
+    tag = "Metric",
+    operation_id = "get_traffic_monthly",
+    params(TrafficMonthlyQueryParams),
+    responses((status = 200, body = CommonApiResp<TrafficMonthlyReport>))
+)]
+async fn get_traffic_monthly(
+    State(state): State<LandscapeApp>,
+    Query(params): Query<TrafficMonthlyQueryParams>,
+) -> LandscapeApiResult<TrafficMonthlyReport> {
+    let month = params.month.clone();
+    let devices = state.enrolled_device_service.list().await;
+    let data = state
+        .metric_service
+        .data
+        .traffic_metric
+        .query_monthly(params, &devices)
+        .await
+        .ok_or_else(|| LdError::ConfigError(format!("invalid month: {month:?}")))?;
+    LandscapeApiResp::success(data)
+}
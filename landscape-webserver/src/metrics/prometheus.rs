@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+
+use axum::extract::State;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use landscape::get_all_devices;
+use landscape::metric::prometheus::{l4_proto_name, read_iface_traffic_stats, PrometheusText};
+use landscape_common::config::iface::IfaceZoneType;
+use landscape_common::database::LandscapeStore;
+use landscape_common::service::controller::{ConfigController, ControllerService};
+use landscape_common::service::{ServiceStatus, WatchService};
+use subtle::ConstantTimeEq;
+
+use crate::LandscapeApp;
+
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// 挂载在 Web 端口上的 `/metrics`, 必须配置令牌后才可访问
+pub fn get_prometheus_route() -> Router<LandscapeApp> {
+    Router::new().route("/metrics", get(metrics_with_token))
+}
+
+/// 在单独的地址上提供 `/metrics`, 配置了令牌时同样需要校验
+pub async fn serve_prometheus_metrics(addr: SocketAddr, state: LandscapeApp) {
+    let app = Router::new().route("/metrics", get(metrics_on_listener)).with_state(state);
+    let listener = match tokio::net::TcpListener::bind(addr).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            tracing::error!("bind prometheus listener {addr} error: {e:?}");
+            return;
+        }
+    };
+    tracing::info!("Prometheus metrics listening on http://{addr}/metrics");
+    if let Err(e) = axum::serve(listener, app.into_make_service()).await {
+        tracing::error!("prometheus listener error: {e:?}");
+    }
+}
+
+async fn metrics_with_token(State(state): State<LandscapeApp>, headers: HeaderMap) -> Response {
+    let Some(token) = state.config_service.get_prometheus_token() else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    if !check_bearer_token(&headers, &token) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    render_response(&state).await
+}
+
+async fn metrics_on_listener(State(state): State<LandscapeApp>, headers: HeaderMap) -> Response {
+    if let Some(token) = state.config_service.get_prometheus_token() {
+        if !check_bearer_token(&headers, &token) {
+            return StatusCode::UNAUTHORIZED.into_response();
+        }
+    }
+    render_response(&state).await
+}
+
+fn check_bearer_token(headers: &HeaderMap, token: &str) -> bool {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .is_some_and(|v| bool::from(v.as_bytes().ct_eq(token.as_bytes())))
+}
+
+async fn render_response(state: &LandscapeApp) -> Response {
+    let mut out = PrometheusText::new();
+    render_iface_metrics(state, &mut out).await;
+    state.metric_service.data.prometheus.render(&mut out);
+    render_nat_metrics(state, &mut out).await;
+    render_service_metrics(state, &mut out).await;
+    ([(header::CONTENT_TYPE, PROMETHEUS_CONTENT_TYPE)], out.finish()).into_response()
+}
+
+/// 网卡收发统计, 按区域标记以区分各 WAN
+async fn render_iface_metrics(state: &LandscapeApp, out: &mut PrometheusText) {
+    let zones: HashMap<String, IfaceZoneType> = state
+        .iface_config_service
+        .list()
+        .await
+        .into_iter()
+        .map(|config| (config.name, config.zone_type))
+        .collect();
+
+    let mut stats = vec![];
+    for dev in get_all_devices().await {
+        let Some(stat) = read_iface_traffic_stats(&dev.name) else {
+            continue;
+        };
+        let zone = match zones.get(&dev.name) {
+            Some(IfaceZoneType::Wan) => "wan",
+            Some(IfaceZoneType::Lan) => "lan",
+            _ => "undefined",
+        };
+        stats.push((dev.name, zone, stat));
+    }
+
+    let families = [
+        ("landscape_iface_rx_bytes_total", "Bytes received by interface"),
+        ("landscape_iface_tx_bytes_total", "Bytes transmitted by interface"),
+        ("landscape_iface_rx_packets_total", "Packets received by interface"),
+        ("landscape_iface_tx_packets_total", "Packets transmitted by interface"),
+    ];
+    for (index, (name, help)) in families.into_iter().enumerate() {
+        out.family(name, "counter", help);
+        for (iface_name, zone, stat) in stats.iter() {
+            let value = match index {
+                0 => stat.rx_bytes,
+                1 => stat.tx_bytes,
+                2 => stat.rx_packets,
+                _ => stat.tx_packets,
+            };
+            out.sample(name, &[("iface", iface_name.as_str()), ("zone", zone)], value);
+        }
+    }
+}
+
+/// 动态 NAT 端口占用与各 WAN 配置的端口池大小
+async fn render_nat_metrics(state: &LandscapeApp, out: &mut PrometheusText) {
+    let usage = landscape_ebpf::map_setting::nat::get_nat4_dynamic_port_usage();
+    out.family("landscape_nat_ports_in_use", "gauge", "Dynamic IPv4 NAT ports in use");
+    for (l4_proto, count) in usage {
+        let proto = l4_proto_name(l4_proto);
+        out.sample("landscape_nat_ports_in_use", &[("proto", proto.as_str())], count);
+    }
+
+    let configs = state.nat_service.get_repository().list().await.unwrap_or_default();
+    out.family("landscape_nat_port_pool_size", "gauge", "Configured NAT port pool size");
+    for config in configs.iter().filter(|config| config.enable) {
+        let nat = &config.nat_config;
+        for (proto, range) in
+            [("tcp", &nat.tcp_range), ("udp", &nat.udp_range), ("icmp", &nat.icmp_in_range)]
+        {
+            let labels = [("iface", config.iface_name.as_str()), ("proto", proto)];
+            out.sample("landscape_nat_port_pool_size", &labels, range.len());
+        }
+    }
+}
+
+async fn render_service_metrics(state: &LandscapeApp, out: &mut PrometheusText) {
+    let services: Vec<(&str, HashMap<String, WatchService>)> = vec![
+        ("ip_config", state.wan_ip_service.get_all_status().await),
+        ("pppd", state.pppd_service.get_all_status().await),
+        ("dhcp_v4", state.dhcp_v4_server_service.get_all_status().await),
+        ("ipv6_pd", state.ipv6_pd_service.get_all_status().await),
+        ("ipv6_ra", state.ipv6_ra_service.get_all_status().await),
+        ("nat", state.nat_service.get_all_status().await),
+        ("firewall", state.firewall_service.get_all_status().await),
+        ("mss_clamp", state.mss_clamp_service.get_all_status().await),
+        ("sqm", state.sqm_service.get_all_status().await),
+        ("route_lan", state.route_lan_service.get_all_status().await),
+        ("route_wan", state.route_wan_service.get_all_status().await),
+        ("wifi", state.wifi_service.get_all_status().await),
+        ("wifi_station", state.wifi_station_service.get_all_status().await),
+        ("wireguard", state.wireguard_service.get_all_status().await),
+        ("metric", HashMap::from([(String::new(), state.metric_service.status.clone())])),
+    ];
+
+    let name = "landscape_service_status";
+    out.family(name, "gauge", "Service status, 1 for the current state");
+    for (service, status_map) in services.iter() {
+        let mut entries: Vec<_> = status_map.iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        for (iface_name, watch) in entries {
+            let current = watch.0.borrow().clone();
+            for (status, label) in [
+                (ServiceStatus::Staring, "starting"),
+                (ServiceStatus::Running, "running"),
+                (ServiceStatus::Stopping, "stopping"),
+                (ServiceStatus::Stop, "stop"),
+            ] {
+                let value = u8::from(current == status);
+                let labels =
+                    [("service", *service), ("iface", iface_name.as_str()), ("status", label)];
+                out.sample(name, &labels, value);
+            }
+        }
+    }
+}
@@ -12,6 +12,9 @@ use crate::dns::upstreams::get_dns_upstream_config_paths;
 use crate::docker::get_docker_paths;
 use crate::firewall::blacklists::get_firewall_blacklist_config_paths;
 use crate::flow::dst_ip_rules::get_dst_ip_rule_config_paths;
+use crate::flow::netns_targets::get_netns_target_config_paths;
+use crate::flow::quotas::get_traffic_quota_config_paths;
+use crate::flow::rate_limits::get_rate_limit_config_paths;
 use crate::flow::rules::get_flow_rule_config_paths;
 use crate::geo::ips::get_geo_ip_config_paths;
 use crate::geo::sites::get_geo_site_config_paths;
@@ -28,9 +31,14 @@ use crate::services::mss_clamp::get_mss_clamp_service_paths;
 use crate::services::nat::get_iface_nat_paths;
 use crate::services::pppoe::get_iface_pppd_paths;
 use crate::services::routing::get_route_paths;
+use crate::services::sqm::get_sqm_service_paths;
 use crate::services::wan::get_route_wan_paths;
 use crate::services::wifi::get_wifi_service_paths;
+use crate::services::wifi_station::get_wifi_station_service_paths;
+use crate::services::wireguard::get_wireguard_service_paths;
+use crate::system::audit::get_audit_paths;
 use crate::system::config::get_sys_config_paths;
+use crate::system::users::get_user_paths;
 use crate::LandscapeApp;
 
 struct SecurityAddon;
@@ -65,15 +73,19 @@ impl Modify for SecurityAddon {
         (name = "Interfaces", description = "Network interface management"),
         (name = "System Config", description = "System configuration management"),
         (name = "System Info", description = "System information and status"),
+        (name = "Users", description = "User accounts and API tokens"),
         (name = "Route", description = "Route tracing and cache management"),
         (name = "Route WAN", description = "WAN route service management"),
         (name = "Route LAN", description = "LAN route service management"),
         (name = "MSS Clamp", description = "MSS clamping service"),
+        (name = "SQM", description = "Smart queue management (traffic shaping)"),
         (name = "Firewall Service", description = "Interface firewall service"),
         (name = "IP Config", description = "Interface IP configuration service"),
         (name = "DHCPv4", description = "DHCPv4 server service"),
         (name = "PPPoE", description = "PPPoE service"),
         (name = "WiFi", description = "WiFi service"),
+        (name = "WiFi Station", description = "WiFi station (client) service"),
+        (name = "WireGuard", description = "WireGuard tunnel service"),
         (name = "IPv6 PD", description = "IPv6 prefix delegation service"),
         (name = "ICMPv6 RA", description = "ICMPv6 router advertisement service"),
         (name = "NAT Service", description = "NAT service"),
@@ -84,6 +96,8 @@ impl Modify for SecurityAddon {
         (name = "Firewall Blacklists", description = "Firewall blacklist configuration"),
         (name = "Flow Rules", description = "Flow rule configuration"),
         (name = "Destination IP Rules", description = "Destination IP rule configuration"),
+        (name = "Traffic Quotas", description = "Traffic quota configuration and usage"),
+        (name = "Namespace Targets", description = "Non-Docker network namespace flow targets"),
         (name = "Static NAT Mappings", description = "Static NAT mapping configuration"),
         (name = "Geo Sites", description = "Geo site configuration"),
         (name = "Geo IPs", description = "Geo IP configuration"),
@@ -91,6 +105,7 @@ impl Modify for SecurityAddon {
         (name = "Docker", description = "Docker container management"),
         (name = "Docker Images", description = "Docker image management"),
         (name = "Docker Networks", description = "Docker network management"),
+        (name = "Docker Managed Containers", description = "Managed proxy container lifecycle"),
         (name = "Metric", description = "Metric data and statistics"),
     ),
     components(schemas(
@@ -106,6 +121,7 @@ impl Modify for SecurityAddon {
         landscape_common::metric::dns::DnsSortKey,
         landscape_common::metric::dns::DnsResultStatus,
         landscape_common::config::dns::LandscapeDnsRecordType,
+        landscape_common::config::import::ConfigImportMode,
         // WebSocket types (no endpoint, registered for ORVAL codegen)
         landscape_common::docker::image::ImgPullEvent,
         landscape_common::pty::SessionStatus,
@@ -126,7 +142,10 @@ pub fn build_interfaces_openapi_router() -> OpenApiRouter<LandscapeApp> {
 
 /// /system — system info + global config (sysinfo has its own state type, handled separately)
 pub fn build_system_openapi_router() -> OpenApiRouter<LandscapeApp> {
-    OpenApiRouter::new().merge(get_sys_config_paths())
+    OpenApiRouter::new()
+        .merge(get_sys_config_paths())
+        .merge(get_user_paths())
+        .merge(get_audit_paths())
 }
 
 /// /services — per-interface network services
@@ -136,11 +155,14 @@ pub fn build_services_openapi_router() -> OpenApiRouter<LandscapeApp> {
         .merge(get_route_wan_paths())
         .merge(get_route_lan_paths())
         .merge(get_mss_clamp_service_paths())
+        .merge(get_sqm_service_paths())
         .merge(get_firewall_service_paths())
         .merge(get_iface_ipconfig_paths())
         .merge(get_dhcp_v4_service_paths())
         .merge(get_iface_pppd_paths())
         .merge(get_wifi_service_paths())
+        .merge(get_wifi_station_service_paths())
+        .merge(get_wireguard_service_paths())
         .merge(get_iface_pdclient_paths())
         .merge(get_iface_icmpv6ra_paths())
         .merge(get_iface_nat_paths())
@@ -160,9 +182,14 @@ pub fn build_firewall_openapi_router() -> OpenApiRouter<LandscapeApp> {
     OpenApiRouter::new().merge(get_firewall_blacklist_config_paths())
 }
 
-/// /flow — flow rules + destination IP rules
+/// /flow — flow rules + destination IP rules + traffic quotas + namespace targets
 pub fn build_flow_openapi_router() -> OpenApiRouter<LandscapeApp> {
-    OpenApiRouter::new().merge(get_flow_rule_config_paths()).merge(get_dst_ip_rule_config_paths())
+    OpenApiRouter::new()
+        .merge(get_flow_rule_config_paths())
+        .merge(get_dst_ip_rule_config_paths())
+        .merge(get_traffic_quota_config_paths())
+        .merge(get_rate_limit_config_paths())
+        .merge(get_netns_target_config_paths())
 }
 
 /// /nat — static NAT mappings
@@ -180,7 +207,7 @@ pub fn build_devices_openapi_router() -> OpenApiRouter<LandscapeApp> {
     OpenApiRouter::new().merge(get_enrolled_device_config_paths())
 }
 
-/// /docker — Docker service + containers + images + networks
+/// /docker — Docker service + containers + images + networks + managed containers
 pub fn build_docker_openapi_router() -> OpenApiRouter<LandscapeApp> {
     OpenApiRouter::new().merge(get_docker_paths())
 }
@@ -207,7 +234,7 @@ pub fn build_full_openapi_spec() -> utoipa::openapi::OpenApi {
     let (_, mut spec) =
         OpenApiRouter::<LandscapeApp>::with_openapi(ApiDoc::openapi()).split_for_parts();
 
-    // Auth (state = Arc<AuthRuntimeConfig>)
+    // Auth (state = AuthState)
     let (_, mut auth_openapi) = get_auth_openapi_router().split_for_parts();
     prefix_paths(&mut auth_openapi, "/api/auth");
     spec.merge(auth_openapi);
@@ -297,11 +324,14 @@ pub fn build_full_openapi_spec() -> utoipa::openapi::OpenApi {
                 "Route WAN",
                 "Route LAN",
                 "MSS Clamp",
+                "SQM",
                 "Firewall Service",
                 "IP Config",
                 "DHCPv4",
                 "PPPoE",
                 "WiFi",
+                "WiFi Station",
+                "WireGuard",
                 "IPv6 PD",
                 "ICMPv6 RA",
                 "NAT Service"
@@ -326,7 +356,9 @@ pub fn build_full_openapi_spec() -> utoipa::openapi::OpenApi {
             "name": "Flow",
             "tags": [
                 "Flow Rules",
-                "Destination IP Rules"
+                "Destination IP Rules",
+                "Traffic Quotas",
+                "Namespace Targets"
             ]
         },
         {
@@ -349,7 +381,8 @@ pub fn build_full_openapi_spec() -> utoipa::openapi::OpenApi {
             "tags": [
                 "Docker",
                 "Docker Images",
-                "Docker Networks"
+                "Docker Networks",
+                "Docker Managed Containers"
             ]
         },
         {
@@ -378,4 +411,18 @@ mod tests {
         std::fs::write(&out_path, json).expect("Failed to write openapi.json");
         println!("OpenAPI spec written to {}", out_path.display());
     }
+
+    #[test]
+    fn read_only_routes_from_security_scope() {
+        use axum::http::Method;
+
+        let routes = crate::auth::ReadOnlyRoutes::from_openapi(&build_full_openapi_spec());
+        assert!(routes.allows(&Method::GET, "/api/v1/system/config/export"));
+        assert!(routes.allows(&Method::POST, "/api/v1/services/routing/trace/verdict"));
+        assert!(routes.allows(&Method::PUT, "/api/v1/system/account/password"));
+        assert!(routes.allows(&Method::DELETE, "/api/v1/system/account/tokens/{id}"));
+        assert!(!routes.allows(&Method::POST, "/api/v1/services/routing/reset_cache"));
+        assert!(!routes.allows(&Method::POST, "/api/v1/system/config/import"));
+        assert!(!routes.allows(&Method::POST, "/api/v1/system/users"));
+    }
 }
@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use axum::extract::{Path, State};
 use landscape_common::api_response::LandscapeApiResp as CommonApiResp;
 use landscape_common::dhcp::v6_client::config::IPV6PDServiceConfig;
-use landscape_common::ipv6_pd::LDIAPrefix;
+use landscape_common::ipv6_pd::{LDIAPrefix, LDWanIpv6Info};
 use landscape_common::service::controller::ControllerService;
 use landscape_common::service::{ServiceStatus, WatchService};
 use utoipa_axum::router::OpenApiRouter;
@@ -19,6 +19,7 @@ pub fn get_iface_pdclient_paths() -> OpenApiRouter<LandscapeApp> {
     OpenApiRouter::new()
         .routes(routes!(get_all_status))
         .routes(routes!(get_current_ip_prefix_info))
+        .routes(routes!(get_wan_ipv6_info))
         .routes(routes!(handle_iface_pd))
         .routes(routes!(get_iface_pd_config, delete_and_stop_iface_service))
 }
@@ -35,6 +36,18 @@ async fn get_current_ip_prefix_info(
     LandscapeApiResp::success(state.ipv6_pd_service.get_ipv6_prefix_infos().await)
 }
 
+#[utoipa::path(
+    get,
+    path = "/ipv6pd/wan_infos",
+    tag = "IPv6 PD",
+    responses((status = 200, body = CommonApiResp<HashMap<String, LDWanIpv6Info>>))
+)]
+async fn get_wan_ipv6_info(
+    State(state): State<LandscapeApp>,
+) -> LandscapeApiResult<HashMap<String, LDWanIpv6Info>> {
+    LandscapeApiResp::success(state.ipv6_pd_service.get_wan_ipv6_infos().await)
+}
+
 #[utoipa::path(
     get,
     path = "/ipv6pd/status",
@@ -81,6 +94,7 @@ async fn handle_iface_pd(
     JsonBody(config): JsonBody<IPV6PDServiceConfig>,
 ) -> LandscapeApiResult<()> {
     state.validate_zone(&config).await?;
+    config.config.validate()?;
     state.ipv6_pd_service.handle_service_config(config).await?;
     LandscapeApiResp::success(())
 }
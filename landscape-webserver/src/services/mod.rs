@@ -6,7 +6,10 @@ pub mod ipv6pd;
 pub mod mss_clamp;
 pub mod nat;
 pub mod pppoe;
+pub mod sqm;
 pub mod wifi;
+pub mod wifi_station;
+pub mod wireguard;
 
 pub mod lan;
 pub mod routing;
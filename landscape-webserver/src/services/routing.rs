@@ -24,7 +24,6 @@ pub fn get_route_paths() -> OpenApiRouter<LandscapeApp> {
 )]
 async fn reset_cache() -> LandscapeApiResult<()> {
     landscape_ebpf::map_setting::route::cache::recreate_route_lan_cache_inner_map();
-    // landscape_ebpf::map_setting::route::cache::recreate_route_wan_cache_inner_map();
     LandscapeApiResp::success(())
 }
 
@@ -32,6 +31,7 @@ async fn reset_cache() -> LandscapeApiResult<()> {
     post,
     path = "/routing/trace/flow_match",
     tag = "Route",
+    security(("bearer_auth" = ["read_only"])),
     request_body = FlowMatchRequest,
     responses((status = 200, body = CommonApiResp<FlowMatchResult>))
 )]
@@ -46,6 +46,7 @@ async fn trace_flow_match(
     post,
     path = "/routing/trace/verdict",
     tag = "Route",
+    security(("bearer_auth" = ["read_only"])),
     request_body = FlowVerdictRequest,
     responses((status = 200, body = CommonApiResp<FlowVerdictResult>))
 )]
@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+
+use axum::extract::{Path, State};
+use landscape_common::api_response::LandscapeApiResp as CommonApiResp;
+use landscape_common::config::sqm::{SqmServiceConfig, SqmStats};
+use landscape_common::service::controller::ControllerService;
+use landscape_common::service::{ServiceStatus, WatchService};
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+
+use landscape_common::service::ServiceConfigError;
+
+use crate::api::JsonBody;
+use crate::LandscapeApp;
+use crate::{api::LandscapeApiResp, error::LandscapeApiResult};
+
+pub fn get_sqm_service_paths() -> OpenApiRouter<LandscapeApp> {
+    OpenApiRouter::new()
+        .routes(routes!(get_all_iface_service_status))
+        .routes(routes!(handle_service_config))
+        .routes(routes!(get_iface_service_config, delete_and_stop_iface_service))
+        .routes(routes!(get_iface_sqm_stats))
+}
+
+#[utoipa::path(
+    get,
+    path = "/sqm/status",
+    tag = "SQM",
+    operation_id = "get_all_sqm_service_status",
+    responses((status = 200, body = CommonApiResp<HashMap<String, ServiceStatus>>))
+)]
+async fn get_all_iface_service_status(
+    State(state): State<LandscapeApp>,
+) -> LandscapeApiResult<HashMap<String, WatchService>> {
+    LandscapeApiResp::success(state.sqm_service.get_all_status().await)
+}
+
+#[utoipa::path(
+    get,
+    path = "/sqm/{iface_name}",
+    tag = "SQM",
+    operation_id = "get_sqm_service_config",
+    params(("iface_name" = String, Path, description = "Interface name")),
+    responses(
+        (status = 200, body = CommonApiResp<SqmServiceConfig>),
+        (status = 404, description = "Not found")
+    )
+)]
+async fn get_iface_service_config(
+    State(state): State<LandscapeApp>,
+    Path(iface_name): Path<String>,
+) -> LandscapeApiResult<SqmServiceConfig> {
+    if let Some(iface_config) = state.sqm_service.get_config_by_name(iface_name).await {
+        LandscapeApiResp::success(iface_config)
+    } else {
+        Err(ServiceConfigError::NotFound { service_name: "SQM" })?
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/sqm/{iface_name}/stats",
+    tag = "SQM",
+    operation_id = "get_sqm_stats",
+    params(("iface_name" = String, Path, description = "Interface name")),
+    responses(
+        (status = 200, body = CommonApiResp<SqmStats>),
+        (status = 404, description = "Not found")
+    )
+)]
+async fn get_iface_sqm_stats(
+    State(state): State<LandscapeApp>,
+    Path(iface_name): Path<String>,
+) -> LandscapeApiResult<SqmStats> {
+    match state.sqm_service.get_stats(iface_name.clone()).await {
+        Some(stats) => LandscapeApiResp::success(stats),
+        None => Err(ServiceConfigError::IfaceNotFound { iface_name })?,
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/sqm",
+    tag = "SQM",
+    operation_id = "handle_sqm_service_config",
+    request_body = SqmServiceConfig,
+    responses((status = 200, description = "Success"))
+)]
+async fn handle_service_config(
+    State(state): State<LandscapeApp>,
+    JsonBody(config): JsonBody<SqmServiceConfig>,
+) -> LandscapeApiResult<()> {
+    state.validate_zone(&config).await?;
+    config.validate()?;
+    state.sqm_service.handle_service_config(config).await?;
+    LandscapeApiResp::success(())
+}
+
+#[utoipa::path(
+    delete,
+    path = "/sqm/{iface_name}",
+    tag = "SQM",
+    operation_id = "delete_and_stop_sqm_service",
+    params(("iface_name" = String, Path, description = "Interface name")),
+    responses((status = 200, body = CommonApiResp<Option<ServiceStatus>>))
+)]
+async fn delete_and_stop_iface_service(
+    State(state): State<LandscapeApp>,
+    Path(iface_name): Path<String>,
+) -> LandscapeApiResult<Option<WatchService>> {
+    LandscapeApiResp::success(state.sqm_service.delete_and_stop_iface_service(iface_name).await)
+}
@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use axum::extract::{Path, State};
 use landscape_common::api_response::LandscapeApiResp as CommonApiResp;
 use landscape_common::config::wifi::WifiServiceConfig;
+use landscape_common::iface::dev_wifi::{WifiPhyCapability, WifiStationInfo};
 use landscape_common::service::controller::ControllerService;
 use landscape_common::service::{ServiceStatus, WatchService};
 use utoipa_axum::router::OpenApiRouter;
@@ -19,6 +20,8 @@ pub fn get_wifi_service_paths() -> OpenApiRouter<LandscapeApp> {
         .routes(routes!(get_all_iface_service_status))
         .routes(routes!(handle_service_config))
         .routes(routes!(get_iface_service_config, delete_and_stop_iface_service))
+        .routes(routes!(get_capability))
+        .routes(routes!(get_stations))
 }
 
 #[utoipa::path(
@@ -56,6 +59,43 @@ async fn get_iface_service_config(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/wifi/{iface_name}/capability",
+    tag = "WiFi",
+    operation_id = "get_wifi_capability",
+    params(("iface_name" = String, Path, description = "Interface name")),
+    responses(
+        (status = 200, body = CommonApiResp<WifiPhyCapability>),
+        (status = 404, description = "Not found")
+    )
+)]
+async fn get_capability(
+    State(state): State<LandscapeApp>,
+    Path(iface_name): Path<String>,
+) -> LandscapeApiResult<WifiPhyCapability> {
+    if let Some(capability) = state.wifi_service.get_capability(&iface_name).await {
+        LandscapeApiResp::success(capability)
+    } else {
+        Err(ServiceConfigError::IfaceNotFound { iface_name })?
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/wifi/{iface_name}/stations",
+    tag = "WiFi",
+    operation_id = "get_wifi_stations",
+    params(("iface_name" = String, Path, description = "Interface name")),
+    responses((status = 200, body = CommonApiResp<Vec<WifiStationInfo>>))
+)]
+async fn get_stations(
+    State(state): State<LandscapeApp>,
+    Path(iface_name): Path<String>,
+) -> LandscapeApiResult<Vec<WifiStationInfo>> {
+    LandscapeApiResp::success(state.wifi_service.get_stations(iface_name).await)
+}
+
 #[utoipa::path(
     put,
     path = "/wifi",
@@ -70,6 +110,9 @@ async fn handle_service_config(
 ) -> LandscapeApiResult<()> {
     state.validate_zone(&config).await?;
     config.validate()?;
+    if let Some(capability) = state.wifi_service.get_capability(&config.iface_name).await {
+        config.validate_capability(&capability)?;
+    }
     state.wifi_service.handle_service_config(config).await?;
     LandscapeApiResp::success(())
 }
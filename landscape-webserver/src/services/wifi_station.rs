@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use axum::extract::{Path, State};
+use landscape_common::api_response::LandscapeApiResp as CommonApiResp;
+use landscape_common::config::wifi_station::{
+    WifiScanResult, WifiStationServiceConfig, WifiStationStatus,
+};
+use landscape_common::service::controller::ControllerService;
+use landscape_common::service::{ServiceStatus, WatchService};
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+
+use landscape_common::service::ServiceConfigError;
+
+use crate::api::JsonBody;
+use crate::LandscapeApp;
+use crate::{api::LandscapeApiResp, error::LandscapeApiResult};
+
+pub fn get_wifi_station_service_paths() -> OpenApiRouter<LandscapeApp> {
+    OpenApiRouter::new()
+        .routes(routes!(get_all_iface_service_status))
+        .routes(routes!(handle_service_config))
+        .routes(routes!(get_iface_service_config, delete_and_stop_iface_service))
+        .routes(routes!(get_station_status))
+        .routes(routes!(scan))
+}
+
+#[utoipa::path(
+    get,
+    path = "/wifi_station/status",
+    tag = "WiFi Station",
+    operation_id = "get_all_wifi_station_service_status",
+    responses((status = 200, body = CommonApiResp<HashMap<String, ServiceStatus>>))
+)]
+async fn get_all_iface_service_status(
+    State(state): State<LandscapeApp>,
+) -> LandscapeApiResult<HashMap<String, WatchService>> {
+    LandscapeApiResp::success(state.wifi_station_service.get_all_status().await)
+}
+
+#[utoipa::path(
+    get,
+    path = "/wifi_station/{iface_name}",
+    tag = "WiFi Station",
+    operation_id = "get_wifi_station_service_config",
+    params(("iface_name" = String, Path, description = "Interface name")),
+    responses(
+        (status = 200, body = CommonApiResp<WifiStationServiceConfig>),
+        (status = 404, description = "Not found")
+    )
+)]
+async fn get_iface_service_config(
+    State(state): State<LandscapeApp>,
+    Path(iface_name): Path<String>,
+) -> LandscapeApiResult<WifiStationServiceConfig> {
+    if let Some(iface_config) = state.wifi_station_service.get_config_by_name(iface_name).await {
+        LandscapeApiResp::success(iface_config)
+    } else {
+        Err(ServiceConfigError::NotFound { service_name: "WiFi Station" })?
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/wifi_station/{iface_name}/runtime",
+    tag = "WiFi Station",
+    operation_id = "get_wifi_station_runtime_status",
+    params(("iface_name" = String, Path, description = "Interface name")),
+    responses(
+        (status = 200, body = CommonApiResp<WifiStationStatus>),
+        (status = 404, description = "Not found")
+    )
+)]
+async fn get_station_status(
+    State(state): State<LandscapeApp>,
+    Path(iface_name): Path<String>,
+) -> LandscapeApiResult<WifiStationStatus> {
+    if let Some(status) = state.wifi_station_service.get_station_status(&iface_name).await {
+        LandscapeApiResp::success(status)
+    } else {
+        Err(ServiceConfigError::NotFound { service_name: "WiFi Station" })?
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/wifi_station/{iface_name}/scan",
+    tag = "WiFi Station",
+    operation_id = "get_wifi_station_scan_results",
+    params(("iface_name" = String, Path, description = "Interface name")),
+    responses(
+        (status = 200, body = CommonApiResp<Vec<WifiScanResult>>),
+        (status = 404, description = "Not found")
+    )
+)]
+async fn scan(
+    State(state): State<LandscapeApp>,
+    Path(iface_name): Path<String>,
+) -> LandscapeApiResult<Vec<WifiScanResult>> {
+    if let Some(result) = state.wifi_station_service.scan(&iface_name).await {
+        LandscapeApiResp::success(result)
+    } else {
+        Err(ServiceConfigError::IfaceNotFound { iface_name })?
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/wifi_station",
+    tag = "WiFi Station",
+    operation_id = "handle_wifi_station_service_config",
+    request_body = WifiStationServiceConfig,
+    responses((status = 200, description = "Success"))
+)]
+async fn handle_service_config(
+    State(state): State<LandscapeApp>,
+    JsonBody(config): JsonBody<WifiStationServiceConfig>,
+) -> LandscapeApiResult<()> {
+    state.validate_zone(&config).await?;
+    config.validate()?;
+    state.wifi_station_service.handle_service_config(config).await?;
+    LandscapeApiResp::success(())
+}
+
+#[utoipa::path(
+    delete,
+    path = "/wifi_station/{iface_name}",
+    tag = "WiFi Station",
+    operation_id = "delete_and_stop_wifi_station_service",
+    params(("iface_name" = String, Path, description = "Interface name")),
+    responses((status = 200, body = CommonApiResp<Option<ServiceStatus>>))
+)]
+async fn delete_and_stop_iface_service(
+    State(state): State<LandscapeApp>,
+    Path(iface_name): Path<String>,
+) -> LandscapeApiResult<Option<WatchService>> {
+    LandscapeApiResp::success(
+        state.wifi_station_service.delete_and_stop_iface_service(iface_name).await,
+    )
+}
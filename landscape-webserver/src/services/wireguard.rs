@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+
+use axum::extract::{Path, State};
+use landscape_common::api_response::LandscapeApiResp as CommonApiResp;
+use landscape_common::config::wireguard::{
+    WireGuardKeyPair, WireGuardRuntimeInfo, WireGuardServiceConfig,
+};
+use landscape_common::error::LdError;
+use landscape_common::service::controller::ControllerService;
+use landscape_common::service::{ServiceStatus, WatchService};
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+
+use landscape_common::service::ServiceConfigError;
+
+use crate::api::JsonBody;
+use crate::LandscapeApp;
+use crate::{api::LandscapeApiResp, error::LandscapeApiResult};
+
+pub fn get_wireguard_service_paths() -> OpenApiRouter<LandscapeApp> {
+    OpenApiRouter::new()
+        .routes(routes!(get_all_iface_service_status))
+        .routes(routes!(gen_key_pair))
+        .routes(routes!(handle_service_config))
+        .routes(routes!(get_iface_service_config, delete_and_stop_iface_service))
+        .routes(routes!(get_runtime_info))
+        .routes(routes!(get_peer_config))
+}
+
+#[utoipa::path(
+    get,
+    path = "/wireguard/status",
+    tag = "WireGuard",
+    operation_id = "get_all_wireguard_service_status",
+    responses((status = 200, body = CommonApiResp<HashMap<String, ServiceStatus>>))
+)]
+async fn get_all_iface_service_status(
+    State(state): State<LandscapeApp>,
+) -> LandscapeApiResult<HashMap<String, WatchService>> {
+    LandscapeApiResp::success(state.wireguard_service.get_all_status().await)
+}
+
+#[utoipa::path(
+    get,
+    path = "/wireguard/keypair",
+    tag = "WireGuard",
+    operation_id = "gen_wireguard_key_pair",
+    responses((status = 200, body = CommonApiResp<WireGuardKeyPair>))
+)]
+async fn gen_key_pair() -> LandscapeApiResult<WireGuardKeyPair> {
+    LandscapeApiResp::success(landscape::wireguard::netlink::gen_key_pair())
+}
+
+#[utoipa::path(
+    get,
+    path = "/wireguard/{iface_name}",
+    tag = "WireGuard",
+    operation_id = "get_wireguard_service_config",
+    params(("iface_name" = String, Path, description = "Interface name")),
+    responses(
+        (status = 200, body = CommonApiResp<WireGuardServiceConfig>),
+        (status = 404, description = "Not found")
+    )
+)]
+async fn get_iface_service_config(
+    State(state): State<LandscapeApp>,
+    Path(iface_name): Path<String>,
+) -> LandscapeApiResult<WireGuardServiceConfig> {
+    if let Some(iface_config) = state.wireguard_service.get_config_by_name(iface_name).await {
+        LandscapeApiResp::success(iface_config)
+    } else {
+        Err(ServiceConfigError::NotFound { service_name: "WireGuard" })?
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/wireguard/{iface_name}/runtime",
+    tag = "WireGuard",
+    operation_id = "get_wireguard_runtime_info",
+    params(("iface_name" = String, Path, description = "Interface name")),
+    responses((status = 200, body = CommonApiResp<WireGuardRuntimeInfo>))
+)]
+async fn get_runtime_info(
+    Path(iface_name): Path<String>,
+) -> LandscapeApiResult<WireGuardRuntimeInfo> {
+    let info = landscape::wireguard::netlink::get_device(&iface_name)
+        .await
+        .map_err(LdError::NetDevError)?;
+    LandscapeApiResp::success(info)
+}
+
+#[utoipa::path(
+    get,
+    path = "/wireguard/{iface_name}/peers/{peer_name}/config",
+    tag = "WireGuard",
+    operation_id = "get_wireguard_peer_config",
+    params(
+        ("iface_name" = String, Path, description = "Interface name"),
+        ("peer_name" = String, Path, description = "Peer name")
+    ),
+    responses(
+        (status = 200, body = CommonApiResp<String>),
+        (status = 404, description = "Not found")
+    )
+)]
+async fn get_peer_config(
+    State(state): State<LandscapeApp>,
+    Path((iface_name, peer_name)): Path<(String, String)>,
+) -> LandscapeApiResult<String> {
+    if let Some(config) = state.wireguard_service.get_peer_config(iface_name, peer_name).await {
+        LandscapeApiResp::success(config)
+    } else {
+        Err(ServiceConfigError::NotFound { service_name: "WireGuard Peer" })?
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/wireguard",
+    tag = "WireGuard",
+    operation_id = "handle_wireguard_service_config",
+    request_body = WireGuardServiceConfig,
+    responses((status = 200, description = "Success"))
+)]
+async fn handle_service_config(
+    State(state): State<LandscapeApp>,
+    JsonBody(config): JsonBody<WireGuardServiceConfig>,
+) -> LandscapeApiResult<()> {
+    state.validate_zone(&config).await?;
+    config.validate()?;
+    state.wireguard_service.handle_service_config(config).await?;
+    LandscapeApiResp::success(())
+}
+
+#[utoipa::path(
+    delete,
+    path = "/wireguard/{iface_name}",
+    tag = "WireGuard",
+    operation_id = "delete_and_stop_wireguard_service",
+    params(("iface_name" = String, Path, description = "Interface name")),
+    responses((status = 200, body = CommonApiResp<Option<ServiceStatus>>))
+)]
+async fn delete_and_stop_iface_service(
+    State(state): State<LandscapeApp>,
+    Path(iface_name): Path<String>,
+) -> LandscapeApiResult<Option<WatchService>> {
+    LandscapeApiResp::success(
+        state.wireguard_service.delete_and_stop_iface_service(iface_name).await,
+    )
+}
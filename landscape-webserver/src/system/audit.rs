@@ -0,0 +1,103 @@
+use std::net::SocketAddr;
+
+use axum::extract::{ConnectInfo, Query, Request, State};
+use axum::http::Method;
+use axum::middleware::Next;
+use axum::response::Response;
+use axum::Extension;
+use landscape_common::api_response::LandscapeApiResp as CommonApiResp;
+use landscape_common::auth::audit::{AuditLog, AuditLogKind, AuditLogQuery};
+use landscape_common::config::import::ConfigSectionDiff;
+use landscape_common::config::ConfigId;
+use landscape_common::utils::id::gen_database_uuid;
+use landscape_common::utils::time::get_f64_timestamp;
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+
+use super::config_snapshot::ConfigChange;
+use crate::api::LandscapeApiResp;
+use crate::auth::AuthUser;
+use crate::error::LandscapeApiResult;
+use crate::LandscapeApp;
+
+pub fn get_audit_paths() -> OpenApiRouter<LandscapeApp> {
+    OpenApiRouter::new().routes(routes!(list_audit_logs))
+}
+
+/// 记录每个修改类 API 调用, 包括失败的请求
+pub async fn audit_layer(State(state): State<LandscapeApp>, req: Request, next: Next) -> Response {
+    if !matches!(*req.method(), Method::POST | Method::PUT | Method::PATCH | Method::DELETE) {
+        return next.run(req).await;
+    }
+
+    let method = req.method().to_string();
+    let route = req.uri().path().to_string();
+    let username = req.extensions().get::<AuthUser>().map(|u| u.username.clone());
+    let source_ip = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_canonical().to_string());
+
+    let response = next.run(req).await;
+
+    let change = response.extensions().get::<ConfigChange>().copied();
+    let summary = match change {
+        Some(ConfigChange { before, after }) if before != after => state
+            .config_snapshot_service
+            .get(after)
+            .await
+            .map(|snapshot| summarize_diff(&snapshot.diff))
+            .unwrap_or_default(),
+        _ => String::new(),
+    };
+
+    let audit_service = state.audit_service.clone();
+    let log = AuditLog {
+        id: gen_database_uuid(),
+        kind: AuditLogKind::Api,
+        username: username.unwrap_or_default(),
+        source_ip,
+        config_id: find_config_id(&route),
+        method,
+        route,
+        status: response.status().as_u16(),
+        before: change.map(|c| c.before),
+        after: change.map(|c| c.after),
+        summary,
+        update_at: get_f64_timestamp(),
+    };
+    tokio::spawn(async move { audit_service.record(log).await });
+    response
+}
+
+/// 路径中最后一个 UUID 视为被修改的配置 ID
+fn find_config_id(route: &str) -> Option<String> {
+    route.rsplit('/').find(|seg| ConfigId::parse_str(seg).is_ok()).map(str::to_string)
+}
+
+/// 例如 `nat: +1 ~2 -0; flow_rules: +0 ~1 -0`
+fn summarize_diff(diff: &[ConfigSectionDiff]) -> String {
+    diff.iter()
+        .map(|s| {
+            format!("{}: +{} ~{} -{}", s.section, s.added.len(), s.changed.len(), s.removed.len())
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+#[utoipa::path(
+    get,
+    path = "/audit_logs",
+    tag = "Users",
+    operation_id = "list_audit_logs",
+    params(AuditLogQuery),
+    responses((status = 200, body = CommonApiResp<Vec<AuditLog>>))
+)]
+async fn list_audit_logs(
+    State(state): State<LandscapeApp>,
+    Extension(current): Extension<AuthUser>,
+    Query(query): Query<AuditLogQuery>,
+) -> LandscapeApiResult<Vec<AuditLog>> {
+    current.require_admin()?;
+    LandscapeApiResp::success(state.audit_service.query(query).await)
+}
@@ -1,15 +1,26 @@
-use axum::extract::State;
+use axum::extract::{Query, State};
+use axum::Extension;
 use landscape_common::api_response::LandscapeApiResp as CommonApiResp;
+use landscape_common::config::import::{ConfigImportQuery, ConfigImportResult};
+use landscape_common::config::InitConfig;
 use utoipa_axum::router::OpenApiRouter;
 use utoipa_axum::routes;
 
 use crate::api::LandscapeApiResp;
+use crate::auth::AuthUser;
 use crate::error::LandscapeApiResult;
 use crate::LandscapeApp;
 
 pub fn get_sys_config_paths() -> OpenApiRouter<LandscapeApp> {
     OpenApiRouter::new()
         .routes(routes!(export_init_config))
+        .routes(routes!(import_init_config))
+        .routes(routes!(super::config_snapshot::list_config_snapshots))
+        .routes(routes!(super::config_snapshot::compare_config_snapshots))
+        .routes(routes!(super::config_snapshot::get_config_snapshot))
+        .routes(routes!(super::config_snapshot::restore_config_snapshot))
+        .routes(routes!(super::config_snapshot::list_pending_config_confirms))
+        .routes(routes!(super::config_snapshot::confirm_config_change))
         .routes(routes!(super::ui_config::get_ui_config_fast))
         .routes(routes!(super::ui_config::get_ui_config, super::ui_config::update_ui_config))
         .routes(routes!(super::metric_config::get_metric_config_fast))
@@ -26,11 +37,38 @@ pub fn get_sys_config_paths() -> OpenApiRouter<LandscapeApp> {
     path = "/config/export",
     tag = "System Config",
     operation_id = "export_init_config",
-    responses((status = 200, body = CommonApiResp<String>))
+    responses(
+        (status = 200, body = CommonApiResp<String>),
+        (status = 403, description = "Admin only")
+    )
 )]
-async fn export_init_config(State(state): State<LandscapeApp>) -> LandscapeApiResult<String> {
+async fn export_init_config(
+    State(state): State<LandscapeApp>,
+    Extension(current): Extension<AuthUser>,
+) -> LandscapeApiResult<String> {
+    // 导出内容包含管理员密码与各类密钥
+    current.require_admin()?;
     let config = state.config_service.export_init_config().await;
     let config_file_content = toml::to_string(&config).unwrap();
 
     LandscapeApiResp::success(config_file_content)
 }
+
+#[utoipa::path(
+    post,
+    path = "/config/import",
+    tag = "System Config",
+    operation_id = "import_init_config",
+    params(ConfigImportQuery),
+    request_body(content = String, content_type = "text/plain", description = "landscape_init.toml"),
+    responses((status = 200, body = CommonApiResp<ConfigImportResult>))
+)]
+async fn import_init_config(
+    State(state): State<LandscapeApp>,
+    Query(query): Query<ConfigImportQuery>,
+    body: String,
+) -> LandscapeApiResult<ConfigImportResult> {
+    let incoming = InitConfig::from_toml(&body)?;
+    let result = state.apply_init_config(incoming, query.mode, query.dry_run).await?;
+    LandscapeApiResp::success(result)
+}
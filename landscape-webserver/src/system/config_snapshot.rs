@@ -0,0 +1,267 @@
+use std::time::Duration;
+
+use axum::extract::{Path, Query, Request, State};
+use axum::http::HeaderValue;
+use axum::middleware::Next;
+use axum::response::Response;
+use axum::Extension;
+use landscape::sys_service::snapshot_service::SYSTEM_AUTHOR;
+use landscape_common::api_response::LandscapeApiResp as CommonApiResp;
+use landscape_common::config::import::{ConfigImportMode, ConfigImportResult, ConfigSectionDiff};
+use landscape_common::config::snapshot::{
+    ConfigSnapshot, ConfigSnapshotCompareQuery, ConfigSnapshotError, ConfigSnapshotInfo,
+    PendingConfigConfirm, COMMIT_CONFIRM_HEADER, COMMIT_CONFIRM_ID_HEADER, MAX_COMMIT_CONFIRM_SECS,
+};
+use landscape_common::config::{ConfigId, InitConfig};
+use landscape_common::utils::time::get_current_time_ms;
+
+use crate::api::LandscapeApiResp;
+use crate::auth::{AuthUser, ReadOnlyRoute};
+use crate::error::{LandscapeApiError, LandscapeApiResult};
+use crate::LandscapeApp;
+
+/// 修改前后的快照 ID, 写入响应扩展供审计日志使用
+#[derive(Debug, Clone, Copy)]
+pub struct ConfigChange {
+    pub before: ConfigId,
+    pub after: ConfigId,
+}
+
+/// 修改配置的请求成功后记录配置快照, 只挂载在配置相关的路由上;
+/// 带有 `X-Commit-Confirm: <秒>` 的请求需在期限内确认, 否则回滚到修改前的快照
+pub async fn config_snapshot_layer(
+    State(state): State<LandscapeApp>,
+    req: Request,
+    next: Next,
+) -> Result<Response, LandscapeApiError> {
+    // GET 及查询类 POST 不会修改配置
+    if req.extensions().get::<ReadOnlyRoute>().is_some() {
+        return Ok(next.run(req).await);
+    }
+
+    let confirm_secs = match req.headers().get(COMMIT_CONFIRM_HEADER) {
+        Some(value) => {
+            let value = value.to_str().unwrap_or_default();
+            match value.parse::<u64>() {
+                Ok(secs) if (1..=MAX_COMMIT_CONFIRM_SECS).contains(&secs) => Some(secs),
+                _ => Err(ConfigSnapshotError::InvalidTimeout(value.to_string()))?,
+            }
+        }
+        None => None,
+    };
+
+    let author = req
+        .extensions()
+        .get::<AuthUser>()
+        .map(|u| u.username.clone())
+        .unwrap_or_else(|| SYSTEM_AUTHOR.to_string());
+    let summary = format!("{} {}", req.method(), req.uri().path());
+
+    // 持有到修改后的快照记录完成, 避免并发修改被记到其他请求名下
+    let _guard = state.config_snapshot_service.lock_writes().await;
+    let before = state.config_snapshot_service.baseline(confirm_secs.is_some()).await?;
+
+    let mut response = next.run(req).await;
+    if !response.status().is_success() {
+        return Ok(response);
+    }
+
+    let after = state.config_snapshot_service.record(&author, &summary).await?;
+    response.extensions_mut().insert(ConfigChange { before, after });
+    if let Some(secs) = confirm_secs {
+        if after != before {
+            let pending = state
+                .config_snapshot_service
+                .add_pending(before, after, &author, &summary, secs)
+                .await?;
+            if let Ok(value) = HeaderValue::from_str(&pending.id.to_string()) {
+                response.headers_mut().insert(COMMIT_CONFIRM_ID_HEADER, value);
+                response.headers_mut().append(
+                    axum::http::header::ACCESS_CONTROL_EXPOSE_HEADERS,
+                    HeaderValue::from_static(COMMIT_CONFIRM_ID_HEADER),
+                );
+            }
+            tokio::spawn(auto_revert(state, pending));
+        }
+    }
+    Ok(response)
+}
+
+/// 启动时恢复重启前未确认的修改, 已过期的立即回滚
+pub async fn resume_pending_confirms(state: LandscapeApp) {
+    for pending in state.config_snapshot_service.list_pending().await {
+        tracing::info!(
+            "resume commit confirm {}, revert to snapshot {} at {}",
+            pending.id,
+            pending.revert_to,
+            pending.deadline
+        );
+        tokio::spawn(auto_revert(state.clone(), pending));
+    }
+}
+
+async fn auto_revert(state: LandscapeApp, pending: PendingConfigConfirm) {
+    let now = get_current_time_ms().unwrap_or_default();
+    tokio::time::sleep(Duration::from_millis(pending.deadline.saturating_sub(now))).await;
+    let Some(pending) = state.config_snapshot_service.take_pending(pending.id).await else {
+        return;
+    };
+    let _guard = state.config_snapshot_service.lock_writes().await;
+    // 之后的修改可能依赖本次修改, 回滚会一并撤销它们, 此时只提示不回滚
+    match state.config_snapshot_service.record(SYSTEM_AUTHOR, "baseline").await {
+        Ok(latest) if latest == pending.applied => {}
+        Ok(latest) => {
+            tracing::warn!(
+                "commit confirm {} expired, but snapshot {} was recorded after it, skip revert to {}",
+                pending.id,
+                latest,
+                pending.revert_to
+            );
+            return;
+        }
+        Err(e) => {
+            tracing::error!("record config snapshot error: {e:?}");
+            return;
+        }
+    }
+    tracing::warn!(
+        "commit confirm {} expired, revert to snapshot {}",
+        pending.id,
+        pending.revert_to
+    );
+    if let Err(e) = restore(&state, pending.revert_to).await {
+        tracing::error!("auto revert to snapshot {} error: {e:?}", pending.revert_to);
+        return;
+    }
+    let summary = format!("auto revert: {}", pending.summary);
+    if let Err(e) = state.config_snapshot_service.record(SYSTEM_AUTHOR, &summary).await {
+        tracing::error!("record config snapshot error: {e:?}");
+    }
+}
+
+async fn restore(
+    state: &LandscapeApp,
+    id: ConfigId,
+) -> Result<ConfigImportResult, LandscapeApiError> {
+    let snapshot =
+        state.config_snapshot_service.get(id).await.ok_or(ConfigSnapshotError::NotFound(id))?;
+    let config = InitConfig::from_toml(&snapshot.content)?;
+    state.apply_init_config(config, ConfigImportMode::Replace, false).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/config/snapshots",
+    tag = "System Config",
+    operation_id = "list_config_snapshots",
+    responses((status = 200, body = CommonApiResp<Vec<ConfigSnapshotInfo>>))
+)]
+pub async fn list_config_snapshots(
+    State(state): State<LandscapeApp>,
+) -> LandscapeApiResult<Vec<ConfigSnapshotInfo>> {
+    LandscapeApiResp::success(state.config_snapshot_service.list().await)
+}
+
+#[utoipa::path(
+    get,
+    path = "/config/snapshots/compare",
+    tag = "System Config",
+    operation_id = "compare_config_snapshots",
+    params(ConfigSnapshotCompareQuery),
+    responses(
+        (status = 200, body = CommonApiResp<Vec<ConfigSectionDiff>>),
+        (status = 403, description = "Admin only")
+    )
+)]
+pub async fn compare_config_snapshots(
+    State(state): State<LandscapeApp>,
+    Extension(current): Extension<AuthUser>,
+    Query(query): Query<ConfigSnapshotCompareQuery>,
+) -> LandscapeApiResult<Vec<ConfigSectionDiff>> {
+    current.require_admin()?;
+    for id in [query.from, query.to] {
+        if state.config_snapshot_service.get(id).await.is_none() {
+            return Err(ConfigSnapshotError::NotFound(id))?;
+        }
+    }
+    let diff =
+        state.config_snapshot_service.compare(query.from, query.to).await.unwrap_or_default();
+    LandscapeApiResp::success(diff)
+}
+
+#[utoipa::path(
+    get,
+    path = "/config/snapshots/{id}",
+    tag = "System Config",
+    operation_id = "get_config_snapshot",
+    params(("id" = Uuid, Path, description = "Config snapshot ID")),
+    responses(
+        (status = 200, body = CommonApiResp<ConfigSnapshot>),
+        (status = 403, description = "Admin only"),
+        (status = 404, description = "Not found")
+    )
+)]
+pub async fn get_config_snapshot(
+    State(state): State<LandscapeApp>,
+    Extension(current): Extension<AuthUser>,
+    Path(id): Path<ConfigId>,
+) -> LandscapeApiResult<ConfigSnapshot> {
+    // 快照内容为完整配置, 包含各类密钥
+    current.require_admin()?;
+    match state.config_snapshot_service.get(id).await {
+        Some(snapshot) => LandscapeApiResp::success(snapshot),
+        None => Err(ConfigSnapshotError::NotFound(id))?,
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/config/snapshots/{id}/restore",
+    tag = "System Config",
+    operation_id = "restore_config_snapshot",
+    params(("id" = Uuid, Path, description = "Config snapshot ID")),
+    responses(
+        (status = 200, body = CommonApiResp<ConfigImportResult>),
+        (status = 404, description = "Not found")
+    )
+)]
+pub async fn restore_config_snapshot(
+    State(state): State<LandscapeApp>,
+    Path(id): Path<ConfigId>,
+) -> LandscapeApiResult<ConfigImportResult> {
+    LandscapeApiResp::success(restore(&state, id).await?)
+}
+
+#[utoipa::path(
+    get,
+    path = "/config/pending_confirms",
+    tag = "System Config",
+    operation_id = "list_pending_config_confirms",
+    responses((status = 200, body = CommonApiResp<Vec<PendingConfigConfirm>>))
+)]
+pub async fn list_pending_config_confirms(
+    State(state): State<LandscapeApp>,
+) -> LandscapeApiResult<Vec<PendingConfigConfirm>> {
+    LandscapeApiResp::success(state.config_snapshot_service.list_pending().await)
+}
+
+#[utoipa::path(
+    post,
+    path = "/config/pending_confirms/{id}/confirm",
+    tag = "System Config",
+    operation_id = "confirm_config_change",
+    params(("id" = Uuid, Path, description = "Pending confirmation ID")),
+    responses(
+        (status = 200, description = "Success"),
+        (status = 404, description = "Not found")
+    )
+)]
+pub async fn confirm_config_change(
+    State(state): State<LandscapeApp>,
+    Path(id): Path<ConfigId>,
+) -> LandscapeApiResult<()> {
+    match state.config_snapshot_service.take_pending(id).await {
+        Some(_) => LandscapeApiResp::success(()),
+        None => Err(ConfigSnapshotError::ConfirmNotFound(id))?,
+    }
+}
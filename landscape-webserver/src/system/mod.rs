@@ -1,5 +1,8 @@
+pub mod audit;
 pub mod config;
+pub mod config_snapshot;
 pub mod dns_config;
 pub mod info;
 pub mod metric_config;
 pub mod ui_config;
+pub mod users;
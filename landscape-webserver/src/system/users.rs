@@ -0,0 +1,384 @@
+use axum::extract::{Path, State};
+use axum::Extension;
+use landscape_common::api_response::LandscapeApiResp as CommonApiResp;
+use landscape_common::auth::user::{
+    ApiToken, ChangePasswordRequest, CreateApiTokenRequest, CreateApiTokenResult,
+    CreateUserRequest, LandscapeUser, TotpCodeRequest, TotpEnrollRequest, TotpEnrollment,
+    UpdateUserRequest, UserError, MIN_PASSWORD_LENGTH,
+};
+use landscape_common::config::ConfigId;
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+
+use crate::api::{JsonBody, LandscapeApiResp};
+use crate::auth::AuthUser;
+use crate::error::LandscapeApiResult;
+use crate::LandscapeApp;
+
+const MAX_USERNAME_LENGTH: usize = 32;
+
+pub fn get_user_paths() -> OpenApiRouter<LandscapeApp> {
+    OpenApiRouter::new()
+        .routes(routes!(list_users, create_user))
+        .routes(routes!(update_user, delete_user))
+        .routes(routes!(list_user_tokens))
+        .routes(routes!(get_current_account))
+        .routes(routes!(change_password))
+        .routes(routes!(enroll_totp))
+        .routes(routes!(activate_totp))
+        .routes(routes!(disable_totp))
+        .routes(routes!(reset_user_totp))
+        .routes(routes!(list_account_tokens, create_account_token))
+        .routes(routes!(revoke_api_token))
+}
+
+fn check_username(username: &str) -> Result<(), UserError> {
+    let valid = !username.is_empty()
+        && username.len() <= MAX_USERNAME_LENGTH
+        && username.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(UserError::InvalidUsername(username.to_string()))
+    }
+}
+
+fn check_password(password: &str) -> Result<(), UserError> {
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(UserError::PasswordTooShort(MIN_PASSWORD_LENGTH));
+    }
+    Ok(())
+}
+
+#[utoipa::path(
+    get,
+    path = "/users",
+    tag = "Users",
+    responses((status = 200, body = CommonApiResp<Vec<LandscapeUser>>))
+)]
+async fn list_users(
+    State(state): State<LandscapeApp>,
+    Extension(current): Extension<AuthUser>,
+) -> LandscapeApiResult<Vec<LandscapeUser>> {
+    current.require_admin()?;
+    LandscapeApiResp::success(state.user_service.list_users().await)
+}
+
+#[utoipa::path(
+    post,
+    path = "/users",
+    tag = "Users",
+    request_body = CreateUserRequest,
+    responses(
+        (status = 200, body = CommonApiResp<LandscapeUser>),
+        (status = 409, description = "Username already exists")
+    )
+)]
+async fn create_user(
+    State(state): State<LandscapeApp>,
+    Extension(current): Extension<AuthUser>,
+    JsonBody(req): JsonBody<CreateUserRequest>,
+) -> LandscapeApiResult<LandscapeUser> {
+    current.require_admin()?;
+    check_username(&req.username)?;
+    check_password(&req.password)?;
+    if state.user_service.find_user_by_name(&req.username).await.is_some() {
+        return Err(UserError::UsernameExists(req.username))?;
+    }
+    let user = state.user_service.create_user(req.username, &req.password, req.role).await?;
+    LandscapeApiResp::success(user)
+}
+
+#[utoipa::path(
+    put,
+    path = "/users/{id}",
+    tag = "Users",
+    params(("id" = Uuid, Path, description = "User ID")),
+    request_body = UpdateUserRequest,
+    responses(
+        (status = 200, body = CommonApiResp<LandscapeUser>),
+        (status = 404, description = "Not found")
+    )
+)]
+async fn update_user(
+    State(state): State<LandscapeApp>,
+    Extension(current): Extension<AuthUser>,
+    Path(id): Path<ConfigId>,
+    JsonBody(req): JsonBody<UpdateUserRequest>,
+) -> LandscapeApiResult<LandscapeUser> {
+    current.require_admin()?;
+    let user = state.user_service.find_user(id).await.ok_or(UserError::NotFound(id))?;
+    if let Some(password) = &req.password {
+        check_password(password)?;
+    }
+
+    let lose_admin = req.role.is_some_and(|role| !role.is_admin()) || req.enable == Some(false);
+    if user.role.is_admin()
+        && user.enable
+        && lose_admin
+        && state.user_service.count_other_admins(id).await == 0
+    {
+        return Err(UserError::LastAdmin)?;
+    }
+
+    let user =
+        state.user_service.update_user(user, req.role, req.enable, req.password.as_deref()).await?;
+    LandscapeApiResp::success(user)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/users/{id}",
+    tag = "Users",
+    params(("id" = Uuid, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "Success"),
+        (status = 404, description = "Not found")
+    )
+)]
+async fn delete_user(
+    State(state): State<LandscapeApp>,
+    Extension(current): Extension<AuthUser>,
+    Path(id): Path<ConfigId>,
+) -> LandscapeApiResult<()> {
+    current.require_admin()?;
+    let user = state.user_service.find_user(id).await.ok_or(UserError::NotFound(id))?;
+    if user.role.is_admin() && user.enable && state.user_service.count_other_admins(id).await == 0 {
+        return Err(UserError::LastAdmin)?;
+    }
+    state.user_service.delete_user(id).await?;
+    LandscapeApiResp::success(())
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/{id}/tokens",
+    tag = "Users",
+    params(("id" = Uuid, Path, description = "User ID")),
+    responses((status = 200, body = CommonApiResp<Vec<ApiToken>>))
+)]
+async fn list_user_tokens(
+    State(state): State<LandscapeApp>,
+    Extension(current): Extension<AuthUser>,
+    Path(id): Path<ConfigId>,
+) -> LandscapeApiResult<Vec<ApiToken>> {
+    current.require_admin()?;
+    LandscapeApiResp::success(state.user_service.list_tokens(id).await)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/users/{id}/totp",
+    tag = "Users",
+    params(("id" = Uuid, Path, description = "User ID")),
+    responses(
+        (status = 200, body = CommonApiResp<LandscapeUser>),
+        (status = 404, description = "Not found")
+    )
+)]
+async fn reset_user_totp(
+    State(state): State<LandscapeApp>,
+    Extension(current): Extension<AuthUser>,
+    Path(id): Path<ConfigId>,
+) -> LandscapeApiResult<LandscapeUser> {
+    current.require_admin()?;
+    let user = state.user_service.find_user(id).await.ok_or(UserError::NotFound(id))?;
+    LandscapeApiResp::success(state.user_service.disable_totp(user).await?)
+}
+
+#[utoipa::path(
+    get,
+    path = "/account",
+    tag = "Users",
+    responses((status = 200, body = CommonApiResp<LandscapeUser>))
+)]
+async fn get_current_account(
+    State(state): State<LandscapeApp>,
+    Extension(current): Extension<AuthUser>,
+) -> LandscapeApiResult<LandscapeUser> {
+    let mut user = current_user(&state, &current).await?;
+    // 使用 API Token 时返回 Token 的权限范围
+    user.role = current.role;
+    LandscapeApiResp::success(user)
+}
+
+#[utoipa::path(
+    put,
+    path = "/account/password",
+    tag = "Users",
+    security(("bearer_auth" = ["read_only"])),
+    request_body = ChangePasswordRequest,
+    responses(
+        (status = 200, description = "Success"),
+        (status = 400, description = "Current password is incorrect")
+    )
+)]
+async fn change_password(
+    State(state): State<LandscapeApp>,
+    Extension(current): Extension<AuthUser>,
+    JsonBody(req): JsonBody<ChangePasswordRequest>,
+) -> LandscapeApiResult<()> {
+    current.require_session()?;
+    check_password(&req.new_password)?;
+    let Some(user) = state.user_service.verify_password(&current.username, &req.old_password).await
+    else {
+        return Err(UserError::WrongPassword)?;
+    };
+    state.user_service.update_user(user, None, None, Some(&req.new_password)).await?;
+    LandscapeApiResp::success(())
+}
+
+async fn current_user(
+    state: &LandscapeApp,
+    current: &AuthUser,
+) -> Result<LandscapeUser, UserError> {
+    state.user_service.find_user(current.user_id).await.ok_or(UserError::NotFound(current.user_id))
+}
+
+#[utoipa::path(
+    post,
+    path = "/account/totp/enroll",
+    tag = "Users",
+    security(("bearer_auth" = ["read_only"])),
+    request_body = TotpEnrollRequest,
+    responses(
+        (status = 200, body = CommonApiResp<TotpEnrollment>),
+        (status = 400, description = "Invalid TOTP code")
+    )
+)]
+async fn enroll_totp(
+    State(state): State<LandscapeApp>,
+    Extension(current): Extension<AuthUser>,
+    JsonBody(req): JsonBody<TotpEnrollRequest>,
+) -> LandscapeApiResult<TotpEnrollment> {
+    current.require_session()?;
+    let user = current_user(&state, &current).await?;
+    if user.totp_enable {
+        let code = req.code.unwrap_or_default();
+        if !state.user_service.verify_totp(&user, &code) {
+            return Err(UserError::InvalidTotpCode)?;
+        }
+    }
+    let enrollment = state
+        .user_service
+        .enroll_totp(&user)
+        .ok_or_else(|| UserError::InvalidUsername(user.username.clone()))?;
+    LandscapeApiResp::success(enrollment)
+}
+
+#[utoipa::path(
+    post,
+    path = "/account/totp/activate",
+    tag = "Users",
+    security(("bearer_auth" = ["read_only"])),
+    request_body = TotpCodeRequest,
+    responses(
+        (status = 200, body = CommonApiResp<LandscapeUser>),
+        (status = 400, description = "Invalid TOTP code")
+    )
+)]
+async fn activate_totp(
+    State(state): State<LandscapeApp>,
+    Extension(current): Extension<AuthUser>,
+    JsonBody(req): JsonBody<TotpCodeRequest>,
+) -> LandscapeApiResult<LandscapeUser> {
+    current.require_session()?;
+    let user = current_user(&state, &current).await?;
+    let Some(secret) = state.user_service.pending_totp_secret(user.id) else {
+        return Err(UserError::TotpNotEnrolled)?;
+    };
+    if !state.user_service.accept_totp(user.id, &secret, &req.code) {
+        return Err(UserError::InvalidTotpCode)?;
+    }
+    LandscapeApiResp::success(state.user_service.activate_totp(user, secret).await?)
+}
+
+#[utoipa::path(
+    post,
+    path = "/account/totp/disable",
+    tag = "Users",
+    security(("bearer_auth" = ["read_only"])),
+    request_body = TotpCodeRequest,
+    responses(
+        (status = 200, body = CommonApiResp<LandscapeUser>),
+        (status = 400, description = "Invalid TOTP code")
+    )
+)]
+async fn disable_totp(
+    State(state): State<LandscapeApp>,
+    Extension(current): Extension<AuthUser>,
+    JsonBody(req): JsonBody<TotpCodeRequest>,
+) -> LandscapeApiResult<LandscapeUser> {
+    current.require_session()?;
+    let user = current_user(&state, &current).await?;
+    if user.totp_enable && !state.user_service.verify_totp(&user, &req.code) {
+        return Err(UserError::InvalidTotpCode)?;
+    }
+    LandscapeApiResp::success(state.user_service.disable_totp(user).await?)
+}
+
+#[utoipa::path(
+    get,
+    path = "/account/tokens",
+    tag = "Users",
+    responses((status = 200, body = CommonApiResp<Vec<ApiToken>>))
+)]
+async fn list_account_tokens(
+    State(state): State<LandscapeApp>,
+    Extension(current): Extension<AuthUser>,
+) -> LandscapeApiResult<Vec<ApiToken>> {
+    LandscapeApiResp::success(state.user_service.list_tokens(current.user_id).await)
+}
+
+#[utoipa::path(
+    post,
+    path = "/account/tokens",
+    tag = "Users",
+    security(("bearer_auth" = ["read_only"])),
+    request_body = CreateApiTokenRequest,
+    responses(
+        (status = 200, body = CommonApiResp<CreateApiTokenResult>),
+        (status = 403, description = "Token scope exceeds current role")
+    )
+)]
+async fn create_account_token(
+    State(state): State<LandscapeApp>,
+    Extension(current): Extension<AuthUser>,
+    JsonBody(req): JsonBody<CreateApiTokenRequest>,
+) -> LandscapeApiResult<CreateApiTokenResult> {
+    current.require_session()?;
+    let role = req.role.unwrap_or(current.role);
+    if role > current.role {
+        return Err(UserError::TokenScopeExceeded(role))?;
+    }
+    let result =
+        state.user_service.create_token(current.user_id, req.name, role, req.expire_secs).await?;
+    LandscapeApiResp::success(result)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/account/tokens/{id}",
+    tag = "Users",
+    security(("bearer_auth" = ["read_only"])),
+    params(("id" = Uuid, Path, description = "API token ID")),
+    responses(
+        (status = 200, body = CommonApiResp<ApiToken>),
+        (status = 404, description = "Not found")
+    )
+)]
+async fn revoke_api_token(
+    State(state): State<LandscapeApp>,
+    Extension(current): Extension<AuthUser>,
+    Path(id): Path<ConfigId>,
+) -> LandscapeApiResult<ApiToken> {
+    current.require_session()?;
+    // 管理员可以吊销任意用户的 Token
+    let token = state
+        .user_service
+        .find_token(id)
+        .await
+        .filter(|t| t.user_id == current.user_id || current.role.is_admin())
+        .ok_or(UserError::TokenNotFound(id))?;
+    LandscapeApiResp::success(state.user_service.revoke_token(token).await?)
+}
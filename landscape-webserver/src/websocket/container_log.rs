@@ -0,0 +1,51 @@
+use axum::{
+    extract::{
+        ws::{Message, Utf8Bytes, WebSocket},
+        Path, State, WebSocketUpgrade,
+    },
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use landscape_common::config::ConfigId;
+use tokio::sync::mpsc;
+
+use crate::error::LandscapeApiError;
+use crate::LandscapeApp;
+
+pub async fn get_container_log_socks_paths() -> Router<LandscapeApp> {
+    Router::new().route("/managed_containers/{id}/logs", get(listen_container_log))
+}
+
+async fn listen_container_log(
+    State(state): State<LandscapeApp>,
+    Path(id): Path<ConfigId>,
+    ws: WebSocketUpgrade,
+) -> Result<Response, LandscapeApiError> {
+    let logs = state.managed_container_service.logs(id).await?;
+    Ok(ws.on_upgrade(move |socket| handle_socket(socket, logs)).into_response())
+}
+
+async fn handle_socket(mut socket: WebSocket, mut logs: mpsc::Receiver<String>) {
+    loop {
+        tokio::select! {
+            msg = socket.recv() => {
+                match msg {
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => {}
+                }
+            }
+            line = logs.recv() => {
+                let Some(line) = line else {
+                    break;
+                };
+                if let Err(e) = socket.send(Message::Text(Utf8Bytes::from(line))).await {
+                    tracing::info!("send container log error: {e:?}");
+                    break;
+                }
+            }
+        }
+    }
+    let _ = socket.send(Message::Close(None)).await;
+    tracing::info!("Container log websocket closed");
+}
@@ -1,2 +1,3 @@
+pub mod container_log;
 pub mod docker_task;
 pub mod web_pty;
@@ -3,25 +3,31 @@ use axum::{
         ws::{Message, WebSocket},
         Query, WebSocketUpgrade,
     },
-    response::IntoResponse,
+    response::{IntoResponse, Response},
     routing::get,
-    Router,
+    Extension, Router,
 };
 use futures::SinkExt;
 use landscape::sys_service::web_pty::LandscapePtySession;
 use landscape_common::pty::{LandscapePtyConfig, LandscapePtySize, PtyInMessage, PtyOutMessage};
 use tokio::sync::{broadcast, mpsc};
 
+use crate::auth::AuthUser;
+use crate::error::LandscapeApiError;
 use crate::LandscapeApp;
 
 pub async fn get_web_pty_socks_paths() -> Router<LandscapeApp> {
     Router::new().route("/sessions", get(create_pty))
 }
 
+/// 终端为 root shell, 仅管理员可用
 async fn create_pty(
+    Extension(current): Extension<AuthUser>,
     Query(param): Query<serde_json::Value>,
     ws: WebSocketUpgrade,
-) -> impl IntoResponse {
+) -> Result<Response, LandscapeApiError> {
+    current.require_admin()?;
+
     fn parse_u16(v: &serde_json::Value) -> u16 {
         v.as_str().and_then(|s| s.parse::<u16>().ok()).unwrap_or(0)
     }
@@ -40,7 +46,7 @@ async fn create_pty(
     println!("query: {config:?}");
 
     let session = LandscapePtySession::new(config).await.unwrap();
-    ws.on_upgrade(move |socket| handle_socket(socket, session))
+    Ok(ws.on_upgrade(move |socket| handle_socket(socket, session)).into_response())
 }
 
 async fn handle_socket(mut socket: WebSocket, session: LandscapePtySession) {
@@ -0,0 +1,62 @@
+import {
+  getManagedContainerStatus,
+  addManagedContainer,
+  delManagedContainer,
+  startManagedContainer,
+  stopManagedContainer,
+  recreateManagedContainer,
+  updateManagedContainer,
+  checkManagedContainerUpdate,
+} from "@landscape-router/types/api/docker-managed-containers/docker-managed-containers";
+import type {
+  ManagedContainerConfig,
+  ManagedContainerStatus,
+} from "@landscape-router/types/api/schemas";
+import { LANDSCAPE_TOKEN_KEY } from "@/lib/common";
+
+export type { ManagedContainerConfig, ManagedContainerStatus };
+
+export async function get_managed_container_status(): Promise<
+  ManagedContainerStatus[]
+> {
+  return getManagedContainerStatus();
+}
+
+export async function push_managed_container(
+  config: ManagedContainerConfig,
+): Promise<void> {
+  await addManagedContainer(config);
+}
+
+export async function delete_managed_container(id: string): Promise<void> {
+  await delManagedContainer(id);
+}
+
+export async function start_managed_container(id: string): Promise<void> {
+  await startManagedContainer(id);
+}
+
+export async function stop_managed_container(id: string): Promise<void> {
+  await stopManagedContainer(id);
+}
+
+export async function recreate_managed_container(id: string): Promise<void> {
+  await recreateManagedContainer(id);
+}
+
+export async function update_managed_container(id: string): Promise<void> {
+  await updateManagedContainer(id);
+}
+
+export async function check_managed_container_update(
+  id: string,
+): Promise<boolean> {
+  return checkManagedContainerUpdate(id);
+}
+
+export function managed_container_log_socket(id: string): WebSocket {
+  const token = localStorage.getItem(LANDSCAPE_TOKEN_KEY);
+  return new WebSocket(
+    `wss://${window.location.hostname}:${window.location.port}/api/ws/docker/managed_containers/${id}/logs?token=${token}`,
+  );
+}
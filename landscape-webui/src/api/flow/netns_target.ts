@@ -0,0 +1,30 @@
+import {
+  getNetnsTargets,
+  getNetnsTargetStatus,
+  addNetnsTarget,
+  delNetnsTarget,
+} from "@landscape-router/types/api/namespace-targets/namespace-targets";
+import type {
+  NetnsTargetConfig,
+  NetnsTargetStatus,
+} from "@landscape-router/types/api/schemas";
+
+export type { NetnsTargetConfig, NetnsTargetStatus };
+
+export async function get_netns_targets(): Promise<NetnsTargetConfig[]> {
+  return getNetnsTargets();
+}
+
+export async function get_netns_target_status(): Promise<NetnsTargetStatus[]> {
+  return getNetnsTargetStatus();
+}
+
+export async function push_netns_target(
+  config: NetnsTargetConfig,
+): Promise<void> {
+  await addNetnsTarget(config);
+}
+
+export async function delete_netns_target(id: string): Promise<void> {
+  await delNetnsTarget(id);
+}
@@ -0,0 +1,32 @@
+import {
+  getTrafficQuotas,
+  getTrafficQuotaStatus,
+  addTrafficQuota,
+  delTrafficQuota,
+} from "@landscape-router/types/api/traffic-quotas/traffic-quotas";
+import type {
+  TrafficQuotaConfig,
+  TrafficQuotaStatus,
+} from "@landscape-router/types/api/schemas";
+
+export type { TrafficQuotaConfig, TrafficQuotaStatus };
+
+export async function get_traffic_quotas(): Promise<TrafficQuotaConfig[]> {
+  return getTrafficQuotas();
+}
+
+export async function get_traffic_quota_status(): Promise<
+  TrafficQuotaStatus[]
+> {
+  return getTrafficQuotaStatus();
+}
+
+export async function push_traffic_quota(
+  config: TrafficQuotaConfig,
+): Promise<void> {
+  await addTrafficQuota(config);
+}
+
+export async function delete_traffic_quota(id: string): Promise<void> {
+  await delTrafficQuota(id);
+}
@@ -0,0 +1,28 @@
+import {
+  getRateLimits,
+  getRateLimitStatus,
+  addRateLimit,
+  delRateLimit,
+} from "@landscape-router/types/api/rate-limits/rate-limits";
+import type {
+  RateLimitConfig,
+  RateLimitStatus,
+} from "@landscape-router/types/api/schemas";
+
+export type { RateLimitConfig, RateLimitStatus };
+
+export async function get_rate_limits(): Promise<RateLimitConfig[]> {
+  return getRateLimits();
+}
+
+export async function get_rate_limit_status(): Promise<RateLimitStatus[]> {
+  return getRateLimitStatus();
+}
+
+export async function push_rate_limit(config: RateLimitConfig): Promise<void> {
+  await addRateLimit(config);
+}
+
+export async function delete_rate_limit(id: string): Promise<void> {
+  await delRateLimit(id);
+}
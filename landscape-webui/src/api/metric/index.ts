@@ -7,6 +7,7 @@ import type {
   ConnectGlobalStats,
   IpRealtimeStat,
   IpHistoryStat,
+  DomainHistoryStat,
   GetConnectHistoryParams as ConnectHistoryQueryParams,
   MetricResolution,
 } from "@landscape-router/types/api/schemas";
@@ -20,9 +21,11 @@ import {
   getConnectMetricInfo as _getConnectMetricInfo,
   getHistorySrcIpStats as _getHistorySrcIpStats,
   getHistoryDstIpStats as _getHistoryDstIpStats,
+  getHistoryDomainStats as _getHistoryDomainStats,
 } from "@landscape-router/types/api/metric/metric";
 
 export * from "./dns";
+export * from "./traffic";
 
 export async function get_src_ip_stats(): Promise<IpRealtimeStat[]> {
   return _getSrcIpStats();
@@ -68,3 +71,9 @@ export async function get_history_dst_ip_stats(
 ): Promise<IpHistoryStat[]> {
   return _getHistoryDstIpStats(params);
 }
+
+export async function get_history_domain_stats(
+  params?: ConnectHistoryQueryParams,
+): Promise<DomainHistoryStat[]> {
+  return _getHistoryDomainStats(params);
+}
@@ -0,0 +1,33 @@
+import type {
+  TrafficPoint,
+  TrafficTotal,
+  TrafficMonthlyReport,
+  GetTrafficHistoryParams,
+  GetTrafficTopTalkersParams,
+  GetTrafficMonthlyParams,
+} from "@landscape-router/types/api/schemas";
+import {
+  getTrafficHistory as _getTrafficHistory,
+  getTrafficTopTalkers as _getTrafficTopTalkers,
+  getTrafficMonthly as _getTrafficMonthly,
+} from "@landscape-router/types/api/metric/metric";
+
+export type { TrafficPoint, TrafficTotal, TrafficMonthlyReport };
+
+export async function get_traffic_history(
+  params: GetTrafficHistoryParams,
+): Promise<TrafficPoint[]> {
+  return _getTrafficHistory(params);
+}
+
+export async function get_traffic_top_talkers(
+  params: GetTrafficTopTalkersParams,
+): Promise<TrafficTotal[]> {
+  return _getTrafficTopTalkers(params);
+}
+
+export async function get_traffic_monthly(
+  params: GetTrafficMonthlyParams,
+): Promise<TrafficMonthlyReport> {
+  return _getTrafficMonthly(params);
+}
@@ -4,6 +4,8 @@ import {
   setController as add_controller,
   createBridge,
   deleteBridge as delete_bridge,
+  createVirtualDev as create_virtual_dev,
+  deleteVirtualDev as delete_virtual_dev,
   changeZone as change_zone,
   changeDevStatus as change_iface_status,
   changeWifiMode as change_wifi_mode,
@@ -12,6 +14,8 @@ import {
 export {
   add_controller,
   delete_bridge,
+  create_virtual_dev,
+  delete_virtual_dev,
   change_zone,
   change_iface_status,
   change_wifi_mode,
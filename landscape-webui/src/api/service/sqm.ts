@@ -0,0 +1,45 @@
+import { ServiceStatus } from "@/lib/services";
+import type {
+  SqmServiceConfig,
+  SqmStats,
+} from "@landscape-router/types/api/schemas";
+import {
+  getAllSqmServiceStatus,
+  getSqmServiceConfig,
+  getSqmStats,
+  handleSqmServiceConfig,
+  deleteAndStopSqmService,
+} from "@landscape-router/types/api/sqm/sqm";
+
+export async function get_all_sqm_status(): Promise<
+  Map<string, ServiceStatus>
+> {
+  const data = await getAllSqmServiceStatus();
+  const map = new Map<string, ServiceStatus>();
+  for (const [key, value] of Object.entries(data)) {
+    map.set(key, value as ServiceStatus);
+  }
+  return map;
+}
+
+export async function get_iface_sqm_config(
+  iface_name: string,
+): Promise<SqmServiceConfig> {
+  return await getSqmServiceConfig(iface_name);
+}
+
+export async function get_iface_sqm_stats(
+  iface_name: string,
+): Promise<SqmStats> {
+  return await getSqmStats(iface_name);
+}
+
+export async function update_sqm_config(
+  sqm_config: SqmServiceConfig,
+): Promise<void> {
+  await handleSqmServiceConfig(sqm_config);
+}
+
+export async function stop_and_del_iface_sqm(name: string): Promise<void> {
+  await deleteAndStopSqmService(name);
+}
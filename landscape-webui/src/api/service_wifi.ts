@@ -5,7 +5,13 @@ import {
   getWifiServiceConfig,
   handleWifiServiceConfig,
   deleteAndStopWifiService,
+  getWifiStations,
+  getWifiCapability,
 } from "@landscape-router/types/api/wi-fi/wi-fi";
+import type {
+  WifiPhyCapability,
+  WifiStationInfo,
+} from "@landscape-router/types/api/schemas";
 
 export async function get_all_wifi_status(): Promise<
   Map<string, ServiceStatus>
@@ -34,3 +40,15 @@ export async function update_wifi_config(
 export async function stop_and_del_iface_wifi(name: string): Promise<void> {
   await deleteAndStopWifiService(name);
 }
+
+export async function get_wifi_stations(
+  iface_name: string,
+): Promise<WifiStationInfo[]> {
+  return await getWifiStations(iface_name);
+}
+
+export async function get_wifi_capability(
+  iface_name: string,
+): Promise<WifiPhyCapability> {
+  return await getWifiCapability(iface_name);
+}
@@ -0,0 +1,55 @@
+import { ServiceStatus } from "@/lib/services";
+import {
+  getAllWifiStationServiceStatus,
+  getWifiStationServiceConfig,
+  handleWifiStationServiceConfig,
+  deleteAndStopWifiStationService,
+  getWifiStationRuntimeStatus,
+  getWifiStationScanResults,
+} from "@landscape-router/types/api/wi-fi-station/wi-fi-station";
+import type {
+  WifiScanResult,
+  WifiStationServiceConfig,
+  WifiStationStatus,
+} from "@landscape-router/types/api/schemas";
+
+export async function get_all_wifi_station_status(): Promise<
+  Map<string, ServiceStatus>
+> {
+  const data = await getAllWifiStationServiceStatus();
+  const map = new Map<string, ServiceStatus>();
+  for (const [key, value] of Object.entries(data)) {
+    map.set(key, value as ServiceStatus);
+  }
+  return map;
+}
+
+export async function get_iface_wifi_station_config(
+  iface_name: string,
+): Promise<WifiStationServiceConfig> {
+  return await getWifiStationServiceConfig(iface_name);
+}
+
+export async function update_wifi_station_config(
+  config: WifiStationServiceConfig,
+): Promise<void> {
+  await handleWifiStationServiceConfig(config);
+}
+
+export async function stop_and_del_iface_wifi_station(
+  name: string,
+): Promise<void> {
+  await deleteAndStopWifiStationService(name);
+}
+
+export async function get_wifi_station_status(
+  iface_name: string,
+): Promise<WifiStationStatus> {
+  return await getWifiStationRuntimeStatus(iface_name);
+}
+
+export async function get_wifi_scan_results(
+  iface_name: string,
+): Promise<WifiScanResult[]> {
+  return await getWifiStationScanResults(iface_name);
+}
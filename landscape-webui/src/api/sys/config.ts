@@ -1,4 +1,9 @@
 import type {
+  ConfigImportMode,
+  ConfigImportResult,
+  ConfigSectionDiff,
+  ConfigSnapshot,
+  ConfigSnapshotInfo,
   GetDnsConfigResponse,
   GetDnsConfigResponse as GetDnsConfigFastResponse,
   GetMetricConfigResponse,
@@ -6,11 +11,19 @@ import type {
   LandscapeDnsConfig,
   LandscapeMetricConfig,
   LandscapeUIConfig,
+  PendingConfigConfirm,
   UpdateMetricConfigRequest,
   UpdateUIConfigRequest,
 } from "@landscape-router/types/api/schemas";
 import {
   exportInitConfig,
+  importInitConfig,
+  listConfigSnapshots,
+  compareConfigSnapshots,
+  getConfigSnapshot,
+  restoreConfigSnapshot,
+  listPendingConfigConfirms,
+  confirmConfigChange,
   getUiConfigFast,
   getUiConfig,
   updateUiConfig,
@@ -50,6 +63,45 @@ export async function get_init_config(): Promise<void> {
   }
 }
 
+export async function import_init_config(
+  content: string,
+  mode: ConfigImportMode,
+  dry_run: boolean,
+): Promise<ConfigImportResult> {
+  return await importInitConfig(content, { mode, dry_run });
+}
+
+export async function list_config_snapshots(): Promise<ConfigSnapshotInfo[]> {
+  return await listConfigSnapshots();
+}
+
+export async function get_config_snapshot(id: string): Promise<ConfigSnapshot> {
+  return await getConfigSnapshot(id);
+}
+
+export async function compare_config_snapshots(
+  from: string,
+  to: string,
+): Promise<ConfigSectionDiff[]> {
+  return await compareConfigSnapshots({ from, to });
+}
+
+export async function restore_config_snapshot(
+  id: string,
+): Promise<ConfigImportResult> {
+  return await restoreConfigSnapshot(id);
+}
+
+export async function list_pending_config_confirms(): Promise<
+  PendingConfigConfirm[]
+> {
+  return await listPendingConfigConfirms();
+}
+
+export async function confirm_config_change(id: string): Promise<void> {
+  await confirmConfigChange(id);
+}
+
 export async function get_ui_config(): Promise<LandscapeUIConfig> {
   return await getUiConfigFast();
 }
@@ -0,0 +1,100 @@
+import type {
+  ApiToken,
+  AuditLog,
+  AuditLogQuery,
+  ChangePasswordRequest,
+  CreateApiTokenRequest,
+  CreateApiTokenResult,
+  CreateUserRequest,
+  LandscapeUser,
+  TotpEnrollment,
+  UpdateUserRequest,
+} from "@landscape-router/types/api/schemas";
+import {
+  listUsers,
+  createUser,
+  updateUser,
+  deleteUser,
+  listUserTokens,
+  getCurrentAccount,
+  changePassword,
+  listAccountTokens,
+  createAccountToken,
+  revokeApiToken,
+  enrollTotp,
+  activateTotp,
+  disableTotp,
+  resetUserTotp,
+  listAuditLogs,
+} from "@landscape-router/types/api/users/users";
+
+export async function list_users(): Promise<LandscapeUser[]> {
+  return await listUsers();
+}
+
+export async function create_user(
+  payload: CreateUserRequest,
+): Promise<LandscapeUser> {
+  return await createUser(payload);
+}
+
+export async function update_user(
+  id: string,
+  payload: UpdateUserRequest,
+): Promise<LandscapeUser> {
+  return await updateUser(id, payload);
+}
+
+export async function delete_user(id: string): Promise<void> {
+  await deleteUser(id);
+}
+
+export async function list_user_tokens(id: string): Promise<ApiToken[]> {
+  return await listUserTokens(id);
+}
+
+export async function get_current_account(): Promise<LandscapeUser> {
+  return await getCurrentAccount();
+}
+
+export async function change_password(
+  payload: ChangePasswordRequest,
+): Promise<void> {
+  await changePassword(payload);
+}
+
+export async function list_account_tokens(): Promise<ApiToken[]> {
+  return await listAccountTokens();
+}
+
+export async function create_account_token(
+  payload: CreateApiTokenRequest,
+): Promise<CreateApiTokenResult> {
+  return await createAccountToken(payload);
+}
+
+export async function revoke_api_token(id: string): Promise<ApiToken> {
+  return await revokeApiToken(id);
+}
+
+export async function enroll_totp(code?: string): Promise<TotpEnrollment> {
+  return await enrollTotp({ code });
+}
+
+export async function activate_totp(code: string): Promise<LandscapeUser> {
+  return await activateTotp({ code });
+}
+
+export async function disable_totp(code: string): Promise<LandscapeUser> {
+  return await disableTotp({ code });
+}
+
+export async function reset_user_totp(id: string): Promise<LandscapeUser> {
+  return await resetUserTotp(id);
+}
+
+export async function list_audit_logs(
+  query: AuditLogQuery,
+): Promise<AuditLog[]> {
+  return await listAuditLogs(query);
+}
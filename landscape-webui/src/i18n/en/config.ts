@@ -47,10 +47,100 @@ export default {
   max_memory_desc: "Maximum memory allowed for metric cache",
   max_threads: "Max Threads",
   max_threads_desc: "Number of background threads for processing metric data",
+  metric_db_path: "Database Directory",
+  metric_db_path_desc:
+    "Directory for the metric database, defaults to the metric directory under the config dir. Takes effect after restart",
+  prometheus_settings: "Prometheus Export",
+  prometheus_token: "Access Token",
+  prometheus_token_desc:
+    "Bearer token for /metrics; /metrics is not served on the web port when unset",
+  prometheus_listen: "Separate Listen",
+  prometheus_listen_desc:
+    "Plain HTTP address serving /metrics, e.g. 127.0.0.1:9100. Takes effect after restart",
 
   backup_desc:
     "You can export all current router configurations (including DNS, firewall, network interfaces, etc.) as an init file for quick recovery or migration.",
   export_init: "Export all current configurations as Init file",
+  import_init: "Import Init file",
+  import_mode_replace: "Replace all",
+  import_mode_merge: "Merge by ID",
+  import_section: "Section",
+  import_added: "Added",
+  import_changed: "Changed",
+  import_removed: "Removed",
+  import_no_change: "No differences from current configuration",
+  import_apply: "Apply import",
+  import_confirm:
+    "Affected services will be restarted after import. Continue?",
+  import_cancel: "Cancel",
+  import_success: "Configuration imported",
+  import_failed: "Import failed",
+  snapshot_title: "Config History",
+  snapshot_desc:
+    "A snapshot is recorded after every configuration change. Send the X-Commit-Confirm header with a number of seconds to make an API change revert automatically unless confirmed in time.",
+  snapshot_refresh: "Refresh",
+  snapshot_pending: "Change waiting for confirmation, reverts at",
+  snapshot_confirm: "Confirm",
+  snapshot_empty: "No snapshots yet",
+  snapshot_time: "Time",
+  snapshot_author: "Author",
+  snapshot_summary: "Change",
+  snapshot_changes: "Items",
+  snapshot_compare: "Compare with latest",
+  snapshot_restore: "Restore",
+  snapshot_restore_success: "Snapshot restored",
+  user_title: "Users",
+  user_current: "Signed in as {name}",
+  user_role: "Role",
+  user_role_admin: "Admin",
+  user_role_operator: "Operator",
+  user_role_read_only: "Read-only",
+  user_change_password: "Change password",
+  user_old_password: "Current password",
+  user_new_password: "New password",
+  user_save: "Save",
+  user_password_changed: "Password changed, please log in again",
+  user_tokens: "API tokens",
+  user_token_name: "Name",
+  user_token_role: "Scope (default: own role)",
+  user_token_expire_days: "Valid days",
+  user_token_expire_at: "Expires",
+  user_token_never: "Never",
+  user_token_create: "Create token",
+  user_token_secret: "Copy the token now, it will not be shown again",
+  user_token_revoke: "Revoke",
+  user_token_revoked: "Revoked",
+  user_token_revoke_confirm: "Revoke this token?",
+  user_list: "User management",
+  user_username: "Username",
+  user_password: "Password",
+  user_enable: "Enabled",
+  user_create: "Add user",
+  user_delete: "Delete",
+  user_delete_confirm: "Delete this user and all of its tokens?",
+  user_totp: "Two-factor authentication",
+  user_totp_enabled: "Enabled",
+  user_totp_enroll: "Enable two-factor authentication",
+  user_totp_enroll_desc:
+    "Add this key to your authenticator app, then enter the 6-digit code to finish",
+  user_totp_code: "6-digit code",
+  user_totp_activate: "Verify and enable",
+  user_totp_disable: "Disable",
+  user_totp_saved: "Two-factor authentication updated",
+  user_totp_reset: "Reset 2FA",
+  audit_title: "Audit Log",
+  audit_time: "Time",
+  audit_user: "User",
+  audit_source_ip: "Source IP",
+  audit_route: "Request",
+  audit_status: "Status",
+  audit_summary: "Changes",
+  audit_kind: "Type",
+  audit_kind_login: "Login",
+  audit_kind_login_failed: "Login failed",
+  audit_kind_api: "API",
+  audit_search: "Search",
+  audit_empty: "No records",
 
   load_failed: "Failed to load configuration",
   save_success: "Save successful",
@@ -6,6 +6,12 @@ export default {
   "flow_rule.duplicate_entry": "Duplicate entry match rule: {0}",
   "flow_rule.conflict_entry":
     "Entry rule '{rule}' conflicts with flow '{flow_remark}' (ID: {flow_id})",
+  "flow_rule.invalid_dscp": "Invalid DSCP value {0}, must be 0-63",
+  "flow_rule.invalid_vlan": "Invalid VLAN ID {0}, must be 1-4094",
+  "flow_rule.empty_iface": "Entry rule requires an interface name",
+  "flow_rule.invalid_health_check": "Invalid health check: {0}",
+  "flow_rule.invalid_target": "Invalid flow target: {0}",
+  "flow_rule.bridge_port_ifindex": "Bridge port {0} ifindex {1} exceeds 16 bits",
   "firewall_rule.not_found": "Firewall rule not found (ID: {0})",
   "firewall_blacklist.not_found": "Firewall blacklist not found (ID: {0})",
   "dhcp.config_not_found": "DHCP service config for '{0}' not found",
@@ -20,13 +26,55 @@ export default {
   "geo_ip.file_read_error": "GeoIP file read error",
   "static_nat.not_found": "Static NAT mapping not found (ID: {0})",
   "dst_ip_rule.not_found": "Destination IP rule not found (ID: {0})",
+  "dst_ip_rule.invalid": "Invalid destination IP rule: {0}",
+  "dst_ip_rule.port_rule_limit": "Flow {0} has more than {1} protocol / port rules per IP version",
   "enrolled_device.invalid": "Invalid enrolled device data: {0}",
+  "traffic_quota.not_found": "Traffic quota not found (ID: {0})",
+  "traffic_quota.invalid": "Invalid traffic quota: {0}",
+  "rate_limit.not_found": "Rate limit not found (ID: {0})",
+  "rate_limit.invalid": "Invalid rate limit: {0}",
+  "netns_target.not_found": "Namespace target not found (ID: {0})",
+  "netns_target.duplicate_name": "Namespace target name '{0}' is already used",
+  "netns_target.invalid": "Invalid namespace target: {0}",
+  "managed_container.not_found": "Managed container not found (ID: {0})",
+  "managed_container.duplicate_name":
+    "Managed container name '{0}' is already used",
+  "managed_container.invalid": "Invalid managed container: {0}",
+  "managed_container.not_managed":
+    "Container '{0}' exists but is not managed by this definition",
+  "managed_container.docker": "Docker error: {0}",
+  "config_import.parse_failed": "Failed to parse config file: {0}",
+  "config_import.invalid_section":
+    "Invalid config in section '{section}' ({id}): {reason}",
+  "config_import.reload_failed":
+    "Failed to reload '{0}' after import, the previous config has been restored",
+  "config_snapshot.not_found": "Config snapshot not found (ID: {0})",
+  "config_snapshot.confirm_not_found":
+    "Pending confirmation not found or already expired (ID: {0})",
+  "config_snapshot.invalid_timeout": "Invalid commit confirm timeout: {0}",
+  "user.not_found": "User not found (ID: {0})",
+  "user.username_exists": "Username already exists: {0}",
+  "user.invalid_username": "Invalid username: {0}",
+  "user.password_too_short": "Password must be at least {0} characters",
+  "user.wrong_password": "Current password is incorrect",
+  "user.totp_not_enrolled": "Two-factor authentication has not been enrolled",
+  "user.invalid_totp_code": "Invalid two-factor authentication code",
+  "user.last_admin": "At least one enabled admin must remain",
+  "user.token_not_found": "API token not found (ID: {0})",
+  "user.token_scope_exceeded": "Token scope exceeds your role",
   "service.config_not_found": "{service_name} service config not found",
   "auth.missing_header": "Missing Authorization header",
   "auth.invalid_format": "Invalid Authorization header format",
   "auth.invalid_token": "Invalid token, please log in again",
   "auth.unauthorized": "Unauthorized user",
+  "auth.forbidden": "Your role is not allowed to perform this action",
+  "auth.session_required":
+    "Account settings can only be changed after logging in, not with an API token",
   "auth.invalid_credentials": "Invalid username or password",
+  "auth.totp_required": "Enter the code from your authenticator app",
+  "auth.invalid_totp_code": "Invalid two-factor authentication code",
+  "auth.too_many_attempts":
+    "Too many failed login attempts, retry in {0} seconds",
   "auth.token_creation_failed": "Token creation failed",
   "docker.create_failed": "Failed to create container",
   "docker.start_failed": "Failed to start container",
@@ -1,5 +1,8 @@
 import dns from "./metric/dns";
 import connect from "./metric/connect";
+import traffic from "./metric/traffic";
+import quota from "./metric/quota";
+import rate_limit from "./metric/rate_limit";
 import sysinfo from "./sysinfo";
 import config from "./config";
 import error from "./error";
@@ -11,6 +14,9 @@ export default {
   metric: {
     dns,
     connect,
+    traffic,
+    quota,
+    rate_limit,
   },
   sysinfo,
   config,
@@ -42,7 +48,11 @@ export default {
     "connect-dst": "Dst IP Stats",
     "connect-history-src": "Src IP History",
     "connect-history-dst": "Dst IP History",
+    "connect-history-domain": "Domain History",
     "dns-metric": "DNS Metrics",
+    "traffic-quota": "Traffic Quotas",
+    "rate-limit": "Rate Limits",
+    "traffic-metric": "Traffic Statistics",
     "ipv6-pd": "IPv6 PD",
     "dhcp-v4": "DHCPv4 Service",
     "ipv6-ra": "IPv6 RA",
@@ -10,6 +10,7 @@ export default {
     history: "History",
     history_src: "Src IP History",
     history_dst: "Dst IP History",
+    history_domain: "Domain History",
   },
   filter: {
     now: "Active at",
@@ -33,6 +34,7 @@ export default {
     gress: "Direction",
     gress_egress: "Outbound",
     gress_ingress: "Inbound",
+    domain: "Domain",
   },
   stats: {
     live_src: "Src IP Real-time Stats",
@@ -55,6 +57,7 @@ export default {
     filter_ingress: "Filtered Ingress",
     filter_egress_pkts: "Filtered Egress Pkts",
     filter_ingress_pkts: "Filtered Ingress Pkts",
+    history_domain: "Domain Traffic History Aggregation",
   },
   col: {
     ip: "IP Address",
@@ -72,6 +75,7 @@ export default {
     egress_pkts: "Egress Packets",
     ingress_pkts: "Ingress Packets",
     port: "Port",
+    domain: "Domain",
   },
   tip: {
     search_ip: "Search this IP",
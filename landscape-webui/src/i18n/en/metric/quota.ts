@@ -0,0 +1,30 @@
+export default {
+  title: "Traffic Quota",
+  add: "Add Quota",
+  refresh: "Refresh",
+  edit: "Edit",
+  delete: "Delete",
+  save: "Save",
+  cancel: "Cancel",
+  saved: "Saved",
+  enable: "Enable",
+  disabled: "Disabled",
+  remark: "Remark",
+  limit: "Limit",
+  daily: "Daily",
+  monthly: "Monthly",
+  reset_day: "Reset day",
+  iface_name: "WAN interface name",
+  target_device: "Device",
+  target_flow: "Flow",
+  target_wan: "WAN",
+  action_notify: "Notify",
+  action_throttle: "Throttle",
+  action_switch_flow: "Switch Flow",
+  action_drop: "Drop",
+  col_target: "Target",
+  col_period: "Period",
+  col_usage: "Usage",
+  col_action: "When exceeded",
+  col_exceeded: "Exceeded at",
+};
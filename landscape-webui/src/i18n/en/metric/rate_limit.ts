@@ -0,0 +1,27 @@
+export default {
+  title: "Rate Limit",
+  add: "Add Rate Limit",
+  refresh: "Refresh",
+  edit: "Edit",
+  delete: "Delete",
+  save: "Save",
+  cancel: "Cancel",
+  saved: "Saved",
+  enable: "Enable",
+  disabled: "Disabled",
+  remark: "Remark",
+  upload: "Upload",
+  download: "Download",
+  unlimited_hint: "0 means unlimited",
+  target_device: "Device",
+  target_mac: "MAC",
+  target_ip: "IP",
+  target_flow: "Flow",
+  col_target: "Target",
+  col_limit: "Limit (up / down)",
+  col_rate: "Current rate (up / down)",
+  col_drops: "Dropped",
+  unlimited: "Unlimited",
+  edt_hint:
+    "Packets are paced by the fq qdisc on the egress interface. Interfaces with SQM (CAKE / HTB) do not apply these limits.",
+};
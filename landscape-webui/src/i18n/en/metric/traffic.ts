@@ -0,0 +1,18 @@
+export default {
+  title: "Traffic Statistics",
+  top: "Top Talkers",
+  monthly: "Monthly Usage",
+  kind_iface: "Interface",
+  kind_ip: "Host IP",
+  kind_device: "Device",
+  kind_flow: "Flow",
+  month: "Month",
+  reset_day: "Reset day",
+  period: "Billing period: {start} ~ {end}",
+  col_target: "Target",
+  col_device: "Device",
+  col_rx: "Download / RX",
+  col_tx: "Upload / TX",
+  col_total: "Total",
+  query: "Query",
+};
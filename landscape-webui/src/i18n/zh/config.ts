@@ -41,10 +41,97 @@ export default {
   max_memory_desc: "指标缓存允许占用的最大内存",
   max_threads: "并发处理线程",
   max_threads_desc: "用于处理指标数据的后台线程数",
+  metric_db_path: "数据库目录",
+  metric_db_path_desc: "指标数据库所在目录, 默认为配置目录下的 metric, 重启后生效",
+  prometheus_settings: "Prometheus 导出",
+  prometheus_token: "访问令牌",
+  prometheus_token_desc:
+    "以 Bearer 方式访问 /metrics 的令牌, 未设置时 Web 端口不提供 /metrics",
+  prometheus_listen: "独立监听地址",
+  prometheus_listen_desc: "单独提供 /metrics 的 HTTP 地址, 如 127.0.0.1:9100, 重启后生效",
 
   backup_desc:
     "你可以将当前路由器的所有配置（除 Docker 相关）导出为一个初始化文件，用于快速恢复或迁移。",
   export_init: "导出当前所有配置为 Init 文件",
+  import_init: "导入 Init 文件",
+  import_mode_replace: "整体替换",
+  import_mode_merge: "按 ID 合并",
+  import_section: "分区",
+  import_added: "新增",
+  import_changed: "修改",
+  import_removed: "删除",
+  import_no_change: "与当前配置无差异",
+  import_apply: "应用导入",
+  import_confirm: "导入后将重启受影响的服务, 是否继续?",
+  import_cancel: "取消",
+  import_success: "配置已导入",
+  import_failed: "导入失败",
+  snapshot_title: "配置历史",
+  snapshot_desc:
+    "每次修改配置后都会记录一个快照。通过 API 修改时携带 X-Commit-Confirm 请求头 (秒数), 未在期限内确认的修改将自动回滚。",
+  snapshot_refresh: "刷新",
+  snapshot_pending: "有待确认的修改, 回滚时间",
+  snapshot_confirm: "确认",
+  snapshot_empty: "暂无快照",
+  snapshot_time: "时间",
+  snapshot_author: "操作者",
+  snapshot_summary: "变更",
+  snapshot_changes: "变更项",
+  snapshot_compare: "与最新比较",
+  snapshot_restore: "恢复",
+  snapshot_restore_success: "已恢复到该快照",
+  user_title: "用户",
+  user_current: "当前用户: {name}",
+  user_role: "角色",
+  user_role_admin: "管理员",
+  user_role_operator: "操作员",
+  user_role_read_only: "只读",
+  user_change_password: "修改密码",
+  user_old_password: "当前密码",
+  user_new_password: "新密码",
+  user_save: "保存",
+  user_password_changed: "密码已修改, 请重新登录",
+  user_tokens: "API Token",
+  user_token_name: "名称",
+  user_token_role: "权限范围 (默认同当前角色)",
+  user_token_expire_days: "有效天数",
+  user_token_expire_at: "过期时间",
+  user_token_never: "永不过期",
+  user_token_create: "创建 Token",
+  user_token_secret: "请立即复制 Token, 之后将不再显示",
+  user_token_revoke: "吊销",
+  user_token_revoked: "已吊销",
+  user_token_revoke_confirm: "确定吊销该 Token?",
+  user_list: "用户管理",
+  user_username: "用户名",
+  user_password: "密码",
+  user_enable: "启用",
+  user_create: "添加用户",
+  user_delete: "删除",
+  user_delete_confirm: "删除该用户及其所有 Token?",
+  user_totp: "两步验证",
+  user_totp_enabled: "已启用",
+  user_totp_enroll: "启用两步验证",
+  user_totp_enroll_desc:
+    "在验证器应用中添加以下密钥, 然后输入 6 位验证码完成启用",
+  user_totp_code: "6 位验证码",
+  user_totp_activate: "验证并启用",
+  user_totp_disable: "停用",
+  user_totp_saved: "两步验证已更新",
+  user_totp_reset: "重置两步验证",
+  audit_title: "审计日志",
+  audit_time: "时间",
+  audit_user: "用户",
+  audit_source_ip: "来源 IP",
+  audit_route: "请求",
+  audit_status: "状态",
+  audit_summary: "变更",
+  audit_kind: "类型",
+  audit_kind_login: "登录",
+  audit_kind_login_failed: "登录失败",
+  audit_kind_api: "API",
+  audit_search: "查询",
+  audit_empty: "暂无记录",
 
   load_failed: "加载配置失败",
   save_success: "保存成功",
@@ -6,6 +6,12 @@ export default {
   "flow_rule.duplicate_entry": "入口匹配规则存在重复项: {0}",
   "flow_rule.conflict_entry":
     "入口规则 '{rule}' 与流 '{flow_remark}' (ID: {flow_id}) 冲突",
+  "flow_rule.invalid_dscp": "DSCP 值 {0} 无效, 取值范围为 0-63",
+  "flow_rule.invalid_vlan": "VLAN ID {0} 无效, 取值范围为 1-4094",
+  "flow_rule.empty_iface": "入口规则需要选择网卡",
+  "flow_rule.invalid_health_check": "健康检查配置无效: {0}",
+  "flow_rule.invalid_target": "分流出口无效: {0}",
+  "flow_rule.bridge_port_ifindex": "网桥端口 {0} 的 ifindex {1} 超出 16 位",
   "firewall_rule.not_found": "找不到防火墙规则 (ID: {0})",
   "firewall_blacklist.not_found": "找不到防火墙黑名单 (ID: {0})",
   "dhcp.config_not_found": "找不到 '{0}' 的 DHCP 服务配置",
@@ -20,13 +26,49 @@ export default {
   "geo_ip.file_read_error": "GeoIP 文件读取错误",
   "static_nat.not_found": "找不到静态 NAT 映射 (ID: {0})",
   "dst_ip_rule.not_found": "找不到目标 IP 规则 (ID: {0})",
+  "dst_ip_rule.invalid": "目标 IP 规则无效: {0}",
+  "dst_ip_rule.port_rule_limit": "Flow {0} 中同一 IP 版本带协议 / 端口条件的规则超过 {1} 条",
   "enrolled_device.invalid": "设备数据无效: {0}",
+  "traffic_quota.not_found": "流量配额不存在 (ID: {0})",
+  "traffic_quota.invalid": "流量配额无效: {0}",
+  "rate_limit.not_found": "限速不存在 (ID: {0})",
+  "rate_limit.invalid": "限速无效: {0}",
+  "netns_target.not_found": "命名空间出口不存在 (ID: {0})",
+  "netns_target.duplicate_name": "命名空间出口名称 '{0}' 已被使用",
+  "netns_target.invalid": "命名空间出口无效: {0}",
+  "managed_container.not_found": "托管容器不存在 (ID: {0})",
+  "managed_container.duplicate_name": "托管容器名称 '{0}' 已被使用",
+  "managed_container.invalid": "托管容器配置无效: {0}",
+  "managed_container.not_managed": "容器 '{0}' 已存在但不属于该托管配置",
+  "managed_container.docker": "Docker 错误: {0}",
+  "config_import.parse_failed": "配置文件解析失败: {0}",
+  "config_import.invalid_section":
+    "分区 '{section}' 中的配置 ({id}) 无效: {reason}",
+  "config_import.reload_failed": "导入后重新加载 '{0}' 失败, 已恢复导入前的配置",
+  "config_snapshot.not_found": "配置快照不存在 (ID: {0})",
+  "config_snapshot.confirm_not_found": "待确认的修改不存在或已超时 (ID: {0})",
+  "config_snapshot.invalid_timeout": "确认超时时间无效: {0}",
+  "user.not_found": "用户不存在 (ID: {0})",
+  "user.username_exists": "用户名已存在: {0}",
+  "user.invalid_username": "用户名无效: {0}",
+  "user.password_too_short": "密码至少需要 {0} 个字符",
+  "user.wrong_password": "当前密码错误",
+  "user.totp_not_enrolled": "尚未登记两步验证",
+  "user.invalid_totp_code": "两步验证码错误",
+  "user.last_admin": "至少需要保留一个启用的管理员",
+  "user.token_not_found": "API Token 不存在 (ID: {0})",
+  "user.token_scope_exceeded": "Token 的权限范围不能超过当前角色",
   "service.config_not_found": "找不到 {service_name} 服务配置",
   "auth.missing_header": "缺少认证头",
   "auth.invalid_format": "认证头格式无效",
   "auth.invalid_token": "登录令牌无效，请重新登录",
   "auth.unauthorized": "未授权用户",
+  "auth.forbidden": "当前角色无权执行此操作",
+  "auth.session_required": "账号设置只能在登录后修改, 不能使用 API Token",
   "auth.invalid_credentials": "用户名或密码错误",
+  "auth.totp_required": "请输入验证器中的两步验证码",
+  "auth.invalid_totp_code": "两步验证码错误",
+  "auth.too_many_attempts": "登录失败次数过多, 请 {0} 秒后重试",
   "auth.token_creation_failed": "令牌创建失败",
   "docker.create_failed": "创建容器失败",
   "docker.start_failed": "启动容器失败",
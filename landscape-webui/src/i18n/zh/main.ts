@@ -1,5 +1,8 @@
 import dns from "./metric/dns";
 import connect from "./metric/connect";
+import traffic from "./metric/traffic";
+import quota from "./metric/quota";
+import rate_limit from "./metric/rate_limit";
 import sysinfo from "./sysinfo";
 import config from "./config";
 import error from "./error";
@@ -12,6 +15,9 @@ export default {
   metric: {
     dns,
     connect,
+    traffic,
+    quota,
+    rate_limit,
   },
   sysinfo,
   config,
@@ -44,7 +50,11 @@ export default {
     "connect-dst": "目的 IP 统计",
     "connect-history-src": "源 IP 历史",
     "connect-history-dst": "目的 IP 历史",
+    "connect-history-domain": "域名历史",
     "dns-metric": "DNS 指标",
+    "traffic-quota": "流量配额",
+    "rate-limit": "限速",
+    "traffic-metric": "流量统计",
     "ipv6-pd": "IPv6 PD",
     "dhcp-v4": "DHCPv4 服务",
     "ipv6-ra": "IPv6 RA",
@@ -10,6 +10,7 @@ export default {
     history: "连接历史",
     history_src: "源 IP 历史",
     history_dst: "目的 IP 历史",
+    history_domain: "域名历史",
   },
   filter: {
     now: "活跃于",
@@ -33,6 +34,7 @@ export default {
     gress: "方向",
     gress_egress: "出站",
     gress_ingress: "入站",
+    domain: "域名",
   },
   stats: {
     live_src: "源 IP 实时统计",
@@ -55,6 +57,7 @@ export default {
     filter_ingress: "过滤结果总下行",
     filter_egress_pkts: "过滤结果总出站",
     filter_ingress_pkts: "过滤结果总入站",
+    history_domain: "域名历史流量聚合",
   },
   col: {
     ip: "IP 地址",
@@ -72,6 +75,7 @@ export default {
     egress_pkts: "上传数据包",
     ingress_pkts: "下载数据包",
     port: "端口",
+    domain: "域名",
   },
   tip: {
     search_ip: "搜索此 IP",
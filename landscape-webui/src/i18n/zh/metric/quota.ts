@@ -0,0 +1,30 @@
+export default {
+  title: "流量配额",
+  add: "添加配额",
+  refresh: "刷新",
+  edit: "编辑",
+  delete: "删除",
+  save: "保存",
+  cancel: "取消",
+  saved: "已保存",
+  enable: "启用",
+  disabled: "已禁用",
+  remark: "备注",
+  limit: "配额",
+  daily: "每日",
+  monthly: "每月",
+  reset_day: "重置日",
+  iface_name: "WAN 网卡名称",
+  target_device: "设备",
+  target_flow: "Flow",
+  target_wan: "WAN",
+  action_notify: "仅提示",
+  action_throttle: "限速",
+  action_switch_flow: "切换 Flow",
+  action_drop: "断网",
+  col_target: "对象",
+  col_period: "周期",
+  col_usage: "用量",
+  col_action: "超出后",
+  col_exceeded: "超出时间",
+};
@@ -0,0 +1,27 @@
+export default {
+  title: "限速",
+  add: "添加限速",
+  refresh: "刷新",
+  edit: "编辑",
+  delete: "删除",
+  save: "保存",
+  cancel: "取消",
+  saved: "已保存",
+  enable: "启用",
+  disabled: "已禁用",
+  remark: "备注",
+  upload: "上传",
+  download: "下载",
+  unlimited_hint: "0 表示不限制",
+  target_device: "设备",
+  target_mac: "MAC",
+  target_ip: "IP",
+  target_flow: "Flow",
+  col_target: "对象",
+  col_limit: "限速 (上传 / 下载)",
+  col_rate: "当前速率 (上传 / 下载)",
+  col_drops: "丢包",
+  unlimited: "不限制",
+  edt_hint:
+    "限速由出口网卡上的 fq 队列按发送时间排队, 已开启 SQM (CAKE / HTB) 的网卡上限速不生效",
+};
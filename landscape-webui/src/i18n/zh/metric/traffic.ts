@@ -0,0 +1,18 @@
+export default {
+  title: "流量统计",
+  top: "流量排行",
+  monthly: "月度用量",
+  kind_iface: "网卡",
+  kind_ip: "主机 IP",
+  kind_device: "设备",
+  kind_flow: "Flow",
+  month: "月份",
+  reset_day: "重置日",
+  period: "计费周期: {start} ~ {end}",
+  col_target: "对象",
+  col_device: "设备",
+  col_rx: "下载 / 接收",
+  col_tx: "上传 / 发送",
+  col_total: "合计",
+  query: "查询",
+};
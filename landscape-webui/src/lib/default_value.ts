@@ -1,4 +1,7 @@
-import type { FlowConfig } from "@landscape-router/types/api/schemas";
+import type {
+  FlowConfig,
+  FlowTarget,
+} from "@landscape-router/types/api/schemas";
 
 export function flow_config_default(): FlowConfig {
   return {
@@ -13,6 +16,8 @@ export function flow_config_default(): FlowConfig {
 export enum FlowTargetTypes {
   INTERFACE = "interface",
   NETNS = "netns",
+  NAMESPACE = "namespace",
+  TPROXY = "tproxy",
 }
 
 export function flow_target_options(): { label: string; value: string }[] {
@@ -25,9 +30,28 @@ export function flow_target_options(): { label: string; value: string }[] {
       label: "Docker 容器名称",
       value: FlowTargetTypes.NETNS,
     },
+    {
+      label: "命名空间出口名称",
+      value: FlowTargetTypes.NAMESPACE,
+    },
+    {
+      label: "本机 TProxy 端口",
+      value: FlowTargetTypes.TPROXY,
+    },
   ];
 }
 
+export function flow_target_name(target: FlowTarget): string {
+  switch (target.t) {
+    case "netns":
+      return target.container_name;
+    case "tproxy":
+      return `TProxy :${target.port}`;
+    default:
+      return target.name;
+  }
+}
+
 export enum FlowMarkType {
   KeepGoing = "keep_going",
   Direct = "direct",
@@ -50,6 +50,7 @@ export function convert_flow_mark(flow_mark?: FlowMark): FlowMark {
       action: { t: FlowMarkType.KeepGoing },
       flow_id: 0,
       allow_reuse_port: false,
+      qos: null,
     };
   }
 }
@@ -19,8 +19,19 @@ export class IPV6PDServiceConfig {
 
 export class IPV6PDConfig {
   mac: string;
+  request_pd: boolean;
+  request_na: boolean;
+  accept_ra: boolean;
 
-  constructor(obj?: { mac?: string }) {
+  constructor(obj?: {
+    mac?: string;
+    request_pd?: boolean;
+    request_na?: boolean;
+    accept_ra?: boolean;
+  }) {
     this.mac = obj?.mac ?? "";
+    this.request_pd = obj?.request_pd ?? true;
+    this.request_na = obj?.request_na ?? false;
+    this.accept_ra = obj?.accept_ra ?? false;
   }
 }
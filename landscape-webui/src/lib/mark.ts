@@ -31,7 +31,13 @@ export class WanIpRuleConfigClass implements WanIpRuleConfig {
 
 export function new_wan_rules(e: WanIPRuleSource): WanIPRuleSource {
   if (e.t == "config") {
-    return { t: "config", ip: e.ip, prefix: e.prefix };
+    return {
+      t: "config",
+      ip: e.ip,
+      prefix: e.prefix,
+      l4_protocol: e.l4_protocol ?? null,
+      dst_port: e.dst_port ?? null,
+    };
   } else {
     return {
       t: "geo_key",
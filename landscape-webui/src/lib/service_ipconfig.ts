@@ -54,6 +54,8 @@ export type IfaceIpModelConfig =
       ipv4: string;
       ipv4_mask: number;
       ipv6: string | undefined;
+      ipv6_addrs?: { ip: string; prefix: number }[];
+      ipv6_gateway?: string;
     }
   | {
       t: "pppoe";
@@ -42,6 +42,7 @@ export class ServiceExhibitSwitch {
   station: boolean;
   dhcp_v4: boolean;
   mss_clamp: boolean;
+  sqm: boolean;
   route_lan: boolean;
   route_wan: boolean;
 
@@ -60,6 +61,7 @@ export class ServiceExhibitSwitch {
     this.station = false;
     this.dhcp_v4 = false;
     this.mss_clamp = false;
+    this.sqm = false;
 
     this.route_lan = false;
     this.route_wan = false;
@@ -91,6 +93,7 @@ export class ServiceExhibitSwitch {
       this.ipv6pd = true;
       this.firewall = true;
       this.mss_clamp = true;
+      this.sqm = true;
       this.route_wan = true;
     } else if (dev.name === "docker0") {
       this.zone_type = false;
@@ -109,6 +112,7 @@ export class ServiceExhibitSwitch {
       this.ipv6pd = true;
       this.firewall = true;
       this.mss_clamp = true;
+      this.sqm = true;
       this.route_wan = true;
     }
   }
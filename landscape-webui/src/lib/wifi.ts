@@ -1,18 +1,39 @@
+import type {
+  WifiApConfig,
+  WifiBssConfig,
+} from "@landscape-router/types/api/schemas";
+
 export class WifiServiceConfig {
   iface_name: string;
   enable: boolean;
-  config: string;
+  ap: WifiApConfig;
   update_at?: number;
 
   constructor(obj?: {
     iface_name: string;
     enable?: boolean;
-    config?: string;
+    ap?: WifiApConfig;
     update_at?: number;
   }) {
     this.iface_name = obj?.iface_name ?? "";
     this.enable = obj?.enable ?? true;
-    this.config = obj?.config ?? "";
+    this.ap = obj?.ap ?? {
+      country: "CN",
+      band: "2g",
+      channel: 0,
+      width: "20",
+      bss: [new_wifi_bss()],
+    };
     this.update_at = obj?.update_at;
   }
 }
+
+export function new_wifi_bss(): WifiBssConfig {
+  return {
+    ssid: "",
+    security: { t: "wpa2_psk", passphrase: "" },
+    hidden: false,
+    isolate: false,
+    bridge: null,
+  };
+}
@@ -6,6 +6,10 @@ import SrcIpMetric from "@/views/metric/conn/SrcIpMetric.vue";
 import DstIpMetric from "@/views/metric/conn/DstIpMetric.vue";
 import HistorySrcIpMetric from "@/views/metric/conn/HistorySrcIpMetric.vue";
 import HistoryDstIpMetric from "@/views/metric/conn/HistoryDstIpMetric.vue";
+import HistoryDomainMetric from "@/views/metric/conn/HistoryDomainMetric.vue";
+import TrafficMetric from "@/views/metric/TrafficMetric.vue";
+import TrafficQuota from "@/views/metric/TrafficQuota.vue";
+import RateLimit from "@/views/metric/RateLimit.vue";
 
 const metric_route: Array<RouteRecordRaw> = [
   {
@@ -38,11 +42,31 @@ const metric_route: Array<RouteRecordRaw> = [
     name: "routes.connect-history-dst",
     component: HistoryDstIpMetric,
   },
+  {
+    path: "/metric/conn/history-domain",
+    name: "routes.connect-history-domain",
+    component: HistoryDomainMetric,
+  },
   {
     path: "/metric/dns",
     name: "routes.dns-metric",
     component: DNSMetric,
   },
+  {
+    path: "/metric/traffic",
+    name: "routes.traffic-metric",
+    component: TrafficMetric,
+  },
+  {
+    path: "/metric/quota",
+    name: "routes.traffic-quota",
+    component: TrafficQuota,
+  },
+  {
+    path: "/metric/rate-limit",
+    name: "routes.rate-limit",
+    component: RateLimit,
+  },
 ];
 
 export default metric_route;
@@ -15,6 +15,7 @@ import { useDHCPv4ConfigStore } from "./status_dhcp_v4";
 import { useTopologyStore } from "./topology";
 import { useMetricStore } from "./status_metric";
 import { useMSSClampConfigStore } from "./status_mss_clamp";
+import { useSQMConfigStore } from "./status_sqm";
 import { useRouteLanConfigStore } from "./status_route_lan";
 import { useRouteWanConfigStore } from "./status_route_wan";
 import { useEnrolledDeviceStore } from "./enrolled_device";
@@ -36,6 +37,7 @@ export const useFetchIntervalStore = defineStore("fetch_interval", () => {
   const topologyStore = useTopologyStore();
   const metricStore = useMetricStore();
   const mssclampConfigStore = useMSSClampConfigStore();
+  const sqmConfigStore = useSQMConfigStore();
   const routeLanConfigStore = useRouteLanConfigStore();
   const routeWanConfigStore = useRouteWanConfigStore();
   const enrolledDeviceStore = useEnrolledDeviceStore();
@@ -62,6 +64,7 @@ export const useFetchIntervalStore = defineStore("fetch_interval", () => {
       await dhcpv4ConfigStore.UPDATE_INFO();
       await metricStore.UPDATE_INFO();
       await mssclampConfigStore.UPDATE_INFO();
+      await sqmConfigStore.UPDATE_INFO();
 
       await routeLanConfigStore.UPDATE_INFO();
       await routeWanConfigStore.UPDATE_INFO();
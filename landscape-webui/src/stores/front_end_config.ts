@@ -34,7 +34,7 @@ export const useFrontEndStore = defineStore(
     const conn_sort_order = ref<"asc" | "desc">("desc");
 
     const history_conn_sort_key = ref<
-      "time" | "port" | "ingress" | "egress" | "duration"
+      "time" | "port" | "ingress" | "egress" | "duration" | "domain"
     >("time");
     const history_conn_sort_order = ref<"asc" | "desc">("desc");
 
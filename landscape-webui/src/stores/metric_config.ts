@@ -13,6 +13,13 @@ export const useMetricConfigStore = defineStore("metric_config", () => {
   const flushIntervalSecs = ref<number | undefined>(undefined);
   const maxMemory = ref<number | undefined>(undefined);
   const maxThreads = ref<number | undefined>(undefined);
+  const dbPath = ref<string | undefined>(undefined);
+  const prometheusToken = ref<string | undefined>(undefined);
+  const prometheusListen = ref<string | undefined>(undefined);
+  // 流导出采集器目前仅支持在配置文件中编辑, 保存时原样带回
+  const flowCollectors = ref<LandscapeMetricConfig["flow_collectors"]>(
+    undefined,
+  );
   const expectedHash = ref<string>("");
 
   async function loadMetricConfig() {
@@ -27,6 +34,10 @@ export const useMetricConfigStore = defineStore("metric_config", () => {
     flushIntervalSecs.value = metric.flush_interval_secs ?? undefined;
     maxMemory.value = metric.max_memory ?? undefined;
     maxThreads.value = metric.max_threads ?? undefined;
+    dbPath.value = metric.db_path ?? undefined;
+    prometheusToken.value = metric.prometheus_token ?? undefined;
+    prometheusListen.value = metric.prometheus_listen ?? undefined;
+    flowCollectors.value = metric.flow_collectors;
     expectedHash.value = hash;
   }
 
@@ -41,6 +52,10 @@ export const useMetricConfigStore = defineStore("metric_config", () => {
       flush_interval_secs: flushIntervalSecs.value,
       max_memory: maxMemory.value,
       max_threads: maxThreads.value,
+      db_path: dbPath.value || undefined,
+      prometheus_token: prometheusToken.value || undefined,
+      prometheus_listen: prometheusListen.value || undefined,
+      flow_collectors: flowCollectors.value,
     };
     await update_metric_config({
       new_metric,
@@ -62,6 +77,9 @@ export const useMetricConfigStore = defineStore("metric_config", () => {
     flushIntervalSecs,
     maxMemory,
     maxThreads,
+    dbPath,
+    prometheusToken,
+    prometheusListen,
     expectedHash,
     loadMetricConfig,
     saveMetricConfig,
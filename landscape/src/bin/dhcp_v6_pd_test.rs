@@ -13,7 +13,8 @@ use landscape::{
 };
 use landscape_common::{
     config::ra::IPV6RAConfig,
-    ipv6_pd::IAPrefixMap,
+    dhcp::v6_client::config::IPV6PDConfig,
+    ipv6_pd::{IAPrefixMap, WanIpv6InfoMap},
     lan_services::ipv6_ra::IPv6NAInfo,
     net::MacAddr,
     route::{LanRouteInfo, LanRouteMode, RouteTargetInfo},
@@ -72,6 +73,8 @@ async fn main() {
                 weight: 1,
                 mac: iface.mac.clone(),
                 is_docker: false,
+                is_namespace: false,
+                tproxy: None,
                 iface_name: "test".to_string(),
                 iface_ip: IpAddr::V6(Ipv6Addr::UNSPECIFIED),
                 default_route: true,
@@ -81,12 +84,18 @@ async fn main() {
                 args.dhcp_client_iface,
                 iface.index,
                 iface.mac,
-                mac_addr,
+                IPV6PDConfig {
+                    mac: mac_addr,
+                    request_pd: true,
+                    request_na: false,
+                    accept_ra: false,
+                },
                 LANDSCAPE_DEFAULE_DHCP_V6_CLIENT_PORT,
                 status,
                 route_info,
                 ip_route_service,
                 prefix_map_clone,
+                WanIpv6InfoMap::new(),
             )
             .await;
         }
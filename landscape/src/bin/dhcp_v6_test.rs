@@ -9,7 +9,11 @@ use std::{
 
 use clap::Parser;
 use landscape::{dhcp_client::v6::dhcp_v6_pd_client, iface::get_iface_by_name};
-use landscape_common::{ipv6_pd::IAPrefixMap, route::RouteTargetInfo};
+use landscape_common::{
+    dhcp::v6_client::config::IPV6PDConfig,
+    ipv6_pd::{IAPrefixMap, WanIpv6InfoMap},
+    route::RouteTargetInfo,
+};
 use landscape_common::{
     service::{ServiceStatus, WatchService},
     LANDSCAPE_DEFAULE_DHCP_V6_CLIENT_PORT,
@@ -57,6 +61,8 @@ async fn main() {
             weight: 1,
             mac: iface.mac.clone(),
             is_docker: false,
+            is_namespace: false,
+            tproxy: None,
             iface_name: "test".to_string(),
             iface_ip: IpAddr::V6(Ipv6Addr::UNSPECIFIED),
             default_route: true,
@@ -66,12 +72,18 @@ async fn main() {
             args.iface_name,
             iface.index,
             iface.mac,
-            mac_addr,
+            IPV6PDConfig {
+                mac: mac_addr,
+                request_pd: true,
+                request_na: false,
+                accept_ra: false,
+            },
             LANDSCAPE_DEFAULE_DHCP_V6_CLIENT_PORT,
             status,
             route_info,
             ip_route,
             prefix_map,
+            WanIpv6InfoMap::new(),
         )
         .await;
     });
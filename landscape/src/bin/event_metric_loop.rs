@@ -27,7 +27,7 @@ async fn main() {
     let (other_tx, other_rx) = oneshot::channel::<()>();
 
     let metric_service = MetricData::new(
-        metric_path,
+        metric_path.clone(),
         landscape_common::config::MetricRuntimeConfig {
             conn_retention_mins: landscape_common::DEFAULT_CONN_METRIC_RETENTION_MINS,
             conn_retention_minute_days: landscape_common::DEFAULT_CONN_METRIC_RETENTION_DAYS_1M,
@@ -38,6 +38,10 @@ async fn main() {
             flush_interval_secs: landscape_common::DEFAULT_METRIC_FLUSH_INTERVAL_SECS,
             max_memory: 128,
             max_threads: 1,
+            db_path: metric_path,
+            prometheus_token: None,
+            prometheus_listen: None,
+            flow_collectors: vec![],
         },
     )
     .await;
@@ -2,7 +2,7 @@ use std::collections::{HashMap, HashSet};
 
 use landscape_common::{
     event::dns::DstIpEvent,
-    ip_mark::WanIpRuleConfig,
+    ip_mark::{check_flow_port_rule_limit, DstIpRuleError, WanIpRuleConfig},
     service::controller::{ConfigController, FlowConfigController},
 };
 use landscape_database::{
@@ -44,6 +44,26 @@ impl DstIpRuleService {
 
         dst_ip_rule_service
     }
+
+    /// 检查写入后各 Flow 的 L4 规则数量是否超出 eBPF 的上限
+    pub async fn check_port_rule_limit(
+        &self,
+        rules: &[WanIpRuleConfig],
+    ) -> Result<(), DstIpRuleError> {
+        let mut merged: Vec<WanIpRuleConfig> = self
+            .list()
+            .await
+            .into_iter()
+            .filter(|old| !rules.iter().any(|rule| rule.id.is_some() && rule.id == old.id))
+            .collect();
+        merged.extend(rules.iter().cloned());
+
+        let flow_ids: HashSet<u32> = rules.iter().map(|rule| rule.flow_id).collect();
+        for flow_id in flow_ids {
+            check_flow_port_rule_limit(flow_id, merged.iter())?;
+        }
+        Ok(())
+    }
 }
 
 impl FlowConfigController for DstIpRuleService {}
@@ -0,0 +1,241 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+
+use landscape_common::{
+    config::FlowId,
+    flow::{
+        config::FlowConfig,
+        health::{FlowHealthCheck, FlowHealthStatus, FlowTargetHealth, FlowTargetSwitchEvent},
+        FlowTarget,
+    },
+    netns_target::NetnsTargetConfig,
+    utils::time::get_current_time_ms,
+};
+use landscape_database::{
+    flow_rule::repository::FlowConfigRepository, netns_target::repository::NetnsTargetRepository,
+    provider::LandscapeDBServiceProvider, repository::Repository,
+};
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+use crate::flow::health::probe_flow_target;
+use crate::route::IpRouteService;
+
+/// 检查各 Flow 是否到达检查时间的间隔
+const HEALTH_TICK_INTERVAL: Duration = Duration::from_secs(1);
+/// 每个 Flow 保留的切换记录数量
+const SWITCH_EVENT_LIMIT: usize = 20;
+
+struct FlowHealthState {
+    /// 检查时配置的 update_at, 配置变更后立即重新检查
+    update_at: f64,
+    next_check: Instant,
+    targets: Vec<FlowTargetHealth>,
+    active_target: Option<FlowTarget>,
+    direct: bool,
+    switch_events: VecDeque<FlowTargetSwitchEvent>,
+}
+
+impl FlowHealthState {
+    fn unhealthy_targets(&self) -> HashSet<FlowTarget> {
+        self.targets.iter().filter(|t| !t.healthy).map(|t| t.target.clone()).collect()
+    }
+}
+
+#[derive(Clone)]
+pub struct FlowHealthService {
+    flow_store: FlowConfigRepository,
+    netns_target_store: NetnsTargetRepository,
+    route_service: IpRouteService,
+    state: Arc<Mutex<HashMap<FlowId, FlowHealthState>>>,
+}
+
+impl FlowHealthService {
+    pub fn new(store: LandscapeDBServiceProvider, route_service: IpRouteService) -> Self {
+        let service = Self {
+            flow_store: store.flow_rule_store(),
+            netns_target_store: store.netns_target_store(),
+            route_service,
+            state: Arc::new(Mutex::new(HashMap::new())),
+        };
+
+        let service_clone = service.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(HEALTH_TICK_INTERVAL);
+            loop {
+                interval.tick().await;
+                service_clone.check_due_flows().await;
+            }
+        });
+
+        service
+    }
+
+    async fn check_due_flows(&self) {
+        let configs = self.flow_store.list_all().await.unwrap_or_default();
+        let configs: Vec<FlowConfig> = configs
+            .into_iter()
+            .filter(|config| config.enable && config.active_health_check().is_some())
+            .collect();
+
+        // 已删除或关闭健康检查的 Flow 恢复按配置顺序选择出口
+        let removed: Vec<FlowId> = {
+            let mut state = self.state.lock().await;
+            let removed: Vec<FlowId> = state
+                .keys()
+                .filter(|flow_id| !configs.iter().any(|config| config.flow_id == **flow_id))
+                .cloned()
+                .collect();
+            for flow_id in removed.iter() {
+                state.remove(flow_id);
+            }
+            removed
+        };
+        for flow_id in removed {
+            self.route_service.set_flow_unhealthy_targets(flow_id, HashSet::new()).await;
+        }
+
+        let now = Instant::now();
+        let due: Vec<&FlowConfig> = {
+            let state = self.state.lock().await;
+            configs
+                .iter()
+                .filter(|config| {
+                    state
+                        .get(&config.flow_id)
+                        .map_or(true, |s| s.update_at != config.update_at || s.next_check <= now)
+                })
+                .collect()
+        };
+
+        let netns_targets = self.netns_target_store.list_all().await.unwrap_or_default();
+        futures::future::join_all(
+            due.into_iter().map(|config| self.check_flow(config, &netns_targets)),
+        )
+        .await;
+    }
+
+    async fn check_flow(&self, config: &FlowConfig, netns_targets: &[NetnsTargetConfig]) {
+        let Some(check) = config.active_health_check() else {
+            return;
+        };
+        let results = futures::future::join_all(
+            config
+                .flow_targets
+                .iter()
+                .map(|target| probe_flow_target(target, check, netns_targets)),
+        )
+        .await;
+        let now = get_current_time_ms().unwrap_or_default();
+
+        let mut state = self.state.lock().await;
+        let entry = state.entry(config.flow_id).or_insert_with(|| FlowHealthState {
+            update_at: config.update_at,
+            next_check: Instant::now(),
+            targets: vec![],
+            active_target: config.flow_targets.first().cloned(),
+            direct: false,
+            switch_events: VecDeque::new(),
+        });
+        let old_unhealthy = entry.unhealthy_targets();
+
+        let targets: Vec<FlowTargetHealth> = config
+            .flow_targets
+            .iter()
+            .zip(results)
+            .map(|(target, result)| {
+                let last_failures = entry
+                    .targets
+                    .iter()
+                    .find(|t| t.target == *target)
+                    .map_or(0, |t| t.consecutive_failures);
+                let (consecutive_failures, last_error) = match result {
+                    Ok(()) => (0, None),
+                    Err(e) => (last_failures + 1, Some(e)),
+                };
+                FlowTargetHealth {
+                    target: target.clone(),
+                    healthy: consecutive_failures < check.failure_threshold,
+                    consecutive_failures,
+                    last_check: now,
+                    last_error,
+                }
+            })
+            .collect();
+
+        entry.update_at = config.update_at;
+        entry.next_check = Instant::now() + Duration::from_secs(check.interval_secs as u64);
+        entry.targets = targets;
+
+        let (active_target, direct) = select_active_target(&entry.targets, check);
+        if active_target != entry.active_target || direct != entry.direct {
+            let reason = switch_reason(&entry.targets, entry.active_target.as_ref());
+            tracing::warn!(
+                "flow {} switch target {:?} -> {:?} (direct: {}): {}",
+                config.flow_id,
+                entry.active_target,
+                active_target,
+                direct,
+                reason
+            );
+            entry.switch_events.push_front(FlowTargetSwitchEvent {
+                flow_id: config.flow_id,
+                from: entry.active_target.clone(),
+                to: active_target.clone(),
+                direct,
+                reason,
+                time: now,
+            });
+            entry.switch_events.truncate(SWITCH_EVENT_LIMIT);
+            entry.active_target = active_target;
+            entry.direct = direct;
+        }
+
+        let unhealthy = entry.unhealthy_targets();
+        drop(state);
+        if unhealthy != old_unhealthy {
+            self.route_service.set_flow_unhealthy_targets(config.flow_id, unhealthy).await;
+        }
+    }
+
+    pub async fn list_status(&self) -> Vec<FlowHealthStatus> {
+        let state = self.state.lock().await;
+        let mut result: Vec<FlowHealthStatus> = state
+            .iter()
+            .map(|(flow_id, s)| FlowHealthStatus {
+                flow_id: *flow_id,
+                active_target: s.active_target.clone(),
+                direct: s.direct,
+                targets: s.targets.clone(),
+                switch_events: s.switch_events.iter().cloned().collect(),
+            })
+            .collect();
+        result.sort_by(|a, b| a.flow_id.cmp(&b.flow_id));
+        result
+    }
+}
+
+/// 按顺序选出第一个健康的出口, 全部不可用时按配置回退到默认 WAN
+fn select_active_target(
+    targets: &[FlowTargetHealth],
+    check: &FlowHealthCheck,
+) -> (Option<FlowTarget>, bool) {
+    match targets.iter().find(|t| t.healthy) {
+        Some(t) => (Some(t.target.clone()), false),
+        None => (None, check.fallback_direct),
+    }
+}
+
+fn switch_reason(targets: &[FlowTargetHealth], previous: Option<&FlowTarget>) -> String {
+    let Some(previous) = previous else {
+        return "preferred target recovered".to_string();
+    };
+    match targets.iter().find(|t| t.target == *previous) {
+        Some(t) if !t.healthy => {
+            format!("target unhealthy: {}", t.last_error.clone().unwrap_or_default())
+        }
+        Some(_) => "preferred target recovered".to_string(),
+        None => "target removed from flow".to_string(),
+    }
+}
@@ -1,22 +1,35 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
 use landscape_common::{
     error::LdError,
     event::{dns::DnsEvent, route::RouteEvent},
-    flow::{config::FlowConfig, FlowEntryMatchMode},
+    flow::{
+        check_bridge_port_ifindex, config::FlowConfig, FlowEbpfMatchPair, FlowEntryMatchMode,
+        FlowEntryRule, FlowRuleError,
+    },
+    observer::IfaceObserverAction,
     service::controller::{ConfigController, FlowConfigController},
 };
 use landscape_database::{
     flow_rule::repository::FlowConfigRepository, provider::LandscapeDBServiceProvider,
 };
-use tokio::sync::mpsc;
+use tokio::sync::{broadcast, mpsc, Mutex};
 use uuid::Uuid;
 
-use crate::flow::update_flow_matchs;
+use crate::flow::{bridge_port::BridgePortTagger, update_flow_matchs};
+use crate::iface::get_iface_by_name;
 
 #[derive(Clone)]
 pub struct FlowRuleService {
     store: FlowConfigRepository,
     dns_events_tx: mpsc::Sender<DnsEvent>,
     route_events_tx: mpsc::Sender<RouteEvent>,
+    bridge_port_tagger: BridgePortTagger,
+    /// 匹配规则重写后通知, 供依赖 Flow 规则的服务重新执行
+    updated_tx: broadcast::Sender<()>,
+    /// 按网卡匹配的规则写入 eBPF 时使用的 ifindex, 网卡重建后据此删除旧的匹配键
+    iface_indexes: Arc<Mutex<HashMap<String, u32>>>,
 }
 
 impl FlowRuleService {
@@ -24,12 +37,102 @@ impl FlowRuleService {
         store: LandscapeDBServiceProvider,
         dns_events_tx: mpsc::Sender<DnsEvent>,
         route_events_tx: mpsc::Sender<RouteEvent>,
+        mut dev_observer: broadcast::Receiver<IfaceObserverAction>,
     ) -> Self {
         let store = store.flow_rule_store();
-        let result = Self { store, dns_events_tx, route_events_tx };
+        let result = Self {
+            store,
+            dns_events_tx,
+            route_events_tx,
+            bridge_port_tagger: BridgePortTagger::default(),
+            updated_tx: broadcast::channel(8).0,
+            iface_indexes: Arc::new(Mutex::new(HashMap::new())),
+        };
         result.after_update_config(result.list().await, vec![]).await;
+
+        let service = result.clone();
+        tokio::spawn(async move {
+            while let Ok(msg) = dev_observer.recv().await {
+                let (IfaceObserverAction::Up(iface_name) | IfaceObserverAction::Down(iface_name)) =
+                    msg;
+                let configs = service.list().await;
+                let used = configs
+                    .iter()
+                    .flat_map(|config| config.flow_match_rules.iter())
+                    .any(|rule| rule.mode.iface_name() == Some(iface_name.as_str()));
+                if used {
+                    service.sync_iface_rules(&configs).await;
+                }
+            }
+        });
         result
     }
+
+    /// 网卡创建或重建后 ifindex 会变化, 按网卡匹配的规则需删除旧键并按新的 ifindex 重新写入
+    async fn sync_iface_rules(&self, configs: &[FlowConfig]) {
+        let mut rules: HashMap<String, Vec<(FlowEntryRule, u32)>> = HashMap::new();
+        for config in configs.iter().filter(|config| config.enable) {
+            for rule in config.flow_match_rules.iter() {
+                if let Some(iface_name) = rule.mode.iface_name() {
+                    rules
+                        .entry(iface_name.to_string())
+                        .or_default()
+                        .push((rule.clone(), config.flow_id));
+                }
+            }
+        }
+
+        let mut changed = false;
+        let mut iface_indexes = self.iface_indexes.lock().await;
+        iface_indexes.retain(|iface_name, _| rules.contains_key(iface_name));
+        for (iface_name, rules) in rules {
+            let ifindex = get_iface_by_name(&iface_name).await.map(|iface| iface.index);
+            let old_ifindex = iface_indexes.get(&iface_name).copied();
+            if ifindex == old_ifindex {
+                continue;
+            }
+            tracing::info!("interface {iface_name} ifindex {old_ifindex:?} -> {ifindex:?}");
+            if let Some(old_ifindex) = old_ifindex {
+                let keys = rules.iter().map(|(rule, _)| rule.clone()).collect();
+                landscape_ebpf::map_setting::flow::del_flow_match_rule_with(keys, |_| {
+                    Some(old_ifindex)
+                });
+            }
+            match ifindex {
+                Some(ifindex) => {
+                    landscape_ebpf::map_setting::flow::update_flow_match_rule(
+                        rules
+                            .into_iter()
+                            .map(|(rule, flow_id)| FlowEbpfMatchPair::new(rule, flow_id))
+                            .collect(),
+                    );
+                    iface_indexes.insert(iface_name, ifindex);
+                }
+                None => {
+                    iface_indexes.remove(&iface_name);
+                }
+            }
+            changed = true;
+        }
+        drop(iface_indexes);
+
+        self.bridge_port_tagger.sync(configs).await;
+        if changed {
+            let _ = self.updated_tx.send(());
+        }
+    }
+
+    /// 网桥端口规则要求网卡的 ifindex 不超过 16 位, 网卡尚不存在时在写入 eBPF 时再检查
+    pub async fn check_entry_ifaces(&self, config: &FlowConfig) -> Result<(), FlowRuleError> {
+        for rule in config.flow_match_rules.iter() {
+            if let FlowEntryMatchMode::BridgePort { iface_name } = &rule.mode {
+                if let Some(iface) = get_iface_by_name(iface_name).await {
+                    check_bridge_port_ifindex(iface_name, iface.index)?;
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 impl FlowRuleService {
@@ -40,6 +143,10 @@ impl FlowRuleService {
     ) -> Result<Option<FlowConfig>, LdError> {
         self.store.find_conflict_by_entry_mode(exclude_id, mode).await
     }
+
+    pub fn subscribe_updated(&self) -> broadcast::Receiver<()> {
+        self.updated_tx.subscribe()
+    }
 }
 
 impl FlowConfigController for FlowRuleService {}
@@ -75,7 +182,9 @@ impl ConfigController for FlowRuleService {
         new_configs: Vec<Self::Config>,
         old_configs: Vec<Self::Config>,
     ) {
-        update_flow_matchs(new_configs, old_configs).await;
+        update_flow_matchs(new_configs.clone(), old_configs).await;
+        self.sync_iface_rules(&new_configs).await;
+        let _ = self.updated_tx.send(());
         let _ = self.dns_events_tx.send(DnsEvent::FlowUpdated).await;
     }
 }
@@ -91,18 +91,20 @@ impl GeoIpService {
                 match each {
                     WanIPRuleSource::GeoKey(config_key) => {
                         if let Some(ips) = lock.get(&config_key.get_file_cache_key()) {
-                            source.extend(ips.values.iter().cloned());
+                            source.extend(ips.values.iter().map(|cidr| (cidr.clone(), None, None)));
                         }
                     }
                     WanIPRuleSource::Config(c) => {
-                        source.push(c.clone());
+                        source.push((c.cidr(), c.l4_protocol.clone(), c.dst_port));
                     }
                 }
             }
 
-            let ip_marks = source.into_iter().map(|cidr| IpMarkInfo {
+            let ip_marks = source.into_iter().map(|(cidr, l4_protocol, dst_port)| IpMarkInfo {
                 mark: config.mark,
                 cidr,
+                l4_protocol,
+                dst_port,
                 priority: config.index as u16,
             });
             result.extend(ip_marks);
@@ -2,11 +2,15 @@ pub mod dns_rule;
 pub mod dst_ip_rule;
 pub mod firewall_blacklist;
 pub mod firewall_rule;
+pub mod flow_health;
 pub mod flow_rule;
 pub mod geo_ip_service;
 pub mod geo_site_service;
+pub mod netns_target;
 
 pub mod static_nat_mapping;
 
 pub mod dns;
 pub mod enrolled_device;
+pub mod rate_limit;
+pub mod traffic_quota;
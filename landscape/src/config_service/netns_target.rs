@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use landscape_common::{
+    netns_target::{NetnsTargetConfig, NetnsTargetError, NetnsTargetStatus},
+    route::RouteTargetInfo,
+    service::controller::ConfigController,
+};
+use landscape_database::{
+    netns_target::repository::NetnsTargetRepository, provider::LandscapeDBServiceProvider,
+};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::iface::get_iface_by_name;
+use crate::netns::{find_veth_peer_ifindex, resolve_netns_path};
+use crate::route::IpRouteService;
+
+/// 重新解析命名空间的间隔, 进程重启后 PID 与 veth 都可能变化
+const NETNS_RESOLVE_INTERVAL: Duration = Duration::from_secs(15);
+
+#[derive(Default)]
+struct NetnsTargetState {
+    /// 已注册为出口的名称及其宿主机侧 ifindex
+    registered: HashMap<String, u32>,
+    errors: HashMap<Uuid, String>,
+}
+
+#[derive(Clone)]
+pub struct NetnsTargetService {
+    store: NetnsTargetRepository,
+    route_service: IpRouteService,
+    state: Arc<Mutex<NetnsTargetState>>,
+}
+
+impl NetnsTargetService {
+    pub async fn new(store: LandscapeDBServiceProvider, route_service: IpRouteService) -> Self {
+        let service = Self {
+            store: store.netns_target_store(),
+            route_service,
+            state: Arc::new(Mutex::new(NetnsTargetState::default())),
+        };
+        service.reconcile().await;
+
+        let service_clone = service.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(NETNS_RESOLVE_INTERVAL);
+            loop {
+                interval.tick().await;
+                service_clone.reconcile().await;
+            }
+        });
+
+        service
+    }
+
+    /// 解析所有启用的命名空间, 使已注册的出口与之保持一致
+    async fn reconcile(&self) {
+        let configs = self.list().await;
+
+        let mut state = self.state.lock().await;
+        let mut resolved: HashMap<String, u32> = HashMap::new();
+        let mut errors = HashMap::new();
+        for config in configs.iter().filter(|config| config.enable) {
+            // 导入的配置未经接口检查, 重名时不注册以免覆盖其他出口
+            if resolved.contains_key(&config.name) || self.name_conflicts(&config.name).await {
+                errors.insert(config.id, format!("name {} is already used", config.name));
+                continue;
+            }
+            let result = match resolve_netns_path(&config.source).await {
+                Ok(path) => find_veth_peer_ifindex(&path).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(ifindex) => {
+                    resolved.insert(config.name.clone(), ifindex);
+                }
+                Err(e) => {
+                    errors.insert(config.id, e);
+                }
+            }
+        }
+
+        let stale: Vec<String> = state
+            .registered
+            .iter()
+            .filter(|(name, ifindex)| resolved.get(*name) != Some(*ifindex))
+            .map(|(name, _)| name.clone())
+            .collect();
+        for name in stale {
+            tracing::info!("remove namespace target: {name}");
+            self.route_service.remove_ipv4_wan_route(&name).await;
+            self.route_service.remove_ipv6_wan_route(&name).await;
+            state.registered.remove(&name);
+        }
+
+        for (name, ifindex) in resolved {
+            if state.registered.contains_key(&name) {
+                continue;
+            }
+            tracing::info!("register namespace target: {name}, ifindex: {ifindex}");
+            let (ipv4, ipv6) = RouteTargetInfo::netns_new(ifindex, &name);
+            self.route_service.insert_ipv4_wan_route(&name, ipv4).await;
+            self.route_service.insert_ipv6_wan_route(&name, ipv6).await;
+            state.registered.insert(name, ifindex);
+        }
+        state.errors = errors;
+    }
+
+    /// 名称不能与其他命名空间出口、网卡或 Docker 容器出口重复
+    pub async fn check_name(&self, config: &NetnsTargetConfig) -> Result<(), NetnsTargetError> {
+        let configs = self.list().await;
+        if configs.iter().any(|other| other.id != config.id && other.name == config.name)
+            || self.name_conflicts(&config.name).await
+        {
+            return Err(NetnsTargetError::DuplicateName(config.name.clone()));
+        }
+        Ok(())
+    }
+
+    async fn name_conflicts(&self, name: &str) -> bool {
+        get_iface_by_name(name).await.is_some() || self.route_service.is_wan_name_taken(name).await
+    }
+
+    pub async fn list_status(&self) -> Vec<NetnsTargetStatus> {
+        let configs = self.list().await;
+        let state = self.state.lock().await;
+        configs
+            .into_iter()
+            .map(|config| NetnsTargetStatus {
+                ifindex: state.registered.get(&config.name).copied().filter(|_| config.enable),
+                error: state.errors.get(&config.id).cloned(),
+                config,
+            })
+            .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl ConfigController for NetnsTargetService {
+    type Id = Uuid;
+    type Config = NetnsTargetConfig;
+    type DatabseAction = NetnsTargetRepository;
+
+    fn get_repository(&self) -> &Self::DatabseAction {
+        &self.store
+    }
+
+    async fn after_update_config(
+        &self,
+        _new_configs: Vec<Self::Config>,
+        _old_configs: Vec<Self::Config>,
+    ) {
+        self.reconcile().await;
+    }
+}
@@ -0,0 +1,302 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use landscape_common::{
+    enrolled_device::EnrolledDevice,
+    rate_limit::{
+        RateLimitConfig, RateLimitCounter, RateLimitDirection, RateLimitScope, RateLimitStatus,
+        RateLimitTarget,
+    },
+    service::controller::ConfigController,
+};
+use landscape_database::{
+    enrolled_device::repository::EnrolledDeviceRepository, provider::LandscapeDBServiceProvider,
+    rate_limit::repository::RateLimitRepository, repository::Repository,
+    route_lan::repository::RouteLanServiceRepository,
+    route_wan::repository::RouteWanServiceRepository,
+};
+use landscape_ebpf::map_setting::rate_limit::{
+    clear_rate_limits, del_rate_limit, read_rate_limit_counter, update_rate_limit,
+};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::iface::get_iface_by_name;
+use crate::sqm::tc::{ensure_fq_root, restore_root_qdisc};
+
+/// 速率采样间隔
+const RATE_SAMPLE_INTERVAL: Duration = Duration::from_secs(2);
+/// 重新解析设备 MAC 与网卡队列的间隔
+const RATE_LIMIT_RECONCILE_INTERVAL: Duration = Duration::from_secs(60);
+
+type RateLimitKey = (RateLimitScope, RateLimitDirection);
+
+#[derive(Default)]
+struct RateSample {
+    at: Option<Instant>,
+    upload: RateLimitCounter,
+    download: RateLimitCounter,
+    upload_bps: u64,
+    download_bps: u64,
+}
+
+#[derive(Default)]
+struct RateLimitState {
+    /// 已写入 eBPF 的限速记录及其速率 (字节每秒)
+    applied: HashMap<RateLimitKey, u64>,
+    /// 已确保使用 fq 根队列的网卡, 值为被替换的原根队列类型
+    fq_ifaces: HashMap<String, Option<String>>,
+    samples: HashMap<Uuid, RateSample>,
+}
+
+#[derive(Clone)]
+pub struct RateLimitService {
+    store: RateLimitRepository,
+    device_store: EnrolledDeviceRepository,
+    route_lan_store: RouteLanServiceRepository,
+    route_wan_store: RouteWanServiceRepository,
+    state: Arc<Mutex<RateLimitState>>,
+}
+
+impl RateLimitService {
+    pub async fn new(store: LandscapeDBServiceProvider) -> Self {
+        let service = Self {
+            store: store.rate_limit_store(),
+            device_store: store.enrolled_device_store(),
+            route_lan_store: store.route_lan_service_store(),
+            route_wan_store: store.route_wan_service_store(),
+            state: Arc::new(Mutex::new(RateLimitState::default())),
+        };
+
+        // 丢弃上次运行遗留的记录, 由配置重新写入
+        clear_rate_limits();
+        service.reconcile().await;
+
+        let service_clone = service.clone();
+        tokio::spawn(async move {
+            let mut sample_interval = tokio::time::interval(RATE_SAMPLE_INTERVAL);
+            let mut reconcile_interval = tokio::time::interval(RATE_LIMIT_RECONCILE_INTERVAL);
+            loop {
+                tokio::select! {
+                    _ = sample_interval.tick() => service_clone.sample().await,
+                    _ = reconcile_interval.tick() => service_clone.reconcile().await,
+                }
+            }
+        });
+
+        service
+    }
+
+    /// 使 eBPF 中的限速记录与配置保持一致
+    async fn reconcile(&self) {
+        let configs = self.list().await;
+        let devices = self.device_store.list_all().await.unwrap_or_default();
+
+        let mut desired: HashMap<RateLimitKey, u64> = HashMap::new();
+        for config in configs.iter().filter(|config| config.enable) {
+            for scope in resolve_scopes(&config.target, &devices) {
+                for (direction, kbps) in [
+                    (RateLimitDirection::Upload, config.upload_kbps),
+                    (RateLimitDirection::Download, config.download_kbps),
+                ] {
+                    if kbps > 0 {
+                        desired.insert((scope.clone(), direction), kbps as u64 * 1000 / 8);
+                    }
+                }
+            }
+        }
+
+        let mut state = self.state.lock().await;
+        for key in state.applied.keys() {
+            if !desired.contains_key(key) {
+                del_rate_limit(&key.0, key.1);
+            }
+        }
+        for (key, rate) in desired.iter() {
+            if state.applied.get(key) != Some(rate) {
+                update_rate_limit(&key.0, key.1, *rate);
+            }
+        }
+        let need_fq = !desired.is_empty();
+        state.applied = desired;
+        state.samples.retain(|id, _| configs.iter().any(|config| config.id == *id));
+
+        if need_fq {
+            self.ensure_fq_ifaces(&mut state).await;
+        } else {
+            restore_fq_ifaces(std::mem::take(&mut state.fq_ifaces)).await;
+        }
+    }
+
+    /// EDT 限速需要出口网卡使用 fq, 上传在 WAN 口, 下载在 LAN 口
+    async fn ensure_fq_ifaces(&self, state: &mut RateLimitState) {
+        let mut iface_names: Vec<String> = vec![];
+        for config in self.route_lan_store.list_all().await.unwrap_or_default() {
+            if config.enable {
+                iface_names.push(config.iface_name);
+            }
+        }
+        for config in self.route_wan_store.list_all().await.unwrap_or_default() {
+            if config.enable {
+                iface_names.push(config.iface_name);
+            }
+        }
+
+        // 不再承载限速的网卡恢复原根队列
+        let (kept, removed) = std::mem::take(&mut state.fq_ifaces)
+            .into_iter()
+            .partition(|(iface_name, _)| iface_names.contains(iface_name));
+        state.fq_ifaces = kept;
+        restore_fq_ifaces(removed).await;
+
+        for iface_name in iface_names {
+            if state.fq_ifaces.contains_key(&iface_name) {
+                continue;
+            }
+            let Some(iface) = get_iface_by_name(&iface_name).await else {
+                continue;
+            };
+            match ensure_fq_root(&iface_name, iface.index).await {
+                Ok(previous) => {
+                    state.fq_ifaces.insert(iface_name, previous);
+                }
+                Err(e) => tracing::warn!("rate limit: {e}"),
+            }
+        }
+    }
+
+    /// 读取 eBPF 累计计数, 计算每个限速的当前速率
+    async fn sample(&self) {
+        let configs = self.list().await;
+        let devices = self.device_store.list_all().await.unwrap_or_default();
+        let now = Instant::now();
+
+        let mut state = self.state.lock().await;
+        for config in configs.iter().filter(|config| config.enable) {
+            let mut upload = RateLimitCounter::default();
+            let mut download = RateLimitCounter::default();
+            for scope in resolve_scopes(&config.target, &devices) {
+                if let Some(counter) = read_rate_limit_counter(&scope, RateLimitDirection::Upload) {
+                    upload.bytes += counter.bytes;
+                    upload.drops += counter.drops;
+                }
+                if let Some(counter) = read_rate_limit_counter(&scope, RateLimitDirection::Download)
+                {
+                    download.bytes += counter.bytes;
+                    download.drops += counter.drops;
+                }
+            }
+
+            let sample = state.samples.entry(config.id).or_default();
+            if let Some(at) = sample.at {
+                let elapsed = now.duration_since(at).as_secs_f64();
+                if elapsed > 0.0 {
+                    let bps = |current: u64, last: u64| {
+                        (current.saturating_sub(last) as f64 * 8.0 / elapsed) as u64
+                    };
+                    sample.upload_bps = bps(upload.bytes, sample.upload.bytes);
+                    sample.download_bps = bps(download.bytes, sample.download.bytes);
+                }
+            }
+            sample.at = Some(now);
+            sample.upload = upload;
+            sample.download = download;
+        }
+    }
+
+    pub async fn list_status(&self) -> Vec<RateLimitStatus> {
+        let configs = self.list().await;
+        let state = self.state.lock().await;
+        configs
+            .into_iter()
+            .map(|config| {
+                let sample = state.samples.get(&config.id).filter(|_| config.enable);
+                RateLimitStatus {
+                    upload_bps: sample.map(|s| s.upload_bps).unwrap_or(0),
+                    download_bps: sample.map(|s| s.download_bps).unwrap_or(0),
+                    dropped_packets: sample.map(|s| s.upload.drops + s.download.drops).unwrap_or(0),
+                    config,
+                }
+            })
+            .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl ConfigController for RateLimitService {
+    type Id = Uuid;
+    type Config = RateLimitConfig;
+    type DatabseAction = RateLimitRepository;
+
+    fn get_repository(&self) -> &Self::DatabseAction {
+        &self.store
+    }
+
+    async fn after_update_config(
+        &self,
+        _new_configs: Vec<Self::Config>,
+        _old_configs: Vec<Self::Config>,
+    ) {
+        self.reconcile().await;
+    }
+}
+
+/// 限速对象对应的 eBPF 记录, 设备按其 MAC 限速
+fn resolve_scopes(target: &RateLimitTarget, devices: &[EnrolledDevice]) -> Vec<RateLimitScope> {
+    match target {
+        RateLimitTarget::Device { device_id } => devices
+            .iter()
+            .find(|device| device.id == *device_id)
+            .map(|device| vec![RateLimitScope::Mac(device.mac)])
+            .unwrap_or_default(),
+        RateLimitTarget::Mac { mac } => vec![RateLimitScope::Mac(*mac)],
+        RateLimitTarget::Ip { ip } => vec![RateLimitScope::Ip(*ip)],
+        RateLimitTarget::Flow { flow_id } => vec![RateLimitScope::Flow(*flow_id)],
+    }
+}
+
+/// 恢复被替换为 fq 的根队列, 原本即为 fq 的网卡不做处理
+async fn restore_fq_ifaces(fq_ifaces: HashMap<String, Option<String>>) {
+    for (iface_name, previous) in fq_ifaces {
+        let Some(previous) = previous else {
+            continue;
+        };
+        if let Some(iface) = get_iface_by_name(&iface_name).await {
+            restore_root_qdisc(&iface_name, iface.index, &previous).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use landscape_common::net::MacAddr;
+
+    use super::*;
+
+    #[test]
+    fn device_target_resolves_to_its_mac() {
+        let mac = MacAddr(0, 0x11, 0x22, 0x33, 0x44, 0x55);
+        let device = EnrolledDevice {
+            id: Uuid::new_v4(),
+            update_at: 0.0,
+            iface_name: None,
+            name: "phone".to_string(),
+            fake_name: None,
+            remark: None,
+            mac,
+            ipv4: None,
+            ipv6: None,
+            tag: vec![],
+        };
+        let devices = [device.clone()];
+
+        let target = RateLimitTarget::Device { device_id: device.id };
+        assert_eq!(resolve_scopes(&target, &devices), vec![RateLimitScope::Mac(mac)]);
+        // 设备被删除后不再限速
+        assert!(resolve_scopes(&target, &[]).is_empty());
+
+        let target = RateLimitTarget::Flow { flow_id: 2 };
+        assert_eq!(resolve_scopes(&target, &devices), vec![RateLimitScope::Flow(2)]);
+    }
+}
@@ -80,8 +80,12 @@ pub fn update_mapping_rules(
     tracing::debug!("delete static mapping items: {:?}", to_delete);
     tracing::debug!("add static mapping items: {:?}", to_add);
 
+    // 映射删除后, 经该映射进入的连接不再需要按入站 WAN 回程
+    let deleted_locals: Vec<_> =
+        to_delete.iter().map(|item| (item.lan_ip, item.lan_port, item.l4_protocol)).collect();
     landscape_ebpf::map_setting::nat::del_static_nat_mapping(to_delete.into_iter());
     landscape_ebpf::map_setting::nat::add_static_nat_mapping(to_add.into_iter());
+    landscape_ebpf::map_setting::route::del_route_reply_by_local(deleted_locals);
 }
 
 pub fn mapping_rule_into_hash(
@@ -0,0 +1,467 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{Duration as ChronoDuration, Local, TimeZone};
+use landscape_common::{
+    enrolled_device::EnrolledDevice,
+    flow::config::FlowConfig,
+    metric::traffic::{TrafficSample, TrafficTargetKind},
+    quota::{
+        TrafficQuotaConfig, TrafficQuotaPeriod, TrafficQuotaStatus, TrafficQuotaTarget,
+        TrafficQuotaUsage,
+    },
+    service::controller::ConfigController,
+    utils::time::{get_current_time_ms, get_f64_timestamp},
+};
+use landscape_database::{
+    enrolled_device::repository::EnrolledDeviceRepository,
+    flow_rule::repository::FlowConfigRepository, provider::LandscapeDBServiceProvider,
+    repository::Repository, traffic_quota::repository::TrafficQuotaRepository,
+    traffic_quota_usage::repository::TrafficQuotaUsageRepository,
+};
+use tokio::sync::{broadcast, Mutex};
+use uuid::Uuid;
+
+use crate::flow::quota::{apply_quota_action, release_quota_action, QuotaEnforcement};
+use crate::metric::traffic_manager::billing_cycle;
+
+/// 检查周期切换与配置变更的间隔
+const QUOTA_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Default)]
+struct QuotaState {
+    usages: HashMap<Uuid, TrafficQuotaUsage>,
+    /// 已执行的处理动作
+    enforced: HashMap<Uuid, EnforcedAction>,
+    /// 收到 Flow 匹配规则重写通知的次数
+    flow_generation: u64,
+}
+
+struct EnforcedAction {
+    /// 执行时配额配置的 update_at
+    update_at: f64,
+    /// 执行时 Flow 匹配规则的版本, 仅切换 Flow 时使用
+    flow_version: Option<(u64, FlowConfigVersion)>,
+    enforcement: QuotaEnforcement,
+}
+
+/// Flow 配置的数量与最新的 update_at, 任一变化说明匹配规则已被重写
+type FlowConfigVersion = (usize, f64);
+
+fn flow_config_version(flow_configs: &[FlowConfig]) -> FlowConfigVersion {
+    let update_at = flow_configs.iter().map(|config| config.update_at).fold(0.0, f64::max);
+    (flow_configs.len(), update_at)
+}
+
+#[derive(Clone)]
+pub struct TrafficQuotaService {
+    store: TrafficQuotaRepository,
+    usage_store: TrafficQuotaUsageRepository,
+    flow_store: FlowConfigRepository,
+    device_store: EnrolledDeviceRepository,
+    state: Arc<Mutex<QuotaState>>,
+}
+
+impl TrafficQuotaService {
+    pub async fn new(
+        store: LandscapeDBServiceProvider,
+        mut receiver: broadcast::Receiver<Arc<Vec<TrafficSample>>>,
+        mut flow_updated: broadcast::Receiver<()>,
+    ) -> Self {
+        let service = Self {
+            store: store.traffic_quota_store(),
+            usage_store: store.traffic_quota_usage_store(),
+            flow_store: store.flow_rule_store(),
+            device_store: store.enrolled_device_store(),
+            state: Arc::new(Mutex::new(QuotaState::default())),
+        };
+
+        // 丢弃上次运行遗留的限制, 由持久化的用量重新计算
+        landscape_ebpf::map_setting::quota::clear_quota_limits();
+        {
+            let mut state = service.state.lock().await;
+            for usage in service.usage_store.list_all().await.unwrap_or_default() {
+                state.usages.insert(usage.id, usage);
+            }
+        }
+        service.reconcile(&[]).await;
+
+        let service_clone = service.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(QUOTA_CHECK_INTERVAL);
+            loop {
+                tokio::select! {
+                    result = receiver.recv() => match result {
+                        Ok(samples) => service_clone.reconcile(&samples).await,
+                        Err(broadcast::error::RecvError::Lagged(n)) => {
+                            tracing::warn!("traffic quota lagged {n} sample batches");
+                        }
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    },
+                    // Flow 规则被重写后立即重新执行切换 Flow 的动作
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) = flow_updated.recv() => {
+                        service_clone.state.lock().await.flow_generation += 1;
+                        service_clone.reconcile(&[]).await
+                    }
+                    _ = interval.tick() => service_clone.reconcile(&[]).await,
+                }
+            }
+        });
+
+        service
+    }
+
+    /// 计入新的流量采样, 处理周期重置, 并使处理动作与配额状态保持一致
+    async fn reconcile(&self, samples: &[TrafficSample]) {
+        let configs = self.list().await;
+        let devices = self.device_store.list_all().await.unwrap_or_default();
+        let flow_configs = self.flow_store.list_all().await.unwrap_or_default();
+        let now = get_current_time_ms().unwrap_or_default();
+
+        let mut state = self.state.lock().await;
+        let flow_version = (state.flow_generation, flow_config_version(&flow_configs));
+        let mut changed = vec![];
+
+        // 已删除的配额
+        let removed: Vec<Uuid> = state
+            .usages
+            .keys()
+            .chain(state.enforced.keys())
+            .filter(|id| !configs.iter().any(|config| config.id == **id))
+            .cloned()
+            .collect();
+        for id in removed {
+            if state.usages.remove(&id).is_some() {
+                let _ = self.usage_store.delete_model(id).await;
+            }
+            if let Some(enforced) = state.enforced.remove(&id) {
+                release_quota_action(enforced.enforcement, flow_configs.clone()).await;
+            }
+        }
+
+        for config in configs.iter() {
+            let Some((period_start, _)) = quota_period_range(&config.period) else {
+                continue;
+            };
+            let device = find_quota_device(&config.target, &devices);
+
+            let usage = state.usages.entry(config.id).or_insert_with(|| TrafficQuotaUsage {
+                id: config.id,
+                period_start,
+                used_bytes: 0,
+                exceeded_at: None,
+                update_at: get_f64_timestamp(),
+            });
+            let reset = usage.period_start != period_start;
+            if reset {
+                tracing::info!("traffic quota {} period reset", config.id);
+            }
+            let exceeded_before = !reset && usage.exceeded_at.is_some();
+
+            let bytes: u64 = samples
+                .iter()
+                .filter(|sample| sample_matches(sample, &config.target, device))
+                .map(|sample| sample.rx_bytes + sample.tx_bytes)
+                .sum();
+            let dirty = account_usage(usage, period_start, bytes, config.limit_bytes, now);
+            if !exceeded_before && usage.exceeded_at.is_some() {
+                tracing::warn!(
+                    "traffic quota {} ({}) exceeded: {} / {} bytes",
+                    config.id,
+                    config.remark,
+                    usage.used_bytes,
+                    config.limit_bytes
+                );
+            }
+            let exceeded = config.enable && usage.exceeded_at.is_some();
+            if dirty {
+                usage.update_at = get_f64_timestamp();
+                changed.push(usage.clone());
+            }
+
+            // 配置变更、Flow 规则被重写或不再超出时先解除, 再按需重新执行
+            let stale = match state.enforced.get(&config.id) {
+                Some(enforced) => {
+                    !exceeded
+                        || enforced.update_at != config.update_at
+                        || enforced.flow_version.is_some_and(|version| version != flow_version)
+                }
+                None => false,
+            };
+            if stale {
+                if let Some(enforced) = state.enforced.remove(&config.id) {
+                    release_quota_action(enforced.enforcement, flow_configs.clone()).await;
+                }
+            }
+            if exceeded && !state.enforced.contains_key(&config.id) {
+                let enforcement = apply_quota_action(config, device, &flow_configs);
+                let flow_version =
+                    matches!(enforcement, QuotaEnforcement::SwitchFlow(_)).then_some(flow_version);
+                state.enforced.insert(
+                    config.id,
+                    EnforcedAction {
+                        update_at: config.update_at,
+                        flow_version,
+                        enforcement,
+                    },
+                );
+            }
+        }
+        drop(state);
+
+        for usage in changed {
+            if let Err(e) = self.usage_store.set_or_update_model(usage.id, usage).await {
+                tracing::error!("save traffic quota usage error: {e:?}");
+            }
+        }
+    }
+
+    pub async fn list_status(&self) -> Vec<TrafficQuotaStatus> {
+        let configs = self.list().await;
+        let state = self.state.lock().await;
+        configs
+            .into_iter()
+            .filter_map(|config| {
+                let (period_start, period_end) = quota_period_range(&config.period)?;
+                let usage = state.usages.get(&config.id).filter(|u| u.period_start == period_start);
+                Some(TrafficQuotaStatus {
+                    period_start,
+                    period_end,
+                    used_bytes: usage.map(|u| u.used_bytes).unwrap_or(0),
+                    exceeded_at: usage.and_then(|u| u.exceeded_at),
+                    config,
+                })
+            })
+            .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl ConfigController for TrafficQuotaService {
+    type Id = Uuid;
+    type Config = TrafficQuotaConfig;
+    type DatabseAction = TrafficQuotaRepository;
+
+    fn get_repository(&self) -> &Self::DatabseAction {
+        &self.store
+    }
+
+    async fn after_update_config(
+        &self,
+        _new_configs: Vec<Self::Config>,
+        _old_configs: Vec<Self::Config>,
+    ) {
+        self.reconcile(&[]).await;
+    }
+}
+
+/// 当前周期的起止时间 (本地时间)
+fn quota_period_range(period: &TrafficQuotaPeriod) -> Option<(u64, u64)> {
+    match period {
+        TrafficQuotaPeriod::Daily => {
+            let today = Local::now().date_naive();
+            let start = Local.from_local_datetime(&today.and_hms_opt(0, 0, 0)?).earliest()?;
+            let end = Local
+                .from_local_datetime(&(today + ChronoDuration::days(1)).and_hms_opt(0, 0, 0)?)
+                .earliest()?;
+            Some((start.timestamp_millis() as u64, end.timestamp_millis() as u64))
+        }
+        TrafficQuotaPeriod::Monthly { reset_day } => billing_cycle(None, *reset_day as u32),
+    }
+}
+
+/// 计入周期内新增的流量, 周期切换时先清零; 返回用量是否有变化
+fn account_usage(
+    usage: &mut TrafficQuotaUsage,
+    period_start: u64,
+    bytes: u64,
+    limit_bytes: u64,
+    now: u64,
+) -> bool {
+    let mut dirty = false;
+    if usage.period_start != period_start {
+        usage.period_start = period_start;
+        usage.used_bytes = 0;
+        usage.exceeded_at = None;
+        dirty = true;
+    }
+    if bytes > 0 {
+        usage.used_bytes += bytes;
+        dirty = true;
+    }
+    if usage.exceeded_at.is_none() && usage.used_bytes >= limit_bytes {
+        usage.exceeded_at = Some(now);
+        dirty = true;
+    }
+    dirty
+}
+
+fn find_quota_device<'a>(
+    target: &TrafficQuotaTarget,
+    devices: &'a [EnrolledDevice],
+) -> Option<&'a EnrolledDevice> {
+    match target {
+        TrafficQuotaTarget::Device { device_id } => {
+            devices.iter().find(|device| device.id == *device_id)
+        }
+        _ => None,
+    }
+}
+
+fn sample_matches(
+    sample: &TrafficSample,
+    target: &TrafficQuotaTarget,
+    device: Option<&EnrolledDevice>,
+) -> bool {
+    match target {
+        TrafficQuotaTarget::Wan { iface_name } => {
+            sample.kind == TrafficTargetKind::Iface && sample.target == *iface_name
+        }
+        TrafficQuotaTarget::Flow { flow_id } => {
+            sample.kind == TrafficTargetKind::Flow && sample.target == flow_id.to_string()
+        }
+        TrafficQuotaTarget::Device { .. } => {
+            let Some(device) = device else {
+                return false;
+            };
+            if sample.kind != TrafficTargetKind::Ip {
+                return false;
+            }
+            if sample.mac.as_deref() == Some(device.mac.to_string().as_str()) {
+                return true;
+            }
+            match sample.target.parse::<IpAddr>() {
+                Ok(IpAddr::V4(ip)) => device.ipv4 == Some(ip),
+                Ok(IpAddr::V6(ip)) => device.ipv6 == Some(ip),
+                Err(_) => false,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use landscape_common::net::MacAddr;
+
+    use super::*;
+
+    fn usage(period_start: u64) -> TrafficQuotaUsage {
+        TrafficQuotaUsage {
+            id: Uuid::nil(),
+            period_start,
+            used_bytes: 0,
+            exceeded_at: None,
+            update_at: 0.0,
+        }
+    }
+
+    fn sample(kind: TrafficTargetKind, target: &str, mac: Option<&str>) -> TrafficSample {
+        TrafficSample {
+            kind,
+            target: target.to_string(),
+            mac: mac.map(str::to_string),
+            report_time: 0,
+            rx_bytes: 100,
+            tx_bytes: 20,
+            rx_packets: 1,
+            tx_packets: 1,
+        }
+    }
+
+    #[test]
+    fn usage_accumulates_until_exceeded() {
+        let mut usage = usage(1000);
+        assert!(!account_usage(&mut usage, 1000, 0, 500, 1));
+        assert!(account_usage(&mut usage, 1000, 300, 500, 2));
+        assert_eq!(usage.exceeded_at, None);
+
+        assert!(account_usage(&mut usage, 1000, 200, 500, 3));
+        assert_eq!(usage.used_bytes, 500);
+        assert_eq!(usage.exceeded_at, Some(3));
+
+        // 超出时间保持为首次超出的时间
+        account_usage(&mut usage, 1000, 100, 500, 4);
+        assert_eq!(usage.exceeded_at, Some(3));
+    }
+
+    #[test]
+    fn usage_resets_on_new_period() {
+        let mut usage = usage(1000);
+        account_usage(&mut usage, 1000, 600, 500, 1);
+        assert!(usage.exceeded_at.is_some());
+
+        assert!(account_usage(&mut usage, 2000, 100, 500, 2));
+        assert_eq!(usage.period_start, 2000);
+        assert_eq!(usage.used_bytes, 100);
+        assert_eq!(usage.exceeded_at, None);
+    }
+
+    #[test]
+    fn device_samples_match_by_mac_or_ip() {
+        let device = EnrolledDevice {
+            id: Uuid::nil(),
+            update_at: 0.0,
+            iface_name: None,
+            name: "phone".to_string(),
+            fake_name: None,
+            remark: None,
+            mac: MacAddr(0, 0x11, 0x22, 0x33, 0x44, 0x55),
+            ipv4: Some(Ipv4Addr::new(192, 168, 1, 10)),
+            ipv6: None,
+            tag: vec![],
+        };
+        let target = TrafficQuotaTarget::Device { device_id: device.id };
+        let matches = |sample: &TrafficSample| sample_matches(sample, &target, Some(&device));
+
+        assert!(matches(&sample(TrafficTargetKind::Ip, "192.168.1.10", None)));
+        assert!(matches(&sample(TrafficTargetKind::Ip, "192.168.1.99", Some("00:11:22:33:44:55"))));
+        assert!(!matches(&sample(TrafficTargetKind::Ip, "192.168.1.11", None)));
+        assert!(!matches(&sample(TrafficTargetKind::Iface, "192.168.1.10", None)));
+        assert!(!sample_matches(
+            &sample(TrafficTargetKind::Ip, "192.168.1.10", None),
+            &target,
+            None
+        ));
+    }
+
+    #[test]
+    fn wan_and_flow_samples_match_by_target() {
+        let wan = TrafficQuotaTarget::Wan { iface_name: "eth0".to_string() };
+        assert!(sample_matches(&sample(TrafficTargetKind::Iface, "eth0", None), &wan, None));
+        assert!(!sample_matches(&sample(TrafficTargetKind::Iface, "eth1", None), &wan, None));
+
+        let flow = TrafficQuotaTarget::Flow { flow_id: 3 };
+        assert!(sample_matches(&sample(TrafficTargetKind::Flow, "3", None), &flow, None));
+        assert!(!sample_matches(&sample(TrafficTargetKind::Iface, "3", None), &flow, None));
+    }
+    fn flow_config(update_at: f64) -> FlowConfig {
+        FlowConfig {
+            id: Uuid::new_v4(),
+            enable: true,
+            flow_id: 1,
+            flow_match_rules: vec![],
+            flow_targets: vec![],
+            health_check: None,
+            remark: String::new(),
+            update_at,
+        }
+    }
+
+    #[test]
+    fn flow_config_version_changes_on_edit_and_delete() {
+        let mut configs = vec![flow_config(1.0), flow_config(2.0)];
+        let version = flow_config_version(&configs);
+        assert_eq!(version, flow_config_version(&configs));
+
+        configs[0].update_at = 3.0;
+        let edited = flow_config_version(&configs);
+        assert_ne!(version, edited);
+
+        configs.pop();
+        assert_ne!(edited, flow_config_version(&configs));
+    }
+}
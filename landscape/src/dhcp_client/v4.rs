@@ -611,6 +611,8 @@ async fn bind_ipv4(
                         weight: 1,
                         mac: Some(mac_addr.clone()),
                         is_docker: false,
+                        is_namespace: false,
+                        tproxy: None,
                         default_route: default_router,
                         iface_name: iface_name.to_string(),
                         iface_ip: IpAddr::V4(new_yiaddr),
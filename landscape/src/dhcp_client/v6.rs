@@ -6,7 +6,7 @@ use std::{
 };
 
 use dhcproto::{
-    v6::{self, DhcpOption, DhcpOptions, IAPrefix, Message, OptionCode},
+    v6::{self, DhcpOption, DhcpOptions, IAAddr, IAPrefix, Message, OptionCode},
     Decodable, Decoder, Encodable, Encoder,
 };
 
@@ -16,7 +16,8 @@ use tokio::{net::UdpSocket, time::Instant};
 use crate::{dump::udp_packet::dhcp_v6::get_solicit_options, route::IpRouteService};
 
 use landscape_common::{
-    ipv6_pd::IAPrefixMap,
+    dhcp::v6_client::config::IPV6PDConfig,
+    ipv6_pd::{IAPrefixMap, LDWanIpv6Addr, LDWanIpv6AddrSource, WanIpv6InfoMap},
     service::{ServiceStatus, WatchService},
     utils::time::get_f64_timestamp,
     LANDSCAPE_DEFAULE_DHCP_V6_SERVER_PORT,
@@ -41,20 +42,20 @@ pub enum IpV6PdState {
     Request {
         xid: u32,
         service_id: Vec<u8>,
-        iapd: v6::IAPD,
+        lease: IaLease,
         service_sock: SocketAddr,
         send_times: u8,
     },
 
     /// 地址激活使用
-    Bound { xid: u32, service_id: Vec<u8>, iapd: v6::IAPD, bound_time: Instant },
+    Bound { xid: u32, service_id: Vec<u8>, lease: IaLease, bound_time: Instant },
     /// 确认当前地址状态
     Confirm,
     /// Renew 续订 T1 事件触发
     Renew {
         xid: u32,
         service_id: Vec<u8>,
-        iapd: v6::IAPD,
+        lease: IaLease,
         renew_time: Instant,
         bound_time: Instant,
     },
@@ -62,14 +63,14 @@ pub enum IpV6PdState {
         // 用于在 WaitToRebind 是也可确认 Renew 最后一次发送的数据包
         xid: u32,
         service_id: Vec<u8>,
-        iapd: v6::IAPD,
+        lease: IaLease,
         bound_time: Instant,
     },
     /// 续订超时
     Rebind {
         xid: u32,
         service_id: Vec<u8>,
-        iapd: v6::IAPD,
+        lease: IaLease,
         rebind_time: Instant,
         bound_time: Instant,
     },
@@ -82,6 +83,67 @@ pub enum IpV6PdState {
     Stop,
 }
 
+/// 服务器分配的 IA_PD / IA_NA
+#[derive(Clone, Debug, Default)]
+pub struct IaLease {
+    pub iapd: Option<v6::IAPD>,
+    pub iana: Option<v6::IANA>,
+}
+
+impl IaLease {
+    pub fn is_empty(&self) -> bool {
+        self.iapd.is_none() && self.iana.is_none()
+    }
+
+    /// 取已获取的 IA 中最小的 T1
+    pub fn t1(&self) -> u64 {
+        let t1 = [self.iapd.as_ref().map(|i| i.t1), self.iana.as_ref().map(|i| i.t1)]
+            .into_iter()
+            .flatten()
+            .filter(|t| *t != 0)
+            .min();
+        t1.map(|t| t as u64).unwrap_or(IPV6_T1_DEFAULT)
+    }
+
+    /// 取已获取的 IA 中最小的 T2
+    pub fn t2(&self) -> u64 {
+        let t2 = [self.iapd.as_ref().map(|i| i.t2), self.iana.as_ref().map(|i| i.t2)]
+            .into_iter()
+            .flatten()
+            .filter(|t| *t != 0)
+            .min();
+        t2.map(|t| t as u64).unwrap_or(IPV6_T2_DEFAULT)
+    }
+
+    /// 将当前持有的 IA 写入 Request / Renew / Rebind 报文
+    fn insert_into(&self, opts: &mut DhcpOptions) {
+        if let Some(iapd) = &self.iapd {
+            let mut options = DhcpOptions::new();
+            if let Some(ia_prefix) = iapd.opts.get(OptionCode::IAPrefix) {
+                options.insert(ia_prefix.clone());
+            }
+            opts.insert(DhcpOption::IAPD(v6::IAPD {
+                id: iapd.id,
+                t1: iapd.t1,
+                t2: iapd.t2,
+                opts: options,
+            }));
+        }
+        if let Some(iana) = &self.iana {
+            let mut options = DhcpOptions::new();
+            if let Some(ia_addr) = iana.opts.get(OptionCode::IAAddr) {
+                options.insert(ia_addr.clone());
+            }
+            opts.insert(DhcpOption::IANA(v6::IANA {
+                id: iana.id,
+                t1: iana.t1,
+                t2: iana.t2,
+                opts: options,
+            }));
+        }
+    }
+}
+
 fn get_new_ipv6_xid() -> u32 {
     let mut xid = rand::random();
     xid = xid & 0x00FFFFFF;
@@ -177,15 +239,29 @@ pub async fn dhcp_v6_pd_client(
     // for ebpf map setting
     mac_addr: Option<MacAddr>,
     // for pd request
-    config_mac: MacAddr,
+    config: IPV6PDConfig,
     client_port: u16,
     service_status: WatchService,
     wan_route_info: RouteTargetInfo,
     route_service: IpRouteService,
     prefix_map: IAPrefixMap,
+    wan_ipv6_info: WanIpv6InfoMap,
 ) {
     prefix_map.init(&iface_name).await;
-    let client_id = gen_client_id(config_mac);
+    let client_id = gen_client_id(config.mac);
+    // 开启 RA 处理时, 默认路由由 RA 客户端维护
+    let ctx = DhcpV6ClientCtx {
+        iface_name: iface_name.clone(),
+        ifindex,
+        mac_addr,
+        request_pd: config.request_pd,
+        request_na: config.request_na,
+        manage_route: !config.accept_ra,
+        wan_route_info,
+        route_service: route_service.clone(),
+        prefix_map,
+        wan_ipv6_info: wan_ipv6_info.clone(),
+    };
     service_status.just_change_status(ServiceStatus::Staring);
 
     // if let Err(e) = std::process::Command::new("sysctl")
@@ -289,7 +365,7 @@ pub async fn dhcp_v6_pd_client(
                     }
                 }
 
-                let need_reset_timeout = send_current_status_packet(&client_id, &send_socket, &mut status).await;
+                let need_reset_timeout = send_current_status_packet(&ctx, &client_id, &send_socket, &mut status).await;
                 if need_reset_timeout {
                     timeout_times = 0;
                 }
@@ -301,7 +377,7 @@ pub async fn dhcp_v6_pd_client(
                 // 处理接收到的数据包
                 match message_result {
                     Some(data) => {
-                        let need_reset_time = handle_packet(&iface_name, ifindex, &client_id, &mut status, data, &wan_route_info, &route_service, &prefix_map, &mac_addr).await;
+                        let need_reset_time = handle_packet(&ctx, &client_id, &mut status, data).await;
                         if need_reset_time {
                             timeout_times = get_status_timeout_config(&status, 0, active_send.as_mut());
                             // current_timeout_time = t2;
@@ -333,7 +409,15 @@ pub async fn dhcp_v6_pd_client(
         }
     }
 
-    route_service.remove_ipv6_wan_route(&iface_name).await;
+    if ctx.manage_route {
+        route_service.remove_ipv6_wan_route(&iface_name).await;
+    }
+    if let Some(info) = wan_ipv6_info.get_iface_info(&iface_name).await {
+        for addr in info.addrs.iter().filter(|a| a.source == LDWanIpv6AddrSource::Dhcpv6) {
+            crate::icmp::v6::del_iface_ip(addr.ip, addr.prefix_len, &iface_name);
+        }
+    }
+    wan_ipv6_info.clean_source(&iface_name, LDWanIpv6AddrSource::Dhcpv6).await;
     tracing::info!("DHCP V6 Client Stop: {:#?}", service_status);
 
     if !service_status.is_stop() {
@@ -344,6 +428,7 @@ pub async fn dhcp_v6_pd_client(
 /// 处理当前状态应该发送什么数据包
 /// 当需要重置 timeout 就返回 true
 async fn send_current_status_packet(
+    ctx: &DhcpV6ClientCtx,
     my_client_id: &[u8],
     send_socket: &UdpSocket,
     current_status: &mut IpV6PdState,
@@ -351,27 +436,23 @@ async fn send_current_status_packet(
     match current_status {
         IpV6PdState::Solicit { xid } => {
             let mut msg = v6::Message::new(v6::MessageType::Solicit);
-            msg.set_opts(get_solicit_options());
+            msg.set_opts(get_solicit_options(ctx.request_pd, ctx.request_na));
             msg.set_xid_num(xid.clone());
             msg.opts_mut().insert(v6::DhcpOption::ClientId(my_client_id.to_vec()));
 
             send_data(&msg, send_socket, None).await;
         }
         // IpV6PdState::Advertise { xid } => todo!(),
-        IpV6PdState::Request { xid, service_id, iapd, service_sock: _, send_times } => {
+        IpV6PdState::Request {
+            xid,
+            service_id,
+            lease,
+            service_sock: _,
+            send_times,
+        } => {
             let mut send_msg = v6::Message::new(V6MessageType::Request);
             send_msg.set_xid_num(*xid);
-            let mut options = DhcpOptions::new();
-            if let Some(ia_prefix) = iapd.opts.get(OptionCode::IAPrefix) {
-                options.insert(ia_prefix.clone());
-            }
-            let iapd = DhcpOption::IAPD(v6::IAPD {
-                id: iapd.id,
-                t1: iapd.t1,
-                t2: iapd.t2,
-                opts: options,
-            });
-            send_msg.opts_mut().insert(iapd);
+            lease.insert_into(send_msg.opts_mut());
             send_msg.opts_mut().insert(v6::DhcpOption::ClientId(my_client_id.to_vec()));
             send_msg.opts_mut().insert(DhcpOption::ServerId(service_id.clone()));
 
@@ -386,33 +467,23 @@ async fn send_current_status_packet(
             }
             *send_times += 1;
         }
-        IpV6PdState::Bound { xid: _, service_id, iapd, bound_time } => {
+        IpV6PdState::Bound { xid: _, service_id, lease, bound_time } => {
             // t1 时间到 转换状态为 Renew
             *current_status = IpV6PdState::Renew {
                 xid: get_new_ipv6_xid(),
                 service_id: service_id.clone(),
                 renew_time: Instant::now(),
                 bound_time: bound_time.clone(),
-                iapd: iapd.clone(),
+                lease: lease.clone(),
             };
             return true;
         }
         IpV6PdState::Confirm => todo!(),
-        IpV6PdState::Renew { xid, service_id, iapd, renew_time, bound_time } => {
+        IpV6PdState::Renew { xid, service_id, lease, renew_time, bound_time } => {
             //
             let mut send_msg = v6::Message::new(V6MessageType::Renew);
             send_msg.set_xid_num(xid.clone());
-            let mut options = DhcpOptions::new();
-            if let Some(ia_prefix) = iapd.opts.get(OptionCode::IAPrefix) {
-                options.insert(ia_prefix.clone());
-            }
-            let iapd_options = DhcpOption::IAPD(v6::IAPD {
-                id: iapd.id,
-                t1: iapd.t1,
-                t2: iapd.t2,
-                opts: options,
-            });
-            send_msg.opts_mut().insert(iapd_options);
+            lease.insert_into(send_msg.opts_mut());
             //
             let now = (renew_time.elapsed().as_millis() as u16) / 10;
             send_msg.opts_mut().insert(v6::DhcpOption::ElapsedTime(now));
@@ -421,8 +492,7 @@ async fn send_current_status_packet(
 
             send_data(&send_msg, send_socket, None).await;
 
-            let t2 = if iapd.t2 == 0 { IPV6_T2_DEFAULT } else { iapd.t2 as u64 };
-            let t2 = t2 / 10 * 8;
+            let t2 = lease.t2() / 10 * 8;
             // Reach 80% wait to rebind
             if bound_time.elapsed().as_secs() >= t2 {
                 tracing::warn!("Renew turn to WaitToRebind");
@@ -431,12 +501,12 @@ async fn send_current_status_packet(
                     xid: xid.clone(),
                     service_id: service_id.clone(),
                     bound_time: bound_time.clone(),
-                    iapd: iapd.clone(),
+                    lease: lease.clone(),
                 };
                 return true;
             }
         }
-        IpV6PdState::WaitToRebind { xid: _, service_id, iapd, bound_time } => {
+        IpV6PdState::WaitToRebind { xid: _, service_id, lease, bound_time } => {
             tracing::warn!("WaitToRebind turn to Rebind");
             // 切换状态为 Rebind
             *current_status = IpV6PdState::Rebind {
@@ -444,13 +514,12 @@ async fn send_current_status_packet(
                 service_id: service_id.clone(),
                 rebind_time: Instant::now(),
                 bound_time: bound_time.clone(),
-                iapd: iapd.clone(),
+                lease: lease.clone(),
             };
             return true;
         }
-        IpV6PdState::Rebind { xid, service_id: _, iapd, rebind_time, bound_time } => {
-            let bind_end = if iapd.t2 == 0 { IPV6_T2_DEFAULT } else { iapd.t2 as u64 };
-            let bind_end = bind_end / 8 * 10;
+        IpV6PdState::Rebind { xid, service_id: _, lease, rebind_time, bound_time } => {
+            let bind_end = lease.t2() / 8 * 10;
             // Reach 125% to Solicit
             if bound_time.elapsed().as_secs() >= bind_end {
                 tracing::warn!("Rebind turn to Solicit");
@@ -461,17 +530,7 @@ async fn send_current_status_packet(
 
             let mut send_msg = v6::Message::new(V6MessageType::Rebind);
             send_msg.set_xid_num(xid.clone());
-            let mut options = DhcpOptions::new();
-            if let Some(ia_prefix) = iapd.opts.get(OptionCode::IAPrefix) {
-                options.insert(ia_prefix.clone());
-            }
-            let iapd = DhcpOption::IAPD(v6::IAPD {
-                id: iapd.id,
-                t1: iapd.t1,
-                t2: iapd.t2,
-                opts: options,
-            });
-            send_msg.opts_mut().insert(iapd);
+            lease.insert_into(send_msg.opts_mut());
             //
             let now = (rebind_time.elapsed().as_millis() as u16) / 10;
             send_msg.opts_mut().insert(v6::DhcpOption::ElapsedTime(now));
@@ -514,12 +573,11 @@ fn get_status_timeout_config(
     mut timeout: Pin<&mut tokio::time::Sleep>,
 ) -> u64 {
     let current_timeout_time = match current_status {
-        // 绑定后的超时时间是 由 iapd 的 t1 决定
-        IpV6PdState::Bound { iapd, .. } => iapd.t1 as u64,
+        // 绑定后的超时时间是 由 IA 的 t1 决定
+        IpV6PdState::Bound { lease, .. } => lease.t1(),
         // 等待的时间是 t2 - bound_time
-        IpV6PdState::WaitToRebind { iapd, bound_time, .. } => {
-            let t2 = if iapd.t2 == 0 { IPV6_T2_DEFAULT } else { iapd.t2 as u64 };
-            t2 - bound_time.elapsed().as_secs()
+        IpV6PdState::WaitToRebind { lease, bound_time, .. } => {
+            lease.t2().saturating_sub(bound_time.elapsed().as_secs())
         }
         _ => IPV6_TIMEOUT_DEFAULT_DURACTION * prev_timeout_times,
     };
@@ -527,18 +585,36 @@ fn get_status_timeout_config(
     timeout.set(tokio::time::sleep(Duration::from_secs(current_timeout_time)));
     prev_timeout_times + 1
 }
+/// 客户端运行期间不变的上下文
+struct DhcpV6ClientCtx {
+    iface_name: String,
+    ifindex: u32,
+    mac_addr: Option<MacAddr>,
+    request_pd: bool,
+    request_na: bool,
+    /// 是否由 DHCPv6 客户端维护 WAN 默认路由
+    manage_route: bool,
+    wan_route_info: RouteTargetInfo,
+    route_service: IpRouteService,
+    prefix_map: IAPrefixMap,
+    wan_ipv6_info: WanIpv6InfoMap,
+}
+
+/// 检查 IA 中的状态码, 没有状态码视为成功
+fn ia_status_success(opts: &DhcpOptions) -> bool {
+    match opts.get(OptionCode::StatusCode) {
+        Some(DhcpOption::StatusCode(code)) => matches!(code.status, v6::Status::Success),
+        _ => true,
+    }
+}
+
 /// 处理接收到的报文，根据当前状态决定如何处理
 /// 返回值为是否要进行检查刷新超时时间
 async fn handle_packet(
-    iface_name: &str,
-    ifindex: u32,
+    ctx: &DhcpV6ClientCtx,
     my_client_id: &[u8],
     current_status: &mut IpV6PdState,
     (msg, msg_addr): (Vec<u8>, SocketAddr),
-    wan_route_info: &RouteTargetInfo,
-    route_service: &IpRouteService,
-    prefix_map: &IAPrefixMap,
-    mac_addr: &Option<MacAddr>,
 ) -> bool {
     let IpAddr::V6(ipv6addr) = msg_addr.ip() else {
         tracing::error!("unexpected IPV4 packet");
@@ -583,7 +659,7 @@ async fn handle_packet(
             // *current_status = IpV6PdState::Advertise { xid };
 
             let mut my_service_id = vec![];
-            let mut iapd = None;
+            let mut lease = IaLease::default();
 
             if let Some(v6::DhcpOption::ServerId(service_id)) =
                 new_v6_msg.opts().get(OptionCode::ServerId)
@@ -592,16 +668,27 @@ async fn handle_packet(
                 tracing::info!("service_id: {:?}", service_id);
             }
 
-            if let Some(v6::DhcpOption::IAPD(new_iapd)) = new_v6_msg.opts().get(OptionCode::IAPD) {
-                iapd = Some(new_iapd.clone());
+            if ctx.request_pd {
+                if let Some(v6::DhcpOption::IAPD(new_iapd)) =
+                    new_v6_msg.opts().get(OptionCode::IAPD)
+                {
+                    lease.iapd = Some(new_iapd.clone());
+                }
+            }
+            if ctx.request_na {
+                if let Some(v6::DhcpOption::IANA(new_iana)) =
+                    new_v6_msg.opts().get(OptionCode::IANA)
+                {
+                    lease.iana = Some(new_iana.clone());
+                }
             }
 
             if !my_service_id.is_empty() {
-                if let Some(iapd) = iapd {
+                if !lease.is_empty() {
                     *current_status = IpV6PdState::Request {
                         xid: get_new_ipv6_xid(),
                         service_id: my_service_id,
-                        iapd,
+                        lease,
                         service_sock: msg_addr,
                         send_times: 0,
                     };
@@ -609,7 +696,7 @@ async fn handle_packet(
                     tracing::debug!("current status move to: {:#?}", current_status);
                     return true;
                 } else {
-                    tracing::debug!("iapd not exist");
+                    tracing::debug!("iapd and iana not exist");
                 }
             } else {
                 tracing::error!("service_id is empty, ignore this msg");
@@ -632,65 +719,95 @@ async fn handle_packet(
                     }
                 }
 
+                let mut lease = IaLease::default();
+                let mut ia_prefix = None;
+                let mut ia_addr = None;
+
                 if let Some(v6::DhcpOption::IAPD(iapd)) = new_v6_msg.opts().get(OptionCode::IAPD) {
-                    let mut success = true;
-                    let mut ia_prefix = None;
-                    for opt in iapd.opts.iter() {
-                        match opt {
-                            DhcpOption::StatusCode(code) => {
-                                if matches!(code.status, v6::Status::Success) {
-                                    success = true;
-                                } else {
-                                    success = false;
-                                    tracing::error!(
-                                        "current_status {:#?}, replay error: {:?}",
-                                        current_status,
-                                        new_v6_msg
-                                    );
-                                }
-                            }
-                            DhcpOption::IAPrefix(data) => {
-                                ia_prefix = Some(data);
-                            }
-                            _ => {}
-                        }
-                    }
-                    if let Some(ia_prefix) = ia_prefix {
-                        if success {
-                            *current_status = IpV6PdState::Bound {
-                                xid: get_new_ipv6_xid(),
-                                service_id,
-                                iapd: iapd.clone(),
-                                bound_time: Instant::now(),
-                            };
-
-                            let mut info = wan_route_info.clone();
-                            // info.iface_ip =
-                            info.gateway_ip = IpAddr::V6(ipv6addr.clone());
-                            route_service.insert_ipv6_wan_route(&iface_name, info).await;
-                            replace_ip_route(&ia_prefix, ipv6addr, iface_name, ifindex, mac_addr);
-                            // setting IA prefix to IAPrefixMap
-                            prefix_map
-                                .insert_or_replace(
-                                    iface_name,
-                                    landscape_common::ipv6_pd::LDIAPrefix {
-                                        preferred_lifetime: ia_prefix.preferred_lifetime,
-                                        valid_lifetime: ia_prefix.valid_lifetime,
-                                        prefix_len: ia_prefix.prefix_len,
-                                        prefix_ip: ia_prefix.prefix_ip,
-                                        last_update_time: get_f64_timestamp(),
-                                    },
-                                )
-                                .await;
-                            tracing::debug!("current status move to: {:#?}", current_status);
-                            return true;
-                        } else {
-                            tracing::error!("current status error: {:#?}", new_v6_msg);
-                        }
+                    if !ia_status_success(&iapd.opts) {
+                        tracing::error!(
+                            "current_status {:#?}, replay error: {:?}",
+                            current_status,
+                            new_v6_msg
+                        );
+                    } else if let Some(DhcpOption::IAPrefix(data)) =
+                        iapd.opts.get(OptionCode::IAPrefix)
+                    {
+                        ia_prefix = Some(data.clone());
+                        lease.iapd = Some(iapd.clone());
                     } else {
                         tracing::error!("current msg without ia_prefix: {:#?}", new_v6_msg);
                     }
                 }
+
+                if let Some(v6::DhcpOption::IANA(iana)) = new_v6_msg.opts().get(OptionCode::IANA) {
+                    if !ia_status_success(&iana.opts) {
+                        tracing::error!("IA_NA replay error: {:?}", new_v6_msg);
+                    } else if let Some(DhcpOption::IAAddr(data)) = iana.opts.get(OptionCode::IAAddr)
+                    {
+                        ia_addr = Some(data.clone());
+                        lease.iana = Some(iana.clone());
+                    } else {
+                        tracing::error!("current msg without ia_addr: {:#?}", new_v6_msg);
+                    }
+                }
+
+                if lease.is_empty() {
+                    tracing::error!("current status error: {:#?}", new_v6_msg);
+                    return false;
+                }
+
+                *current_status = IpV6PdState::Bound {
+                    xid: get_new_ipv6_xid(),
+                    service_id,
+                    lease,
+                    bound_time: Instant::now(),
+                };
+
+                let iface_name = ctx.iface_name.as_str();
+                if ctx.manage_route {
+                    let mut info = ctx.wan_route_info.clone();
+                    if let Some(ia_addr) = &ia_addr {
+                        info.iface_ip = IpAddr::V6(ia_addr.addr);
+                    }
+                    info.gateway_ip = IpAddr::V6(ipv6addr.clone());
+                    ctx.route_service.insert_ipv6_wan_route(&iface_name, info).await;
+                }
+
+                if let Some(ia_prefix) = ia_prefix {
+                    replace_ip_route(&ia_prefix, ipv6addr, iface_name, ctx.ifindex, &ctx.mac_addr);
+                    // setting IA prefix to IAPrefixMap
+                    ctx.prefix_map
+                        .insert_or_replace(
+                            iface_name,
+                            landscape_common::ipv6_pd::LDIAPrefix {
+                                preferred_lifetime: ia_prefix.preferred_lifetime,
+                                valid_lifetime: ia_prefix.valid_lifetime,
+                                prefix_len: ia_prefix.prefix_len,
+                                prefix_ip: ia_prefix.prefix_ip,
+                                last_update_time: get_f64_timestamp(),
+                            },
+                        )
+                        .await;
+                }
+
+                if let Some(ia_addr) = ia_addr {
+                    set_ia_addr(&ia_addr, ipv6addr, iface_name, ctx.ifindex, &ctx.mac_addr);
+                    ctx.wan_ipv6_info
+                        .modify(iface_name, |info| {
+                            info.upsert_addr(LDWanIpv6Addr {
+                                ip: ia_addr.addr,
+                                prefix_len: 128,
+                                preferred_lifetime: ia_addr.preferred_life,
+                                valid_lifetime: ia_addr.valid_life,
+                                source: LDWanIpv6AddrSource::Dhcpv6,
+                                last_update_time: get_f64_timestamp(),
+                            });
+                        })
+                        .await;
+                }
+                tracing::debug!("current status move to: {:#?}", current_status);
+                return true;
             }
             _ => {}
         },
@@ -737,3 +854,28 @@ fn replace_ip_route(
         tracing::error!("{e:?}");
     }
 }
+
+/// IA_NA 分配的是 /128 地址, 在链路上的前缀由 RA 决定
+fn set_ia_addr(
+    ia_addr: &IAAddr,
+    route_ip: Ipv6Addr,
+    iface_name: &str,
+    ifindex: u32,
+    mac: &Option<MacAddr>,
+) {
+    crate::icmp::v6::set_iface_ip(
+        ia_addr.addr,
+        128,
+        iface_name,
+        Some(ia_addr.valid_life),
+        Some(ia_addr.preferred_life),
+    );
+    // WAN 地址优先使用 IA_NA 获取的地址
+    landscape_ebpf::map_setting::add_ipv6_wan_ip(
+        ifindex,
+        ia_addr.addr,
+        Some(route_ip),
+        128,
+        mac.clone(),
+    );
+}
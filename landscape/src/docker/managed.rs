@@ -0,0 +1,524 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bollard::{
+    errors::Error as BollardError,
+    query_parameters::{
+        CreateContainerOptions, CreateImageOptions, InspectContainerOptions, LogsOptions,
+        RemoveContainerOptions, StartContainerOptions, StopContainerOptions,
+    },
+    secret::{ContainerCreateBody, HostConfig, PortBinding, RestartPolicy, RestartPolicyNameEnum},
+    Docker,
+};
+use landscape_common::docker::managed::{
+    ManagedContainerConfig, ManagedContainerError, ManagedContainerStatus,
+    LAND_MANAGED_CONTAINER_LABEL,
+};
+use landscape_common::docker::LAND_FLOW_EDGE_LABEL;
+use landscape_common::service::controller::ConfigController;
+use landscape_common::{NAMESPACE_REGISTER_SOCK_PATH, NAMESPACE_REGISTER_SOCK_PATH_IN_DOCKER};
+use landscape_database::{
+    managed_container::repository::ManagedContainerRepository, provider::LandscapeDBServiceProvider,
+};
+use tokio::sync::{mpsc, Mutex};
+use tokio_stream::StreamExt;
+use uuid::Uuid;
+
+use crate::docker::inspect_container_and_set_route;
+use crate::route::IpRouteService;
+
+/// 检查镜像更新的间隔
+const IMAGE_UPDATE_CHECK_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// 打开日志时先推送的历史行数
+const LOG_TAIL_LINES: &str = "200";
+
+#[derive(Default)]
+struct ManagedContainerState {
+    update_available: HashSet<Uuid>,
+    errors: HashMap<Uuid, String>,
+}
+
+#[derive(Clone)]
+pub struct ManagedContainerService {
+    store: ManagedContainerRepository,
+    route_service: IpRouteService,
+    home_path: PathBuf,
+    state: Arc<Mutex<ManagedContainerState>>,
+    /// 串行化容器的创建 / 启停 / 重建
+    lifecycle: Arc<Mutex<()>>,
+}
+
+impl ManagedContainerService {
+    pub async fn new(
+        store: LandscapeDBServiceProvider,
+        route_service: IpRouteService,
+        home_path: PathBuf,
+    ) -> Self {
+        let service = Self {
+            store: store.managed_container_store(),
+            route_service,
+            home_path,
+            state: Arc::new(Mutex::new(ManagedContainerState::default())),
+            lifecycle: Arc::new(Mutex::new(())),
+        };
+
+        let service_clone = service.clone();
+        tokio::spawn(async move {
+            for config in service_clone.list().await.into_iter().filter(|c| c.enable) {
+                let _ = service_clone.start(config.id).await;
+            }
+
+            let mut interval = tokio::time::interval(IMAGE_UPDATE_CHECK_INTERVAL);
+            loop {
+                interval.tick().await;
+                for config in service_clone.list().await {
+                    if config.enable && config.check_update {
+                        if let Err(e) = service_clone.check_update(config.id).await {
+                            tracing::warn!("check image update of {} fail: {e}", config.name);
+                        }
+                    }
+                }
+            }
+        });
+
+        service
+    }
+
+    async fn find_config(&self, id: Uuid) -> Result<ManagedContainerConfig, ManagedContainerError> {
+        self.find_by_id(id).await.ok_or(ManagedContainerError::NotFound(id))
+    }
+
+    /// 记录最近一次操作的结果, 供状态接口展示
+    async fn record_result(
+        &self,
+        id: Uuid,
+        result: Result<(), ManagedContainerError>,
+    ) -> Result<(), ManagedContainerError> {
+        let mut state = self.state.lock().await;
+        match &result {
+            Ok(_) => {
+                state.errors.remove(&id);
+            }
+            Err(e) => {
+                tracing::error!("managed container {id} error: {e}");
+                state.errors.insert(id, e.to_string());
+            }
+        }
+        result
+    }
+
+    /// 启动容器, 容器不存在时先创建, 随后重新注册 Flow 出口
+    pub async fn start(&self, id: Uuid) -> Result<(), ManagedContainerError> {
+        let result = self.start_inner(id).await;
+        self.record_result(id, result).await
+    }
+
+    /// 停止容器并移除其 Flow 出口
+    pub async fn stop(&self, id: Uuid) -> Result<(), ManagedContainerError> {
+        let result = self.stop_inner(id).await;
+        self.record_result(id, result).await
+    }
+
+    /// 删除并按当前配置重新创建容器, `pull` 为 true 时先拉取最新镜像
+    pub async fn recreate(&self, id: Uuid, pull: bool) -> Result<(), ManagedContainerError> {
+        let result = self.recreate_inner(id, pull).await;
+        self.record_result(id, result).await
+    }
+
+    async fn start_inner(&self, id: Uuid) -> Result<(), ManagedContainerError> {
+        let config = self.find_config(id).await?;
+        let _lock = self.lifecycle.lock().await;
+        let docker = connect_docker()?;
+
+        if !is_owned_container(&docker, &config.name, config.id).await? {
+            self.create_container(&docker, &config).await?;
+        }
+        start_container(&docker, &config.name).await?;
+        inspect_container_and_set_route(&config.name, &self.route_service, &docker).await;
+        Ok(())
+    }
+
+    async fn stop_inner(&self, id: Uuid) -> Result<(), ManagedContainerError> {
+        let config = self.find_config(id).await?;
+        let _lock = self.lifecycle.lock().await;
+        let docker = connect_docker()?;
+
+        if !is_owned_container(&docker, &config.name, config.id).await? {
+            return Ok(());
+        }
+        self.remove_target(&config.name).await;
+        let query: Option<StopContainerOptions> = None;
+        match docker.stop_container(&config.name, query).await {
+            Ok(_) => Ok(()),
+            Err(e) if is_status(&e, 304) || is_status(&e, 404) => Ok(()),
+            Err(e) => Err(docker_error(e)),
+        }
+    }
+
+    async fn recreate_inner(&self, id: Uuid, pull: bool) -> Result<(), ManagedContainerError> {
+        let config = self.find_config(id).await?;
+        let _lock = self.lifecycle.lock().await;
+        let docker = connect_docker()?;
+
+        let exists = is_owned_container(&docker, &config.name, config.id).await?;
+        if pull {
+            pull_image(&docker, &config.cmd.image_name).await?;
+            self.state.lock().await.update_available.remove(&id);
+        }
+        if exists {
+            self.remove_target(&config.name).await;
+            remove_container(&docker, &config.name).await?;
+        }
+        self.create_container(&docker, &config).await?;
+        if config.enable {
+            start_container(&docker, &config.name).await?;
+            inspect_container_and_set_route(&config.name, &self.route_service, &docker).await;
+        }
+        Ok(())
+    }
+
+    /// 对比本地镜像与仓库中的摘要, 返回是否有可用更新
+    pub async fn check_update(&self, id: Uuid) -> Result<bool, ManagedContainerError> {
+        let config = self.find_config(id).await?;
+        let docker = connect_docker()?;
+        let image = &config.cmd.image_name;
+
+        let remote = docker.inspect_registry_image(image, None).await.map_err(docker_error)?;
+        let Some(remote_digest) = remote.descriptor.digest else {
+            return Err(ManagedContainerError::Docker(format!("no digest for image: {image}")));
+        };
+        let update_available = match docker.inspect_image(image).await {
+            Ok(local) => !local
+                .repo_digests
+                .unwrap_or_default()
+                .iter()
+                .any(|digest| digest.ends_with(&format!("@{remote_digest}"))),
+            Err(e) if is_status(&e, 404) => true,
+            Err(e) => return Err(docker_error(e)),
+        };
+
+        let mut state = self.state.lock().await;
+        if update_available {
+            state.update_available.insert(id);
+        } else {
+            state.update_available.remove(&id);
+        }
+        Ok(update_available)
+    }
+
+    pub async fn list_status(&self) -> Vec<ManagedContainerStatus> {
+        let configs = self.list().await;
+        let docker = Docker::connect_with_socket_defaults().ok();
+
+        let mut result = Vec::with_capacity(configs.len());
+        for config in configs {
+            let mut container_id = None;
+            let mut container_state = None;
+            if let Some(docker) = &docker {
+                let query: Option<InspectContainerOptions> = None;
+                if let Ok(info) = docker.inspect_container(&config.name, query).await {
+                    container_id = info.id;
+                    container_state =
+                        info.state.and_then(|s| s.status).map(|status| status.to_string());
+                }
+            }
+            let state = self.state.lock().await;
+            result.push(ManagedContainerStatus {
+                container_id,
+                state: container_state,
+                update_available: state.update_available.contains(&config.id),
+                error: state.errors.get(&config.id).cloned(),
+                config,
+            });
+        }
+        result
+    }
+
+    /// 持续输出容器日志, 接收端关闭后停止
+    pub async fn logs(&self, id: Uuid) -> Result<mpsc::Receiver<String>, ManagedContainerError> {
+        let config = self.find_config(id).await?;
+        let docker = connect_docker()?;
+
+        let (tx, rx) = mpsc::channel(256);
+        tokio::spawn(async move {
+            let options = LogsOptions {
+                follow: true,
+                stdout: true,
+                stderr: true,
+                tail: LOG_TAIL_LINES.to_string(),
+                ..Default::default()
+            };
+            let mut stream = docker.logs(&config.name, Some(options));
+            loop {
+                tokio::select! {
+                    item = stream.next() => {
+                        let line = match item {
+                            Some(Ok(output)) => {
+                                String::from_utf8_lossy(&output.into_bytes()).to_string()
+                            }
+                            Some(Err(e)) => format!("{e}"),
+                            None => break,
+                        };
+                        if tx.send(line).await.is_err() {
+                            break;
+                        }
+                    }
+                    _ = tx.closed() => break,
+                }
+            }
+        });
+        Ok(rx)
+    }
+
+    async fn create_container(
+        &self,
+        docker: &Docker,
+        config: &ManagedContainerConfig,
+    ) -> Result<(), ManagedContainerError> {
+        if let Err(e) = docker.inspect_image(&config.cmd.image_name).await {
+            if !is_status(&e, 404) {
+                return Err(docker_error(e));
+            }
+            pull_image(docker, &config.cmd.image_name).await?;
+        }
+
+        let options = CreateContainerOptions {
+            name: Some(config.name.clone()),
+            platform: "".to_string(),
+        };
+        docker
+            .create_container(Some(options), self.build_create_body(config))
+            .await
+            .map_err(docker_error)?;
+        Ok(())
+    }
+
+    async fn remove_by_name(&self, name: &str, id: Uuid) -> Result<(), ManagedContainerError> {
+        let _lock = self.lifecycle.lock().await;
+        let docker = connect_docker()?;
+        if !is_owned_container(&docker, name, id).await? {
+            return Ok(());
+        }
+        self.remove_target(name).await;
+        remove_container(&docker, name).await
+    }
+
+    async fn remove_target(&self, name: &str) {
+        self.route_service.remove_ipv4_wan_route(name).await;
+        self.route_service.remove_ipv6_wan_route(name).await;
+    }
+
+    /// 与 `DockerCmd::generate_docker_command` 保持一致, 作为出口时额外添加权限与注册目录
+    fn build_create_body(&self, config: &ManagedContainerConfig) -> ContainerCreateBody {
+        let cmd = &config.cmd;
+
+        let mut env: Vec<String> =
+            cmd.environment.iter().flatten().map(|pair| pair.separator("=")).collect();
+        let mut labels: HashMap<String, String> = cmd
+            .labels
+            .iter()
+            .flatten()
+            .map(|pair| (pair.key.clone(), pair.value.clone()))
+            .collect();
+        labels.insert(LAND_MANAGED_CONTAINER_LABEL.to_string(), config.id.to_string());
+        if let Some(redirect) = &config.redirect {
+            labels.entry(LAND_FLOW_EDGE_LABEL.to_string()).or_default();
+            env.extend(redirect.envs().into_iter().map(|(key, value)| format!("{key}={value}")));
+        }
+
+        let mut binds: Vec<String> =
+            cmd.volumes.iter().flatten().map(|pair| pair.separator(":")).collect();
+        let mut port_bindings = HashMap::new();
+        for pair in cmd.ports.iter().flatten() {
+            let container_port = if pair.value.contains('/') {
+                pair.value.clone()
+            } else {
+                format!("{}/tcp", pair.value)
+            };
+            port_bindings.insert(
+                container_port,
+                Some(vec![PortBinding { host_ip: None, host_port: Some(pair.key.clone()) }]),
+            );
+        }
+
+        let mut host_config = HostConfig {
+            restart_policy: cmd
+                .restart
+                .as_deref()
+                .map(|restart| restart_policy(restart, cmd.restart_max_retries.unwrap_or(3))),
+            ..Default::default()
+        };
+        if labels.contains_key(LAND_FLOW_EDGE_LABEL) {
+            binds.push(format!(
+                "{}/:/{}/:ro",
+                self.home_path.join(NAMESPACE_REGISTER_SOCK_PATH).display(),
+                NAMESPACE_REGISTER_SOCK_PATH_IN_DOCKER
+            ));
+            host_config.cap_add =
+                Some(vec!["NET_ADMIN".to_string(), "BPF".to_string(), "PERFMON".to_string()]);
+            host_config.sysctls = Some(HashMap::from([(
+                "net.ipv4.conf.lo.accept_local".to_string(),
+                "1".to_string(),
+            )]));
+        }
+        host_config.binds = Some(binds);
+        host_config.port_bindings = Some(port_bindings);
+
+        let params: Vec<String> = cmd
+            .params
+            .iter()
+            .flat_map(|params| params.split_whitespace())
+            .map(|param| param.to_string())
+            .collect();
+
+        ContainerCreateBody {
+            image: Some(cmd.image_name.clone()),
+            env: Some(env),
+            labels: Some(labels),
+            entrypoint: cmd.entrypoint.clone().map(|entrypoint| vec![entrypoint]),
+            cmd: if params.is_empty() { None } else { Some(params) },
+            host_config: Some(host_config),
+            ..Default::default()
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ConfigController for ManagedContainerService {
+    type Id = Uuid;
+    type Config = ManagedContainerConfig;
+    type DatabseAction = ManagedContainerRepository;
+
+    fn get_repository(&self) -> &Self::DatabseAction {
+        &self.store
+    }
+
+    async fn after_update_config(
+        &self,
+        new_configs: Vec<Self::Config>,
+        old_configs: Vec<Self::Config>,
+    ) {
+        let service = self.clone();
+        tokio::spawn(async move {
+            for old in old_configs.iter() {
+                match new_configs.iter().find(|config| config.id == old.id) {
+                    Some(config) if config.name == old.name => {}
+                    Some(_) => {
+                        let result = service.remove_by_name(&old.name, old.id).await;
+                        let _ = service.record_result(old.id, result).await;
+                    }
+                    None => {
+                        if let Err(e) = service.remove_by_name(&old.name, old.id).await {
+                            tracing::error!("remove managed container {} error: {e}", old.name);
+                        }
+                        let mut state = service.state.lock().await;
+                        state.errors.remove(&old.id);
+                        state.update_available.remove(&old.id);
+                    }
+                }
+            }
+
+            for config in new_configs {
+                let old = old_configs.iter().find(|old| old.id == config.id);
+                let _ = match old {
+                    _ if !config.enable => service.stop(config.id).await,
+                    Some(old) if !config.need_recreate(old) => service.start(config.id).await,
+                    _ => service.recreate(config.id, false).await,
+                };
+            }
+        });
+    }
+}
+
+fn connect_docker() -> Result<Docker, ManagedContainerError> {
+    Docker::connect_with_socket_defaults().map_err(docker_error)
+}
+
+fn docker_error(e: BollardError) -> ManagedContainerError {
+    ManagedContainerError::Docker(e.to_string())
+}
+
+fn is_status(e: &BollardError, code: u16) -> bool {
+    matches!(e, BollardError::DockerResponseServerError { status_code, .. } if *status_code == code)
+}
+
+/// 同名容器是否由该配置创建, 容器不存在时返回 false, 属于其他来源时返回错误
+async fn is_owned_container(
+    docker: &Docker,
+    name: &str,
+    id: Uuid,
+) -> Result<bool, ManagedContainerError> {
+    let query: Option<InspectContainerOptions> = None;
+    let info = match docker.inspect_container(name, query).await {
+        Ok(info) => info,
+        Err(e) if is_status(&e, 404) => return Ok(false),
+        Err(e) => return Err(docker_error(e)),
+    };
+    let id = id.to_string();
+    let owned = info
+        .config
+        .and_then(|config| config.labels)
+        .is_some_and(|labels| labels.get(LAND_MANAGED_CONTAINER_LABEL) == Some(&id));
+    if owned {
+        Ok(true)
+    } else {
+        Err(ManagedContainerError::NotManaged(name.to_string()))
+    }
+}
+
+async fn start_container(docker: &Docker, name: &str) -> Result<(), ManagedContainerError> {
+    let query: Option<StartContainerOptions> = None;
+    match docker.start_container(name, query).await {
+        Ok(_) => Ok(()),
+        Err(e) if is_status(&e, 304) => Ok(()),
+        Err(e) => Err(docker_error(e)),
+    }
+}
+
+async fn remove_container(docker: &Docker, name: &str) -> Result<(), ManagedContainerError> {
+    let options = RemoveContainerOptions { force: true, v: false, link: false };
+    match docker.remove_container(name, Some(options)).await {
+        Ok(_) => Ok(()),
+        Err(e) if is_status(&e, 404) => Ok(()),
+        Err(e) => Err(docker_error(e)),
+    }
+}
+
+async fn pull_image(docker: &Docker, image: &str) -> Result<(), ManagedContainerError> {
+    let (from_image, tag) = split_image_tag(image);
+    let options = CreateImageOptions {
+        from_image: Some(from_image),
+        tag: Some(tag),
+        ..Default::default()
+    };
+    let mut stream = docker.create_image(Some(options), None, None);
+    while let Some(result) = stream.next().await {
+        result.map_err(docker_error)?;
+    }
+    Ok(())
+}
+
+/// 拆分镜像名与标签, 镜像仓库地址中可能带有端口
+fn split_image_tag(image: &str) -> (String, String) {
+    if let Some((name, digest)) = image.split_once('@') {
+        return (name.to_string(), digest.to_string());
+    }
+    match image.rsplit_once(':') {
+        Some((name, tag)) if !tag.contains('/') => (name.to_string(), tag.to_string()),
+        _ => (image.to_string(), "latest".to_string()),
+    }
+}
+
+fn restart_policy(restart: &str, max_retries: u32) -> RestartPolicy {
+    let (name, maximum_retry_count) = match restart {
+        "always" => (RestartPolicyNameEnum::ALWAYS, None),
+        "unless-stopped" => (RestartPolicyNameEnum::UNLESS_STOPPED, None),
+        "on-failure" => (RestartPolicyNameEnum::ON_FAILURE, None),
+        "on-failure:<max-retries>" => (RestartPolicyNameEnum::ON_FAILURE, Some(max_retries as i64)),
+        _ => (RestartPolicyNameEnum::NO, None),
+    };
+    RestartPolicy { name: Some(name), maximum_retry_count }
+}
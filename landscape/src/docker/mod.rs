@@ -20,6 +20,7 @@ use tokio_stream::StreamExt;
 use crate::{docker::image::PullManager, get_all_devices, route::IpRouteService};
 
 pub mod image;
+pub mod managed;
 pub mod network;
 pub mod unix_sock;
 
@@ -320,7 +321,7 @@ async fn scan_all_lan_net(ip_route_service: &IpRouteService) {
     }
 }
 
-async fn inspect_container_and_set_route(
+pub(crate) async fn inspect_container_and_set_route(
     name: &str,
     ip_route_service: &IpRouteService,
     docker: &Docker,
@@ -183,7 +183,9 @@ impl dhcproto::Decodable for Icmpv6Message {
             Icmpv6Type::RouterSolicitation => {
                 Icmpv6Message::RouterSolicitation(RouterSolicitation::decode(decoder)?)
             }
-            // Icmpv6Type::RouterAdvertisement => RouterAdvertisement::decode(decoder)?,
+            Icmpv6Type::RouterAdvertisement => {
+                Icmpv6Message::RouterAdvertisement(RouterAdvertisement::decode(decoder)?)
+            }
             Icmpv6Type::NeighborAdvertisement => {
                 Icmpv6Message::NeighborAdvertisement(NeighborAdvertisement::decode(decoder)?)
             }
@@ -195,7 +197,9 @@ impl dhcproto::Decodable for Icmpv6Message {
 impl dhcproto::Encodable for Icmpv6Message {
     fn encode(&self, e: &mut dhcproto::Encoder<'_>) -> dhcproto::v6::EncodeResult<()> {
         match self {
-            Icmpv6Message::RouterSolicitation(_) => todo!(),
+            Icmpv6Message::RouterSolicitation(router_solicitation) => {
+                router_solicitation.encode(e)?;
+            }
             Icmpv6Message::RouterAdvertisement(router_advertisement) => {
                 router_advertisement.encode(e)?;
             }
@@ -231,16 +235,28 @@ impl dhcproto::Decodable for RouterSolicitation {
         })
     }
 }
-// impl dhcproto::Encodable for RouterSolicitation {
-//     fn encode(&self, e: &mut dhcproto::Encoder<'_>) -> dhcproto::error::EncodeResult<()> {
-//         e.write_u8(self.msg_type.into())?;
-//         e.write_u8(self.hop_count)?;
-//         e.write_slice(&self.link_addr.octets())?;
-//         e.write_slice(&self.peer_addr.octets())?;
-//         self.opts.encode(e)?;
-//         Ok(())
-//     }
-// }
+impl RouterSolicitation {
+    pub fn new(opts: IcmpV6Options) -> Self {
+        Self {
+            msg_type: Icmpv6Type::RouterSolicitation,
+            msg_code: 0,
+            checksum: 0,
+            reserved: 0,
+            opts,
+        }
+    }
+}
+
+impl dhcproto::Encodable for RouterSolicitation {
+    fn encode(&self, e: &mut dhcproto::Encoder<'_>) -> dhcproto::v6::EncodeResult<()> {
+        e.write_u8(self.msg_type.into())?;
+        e.write_u8(self.msg_code)?;
+        e.write_u16(self.checksum)?;
+        e.write_u32(self.reserved)?;
+        self.opts.encode(e)?;
+        Ok(())
+    }
+}
 
 #[derive(Debug)]
 pub struct RouterAdvertisement {
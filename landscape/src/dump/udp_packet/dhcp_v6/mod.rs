@@ -1,14 +1,23 @@
-use dhcproto::v6::{DhcpOption, DhcpOptions, OptionCode, IAPD, ORO};
+use dhcproto::v6::{DhcpOption, DhcpOptions, OptionCode, IANA, IAPD, ORO};
 
-pub fn get_solicit_options() -> DhcpOptions {
+pub fn get_solicit_options(request_pd: bool, request_na: bool) -> DhcpOptions {
     let mut options = DhcpOptions::new();
-    let oro = ORO {
-        opts: vec![OptionCode::IAPrefix, OptionCode::DomainNameServers],
-    };
-
-    let iapd = IAPD { id: 1, t1: 0, t2: 0, opts: DhcpOptions::new() };
+    let mut oro_opts = vec![];
+    if request_pd {
+        oro_opts.push(OptionCode::IAPrefix);
+    }
+    oro_opts.push(OptionCode::DomainNameServers);
+    let oro = ORO { opts: oro_opts };
     options.insert(DhcpOption::ORO(oro));
-    options.insert(DhcpOption::IAPD(iapd));
+
+    if request_pd {
+        let iapd = IAPD { id: 1, t1: 0, t2: 0, opts: DhcpOptions::new() };
+        options.insert(DhcpOption::IAPD(iapd));
+    }
+    if request_na {
+        let iana = IANA { id: 1, t1: 0, t2: 0, opts: DhcpOptions::new() };
+        options.insert(DhcpOption::IANA(iana));
+    }
     options.insert(DhcpOption::ReconfAccept);
     options
 }
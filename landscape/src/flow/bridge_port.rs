@@ -0,0 +1,66 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use landscape_common::flow::{check_bridge_port_ifindex, config::FlowConfig, FlowEntryMatchMode};
+use tokio::sync::{oneshot, Mutex};
+
+use crate::iface::get_iface_by_name;
+
+/// 按 Flow 入口规则中出现的网桥端口挂载端口标记程序
+#[derive(Clone, Default)]
+pub struct BridgePortTagger {
+    /// 已挂载的端口及挂载时的 ifindex
+    ports: Arc<Mutex<HashMap<String, (u32, oneshot::Sender<()>)>>>,
+}
+
+impl BridgePortTagger {
+    pub async fn sync(&self, configs: &[FlowConfig]) {
+        let wanted: HashSet<String> = configs
+            .iter()
+            .filter(|config| config.enable)
+            .flat_map(|config| config.flow_match_rules.iter())
+            .filter_map(|rule| match &rule.mode {
+                FlowEntryMatchMode::BridgePort { iface_name } => Some(iface_name.clone()),
+                _ => None,
+            })
+            .collect();
+
+        let mut current = HashMap::new();
+        for name in wanted {
+            match get_iface_by_name(&name).await {
+                Some(iface) => {
+                    current.insert(name, iface.index);
+                }
+                None => tracing::warn!("bridge port {name} not found, entry rule is not active"),
+            }
+        }
+
+        let mut ports = self.ports.lock().await;
+        // 不再需要或网卡重建后 ifindex 变化的端口先卸载
+        let removed: Vec<String> = ports
+            .iter()
+            .filter(|(name, (ifindex, _))| current.get(*name) != Some(ifindex))
+            .map(|(name, _)| name.clone())
+            .collect();
+        for name in removed {
+            if let Some((_, tx)) = ports.remove(&name) {
+                let _ = tx.send(());
+            }
+        }
+
+        for (name, ifindex) in current {
+            if ports.contains_key(&name) {
+                continue;
+            }
+            if let Err(e) = check_bridge_port_ifindex(&name, ifindex) {
+                tracing::warn!("{e}, entry rule is not active");
+                continue;
+            }
+            let (tx, rx) = oneshot::channel();
+            std::thread::spawn(move || {
+                landscape_ebpf::bridge_port::run_bridge_port_tag(ifindex as i32, rx);
+            });
+            ports.insert(name, (ifindex, tx));
+        }
+    }
+}
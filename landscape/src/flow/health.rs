@@ -0,0 +1,135 @@
+use std::io::ErrorKind;
+use std::net::{Ipv4Addr, SocketAddr, TcpStream, UdpSocket};
+use std::time::Duration;
+
+use bollard::query_parameters::InspectContainerOptions;
+use bollard::Docker;
+use landscape_common::flow::health::{FlowHealthCheck, FlowTargetProbe};
+use landscape_common::flow::FlowTarget;
+use landscape_common::netns_target::NetnsTargetConfig;
+
+use crate::netns::{pid_netns_path, resolve_netns_path, run_in_netns};
+
+/// 对单个出口执行全部探测, 返回第一个失败原因
+pub async fn probe_flow_target(
+    target: &FlowTarget,
+    check: &FlowHealthCheck,
+    netns_targets: &[NetnsTargetConfig],
+) -> Result<(), String> {
+    let timeout = Duration::from_millis(check.timeout_ms as u64);
+    let netns_path = match target {
+        FlowTarget::Interface { name } => {
+            for probe in check.probes.iter() {
+                // 容器相关的探测对网卡出口不适用
+                if let FlowTargetProbe::Http { url } = probe {
+                    http_probe(url.clone(), Some(name.clone()), timeout).await?;
+                }
+            }
+            return Ok(());
+        }
+        FlowTarget::Netns { container_name } => {
+            pid_netns_path(inspect_container_pid(container_name).await?)
+        }
+        FlowTarget::Tproxy { port, tcp, .. } => {
+            for probe in check.probes.iter() {
+                // 在宿主机上检查监听端口, HTTP 探测对 TProxy 出口不生效
+                let (port, tcp) = match probe {
+                    FlowTargetProbe::ContainerRunning => (*port, *tcp),
+                    FlowTargetProbe::TcpPort { port } => (*port, true),
+                    FlowTargetProbe::Http { .. } => continue,
+                };
+                tokio::task::spawn_blocking(move || {
+                    if tcp {
+                        tcp_connect_probe(port, timeout)
+                    } else {
+                        udp_listen_probe(port)
+                    }
+                })
+                .await
+                .map_err(|e| e.to_string())??;
+            }
+            return Ok(());
+        }
+        FlowTarget::Namespace { name } => {
+            let config = netns_targets
+                .iter()
+                .find(|config| config.enable && config.name == *name)
+                .ok_or_else(|| format!("namespace target {name} not found"))?;
+            resolve_netns_path(&config.source).await?
+        }
+    };
+
+    for probe in check.probes.iter() {
+        match probe {
+            FlowTargetProbe::ContainerRunning => {}
+            FlowTargetProbe::TcpPort { port } => {
+                let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, *port));
+                run_in_netns(&netns_path, move || {
+                    TcpStream::connect_timeout(&addr, timeout)
+                        .map(|_| ())
+                        .map_err(|e| format!("connect {addr} failed: {e}"))
+                })
+                .await?;
+            }
+            FlowTargetProbe::Http { url } => {
+                let url = url.clone();
+                run_in_netns(&netns_path, move || {
+                    let runtime = tokio::runtime::Builder::new_current_thread()
+                        .enable_all()
+                        .build()
+                        .map_err(|e| e.to_string())?;
+                    runtime.block_on(http_probe(url, None, timeout))
+                })
+                .await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn tcp_connect_probe(port: u16, timeout: Duration) -> Result<(), String> {
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    TcpStream::connect_timeout(&addr, timeout)
+        .map(|_| ())
+        .map_err(|e| format!("connect {addr} failed: {e}"))
+}
+
+/// 仅 UDP 的 TProxy 出口无法建立连接, 通过端口是否已被占用判断进程是否在监听
+fn udp_listen_probe(port: u16) -> Result<(), String> {
+    let addr = SocketAddr::from((Ipv4Addr::UNSPECIFIED, port));
+    match UdpSocket::bind(addr) {
+        Ok(_) => Err(format!("no process is listening on udp port {port}")),
+        Err(e) if e.kind() == ErrorKind::AddrInUse => Ok(()),
+        Err(e) => Err(format!("check udp port {port} failed: {e}")),
+    }
+}
+
+/// 容器未运行时返回错误, 否则返回容器主进程 PID
+async fn inspect_container_pid(container_name: &str) -> Result<i64, String> {
+    let docker = Docker::connect_with_socket_defaults().map_err(|e| e.to_string())?;
+    let query: Option<InspectContainerOptions> = None;
+    let info = docker
+        .inspect_container(container_name, query)
+        .await
+        .map_err(|e| format!("inspect container {container_name} failed: {e}"))?;
+    let state = info.state.unwrap_or_default();
+    if !state.running.unwrap_or(false) {
+        return Err(format!("container {container_name} is not running"));
+    }
+    state.pid.filter(|pid| *pid > 0).ok_or_else(|| format!("container {container_name} has no pid"))
+}
+
+async fn http_probe(url: String, iface: Option<String>, timeout: Duration) -> Result<(), String> {
+    let mut builder = reqwest::Client::builder().timeout(timeout);
+    if let Some(iface) = iface.as_deref() {
+        builder = builder.interface(iface);
+    }
+    let client = builder.build().map_err(|e| e.to_string())?;
+    let resp = client.get(&url).send().await.map_err(|e| format!("request {url} failed: {e}"))?;
+    let status = resp.status();
+    if status.is_success() || status.is_redirection() {
+        Ok(())
+    } else {
+        Err(format!("request {url} returned {status}"))
+    }
+}
@@ -1,10 +1,19 @@
+pub mod bridge_port;
+pub mod health;
+pub mod quota;
+
 use std::collections::HashMap;
 
-use landscape_common::flow::{config::FlowConfig, FlowEbpfMatchPair, FlowEntryRule};
+use landscape_common::flow::{
+    config::FlowConfig, FlowEbpfMatchPair, FlowEntryMatchMode, FlowEntryRule,
+};
+
+/// 以匹配方式为键, 同一入口仅修改 QoS 时只需更新 eBPF 中的值
+type FlowMatchRuleMap = HashMap<FlowEntryMatchMode, (FlowEntryRule, u32)>;
 
-fn convert_mark_map_to_vec_mark(value: HashMap<FlowEntryRule, u32>) -> Vec<FlowEbpfMatchPair> {
+fn convert_mark_map_to_vec_mark(value: FlowMatchRuleMap) -> Vec<FlowEbpfMatchPair> {
     let mut result = Vec::with_capacity(value.len());
-    for (match_rule, flow_id) in value.into_iter() {
+    for (_, (match_rule, flow_id)) in value.into_iter() {
         result.push(FlowEbpfMatchPair::new(match_rule, flow_id));
     }
     result
@@ -35,7 +44,7 @@ pub async fn update_flow_matchs(rules: Vec<FlowConfig>, old_rules: Vec<FlowConfi
     landscape_ebpf::map_setting::route::cache::recreate_route_lan_cache_inner_map();
 }
 
-fn flow_rule_into_hash(rules: Vec<FlowConfig>) -> HashMap<FlowEntryRule, u32> {
+fn flow_rule_into_hash(rules: Vec<FlowConfig>) -> FlowMatchRuleMap {
     let mut new_mark_infos = HashMap::new();
 
     for ip_rule in rules.into_iter() {
@@ -43,15 +52,15 @@ fn flow_rule_into_hash(rules: Vec<FlowConfig>) -> HashMap<FlowEntryRule, u32> {
             continue;
         }
         for item in ip_rule.flow_match_rules.into_iter() {
-            new_mark_infos.insert(item, ip_rule.flow_id);
+            new_mark_infos.insert(item.mode.clone(), (item, ip_rule.flow_id));
         }
     }
     new_mark_infos
 }
 
 fn find_delete_rule_keys(
-    new_rules: &mut HashMap<FlowEntryRule, u32>,
-    old_rules: HashMap<FlowEntryRule, u32>,
+    new_rules: &mut FlowMatchRuleMap,
+    old_rules: FlowMatchRuleMap,
 ) -> Vec<FlowEntryRule> {
     let mut delete_keys = vec![];
     for (key, old_mark) in old_rules.into_iter() {
@@ -62,7 +71,7 @@ fn find_delete_rule_keys(
                 continue;
             }
         } else {
-            delete_keys.push(key);
+            delete_keys.push(old_mark.0);
         }
     }
     delete_keys
@@ -0,0 +1,115 @@
+use std::net::IpAddr;
+
+use landscape_common::enrolled_device::EnrolledDevice;
+use landscape_common::flow::{
+    config::FlowConfig, FlowEbpfMatchPair, FlowEntryMatchMode, FlowEntryRule,
+};
+use landscape_common::quota::{
+    QuotaLimitAction, QuotaLimitScope, TrafficQuotaAction, TrafficQuotaConfig, TrafficQuotaTarget,
+};
+use landscape_ebpf::map_setting::quota::{del_quota_limit, update_quota_limit};
+
+use crate::flow::update_flow_matchs;
+
+/// 超出配额后已写入 eBPF 的处理动作, 用于解除
+#[derive(Debug, Clone)]
+pub enum QuotaEnforcement {
+    Notify,
+    Limit(Vec<QuotaLimitScope>),
+    SwitchFlow(Vec<FlowEntryRule>),
+}
+
+/// 执行超出配额后的动作
+pub fn apply_quota_action(
+    config: &TrafficQuotaConfig,
+    device: Option<&EnrolledDevice>,
+    flow_configs: &[FlowConfig],
+) -> QuotaEnforcement {
+    match &config.action {
+        TrafficQuotaAction::Notify => QuotaEnforcement::Notify,
+        TrafficQuotaAction::Drop => {
+            let scopes = limit_scopes(&config.target, device);
+            for scope in scopes.iter() {
+                update_quota_limit(scope, QuotaLimitAction::Drop);
+            }
+            QuotaEnforcement::Limit(scopes)
+        }
+        TrafficQuotaAction::Throttle { rate_kbps } => {
+            let rate_bytes = *rate_kbps as u64 * 1000 / 8;
+            let scopes = limit_scopes(&config.target, device);
+            for scope in scopes.iter() {
+                update_quota_limit(scope, QuotaLimitAction::Throttle { rate_bytes });
+            }
+            QuotaEnforcement::Limit(scopes)
+        }
+        TrafficQuotaAction::SwitchFlow { flow_id } => {
+            let rules = switch_entry_rules(&config.target, device, flow_configs);
+            landscape_ebpf::map_setting::flow::update_flow_match_rule(
+                rules.iter().map(|rule| FlowEbpfMatchPair::new(rule.clone(), *flow_id)).collect(),
+            );
+            landscape_ebpf::map_setting::route::cache::recreate_route_lan_cache_inner_map();
+            QuotaEnforcement::SwitchFlow(rules)
+        }
+    }
+}
+
+/// 解除处理动作, 切换 Flow 的记录需要按当前 Flow 配置恢复
+pub async fn release_quota_action(enforcement: QuotaEnforcement, flow_configs: Vec<FlowConfig>) {
+    match enforcement {
+        QuotaEnforcement::Notify => {}
+        QuotaEnforcement::Limit(scopes) => {
+            for scope in scopes.iter() {
+                del_quota_limit(scope);
+            }
+        }
+        QuotaEnforcement::SwitchFlow(rules) => {
+            landscape_ebpf::map_setting::flow::del_flow_match_rule(rules);
+            update_flow_matchs(flow_configs, vec![]).await;
+        }
+    }
+}
+
+fn limit_scopes(
+    target: &TrafficQuotaTarget,
+    device: Option<&EnrolledDevice>,
+) -> Vec<QuotaLimitScope> {
+    match target {
+        TrafficQuotaTarget::Device { .. } => {
+            device.map(|device| vec![QuotaLimitScope::Mac(device.mac)]).unwrap_or_default()
+        }
+        TrafficQuotaTarget::Flow { flow_id } => vec![QuotaLimitScope::Flow(*flow_id)],
+        TrafficQuotaTarget::Wan { iface_name } => {
+            landscape_common::dev::get_interface_index_by_name(iface_name)
+                .map(|ifindex| vec![QuotaLimitScope::Ifindex(ifindex)])
+                .unwrap_or_default()
+        }
+    }
+}
+
+fn switch_entry_rules(
+    target: &TrafficQuotaTarget,
+    device: Option<&EnrolledDevice>,
+    flow_configs: &[FlowConfig],
+) -> Vec<FlowEntryRule> {
+    match target {
+        TrafficQuotaTarget::Device { .. } => {
+            let Some(device) = device else {
+                return vec![];
+            };
+            let mut modes = vec![FlowEntryMatchMode::Mac { mac_addr: device.mac }];
+            if let Some(ipv4) = device.ipv4 {
+                modes.push(FlowEntryMatchMode::Ip { ip: IpAddr::V4(ipv4), prefix_len: 32 });
+            }
+            if let Some(ipv6) = device.ipv6 {
+                modes.push(FlowEntryMatchMode::Ip { ip: IpAddr::V6(ipv6), prefix_len: 128 });
+            }
+            modes.into_iter().map(|mode| FlowEntryRule { qos: None, mode }).collect()
+        }
+        TrafficQuotaTarget::Flow { flow_id } => flow_configs
+            .iter()
+            .filter(|config| config.enable && config.flow_id == *flow_id)
+            .flat_map(|config| config.flow_match_rules.clone())
+            .collect(),
+        TrafficQuotaTarget::Wan { .. } => vec![],
+    }
+}
@@ -1 +1,2 @@
+pub mod ra_client;
 pub mod v6;
@@ -0,0 +1,414 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use dhcproto::{Decodable, Decoder, Encodable, Encoder};
+use landscape_common::global_const::default_router::{RouteInfo, RouteType, LD_ALL_ROUTERS};
+use landscape_common::ipv6_pd::{LDWanIpv6Addr, LDWanIpv6AddrSource, WanIpv6InfoMap};
+use landscape_common::net::MacAddr;
+use landscape_common::net_proto::icmpv6::options::{IcmpV6Option, IcmpV6OptionCode, IcmpV6Options};
+use landscape_common::route::RouteTargetInfo;
+use landscape_common::utils::time::get_f64_timestamp;
+use socket2::{Domain, Protocol, Socket, Type};
+use tokio::net::UdpSocket;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+use crate::dump::icmp::v6::options::{Icmpv6Message, RouterAdvertisement, RouterSolicitation};
+use crate::icmp::v6::{del_iface_ip, set_iface_ip};
+use crate::route::IpRouteService;
+
+static ICMPV6_MULTICAST_ROUTER: Ipv6Addr = Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 0, 0x2);
+
+/// RFC 4861 MAX_RTR_SOLICITATIONS
+const MAX_RTR_SOLICITATIONS: u32 = 3;
+/// RFC 4861 RTR_SOLICITATION_INTERVAL
+const RTR_SOLICITATION_INTERVAL: u64 = 4;
+/// 未收到 RA 时重新发起 RS 的间隔
+const RTR_SOLICITATION_RETRY_INTERVAL: u64 = 60;
+/// 用于暂停定时器
+const NEVER: Duration = Duration::from_secs(60 * 60 * 24 * 365);
+
+/// 通过 SLAAC 配置在 WAN 上的地址
+struct SlaacAddr {
+    prefix_len: u8,
+    expire_at: Instant,
+}
+
+/// 处理上游 RA: SLAAC 地址与默认路由, 并记录 RDNSS 供查看
+/// 生命周期由 DHCPv6 客户端服务控制, 通过 cancel 结束
+pub async fn icmp_ra_client(
+    iface_name: String,
+    ifindex: u32,
+    mac_addr: MacAddr,
+    cancel: CancellationToken,
+    wan_route_info: RouteTargetInfo,
+    route_service: IpRouteService,
+    wan_ipv6_info: WanIpv6InfoMap,
+) {
+    let socket = match create_ra_socket(&iface_name, &mac_addr).await {
+        Ok(socket) => socket,
+        Err(e) => {
+            tracing::error!("create RA client socket on {iface_name} error: {e:?}");
+            return;
+        }
+    };
+    let send_socket = Arc::new(socket);
+    let recive_socket_raw = send_socket.clone();
+
+    let (message_tx, mut message_rx) = tokio::sync::mpsc::channel::<(Vec<u8>, SocketAddr)>(1024);
+
+    // 接收数据
+    tokio::spawn(async move {
+        let mut buf = vec![0u8; 65535];
+        loop {
+            tokio::select! {
+                result = recive_socket_raw.recv_from(&mut buf) => {
+                    match result {
+                        Ok((len, addr)) => {
+                            let message = buf[..len].to_vec();
+                            if let Err(e) = message_tx.try_send((message, addr)) {
+                                tracing::error!("Error sending message to channel: {:?}", e);
+                            }
+                        }
+                        Err(e) => {
+                            tracing::error!("Error receiving data: {:?}", e);
+                        }
+                    }
+                },
+                _ = message_tx.closed() => {
+                    break;
+                }
+            }
+        }
+        tracing::info!("RA client recv loop down");
+    });
+
+    let mut slaac_addrs: HashMap<Ipv6Addr, SlaacAddr> = HashMap::new();
+    let mut current_gateway: Option<Ipv6Addr> = None;
+
+    let mut solicit_times = 0;
+    let solicit = tokio::time::sleep(Duration::from_secs(0));
+    tokio::pin!(solicit);
+    // 默认路由过期
+    let router_expire = tokio::time::sleep(NEVER);
+    tokio::pin!(router_expire);
+    // 地址过期检查
+    let mut addr_check = tokio::time::interval(Duration::from_secs(30));
+
+    loop {
+        tokio::select! {
+            _ = solicit.as_mut() => {
+                send_rs(&mac_addr, &send_socket).await;
+                solicit_times += 1;
+                let next = if solicit_times < MAX_RTR_SOLICITATIONS {
+                    RTR_SOLICITATION_INTERVAL
+                } else {
+                    RTR_SOLICITATION_RETRY_INTERVAL
+                };
+                solicit.as_mut().reset(Instant::now() + Duration::from_secs(next));
+            },
+            _ = router_expire.as_mut() => {
+                tracing::warn!("{iface_name} default router {current_gateway:?} expired");
+                if current_gateway.take().is_some() {
+                    remove_default_router(&iface_name, &route_service).await;
+                    wan_ipv6_info.modify(&iface_name, |info| {
+                        info.gateway = None;
+                        info.router_lifetime = 0;
+                    }).await;
+                }
+                router_expire.as_mut().reset(Instant::now() + NEVER);
+                solicit_times = 0;
+                solicit.as_mut().reset(Instant::now());
+            },
+            _ = addr_check.tick() => {
+                let now = Instant::now();
+                let expired: Vec<_> = slaac_addrs
+                    .iter()
+                    .filter(|(_, addr)| addr.expire_at <= now)
+                    .map(|(ip, addr)| (*ip, addr.prefix_len))
+                    .collect();
+                for (ip, prefix_len) in expired {
+                    tracing::info!("SLAAC address {ip}/{prefix_len} on {iface_name} expired");
+                    slaac_addrs.remove(&ip);
+                    del_iface_ip(ip, prefix_len, &iface_name);
+                    wan_ipv6_info.modify(&iface_name, |info| {
+                        info.addrs.retain(|a| !(a.ip == ip && a.source == LDWanIpv6AddrSource::Slaac));
+                    }).await;
+                }
+            },
+            message_result = message_rx.recv() => {
+                let Some((msg, addr)) = message_result else {
+                    break;
+                };
+                let Some(ra) = decode_ra(&msg) else {
+                    continue;
+                };
+                let SocketAddr::V6(addr) = addr else {
+                    continue;
+                };
+                let router_ip = addr.ip().to_owned();
+                // RFC 4861 6.1.2: RA 的源地址必须是链路本地地址
+                if !router_ip.is_unicast_link_local() {
+                    tracing::debug!("ignore RA from non link-local address: {router_ip}");
+                    continue;
+                }
+
+                solicit.as_mut().reset(Instant::now() + NEVER);
+
+                handle_ra(
+                    &iface_name,
+                    ifindex,
+                    &mac_addr,
+                    router_ip,
+                    &ra,
+                    &mut slaac_addrs,
+                    &wan_ipv6_info,
+                ).await;
+
+                if ra.router_lifetime == 0 {
+                    if current_gateway == Some(router_ip) {
+                        tracing::info!("{iface_name} router {router_ip} is no longer a default router");
+                        current_gateway = None;
+                        remove_default_router(&iface_name, &route_service).await;
+                        router_expire.as_mut().reset(Instant::now() + NEVER);
+                    }
+                } else {
+                    if current_gateway != Some(router_ip) {
+                        tracing::info!("{iface_name} set default router: {router_ip}");
+                        if current_gateway.is_some() {
+                            LD_ALL_ROUTERS.del_ipv6_route_by_iface(&iface_name).await;
+                        }
+                        current_gateway = Some(router_ip);
+                        set_default_router(
+                            &iface_name,
+                            router_ip,
+                            &wan_route_info,
+                            &route_service,
+                            slaac_addrs.keys().next().cloned(),
+                        ).await;
+                    }
+                    router_expire.as_mut().reset(
+                        Instant::now() + Duration::from_secs(ra.router_lifetime as u64),
+                    );
+                }
+            },
+            _ = cancel.cancelled() => {
+                break;
+            }
+        }
+    }
+
+    for (ip, addr) in slaac_addrs {
+        del_iface_ip(ip, addr.prefix_len, &iface_name);
+    }
+    if current_gateway.is_some() {
+        remove_default_router(&iface_name, &route_service).await;
+    }
+    wan_ipv6_info.clean_source(&iface_name, LDWanIpv6AddrSource::Slaac).await;
+    tracing::info!("RA client on {iface_name} stop");
+}
+
+async fn create_ra_socket(iface_name: &str, mac_addr: &MacAddr) -> std::io::Result<UdpSocket> {
+    let socket = Socket::new(Domain::IPV6, Type::RAW, Some(Protocol::ICMPV6))?;
+    socket.set_nonblocking(true)?;
+    socket.set_unicast_hops_v6(255)?;
+    socket.set_multicast_hops_v6(255)?;
+    socket.bind_device(Some(iface_name.as_bytes()))?;
+
+    // RS 需要使用链路本地地址发送
+    let setting_result =
+        crate::set_iface_ip_no_limit(iface_name, IpAddr::V6(mac_addr.to_ipv6_link_local()), 64)
+            .await;
+    if !setting_result {
+        tracing::error!("setting unicast_link_local error");
+    }
+
+    UdpSocket::from_std(socket.into())
+}
+
+fn decode_ra(msg: &[u8]) -> Option<RouterAdvertisement> {
+    match Icmpv6Message::decode(&mut Decoder::new(msg)) {
+        Ok(Icmpv6Message::RouterAdvertisement(ra)) => Some(ra),
+        Ok(_) => None,
+        Err(e) => {
+            tracing::error!("decode msg error: {e:?}");
+            None
+        }
+    }
+}
+
+async fn send_rs(mac_addr: &MacAddr, send_socket: &UdpSocket) {
+    let mut opts = IcmpV6Options::new();
+    opts.insert(IcmpV6Option::SourceLinkLayerAddress(mac_addr.octets().to_vec()));
+    let msg = Icmpv6Message::RouterSolicitation(RouterSolicitation::new(opts));
+
+    let mut buf = Vec::new();
+    let mut e = Encoder::new(&mut buf);
+    if let Err(e) = msg.encode(&mut e) {
+        tracing::error!("msg encode error: {e:?}");
+        return;
+    }
+    let target = SocketAddr::new(IpAddr::V6(ICMPV6_MULTICAST_ROUTER), 0);
+    if let Err(e) = send_socket.send_to(&buf, &target).await {
+        tracing::error!("send RS error: {e:?}");
+    }
+}
+
+/// 使用 EUI-64 生成 SLAAC 地址
+fn gen_slaac_addr(prefix: Ipv6Addr, mac_addr: &MacAddr) -> Ipv6Addr {
+    let host_mask = u64::MAX as u128;
+    let network = u128::from(prefix) & !host_mask;
+    let host = u128::from(mac_addr.to_ipv6_link_local()) & host_mask;
+    Ipv6Addr::from(network | host)
+}
+
+async fn handle_ra(
+    iface_name: &str,
+    ifindex: u32,
+    mac_addr: &MacAddr,
+    router_ip: Ipv6Addr,
+    ra: &RouterAdvertisement,
+    slaac_addrs: &mut HashMap<Ipv6Addr, SlaacAddr>,
+    wan_ipv6_info: &WanIpv6InfoMap,
+) {
+    tracing::debug!("recv RA from {router_ip}: {ra:?}");
+    let mut new_addrs = vec![];
+    for opt in ra.opts.get_all(IcmpV6OptionCode::PrefixInformation).unwrap_or_default() {
+        let IcmpV6Option::PrefixInformation(info) = opt else {
+            continue;
+        };
+        // 只有 A 标志且前缀为 /64 时才可进行 SLAAC
+        if !info.autonomous() || info.prefix_length != 64 {
+            continue;
+        }
+        if info.preferred_lifetime > info.valid_lifetime {
+            continue;
+        }
+        let ip = gen_slaac_addr(info.prefix, mac_addr);
+        if info.prefix.is_unicast_link_local() {
+            continue;
+        }
+
+        // RFC 4862 5.5.3 e): 防止通过短生存期的 RA 使地址提前失效
+        let valid_lifetime = match slaac_addrs.get(&ip) {
+            Some(exist) => {
+                let remaining =
+                    exist.expire_at.saturating_duration_since(Instant::now()).as_secs() as u32;
+                if info.valid_lifetime > 7200 || info.valid_lifetime > remaining {
+                    info.valid_lifetime
+                } else if remaining <= 7200 {
+                    remaining
+                } else {
+                    7200
+                }
+            }
+            None => info.valid_lifetime,
+        };
+        if valid_lifetime == 0 {
+            continue;
+        }
+
+        let is_new = !slaac_addrs.contains_key(&ip);
+        set_iface_ip(
+            ip,
+            info.prefix_length,
+            iface_name,
+            Some(valid_lifetime),
+            Some(info.preferred_lifetime),
+        );
+        if is_new {
+            tracing::info!("SLAAC address {ip}/{} on {iface_name}", info.prefix_length);
+            landscape_ebpf::map_setting::add_ipv6_wan_ip(
+                ifindex,
+                ip,
+                Some(router_ip),
+                info.prefix_length,
+                Some(mac_addr.clone()),
+            );
+        }
+        slaac_addrs.insert(
+            ip,
+            SlaacAddr {
+                prefix_len: info.prefix_length,
+                expire_at: Instant::now() + Duration::from_secs(valid_lifetime as u64),
+            },
+        );
+        new_addrs.push(LDWanIpv6Addr {
+            ip,
+            prefix_len: info.prefix_length,
+            preferred_lifetime: info.preferred_lifetime,
+            valid_lifetime,
+            source: LDWanIpv6AddrSource::Slaac,
+            last_update_time: get_f64_timestamp(),
+        });
+    }
+
+    // RDNSS 仅记录在 WAN 信息中, DNS 上游仍以 DNS 上游配置为准
+    let rdnss: Vec<Ipv6Addr> = ra
+        .opts
+        .get_all(IcmpV6OptionCode::RecursiveDNSServer)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|opt| match opt {
+            IcmpV6Option::RecursiveDNSServer((lifetime, ip)) if *lifetime > 0 => Some(*ip),
+            _ => None,
+        })
+        .collect();
+
+    let mtu = match ra.opts.get(IcmpV6OptionCode::MTU) {
+        Some(IcmpV6Option::MTU(mtu)) => Some(*mtu),
+        _ => None,
+    };
+
+    wan_ipv6_info
+        .modify(iface_name, |info| {
+            for addr in new_addrs {
+                info.upsert_addr(addr);
+            }
+            if ra.router_lifetime > 0 {
+                info.gateway = Some(router_ip);
+            } else if info.gateway == Some(router_ip) {
+                info.gateway = None;
+            }
+            info.router_lifetime = ra.router_lifetime;
+            if !rdnss.is_empty() {
+                info.rdnss = rdnss;
+            }
+            if mtu.is_some() {
+                info.mtu = mtu;
+            }
+        })
+        .await;
+}
+
+async fn set_default_router(
+    iface_name: &str,
+    router_ip: Ipv6Addr,
+    wan_route_info: &RouteTargetInfo,
+    route_service: &IpRouteService,
+    iface_ip: Option<Ipv6Addr>,
+) {
+    if wan_route_info.default_route {
+        LD_ALL_ROUTERS
+            .add_route(RouteInfo {
+                iface_name: iface_name.to_string(),
+                weight: 1,
+                route: RouteType::Ipv6(router_ip),
+            })
+            .await;
+    }
+
+    let mut info = wan_route_info.clone();
+    info.gateway_ip = IpAddr::V6(router_ip);
+    if let Some(iface_ip) = iface_ip {
+        info.iface_ip = IpAddr::V6(iface_ip);
+    }
+    route_service.insert_ipv6_wan_route(iface_name, info).await;
+}
+
+async fn remove_default_router(iface_name: &str, route_service: &IpRouteService) {
+    LD_ALL_ROUTERS.del_ipv6_route_by_iface(iface_name).await;
+    route_service.remove_ipv6_wan_route(iface_name).await;
+}
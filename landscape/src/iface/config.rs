@@ -30,6 +30,7 @@ pub fn from_phy_dev_with_wifi_info(
     NetworkIfaceConfig {
         name: iface.name.clone(),
         create_dev_type: create_from(iface),
+        dev_params: None,
         controller_name: None,
         enable_in_boot: matches!(iface.dev_status, crate::dev::DevState::Up),
         zone_type,
@@ -45,6 +46,7 @@ pub fn create_from(iface: &LandscapeInterface) -> CreateDevType {
     } else {
         match iface.dev_kind {
             DeviceKind::Bridge => CreateDevType::Bridge,
+            DeviceKind::Wireguard => CreateDevType::Wireguard,
             _ => CreateDevType::default(),
         }
     }
@@ -1,16 +1,33 @@
+use futures::TryStreamExt;
+use landscape_common::config::wifi::WifiBand;
+use landscape_common::config::wifi_station::WifiScanResult;
 pub use landscape_common::iface::dev_wifi::{LandscapeWifiInterface, WLANType};
-use wl_nl80211::Nl80211Message;
+use landscape_common::iface::dev_wifi::{
+    WifiBandCapability, WifiChannelCapability, WifiPhyCapability, WifiStationInfo,
+};
+use wl_nl80211::{
+    Nl80211Attr, Nl80211BandInfo, Nl80211BandType, Nl80211BssInfo, Nl80211Element,
+    Nl80211FrequencyInfo, Nl80211Message, Nl80211StationInfo,
+};
+
+/// HT Capability Info: Supported Channel Width Set
+const HT_CAP_SUP_WIDTH_20_40: u16 = 1 << 1;
+/// VHT Capability Info: Supported Channel Width Set
+const VHT_CAP_SUPP_CHAN_WIDTH_MASK: u32 = 0b11 << 2;
+/// BSS Capability Information: Privacy
+const BSS_CAP_PRIVACY: u16 = 1 << 4;
 
 pub fn new_landscape_wifi_interface(msg: Nl80211Message) -> Option<LandscapeWifiInterface> {
     let mut name = None;
     let mut index = None;
     let mut wifi_type = None;
+    let mut wiphy = None;
     for nla in msg.attributes.into_iter() {
         match nla {
             wl_nl80211::Nl80211Attr::IfIndex(i) => index = Some(i),
             wl_nl80211::Nl80211Attr::IfName(n) => name = Some(n),
             // wl_nl80211::Nl80211Attr::Mac(_) => todo!(),
-            // wl_nl80211::Nl80211Attr::Wiphy(_) => todo!(),
+            wl_nl80211::Nl80211Attr::Wiphy(w) => wiphy = Some(w),
             // wl_nl80211::Nl80211Attr::WiphyName(_) => todo!(),
             wl_nl80211::Nl80211Attr::IfType(nl80211_interface_type) => {
                 wifi_type = Some(nl80211_type_into_wlan_type(nl80211_interface_type))
@@ -99,7 +116,7 @@ pub fn new_landscape_wifi_interface(msg: Nl80211Message) -> Option<LandscapeWifi
 
     match (index, name, wifi_type) {
         (Some(index), Some(name), Some(wifi_type)) => {
-            Some(LandscapeWifiInterface { name, index, wifi_type })
+            Some(LandscapeWifiInterface { name, index, wifi_type, wiphy })
         }
         _ => None,
     }
@@ -123,3 +140,225 @@ pub fn nl80211_type_into_wlan_type(ty: wl_nl80211::Nl80211InterfaceType) -> WLAN
         wl_nl80211::Nl80211InterfaceType::Other(n) => WLANType::Other(n),
     }
 }
+
+/// 读取物理网卡支持的频段与信道
+pub async fn get_wifi_phy_capability(wiphy: u32) -> Option<WifiPhyCapability> {
+    let (connection, handle, _) = wl_nl80211::new_connection().ok()?;
+    tokio::spawn(connection);
+
+    let mut phy_handle = handle.wireless_physic().get().execute().await;
+    let mut capability = WifiPhyCapability { wiphy, bands: vec![] };
+    let mut found = false;
+    // 内核会将同一张网卡的信息拆分为多条消息
+    while let Ok(Some(msg)) = phy_handle.try_next().await {
+        let attrs = msg.payload.attributes;
+        if !attrs.iter().any(|nla| matches!(nla, Nl80211Attr::Wiphy(w) if *w == wiphy)) {
+            continue;
+        }
+        found = true;
+        for nla in attrs {
+            if let Nl80211Attr::WiphyBands(bands) = nla {
+                for band in bands {
+                    let band_type = match band.kind {
+                        Nl80211BandType::Band2GHz => WifiBand::Band2G,
+                        Nl80211BandType::Band5GHz => WifiBand::Band5G,
+                        Nl80211BandType::Band6GHz => WifiBand::Band6G,
+                        _ => continue,
+                    };
+                    let index = match capability.bands.iter().position(|b| b.band == band_type) {
+                        Some(index) => index,
+                        None => {
+                            capability.bands.push(new_band_capability(band_type));
+                            capability.bands.len() - 1
+                        }
+                    };
+                    update_band_capability(&mut capability.bands[index], band.info);
+                }
+            }
+        }
+    }
+
+    found.then_some(capability)
+}
+
+fn new_band_capability(band: WifiBand) -> WifiBandCapability {
+    WifiBandCapability {
+        band,
+        ht: false,
+        ht40: false,
+        vht: false,
+        vht160: false,
+        he: false,
+        he160: false,
+        channels: vec![],
+    }
+}
+
+fn update_band_capability(capability: &mut WifiBandCapability, infos: Vec<Nl80211BandInfo>) {
+    for info in infos {
+        match info {
+            Nl80211BandInfo::HtCapa(caps) => {
+                capability.ht = true;
+                capability.ht40 = caps.bits() & HT_CAP_SUP_WIDTH_20_40 != 0;
+            }
+            Nl80211BandInfo::VhtCap(caps) => {
+                capability.vht = true;
+                capability.vht160 = caps.bits() & VHT_CAP_SUPP_CHAN_WIDTH_MASK != 0;
+            }
+            Nl80211BandInfo::IftypeData(_) => {
+                capability.he = true;
+            }
+            Nl80211BandInfo::Freqs(freqs) => {
+                for freq in freqs {
+                    let mut channel = WifiChannelCapability {
+                        channel: 0,
+                        freq: 0,
+                        disabled: false,
+                        no_ir: false,
+                        radar: false,
+                        max_tx_power: None,
+                    };
+                    for attr in freq.info {
+                        match attr {
+                            Nl80211FrequencyInfo::Freq(f) => channel.freq = f,
+                            Nl80211FrequencyInfo::Disabled => channel.disabled = true,
+                            Nl80211FrequencyInfo::NoIr => channel.no_ir = true,
+                            Nl80211FrequencyInfo::Radar => channel.radar = true,
+                            Nl80211FrequencyInfo::MaxTxPower(p) => channel.max_tx_power = Some(p),
+                            _ => {}
+                        }
+                    }
+                    if let Some((_, ch)) = WifiBand::freq_to_channel(channel.freq) {
+                        channel.channel = ch;
+                        capability.channels.push(channel);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    // 6GHz 没有 HT / VHT 能力, 160MHz 以 HE 为准
+    capability.he160 = capability.he && (capability.vht160 || capability.band == WifiBand::Band6G);
+}
+
+/// 列出接口上已连接的客户端
+pub async fn get_wifi_stations(iface_name: &str, ifindex: u32) -> Vec<WifiStationInfo> {
+    let (connection, handle, _) = match wl_nl80211::new_connection() {
+        Ok(conn) => conn,
+        Err(_) => return vec![],
+    };
+    tokio::spawn(connection);
+
+    let mut station_handle = handle.station().dump(ifindex).execute().await;
+    let mut result = vec![];
+    while let Ok(Some(msg)) = station_handle.try_next().await {
+        let mut mac = None;
+        let mut station = WifiStationInfo {
+            iface_name: iface_name.to_string(),
+            mac: Default::default(),
+            signal: None,
+            rx_bytes: 0,
+            tx_bytes: 0,
+            connected_time: 0,
+            inactive_time: 0,
+        };
+        for nla in msg.payload.attributes {
+            match nla {
+                Nl80211Attr::Mac(addr) => mac = Some(addr),
+                Nl80211Attr::StationInfo(infos) => {
+                    for info in infos {
+                        match info {
+                            Nl80211StationInfo::Signal(s) => station.signal = Some(s),
+                            Nl80211StationInfo::RxBytes64(b) => station.rx_bytes = b,
+                            Nl80211StationInfo::TxBytes64(b) => station.tx_bytes = b,
+                            Nl80211StationInfo::ConnectedTime(t) => station.connected_time = t,
+                            Nl80211StationInfo::InactiveTime(t) => station.inactive_time = t,
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        if let Some(mac) = mac {
+            station.mac = mac.into();
+            result.push(station);
+        }
+    }
+    result
+}
+
+/// 客户端模式下当前连接的 SSID 与频率, 未连接时返回 None
+pub async fn get_wifi_link(ifindex: u32) -> Option<(String, Option<u32>)> {
+    let (connection, handle, _) = wl_nl80211::new_connection().ok()?;
+    tokio::spawn(connection);
+
+    let mut interface_handle = handle.interface().get().execute().await;
+    while let Ok(Some(msg)) = interface_handle.try_next().await {
+        let attrs = msg.payload.attributes;
+        if !attrs.iter().any(|nla| matches!(nla, Nl80211Attr::IfIndex(i) if *i == ifindex)) {
+            continue;
+        }
+        let mut ssid = None;
+        let mut freq = None;
+        for nla in attrs {
+            match nla {
+                Nl80211Attr::Ssid(s) => ssid = Some(s),
+                Nl80211Attr::WiphyFreq(f) => freq = Some(f),
+                _ => {}
+            }
+        }
+        return ssid.map(|ssid| (ssid, freq));
+    }
+    None
+}
+
+/// 读取内核中缓存的扫描结果
+pub async fn get_wifi_scan_results(ifindex: u32) -> Vec<WifiScanResult> {
+    let (connection, handle, _) = match wl_nl80211::new_connection() {
+        Ok(conn) => conn,
+        Err(_) => return vec![],
+    };
+    tokio::spawn(connection);
+
+    let mut scan_handle = handle.scan().dump(ifindex).execute().await;
+    let mut result = vec![];
+    while let Ok(Some(msg)) = scan_handle.try_next().await {
+        for nla in msg.payload.attributes {
+            let Nl80211Attr::Bss(infos) = nla else {
+                continue;
+            };
+            let mut bssid = None;
+            let mut scan = WifiScanResult {
+                bssid: Default::default(),
+                ssid: String::new(),
+                freq: 0,
+                signal: None,
+                privacy: false,
+            };
+            for info in infos {
+                match info {
+                    Nl80211BssInfo::Bssid(mac) => bssid = Some(mac),
+                    Nl80211BssInfo::Frequency(freq) => scan.freq = freq,
+                    Nl80211BssInfo::SignalMbm(mbm) => scan.signal = Some(mbm / 100),
+                    Nl80211BssInfo::Capability(cap) => {
+                        scan.privacy = cap.bits() & BSS_CAP_PRIVACY != 0
+                    }
+                    Nl80211BssInfo::InformationElements(elements) => {
+                        for element in elements {
+                            if let Nl80211Element::Ssid(ssid) = element {
+                                scan.ssid = ssid;
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            if let Some(bssid) = bssid {
+                scan.bssid = bssid.into();
+                result.push(scan);
+            }
+        }
+    }
+    result
+}
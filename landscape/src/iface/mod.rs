@@ -6,9 +6,11 @@ use landscape_common::dev::LandscapeInterface;
 pub use landscape_common::iface::{IfaceInfo, IfaceTopology, IfacesInfo, RawIfaceInfo};
 use landscape_common::service::controller::ConfigController;
 use landscape_common::{
-    config::iface::{IfaceCpuSoftBalance, IfaceZoneType, NetworkIfaceConfig, WifiMode},
-    error::LdResult,
-    iface::{AddController, BridgeCreate, ChangeZone},
+    config::iface::{
+        CreateDevType, IfaceCpuSoftBalance, IfaceZoneType, NetworkIfaceConfig, WifiMode,
+    },
+    error::{LdError, LdResult},
+    iface::{AddController, BridgeCreate, ChangeZone, VirtualDevCreate},
 };
 use landscape_database::iface::repository::NetIfaceRepository;
 use landscape_database::provider::LandscapeDBServiceProvider;
@@ -31,6 +33,18 @@ pub async fn get_iface_by_name(name: &str) -> Option<LandscapeInterface> {
     }
 }
 
+pub async fn get_iface_by_index(index: u32) -> Option<LandscapeInterface> {
+    let (connection, handle, _) = new_connection().unwrap();
+    tokio::spawn(connection);
+    let mut links = handle.link().get().match_index(index).execute();
+
+    if let Ok(Some(msg)) = links.try_next().await {
+        crate::dev::new_landscape_interface(msg)
+    } else {
+        None
+    }
+}
+
 /// interface manager
 #[derive(Clone)]
 pub struct IfaceManagerService {
@@ -121,11 +135,46 @@ impl IfaceManagerService {
     }
 
     pub async fn delete_bridge(&self, name: String) {
-        if crate::delete_bridge(name.clone()).await {
+        if crate::delete_link(name.clone()).await {
             self.delete(name).await;
         }
     }
 
+    pub async fn create_virtual_dev(
+        &self,
+        VirtualDevCreate { name, params }: VirtualDevCreate,
+    ) -> LdResult<()> {
+        if get_iface_by_name(&name).await.is_some() {
+            return Err(LdError::NetDevError(format!("interface {name} already exists")));
+        }
+        crate::create_virtual_dev(&name, &params).await.map_err(LdError::NetDevError)?;
+        crate::change_dev_status(&name, true).await;
+
+        let config = NetworkIfaceConfig::crate_virtual_dev(name, params);
+        self.set_iface_config(config).await;
+        Ok(())
+    }
+
+    /// 删除 VLAN / Bond / MACVLAN / WireGuard 设备, 返回是否找到对应配置
+    pub async fn delete_virtual_dev(&self, name: String) -> bool {
+        let Some(config) = self.get_iface_config(name.clone()).await else {
+            return false;
+        };
+        if !matches!(
+            config.create_dev_type,
+            CreateDevType::Vlan
+                | CreateDevType::Bond
+                | CreateDevType::Macvlan
+                | CreateDevType::Wireguard
+        ) {
+            return false;
+        }
+        // 设备可能已经不存在了, 依旧清理配置
+        crate::delete_link(name.clone()).await;
+        self.delete(name).await;
+        true
+    }
+
     pub async fn set_controller(
         &self,
         AddController {
@@ -258,6 +307,11 @@ impl IfaceManagerService {
     pub async fn get_all_wan_iface_config(&self) -> Vec<NetworkIfaceConfig> {
         self.store.get_all_wan_iface().await.unwrap_or_default()
     }
+
+    /// 按数据库中的网卡配置重新初始化设备, 用于配置导入后
+    pub async fn reapply_all(&self) {
+        crate::init_devs(self.store.list_all().await.unwrap_or_default()).await;
+    }
 }
 
 #[async_trait::async_trait]
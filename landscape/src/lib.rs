@@ -5,8 +5,10 @@ use std::{
 
 use futures::stream::TryStreamExt;
 use iface::get_iface_by_name;
-use landscape_common::config::iface::{CreateDevType, NetworkIfaceConfig, WifiMode};
-use landscape_common::dev::{DevState, LandscapeInterface};
+use landscape_common::config::iface::{
+    CreateDevParams, CreateDevType, NetworkIfaceConfig, WifiMode,
+};
+use landscape_common::dev::{DevState, DeviceKind, LandscapeInterface};
 use landscape_common::iface::dev_wifi::LandscapeWifiInterface;
 use netlink_packet_route::{address::AddressAttribute, AddressFamily};
 use rtnetlink::new_connection;
@@ -26,12 +28,15 @@ pub mod flow;
 pub mod icmp;
 pub mod iface;
 pub mod metric;
+pub mod netns;
 pub mod observer;
 pub mod pppoe_client;
 pub mod route;
 pub mod service;
+pub mod sqm;
 pub mod sys_service;
 pub mod wifi;
+pub mod wireguard;
 
 pub fn gen_default_config(
     interface_map: &HashMap<String, LandscapeInterface>,
@@ -116,16 +121,31 @@ pub async fn init_devs(network_config: Vec<NetworkIfaceConfig>) {
             {
                 current_iface
             } else {
-                // TODO 依据网卡类型创建网卡
-                match &ifconfig.create_dev_type {
-                    // 目前仅处理桥接设别的创建
-                    CreateDevType::Bridge => {
+                // 依据网卡类型创建网卡
+                match (&ifconfig.create_dev_type, &ifconfig.dev_params) {
+                    (CreateDevType::Bridge, _) => {
                         if let Err(e) =
                             handle.link().add().bridge(ifconfig.name.clone()).execute().await
                         {
                             tracing::error!("create bridge error: {e:?}");
                         }
                     }
+                    (CreateDevType::Wireguard, _) => {
+                        if let Err(e) =
+                            create_virtual_dev(&ifconfig.name, &CreateDevParams::Wireguard).await
+                        {
+                            tracing::error!("create {} error: {e}", ifconfig.name);
+                        }
+                    }
+                    (
+                        CreateDevType::Vlan | CreateDevType::Bond | CreateDevType::Macvlan,
+                        Some(params),
+                    ) => {
+                        // 父网卡可能还未创建, 失败后会进入下一轮
+                        if let Err(e) = create_virtual_dev(&ifconfig.name, params).await {
+                            tracing::error!("create {} error: {e}", ifconfig.name);
+                        }
+                    }
                     _ => (),
                 }
                 // 创建后重新进行获取, 如果获取不到 进行下一轮
@@ -133,7 +153,7 @@ pub async fn init_devs(network_config: Vec<NetworkIfaceConfig>) {
                     dev_tx.send((time + 1, ifconfig)).unwrap();
                     continue;
                 };
-                // 启动刚刚创建的 bridge
+                // 启动刚刚创建的设备
                 if let Ok(_) = handle.link().set(current_iface.index).up().execute().await {
                     current_iface.dev_status = DevState::Up;
                 }
@@ -143,6 +163,10 @@ pub async fn init_devs(network_config: Vec<NetworkIfaceConfig>) {
             // 先检查是否有 master 且 master 是否已经初始化
             if let Some(master_ifac_name) = ifconfig.controller_name.as_ref() {
                 if let Some(master_iface) = get_iface_by_name(master_ifac_name).await {
+                    // bond 成员需要先处于 down 状态
+                    if matches!(master_iface.dev_kind, DeviceKind::Bond) {
+                        let _ = handle.link().set(current_iface.index).down().execute().await;
+                    }
                     let create_result = handle
                         .link()
                         .set(current_iface.index)
@@ -312,7 +336,71 @@ pub async fn create_bridge(name: String) -> bool {
     create_result.is_ok()
 }
 
-pub async fn delete_bridge(name: String) -> bool {
+/// 创建 VLAN / Bond / MACVLAN / WireGuard 设备
+pub async fn create_virtual_dev(name: &str, params: &CreateDevParams) -> Result<(), String> {
+    match params {
+        CreateDevParams::Vlan { parent, vlan_id } => {
+            let Some(parent_dev) = get_iface_by_name(parent).await else {
+                return Err(format!("parent interface {parent} not found"));
+            };
+            let (connection, handle, _) = new_connection().unwrap();
+            tokio::spawn(connection);
+            handle
+                .link()
+                .add()
+                .vlan(name.to_string(), parent_dev.index, *vlan_id)
+                .execute()
+                .await
+                .map_err(|e| e.to_string())
+        }
+        CreateDevParams::Macvlan { parent, mode } => {
+            let Some(parent_dev) = get_iface_by_name(parent).await else {
+                return Err(format!("parent interface {parent} not found"));
+            };
+            let (connection, handle, _) = new_connection().unwrap();
+            tokio::spawn(connection);
+            handle
+                .link()
+                .add()
+                .macvlan(name.to_string(), parent_dev.index, mode.kernel_value())
+                .execute()
+                .await
+                .map_err(|e| e.to_string())
+        }
+        CreateDevParams::Wireguard => {
+            let (connection, handle, _) = new_connection().unwrap();
+            tokio::spawn(connection);
+            handle
+                .link()
+                .add()
+                .wireguard(name.to_string())
+                .execute()
+                .await
+                .map_err(|e| e.to_string())
+        }
+        CreateDevParams::Bond { mode, miimon, xmit_hash_policy } => {
+            let miimon = miimon.to_string();
+            let mut args =
+                vec!["link", "add", name, "type", "bond", "mode", mode.kernel_name(), "miimon"];
+            args.push(&miimon);
+            if let Some(policy) = xmit_hash_policy {
+                args.extend(["xmit_hash_policy", policy.kernel_name()]);
+            }
+            let output = tokio::process::Command::new("ip")
+                .args(&args)
+                .output()
+                .await
+                .map_err(|e| e.to_string())?;
+            if output.status.success() {
+                Ok(())
+            } else {
+                Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+            }
+        }
+    }
+}
+
+pub async fn delete_link(name: String) -> bool {
     let (connection, handle, _) = new_connection().unwrap();
     tokio::spawn(connection);
     let mut result = handle.link().get().match_name(name).execute();
@@ -330,7 +418,7 @@ pub async fn delete_bridge(name: String) -> bool {
                 }
             },
             Err(e) => {
-                tracing::error!("delete bridge error: {e:?}");
+                tracing::error!("delete link error: {e:?}");
                 return false;
             }
         }
@@ -347,8 +435,22 @@ pub async fn set_controller(
     if let Some(dev) = get_iface_by_name(link_name).await {
         let (connection, handle, _) = new_connection().unwrap();
         tokio::spawn(connection);
+        // bond 成员需要先处于 down 状态
+        let is_bond_member = if let Some(master_index) = master_index {
+            iface::get_iface_by_index(master_index)
+                .await
+                .is_some_and(|master| matches!(master.dev_kind, DeviceKind::Bond))
+        } else {
+            false
+        };
+        if is_bond_member {
+            let _ = handle.link().set(dev.index).down().execute().await;
+        }
         let create_result =
             handle.link().set(dev.index).controller(master_index.unwrap_or(0)).execute().await;
+        if is_bond_member {
+            let _ = handle.link().set(dev.index).up().execute().await;
+        }
         if create_result.is_ok() {
             Some(dev)
         } else {
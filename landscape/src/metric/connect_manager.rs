@@ -5,9 +5,13 @@ use tokio::sync::{mpsc, RwLock};
 use landscape_common::event::ConnectMessage;
 use landscape_common::metric::connect::{
     ConnectGlobalStats, ConnectHistoryQueryParams, ConnectHistoryStatus, ConnectKey,
-    ConnectMetricPoint, ConnectRealtimeStatus, IpRealtimeStat, MetricResolution,
+    ConnectMetricPoint, ConnectRealtimeStatus, DomainHistoryStat, IpRealtimeStat, MetricResolution,
 };
 
+use crate::metric::dns_correlation::DomainCorrelator;
+use crate::metric::flow_export::FlowExporter;
+use crate::metric::prometheus::PrometheusCollector;
+use crate::metric::traffic_manager::TrafficMetricManager;
 use crate::metric::MetricStore;
 
 #[derive(Clone)]
@@ -18,14 +22,24 @@ pub struct ConnectMetricManager {
 }
 
 impl ConnectMetricManager {
-    pub fn with_store(metric_store: MetricStore) -> Self {
+    pub fn with_store(
+        metric_store: MetricStore,
+        prometheus: PrometheusCollector,
+        flow_exporter: FlowExporter,
+        domain_correlator: DomainCorrelator,
+        traffic_metric: TrafficMetricManager,
+    ) -> Self {
         let (msg_channel, mut message_rx) = mpsc::channel(1024);
 
         let metric_store_clone = metric_store.clone();
 
         tokio::spawn(async move {
             while let Some(msg) = message_rx.recv().await {
-                let ConnectMessage::Metric(metric) = msg;
+                let ConnectMessage::Metric(mut metric) = msg;
+                domain_correlator.fill_domain(&mut metric);
+                prometheus.record_connect(&metric);
+                flow_exporter.record(&metric);
+                traffic_metric.record_connect(&metric);
                 metric_store_clone.insert_metric(metric).await;
             }
         });
@@ -107,4 +121,11 @@ impl ConnectMetricManager {
     ) -> Vec<landscape_common::metric::connect::IpHistoryStat> {
         self.metric_store.history_dst_ip_stats(params).await
     }
+
+    pub async fn history_domain_stats(
+        &self,
+        params: ConnectHistoryQueryParams,
+    ) -> Vec<DomainHistoryStat> {
+        self.metric_store.history_domain_stats(params).await
+    }
 }
@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+
+use landscape_common::metric::connect::ConnectMetric;
+use landscape_common::metric::dns::DnsMetric;
+
+/// 解析记录保留时间, 超过后不再用于关联连接
+const DOMAIN_RECORD_TIMEOUT_MS: u64 = 60 * 60 * 1000;
+/// 记录数量上限, 超过后清理过期记录
+const DOMAIN_RECORD_MAX_SIZE: usize = 65536;
+/// 清理过期记录后仍超出上限时, 淘汰最旧的记录直到该数量, 避免每次写入都要排序
+const DOMAIN_RECORD_EVICT_TO: usize = DOMAIN_RECORD_MAX_SIZE / 4 * 3;
+
+struct DomainRecord {
+    domain: String,
+    update_at: u64,
+}
+
+/// 根据 DNS 应答记录 (客户端, 应答 IP) -> 域名, 为连接补充目标域名
+#[derive(Clone, Default)]
+pub struct DomainCorrelator {
+    records: Arc<Mutex<HashMap<(IpAddr, IpAddr), DomainRecord>>>,
+}
+
+impl DomainCorrelator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_dns(&self, metric: &DnsMetric) {
+        let domain = metric.domain.strip_suffix('.').unwrap_or(&metric.domain);
+        if domain.is_empty() {
+            return;
+        }
+        let client = metric.src_ip.to_canonical();
+
+        let mut records = self.records.lock().unwrap();
+        for answer in metric.answers.iter() {
+            let Some(ip) = parse_answer_ip(answer) else {
+                continue;
+            };
+            records.insert(
+                (client, ip),
+                DomainRecord {
+                    domain: domain.to_string(),
+                    update_at: metric.report_time,
+                },
+            );
+        }
+
+        if records.len() > DOMAIN_RECORD_MAX_SIZE {
+            let cutoff = metric.report_time.saturating_sub(DOMAIN_RECORD_TIMEOUT_MS);
+            records.retain(|_, record| record.update_at >= cutoff);
+        }
+        if records.len() > DOMAIN_RECORD_MAX_SIZE {
+            let evict = records.len() - DOMAIN_RECORD_EVICT_TO;
+            let mut entries: Vec<_> =
+                records.iter().map(|(key, record)| (record.update_at, *key)).collect();
+            entries.select_nth_unstable_by_key(evict - 1, |(update_at, _)| *update_at);
+            for (_, key) in entries.iter().take(evict) {
+                records.remove(key);
+            }
+        }
+    }
+
+    /// 查找同一客户端最近一次解析到该目标 IP 的域名
+    pub fn fill_domain(&self, metric: &mut ConnectMetric) {
+        if metric.domain.is_some() {
+            return;
+        }
+        let key = (metric.src_ip.to_canonical(), metric.dst_ip.to_canonical());
+        let records = self.records.lock().unwrap();
+        if let Some(record) = records.get(&key) {
+            if metric.report_time.saturating_sub(record.update_at) < DOMAIN_RECORD_TIMEOUT_MS {
+                metric.domain = Some(record.domain.clone());
+            }
+        }
+    }
+}
+
+/// 应答记录格式为 `name ttl class type rdata`, 仅取 A / AAAA 记录
+fn parse_answer_ip(answer: &str) -> Option<IpAddr> {
+    let mut parts = answer.split_whitespace();
+    let record_type = parts.nth(3)?;
+    if record_type != "A" && record_type != "AAAA" {
+        return None;
+    }
+    parts.next()?.parse::<IpAddr>().ok().map(|ip| ip.to_canonical())
+}
+
+#[cfg(test)]
+mod tests {
+    use landscape_common::metric::connect::{ConnectKey, ConnectStatusType};
+    use landscape_common::metric::dns::DnsResultStatus;
+
+    use super::*;
+
+    fn dns(src_ip: &str, domain: &str, answers: &[&str], report_time: u64) -> DnsMetric {
+        DnsMetric {
+            flow_id: 0,
+            domain: domain.to_string(),
+            query_type: "A".to_string(),
+            response_code: "NoError".to_string(),
+            status: DnsResultStatus::Normal,
+            report_time,
+            duration_ms: 1,
+            src_ip: src_ip.parse().unwrap(),
+            answers: answers.iter().map(|answer| answer.to_string()).collect(),
+            rule: None,
+            upstream: None,
+        }
+    }
+
+    fn connect(src_ip: &str, dst_ip: &str, report_time: u64) -> ConnectMetric {
+        ConnectMetric {
+            key: ConnectKey { create_time: 0, cpu_id: 0 },
+            src_ip: src_ip.parse().unwrap(),
+            dst_ip: dst_ip.parse().unwrap(),
+            src_port: 50000,
+            dst_port: 443,
+            l4_proto: 6,
+            l3_proto: 0,
+            flow_id: 0,
+            trace_id: 0,
+            gress: 0,
+            ifindex: 0,
+            domain: None,
+            report_time,
+            create_time_ms: report_time,
+            ingress_bytes: 0,
+            ingress_packets: 0,
+            egress_bytes: 0,
+            egress_packets: 0,
+            status: ConnectStatusType::Active,
+        }
+    }
+
+    #[test]
+    fn test_parse_answer_ip() {
+        assert_eq!(
+            parse_answer_ip("example.com. 300 IN A 93.184.216.34"),
+            Some("93.184.216.34".parse().unwrap())
+        );
+        assert_eq!(
+            parse_answer_ip("example.com. 300 IN AAAA ::ffff:1.2.3.4"),
+            Some("1.2.3.4".parse().unwrap())
+        );
+        assert_eq!(parse_answer_ip("www.example.com. 300 IN CNAME example.com."), None);
+        assert_eq!(parse_answer_ip("example.com. 300 IN A"), None);
+    }
+
+    #[test]
+    fn test_fill_domain() {
+        let correlator = DomainCorrelator::new();
+        correlator.record_dns(&dns(
+            "192.168.1.10",
+            "example.com.",
+            &["www.example.com. 300 IN CNAME example.com.", "example.com. 300 IN A 1.2.3.4"],
+            1000,
+        ));
+
+        let mut metric = connect("192.168.1.10", "1.2.3.4", 2000);
+        correlator.fill_domain(&mut metric);
+        assert_eq!(metric.domain.as_deref(), Some("example.com"));
+
+        // 其他客户端的解析结果不参与关联
+        let mut metric = connect("192.168.1.11", "1.2.3.4", 2000);
+        correlator.fill_domain(&mut metric);
+        assert_eq!(metric.domain, None);
+
+        let mut metric = connect("192.168.1.10", "1.2.3.4", 1000 + DOMAIN_RECORD_TIMEOUT_MS);
+        correlator.fill_domain(&mut metric);
+        assert_eq!(metric.domain, None);
+    }
+
+    #[test]
+    fn test_evict_oldest_records() {
+        let correlator = DomainCorrelator::new();
+        let answers: Vec<String> = (0..DOMAIN_RECORD_MAX_SIZE as u32)
+            .map(|i| format!("old.com. 300 IN A {}", std::net::Ipv4Addr::from(0x0a00_0000 + i)))
+            .collect();
+        let answers: Vec<&str> = answers.iter().map(String::as_str).collect();
+        correlator.record_dns(&dns("192.168.1.10", "old.com.", &answers, 1000));
+        assert_eq!(correlator.records.lock().unwrap().len(), DOMAIN_RECORD_MAX_SIZE);
+
+        // 记录均未过期, 超出上限后淘汰最旧的记录
+        correlator.record_dns(&dns(
+            "192.168.1.10",
+            "new.com.",
+            &["new.com. 300 IN A 1.2.3.4"],
+            2000,
+        ));
+        assert_eq!(correlator.records.lock().unwrap().len(), DOMAIN_RECORD_EVICT_TO);
+
+        let mut metric = connect("192.168.1.10", "1.2.3.4", 3000);
+        correlator.fill_domain(&mut metric);
+        assert_eq!(metric.domain.as_deref(), Some("new.com"));
+    }
+}
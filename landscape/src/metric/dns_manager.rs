@@ -1,3 +1,5 @@
+use crate::metric::dns_correlation::DomainCorrelator;
+use crate::metric::prometheus::PrometheusCollector;
 use crate::metric::MetricStore;
 use landscape_common::event::DnsMetricMessage;
 use landscape_common::metric::dns::{
@@ -13,7 +15,11 @@ pub struct DnsMetricManager {
 }
 
 impl DnsMetricManager {
-    pub fn with_store(metric_store: MetricStore) -> Self {
+    pub fn with_store(
+        metric_store: MetricStore,
+        prometheus: PrometheusCollector,
+        domain_correlator: DomainCorrelator,
+    ) -> Self {
         let (msg_tx, mut msg_rx) = mpsc::channel::<DnsMetricMessage>(1024);
         let store_clone = metric_store.clone();
 
@@ -21,6 +27,8 @@ impl DnsMetricManager {
             while let Some(msg) = msg_rx.recv().await {
                 match msg {
                     DnsMetricMessage::Metric(metric) => {
+                        prometheus.record_dns(&metric);
+                        domain_correlator.record_dns(&metric);
                         store_clone.insert_dns_metric(metric).await;
                     }
                 }
@@ -1,22 +1,23 @@
 use duckdb::{params, Connection};
 use landscape_common::metric::connect::{
     ConnectGlobalStats, ConnectHistoryQueryParams, ConnectHistoryStatus, ConnectKey, ConnectMetric,
-    ConnectMetricPoint, ConnectSortKey, MetricResolution, SortOrder,
+    ConnectMetricPoint, ConnectSortKey, DomainHistoryStat, MetricResolution, SortOrder,
 };
 use std::path::PathBuf;
 
 pub const SUMMARY_INSERT_SQL: &str = "
     INSERT INTO conn_summaries (
         create_time, cpu_id, src_ip, dst_ip, src_port, dst_port, l4_proto, l3_proto, flow_id, trace_id,
-        last_report_time, total_ingress_bytes, total_egress_bytes, total_ingress_pkts, total_egress_pkts, status, create_time_ms, gress
-    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)
+        last_report_time, total_ingress_bytes, total_egress_bytes, total_ingress_pkts, total_egress_pkts, status, create_time_ms, gress, domain
+    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19)
     ON CONFLICT (create_time, cpu_id) DO UPDATE SET
         last_report_time = GREATEST(conn_summaries.last_report_time, EXCLUDED.last_report_time),
         total_ingress_bytes = GREATEST(conn_summaries.total_ingress_bytes, EXCLUDED.total_ingress_bytes),
         total_egress_bytes = GREATEST(conn_summaries.total_egress_bytes, EXCLUDED.total_egress_bytes),
         total_ingress_pkts = GREATEST(conn_summaries.total_ingress_pkts, EXCLUDED.total_ingress_pkts),
         total_egress_pkts = GREATEST(conn_summaries.total_egress_pkts, EXCLUDED.total_egress_pkts),
-        status = CASE WHEN EXCLUDED.last_report_time >= conn_summaries.last_report_time THEN EXCLUDED.status ELSE conn_summaries.status END
+        status = CASE WHEN EXCLUDED.last_report_time >= conn_summaries.last_report_time THEN EXCLUDED.status ELSE conn_summaries.status END,
+        domain = COALESCE(conn_summaries.domain, EXCLUDED.domain)
 ";
 
 pub fn create_summaries_table(conn: &Connection, schema: &str) {
@@ -42,11 +43,13 @@ pub fn create_summaries_table(conn: &Connection, schema: &str) {
             status INTEGER,
             create_time_ms UBIGINT,
             gress INTEGER,
+            domain VARCHAR,
             PRIMARY KEY (create_time, cpu_id)
         );
         CREATE INDEX IF NOT EXISTS idx_conn_summaries_time ON {}conn_summaries (last_report_time);
+        CREATE INDEX IF NOT EXISTS idx_conn_summaries_domain ON {}conn_summaries (domain);
     ",
-        prefix, prefix
+        prefix, prefix, prefix
     );
 
     conn.execute_batch(&sql).expect("Failed to create summaries table");
@@ -255,6 +258,12 @@ pub fn query_historical_summaries_complex(
     if let Some(g) = params.gress {
         where_clauses.push(format!("gress = {}", g));
     }
+    if let Some(domain) = params.domain {
+        if !domain.is_empty() {
+            where_clauses.push("domain LIKE ?".to_string());
+            sql_params.push(Box::new(format!("%{}%", domain)));
+        }
+    }
 
     let where_stmt = if where_clauses.is_empty() {
         String::new()
@@ -270,6 +279,7 @@ pub fn query_historical_summaries_complex(
         ConnectSortKey::Duration => {
             "(CAST(last_report_time AS BIGINT) - CAST(create_time_ms AS BIGINT))"
         }
+        ConnectSortKey::Domain => "domain",
     };
     let sort_order_str = match params.sort_order.unwrap_or_default() {
         SortOrder::Asc => "ASC",
@@ -282,7 +292,7 @@ pub fn query_historical_summaries_complex(
     let stmt_str = format!("
         SELECT
             create_time, cpu_id, src_ip, dst_ip, src_port, dst_port, l4_proto, l3_proto, flow_id, trace_id,
-            total_ingress_bytes, total_egress_bytes, total_ingress_pkts, total_egress_pkts, last_report_time, status, create_time_ms, gress
+            total_ingress_bytes, total_egress_bytes, total_ingress_pkts, total_egress_pkts, last_report_time, status, create_time_ms, gress, domain
         FROM {}
         {}
         ORDER BY {} {}
@@ -322,6 +332,7 @@ pub fn query_historical_summaries_complex(
             status: row.get::<_, i64>(15)? as u8,
             create_time_ms,
             gress: row.get::<_, Option<i64>>(17)?.unwrap_or(0) as u8,
+            domain: row.get(18)?,
         })
     });
 
@@ -474,7 +485,7 @@ pub fn collect_and_cleanup_old_metrics(
     let stmt = "
         SELECT
             s.create_time, s.cpu_id, s.src_ip, s.dst_ip, s.src_port, s.dst_port, s.l4_proto, s.l3_proto, s.flow_id, s.trace_id,
-            m.report_time, m.ingress_bytes, m.ingress_packets, m.egress_bytes, m.egress_packets, m.status, s.create_time_ms, s.gress
+            m.report_time, m.ingress_bytes, m.ingress_packets, m.egress_bytes, m.egress_packets, m.status, s.create_time_ms, s.gress, s.domain
         FROM conn_metrics m
         JOIN conn_summaries s ON m.create_time = s.create_time AND m.cpu_id = s.cpu_id
         WHERE m.report_time < ?1
@@ -505,6 +516,8 @@ pub fn collect_and_cleanup_old_metrics(
             flow_id: row.get::<_, i64>(8)? as u8,
             trace_id: row.get::<_, i64>(9)? as u8,
             gress: row.get::<_, Option<i64>>(17)?.unwrap_or(0) as u8,
+            ifindex: 0,
+            domain: row.get(18)?,
             report_time: row.get(10)?,
             create_time_ms: row.get(16)?,
             ingress_bytes: row.get(11)?,
@@ -607,6 +620,12 @@ pub fn query_connection_ip_history(
             sql_params.push(Box::new(format!("%{}%", ip)));
         }
     }
+    if let Some(domain) = params.domain {
+        if !domain.is_empty() {
+            where_clauses.push("domain LIKE ?".to_string());
+            sql_params.push(Box::new(format!("%{}%", domain)));
+        }
+    }
 
     let where_stmt = if where_clauses.is_empty() {
         String::new()
@@ -670,3 +689,91 @@ pub fn query_connection_ip_history(
         }
     }
 }
+
+pub fn query_connection_domain_history(
+    conn: &Connection,
+    params: ConnectHistoryQueryParams,
+) -> Vec<DomainHistoryStat> {
+    let mut where_clauses = vec!["domain IS NOT NULL".to_string()];
+    let mut sql_params: Vec<Box<dyn duckdb::ToSql>> = Vec::new();
+    if let Some(start) = params.start_time {
+        where_clauses.push(format!("last_report_time >= {}", start));
+    }
+    if let Some(end) = params.end_time {
+        where_clauses.push(format!("last_report_time <= {}", end));
+    }
+    if let Some(p) = params.flow_id {
+        where_clauses.push(format!("flow_id = {}", p));
+    }
+    if let Some(ip) = params.src_ip {
+        if !ip.is_empty() {
+            where_clauses.push("src_ip LIKE ?".to_string());
+            sql_params.push(Box::new(format!("%{}%", ip)));
+        }
+    }
+    if let Some(domain) = params.domain {
+        if !domain.is_empty() {
+            where_clauses.push("domain LIKE ?".to_string());
+            sql_params.push(Box::new(format!("%{}%", domain)));
+        }
+    }
+
+    let sort_col = match params.sort_key.unwrap_or(ConnectSortKey::Ingress) {
+        ConnectSortKey::Egress => "3",
+        ConnectSortKey::Domain => "1",
+        ConnectSortKey::Time => "6",
+        _ => "2",
+    };
+    let sort_order_str = match params.sort_order.unwrap_or(SortOrder::Desc) {
+        SortOrder::Asc => "ASC",
+        SortOrder::Desc => "DESC",
+    };
+    let limit_val = params.limit.unwrap_or(10);
+
+    let stmt_str = format!(
+        "
+        SELECT
+            domain,
+            SUM(total_ingress_bytes), SUM(total_egress_bytes),
+            SUM(total_ingress_pkts), SUM(total_egress_pkts),
+            COUNT(*)
+        FROM conn_summaries
+        WHERE {}
+        GROUP BY 1
+        ORDER BY {} {}
+        LIMIT {}
+    ",
+        where_clauses.join(" AND "),
+        sort_col,
+        sort_order_str,
+        limit_val
+    );
+
+    let mut stmt = match conn.prepare(&stmt_str) {
+        Ok(s) => s,
+        Err(e) => {
+            tracing::error!("Failed to prepare domain history SQL: {}, error: {}", stmt_str, e);
+            return Vec::new();
+        }
+    };
+
+    let param_refs: Vec<&dyn duckdb::ToSql> = sql_params.iter().map(|p| p.as_ref()).collect();
+    let rows = stmt.query_map(&param_refs[..], |row| {
+        Ok(DomainHistoryStat {
+            domain: row.get(0)?,
+            total_ingress_bytes: row.get::<_, Option<i64>>(1)?.unwrap_or(0) as u64,
+            total_egress_bytes: row.get::<_, Option<i64>>(2)?.unwrap_or(0) as u64,
+            total_ingress_pkts: row.get::<_, Option<i64>>(3)?.unwrap_or(0) as u64,
+            total_egress_pkts: row.get::<_, Option<i64>>(4)?.unwrap_or(0) as u64,
+            connect_count: row.get::<_, i64>(5)? as u32,
+        })
+    });
+
+    match rows {
+        Ok(r) => r.filter_map(Result::ok).collect(),
+        Err(e) => {
+            tracing::error!("Failed to execute domain history query: {}", e);
+            Vec::new()
+        }
+    }
+}
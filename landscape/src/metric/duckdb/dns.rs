@@ -171,6 +171,8 @@ pub fn query_dns_history(
                 &row.get::<_, String>(8).unwrap_or_else(|_| "\"normal\"".to_string()),
             )
             .unwrap_or_default(),
+            rule: None,
+            upstream: None,
         })
     });
 
@@ -3,12 +3,16 @@ use duckdb::{params, Appender, DuckdbConnectionManager};
 
 use landscape_common::metric::connect::{
     ConnectGlobalStats, ConnectHistoryQueryParams, ConnectHistoryStatus, ConnectKey, ConnectMetric,
-    ConnectMetricPoint, ConnectRealtimeStatus, ConnectStatusType, MetricResolution,
+    ConnectMetricPoint, ConnectRealtimeStatus, ConnectStatusType, DomainHistoryStat,
+    MetricResolution,
 };
 use landscape_common::metric::dns::{
     DnsHistoryQueryParams, DnsHistoryResponse, DnsLightweightSummaryResponse, DnsMetric,
     DnsSummaryQueryParams, DnsSummaryResponse,
 };
+use landscape_common::metric::traffic::{
+    TrafficHistoryQueryParams, TrafficPoint, TrafficSample, TrafficTargetKind, TrafficTotal,
+};
 use r2d2::{self, PooledConnection};
 use std::net::IpAddr;
 use std::path::PathBuf;
@@ -16,21 +20,11 @@ use std::sync::Arc;
 use std::thread;
 use tokio::sync::{mpsc, oneshot};
 
-fn clean_ip_string(ip: &IpAddr) -> String {
-    match ip {
-        IpAddr::V6(v6) => {
-            if let Some(v4) = v6.to_ipv4_mapped() {
-                v4.to_string()
-            } else {
-                v6.to_string()
-            }
-        }
-        IpAddr::V4(v4) => v4.to_string(),
-    }
-}
+use super::clean_ip_string;
 
 pub mod connect;
 pub mod dns;
+pub mod traffic;
 
 use landscape_common::config::MetricRuntimeConfig;
 
@@ -44,6 +38,7 @@ pub enum DBMessage {
     // Write Operations
     InsertMetric(ConnectMetric),
     InsertDnsMetric(DnsMetric),
+    InsertTrafficSamples(Vec<TrafficSample>),
 
     // Command Operations (Maintenance/Cleanup)
     CollectAndCleanupOldMetrics {
@@ -140,6 +135,7 @@ pub fn start_db_thread(
                                             status_val as i64,
                                             s.create_time_ms as i64,
                                             s.gress as i64,
+                                            s.domain,
                                         ]) {
                                             tracing::error!("Sync summary failed for {}:{}: {}", s.key.create_time, s.key.cpu_id, e);
                                         } else {
@@ -197,6 +193,12 @@ pub fn start_db_thread(
                     if let Ok(conn_disk) = disk_pool.get() {
                         // Rollup raw metrics into 1m/1h/1d buckets
                         let _ = connect::perform_inner_db_rollup(&conn_disk);
+                        if let Err(e) = traffic::perform_traffic_rollup(&conn_disk, now_ms) {
+                            tracing::error!("Failed to rollup traffic metrics: {}", e);
+                        }
+                        traffic::cleanup_old_traffic_metrics(
+                            &conn_disk, cutoff_raw, cutoff_1m, cutoff_1h, cutoff_1d,
+                        );
                         // Use a fresh connection for maintenance instead of conn_disk_writer
                         if let Ok(conn_maint) = disk_pool.get() {
                             let _ = connect::collect_and_cleanup_old_metrics(
@@ -257,6 +259,9 @@ pub fn start_db_thread(
                                                     if metric.status != ConnectStatusType::Unknow {
                                                         e.status.status = metric.status.clone();
                                                     }
+                                                    if e.status.domain.is_none() {
+                                                        e.status.domain = metric.domain.clone();
+                                                    }
                                                     // Ensure counters only increase
                                                     e.last_ingress_bytes = e.last_ingress_bytes.max(metric.ingress_bytes);
                                                     e.last_egress_bytes = e.last_egress_bytes.max(metric.egress_bytes);
@@ -275,6 +280,7 @@ pub fn start_db_thread(
                                                     flow_id: metric.flow_id,
                                                     trace_id: metric.trace_id,
                                                     gress: metric.gress,
+                                                    domain: metric.domain.clone(),
                                                     create_time_ms: metric.create_time_ms,
                                                     ingress_bps: 0,
                                                     egress_bps: 0,
@@ -307,6 +313,14 @@ pub fn start_db_thread(
                                             }
                                         }
 
+                                        DBMessage::InsertTrafficSamples(samples) => {
+                                            if let Ok(mut conn) = disk_pool.get() {
+                                                if let Err(e) = traffic::insert_traffic_samples(&mut conn, &samples) {
+                                                    tracing::error!("Failed to insert traffic samples: {}", e);
+                                                }
+                                            }
+                                        }
+
                                         DBMessage::CollectAndCleanupOldMetrics {
                                             cutoff_raw,
                                             cutoff_1m,
@@ -408,6 +422,8 @@ impl DuckMetricStore {
         connect::create_live_tables(&conn_disk)
             .expect("Failed to create raw metric tables on disk");
         dns::create_dns_table(&conn_disk, "").expect("Failed to create DNS metrics tables on disk");
+        traffic::create_traffic_tables(&conn_disk)
+            .expect("Failed to create traffic metrics tables on disk");
 
         let thread_disk_pool = disk_pool.clone();
         let conn_dns = disk_pool.get().expect("Failed to get DNS writer connection from disk pool");
@@ -553,6 +569,19 @@ impl DuckMetricStore {
         .unwrap_or_default()
     }
 
+    pub async fn history_domain_stats(
+        &self,
+        params: ConnectHistoryQueryParams,
+    ) -> Vec<DomainHistoryStat> {
+        let store = self.clone();
+        tokio::task::spawn_blocking(move || {
+            let conn = store.get_disk_conn();
+            connect::query_connection_domain_history(&conn, params)
+        })
+        .await
+        .unwrap_or_default()
+    }
+
     pub async fn get_global_stats(&self) -> ConnectGlobalStats {
         let store = self.clone();
         tokio::task::spawn_blocking(move || {
@@ -563,6 +592,40 @@ impl DuckMetricStore {
         .unwrap_or_default()
     }
 
+    pub async fn insert_traffic_samples(&self, samples: Vec<TrafficSample>) {
+        let _ = self.tx.send(DBMessage::InsertTrafficSamples(samples)).await;
+    }
+
+    pub async fn query_traffic_history(
+        &self,
+        params: TrafficHistoryQueryParams,
+        resolution: MetricResolution,
+    ) -> Vec<TrafficPoint> {
+        let store = self.clone();
+        tokio::task::spawn_blocking(move || {
+            let conn = store.get_disk_conn();
+            traffic::query_traffic_history(&conn, &params, resolution)
+        })
+        .await
+        .unwrap_or_default()
+    }
+
+    pub async fn query_traffic_totals(
+        &self,
+        kind: TrafficTargetKind,
+        start_time: u64,
+        end_time: u64,
+        resolution: MetricResolution,
+    ) -> Vec<TrafficTotal> {
+        let store = self.clone();
+        tokio::task::spawn_blocking(move || {
+            let conn = store.get_disk_conn();
+            traffic::query_traffic_totals(&conn, kind, start_time, end_time, resolution)
+        })
+        .await
+        .unwrap_or_default()
+    }
+
     pub async fn insert_dns_metric(&self, mut metric: DnsMetric) {
         if metric.domain.ends_with('.') && metric.domain.len() > 1 {
             metric.domain.pop();
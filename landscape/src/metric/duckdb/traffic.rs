@@ -0,0 +1,317 @@
+use duckdb::{params, Connection};
+use landscape_common::metric::connect::MetricResolution;
+use landscape_common::metric::traffic::{
+    TrafficHistoryQueryParams, TrafficPoint, TrafficSample, TrafficTargetKind, TrafficTotal,
+};
+
+pub const TRAFFIC_INSERT_SQL: &str = "
+    INSERT INTO traffic_metrics (
+        kind, target, mac, report_time, rx_bytes, tx_bytes, rx_packets, tx_packets
+    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+";
+
+pub fn create_traffic_tables(conn: &Connection) -> duckdb::Result<()> {
+    let mut sql = String::from(
+        "
+        CREATE TABLE IF NOT EXISTS traffic_metrics (
+            kind VARCHAR,
+            target VARCHAR,
+            mac VARCHAR,
+            report_time BIGINT,
+            rx_bytes BIGINT,
+            tx_bytes BIGINT,
+            rx_packets BIGINT,
+            tx_packets BIGINT
+        );
+        CREATE INDEX IF NOT EXISTS idx_traffic_metrics_time ON traffic_metrics (report_time);
+    ",
+    );
+    for table in ["traffic_metrics_1m", "traffic_metrics_1h", "traffic_metrics_1d"] {
+        sql.push_str(&format!(
+            "
+        CREATE TABLE IF NOT EXISTS {table} (
+            kind VARCHAR,
+            target VARCHAR,
+            mac VARCHAR,
+            report_time BIGINT,
+            rx_bytes BIGINT,
+            tx_bytes BIGINT,
+            rx_packets BIGINT,
+            tx_packets BIGINT,
+            PRIMARY KEY (kind, target, report_time)
+        );
+        CREATE INDEX IF NOT EXISTS idx_{table}_time ON {table} (report_time);
+        "
+        ));
+    }
+    conn.execute_batch(&sql)
+}
+
+fn table_name(resolution: MetricResolution) -> &'static str {
+    match resolution {
+        MetricResolution::Second => "traffic_metrics",
+        MetricResolution::Minute => "traffic_metrics_1m",
+        MetricResolution::Hour => "traffic_metrics_1h",
+        MetricResolution::Day => "traffic_metrics_1d",
+    }
+}
+
+pub fn insert_traffic_samples(
+    conn: &mut Connection,
+    samples: &[TrafficSample],
+) -> duckdb::Result<()> {
+    let tx = conn.transaction()?;
+    {
+        let mut stmt = tx.prepare_cached(TRAFFIC_INSERT_SQL)?;
+        for sample in samples {
+            stmt.execute(params![
+                sample.kind.as_str(),
+                sample.target,
+                sample.mac,
+                sample.report_time as i64,
+                sample.rx_bytes as i64,
+                sample.tx_bytes as i64,
+                sample.rx_packets as i64,
+                sample.tx_packets as i64,
+            ])?;
+        }
+    }
+    tx.commit()
+}
+
+/// 采样值为增量, 汇总时直接求和, 重复汇总同一时间桶时取较大值
+pub fn perform_traffic_rollup(conn: &Connection, now_ms: u64) -> duckdb::Result<()> {
+    let steps = [
+        ("traffic_metrics", "traffic_metrics_1m", 60000_u64, 600000_u64),
+        ("traffic_metrics_1m", "traffic_metrics_1h", 3600000, 7200000),
+        ("traffic_metrics_1h", "traffic_metrics_1d", 86400000, 172800000),
+    ];
+    for (from, to, bucket, window) in steps {
+        let sql = format!(
+            "
+        INSERT INTO {to} (
+            kind, target, mac, report_time, rx_bytes, tx_bytes, rx_packets, tx_packets
+        )
+        SELECT
+            kind, target, MAX(mac), (report_time // {bucket}) * {bucket} as bucket_time,
+            SUM(rx_bytes), SUM(tx_bytes), SUM(rx_packets), SUM(tx_packets)
+        FROM {from}
+        WHERE report_time >= ?1
+        GROUP BY kind, target, bucket_time
+        ON CONFLICT (kind, target, report_time) DO UPDATE SET
+            mac = COALESCE(EXCLUDED.mac, {to}.mac),
+            rx_bytes = GREATEST({to}.rx_bytes, EXCLUDED.rx_bytes),
+            tx_bytes = GREATEST({to}.tx_bytes, EXCLUDED.tx_bytes),
+            rx_packets = GREATEST({to}.rx_packets, EXCLUDED.rx_packets),
+            tx_packets = GREATEST({to}.tx_packets, EXCLUDED.tx_packets)
+        "
+        );
+        let start = (now_ms.saturating_sub(window) / bucket) * bucket;
+        conn.execute(&sql, params![start as i64])?;
+    }
+    Ok(())
+}
+
+pub fn cleanup_old_traffic_metrics(
+    conn: &Connection,
+    cutoff_raw: u64,
+    cutoff_1m: u64,
+    cutoff_1h: u64,
+    cutoff_1d: u64,
+) {
+    for (table, cutoff) in [
+        ("traffic_metrics", cutoff_raw),
+        ("traffic_metrics_1m", cutoff_1m),
+        ("traffic_metrics_1h", cutoff_1h),
+        ("traffic_metrics_1d", cutoff_1d),
+    ] {
+        let _ = conn
+            .execute(&format!("DELETE FROM {table} WHERE report_time < ?1"), params![cutoff as i64])
+            .map_err(|e| tracing::error!("Failed to delete expired {table}: {}", e));
+    }
+}
+
+pub fn query_traffic_history(
+    conn: &Connection,
+    params: &TrafficHistoryQueryParams,
+    resolution: MetricResolution,
+) -> Vec<TrafficPoint> {
+    // 设备按 MAC 汇总其所有 IP 的流量
+    let (kind, target_col) = match params.kind {
+        TrafficTargetKind::Device => (TrafficTargetKind::Ip, "mac"),
+        kind => (kind, "target"),
+    };
+    let stmt_str = format!(
+        "
+        SELECT
+            report_time, SUM(rx_bytes), SUM(tx_bytes), SUM(rx_packets), SUM(tx_packets)
+        FROM {}
+        WHERE kind = ?1 AND {} = ?2 AND report_time >= ?3 AND report_time <= ?4
+        GROUP BY report_time
+        ORDER BY report_time
+    ",
+        table_name(resolution),
+        target_col
+    );
+
+    let mut stmt = match conn.prepare(&stmt_str) {
+        Ok(s) => s,
+        Err(e) => {
+            tracing::error!("Failed to prepare traffic history SQL: {}, error: {}", stmt_str, e);
+            return Vec::new();
+        }
+    };
+
+    let rows = stmt.query_map(
+        params![
+            kind.as_str(),
+            params.target,
+            params.start_time.unwrap_or(0) as i64,
+            params.end_time.unwrap_or(i64::MAX as u64) as i64,
+        ],
+        |row| {
+            Ok(TrafficPoint {
+                report_time: row.get::<_, i64>(0)? as u64,
+                rx_bytes: row.get::<_, Option<i64>>(1)?.unwrap_or(0) as u64,
+                tx_bytes: row.get::<_, Option<i64>>(2)?.unwrap_or(0) as u64,
+                rx_packets: row.get::<_, Option<i64>>(3)?.unwrap_or(0) as u64,
+                tx_packets: row.get::<_, Option<i64>>(4)?.unwrap_or(0) as u64,
+            })
+        },
+    );
+
+    match rows {
+        Ok(r) => r.filter_map(Result::ok).collect(),
+        Err(e) => {
+            tracing::error!("Failed to execute traffic history query: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// 按对象汇总时间范围内的流量
+pub fn query_traffic_totals(
+    conn: &Connection,
+    kind: TrafficTargetKind,
+    start_time: u64,
+    end_time: u64,
+    resolution: MetricResolution,
+) -> Vec<TrafficTotal> {
+    let stmt_str = format!(
+        "
+        SELECT
+            target, MAX(mac), SUM(rx_bytes), SUM(tx_bytes), SUM(rx_packets), SUM(tx_packets)
+        FROM {}
+        WHERE kind = ?1 AND report_time >= ?2 AND report_time < ?3
+        GROUP BY target
+    ",
+        table_name(resolution)
+    );
+
+    let mut stmt = match conn.prepare(&stmt_str) {
+        Ok(s) => s,
+        Err(e) => {
+            tracing::error!("Failed to prepare traffic totals SQL: {}, error: {}", stmt_str, e);
+            return Vec::new();
+        }
+    };
+
+    let rows = stmt.query_map(params![kind.as_str(), start_time as i64, end_time as i64], |row| {
+        Ok(TrafficTotal {
+            kind,
+            target: row.get(0)?,
+            mac: row.get(1)?,
+            rx_bytes: row.get::<_, Option<i64>>(2)?.unwrap_or(0) as u64,
+            tx_bytes: row.get::<_, Option<i64>>(3)?.unwrap_or(0) as u64,
+            rx_packets: row.get::<_, Option<i64>>(4)?.unwrap_or(0) as u64,
+            tx_packets: row.get::<_, Option<i64>>(5)?.unwrap_or(0) as u64,
+            ..Default::default()
+        })
+    });
+
+    match rows {
+        Ok(r) => r.filter_map(Result::ok).collect(),
+        Err(e) => {
+            tracing::error!("Failed to execute traffic totals query: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(target: &str, report_time: u64, rx_bytes: u64) -> TrafficSample {
+        TrafficSample {
+            kind: TrafficTargetKind::Iface,
+            target: target.to_string(),
+            mac: None,
+            report_time,
+            rx_bytes,
+            tx_bytes: 1,
+            rx_packets: 1,
+            tx_packets: 1,
+        }
+    }
+
+    fn rows(conn: &Connection, table: &str) -> Vec<(String, u64, u64, u64)> {
+        let sql = format!(
+            "SELECT target, report_time, rx_bytes, tx_bytes FROM {table} ORDER BY target, report_time"
+        );
+        let mut stmt = conn.prepare(&sql).unwrap();
+        stmt.query_map([], |row| {
+            Ok((
+                row.get(0)?,
+                row.get::<_, i64>(1)? as u64,
+                row.get::<_, i64>(2)? as u64,
+                row.get::<_, i64>(3)? as u64,
+            ))
+        })
+        .unwrap()
+        .map(Result::unwrap)
+        .collect()
+    }
+
+    #[test]
+    fn rollup_sums_samples_per_bucket() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        create_traffic_tables(&conn).unwrap();
+
+        let now = 10 * 3600000 + 30000;
+        let minute = now / 60000 * 60000;
+        let last_minute = minute - 60000;
+        insert_traffic_samples(
+            &mut conn,
+            &[
+                sample("eth0", last_minute + 5000, 10),
+                sample("eth0", minute + 1000, 100),
+                sample("eth0", minute + 2000, 200),
+                sample("eth1", minute + 1000, 7),
+            ],
+        )
+        .unwrap();
+
+        perform_traffic_rollup(&conn, now).unwrap();
+        let expected = vec![
+            ("eth0".to_string(), last_minute, 10, 1),
+            ("eth0".to_string(), minute, 300, 2),
+            ("eth1".to_string(), minute, 7, 1),
+        ];
+        assert_eq!(rows(&conn, "traffic_metrics_1m"), expected);
+
+        // 重复汇总同一时间桶不会重复累计
+        perform_traffic_rollup(&conn, now).unwrap();
+        assert_eq!(rows(&conn, "traffic_metrics_1m"), expected);
+
+        let hourly: u64 = rows(&conn, "traffic_metrics_1h")
+            .into_iter()
+            .filter(|(target, ..)| target == "eth0")
+            .map(|(_, report_time, rx_bytes, _)| {
+                assert_eq!(report_time % 3600000, 0);
+                rx_bytes
+            })
+            .sum();
+        assert_eq!(hourly, 310);
+    }
+}
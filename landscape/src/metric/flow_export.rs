@@ -0,0 +1,452 @@
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+use landscape_common::config::{FlowExportCollector, FlowExportProtocol};
+use landscape_common::metric::connect::{ConnectMetric, ConnectStatusType};
+use landscape_common::utils::time::get_current_time_ms;
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc;
+
+const IPFIX_VERSION: u16 = 10;
+const NETFLOW_V9_VERSION: u16 = 9;
+
+const IPFIX_TEMPLATE_SET_ID: u16 = 2;
+const IPFIX_OPTIONS_TEMPLATE_SET_ID: u16 = 3;
+const NETFLOW_V9_TEMPLATE_SET_ID: u16 = 0;
+const NETFLOW_V9_OPTIONS_TEMPLATE_SET_ID: u16 = 1;
+
+const TEMPLATE_ID_V4: u16 = 256;
+const TEMPLATE_ID_V6: u16 = 257;
+const TEMPLATE_ID_SAMPLING: u16 = 258;
+
+/// 单个 UDP 报文的最大长度, 避免在常见 MTU 下分片
+const MAX_PACKET_SIZE: usize = 1400;
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+const ENTERPRISE_BIT: u16 = 0x8000;
+/// 企业字段: flow_id
+const ENTERPRISE_FIELD_FLOW_ID: u16 = 1;
+/// 企业字段: WAN 网卡 ifindex
+const ENTERPRISE_FIELD_WAN_IFINDEX: u16 = 2;
+
+/// IANA IPFIX 信息元素
+mod ie {
+    pub const OCTET_DELTA_COUNT: u16 = 1;
+    pub const PACKET_DELTA_COUNT: u16 = 2;
+    pub const PROTOCOL_IDENTIFIER: u16 = 4;
+    pub const SOURCE_TRANSPORT_PORT: u16 = 7;
+    pub const SOURCE_IPV4_ADDRESS: u16 = 8;
+    pub const DESTINATION_TRANSPORT_PORT: u16 = 11;
+    pub const DESTINATION_IPV4_ADDRESS: u16 = 12;
+    pub const SOURCE_IPV6_ADDRESS: u16 = 27;
+    pub const DESTINATION_IPV6_ADDRESS: u16 = 28;
+    pub const SAMPLING_INTERVAL: u16 = 34;
+    pub const SAMPLING_ALGORITHM: u16 = 35;
+    pub const FLOW_DIRECTION: u16 = 61;
+    pub const OBSERVATION_DOMAIN_ID: u16 = 149;
+    pub const FLOW_START_MILLISECONDS: u16 = 152;
+    pub const FLOW_END_MILLISECONDS: u16 = 153;
+}
+
+/// NetFlow v9 选项模板中的 System 作用域
+const NETFLOW_V9_SCOPE_SYSTEM: u16 = 1;
+/// 确定性采样 (每 N 个取 1 个)
+const SAMPLING_ALGORITHM_DETERMINISTIC: u8 = 1;
+
+/// (字段 ID, 长度, 是否为企业字段)
+type FieldSpec = (u16, u16, bool);
+
+fn record_fields(is_ipv6: bool) -> Vec<FieldSpec> {
+    let (src, dst, addr_len) = if is_ipv6 {
+        (ie::SOURCE_IPV6_ADDRESS, ie::DESTINATION_IPV6_ADDRESS, 16)
+    } else {
+        (ie::SOURCE_IPV4_ADDRESS, ie::DESTINATION_IPV4_ADDRESS, 4)
+    };
+    vec![
+        (src, addr_len, false),
+        (dst, addr_len, false),
+        (ie::SOURCE_TRANSPORT_PORT, 2, false),
+        (ie::DESTINATION_TRANSPORT_PORT, 2, false),
+        (ie::PROTOCOL_IDENTIFIER, 1, false),
+        (ie::FLOW_DIRECTION, 1, false),
+        (ie::OCTET_DELTA_COUNT, 8, false),
+        (ie::PACKET_DELTA_COUNT, 8, false),
+        (ie::FLOW_START_MILLISECONDS, 8, false),
+        (ie::FLOW_END_MILLISECONDS, 8, false),
+        (ENTERPRISE_FIELD_FLOW_ID, 1, true),
+        (ENTERPRISE_FIELD_WAN_IFINDEX, 4, true),
+    ]
+}
+
+/// 单方向的流记录, 一条结束的连接会拆分为出入两个方向
+struct FlowRecord {
+    src_ip: IpAddr,
+    dst_ip: IpAddr,
+    src_port: u16,
+    dst_port: u16,
+    l4_proto: u8,
+    /// 0: ingress, 1: egress
+    direction: u8,
+    bytes: u64,
+    packets: u64,
+    start_ms: u64,
+    end_ms: u64,
+    flow_id: u8,
+    wan_ifindex: u32,
+}
+
+impl FlowRecord {
+    fn from_metric(metric: &ConnectMetric) -> Vec<FlowRecord> {
+        let mut records = vec![];
+        if metric.egress_packets > 0 {
+            records.push(FlowRecord {
+                src_ip: metric.src_ip,
+                dst_ip: metric.dst_ip,
+                src_port: metric.src_port,
+                dst_port: metric.dst_port,
+                l4_proto: metric.l4_proto,
+                direction: 1,
+                bytes: metric.egress_bytes,
+                packets: metric.egress_packets,
+                start_ms: metric.create_time_ms,
+                end_ms: metric.report_time,
+                flow_id: metric.flow_id,
+                wan_ifindex: metric.ifindex,
+            });
+        }
+        if metric.ingress_packets > 0 {
+            records.push(FlowRecord {
+                src_ip: metric.dst_ip,
+                dst_ip: metric.src_ip,
+                src_port: metric.dst_port,
+                dst_port: metric.src_port,
+                l4_proto: metric.l4_proto,
+                direction: 0,
+                bytes: metric.ingress_bytes,
+                packets: metric.ingress_packets,
+                start_ms: metric.create_time_ms,
+                end_ms: metric.report_time,
+                flow_id: metric.flow_id,
+                wan_ifindex: metric.ifindex,
+            });
+        }
+        records
+    }
+
+    fn is_ipv6(&self) -> bool {
+        self.src_ip.is_ipv6()
+    }
+
+    fn encode(&self, buf: &mut Vec<u8>) {
+        for ip in [self.src_ip, self.dst_ip] {
+            match ip {
+                IpAddr::V4(ip) => buf.extend_from_slice(&ip.octets()),
+                IpAddr::V6(ip) => buf.extend_from_slice(&ip.octets()),
+            }
+        }
+        buf.extend_from_slice(&self.src_port.to_be_bytes());
+        buf.extend_from_slice(&self.dst_port.to_be_bytes());
+        buf.push(self.l4_proto);
+        buf.push(self.direction);
+        buf.extend_from_slice(&self.bytes.to_be_bytes());
+        buf.extend_from_slice(&self.packets.to_be_bytes());
+        buf.extend_from_slice(&self.start_ms.to_be_bytes());
+        buf.extend_from_slice(&self.end_ms.to_be_bytes());
+        buf.push(self.flow_id);
+        buf.extend_from_slice(&self.wan_ifindex.to_be_bytes());
+    }
+}
+
+/// 按协议组装报文, 负责报文头、模板与数据集
+struct PacketEncoder {
+    config: FlowExportCollector,
+    start_time: Instant,
+    /// IPFIX 为已导出的数据记录数, NetFlow v9 为已发送的报文数
+    sequence: u32,
+}
+
+impl PacketEncoder {
+    fn new(config: FlowExportCollector) -> Self {
+        PacketEncoder { config, start_time: Instant::now(), sequence: 0 }
+    }
+
+    fn is_ipfix(&self) -> bool {
+        self.config.protocol == FlowExportProtocol::Ipfix
+    }
+
+    fn header_len(&self) -> usize {
+        if self.is_ipfix() {
+            16
+        } else {
+            20
+        }
+    }
+
+    /// 生成报文头, `count` 为 NetFlow v9 中的记录数 (包含模板)
+    fn finish_packet(&mut self, body: &[u8], count: u16, data_records: u32) -> Vec<u8> {
+        let export_secs = (get_current_time_ms().unwrap_or_default() / 1000) as u32;
+        let mut packet = Vec::with_capacity(self.header_len() + body.len());
+        if self.is_ipfix() {
+            packet.extend_from_slice(&IPFIX_VERSION.to_be_bytes());
+            packet.extend_from_slice(&((16 + body.len()) as u16).to_be_bytes());
+            packet.extend_from_slice(&export_secs.to_be_bytes());
+            packet.extend_from_slice(&self.sequence.to_be_bytes());
+            packet.extend_from_slice(&self.config.observation_domain_id.to_be_bytes());
+            self.sequence = self.sequence.wrapping_add(data_records);
+        } else {
+            let uptime_ms = self.start_time.elapsed().as_millis() as u32;
+            packet.extend_from_slice(&NETFLOW_V9_VERSION.to_be_bytes());
+            packet.extend_from_slice(&count.to_be_bytes());
+            packet.extend_from_slice(&uptime_ms.to_be_bytes());
+            packet.extend_from_slice(&export_secs.to_be_bytes());
+            packet.extend_from_slice(&self.sequence.to_be_bytes());
+            packet.extend_from_slice(&self.config.observation_domain_id.to_be_bytes());
+            self.sequence = self.sequence.wrapping_add(1);
+        }
+        packet.extend_from_slice(body);
+        packet
+    }
+
+    fn push_field(&self, buf: &mut Vec<u8>, (id, len, enterprise): FieldSpec) {
+        if enterprise {
+            buf.extend_from_slice(&(id | ENTERPRISE_BIT).to_be_bytes());
+            buf.extend_from_slice(&len.to_be_bytes());
+            // NetFlow v9 没有企业编号, 仅使用高位区分厂商字段
+            if self.is_ipfix() {
+                buf.extend_from_slice(&self.config.enterprise_number.to_be_bytes());
+            }
+        } else {
+            buf.extend_from_slice(&id.to_be_bytes());
+            buf.extend_from_slice(&len.to_be_bytes());
+        }
+    }
+
+    /// 数据模板与采样选项模板
+    fn template_packet(&mut self) -> Vec<u8> {
+        let mut body = vec![];
+
+        let mut set = vec![];
+        for (template_id, is_ipv6) in [(TEMPLATE_ID_V4, false), (TEMPLATE_ID_V6, true)] {
+            let fields = record_fields(is_ipv6);
+            set.extend_from_slice(&template_id.to_be_bytes());
+            set.extend_from_slice(&(fields.len() as u16).to_be_bytes());
+            for field in fields {
+                self.push_field(&mut set, field);
+            }
+        }
+        let set_id =
+            if self.is_ipfix() { IPFIX_TEMPLATE_SET_ID } else { NETFLOW_V9_TEMPLATE_SET_ID };
+        push_set(&mut body, set_id, &set);
+
+        let mut set = vec![];
+        set.extend_from_slice(&TEMPLATE_ID_SAMPLING.to_be_bytes());
+        if self.is_ipfix() {
+            // field count, scope field count
+            set.extend_from_slice(&3u16.to_be_bytes());
+            set.extend_from_slice(&1u16.to_be_bytes());
+            self.push_field(&mut set, (ie::OBSERVATION_DOMAIN_ID, 4, false));
+        } else {
+            // scope length, option length (bytes)
+            set.extend_from_slice(&4u16.to_be_bytes());
+            set.extend_from_slice(&8u16.to_be_bytes());
+            self.push_field(&mut set, (NETFLOW_V9_SCOPE_SYSTEM, 4, false));
+        }
+        self.push_field(&mut set, (ie::SAMPLING_INTERVAL, 4, false));
+        self.push_field(&mut set, (ie::SAMPLING_ALGORITHM, 1, false));
+        let set_id = if self.is_ipfix() {
+            IPFIX_OPTIONS_TEMPLATE_SET_ID
+        } else {
+            NETFLOW_V9_OPTIONS_TEMPLATE_SET_ID
+        };
+        push_set(&mut body, set_id, &set);
+
+        let mut set = vec![];
+        set.extend_from_slice(&self.config.observation_domain_id.to_be_bytes());
+        set.extend_from_slice(&self.config.sampling_interval.max(1).to_be_bytes());
+        set.push(SAMPLING_ALGORITHM_DETERMINISTIC);
+        push_set(&mut body, TEMPLATE_ID_SAMPLING, &set);
+
+        // 2 个数据模板 + 1 个选项模板 + 1 条选项数据
+        self.finish_packet(&body, 4, 0)
+    }
+
+    /// 将记录按地址族分组并切分为多个报文
+    fn data_packets(&mut self, records: &[FlowRecord]) -> Vec<Vec<u8>> {
+        let mut packets = vec![];
+        for (template_id, is_ipv6) in [(TEMPLATE_ID_V4, false), (TEMPLATE_ID_V6, true)] {
+            let mut set = vec![];
+            let mut count = 0;
+            for record in records.iter().filter(|r| r.is_ipv6() == is_ipv6) {
+                let before = set.len();
+                record.encode(&mut set);
+                let record_len = set.len() - before;
+                if self.header_len() + 4 + set.len() > MAX_PACKET_SIZE {
+                    set.truncate(before);
+                    packets.push(self.data_packet(template_id, &set, count));
+                    set.clear();
+                    count = 0;
+                    record.encode(&mut set);
+                    debug_assert_eq!(set.len(), record_len);
+                }
+                count += 1;
+            }
+            if count > 0 {
+                packets.push(self.data_packet(template_id, &set, count));
+            }
+        }
+        packets
+    }
+
+    fn data_packet(&mut self, template_id: u16, set: &[u8], count: u16) -> Vec<u8> {
+        let mut body = vec![];
+        push_set(&mut body, template_id, set);
+        self.finish_packet(&body, count, count as u32)
+    }
+}
+
+fn push_set(buf: &mut Vec<u8>, set_id: u16, content: &[u8]) {
+    // 按 4 字节对齐填充
+    let padding = (4 - (content.len() + 4) % 4) % 4;
+    buf.extend_from_slice(&set_id.to_be_bytes());
+    buf.extend_from_slice(&((4 + content.len() + padding) as u16).to_be_bytes());
+    buf.extend_from_slice(content);
+    buf.extend(std::iter::repeat(0).take(padding));
+}
+
+/// 将结束的连接以 IPFIX / NetFlow v9 发送到采集器
+#[derive(Clone)]
+pub struct FlowExporter {
+    senders: Vec<mpsc::Sender<ConnectMetric>>,
+}
+
+impl FlowExporter {
+    pub fn new(collectors: Vec<FlowExportCollector>) -> Self {
+        let mut senders = vec![];
+        for collector in collectors {
+            let (tx, rx) = mpsc::channel(4096);
+            tokio::spawn(run_collector_export(collector, rx));
+            senders.push(tx);
+        }
+        FlowExporter { senders }
+    }
+
+    pub fn record(&self, metric: &ConnectMetric) {
+        if metric.status != ConnectStatusType::Disabled {
+            return;
+        }
+        for sender in self.senders.iter() {
+            if let Err(e) = sender.try_send(metric.clone()) {
+                tracing::warn!("flow export queue is full: {e:?}");
+            }
+        }
+    }
+}
+
+async fn run_collector_export(
+    collector: FlowExportCollector,
+    mut metric_rx: mpsc::Receiver<ConnectMetric>,
+) {
+    let bind_addr = if collector.address.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+    let socket = match UdpSocket::bind(bind_addr).await {
+        Ok(socket) => socket,
+        Err(e) => {
+            tracing::error!("bind flow export socket error: {e:?}");
+            return;
+        }
+    };
+    tracing::info!("export {:?} flows to {}", collector.protocol, collector.address);
+
+    let address = collector.address;
+    let sampling_interval = collector.sampling_interval.max(1);
+    let template_refresh = Duration::from_secs(collector.template_refresh_secs.max(1));
+    let mut encoder = PacketEncoder::new(collector);
+
+    let mut last_template: Option<Instant> = None;
+    let mut sampling_counter: u32 = 0;
+    let mut pending: Vec<FlowRecord> = vec![];
+    let mut flush_interval = tokio::time::interval(FLUSH_INTERVAL);
+
+    loop {
+        tokio::select! {
+            metric = metric_rx.recv() => {
+                let Some(metric) = metric else {
+                    break;
+                };
+                sampling_counter = (sampling_counter + 1) % sampling_interval;
+                if sampling_counter == 0 {
+                    pending.extend(FlowRecord::from_metric(&metric));
+                }
+            }
+            _ = flush_interval.tick() => {
+                if last_template.map_or(true, |time| time.elapsed() >= template_refresh) {
+                    let packet = encoder.template_packet();
+                    if let Err(e) = socket.send_to(&packet, address).await {
+                        tracing::debug!("send flow template to {address} error: {e:?}");
+                    }
+                    last_template = Some(Instant::now());
+                }
+                if pending.is_empty() {
+                    continue;
+                }
+                for packet in encoder.data_packets(&pending) {
+                    if let Err(e) = socket.send_to(&packet, address).await {
+                        tracing::debug!("send flow records to {address} error: {e:?}");
+                    }
+                }
+                pending.clear();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use super::{FlowRecord, PacketEncoder};
+    use landscape_common::config::{FlowExportCollector, FlowExportProtocol};
+
+    fn collector(protocol: FlowExportProtocol) -> FlowExportCollector {
+        FlowExportCollector {
+            address: "127.0.0.1:4739".parse().unwrap(),
+            protocol,
+            template_refresh_secs: 60,
+            sampling_interval: 1,
+            observation_domain_id: 1,
+            enterprise_number: 32473,
+        }
+    }
+
+    fn record() -> FlowRecord {
+        FlowRecord {
+            src_ip: IpAddr::V4(Ipv4Addr::new(192, 168, 1, 2)),
+            dst_ip: IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)),
+            src_port: 40000,
+            dst_port: 443,
+            l4_proto: 6,
+            direction: 1,
+            bytes: 1000,
+            packets: 10,
+            start_ms: 1,
+            end_ms: 2,
+            flow_id: 1,
+            wan_ifindex: 2,
+        }
+    }
+
+    #[test]
+    fn test_ipfix_packet_length() {
+        let mut encoder = PacketEncoder::new(collector(FlowExportProtocol::Ipfix));
+        let template = encoder.template_packet();
+        assert_eq!(u16::from_be_bytes([template[2], template[3]]) as usize, template.len());
+
+        let records: Vec<FlowRecord> = (0..100).map(|_| record()).collect();
+        let packets = encoder.data_packets(&records);
+        assert!(packets.len() > 1);
+        for packet in packets.iter() {
+            assert!(packet.len() <= super::MAX_PACKET_SIZE);
+            assert_eq!(u16::from_be_bytes([packet[2], packet[3]]) as usize, packet.len());
+            assert_eq!(packet.len() % 4, 0);
+        }
+    }
+}
@@ -1,40 +1,75 @@
+use std::net::IpAddr;
 use std::path::PathBuf;
 
-use landscape_common::{
-    service::{ServiceStatus, WatchService},
-    LANDSCAPE_METRIC_DIR_NAME,
-};
+use landscape_common::service::{ServiceStatus, WatchService};
 use tokio::sync::oneshot;
 
 pub mod connect_manager;
+pub mod dns_correlation;
 pub mod dns_manager;
 #[cfg(feature = "metric-duckdb")]
 pub mod duckdb;
-pub mod noop_store;
+pub mod flow_export;
 #[cfg(feature = "polars")]
 pub mod polars;
+pub mod prometheus;
+#[cfg(not(feature = "metric-duckdb"))]
+pub mod sqlite;
+pub mod traffic_manager;
 
 #[cfg(feature = "metric-duckdb")]
 pub type MetricStore = duckdb::DuckMetricStore;
 #[cfg(not(feature = "metric-duckdb"))]
-pub type MetricStore = noop_store::NoopMetricStore;
+pub type MetricStore = sqlite::SqliteMetricStore;
 
 use crate::metric::connect_manager::ConnectMetricManager;
+use crate::metric::dns_correlation::DomainCorrelator;
 use crate::metric::dns_manager::DnsMetricManager;
+use crate::metric::flow_export::FlowExporter;
+use crate::metric::prometheus::PrometheusCollector;
+use crate::metric::traffic_manager::TrafficMetricManager;
 use landscape_common::config::MetricRuntimeConfig;
 
+/// IPv4 映射的 IPv6 地址按 IPv4 存储, 便于按 IP 查询
+pub(crate) fn clean_ip_string(ip: &IpAddr) -> String {
+    match ip {
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                v4.to_string()
+            } else {
+                v6.to_string()
+            }
+        }
+        IpAddr::V4(v4) => v4.to_string(),
+    }
+}
+
 #[derive(Clone)]
 pub struct MetricData {
     pub connect_metric: ConnectMetricManager,
     pub dns_metric: DnsMetricManager,
+    pub prometheus: PrometheusCollector,
+    pub traffic_metric: TrafficMetricManager,
 }
 
 impl MetricData {
     pub async fn new(home_path: PathBuf, config: MetricRuntimeConfig) -> Self {
-        let store = MetricStore::new(home_path, config).await;
+        let flow_exporter = FlowExporter::new(config.flow_collectors.clone());
+        let store = MetricStore::new(home_path, config.clone()).await;
+        let prometheus = PrometheusCollector::new();
+        let domain_correlator = DomainCorrelator::new();
+        let traffic_metric = TrafficMetricManager::with_store(store.clone(), config);
         MetricData {
-            connect_metric: ConnectMetricManager::with_store(store.clone()),
-            dns_metric: DnsMetricManager::with_store(store),
+            connect_metric: ConnectMetricManager::with_store(
+                store.clone(),
+                prometheus.clone(),
+                flow_exporter,
+                domain_correlator.clone(),
+                traffic_metric.clone(),
+            ),
+            dns_metric: DnsMetricManager::with_store(store, prometheus.clone(), domain_correlator),
+            prometheus,
+            traffic_metric,
         }
     }
 }
@@ -46,8 +81,8 @@ pub struct MetricService {
 }
 
 impl MetricService {
-    pub async fn new(home_path: PathBuf, config: MetricRuntimeConfig) -> Self {
-        let metric_path = home_path.join(LANDSCAPE_METRIC_DIR_NAME);
+    pub async fn new(config: MetricRuntimeConfig) -> Self {
+        let metric_path = config.db_path.clone();
         if !metric_path.exists() {
             if let Err(e) = std::fs::create_dir_all(&metric_path) {
                 tracing::error!("Failed to create metric directory: {}", e);
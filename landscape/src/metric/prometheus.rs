@@ -0,0 +1,267 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Display, Write};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use landscape_common::metric::connect::{ConnectKey, ConnectMetric, ConnectStatusType};
+use landscape_common::metric::dns::{DnsMetric, DnsResultStatus};
+
+/// DNS 查询耗时直方图的桶 (毫秒)
+const DNS_LATENCY_BUCKETS_MS: [u32; 11] = [1, 5, 10, 25, 50, 100, 250, 500, 1000, 2500, 5000];
+/// 超过该时间未上报的连接视为已结束
+const CONNECT_STALE_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Prometheus 文本格式 (0.0.4) 的输出缓冲
+#[derive(Default)]
+pub struct PrometheusText {
+    buf: String,
+}
+
+impl PrometheusText {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 输出指标族的 HELP / TYPE 行
+    pub fn family(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.buf, "# HELP {name} {help}");
+        let _ = writeln!(self.buf, "# TYPE {name} {kind}");
+    }
+
+    pub fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: impl Display) {
+        self.buf.push_str(name);
+        if !labels.is_empty() {
+            self.buf.push('{');
+            for (index, (key, value)) in labels.iter().enumerate() {
+                if index > 0 {
+                    self.buf.push(',');
+                }
+                let _ = write!(self.buf, "{key}=\"{}\"", escape_label_value(value));
+            }
+            self.buf.push('}');
+        }
+        let _ = writeln!(self.buf, " {value}");
+    }
+
+    pub fn finish(self) -> String {
+        self.buf
+    }
+}
+
+fn escape_label_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+pub fn l4_proto_name(proto: u8) -> String {
+    match proto {
+        1 => "icmp".to_string(),
+        6 => "tcp".to_string(),
+        17 => "udp".to_string(),
+        58 => "icmpv6".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn dns_status_name(status: DnsResultStatus) -> &'static str {
+    match status {
+        DnsResultStatus::Local => "local",
+        DnsResultStatus::Block => "block",
+        DnsResultStatus::Hit => "hit",
+        DnsResultStatus::NxDomain => "nxdomain",
+        DnsResultStatus::Filter => "filter",
+        DnsResultStatus::Normal => "normal",
+        DnsResultStatus::Error => "error",
+    }
+}
+
+#[derive(Default, Clone, Copy)]
+struct TrafficCounter {
+    ingress_bytes: u64,
+    ingress_packets: u64,
+    egress_bytes: u64,
+    egress_packets: u64,
+}
+
+struct ConnectState {
+    flow_id: u8,
+    l4_proto: u8,
+    last: TrafficCounter,
+    update_at: Instant,
+}
+
+#[derive(Default)]
+struct DnsCounter {
+    queries: BTreeMap<&'static str, u64>,
+    buckets: [u64; DNS_LATENCY_BUCKETS_MS.len()],
+    latency_sum_ms: u64,
+    latency_count: u64,
+}
+
+#[derive(Default)]
+struct CollectorState {
+    connects: HashMap<ConnectKey, ConnectState>,
+    /// flow_id -> 累计流量
+    flows: BTreeMap<u8, TrafficCounter>,
+    /// (flow_id, rule, upstream) -> DNS 统计
+    dns: BTreeMap<(u32, String, String), DnsCounter>,
+}
+
+/// 在内存中累计 Prometheus 所需的计数器, 不依赖指标存储后端
+#[derive(Clone, Default)]
+pub struct PrometheusCollector {
+    state: Arc<Mutex<CollectorState>>,
+}
+
+impl PrometheusCollector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 连接上报的是累计值, 这里按连接记录上次的值并将增量计入所属 Flow
+    pub fn record_connect(&self, metric: &ConnectMetric) {
+        let mut state = self.state.lock().unwrap();
+        let current = TrafficCounter {
+            ingress_bytes: metric.ingress_bytes,
+            ingress_packets: metric.ingress_packets,
+            egress_bytes: metric.egress_bytes,
+            egress_packets: metric.egress_packets,
+        };
+        let last = match state.connects.get(&metric.key) {
+            Some(connect) => connect.last,
+            None => TrafficCounter::default(),
+        };
+
+        let flow = state.flows.entry(metric.flow_id).or_default();
+        flow.ingress_bytes += current.ingress_bytes.saturating_sub(last.ingress_bytes);
+        flow.ingress_packets += current.ingress_packets.saturating_sub(last.ingress_packets);
+        flow.egress_bytes += current.egress_bytes.saturating_sub(last.egress_bytes);
+        flow.egress_packets += current.egress_packets.saturating_sub(last.egress_packets);
+
+        if metric.status == ConnectStatusType::Disabled {
+            state.connects.remove(&metric.key);
+        } else {
+            state.connects.insert(
+                metric.key.clone(),
+                ConnectState {
+                    flow_id: metric.flow_id,
+                    l4_proto: metric.l4_proto,
+                    last: current,
+                    update_at: Instant::now(),
+                },
+            );
+        }
+    }
+
+    pub fn record_dns(&self, metric: &DnsMetric) {
+        let mut state = self.state.lock().unwrap();
+        let key = (
+            metric.flow_id,
+            metric.rule.clone().unwrap_or_default(),
+            metric.upstream.clone().unwrap_or_default(),
+        );
+        let counter = state.dns.entry(key).or_default();
+        *counter.queries.entry(dns_status_name(metric.status)).or_default() += 1;
+        for (index, bound) in DNS_LATENCY_BUCKETS_MS.iter().enumerate() {
+            if metric.duration_ms <= *bound {
+                counter.buckets[index] += 1;
+            }
+        }
+        counter.latency_sum_ms += metric.duration_ms as u64;
+        counter.latency_count += 1;
+    }
+
+    /// 输出 Flow 流量, 活跃连接与 DNS 相关指标
+    pub fn render(&self, out: &mut PrometheusText) {
+        let mut state = self.state.lock().unwrap();
+        state.connects.retain(|_, connect| connect.update_at.elapsed() < CONNECT_STALE_TIMEOUT);
+
+        out.family("landscape_flow_bytes_total", "counter", "Bytes forwarded per flow");
+        for (flow_id, counter) in state.flows.iter() {
+            let flow_id = flow_id.to_string();
+            for (direction, value) in
+                [("ingress", counter.ingress_bytes), ("egress", counter.egress_bytes)]
+            {
+                let labels = [("flow_id", flow_id.as_str()), ("direction", direction)];
+                out.sample("landscape_flow_bytes_total", &labels, value);
+            }
+        }
+        out.family("landscape_flow_packets_total", "counter", "Packets forwarded per flow");
+        for (flow_id, counter) in state.flows.iter() {
+            let flow_id = flow_id.to_string();
+            for (direction, value) in
+                [("ingress", counter.ingress_packets), ("egress", counter.egress_packets)]
+            {
+                let labels = [("flow_id", flow_id.as_str()), ("direction", direction)];
+                out.sample("landscape_flow_packets_total", &labels, value);
+            }
+        }
+
+        let mut active: BTreeMap<(u8, u8), u64> = BTreeMap::new();
+        for connect in state.connects.values() {
+            *active.entry((connect.flow_id, connect.l4_proto)).or_default() += 1;
+        }
+        out.family("landscape_active_connections", "gauge", "Active connections per flow");
+        for ((flow_id, l4_proto), count) in active {
+            let flow_id = flow_id.to_string();
+            let proto = l4_proto_name(l4_proto);
+            let labels = [("flow_id", flow_id.as_str()), ("proto", proto.as_str())];
+            out.sample("landscape_active_connections", &labels, count);
+        }
+
+        out.family("landscape_dns_queries_total", "counter", "DNS queries by result status");
+        for ((flow_id, rule, upstream), counter) in state.dns.iter() {
+            let flow_id = flow_id.to_string();
+            for (status, count) in counter.queries.iter() {
+                let labels = [
+                    ("flow_id", flow_id.as_str()),
+                    ("rule", rule.as_str()),
+                    ("upstream", upstream.as_str()),
+                    ("status", status),
+                ];
+                out.sample("landscape_dns_queries_total", &labels, count);
+            }
+        }
+
+        let name = "landscape_dns_query_duration_seconds";
+        out.family(name, "histogram", "DNS query handling latency");
+        for ((flow_id, rule, upstream), counter) in state.dns.iter() {
+            let flow_id = flow_id.to_string();
+            let base = [
+                ("flow_id", flow_id.as_str()),
+                ("rule", rule.as_str()),
+                ("upstream", upstream.as_str()),
+            ];
+            for (bound, count) in DNS_LATENCY_BUCKETS_MS.iter().zip(counter.buckets.iter()) {
+                let le = (*bound as f64 / 1000.0).to_string();
+                let labels = [base[0], base[1], base[2], ("le", le.as_str())];
+                out.sample(&format!("{name}_bucket"), &labels, count);
+            }
+            let labels = [base[0], base[1], base[2], ("le", "+Inf")];
+            out.sample(&format!("{name}_bucket"), &labels, counter.latency_count);
+            out.sample(&format!("{name}_sum"), &base, counter.latency_sum_ms as f64 / 1000.0);
+            out.sample(&format!("{name}_count"), &base, counter.latency_count);
+        }
+    }
+}
+
+/// 网卡累计收发统计
+#[derive(Debug, Default, Clone)]
+pub struct IfaceTrafficStats {
+    pub rx_bytes: u64,
+    pub rx_packets: u64,
+    pub tx_bytes: u64,
+    pub tx_packets: u64,
+}
+
+pub fn read_iface_traffic_stats(iface_name: &str) -> Option<IfaceTrafficStats> {
+    let read = |name: &str| -> Option<u64> {
+        let path = format!("/sys/class/net/{iface_name}/statistics/{name}");
+        std::fs::read_to_string(path).ok()?.trim().parse().ok()
+    };
+    Some(IfaceTrafficStats {
+        rx_bytes: read("rx_bytes")?,
+        rx_packets: read("rx_packets")?,
+        tx_bytes: read("tx_bytes")?,
+        tx_packets: read("tx_packets")?,
+    })
+}
@@ -0,0 +1,731 @@
+use landscape_common::metric::connect::{
+    ConnectGlobalStats, ConnectHistoryQueryParams, ConnectHistoryStatus, ConnectKey, ConnectMetric,
+    ConnectMetricPoint, ConnectSortKey, DomainHistoryStat, IpHistoryStat, MetricResolution,
+    SortOrder,
+};
+use sea_orm::{ConnectionTrait, DbErr, QueryResult, Value};
+
+use super::{statement, RealtimeState};
+use crate::metric::clean_ip_string;
+
+pub const SUMMARY_INSERT_SQL: &str = "
+    INSERT INTO conn_summaries (
+        create_time, cpu_id, src_ip, dst_ip, src_port, dst_port, l4_proto, l3_proto, flow_id, trace_id,
+        last_report_time, total_ingress_bytes, total_egress_bytes, total_ingress_pkts, total_egress_pkts, status, create_time_ms, gress, domain
+    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+    ON CONFLICT (create_time, cpu_id) DO UPDATE SET
+        last_report_time = MAX(conn_summaries.last_report_time, excluded.last_report_time),
+        total_ingress_bytes = MAX(conn_summaries.total_ingress_bytes, excluded.total_ingress_bytes),
+        total_egress_bytes = MAX(conn_summaries.total_egress_bytes, excluded.total_egress_bytes),
+        total_ingress_pkts = MAX(conn_summaries.total_ingress_pkts, excluded.total_ingress_pkts),
+        total_egress_pkts = MAX(conn_summaries.total_egress_pkts, excluded.total_egress_pkts),
+        status = CASE WHEN excluded.last_report_time >= conn_summaries.last_report_time THEN excluded.status ELSE conn_summaries.status END,
+        domain = COALESCE(conn_summaries.domain, excluded.domain)
+";
+
+pub const METRIC_INSERT_SQL: &str = "
+    INSERT INTO conn_metrics (
+        create_time, cpu_id, report_time, ingress_bytes, ingress_packets, egress_bytes, egress_packets, status, create_time_ms
+    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+";
+
+pub fn create_tables_sql() -> Vec<String> {
+    let mut sqls = vec![
+        "CREATE TABLE IF NOT EXISTS conn_summaries (
+            create_time INTEGER,
+            cpu_id INTEGER,
+            src_ip TEXT,
+            dst_ip TEXT,
+            src_port INTEGER,
+            dst_port INTEGER,
+            l4_proto INTEGER,
+            l3_proto INTEGER,
+            flow_id INTEGER,
+            trace_id INTEGER,
+            last_report_time INTEGER,
+            total_ingress_bytes INTEGER,
+            total_egress_bytes INTEGER,
+            total_ingress_pkts INTEGER,
+            total_egress_pkts INTEGER,
+            status INTEGER,
+            create_time_ms INTEGER,
+            gress INTEGER,
+            domain TEXT,
+            PRIMARY KEY (create_time, cpu_id)
+        )"
+        .to_string(),
+        "CREATE INDEX IF NOT EXISTS idx_conn_summaries_time ON conn_summaries (last_report_time)"
+            .to_string(),
+        "CREATE INDEX IF NOT EXISTS idx_conn_summaries_domain ON conn_summaries (domain)"
+            .to_string(),
+        "CREATE TABLE IF NOT EXISTS conn_metrics (
+            create_time INTEGER,
+            cpu_id INTEGER,
+            report_time INTEGER,
+            ingress_bytes INTEGER,
+            ingress_packets INTEGER,
+            egress_bytes INTEGER,
+            egress_packets INTEGER,
+            status INTEGER,
+            create_time_ms INTEGER
+        )"
+        .to_string(),
+        "CREATE INDEX IF NOT EXISTS idx_conn_metrics_time ON conn_metrics (report_time)"
+            .to_string(),
+        "CREATE INDEX IF NOT EXISTS idx_conn_metrics_key ON conn_metrics (create_time, cpu_id)"
+            .to_string(),
+        "CREATE TABLE IF NOT EXISTS global_stats (
+            total_ingress_bytes INTEGER,
+            total_egress_bytes INTEGER,
+            total_ingress_pkts INTEGER,
+            total_egress_pkts INTEGER,
+            total_connect_count INTEGER,
+            last_calculate_time INTEGER
+        )"
+        .to_string(),
+    ];
+    for table in ["conn_metrics_1m", "conn_metrics_1h", "conn_metrics_1d"] {
+        sqls.push(format!(
+            "CREATE TABLE IF NOT EXISTS {table} (
+            create_time INTEGER,
+            cpu_id INTEGER,
+            report_time INTEGER,
+            ingress_bytes INTEGER,
+            ingress_packets INTEGER,
+            egress_bytes INTEGER,
+            egress_packets INTEGER,
+            status INTEGER,
+            create_time_ms INTEGER,
+            PRIMARY KEY (create_time, cpu_id, report_time)
+        )"
+        ));
+        sqls.push(format!("CREATE INDEX IF NOT EXISTS idx_{table}_time ON {table} (report_time)"));
+    }
+    sqls
+}
+
+pub fn metric_values(metric: &ConnectMetric) -> Vec<Value> {
+    let status: u8 = metric.status.clone().into();
+    vec![
+        (metric.key.create_time as i64).into(),
+        (metric.key.cpu_id as i64).into(),
+        (metric.report_time as i64).into(),
+        (metric.ingress_bytes as i64).into(),
+        (metric.ingress_packets as i64).into(),
+        (metric.egress_bytes as i64).into(),
+        (metric.egress_packets as i64).into(),
+        (status as i64).into(),
+        (metric.create_time_ms as i64).into(),
+    ]
+}
+
+pub fn summary_values(state: &RealtimeState) -> Vec<Value> {
+    let s = &state.status;
+    let status: u8 = s.status.clone().into();
+    vec![
+        (s.key.create_time as i64).into(),
+        (s.key.cpu_id as i64).into(),
+        clean_ip_string(&s.src_ip).into(),
+        clean_ip_string(&s.dst_ip).into(),
+        (s.src_port as i64).into(),
+        (s.dst_port as i64).into(),
+        (s.l4_proto as i64).into(),
+        (s.l3_proto as i64).into(),
+        (s.flow_id as i64).into(),
+        (s.trace_id as i64).into(),
+        (s.last_report_time as i64).into(),
+        (state.last_ingress_bytes as i64).into(),
+        (state.last_egress_bytes as i64).into(),
+        (state.last_ingress_pkts as i64).into(),
+        (state.last_egress_pkts as i64).into(),
+        (status as i64).into(),
+        (s.create_time_ms as i64).into(),
+        (s.gress as i64).into(),
+        s.domain.clone().into(),
+    ]
+}
+
+fn get_u64(row: &QueryResult, idx: usize) -> Result<u64, DbErr> {
+    Ok(row.try_get_by_index::<Option<i64>>(idx)?.unwrap_or(0) as u64)
+}
+
+fn parse_ip(value: String) -> std::net::IpAddr {
+    value.parse().unwrap_or("0.0.0.0".parse().unwrap())
+}
+
+/// 按 ConnectHistoryQueryParams 生成公共过滤条件
+fn history_filters(
+    params: &ConnectHistoryQueryParams,
+    where_clauses: &mut Vec<String>,
+    values: &mut Vec<Value>,
+) {
+    if let Some(start) = params.start_time {
+        where_clauses.push("last_report_time >= ?".to_string());
+        values.push((start as i64).into());
+    }
+    if let Some(end) = params.end_time {
+        where_clauses.push("last_report_time <= ?".to_string());
+        values.push((end as i64).into());
+    }
+    if let Some(p) = params.flow_id {
+        where_clauses.push("flow_id = ?".to_string());
+        values.push((p as i64).into());
+    }
+    if let Some(ip) = params.src_ip.as_ref().filter(|ip| !ip.is_empty()) {
+        where_clauses.push("src_ip LIKE ?".to_string());
+        values.push(format!("%{}%", ip).into());
+    }
+    if let Some(domain) = params.domain.as_ref().filter(|domain| !domain.is_empty()) {
+        where_clauses.push("domain LIKE ?".to_string());
+        values.push(format!("%{}%", domain).into());
+    }
+}
+
+pub async fn query_metric_by_key<C: ConnectionTrait>(
+    conn: &C,
+    key: &ConnectKey,
+    resolution: MetricResolution,
+) -> Vec<ConnectMetricPoint> {
+    let table = match resolution {
+        MetricResolution::Second => "conn_metrics",
+        MetricResolution::Minute => "conn_metrics_1m",
+        MetricResolution::Hour => "conn_metrics_1h",
+        MetricResolution::Day => "conn_metrics_1d",
+    };
+
+    let stmt_str = format!(
+        "
+        SELECT
+            report_time, ingress_bytes, ingress_packets, egress_bytes, egress_packets, status
+        FROM {}
+        WHERE create_time = ? AND cpu_id = ?
+        ORDER BY report_time
+    ",
+        table
+    );
+
+    let rows = match conn
+        .query_all(statement(
+            &stmt_str,
+            [(key.create_time as i64).into(), (key.cpu_id as i64).into()],
+        ))
+        .await
+    {
+        Ok(rows) => rows,
+        Err(e) => {
+            tracing::error!("Failed to execute query_metric_by_key: {}", e);
+            return Vec::new();
+        }
+    };
+
+    rows.iter()
+        .filter_map(|row| {
+            Some(ConnectMetricPoint {
+                report_time: get_u64(row, 0).ok()?,
+                ingress_bytes: get_u64(row, 1).ok()?,
+                ingress_packets: get_u64(row, 2).ok()?,
+                egress_bytes: get_u64(row, 3).ok()?,
+                egress_packets: get_u64(row, 4).ok()?,
+                status: (get_u64(row, 5).ok()? as u8).into(),
+            })
+        })
+        .collect()
+}
+
+pub async fn query_historical_summaries_complex<C: ConnectionTrait>(
+    conn: &C,
+    params: ConnectHistoryQueryParams,
+) -> Vec<ConnectHistoryStatus> {
+    let mut where_clauses = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+
+    history_filters(&params, &mut where_clauses, &mut values);
+    if let Some(ip) = params.dst_ip.as_ref().filter(|ip| !ip.is_empty()) {
+        where_clauses.push("dst_ip LIKE ?".to_string());
+        values.push(format!("%{}%", ip).into());
+    }
+    if let Some(p) = params.port_start {
+        where_clauses.push(format!("src_port = {}", p));
+    }
+    if let Some(p) = params.port_end {
+        where_clauses.push(format!("dst_port = {}", p));
+    }
+    if let Some(p) = params.l3_proto {
+        where_clauses.push(format!("l3_proto = {}", p));
+    }
+    if let Some(p) = params.l4_proto {
+        where_clauses.push(format!("l4_proto = {}", p));
+    }
+    if let Some(s) = params.status {
+        where_clauses.push(format!("status = {}", s));
+    }
+    if let Some(g) = params.gress {
+        where_clauses.push(format!("gress = {}", g));
+    }
+
+    let where_stmt = if where_clauses.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", where_clauses.join(" AND "))
+    };
+
+    let sort_col = match params.sort_key.unwrap_or_default() {
+        ConnectSortKey::Port => "src_port",
+        ConnectSortKey::Ingress => "total_ingress_bytes",
+        ConnectSortKey::Egress => "total_egress_bytes",
+        ConnectSortKey::Time => "last_report_time",
+        ConnectSortKey::Duration => "(last_report_time - create_time_ms)",
+        ConnectSortKey::Domain => "domain",
+    };
+    let sort_order_str = match params.sort_order.unwrap_or_default() {
+        SortOrder::Asc => "ASC",
+        SortOrder::Desc => "DESC",
+    };
+
+    let limit_clause =
+        if let Some(l) = params.limit { format!("LIMIT {}", l) } else { String::new() };
+
+    let stmt_str = format!("
+        SELECT
+            create_time, cpu_id, src_ip, dst_ip, src_port, dst_port, l4_proto, l3_proto, flow_id, trace_id,
+            total_ingress_bytes, total_egress_bytes, total_ingress_pkts, total_egress_pkts, last_report_time, status, create_time_ms, gress, domain
+        FROM conn_summaries
+        {}
+        ORDER BY {} {}
+        {}
+    ", where_stmt, sort_col, sort_order_str, limit_clause);
+
+    let rows = match conn.query_all(statement(&stmt_str, values)).await {
+        Ok(rows) => rows,
+        Err(e) => {
+            tracing::error!("Failed to execute query: {}, error: {}", stmt_str, e);
+            return Vec::new();
+        }
+    };
+
+    rows.iter()
+        .filter_map(|row| {
+            Some(ConnectHistoryStatus {
+                key: ConnectKey {
+                    create_time: get_u64(row, 0).ok()?,
+                    cpu_id: get_u64(row, 1).ok()? as u32,
+                },
+                src_ip: parse_ip(row.try_get_by_index(2).ok()?),
+                dst_ip: parse_ip(row.try_get_by_index(3).ok()?),
+                src_port: get_u64(row, 4).ok()? as u16,
+                dst_port: get_u64(row, 5).ok()? as u16,
+                l4_proto: get_u64(row, 6).ok()? as u8,
+                l3_proto: get_u64(row, 7).ok()? as u8,
+                flow_id: get_u64(row, 8).ok()? as u8,
+                trace_id: get_u64(row, 9).ok()? as u8,
+                total_ingress_bytes: get_u64(row, 10).ok()?,
+                total_egress_bytes: get_u64(row, 11).ok()?,
+                total_ingress_pkts: get_u64(row, 12).ok()?,
+                total_egress_pkts: get_u64(row, 13).ok()?,
+                last_report_time: get_u64(row, 14).ok()?,
+                status: get_u64(row, 15).ok()? as u8,
+                create_time_ms: get_u64(row, 16).ok()?,
+                gress: get_u64(row, 17).ok()? as u8,
+                domain: row.try_get_by_index(18).ok()?,
+            })
+        })
+        .collect()
+}
+
+pub async fn query_global_stats<C: ConnectionTrait>(conn: &C) -> ConnectGlobalStats {
+    let stmt_str = "
+        SELECT
+            total_ingress_bytes, total_egress_bytes, total_ingress_pkts, total_egress_pkts,
+            total_connect_count, last_calculate_time
+        FROM global_stats
+        LIMIT 1
+    ";
+
+    let row = match conn.query_one(statement(stmt_str, [])).await {
+        Ok(Some(row)) => row,
+        Ok(None) => return ConnectGlobalStats::default(),
+        Err(e) => {
+            tracing::error!("Failed to query global stats: {}", e);
+            return ConnectGlobalStats::default();
+        }
+    };
+
+    (|| -> Result<ConnectGlobalStats, DbErr> {
+        Ok(ConnectGlobalStats {
+            total_ingress_bytes: get_u64(&row, 0)?,
+            total_egress_bytes: get_u64(&row, 1)?,
+            total_ingress_pkts: get_u64(&row, 2)?,
+            total_egress_pkts: get_u64(&row, 3)?,
+            total_connect_count: get_u64(&row, 4)?,
+            last_calculate_time: get_u64(&row, 5)?,
+        })
+    })()
+    .unwrap_or_default()
+}
+
+pub async fn aggregate_global_stats<C: ConnectionTrait>(
+    conn: &C,
+    now_ms: u64,
+) -> Result<(), DbErr> {
+    conn.execute(statement("DELETE FROM global_stats", [])).await?;
+    conn.execute(statement(
+        "
+        INSERT INTO global_stats
+        SELECT
+            SUM(max_ingress_bytes),
+            SUM(max_egress_bytes),
+            SUM(max_ingress_pkts),
+            SUM(max_egress_pkts),
+            COUNT(*),
+            ?
+        FROM (
+            SELECT
+                MAX(ingress_bytes) as max_ingress_bytes,
+                MAX(egress_bytes) as max_egress_bytes,
+                MAX(ingress_packets) as max_ingress_pkts,
+                MAX(egress_packets) as max_egress_pkts
+            FROM conn_metrics_1d
+            GROUP BY create_time, cpu_id
+        )
+    ",
+        [(now_ms as i64).into()],
+    ))
+    .await?;
+    Ok(())
+}
+
+/// 将原始数据汇总到 1m / 1h / 1d, 计数器为累计值, 取桶内最大值
+pub async fn perform_inner_db_rollup<C: ConnectionTrait>(
+    conn: &C,
+    now_ms: u64,
+) -> Result<(), DbErr> {
+    let steps = [
+        ("conn_metrics", "conn_metrics_1m", 60000_u64, 600000_u64),
+        ("conn_metrics_1m", "conn_metrics_1h", 3600000, 7200000),
+        ("conn_metrics_1h", "conn_metrics_1d", 86400000, 172800000),
+    ];
+    for (from, to, bucket, window) in steps {
+        let sql = format!(
+            "
+        INSERT INTO {to} (
+            create_time, cpu_id, report_time,
+            ingress_bytes, ingress_packets, egress_bytes, egress_packets,
+            status, create_time_ms
+        )
+        SELECT
+            create_time, cpu_id, (report_time / {bucket}) * {bucket} as bucket_time,
+            MAX(ingress_bytes), MAX(ingress_packets), MAX(egress_bytes), MAX(egress_packets),
+            MAX(status), MAX(create_time_ms)
+        FROM {from}
+        WHERE report_time >= ?
+        GROUP BY 1, 2, 3
+        ON CONFLICT (create_time, cpu_id, report_time) DO UPDATE SET
+            ingress_bytes = MAX({to}.ingress_bytes, excluded.ingress_bytes),
+            ingress_packets = MAX({to}.ingress_packets, excluded.ingress_packets),
+            egress_bytes = MAX({to}.egress_bytes, excluded.egress_bytes),
+            egress_packets = MAX({to}.egress_packets, excluded.egress_packets),
+            status = MAX({to}.status, excluded.status)
+        "
+        );
+        conn.execute(statement(&sql, [(now_ms.saturating_sub(window) as i64).into()])).await?;
+    }
+    Ok(())
+}
+
+pub async fn collect_and_cleanup_old_metrics<C: ConnectionTrait>(
+    conn: &C,
+    cutoff_raw: u64,
+    cutoff_1m: u64,
+    cutoff_1h: u64,
+    cutoff_1d: u64,
+) -> Box<Vec<ConnectMetric>> {
+    let stmt_str = "
+        SELECT
+            s.create_time, s.cpu_id, s.src_ip, s.dst_ip, s.src_port, s.dst_port, s.l4_proto, s.l3_proto, s.flow_id, s.trace_id,
+            m.report_time, m.ingress_bytes, m.ingress_packets, m.egress_bytes, m.egress_packets, m.status, s.create_time_ms, s.gress, s.domain
+        FROM conn_metrics m
+        JOIN conn_summaries s ON m.create_time = s.create_time AND m.cpu_id = s.cpu_id
+        WHERE m.report_time < ?
+    ";
+
+    let metrics = match conn.query_all(statement(stmt_str, [(cutoff_raw as i64).into()])).await {
+        Ok(rows) => rows
+            .iter()
+            .filter_map(|row| {
+                Some(ConnectMetric {
+                    key: ConnectKey {
+                        create_time: get_u64(row, 0).ok()?,
+                        cpu_id: get_u64(row, 1).ok()? as u32,
+                    },
+                    src_ip: parse_ip(row.try_get_by_index(2).ok()?),
+                    dst_ip: parse_ip(row.try_get_by_index(3).ok()?),
+                    src_port: get_u64(row, 4).ok()? as u16,
+                    dst_port: get_u64(row, 5).ok()? as u16,
+                    l4_proto: get_u64(row, 6).ok()? as u8,
+                    l3_proto: get_u64(row, 7).ok()? as u8,
+                    flow_id: get_u64(row, 8).ok()? as u8,
+                    trace_id: get_u64(row, 9).ok()? as u8,
+                    gress: get_u64(row, 17).ok()? as u8,
+                    ifindex: 0,
+                    domain: row.try_get_by_index(18).ok()?,
+                    report_time: get_u64(row, 10).ok()?,
+                    create_time_ms: get_u64(row, 16).ok()?,
+                    ingress_bytes: get_u64(row, 11).ok()?,
+                    ingress_packets: get_u64(row, 12).ok()?,
+                    egress_bytes: get_u64(row, 13).ok()?,
+                    egress_packets: get_u64(row, 14).ok()?,
+                    status: (get_u64(row, 15).ok()? as u8).into(),
+                })
+            })
+            .collect(),
+        Err(e) => {
+            tracing::error!("Failed to execute cleanup SELECT: {}", e);
+            Vec::new()
+        }
+    };
+
+    let mut deleted = [0_u64; 5];
+    for (index, (table, column, cutoff)) in [
+        ("conn_metrics", "report_time", cutoff_raw),
+        ("conn_metrics_1m", "report_time", cutoff_1m),
+        ("conn_metrics_1h", "report_time", cutoff_1h),
+        ("conn_metrics_1d", "report_time", cutoff_1d),
+        ("conn_summaries", "last_report_time", cutoff_1d),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let sql = format!("DELETE FROM {table} WHERE {column} < ?");
+        match conn.execute(statement(&sql, [(cutoff as i64).into()])).await {
+            Ok(result) => deleted[index] = result.rows_affected(),
+            Err(e) => tracing::error!("Failed to delete expired {table}: {}", e),
+        }
+    }
+
+    tracing::info!(
+        "Metric cleanup complete: deleted {} raw metric records, {} summaries",
+        deleted[0],
+        deleted[4]
+    );
+
+    Box::new(metrics)
+}
+
+pub async fn query_connection_ip_history<C: ConnectionTrait>(
+    conn: &C,
+    params: ConnectHistoryQueryParams,
+    is_src: bool,
+) -> Vec<IpHistoryStat> {
+    let mut where_clauses = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+    let col = if is_src { "src_ip" } else { "dst_ip" };
+
+    history_filters(&params, &mut where_clauses, &mut values);
+    if let Some(ip) = params.dst_ip.as_ref().filter(|ip| !ip.is_empty()) {
+        where_clauses.push("dst_ip LIKE ?".to_string());
+        values.push(format!("%{}%", ip).into());
+    }
+
+    let where_stmt = if where_clauses.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", where_clauses.join(" AND "))
+    };
+
+    let sort_col = match params.sort_key.unwrap_or(ConnectSortKey::Ingress) {
+        ConnectSortKey::Egress => "3",
+        _ => "2",
+    };
+    let sort_order_str = match params.sort_order.unwrap_or(SortOrder::Desc) {
+        SortOrder::Asc => "ASC",
+        SortOrder::Desc => "DESC",
+    };
+    let limit_val = params.limit.unwrap_or(10);
+
+    let stmt_str = format!(
+        "
+        SELECT
+            {},
+            SUM(total_ingress_bytes), SUM(total_egress_bytes),
+            SUM(total_ingress_pkts), SUM(total_egress_pkts),
+            COUNT(*)
+        FROM conn_summaries
+        {}
+        GROUP BY 1
+        ORDER BY {} {}
+        LIMIT {}
+    ",
+        col, where_stmt, sort_col, sort_order_str, limit_val
+    );
+
+    let rows = match conn.query_all(statement(&stmt_str, values)).await {
+        Ok(rows) => rows,
+        Err(e) => {
+            tracing::error!("Failed to execute IP history query: {}, error: {}", stmt_str, e);
+            return Vec::new();
+        }
+    };
+
+    rows.iter()
+        .filter_map(|row| {
+            Some(IpHistoryStat {
+                ip: parse_ip(row.try_get_by_index(0).ok()?),
+                flow_id: 0,
+                total_ingress_bytes: get_u64(row, 1).ok()?,
+                total_egress_bytes: get_u64(row, 2).ok()?,
+                total_ingress_pkts: get_u64(row, 3).ok()?,
+                total_egress_pkts: get_u64(row, 4).ok()?,
+                connect_count: get_u64(row, 5).ok()? as u32,
+            })
+        })
+        .collect()
+}
+
+pub async fn query_connection_domain_history<C: ConnectionTrait>(
+    conn: &C,
+    params: ConnectHistoryQueryParams,
+) -> Vec<DomainHistoryStat> {
+    let mut where_clauses = vec!["domain IS NOT NULL".to_string()];
+    let mut values: Vec<Value> = Vec::new();
+    history_filters(&params, &mut where_clauses, &mut values);
+
+    let sort_col = match params.sort_key.unwrap_or(ConnectSortKey::Ingress) {
+        ConnectSortKey::Egress => "3",
+        ConnectSortKey::Domain => "1",
+        ConnectSortKey::Time => "6",
+        _ => "2",
+    };
+    let sort_order_str = match params.sort_order.unwrap_or(SortOrder::Desc) {
+        SortOrder::Asc => "ASC",
+        SortOrder::Desc => "DESC",
+    };
+    let limit_val = params.limit.unwrap_or(10);
+
+    let stmt_str = format!(
+        "
+        SELECT
+            domain,
+            SUM(total_ingress_bytes), SUM(total_egress_bytes),
+            SUM(total_ingress_pkts), SUM(total_egress_pkts),
+            COUNT(*)
+        FROM conn_summaries
+        WHERE {}
+        GROUP BY 1
+        ORDER BY {} {}
+        LIMIT {}
+    ",
+        where_clauses.join(" AND "),
+        sort_col,
+        sort_order_str,
+        limit_val
+    );
+
+    let rows = match conn.query_all(statement(&stmt_str, values)).await {
+        Ok(rows) => rows,
+        Err(e) => {
+            tracing::error!("Failed to execute domain history query: {}, error: {}", stmt_str, e);
+            return Vec::new();
+        }
+    };
+
+    rows.iter()
+        .filter_map(|row| {
+            Some(DomainHistoryStat {
+                domain: row.try_get_by_index(0).ok()?,
+                total_ingress_bytes: get_u64(row, 1).ok()?,
+                total_egress_bytes: get_u64(row, 2).ok()?,
+                total_ingress_pkts: get_u64(row, 3).ok()?,
+                total_egress_pkts: get_u64(row, 4).ok()?,
+                connect_count: get_u64(row, 5).ok()? as u32,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{memory_db, metric, write_summaries};
+    use super::super::write_batch;
+    use super::*;
+
+    const HOUR: u64 = 3600000;
+
+    #[tokio::test]
+    async fn rollup_keeps_bucket_max_and_queries_by_key() {
+        let db = memory_db().await;
+        let now = 10 * HOUR;
+        let minute = now - 120000;
+
+        let rows = [
+            metric(1, "192.168.1.2", minute + 1000, 100),
+            metric(1, "192.168.1.2", minute + 2000, 300),
+            metric(1, "192.168.1.2", minute + 61000, 500),
+            metric(2, "192.168.1.3", minute + 1000, 70),
+        ];
+        let values = rows.iter().map(metric_values).collect();
+        write_batch(&db, METRIC_INSERT_SQL, values).await.unwrap();
+
+        perform_inner_db_rollup(&db, now).await.unwrap();
+        // 重复汇总结果不变
+        perform_inner_db_rollup(&db, now).await.unwrap();
+
+        let key = rows[0].key.clone();
+        let points = query_metric_by_key(&db, &key, MetricResolution::Minute).await;
+        let points: Vec<_> = points.iter().map(|p| (p.report_time, p.ingress_bytes)).collect();
+        assert_eq!(points, vec![(minute, 300), (minute + 60000, 500)]);
+
+        let points = query_metric_by_key(&db, &key, MetricResolution::Hour).await;
+        let points: Vec<_> = points.iter().map(|p| (p.report_time, p.ingress_bytes)).collect();
+        assert_eq!(points, vec![(9 * HOUR, 500)]);
+
+        aggregate_global_stats(&db, now).await.unwrap();
+        let stats = query_global_stats(&db).await;
+        assert_eq!(stats.total_ingress_bytes, 570);
+        assert_eq!(stats.total_connect_count, 2);
+        assert_eq!(stats.last_calculate_time, now);
+    }
+
+    #[tokio::test]
+    async fn history_queries_apply_filters() {
+        let db = memory_db().await;
+        let mut with_domain = metric(1, "192.168.1.2", 1000, 100);
+        with_domain.domain = Some("example.com".to_string());
+        let mut other_flow = metric(2, "192.168.1.2", 2000, 300);
+        other_flow.flow_id = 2;
+        let other_src = metric(3, "192.168.1.20", 3000, 200);
+        write_summaries(&db, &[with_domain, other_flow, other_src]).await;
+
+        let params = ConnectHistoryQueryParams {
+            src_ip: Some("192.168.1.2".to_string()),
+            sort_key: Some(ConnectSortKey::Ingress),
+            sort_order: Some(SortOrder::Desc),
+            ..Default::default()
+        };
+        let result = query_historical_summaries_complex(&db, params).await;
+        // 按 LIKE 匹配, 192.168.1.20 同样命中
+        let ingress: Vec<_> = result.iter().map(|s| s.total_ingress_bytes).collect();
+        assert_eq!(ingress, vec![300, 200, 100]);
+
+        let params = ConnectHistoryQueryParams {
+            flow_id: Some(1),
+            start_time: Some(1500),
+            ..Default::default()
+        };
+        let result = query_historical_summaries_complex(&db, params).await;
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].key.create_time, 3);
+
+        let stats =
+            query_connection_ip_history(&db, ConnectHistoryQueryParams::default(), true).await;
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].ip.to_string(), "192.168.1.2");
+        assert_eq!(stats[0].total_ingress_bytes, 400);
+        assert_eq!(stats[0].connect_count, 2);
+
+        let domains =
+            query_connection_domain_history(&db, ConnectHistoryQueryParams::default()).await;
+        assert_eq!(domains.len(), 1);
+        assert_eq!(domains[0].domain, "example.com");
+        assert_eq!(domains[0].total_ingress_bytes, 100);
+    }
+}
@@ -0,0 +1,530 @@
+use landscape_common::metric::connect::SortOrder;
+use landscape_common::metric::dns::{
+    DnsHistoryQueryParams, DnsHistoryResponse, DnsLightweightSummaryResponse, DnsMetric,
+    DnsSortKey, DnsStatEntry, DnsSummaryQueryParams, DnsSummaryResponse,
+};
+use sea_orm::{ConnectionTrait, DbErr, QueryResult, Value};
+
+use super::statement;
+use crate::metric::clean_ip_string;
+
+pub const DNS_INSERT_SQL: &str = "
+    INSERT INTO dns_metrics (
+        flow_id, domain, query_type, response_code, report_time, duration_ms, src_ip, answers, status
+    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+";
+
+/// 参与耗时统计的查询, 排除拦截、错误、本地应答与缓存命中
+const UPSTREAM_FILTER: &str =
+    "status NOT IN ('\"block\"', '\"filter\"', '\"error\"', '\"local\"', '\"hit\"')";
+
+pub fn create_tables_sql() -> Vec<String> {
+    vec![
+        "CREATE TABLE IF NOT EXISTS dns_metrics (
+            flow_id INTEGER,
+            domain TEXT,
+            query_type TEXT,
+            response_code TEXT,
+            report_time INTEGER,
+            duration_ms INTEGER,
+            src_ip TEXT,
+            answers TEXT,
+            status TEXT
+        )"
+        .to_string(),
+        "CREATE INDEX IF NOT EXISTS idx_dns_report_time ON dns_metrics (report_time)".to_string(),
+        "CREATE INDEX IF NOT EXISTS idx_dns_domain ON dns_metrics (domain)".to_string(),
+        "CREATE INDEX IF NOT EXISTS idx_dns_src_ip ON dns_metrics (src_ip)".to_string(),
+        "CREATE INDEX IF NOT EXISTS idx_dns_status ON dns_metrics (status)".to_string(),
+    ]
+}
+
+pub fn dns_values(metric: &DnsMetric) -> Vec<Value> {
+    vec![
+        (metric.flow_id as i64).into(),
+        metric.domain.clone().into(),
+        metric.query_type.clone().into(),
+        metric.response_code.clone().into(),
+        (metric.report_time as i64).into(),
+        (metric.duration_ms as i64).into(),
+        clean_ip_string(&metric.src_ip).into(),
+        serde_json::to_string(&metric.answers).unwrap_or_default().into(),
+        serde_json::to_string(&metric.status).unwrap_or_default().into(),
+    ]
+}
+
+fn get_usize(row: &QueryResult, idx: usize) -> Result<usize, DbErr> {
+    Ok(row.try_get_by_index::<Option<i64>>(idx)?.unwrap_or(0) as usize)
+}
+
+fn get_f64(row: &QueryResult, idx: usize) -> Result<f64, DbErr> {
+    Ok(row.try_get_by_index::<Option<f64>>(idx)?.unwrap_or(0.0))
+}
+
+pub async fn query_dns_history<C: ConnectionTrait>(
+    conn: &C,
+    mut params: DnsHistoryQueryParams,
+) -> DnsHistoryResponse {
+    let mut where_clauses = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+
+    if let Some(start) = params.start_time {
+        where_clauses.push("report_time >= ?");
+        values.push((start as i64).into());
+    }
+    if let Some(end) = params.end_time {
+        where_clauses.push("report_time <= ?");
+        values.push((end as i64).into());
+    }
+    if let Some(domain) = params.domain.as_mut() {
+        if !domain.is_empty() {
+            if domain.ends_with('.') && domain.len() > 1 {
+                domain.pop();
+            }
+            where_clauses.push("domain LIKE ?");
+            values.push(format!("%{}%", domain).into());
+        }
+    }
+    if let Some(ip) = params.src_ip.filter(|ip| !ip.is_empty()) {
+        where_clauses.push("src_ip LIKE ?");
+        values.push(format!("%{}%", ip).into());
+    }
+    if let Some(flow_id) = params.flow_id {
+        where_clauses.push("flow_id = ?");
+        values.push((flow_id as i64).into());
+    }
+    if let Some(qtype) = params.query_type.filter(|qtype| !qtype.is_empty()) {
+        where_clauses.push("query_type = ?");
+        values.push(qtype.into());
+    }
+    if let Some(status) = params.status {
+        where_clauses.push("status = ?");
+        values.push(serde_json::to_string(&status).unwrap_or_default().into());
+    }
+    if let Some(min_dur) = params.min_duration_ms {
+        where_clauses.push("duration_ms >= ?");
+        values.push((min_dur as i64).into());
+    }
+    if let Some(max_dur) = params.max_duration_ms {
+        where_clauses.push("duration_ms <= ?");
+        values.push((max_dur as i64).into());
+    }
+
+    let where_stmt = if where_clauses.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", where_clauses.join(" AND "))
+    };
+
+    let count_stmt_str = format!("SELECT COUNT(*) FROM dns_metrics {}", where_stmt);
+    let total = match conn.query_one(statement(&count_stmt_str, values.clone())).await {
+        Ok(Some(row)) => get_usize(&row, 0).unwrap_or(0),
+        Ok(None) => 0,
+        Err(e) => {
+            tracing::error!("Failed to execute DNS count SQL: {}, error: {}", count_stmt_str, e);
+            0
+        }
+    };
+
+    let sort_col = match params.sort_key.unwrap_or_default() {
+        DnsSortKey::Time => "report_time",
+        DnsSortKey::Domain => "domain",
+        DnsSortKey::Duration => "duration_ms",
+    };
+    let sort_order_str = match params.sort_order.unwrap_or_default() {
+        SortOrder::Asc => "ASC",
+        SortOrder::Desc => "DESC",
+    };
+    let order_by = if sort_col == "report_time" {
+        format!("{} {}", sort_col, sort_order_str)
+    } else {
+        format!("{} {}, report_time DESC", sort_col, sort_order_str)
+    };
+
+    let query_stmt_str = format!(
+        "
+        SELECT
+            flow_id, domain, query_type, response_code, report_time, duration_ms, src_ip, answers, status
+        FROM dns_metrics
+        {}
+        ORDER BY {}
+        LIMIT {} OFFSET {}
+    ",
+        where_stmt,
+        order_by,
+        params.limit.unwrap_or(20),
+        params.offset.unwrap_or(0)
+    );
+
+    let rows = match conn.query_all(statement(&query_stmt_str, values)).await {
+        Ok(rows) => rows,
+        Err(e) => {
+            tracing::error!("Failed to execute DNS SQL: {}, error: {}", query_stmt_str, e);
+            return DnsHistoryResponse { items: Vec::new(), total };
+        }
+    };
+
+    let items = rows
+        .iter()
+        .filter_map(|row| {
+            let answers: String = row.try_get_by_index(7).ok()?;
+            let status: Option<String> = row.try_get_by_index(8).ok()?;
+            Some(DnsMetric {
+                flow_id: get_usize(row, 0).ok()? as u32,
+                domain: row.try_get_by_index(1).ok()?,
+                query_type: row.try_get_by_index(2).ok()?,
+                response_code: row.try_get_by_index(3).ok()?,
+                report_time: get_usize(row, 4).ok()? as u64,
+                duration_ms: get_usize(row, 5).ok()? as u32,
+                src_ip: row
+                    .try_get_by_index::<String>(6)
+                    .ok()?
+                    .parse()
+                    .unwrap_or("0.0.0.0".parse().unwrap()),
+                answers: serde_json::from_str(&answers).unwrap_or_default(),
+                status: serde_json::from_str(&status.unwrap_or_else(|| "\"normal\"".to_string()))
+                    .unwrap_or_default(),
+                rule: None,
+                upstream: None,
+            })
+        })
+        .collect();
+
+    DnsHistoryResponse { items, total }
+}
+
+pub async fn cleanup_old_dns_metrics<C: ConnectionTrait>(conn: &C, cutoff: u64) {
+    if let Err(e) = conn
+        .execute(statement(
+            "DELETE FROM dns_metrics WHERE report_time < ?",
+            [(cutoff as i64).into()],
+        ))
+        .await
+    {
+        tracing::error!("Failed to delete expired DNS metrics: {}", e);
+    }
+}
+
+fn summary_filter(params: &DnsSummaryQueryParams) -> (String, Vec<Value>) {
+    let mut where_clauses = vec!["report_time >= ?", "report_time <= ?"];
+    let mut values: Vec<Value> =
+        vec![(params.start_time as i64).into(), (params.end_time as i64).into()];
+    if let Some(flow_id) = params.flow_id {
+        where_clauses.push("flow_id = ?");
+        values.push((flow_id as i64).into());
+    }
+    (format!("WHERE {}", where_clauses.join(" AND ")), values)
+}
+
+/// 与 DuckDB percentile_cont 一致的线性插值
+fn percentile_cont(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = p * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+async fn query_stats<C: ConnectionTrait>(
+    conn: &C,
+    where_stmt: &str,
+    values: &[Value],
+) -> DnsLightweightSummaryResponse {
+    let stats_sql = format!(
+        "SELECT
+            COUNT(*),
+            COUNT(CASE WHEN status = '\"hit\"' THEN 1 END),
+            COUNT(CASE WHEN status NOT IN ('\"block\"', '\"filter\"', '\"error\"') THEN 1 END),
+
+            COUNT(CASE WHEN query_type = 'A' AND status NOT IN ('\"block\"', '\"filter\"', '\"error\"') THEN 1 END),
+            COUNT(CASE WHEN query_type = 'A' AND status = '\"hit\"' THEN 1 END),
+
+            COUNT(CASE WHEN query_type = 'AAAA' AND status NOT IN ('\"block\"', '\"filter\"', '\"error\"') THEN 1 END),
+            COUNT(CASE WHEN query_type = 'AAAA' AND status = '\"hit\"' THEN 1 END),
+
+            COUNT(CASE WHEN query_type NOT IN ('A', 'AAAA') AND status NOT IN ('\"block\"', '\"filter\"', '\"error\"') THEN 1 END),
+            COUNT(CASE WHEN query_type NOT IN ('A', 'AAAA') AND status = '\"hit\"' THEN 1 END),
+
+            COUNT(CASE WHEN status = '\"block\"' THEN 1 END),
+            COUNT(CASE WHEN status = '\"filter\"' THEN 1 END),
+            COUNT(CASE WHEN status = '\"nxdomain\"' THEN 1 END),
+            COUNT(CASE WHEN status = '\"error\"' THEN 1 END),
+            AVG(CASE WHEN {UPSTREAM_FILTER} THEN duration_ms END),
+            MAX(CASE WHEN {UPSTREAM_FILTER} THEN duration_ms END)
+        FROM dns_metrics {where_stmt}"
+    );
+
+    let mut summary = match conn.query_one(statement(&stats_sql, values.to_vec())).await {
+        Ok(Some(row)) => (|| -> Result<DnsLightweightSummaryResponse, DbErr> {
+            Ok(DnsLightweightSummaryResponse {
+                total_queries: get_usize(&row, 0)?,
+                cache_hit_count: get_usize(&row, 1)?,
+                total_effective_queries: get_usize(&row, 2)?,
+                total_v4: get_usize(&row, 3)?,
+                hit_count_v4: get_usize(&row, 4)?,
+                total_v6: get_usize(&row, 5)?,
+                hit_count_v6: get_usize(&row, 6)?,
+                total_other: get_usize(&row, 7)?,
+                hit_count_other: get_usize(&row, 8)?,
+                block_count: get_usize(&row, 9)?,
+                filter_count: get_usize(&row, 10)?,
+                nxdomain_count: get_usize(&row, 11)?,
+                error_count: get_usize(&row, 12)?,
+                avg_duration_ms: get_f64(&row, 13)?,
+                max_duration_ms: get_usize(&row, 14)? as f64,
+                ..Default::default()
+            })
+        })()
+        .unwrap_or_default(),
+        Ok(None) => DnsLightweightSummaryResponse::default(),
+        Err(e) => {
+            tracing::error!("Failed to execute DNS stats SQL: {}", e);
+            DnsLightweightSummaryResponse::default()
+        }
+    };
+
+    // SQLite 没有 percentile_cont, 取出耗时后在内存中计算
+    let durations_sql = format!(
+        "SELECT duration_ms FROM dns_metrics {where_stmt} AND {UPSTREAM_FILTER} ORDER BY duration_ms"
+    );
+    match conn.query_all(statement(&durations_sql, values.to_vec())).await {
+        Ok(rows) => {
+            let durations: Vec<f64> =
+                rows.iter().filter_map(|row| get_usize(row, 0).ok()).map(|d| d as f64).collect();
+            summary.p50_duration_ms = percentile_cont(&durations, 0.5);
+            summary.p95_duration_ms = percentile_cont(&durations, 0.95);
+            summary.p99_duration_ms = percentile_cont(&durations, 0.99);
+        }
+        Err(e) => tracing::error!("Failed to execute DNS duration SQL: {}", e),
+    }
+
+    summary
+}
+
+async fn query_top<C: ConnectionTrait>(
+    conn: &C,
+    name: &str,
+    sql: &str,
+    values: &[Value],
+    with_value: bool,
+) -> Vec<DnsStatEntry> {
+    match conn.query_all(statement(sql, values.to_vec())).await {
+        Ok(rows) => rows
+            .iter()
+            .filter_map(|row| {
+                Some(DnsStatEntry {
+                    name: row.try_get_by_index(0).ok()?,
+                    count: get_usize(row, 1).ok()?,
+                    value: if with_value { Some(get_f64(row, 2).ok()?) } else { None },
+                })
+            })
+            .collect(),
+        Err(e) => {
+            tracing::error!("Failed to execute {} query: {}", name, e);
+            Vec::new()
+        }
+    }
+}
+
+pub async fn query_dns_summary<C: ConnectionTrait>(
+    conn: &C,
+    params: DnsSummaryQueryParams,
+) -> DnsSummaryResponse {
+    let (where_stmt, values) = summary_filter(&params);
+    let stats = query_stats(conn, &where_stmt, &values).await;
+
+    let top_clients = query_top(
+        conn,
+        "top_clients",
+        &format!(
+            "SELECT src_ip, COUNT(*) as c FROM dns_metrics {} GROUP BY src_ip ORDER BY c DESC LIMIT 10",
+            where_stmt
+        ),
+        &values,
+        false,
+    )
+    .await;
+    let top_domains = query_top(
+        conn,
+        "top_domains",
+        &format!(
+            "SELECT domain, COUNT(*) as c FROM dns_metrics {} GROUP BY domain ORDER BY c DESC LIMIT 10",
+            where_stmt
+        ),
+        &values,
+        false,
+    )
+    .await;
+    let top_blocked = query_top(
+        conn,
+        "top_blocked",
+        &format!(
+            "SELECT domain, COUNT(*) as c FROM dns_metrics {} AND status = '\"block\"' GROUP BY domain ORDER BY c DESC LIMIT 10",
+            where_stmt
+        ),
+        &values,
+        false,
+    )
+    .await;
+    let slowest_domains = query_top(
+        conn,
+        "slowest_domains",
+        &format!(
+            "SELECT domain, COUNT(*) as c, AVG(duration_ms) as avg_d FROM dns_metrics {} GROUP BY domain HAVING c > 2 ORDER BY avg_d DESC LIMIT 10",
+            where_stmt
+        ),
+        &values,
+        true,
+    )
+    .await;
+
+    DnsSummaryResponse {
+        total_queries: stats.total_queries,
+        total_effective_queries: stats.total_effective_queries,
+        cache_hit_count: stats.cache_hit_count,
+        hit_count_v4: stats.hit_count_v4,
+        hit_count_v6: stats.hit_count_v6,
+        hit_count_other: stats.hit_count_other,
+        total_v4: stats.total_v4,
+        total_v6: stats.total_v6,
+        total_other: stats.total_other,
+        block_count: stats.block_count,
+        filter_count: stats.filter_count,
+        nxdomain_count: stats.nxdomain_count,
+        error_count: stats.error_count,
+        avg_duration_ms: stats.avg_duration_ms,
+        p50_duration_ms: stats.p50_duration_ms,
+        p95_duration_ms: stats.p95_duration_ms,
+        p99_duration_ms: stats.p99_duration_ms,
+        max_duration_ms: stats.max_duration_ms,
+        top_clients,
+        top_domains,
+        top_blocked,
+        slowest_domains,
+    }
+}
+
+pub async fn query_dns_lightweight_summary<C: ConnectionTrait>(
+    conn: &C,
+    params: DnsSummaryQueryParams,
+) -> DnsLightweightSummaryResponse {
+    let (where_stmt, values) = summary_filter(&params);
+    query_stats(conn, &where_stmt, &values).await
+}
+
+#[cfg(test)]
+mod tests {
+    use landscape_common::metric::dns::DnsResultStatus;
+
+    use super::super::tests::{count, memory_db};
+    use super::super::write_batch;
+    use super::*;
+
+    fn dns(domain: &str, status: DnsResultStatus, report_time: u64, duration_ms: u32) -> DnsMetric {
+        DnsMetric {
+            flow_id: 0,
+            domain: domain.to_string(),
+            query_type: "A".to_string(),
+            response_code: "NoError".to_string(),
+            status,
+            report_time,
+            duration_ms,
+            src_ip: "::ffff:192.168.1.2".parse().unwrap(),
+            answers: vec!["1.1.1.1".to_string()],
+            rule: None,
+            upstream: None,
+        }
+    }
+
+    async fn insert(db: &sea_orm::DatabaseConnection) {
+        let rows = [
+            dns("example.com", DnsResultStatus::Normal, 1000, 10),
+            dns("api.example.com", DnsResultStatus::Normal, 2000, 30),
+            dns("api.example.com", DnsResultStatus::Hit, 3000, 0),
+            dns("ads.net", DnsResultStatus::Block, 4000, 0),
+        ];
+        write_batch(db, DNS_INSERT_SQL, rows.iter().map(dns_values).collect()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn history_filters_and_pages() {
+        let db = memory_db().await;
+        insert(&db).await;
+
+        let params = DnsHistoryQueryParams {
+            domain: Some("example.com.".to_string()),
+            sort_key: Some(DnsSortKey::Duration),
+            sort_order: Some(SortOrder::Desc),
+            ..Default::default()
+        };
+        let result = query_dns_history(&db, params).await;
+        assert_eq!(result.total, 3);
+        let durations: Vec<_> = result.items.iter().map(|m| m.duration_ms).collect();
+        assert_eq!(durations, vec![30, 10, 0]);
+        assert_eq!(result.items[0].answers, vec!["1.1.1.1".to_string()]);
+
+        let params = DnsHistoryQueryParams {
+            status: Some(DnsResultStatus::Block),
+            src_ip: Some("192.168.1.2".to_string()),
+            ..Default::default()
+        };
+        let result = query_dns_history(&db, params).await;
+        assert_eq!(result.total, 1);
+        assert_eq!(result.items[0].domain, "ads.net");
+        assert_eq!(result.items[0].status, DnsResultStatus::Block);
+
+        let params = DnsHistoryQueryParams {
+            start_time: Some(1500),
+            limit: Some(1),
+            offset: Some(1),
+            sort_order: Some(SortOrder::Asc),
+            ..Default::default()
+        };
+        let result = query_dns_history(&db, params).await;
+        assert_eq!(result.total, 3);
+        assert_eq!(result.items.len(), 1);
+        assert_eq!(result.items[0].report_time, 3000);
+
+        cleanup_old_dns_metrics(&db, 2500).await;
+        assert_eq!(count(&db, "dns_metrics").await, 2);
+    }
+
+    #[tokio::test]
+    async fn summary_counts_only_upstream_latency() {
+        let db = memory_db().await;
+        insert(&db).await;
+
+        let params = DnsSummaryQueryParams { start_time: 0, end_time: 10000, flow_id: None };
+        let summary = query_dns_summary(&db, params).await;
+        assert_eq!(summary.total_queries, 4);
+        assert_eq!(summary.total_effective_queries, 3);
+        assert_eq!(summary.cache_hit_count, 1);
+        assert_eq!(summary.block_count, 1);
+        // 缓存命中与拦截不计入耗时
+        assert_eq!(summary.avg_duration_ms, 20.0);
+        assert_eq!(summary.p50_duration_ms, 20.0);
+        assert_eq!(summary.max_duration_ms, 30.0);
+        assert_eq!(summary.top_domains[0].name, "api.example.com");
+        assert_eq!(summary.top_domains[0].count, 2);
+        assert_eq!(summary.top_blocked.len(), 1);
+        assert_eq!(summary.top_clients[0].name, "192.168.1.2");
+
+        let params = DnsSummaryQueryParams {
+            start_time: 1500,
+            end_time: 10000,
+            flow_id: Some(0),
+        };
+        let summary = query_dns_lightweight_summary(&db, params).await;
+        assert_eq!(summary.total_queries, 3);
+        assert_eq!(summary.avg_duration_ms, 30.0);
+    }
+
+    #[test]
+    fn percentile_interpolates_like_duckdb() {
+        assert_eq!(percentile_cont(&[], 0.5), 0.0);
+        assert_eq!(percentile_cont(&[10.0, 20.0, 30.0, 40.0], 0.5), 25.0);
+        assert_eq!(percentile_cont(&[10.0, 20.0, 30.0, 40.0], 1.0), 40.0);
+    }
+}
@@ -0,0 +1,614 @@
+//! 未编译 DuckDB 时使用的轻量指标存储, 基于 SQLite, 提供与 DuckDB 相同的查询接口
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use landscape_common::config::MetricRuntimeConfig;
+use landscape_common::metric::connect::{
+    ConnectGlobalStats, ConnectHistoryQueryParams, ConnectHistoryStatus, ConnectKey, ConnectMetric,
+    ConnectMetricPoint, ConnectRealtimeStatus, ConnectStatusType, DomainHistoryStat,
+    IpAggregatedStats, IpHistoryStat, IpRealtimeStat, MetricResolution,
+};
+use landscape_common::metric::dns::{
+    DnsHistoryQueryParams, DnsHistoryResponse, DnsLightweightSummaryResponse, DnsMetric,
+    DnsSummaryQueryParams, DnsSummaryResponse,
+};
+use landscape_common::metric::traffic::{
+    TrafficHistoryQueryParams, TrafficPoint, TrafficSample, TrafficTargetKind, TrafficTotal,
+};
+use landscape_common::utils::time::get_current_time_ms;
+use sea_orm::{
+    ConnectOptions, ConnectionTrait, Database, DatabaseConnection, DbBackend, DbErr, Statement,
+    TransactionTrait, Value,
+};
+use tokio::sync::{mpsc, oneshot};
+
+pub mod connect;
+pub mod dns;
+pub mod traffic;
+
+const A_MIN: u64 = 60 * 1000;
+const MS_PER_MINUTE: u64 = A_MIN;
+const MS_PER_DAY: u64 = 24 * 60 * A_MIN;
+const STALE_TIMEOUT_MS: u64 = 5 * A_MIN;
+
+pub(crate) fn statement(sql: &str, values: impl IntoIterator<Item = Value>) -> Statement {
+    Statement::from_sql_and_values(DbBackend::Sqlite, sql, values)
+}
+
+/// Database operation messages
+pub enum DBMessage {
+    InsertMetric(ConnectMetric),
+    InsertDnsMetric(DnsMetric),
+    InsertTrafficSamples(Vec<TrafficSample>),
+    CollectAndCleanupOldMetrics {
+        cutoff_raw: u64,
+        cutoff_1m: u64,
+        cutoff_1h: u64,
+        cutoff_1d: u64,
+        cutoff_dns: u64,
+        resp: oneshot::Sender<Box<Vec<ConnectMetric>>>,
+    },
+}
+
+#[derive(Clone)]
+pub struct RealtimeState {
+    pub status: ConnectRealtimeStatus,
+    pub last_ingress_bytes: u64,
+    pub last_egress_bytes: u64,
+    pub last_ingress_pkts: u64,
+    pub last_egress_pkts: u64,
+}
+
+type RealtimeCache = Arc<Mutex<HashMap<ConnectKey, RealtimeState>>>;
+
+#[derive(Clone)]
+pub struct SqliteMetricStore {
+    tx: mpsc::Sender<DBMessage>,
+    pub db_path: PathBuf,
+    pub config: MetricRuntimeConfig,
+    pub db: DatabaseConnection,
+    pub realtime_cache: RealtimeCache,
+}
+
+/// 更新实时连接缓存, 根据两次上报的差值计算速率
+fn update_realtime_cache(cache: &RealtimeCache, metric: &ConnectMetric) {
+    let mut cache = cache.lock().unwrap();
+    let now = metric.report_time;
+    match cache.get_mut(&metric.key) {
+        Some(e) => {
+            if now > e.status.last_report_time {
+                let delta_t = now - e.status.last_report_time;
+                e.status.ingress_bps =
+                    metric.ingress_bytes.saturating_sub(e.last_ingress_bytes) * 8000 / delta_t;
+                e.status.egress_bps =
+                    metric.egress_bytes.saturating_sub(e.last_egress_bytes) * 8000 / delta_t;
+                e.status.ingress_pps =
+                    metric.ingress_packets.saturating_sub(e.last_ingress_pkts) * 1000 / delta_t;
+                e.status.egress_pps =
+                    metric.egress_packets.saturating_sub(e.last_egress_pkts) * 1000 / delta_t;
+                e.status.last_report_time = now;
+                if metric.status != ConnectStatusType::Unknow {
+                    e.status.status = metric.status.clone();
+                }
+                if e.status.domain.is_none() {
+                    e.status.domain = metric.domain.clone();
+                }
+                e.last_ingress_bytes = e.last_ingress_bytes.max(metric.ingress_bytes);
+                e.last_egress_bytes = e.last_egress_bytes.max(metric.egress_bytes);
+                e.last_ingress_pkts = e.last_ingress_pkts.max(metric.ingress_packets);
+                e.last_egress_pkts = e.last_egress_pkts.max(metric.egress_packets);
+            }
+        }
+        None => {
+            cache.insert(
+                metric.key.clone(),
+                RealtimeState {
+                    status: ConnectRealtimeStatus {
+                        key: metric.key.clone(),
+                        src_ip: metric.src_ip,
+                        dst_ip: metric.dst_ip,
+                        src_port: metric.src_port,
+                        dst_port: metric.dst_port,
+                        l4_proto: metric.l4_proto,
+                        l3_proto: metric.l3_proto,
+                        flow_id: metric.flow_id,
+                        trace_id: metric.trace_id,
+                        gress: metric.gress,
+                        domain: metric.domain.clone(),
+                        create_time_ms: metric.create_time_ms,
+                        ingress_bps: 0,
+                        egress_bps: 0,
+                        ingress_pps: 0,
+                        egress_pps: 0,
+                        last_report_time: now,
+                        status: metric.status.clone(),
+                    },
+                    last_ingress_bytes: metric.ingress_bytes,
+                    last_egress_bytes: metric.egress_bytes,
+                    last_ingress_pkts: metric.ingress_packets,
+                    last_egress_pkts: metric.egress_packets,
+                },
+            );
+        }
+    }
+}
+
+/// 在一个事务内批量写入
+async fn write_batch(
+    db: &DatabaseConnection,
+    sql: &str,
+    rows: Vec<Vec<Value>>,
+) -> Result<(), DbErr> {
+    if rows.is_empty() {
+        return Ok(());
+    }
+    let txn = db.begin().await?;
+    for values in rows {
+        txn.execute(statement(sql, values)).await?;
+    }
+    txn.commit().await
+}
+
+#[derive(Default)]
+struct PendingWrites {
+    metrics: Vec<Vec<Value>>,
+    dns: Vec<Vec<Value>>,
+}
+
+impl PendingWrites {
+    fn len(&self) -> usize {
+        self.metrics.len() + self.dns.len()
+    }
+
+    async fn flush(&mut self, db: &DatabaseConnection) {
+        if let Err(e) =
+            write_batch(db, connect::METRIC_INSERT_SQL, std::mem::take(&mut self.metrics)).await
+        {
+            tracing::error!("Failed to write connect metrics: {}", e);
+        }
+        if let Err(e) = write_batch(db, dns::DNS_INSERT_SQL, std::mem::take(&mut self.dns)).await {
+            tracing::error!("Failed to write DNS metrics: {}", e);
+        }
+    }
+}
+
+async fn sync_summaries(db: &DatabaseConnection, cache: &RealtimeCache) {
+    let now_ms = get_current_time_ms().unwrap_or_default();
+    let cutoff_live = now_ms.saturating_sub(STALE_TIMEOUT_MS);
+
+    let rows: Vec<Vec<Value>> = {
+        let cache = cache.lock().unwrap();
+        cache.values().map(connect::summary_values).collect()
+    };
+    let count = rows.len();
+    match write_batch(db, connect::SUMMARY_INSERT_SQL, rows).await {
+        Ok(()) => tracing::debug!("Synced {} summaries to disk", count),
+        Err(e) => tracing::error!("Sync summary failed: {}", e),
+    }
+
+    cache.lock().unwrap().retain(|_, v| {
+        let is_disabled = v.status.status == ConnectStatusType::Disabled;
+        let is_stale = v.status.last_report_time < cutoff_live;
+        !is_disabled && !is_stale
+    });
+}
+
+async fn cleanup(
+    db: &DatabaseConnection,
+    cutoff_raw: u64,
+    cutoff_1m: u64,
+    cutoff_1h: u64,
+    cutoff_1d: u64,
+    cutoff_dns: u64,
+) -> Box<Vec<ConnectMetric>> {
+    dns::cleanup_old_dns_metrics(db, cutoff_dns).await;
+    traffic::cleanup_old_traffic_metrics(db, cutoff_raw, cutoff_1m, cutoff_1h, cutoff_1d).await;
+    connect::collect_and_cleanup_old_metrics(db, cutoff_raw, cutoff_1m, cutoff_1h, cutoff_1d).await
+}
+
+async fn create_tables(db: &DatabaseConnection) -> Result<(), DbErr> {
+    let mut sqls = connect::create_tables_sql();
+    sqls.extend(dns::create_tables_sql());
+    sqls.extend(traffic::create_tables_sql());
+    for sql in sqls {
+        db.execute_unprepared(&sql).await?;
+    }
+    Ok(())
+}
+
+async fn run_db_writer(
+    mut rx: mpsc::Receiver<DBMessage>,
+    metric_config: MetricRuntimeConfig,
+    db: DatabaseConnection,
+    realtime_cache: RealtimeCache,
+) {
+    let mut flush_interval = tokio::time::interval(std::time::Duration::from_secs(
+        metric_config.flush_interval_secs.max(1),
+    ));
+    let mut cleanup_interval = tokio::time::interval(std::time::Duration::from_secs(
+        landscape_common::DEFAULT_METRIC_CLEANUP_INTERVAL_SECS,
+    ));
+    let mut summary_sync_interval = tokio::time::interval(std::time::Duration::from_secs(60));
+    let mut pending = PendingWrites::default();
+
+    loop {
+        tokio::select! {
+            _ = summary_sync_interval.tick() => {
+                pending.flush(&db).await;
+                sync_summaries(&db, &realtime_cache).await;
+            }
+            _ = flush_interval.tick() => {
+                pending.flush(&db).await;
+            }
+            _ = cleanup_interval.tick() => {
+                pending.flush(&db).await;
+                let now_ms = get_current_time_ms().unwrap_or_default();
+                let cutoff_raw = now_ms.saturating_sub(metric_config.conn_retention_mins * MS_PER_MINUTE);
+                let cutoff_1m = now_ms.saturating_sub(metric_config.conn_retention_minute_days * MS_PER_DAY);
+                let cutoff_1h = now_ms.saturating_sub(metric_config.conn_retention_hour_days * MS_PER_DAY);
+                let cutoff_1d = now_ms.saturating_sub(metric_config.conn_retention_day_days * MS_PER_DAY);
+                let cutoff_dns = now_ms.saturating_sub(metric_config.dns_retention_days * MS_PER_DAY);
+
+                if let Err(e) = connect::perform_inner_db_rollup(&db, now_ms).await {
+                    tracing::error!("Failed to rollup connect metrics: {}", e);
+                }
+                if let Err(e) = traffic::perform_traffic_rollup(&db, now_ms).await {
+                    tracing::error!("Failed to rollup traffic metrics: {}", e);
+                }
+                let _ = cleanup(&db, cutoff_raw, cutoff_1m, cutoff_1h, cutoff_1d, cutoff_dns).await;
+                if let Err(e) = connect::aggregate_global_stats(&db, now_ms).await {
+                    tracing::error!("Failed to aggregate global stats: {}", e);
+                }
+
+                tracing::info!(
+                    "Auto cleanup metrics, raw: {}, 1m: {}, 1h: {}, 1d: {}, dns: {}",
+                    cutoff_raw, cutoff_1m, cutoff_1h, cutoff_1d, cutoff_dns
+                );
+            }
+            msg_opt = rx.recv() => {
+                let Some(msg) = msg_opt else {
+                    pending.flush(&db).await;
+                    break;
+                };
+                match msg {
+                    DBMessage::InsertMetric(metric) => {
+                        pending.metrics.push(connect::metric_values(&metric));
+                        update_realtime_cache(&realtime_cache, &metric);
+                    }
+                    DBMessage::InsertDnsMetric(metric) => {
+                        pending.dns.push(dns::dns_values(&metric));
+                    }
+                    DBMessage::InsertTrafficSamples(samples) => {
+                        let rows = samples.iter().map(traffic::traffic_values).collect();
+                        if let Err(e) = write_batch(&db, traffic::TRAFFIC_INSERT_SQL, rows).await {
+                            tracing::error!("Failed to insert traffic samples: {}", e);
+                        }
+                    }
+                    DBMessage::CollectAndCleanupOldMetrics {
+                        cutoff_raw,
+                        cutoff_1m,
+                        cutoff_1h,
+                        cutoff_1d,
+                        cutoff_dns,
+                        resp,
+                    } => {
+                        pending.flush(&db).await;
+                        let result =
+                            cleanup(&db, cutoff_raw, cutoff_1m, cutoff_1h, cutoff_1d, cutoff_dns).await;
+                        let _ = resp.send(result);
+                    }
+                }
+                if pending.len() >= metric_config.batch_size {
+                    pending.flush(&db).await;
+                }
+            }
+        }
+    }
+}
+
+impl SqliteMetricStore {
+    pub async fn new(base_path: PathBuf, config: MetricRuntimeConfig) -> Self {
+        let db_path = base_path
+            .join(format!("metrics_v{}.sqlite", landscape_common::LANDSCAPE_METRIC_DB_VERSION));
+        if let Some(parent) = db_path.parent() {
+            if !parent.exists() {
+                std::fs::create_dir_all(parent).expect("Failed to create base directory");
+            }
+        }
+
+        let mut opt = ConnectOptions::new(format!("sqlite://{}?mode=rwc", db_path.display()));
+        opt.max_connections(config.max_threads.max(2) as u32).sqlx_logging(false);
+        let db = Database::connect(opt).await.expect("Failed to open metric database");
+
+        db.execute_unprepared("PRAGMA journal_mode=WAL").await.expect("Failed to set journal mode");
+        create_tables(&db).await.expect("Failed to create metric tables");
+
+        let (tx, rx) = mpsc::channel::<DBMessage>(1024);
+        let realtime_cache: RealtimeCache = Arc::new(Mutex::new(HashMap::new()));
+        tokio::spawn(run_db_writer(rx, config.clone(), db.clone(), realtime_cache.clone()));
+
+        SqliteMetricStore { tx, db_path, config, db, realtime_cache }
+    }
+
+    pub async fn insert_metric(&self, metric: ConnectMetric) {
+        let _ = self.tx.send(DBMessage::InsertMetric(metric)).await;
+    }
+
+    pub async fn connect_infos(&self) -> Vec<ConnectRealtimeStatus> {
+        let mut infos: Vec<_> =
+            self.realtime_cache.lock().unwrap().values().map(|v| v.status.clone()).collect();
+        infos.sort_by(|a, b| b.last_report_time.cmp(&a.last_report_time));
+        infos
+    }
+
+    pub async fn get_realtime_ip_stats(&self, is_src: bool) -> Vec<IpRealtimeStat> {
+        let mut stats_map: HashMap<IpAddr, IpAggregatedStats> = HashMap::new();
+        for state in self.realtime_cache.lock().unwrap().values() {
+            let status = &state.status;
+            let ip = if is_src { status.src_ip } else { status.dst_ip };
+
+            let stats = stats_map.entry(ip).or_default();
+            stats.ingress_bps += status.ingress_bps;
+            stats.egress_bps += status.egress_bps;
+            stats.ingress_pps += status.ingress_pps;
+            stats.egress_pps += status.egress_pps;
+            stats.active_conns += 1;
+        }
+
+        stats_map.into_iter().map(|(ip, stats)| IpRealtimeStat { ip, stats }).collect()
+    }
+
+    pub async fn query_metric_by_key(
+        &self,
+        key: ConnectKey,
+        resolution: MetricResolution,
+    ) -> Vec<ConnectMetricPoint> {
+        connect::query_metric_by_key(&self.db, &key, resolution).await
+    }
+
+    pub async fn collect_and_cleanup_old_metrics(
+        &self,
+        cutoff_raw: u64,
+        cutoff_1m: u64,
+        cutoff_1h: u64,
+        cutoff_1d: u64,
+    ) -> Box<Vec<ConnectMetric>> {
+        let (resp, rx) = oneshot::channel();
+        let now_ms = get_current_time_ms().unwrap_or_default();
+        let cutoff_dns = now_ms.saturating_sub(self.config.dns_retention_days * MS_PER_DAY);
+
+        let _ = self
+            .tx
+            .send(DBMessage::CollectAndCleanupOldMetrics {
+                cutoff_raw,
+                cutoff_1m,
+                cutoff_1h,
+                cutoff_1d,
+                cutoff_dns,
+                resp,
+            })
+            .await;
+        rx.await.unwrap_or_default()
+    }
+
+    pub async fn history_summaries_complex(
+        &self,
+        params: ConnectHistoryQueryParams,
+    ) -> Vec<ConnectHistoryStatus> {
+        connect::query_historical_summaries_complex(&self.db, params).await
+    }
+
+    pub async fn history_src_ip_stats(
+        &self,
+        params: ConnectHistoryQueryParams,
+    ) -> Vec<IpHistoryStat> {
+        connect::query_connection_ip_history(&self.db, params, true).await
+    }
+
+    pub async fn history_dst_ip_stats(
+        &self,
+        params: ConnectHistoryQueryParams,
+    ) -> Vec<IpHistoryStat> {
+        connect::query_connection_ip_history(&self.db, params, false).await
+    }
+
+    pub async fn history_domain_stats(
+        &self,
+        params: ConnectHistoryQueryParams,
+    ) -> Vec<DomainHistoryStat> {
+        connect::query_connection_domain_history(&self.db, params).await
+    }
+
+    pub async fn get_global_stats(&self) -> ConnectGlobalStats {
+        connect::query_global_stats(&self.db).await
+    }
+
+    pub async fn insert_traffic_samples(&self, samples: Vec<TrafficSample>) {
+        let _ = self.tx.send(DBMessage::InsertTrafficSamples(samples)).await;
+    }
+
+    pub async fn query_traffic_history(
+        &self,
+        params: TrafficHistoryQueryParams,
+        resolution: MetricResolution,
+    ) -> Vec<TrafficPoint> {
+        traffic::query_traffic_history(&self.db, &params, resolution).await
+    }
+
+    pub async fn query_traffic_totals(
+        &self,
+        kind: TrafficTargetKind,
+        start_time: u64,
+        end_time: u64,
+        resolution: MetricResolution,
+    ) -> Vec<TrafficTotal> {
+        traffic::query_traffic_totals(&self.db, kind, start_time, end_time, resolution).await
+    }
+
+    pub async fn insert_dns_metric(&self, mut metric: DnsMetric) {
+        if metric.domain.ends_with('.') && metric.domain.len() > 1 {
+            metric.domain.pop();
+        }
+        let _ = self.tx.send(DBMessage::InsertDnsMetric(metric)).await;
+    }
+
+    pub async fn query_dns_history(&self, params: DnsHistoryQueryParams) -> DnsHistoryResponse {
+        dns::query_dns_history(&self.db, params).await
+    }
+
+    pub async fn get_dns_summary(&self, params: DnsSummaryQueryParams) -> DnsSummaryResponse {
+        dns::query_dns_summary(&self.db, params).await
+    }
+
+    pub async fn get_dns_lightweight_summary(
+        &self,
+        params: DnsSummaryQueryParams,
+    ) -> DnsLightweightSummaryResponse {
+        dns::query_dns_lightweight_summary(&self.db, params).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 内存数据库只能有一个连接, 否则每个连接各自一份数据
+    pub(super) async fn memory_db() -> DatabaseConnection {
+        let mut opt = ConnectOptions::new("sqlite::memory:");
+        opt.max_connections(1).min_connections(1).sqlx_logging(false);
+        let db = Database::connect(opt).await.unwrap();
+        create_tables(&db).await.unwrap();
+        db
+    }
+
+    pub(super) async fn count(db: &DatabaseConnection, table: &str) -> u64 {
+        let sql = format!("SELECT COUNT(*) FROM {table}");
+        let row = db.query_one(statement(&sql, [])).await.unwrap().unwrap();
+        row.try_get_by_index::<i64>(0).unwrap() as u64
+    }
+
+    pub(super) fn metric(
+        create_time: u64,
+        src_ip: &str,
+        report_time: u64,
+        ingress_bytes: u64,
+    ) -> ConnectMetric {
+        ConnectMetric {
+            key: ConnectKey { create_time, cpu_id: 0 },
+            src_ip: src_ip.parse().unwrap(),
+            dst_ip: "1.1.1.1".parse().unwrap(),
+            src_port: 50000,
+            dst_port: 443,
+            l4_proto: 6,
+            l3_proto: 0,
+            flow_id: 1,
+            trace_id: 0,
+            gress: 0,
+            ifindex: 0,
+            domain: None,
+            report_time,
+            create_time_ms: create_time,
+            ingress_bytes,
+            ingress_packets: ingress_bytes / 100,
+            egress_bytes: ingress_bytes / 2,
+            egress_packets: 1,
+            status: ConnectStatusType::Active,
+        }
+    }
+
+    /// 按实时缓存的方式生成连接汇总并写入
+    pub(super) async fn write_summaries(db: &DatabaseConnection, metrics: &[ConnectMetric]) {
+        let cache: RealtimeCache = Arc::new(Mutex::new(HashMap::new()));
+        for metric in metrics {
+            update_realtime_cache(&cache, metric);
+        }
+        let rows = cache.lock().unwrap().values().map(connect::summary_values).collect();
+        write_batch(db, connect::SUMMARY_INSERT_SQL, rows).await.unwrap();
+    }
+
+    #[test]
+    fn realtime_cache_computes_rate_from_delta() {
+        let cache: RealtimeCache = Arc::new(Mutex::new(HashMap::new()));
+        let first = metric(1, "192.168.1.2", 1000, 1000);
+        update_realtime_cache(&cache, &first);
+
+        let mut second = metric(1, "192.168.1.2", 3000, 3000);
+        second.status = ConnectStatusType::Unknow;
+        second.domain = Some("example.com".to_string());
+        update_realtime_cache(&cache, &second);
+
+        // 早于上次上报的数据不参与计算
+        update_realtime_cache(&cache, &metric(1, "192.168.1.2", 2000, 9000));
+
+        let cache = cache.lock().unwrap();
+        let state = cache.get(&first.key).unwrap();
+        assert_eq!(state.status.ingress_bps, 2000 * 8000 / 2000);
+        assert_eq!(state.status.ingress_pps, 20 * 1000 / 2000);
+        assert_eq!(state.status.last_report_time, 3000);
+        assert_eq!(state.status.status, ConnectStatusType::Active);
+        assert_eq!(state.status.domain.as_deref(), Some("example.com"));
+        assert_eq!(state.last_ingress_bytes, 3000);
+    }
+
+    #[tokio::test]
+    async fn cleanup_applies_retention_per_table() {
+        let db = memory_db().await;
+
+        let old = metric(1, "192.168.1.2", 1000, 100);
+        let new = metric(1, "192.168.1.2", 5000, 200);
+        write_summaries(&db, &[old.clone(), new.clone()]).await;
+        write_batch(
+            &db,
+            connect::METRIC_INSERT_SQL,
+            vec![connect::metric_values(&old), connect::metric_values(&new)],
+        )
+        .await
+        .unwrap();
+        for report_time in [1000, 5000] {
+            let sample = TrafficSample {
+                kind: TrafficTargetKind::Iface,
+                target: "eth0".to_string(),
+                mac: None,
+                report_time,
+                rx_bytes: 1,
+                tx_bytes: 1,
+                rx_packets: 1,
+                tx_packets: 1,
+            };
+            let dns = DnsMetric {
+                flow_id: 0,
+                domain: "example.com".to_string(),
+                query_type: "A".to_string(),
+                response_code: "NoError".to_string(),
+                status: Default::default(),
+                report_time,
+                duration_ms: 1,
+                src_ip: "192.168.1.2".parse().unwrap(),
+                answers: vec![],
+                rule: None,
+                upstream: None,
+            };
+            write_batch(&db, traffic::TRAFFIC_INSERT_SQL, vec![traffic::traffic_values(&sample)])
+                .await
+                .unwrap();
+            write_batch(&db, dns::DNS_INSERT_SQL, vec![dns::dns_values(&dns)]).await.unwrap();
+        }
+
+        let expired = cleanup(&db, 3000, 3000, 3000, 3000, 3000).await;
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].report_time, 1000);
+        assert_eq!(expired[0].src_ip, old.src_ip);
+
+        assert_eq!(count(&db, "conn_metrics").await, 1);
+        assert_eq!(count(&db, "traffic_metrics").await, 1);
+        assert_eq!(count(&db, "dns_metrics").await, 1);
+        // 汇总按最后上报时间保留
+        assert_eq!(count(&db, "conn_summaries").await, 1);
+
+        cleanup(&db, 9000, 9000, 9000, 9000, 9000).await;
+        assert_eq!(count(&db, "conn_summaries").await, 0);
+        assert_eq!(count(&db, "conn_metrics").await, 0);
+    }
+}
@@ -0,0 +1,381 @@
+use landscape_common::metric::connect::MetricResolution;
+use landscape_common::metric::traffic::{
+    TrafficHistoryQueryParams, TrafficPoint, TrafficSample, TrafficTargetKind, TrafficTotal,
+};
+use sea_orm::{ConnectionTrait, DbErr, QueryResult, Value};
+
+use super::statement;
+
+pub const TRAFFIC_INSERT_SQL: &str = "
+    INSERT INTO traffic_metrics (
+        kind, target, mac, report_time, rx_bytes, tx_bytes, rx_packets, tx_packets
+    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+";
+
+pub fn create_tables_sql() -> Vec<String> {
+    let mut sqls = vec![
+        "CREATE TABLE IF NOT EXISTS traffic_metrics (
+            kind TEXT,
+            target TEXT,
+            mac TEXT,
+            report_time INTEGER,
+            rx_bytes INTEGER,
+            tx_bytes INTEGER,
+            rx_packets INTEGER,
+            tx_packets INTEGER
+        )"
+        .to_string(),
+        "CREATE INDEX IF NOT EXISTS idx_traffic_metrics_time ON traffic_metrics (report_time)"
+            .to_string(),
+    ];
+    for table in ["traffic_metrics_1m", "traffic_metrics_1h", "traffic_metrics_1d"] {
+        sqls.push(format!(
+            "CREATE TABLE IF NOT EXISTS {table} (
+            kind TEXT,
+            target TEXT,
+            mac TEXT,
+            report_time INTEGER,
+            rx_bytes INTEGER,
+            tx_bytes INTEGER,
+            rx_packets INTEGER,
+            tx_packets INTEGER,
+            PRIMARY KEY (kind, target, report_time)
+        )"
+        ));
+        sqls.push(format!("CREATE INDEX IF NOT EXISTS idx_{table}_time ON {table} (report_time)"));
+    }
+    sqls
+}
+
+fn table_name(resolution: MetricResolution) -> &'static str {
+    match resolution {
+        MetricResolution::Second => "traffic_metrics",
+        MetricResolution::Minute => "traffic_metrics_1m",
+        MetricResolution::Hour => "traffic_metrics_1h",
+        MetricResolution::Day => "traffic_metrics_1d",
+    }
+}
+
+pub fn traffic_values(sample: &TrafficSample) -> Vec<Value> {
+    vec![
+        sample.kind.as_str().into(),
+        sample.target.clone().into(),
+        sample.mac.clone().into(),
+        (sample.report_time as i64).into(),
+        (sample.rx_bytes as i64).into(),
+        (sample.tx_bytes as i64).into(),
+        (sample.rx_packets as i64).into(),
+        (sample.tx_packets as i64).into(),
+    ]
+}
+
+fn get_u64(row: &QueryResult, idx: usize) -> Result<u64, DbErr> {
+    Ok(row.try_get_by_index::<Option<i64>>(idx)?.unwrap_or(0) as u64)
+}
+
+/// 采样值为增量, 汇总时直接求和, 重复汇总同一时间桶时取较大值
+pub async fn perform_traffic_rollup<C: ConnectionTrait>(
+    conn: &C,
+    now_ms: u64,
+) -> Result<(), DbErr> {
+    let steps = [
+        ("traffic_metrics", "traffic_metrics_1m", 60000_u64, 600000_u64),
+        ("traffic_metrics_1m", "traffic_metrics_1h", 3600000, 7200000),
+        ("traffic_metrics_1h", "traffic_metrics_1d", 86400000, 172800000),
+    ];
+    for (from, to, bucket, window) in steps {
+        let sql = format!(
+            "
+        INSERT INTO {to} (
+            kind, target, mac, report_time, rx_bytes, tx_bytes, rx_packets, tx_packets
+        )
+        SELECT
+            kind, target, MAX(mac), (report_time / {bucket}) * {bucket} as bucket_time,
+            SUM(rx_bytes), SUM(tx_bytes), SUM(rx_packets), SUM(tx_packets)
+        FROM {from}
+        WHERE report_time >= ?
+        GROUP BY kind, target, bucket_time
+        ON CONFLICT (kind, target, report_time) DO UPDATE SET
+            mac = COALESCE(excluded.mac, {to}.mac),
+            rx_bytes = MAX({to}.rx_bytes, excluded.rx_bytes),
+            tx_bytes = MAX({to}.tx_bytes, excluded.tx_bytes),
+            rx_packets = MAX({to}.rx_packets, excluded.rx_packets),
+            tx_packets = MAX({to}.tx_packets, excluded.tx_packets)
+        "
+        );
+        let start = (now_ms.saturating_sub(window) / bucket) * bucket;
+        conn.execute(statement(&sql, [(start as i64).into()])).await?;
+    }
+    Ok(())
+}
+
+pub async fn cleanup_old_traffic_metrics<C: ConnectionTrait>(
+    conn: &C,
+    cutoff_raw: u64,
+    cutoff_1m: u64,
+    cutoff_1h: u64,
+    cutoff_1d: u64,
+) {
+    for (table, cutoff) in [
+        ("traffic_metrics", cutoff_raw),
+        ("traffic_metrics_1m", cutoff_1m),
+        ("traffic_metrics_1h", cutoff_1h),
+        ("traffic_metrics_1d", cutoff_1d),
+    ] {
+        let sql = format!("DELETE FROM {table} WHERE report_time < ?");
+        if let Err(e) = conn.execute(statement(&sql, [(cutoff as i64).into()])).await {
+            tracing::error!("Failed to delete expired {table}: {}", e);
+        }
+    }
+}
+
+pub async fn query_traffic_history<C: ConnectionTrait>(
+    conn: &C,
+    params: &TrafficHistoryQueryParams,
+    resolution: MetricResolution,
+) -> Vec<TrafficPoint> {
+    // 设备按 MAC 汇总其所有 IP 的流量
+    let (kind, target_col) = match params.kind {
+        TrafficTargetKind::Device => (TrafficTargetKind::Ip, "mac"),
+        kind => (kind, "target"),
+    };
+    let stmt_str = format!(
+        "
+        SELECT
+            report_time, SUM(rx_bytes), SUM(tx_bytes), SUM(rx_packets), SUM(tx_packets)
+        FROM {}
+        WHERE kind = ? AND {} = ? AND report_time >= ? AND report_time <= ?
+        GROUP BY report_time
+        ORDER BY report_time
+    ",
+        table_name(resolution),
+        target_col
+    );
+
+    let values: Vec<Value> = vec![
+        kind.as_str().into(),
+        params.target.clone().into(),
+        (params.start_time.unwrap_or(0) as i64).into(),
+        (params.end_time.unwrap_or(i64::MAX as u64) as i64).into(),
+    ];
+    let rows = match conn.query_all(statement(&stmt_str, values)).await {
+        Ok(rows) => rows,
+        Err(e) => {
+            tracing::error!("Failed to execute traffic history query: {}, error: {}", stmt_str, e);
+            return Vec::new();
+        }
+    };
+
+    rows.iter()
+        .filter_map(|row| {
+            Some(TrafficPoint {
+                report_time: get_u64(row, 0).ok()?,
+                rx_bytes: get_u64(row, 1).ok()?,
+                tx_bytes: get_u64(row, 2).ok()?,
+                rx_packets: get_u64(row, 3).ok()?,
+                tx_packets: get_u64(row, 4).ok()?,
+            })
+        })
+        .collect()
+}
+
+/// 按对象汇总时间范围内的流量
+pub async fn query_traffic_totals<C: ConnectionTrait>(
+    conn: &C,
+    kind: TrafficTargetKind,
+    start_time: u64,
+    end_time: u64,
+    resolution: MetricResolution,
+) -> Vec<TrafficTotal> {
+    let stmt_str = format!(
+        "
+        SELECT
+            target, MAX(mac), SUM(rx_bytes), SUM(tx_bytes), SUM(rx_packets), SUM(tx_packets)
+        FROM {}
+        WHERE kind = ? AND report_time >= ? AND report_time < ?
+        GROUP BY target
+    ",
+        table_name(resolution)
+    );
+
+    let values: Vec<Value> =
+        vec![kind.as_str().into(), (start_time as i64).into(), (end_time as i64).into()];
+    let rows = match conn.query_all(statement(&stmt_str, values)).await {
+        Ok(rows) => rows,
+        Err(e) => {
+            tracing::error!("Failed to execute traffic totals query: {}, error: {}", stmt_str, e);
+            return Vec::new();
+        }
+    };
+
+    rows.iter()
+        .filter_map(|row| {
+            Some(TrafficTotal {
+                kind,
+                target: row.try_get_by_index(0).ok()?,
+                mac: row.try_get_by_index(1).ok()?,
+                rx_bytes: get_u64(row, 2).ok()?,
+                tx_bytes: get_u64(row, 3).ok()?,
+                rx_packets: get_u64(row, 4).ok()?,
+                tx_packets: get_u64(row, 5).ok()?,
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::DatabaseConnection;
+
+    use super::super::tests::{count, memory_db};
+    use super::super::write_batch;
+    use super::*;
+
+    fn sample(
+        kind: TrafficTargetKind,
+        target: &str,
+        mac: Option<&str>,
+        report_time: u64,
+        rx_bytes: u64,
+    ) -> TrafficSample {
+        TrafficSample {
+            kind,
+            target: target.to_string(),
+            mac: mac.map(str::to_string),
+            report_time,
+            rx_bytes,
+            tx_bytes: 1,
+            rx_packets: 1,
+            tx_packets: 1,
+        }
+    }
+
+    async fn insert(db: &DatabaseConnection, samples: &[TrafficSample]) {
+        write_batch(db, TRAFFIC_INSERT_SQL, samples.iter().map(traffic_values).collect())
+            .await
+            .unwrap();
+    }
+
+    async fn rows(db: &DatabaseConnection, table: &str) -> Vec<(String, u64, u64, u64)> {
+        let sql = format!(
+            "SELECT target, report_time, rx_bytes, tx_bytes FROM {table} ORDER BY target, report_time"
+        );
+        db.query_all(statement(&sql, []))
+            .await
+            .unwrap()
+            .iter()
+            .map(|row| {
+                (
+                    row.try_get_by_index(0).unwrap(),
+                    get_u64(row, 1).unwrap(),
+                    get_u64(row, 2).unwrap(),
+                    get_u64(row, 3).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn rollup_sums_samples_per_bucket() {
+        let db = memory_db().await;
+        let now = 10 * 3600000 + 30000;
+        let minute = now / 60000 * 60000;
+        let last_minute = minute - 60000;
+        let iface = TrafficTargetKind::Iface;
+        insert(
+            &db,
+            &[
+                sample(iface, "eth0", None, last_minute + 5000, 10),
+                sample(iface, "eth0", None, minute + 1000, 100),
+                sample(iface, "eth0", None, minute + 2000, 200),
+                sample(iface, "eth1", None, minute + 1000, 7),
+            ],
+        )
+        .await;
+
+        perform_traffic_rollup(&db, now).await.unwrap();
+        let expected = vec![
+            ("eth0".to_string(), last_minute, 10, 1),
+            ("eth0".to_string(), minute, 300, 2),
+            ("eth1".to_string(), minute, 7, 1),
+        ];
+        assert_eq!(rows(&db, "traffic_metrics_1m").await, expected);
+
+        // 重复汇总同一时间桶不会重复累计
+        perform_traffic_rollup(&db, now).await.unwrap();
+        assert_eq!(rows(&db, "traffic_metrics_1m").await, expected);
+
+        let expected_hour = vec![
+            ("eth0".to_string(), 9 * 3600000, 10, 1),
+            ("eth0".to_string(), 10 * 3600000, 300, 2),
+            ("eth1".to_string(), 10 * 3600000, 7, 1),
+        ];
+        assert_eq!(rows(&db, "traffic_metrics_1h").await, expected_hour);
+    }
+
+    #[tokio::test]
+    async fn device_history_and_totals() {
+        let db = memory_db().await;
+        let ip = TrafficTargetKind::Ip;
+        let mac = "00:11:22:33:44:55";
+        insert(
+            &db,
+            &[
+                sample(ip, "192.168.1.2", Some(mac), 1000, 10),
+                sample(ip, "fd00::2", Some(mac), 1000, 20),
+                sample(ip, "192.168.1.2", Some(mac), 2000, 30),
+                sample(ip, "192.168.1.3", None, 2000, 40),
+                sample(TrafficTargetKind::Iface, "eth0", None, 2000, 50),
+            ],
+        )
+        .await;
+
+        // 设备按 MAC 汇总其所有 IP
+        let params = TrafficHistoryQueryParams {
+            kind: TrafficTargetKind::Device,
+            target: mac.to_string(),
+            start_time: None,
+            end_time: None,
+            resolution: None,
+        };
+        let points = query_traffic_history(&db, &params, MetricResolution::Second).await;
+        let points: Vec<_> = points.iter().map(|p| (p.report_time, p.rx_bytes)).collect();
+        assert_eq!(points, vec![(1000, 30), (2000, 30)]);
+
+        let params = TrafficHistoryQueryParams {
+            kind: ip,
+            target: "192.168.1.2".to_string(),
+            start_time: Some(1500),
+            end_time: None,
+            resolution: None,
+        };
+        let points = query_traffic_history(&db, &params, MetricResolution::Second).await;
+        assert_eq!(points.len(), 1);
+        assert_eq!(points[0].rx_bytes, 30);
+
+        let mut totals = query_traffic_totals(&db, ip, 0, 2000, MetricResolution::Second).await;
+        totals.sort_by(|a, b| a.target.cmp(&b.target));
+        let totals: Vec<_> = totals.iter().map(|t| (t.target.as_str(), t.rx_bytes)).collect();
+        // 结束时间不包含在内
+        assert_eq!(totals, vec![("192.168.1.2", 10), ("fd00::2", 20)]);
+    }
+
+    #[tokio::test]
+    async fn cleanup_uses_cutoff_per_resolution() {
+        let db = memory_db().await;
+        let iface = TrafficTargetKind::Iface;
+        insert(&db, &[sample(iface, "eth0", None, 1000, 1), sample(iface, "eth0", None, 5000, 1)])
+            .await;
+        for table in ["traffic_metrics_1m", "traffic_metrics_1h", "traffic_metrics_1d"] {
+            let sql = format!("INSERT INTO {table} SELECT * FROM traffic_metrics");
+            db.execute(statement(&sql, [])).await.unwrap();
+        }
+
+        cleanup_old_traffic_metrics(&db, 3000, 6000, 0, 3000).await;
+        assert_eq!(count(&db, "traffic_metrics").await, 1);
+        assert_eq!(count(&db, "traffic_metrics_1m").await, 0);
+        assert_eq!(count(&db, "traffic_metrics_1h").await, 2);
+        assert_eq!(count(&db, "traffic_metrics_1d").await, 1);
+    }
+}
@@ -0,0 +1,485 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{Datelike, Local, NaiveDate, TimeZone};
+use landscape_common::config::MetricRuntimeConfig;
+use landscape_common::enrolled_device::EnrolledDevice;
+use landscape_common::metric::connect::{
+    ConnectKey, ConnectMetric, ConnectStatusType, MetricResolution, SortOrder,
+};
+use landscape_common::metric::traffic::{
+    TrafficHistoryQueryParams, TrafficMonthlyQueryParams, TrafficMonthlyReport, TrafficPoint,
+    TrafficSample, TrafficSortKey, TrafficTargetKind, TrafficTopQueryParams, TrafficTotal,
+};
+use landscape_common::utils::time::get_current_time_ms;
+
+use tokio::sync::broadcast;
+
+use crate::metric::prometheus::{read_iface_traffic_stats, IfaceTrafficStats};
+use crate::metric::MetricStore;
+
+/// 流量采样间隔
+const TRAFFIC_SAMPLE_INTERVAL: Duration = Duration::from_secs(30);
+/// 超过该时间未上报的连接不再参与计算
+const CONNECT_STALE_TIMEOUT_MS: u64 = 10 * 60 * 1000;
+const MS_PER_DAY: u64 = 24 * 60 * 60 * 1000;
+
+#[derive(Default, Clone, Copy)]
+struct TrafficCounter {
+    rx_bytes: u64,
+    tx_bytes: u64,
+    rx_packets: u64,
+    tx_packets: u64,
+}
+
+impl TrafficCounter {
+    fn is_empty(&self) -> bool {
+        self.rx_bytes == 0 && self.tx_bytes == 0 && self.rx_packets == 0 && self.tx_packets == 0
+    }
+
+    fn add_delta(&mut self, current: &TrafficCounter, last: &TrafficCounter) {
+        self.rx_bytes += current.rx_bytes.saturating_sub(last.rx_bytes);
+        self.tx_bytes += current.tx_bytes.saturating_sub(last.tx_bytes);
+        self.rx_packets += current.rx_packets.saturating_sub(last.rx_packets);
+        self.tx_packets += current.tx_packets.saturating_sub(last.tx_packets);
+    }
+}
+
+#[derive(Default)]
+struct AccountingState {
+    /// 连接上次上报的累计值与时间
+    connects: HashMap<ConnectKey, (TrafficCounter, u64)>,
+    /// 内网主机在当前采样周期内的增量
+    hosts: HashMap<IpAddr, TrafficCounter>,
+    /// 各 Flow 在当前采样周期内的增量
+    flows: HashMap<u8, TrafficCounter>,
+    /// 网卡上次读取的累计值
+    ifaces: HashMap<String, IfaceTrafficStats>,
+}
+
+impl AccountingState {
+    /// 连接上报的是累计值, 按连接计算增量并计入内网主机
+    fn record_connect(&mut self, metric: &ConnectMetric) {
+        let current = TrafficCounter {
+            rx_bytes: metric.ingress_bytes,
+            tx_bytes: metric.egress_bytes,
+            rx_packets: metric.ingress_packets,
+            tx_packets: metric.egress_packets,
+        };
+        let last = self.connects.get(&metric.key).map(|(last, _)| *last).unwrap_or_default();
+
+        let host = self.hosts.entry(lan_host(metric)).or_default();
+        host.add_delta(&current, &last);
+        let flow = self.flows.entry(metric.flow_id).or_default();
+        flow.add_delta(&current, &last);
+
+        if metric.status == ConnectStatusType::Disabled {
+            self.connects.remove(&metric.key);
+        } else {
+            self.connects.insert(metric.key.clone(), (current, metric.report_time));
+        }
+    }
+}
+
+/// 连接中内网主机一侧的地址. 出站连接由内网主机发起; 入站连接 (端口映射) 由外部主机发起,
+/// 但 NAT 上报时 src 固定为映射后的内网主机, dst 为外部主机, 两个方向都不能按发起方计费.
+/// 字节数按 WAN 网卡方向统计, ingress 即内网主机的下载
+fn lan_host(metric: &ConnectMetric) -> IpAddr {
+    metric.src_ip.to_canonical()
+}
+
+/// 采集网卡与内网主机的流量, 并按连接统计的汇总规则保存
+#[derive(Clone)]
+pub struct TrafficMetricManager {
+    metric_store: MetricStore,
+    config: MetricRuntimeConfig,
+    state: Arc<Mutex<AccountingState>>,
+    /// 每个采样周期的结果, 供流量配额等订阅
+    sample_tx: broadcast::Sender<Arc<Vec<TrafficSample>>>,
+}
+
+impl TrafficMetricManager {
+    pub fn with_store(metric_store: MetricStore, config: MetricRuntimeConfig) -> Self {
+        let (sample_tx, _) = broadcast::channel(8);
+        let manager = TrafficMetricManager {
+            metric_store,
+            config,
+            state: Arc::new(Mutex::new(AccountingState::default())),
+            sample_tx,
+        };
+
+        let manager_clone = manager.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(TRAFFIC_SAMPLE_INTERVAL);
+            loop {
+                interval.tick().await;
+                let samples = manager_clone.collect_samples();
+                if !samples.is_empty() {
+                    let _ = manager_clone.sample_tx.send(Arc::new(samples.clone()));
+                    manager_clone.metric_store.insert_traffic_samples(samples).await;
+                }
+            }
+        });
+
+        manager
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<Vec<TrafficSample>>> {
+        self.sample_tx.subscribe()
+    }
+
+    pub fn record_connect(&self, metric: &ConnectMetric) {
+        self.state.lock().unwrap().record_connect(metric);
+    }
+
+    fn collect_samples(&self) -> Vec<TrafficSample> {
+        let now = get_current_time_ms().unwrap_or_default();
+        let neighbors = read_ipv4_neighbors();
+        let iface_stats: Vec<(String, IfaceTrafficStats)> = list_iface_names()
+            .into_iter()
+            .filter_map(|name| read_iface_traffic_stats(&name).map(|stat| (name, stat)))
+            .collect();
+
+        let mut samples = vec![];
+        let mut state = self.state.lock().unwrap();
+
+        for (name, stat) in iface_stats {
+            if let Some(last) = state.ifaces.insert(name.clone(), stat.clone()) {
+                // 计数器回绕或网卡重建时直接使用当前值
+                let delta = |cur: u64, last: u64| if cur >= last { cur - last } else { cur };
+                let counter = TrafficCounter {
+                    rx_bytes: delta(stat.rx_bytes, last.rx_bytes),
+                    tx_bytes: delta(stat.tx_bytes, last.tx_bytes),
+                    rx_packets: delta(stat.rx_packets, last.rx_packets),
+                    tx_packets: delta(stat.tx_packets, last.tx_packets),
+                };
+                if !counter.is_empty() {
+                    samples.push(new_sample(TrafficTargetKind::Iface, name, None, now, counter));
+                }
+            }
+        }
+
+        for (ip, counter) in state.hosts.drain() {
+            if counter.is_empty() {
+                continue;
+            }
+            let mac = neighbors.get(&ip).cloned();
+            samples.push(new_sample(TrafficTargetKind::Ip, ip.to_string(), mac, now, counter));
+        }
+
+        for (flow_id, counter) in state.flows.drain() {
+            if counter.is_empty() {
+                continue;
+            }
+            samples.push(new_sample(
+                TrafficTargetKind::Flow,
+                flow_id.to_string(),
+                None,
+                now,
+                counter,
+            ));
+        }
+
+        let cutoff = now.saturating_sub(CONNECT_STALE_TIMEOUT_MS);
+        state.connects.retain(|_, (_, update_at)| *update_at >= cutoff);
+
+        samples
+    }
+
+    /// 根据起始时间和各精度的保留时长选择可用的最细精度
+    fn pick_resolution(&self, start_time: u64) -> MetricResolution {
+        let now = get_current_time_ms().unwrap_or_default();
+        if start_time >= now.saturating_sub(self.config.conn_retention_minute_days * MS_PER_DAY) {
+            MetricResolution::Minute
+        } else if start_time
+            >= now.saturating_sub(self.config.conn_retention_hour_days * MS_PER_DAY)
+        {
+            MetricResolution::Hour
+        } else {
+            MetricResolution::Day
+        }
+    }
+
+    pub async fn query_history(&self, params: TrafficHistoryQueryParams) -> Vec<TrafficPoint> {
+        let resolution = match params.resolution {
+            Some(resolution) => resolution,
+            None => self.pick_resolution(params.start_time.unwrap_or(0)),
+        };
+        self.metric_store.query_traffic_history(params, resolution).await
+    }
+
+    async fn query_totals(
+        &self,
+        kind: TrafficTargetKind,
+        start_time: u64,
+        end_time: u64,
+        resolution: MetricResolution,
+        devices: &[EnrolledDevice],
+    ) -> Vec<TrafficTotal> {
+        let store_kind = match kind {
+            TrafficTargetKind::Device => TrafficTargetKind::Ip,
+            kind => kind,
+        };
+        let totals = self
+            .metric_store
+            .query_traffic_totals(store_kind, start_time, end_time, resolution)
+            .await;
+        match kind {
+            TrafficTargetKind::Iface | TrafficTargetKind::Flow => totals,
+            TrafficTargetKind::Ip => {
+                totals.into_iter().map(|total| attach_device(total, devices)).collect()
+            }
+            TrafficTargetKind::Device => aggregate_devices(totals, devices),
+        }
+    }
+
+    /// 时间范围内流量最多的对象
+    pub async fn query_top(
+        &self,
+        params: TrafficTopQueryParams,
+        devices: &[EnrolledDevice],
+    ) -> Vec<TrafficTotal> {
+        let now = get_current_time_ms().unwrap_or_default();
+        let start_time = params.start_time.unwrap_or(now.saturating_sub(MS_PER_DAY));
+        let end_time = params.end_time.unwrap_or(now);
+        let resolution = self.pick_resolution(start_time);
+
+        let mut totals =
+            self.query_totals(params.kind, start_time, end_time, resolution, devices).await;
+        sort_totals(
+            &mut totals,
+            params.sort_key.unwrap_or_default(),
+            params.sort_order.unwrap_or_default(),
+        );
+        totals.truncate(params.limit.unwrap_or(10));
+        totals
+    }
+
+    /// 按计费周期汇总的月流量
+    pub async fn query_monthly(
+        &self,
+        params: TrafficMonthlyQueryParams,
+        devices: &[EnrolledDevice],
+    ) -> Option<TrafficMonthlyReport> {
+        let reset_day = params.reset_day.unwrap_or(1).clamp(1, 28) as u32;
+        let (start_time, end_time) = billing_cycle(params.month.as_deref(), reset_day)?;
+
+        // 小时精度可按本地时间对齐, 超出保留时长后使用天精度
+        let resolution = match self.pick_resolution(start_time) {
+            MetricResolution::Day => MetricResolution::Day,
+            _ => MetricResolution::Hour,
+        };
+        let mut items =
+            self.query_totals(params.kind, start_time, end_time, resolution, devices).await;
+        sort_totals(&mut items, TrafficSortKey::Total, SortOrder::Desc);
+        Some(TrafficMonthlyReport { start_time, end_time, items })
+    }
+}
+
+fn new_sample(
+    kind: TrafficTargetKind,
+    target: String,
+    mac: Option<String>,
+    report_time: u64,
+    counter: TrafficCounter,
+) -> TrafficSample {
+    TrafficSample {
+        kind,
+        target,
+        mac,
+        report_time,
+        rx_bytes: counter.rx_bytes,
+        tx_bytes: counter.tx_bytes,
+        rx_packets: counter.rx_packets,
+        tx_packets: counter.tx_packets,
+    }
+}
+
+fn list_iface_names() -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir("/sys/class/net") else {
+        return vec![];
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| name != "lo")
+        .collect()
+}
+
+/// 读取 IPv4 邻居表, 用于记录主机的 MAC
+fn read_ipv4_neighbors() -> HashMap<IpAddr, String> {
+    let Ok(content) = std::fs::read_to_string("/proc/net/arp") else {
+        return HashMap::new();
+    };
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 4 || fields[3] == "00:00:00:00:00:00" {
+                return None;
+            }
+            let ip = fields[0].parse::<IpAddr>().ok()?;
+            Some((ip, fields[3].to_lowercase()))
+        })
+        .collect()
+}
+
+fn find_device<'a>(
+    total: &TrafficTotal,
+    devices: &'a [EnrolledDevice],
+) -> Option<&'a EnrolledDevice> {
+    let ip = total.target.parse::<IpAddr>().ok();
+    devices
+        .iter()
+        .find(|device| total.mac.as_deref() == Some(device.mac.to_string().as_str()))
+        .or_else(|| {
+            devices.iter().find(|device| match ip {
+                Some(IpAddr::V4(ip)) => device.ipv4 == Some(ip),
+                Some(IpAddr::V6(ip)) => device.ipv6 == Some(ip),
+                None => false,
+            })
+        })
+}
+
+fn attach_device(mut total: TrafficTotal, devices: &[EnrolledDevice]) -> TrafficTotal {
+    if let Some(device) = find_device(&total, devices) {
+        total.device_id = Some(device.id);
+        total.device_name = Some(device.name.clone());
+        total.mac = Some(device.mac.to_string());
+    }
+    total
+}
+
+/// 将各 IP 的流量按 MAC 合并, 未知 MAC 的主机单独统计
+fn aggregate_devices(totals: Vec<TrafficTotal>, devices: &[EnrolledDevice]) -> Vec<TrafficTotal> {
+    let mut result: HashMap<String, TrafficTotal> = HashMap::new();
+    for total in totals {
+        let total = attach_device(total, devices);
+        let key = total.mac.clone().unwrap_or_else(|| total.target.clone());
+        let entry = result.entry(key.clone()).or_insert_with(|| TrafficTotal {
+            kind: TrafficTargetKind::Device,
+            target: key,
+            mac: total.mac.clone(),
+            device_id: total.device_id,
+            device_name: total.device_name.clone(),
+            ..Default::default()
+        });
+        entry.ips.push(total.target);
+        entry.rx_bytes += total.rx_bytes;
+        entry.tx_bytes += total.tx_bytes;
+        entry.rx_packets += total.rx_packets;
+        entry.tx_packets += total.tx_packets;
+    }
+    result.into_values().collect()
+}
+
+fn sort_totals(totals: &mut [TrafficTotal], sort_key: TrafficSortKey, sort_order: SortOrder) {
+    let value = |total: &TrafficTotal| match sort_key {
+        TrafficSortKey::Total => total.rx_bytes + total.tx_bytes,
+        TrafficSortKey::Rx => total.rx_bytes,
+        TrafficSortKey::Tx => total.tx_bytes,
+    };
+    totals.sort_by(|a, b| match sort_order {
+        SortOrder::Asc => value(a).cmp(&value(b)),
+        SortOrder::Desc => value(b).cmp(&value(a)),
+    });
+}
+
+/// 计算计费周期的起止时间 (本地时间), `month` 为 `YYYY-MM`, 为空时取当前周期
+pub(crate) fn billing_cycle(month: Option<&str>, reset_day: u32) -> Option<(u64, u64)> {
+    let (year, month) = match month {
+        Some(month) => {
+            let (year, month) = month.split_once('-')?;
+            (year.parse::<i32>().ok()?, month.parse::<u32>().ok()?)
+        }
+        None => {
+            let today = Local::now().date_naive();
+            if today.day() >= reset_day {
+                (today.year(), today.month())
+            } else if today.month() == 1 {
+                (today.year() - 1, 12)
+            } else {
+                (today.year(), today.month() - 1)
+            }
+        }
+    };
+    let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+
+    let to_ms = |year: i32, month: u32| -> Option<u64> {
+        let date = NaiveDate::from_ymd_opt(year, month, reset_day)?;
+        let time = Local.from_local_datetime(&date.and_hms_opt(0, 0, 0)?).earliest()?;
+        Some(time.timestamp_millis() as u64)
+    };
+    Some((to_ms(year, month)?, to_ms(next_year, next_month)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn connect(
+        cpu_id: u32,
+        gress: u8,
+        src_ip: &str,
+        dst_ip: &str,
+        bytes: (u64, u64),
+    ) -> ConnectMetric {
+        ConnectMetric {
+            key: ConnectKey { create_time: 1, cpu_id },
+            src_ip: src_ip.parse().unwrap(),
+            dst_ip: dst_ip.parse().unwrap(),
+            src_port: 50000,
+            dst_port: 443,
+            l4_proto: 6,
+            l3_proto: 0,
+            flow_id: 1,
+            trace_id: 0,
+            gress,
+            ifindex: 0,
+            domain: None,
+            report_time: 1000,
+            create_time_ms: 1000,
+            ingress_bytes: bytes.0,
+            ingress_packets: 1,
+            egress_bytes: bytes.1,
+            egress_packets: 1,
+            status: ConnectStatusType::Active,
+        }
+    }
+
+    fn host(state: &AccountingState, ip: &str) -> Option<(u64, u64)> {
+        let counter = state.hosts.get(&ip.parse::<IpAddr>().unwrap())?;
+        Some((counter.rx_bytes, counter.tx_bytes))
+    }
+
+    #[test]
+    fn record_connect_charges_lan_host_by_delta() {
+        let mut state = AccountingState::default();
+
+        // 出站连接, 累计值按增量计入
+        state.record_connect(&connect(0, 1, "192.168.1.10", "1.1.1.1", (1000, 100)));
+        state.record_connect(&connect(0, 1, "192.168.1.10", "1.1.1.1", (1500, 150)));
+        assert_eq!(host(&state, "192.168.1.10"), Some((1500, 150)));
+
+        // 入站连接 (端口映射) 计入映射后的内网主机, 而不是发起连接的外部主机
+        let mut inbound = connect(1, 0, "192.168.1.20", "8.8.8.8", (300, 3000));
+        inbound.src_port = 80;
+        inbound.dst_port = 50000;
+        state.record_connect(&inbound);
+        assert_eq!(host(&state, "192.168.1.20"), Some((300, 3000)));
+        assert_eq!(host(&state, "8.8.8.8"), None);
+        assert_eq!(host(&state, "1.1.1.1"), None);
+
+        let flow = state.flows[&1];
+        assert_eq!((flow.rx_bytes, flow.tx_bytes), (1800, 3150));
+
+        // 连接结束后不再保留累计值
+        inbound.status = ConnectStatusType::Disabled;
+        state.record_connect(&inbound);
+        assert_eq!(state.connects.len(), 1);
+        assert_eq!(host(&state, "192.168.1.20"), Some((300, 3000)));
+    }
+}
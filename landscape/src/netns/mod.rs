@@ -0,0 +1,130 @@
+use std::fs::File;
+use std::os::fd::AsRawFd;
+use std::path::{Path, PathBuf};
+
+use landscape_common::dev::DeviceKind;
+use landscape_common::netns_target::NetnsSource;
+use tokio::process::Command;
+
+/// 解析命名空间来源对应的 netns 文件
+pub async fn resolve_netns_path(source: &NetnsSource) -> Result<PathBuf, String> {
+    let path = match source {
+        NetnsSource::Named { netns } => PathBuf::from(format!("/run/netns/{netns}")),
+        NetnsSource::Pid { pid } => pid_netns_path(*pid as i64),
+        NetnsSource::Podman { container } => pid_netns_path(podman_container_pid(container).await?),
+        NetnsSource::Containerd { container, namespace } => {
+            pid_netns_path(containerd_task_pid(namespace, container).await?)
+        }
+    };
+    if !path.exists() {
+        return Err(format!("netns {} not found", path.display()));
+    }
+    Ok(path)
+}
+
+pub fn pid_netns_path(pid: i64) -> PathBuf {
+    PathBuf::from(format!("/proc/{pid}/ns/net"))
+}
+
+async fn podman_container_pid(container: &str) -> Result<i64, String> {
+    let output = Command::new("podman")
+        .args(["inspect", "--format", "{{.State.Pid}}", container])
+        .output()
+        .await
+        .map_err(|e| format!("run podman failed: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "podman inspect {container} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    match String::from_utf8_lossy(&output.stdout).trim().parse::<i64>() {
+        Ok(pid) if pid > 0 => Ok(pid),
+        _ => Err(format!("podman container {container} is not running")),
+    }
+}
+
+async fn containerd_task_pid(namespace: &str, container: &str) -> Result<i64, String> {
+    let output = Command::new("ctr")
+        .args(["-n", namespace, "task", "ls"])
+        .output()
+        .await
+        .map_err(|e| format!("run ctr failed: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "ctr task ls failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    parse_ctr_task_pid(&String::from_utf8_lossy(&output.stdout), container)
+        .ok_or_else(|| format!("containerd task {container} is not running"))
+}
+
+/// `ctr task ls` 输出格式: TASK PID STATUS
+fn parse_ctr_task_pid(output: &str, container: &str) -> Option<i64> {
+    output.lines().skip(1).find_map(|line| {
+        let mut cols = line.split_whitespace();
+        let (task, pid, status) = (cols.next()?, cols.next()?, cols.next()?);
+        if task == container && status == "RUNNING" {
+            pid.parse().ok()
+        } else {
+            None
+        }
+    })
+}
+
+/// 找到命名空间内第一个 veth, 返回其位于宿主机一侧的对端 ifindex
+/// 与 redirect_pkg_handler 向 Docker 注册时上报的 ifindex 一致
+pub async fn find_veth_peer_ifindex(netns_path: &Path) -> Result<u32, String> {
+    run_in_netns(netns_path, || {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| e.to_string())?;
+        // netlink socket 在切换后的命名空间中创建
+        let devices = runtime.block_on(crate::get_all_devices());
+        devices
+            .into_iter()
+            .find(|dev| matches!(dev.dev_kind, DeviceKind::Veth) && dev.netns_id.is_some())
+            .and_then(|dev| dev.peer_link_id)
+            .ok_or_else(|| "no veth with peer in another netns".to_string())
+    })
+    .await
+}
+
+/// 在独立线程中切换到指定网络命名空间后执行, 线程结束即丢弃该命名空间
+pub async fn run_in_netns<F, T>(netns_path: &Path, f: F) -> Result<T, String>
+where
+    F: FnOnce() -> Result<T, String> + Send + 'static,
+    T: Send + 'static,
+{
+    let netns_path = netns_path.to_path_buf();
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    std::thread::spawn(move || {
+        let result = File::open(&netns_path)
+            .map_err(|e| format!("open {} failed: {e}", netns_path.display()))
+            .and_then(|ns| {
+                if unsafe { libc::setns(ns.as_raw_fd(), libc::CLONE_NEWNET) } != 0 {
+                    Err(format!("setns failed: {}", std::io::Error::last_os_error()))
+                } else {
+                    Ok(())
+                }
+            })
+            .and_then(|_| f());
+        let _ = tx.send(result);
+    });
+    rx.await.map_err(|_| "netns thread exited".to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ctr_task_pid() {
+        let output = "TASK    PID     STATUS\nproxy   4321    RUNNING\nidle    0       STOPPED\n";
+        assert_eq!(parse_ctr_task_pid(output, "proxy"), Some(4321));
+        assert_eq!(parse_ctr_task_pid(output, "idle"), None);
+        assert_eq!(parse_ctr_task_pid(output, "missing"), None);
+    }
+}
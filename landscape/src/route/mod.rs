@@ -1,11 +1,16 @@
+pub mod tproxy;
+
 use core::mem::drop;
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
 use landscape_common::{
     config::FlowId,
     event::route::RouteEvent,
     flow::{config::FlowConfig, FlowTarget},
-    route::{LanIPv6RouteKey, LanRouteInfo, RouteTargetInfo},
+    route::{LanIPv6RouteKey, LanRouteInfo, RouteTargetInfo, RouteTproxyInfo},
 };
 use landscape_database::flow_rule::repository::FlowConfigRepository;
 use landscape_ebpf::map_setting::route::{add_lan_route, del_lan_route};
@@ -22,6 +27,9 @@ pub struct IpRouteService {
 
     ipv4_lan_ifaces: ShareRwLock<HashMap<String, LanRouteInfo>>,
     ipv6_lan_ifaces: ShareRwLock<HashMap<LanIPv6RouteKey, LanRouteInfo>>,
+
+    /// 健康检查判定为不可用的 Flow 出口
+    unhealthy_targets: ShareRwLock<HashMap<FlowId, HashSet<FlowTarget>>>,
 }
 
 impl IpRouteService {
@@ -35,6 +43,7 @@ impl IpRouteService {
             ipv6_wan_ifaces: Arc::new(RwLock::new(HashMap::new())),
             ipv4_lan_ifaces: Arc::new(RwLock::new(HashMap::new())),
             ipv6_lan_ifaces: Arc::new(RwLock::new(HashMap::new())),
+            unhealthy_targets: Arc::new(RwLock::new(HashMap::new())),
         };
         let route_service = service.clone();
         tokio::spawn(async move {
@@ -43,38 +52,21 @@ impl IpRouteService {
                 //
                 match event {
                     RouteEvent::FlowRuleUpdate { flow_id: Some(flow_id) } => {
-                        if let Ok(Some(flow_config)) =
-                            route_service.flow_repo.find_by_flow_id(flow_id).await
-                        {
-                            let ipv4_wan_infos = {
-                                let read_lock = route_service.ipv4_wan_ifaces.read().await;
-                                read_lock.clone()
-                            };
-
-                            let ipv6_wan_infos = {
-                                let read_lock = route_service.ipv6_wan_ifaces.read().await;
-                                read_lock.clone()
-                            };
-
-                            let flow_configs = vec![flow_config];
-                            refresh_ipv4_target_bpf_map(&flow_configs, ipv4_wan_infos);
-                            refresh_ipv6_target_bpf_map(&flow_configs, ipv6_wan_infos);
+                        match route_service.flow_repo.find_by_flow_id(flow_id).await {
+                            Ok(Some(flow_config)) => {
+                                route_service.refresh_flow_configs(&vec![flow_config]).await;
+                            }
+                            // Flow 已删除, 不再占用 TProxy 策略路由
+                            Ok(None) => {
+                                tproxy::update_tproxy_policy_route(false, [(flow_id, false)]).await;
+                                tproxy::update_tproxy_policy_route(true, [(flow_id, false)]).await;
+                            }
+                            Err(_) => {}
                         }
                     }
                     RouteEvent::FlowRuleUpdate { flow_id: None } => {
                         let flow_configs = route_service.flow_repo.list().await.unwrap_or_default();
-                        let ipv4_wan_infos = {
-                            let read_lock = route_service.ipv4_wan_ifaces.read().await;
-                            read_lock.clone()
-                        };
-
-                        let ipv6_wan_infos = {
-                            let read_lock = route_service.ipv6_wan_ifaces.read().await;
-                            read_lock.clone()
-                        };
-
-                        refresh_ipv4_target_bpf_map(&flow_configs, ipv4_wan_infos);
-                        refresh_ipv6_target_bpf_map(&flow_configs, ipv6_wan_infos);
+                        route_service.refresh_flow_configs(&flow_configs).await;
                     }
                 }
             }
@@ -85,12 +77,12 @@ impl IpRouteService {
     pub async fn remove_all_wan_docker(&self) {
         {
             let mut lock = self.ipv4_wan_ifaces.write().await;
-            lock.retain(|_, value| !value.is_docker);
+            lock.retain(|_, value| !value.is_docker || value.is_namespace);
         }
 
         {
             let mut lock = self.ipv6_wan_ifaces.write().await;
-            lock.retain(|_, value| !value.is_docker);
+            lock.retain(|_, value| !value.is_docker || value.is_namespace);
         }
     }
 
@@ -174,8 +166,11 @@ impl IpRouteService {
         let mut refresh_default_router = info.default_route;
         let target = info.get_flow_target();
         let mut lock = self.ipv6_wan_ifaces.write().await;
-        if let Some(old_info) = lock.insert(key.to_string(), info) {
+        if let Some(old_info) = lock.insert(key.to_string(), info.clone()) {
             refresh_default_router = refresh_default_router || old_info.default_route;
+            if old_info != info {
+                landscape_ebpf::map_setting::route::del_ipv6_route_reply_by_wan(old_info.ifindex);
+            }
         }
         drop(lock);
         self.refresh_ipv6_target_map(target).await;
@@ -188,8 +183,11 @@ impl IpRouteService {
         let mut refresh_default_router = info.default_route;
         let target = info.get_flow_target();
         let mut lock = self.ipv4_wan_ifaces.write().await;
-        if let Some(old_info) = lock.insert(key.to_string(), info) {
+        if let Some(old_info) = lock.insert(key.to_string(), info.clone()) {
             refresh_default_router = refresh_default_router || old_info.default_route;
+            if old_info != info {
+                landscape_ebpf::map_setting::route::del_ipv4_route_reply_by_wan(old_info.ifindex);
+            }
         }
         drop(lock);
         self.refresh_ipv4_target_map(target).await;
@@ -198,11 +196,19 @@ impl IpRouteService {
         }
     }
 
+    /// 名称是否已被网卡或 Docker 容器出口占用, 不含命名空间出口
+    pub async fn is_wan_name_taken(&self, key: &str) -> bool {
+        let taken = |info: Option<&RouteTargetInfo>| info.is_some_and(|info| !info.is_namespace);
+        taken(self.ipv4_wan_ifaces.read().await.get(key))
+            || taken(self.ipv6_wan_ifaces.read().await.get(key))
+    }
+
     pub async fn remove_ipv4_wan_route(&self, key: &str) {
         let mut lock = self.ipv4_wan_ifaces.write().await;
         let result = lock.remove(key);
         drop(lock);
         if let Some(info) = result {
+            landscape_ebpf::map_setting::route::del_ipv4_route_reply_by_wan(info.ifindex);
             self.refresh_ipv4_target_map(info.get_flow_target()).await;
             if info.default_route {
                 self.refresh_default_router().await;
@@ -215,6 +221,7 @@ impl IpRouteService {
         let result = lock.remove(key);
         drop(lock);
         if let Some(info) = result {
+            landscape_ebpf::map_setting::route::del_ipv6_route_reply_by_wan(info.ifindex);
             self.refresh_ipv6_target_map(info.get_flow_target()).await;
             if info.default_route {
                 self.refresh_default_router().await;
@@ -243,8 +250,9 @@ impl IpRouteService {
             let read_lock = self.ipv4_wan_ifaces.read().await;
             read_lock.clone()
         };
+        let unhealthy_targets = self.unhealthy_targets.read().await;
 
-        refresh_ipv4_target_bpf_map(&flow_configs, ipv4_wan_infos);
+        refresh_ipv4_target_bpf_map(&flow_configs, ipv4_wan_infos, &unhealthy_targets).await;
     }
     pub async fn refresh_ipv6_target_map(&self, t: FlowTarget) {
         let flow_configs = self.flow_repo.find_by_target(t).await.unwrap_or_default();
@@ -253,84 +261,145 @@ impl IpRouteService {
             let read_lock = self.ipv6_wan_ifaces.read().await;
             read_lock.clone()
         };
+        let unhealthy_targets = self.unhealthy_targets.read().await;
+
+        refresh_ipv6_target_bpf_map(&flow_configs, ipv6_wan_infos, &unhealthy_targets).await;
+    }
+
+    /// 更新 Flow 中不可用的出口, 并刷新该 Flow 的出口
+    pub async fn set_flow_unhealthy_targets(&self, flow_id: FlowId, targets: HashSet<FlowTarget>) {
+        {
+            let mut lock = self.unhealthy_targets.write().await;
+            if targets.is_empty() {
+                lock.remove(&flow_id);
+            } else {
+                lock.insert(flow_id, targets);
+            }
+        }
+        if let Ok(Some(flow_config)) = self.flow_repo.find_by_flow_id(flow_id).await {
+            self.refresh_flow_configs(&vec![flow_config]).await;
+        }
+    }
+
+    async fn refresh_flow_configs(&self, flow_configs: &Vec<FlowConfig>) {
+        let ipv4_wan_infos = {
+            let read_lock = self.ipv4_wan_ifaces.read().await;
+            read_lock.clone()
+        };
+
+        let ipv6_wan_infos = {
+            let read_lock = self.ipv6_wan_ifaces.read().await;
+            read_lock.clone()
+        };
+        let unhealthy_targets = self.unhealthy_targets.read().await;
 
-        refresh_ipv6_target_bpf_map(&flow_configs, ipv6_wan_infos);
+        refresh_ipv4_target_bpf_map(flow_configs, ipv4_wan_infos, &unhealthy_targets).await;
+        refresh_ipv6_target_bpf_map(flow_configs, ipv6_wan_infos, &unhealthy_targets).await;
     }
 }
 
-pub fn refresh_ipv4_target_bpf_map(
+pub async fn refresh_ipv4_target_bpf_map(
     flow_configs: &Vec<FlowConfig>,
     ipv4_wan_infos: HashMap<String, RouteTargetInfo>,
+    unhealthy_targets: &HashMap<FlowId, HashSet<FlowTarget>>,
 ) {
-    let mut result: HashMap<FlowId, Vec<RouteTargetInfo>> = HashMap::new();
+    let mut result: HashMap<FlowId, Option<RouteTargetInfo>> = HashMap::new();
     for each_flow_config in flow_configs.iter() {
-        let mut targets = vec![];
-        if each_flow_config.enable {
-            for target in each_flow_config.flow_targets.iter() {
-                match target {
-                    landscape_common::flow::FlowTarget::Interface { name } => {
-                        if let Some(result) = ipv4_wan_infos.get(name) {
-                            targets.push(result.clone());
-                        }
-                    }
-                    landscape_common::flow::FlowTarget::Netns { container_name } => {
-                        if let Some(result) = ipv4_wan_infos.get(container_name) {
-                            targets.push(result.clone());
-                        }
-                    }
-                }
-            }
-        }
-        result.insert(each_flow_config.flow_id, targets);
+        let target = select_flow_target(
+            each_flow_config,
+            &ipv4_wan_infos,
+            unhealthy_targets.get(&each_flow_config.flow_id),
+            false,
+        );
+        result.insert(each_flow_config.flow_id, target);
     }
 
     tracing::info!("ipv4 flow target refresh result: {:#?}", result);
-    for (flow_id, configes) in result {
-        if let Some(info) = configes.get(0) {
-            landscape_ebpf::map_setting::route::add_wan_route(flow_id, info.clone());
+    let tproxy_flows: Vec<_> = result
+        .iter()
+        .map(|(flow_id, info)| (*flow_id, info.as_ref().is_some_and(|i| i.tproxy.is_some())))
+        .collect();
+    tproxy::update_tproxy_policy_route(false, tproxy_flows).await;
+    for (flow_id, target) in result {
+        if let Some(info) = target {
+            landscape_ebpf::map_setting::route::add_wan_route(flow_id, info);
         } else {
             landscape_ebpf::map_setting::route::del_ipv4_wan_route(flow_id);
         }
     }
 }
 
-pub fn refresh_ipv6_target_bpf_map(
+pub async fn refresh_ipv6_target_bpf_map(
     flow_configs: &Vec<FlowConfig>,
     ipv6_wan_infos: HashMap<String, RouteTargetInfo>,
+    unhealthy_targets: &HashMap<FlowId, HashSet<FlowTarget>>,
 ) {
     // IPV6
-    let mut result: HashMap<FlowId, Vec<RouteTargetInfo>> = HashMap::new();
+    let mut result: HashMap<FlowId, Option<RouteTargetInfo>> = HashMap::new();
     for each_flow_config in flow_configs.iter() {
-        let mut targets = vec![];
-        if each_flow_config.enable {
-            for target in each_flow_config.flow_targets.iter() {
-                match target {
-                    landscape_common::flow::FlowTarget::Interface { name } => {
-                        if let Some(result) = ipv6_wan_infos.get(name) {
-                            targets.push(result.clone());
-                        }
-                    }
-                    landscape_common::flow::FlowTarget::Netns { container_name } => {
-                        if let Some(result) = ipv6_wan_infos.get(container_name) {
-                            targets.push(result.clone());
-                        }
-                    }
-                }
-            }
-        }
-        result.insert(each_flow_config.flow_id, targets);
+        let target = select_flow_target(
+            each_flow_config,
+            &ipv6_wan_infos,
+            unhealthy_targets.get(&each_flow_config.flow_id),
+            true,
+        );
+        result.insert(each_flow_config.flow_id, target);
     }
 
     tracing::info!("ipv6 flow target refresh result: {:#?}", result);
-    for (flow_id, configes) in result {
-        if let Some(info) = configes.get(0) {
-            landscape_ebpf::map_setting::route::add_wan_route(flow_id, info.clone());
+    let tproxy_flows: Vec<_> = result
+        .iter()
+        .map(|(flow_id, info)| (*flow_id, info.as_ref().is_some_and(|i| i.tproxy.is_some())))
+        .collect();
+    tproxy::update_tproxy_policy_route(true, tproxy_flows).await;
+    for (flow_id, target) in result {
+        if let Some(info) = target {
+            landscape_ebpf::map_setting::route::add_wan_route(flow_id, info);
         } else {
             landscape_ebpf::map_setting::route::del_ipv6_wan_route(flow_id);
         }
     }
 }
 
+/// 按顺序选出第一个已就绪且健康的出口, 全部不可用时按健康检查配置回退到默认 WAN
+fn select_flow_target(
+    flow_config: &FlowConfig,
+    wan_infos: &HashMap<String, RouteTargetInfo>,
+    unhealthy: Option<&HashSet<FlowTarget>>,
+    ipv6: bool,
+) -> Option<RouteTargetInfo> {
+    if !flow_config.enable {
+        return None;
+    }
+    for target in flow_config.flow_targets.iter() {
+        if unhealthy.map_or(false, |set| set.contains(target)) {
+            continue;
+        }
+        let key = match target {
+            FlowTarget::Interface { name } => name,
+            FlowTarget::Netns { container_name } => container_name,
+            FlowTarget::Namespace { name } => name,
+            FlowTarget::Tproxy { port, ipv4, ipv6: tproxy_ipv6, tcp, udp } => {
+                if (ipv6 && *tproxy_ipv6) || (!ipv6 && *ipv4) {
+                    let tproxy = RouteTproxyInfo { port: *port, tcp: *tcp, udp: *udp };
+                    return Some(RouteTargetInfo::tproxy_new(ipv6, tproxy));
+                }
+                continue;
+            }
+        };
+        if let Some(info) = wan_infos.get(key) {
+            return Some(info.clone());
+        }
+    }
+
+    let fallback_direct =
+        flow_config.active_health_check().map_or(false, |check| check.fallback_direct);
+    if fallback_direct {
+        return wan_infos.values().find(|e| e.default_route).cloned();
+    }
+    None
+}
+
 pub async fn test_used_ip_route() -> (mpsc::Sender<RouteEvent>, IpRouteService) {
     let db_store_provider =
         landscape_database::provider::LandscapeDBServiceProvider::mem_test_db().await;
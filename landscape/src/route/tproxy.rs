@@ -0,0 +1,82 @@
+use std::collections::BTreeSet;
+
+use landscape_common::config::FlowId;
+use landscape_common::{LANDSCAPE_TPROXY_MARK, LANDSCAPE_TPROXY_ROUTE_TABLE};
+use tokio::process::Command;
+use tokio::sync::Mutex;
+
+struct PolicyRouteState {
+    /// 当前出口为 TProxy 的 Flow
+    flows: BTreeSet<FlowId>,
+    installed: bool,
+}
+
+impl PolicyRouteState {
+    const fn new() -> Self {
+        PolicyRouteState { flows: BTreeSet::new(), installed: false }
+    }
+}
+
+static IPV4_POLICY_ROUTE: Mutex<PolicyRouteState> = Mutex::const_new(PolicyRouteState::new());
+static IPV6_POLICY_ROUTE: Mutex<PolicyRouteState> = Mutex::const_new(PolicyRouteState::new());
+
+fn family(ipv6: bool) -> (&'static str, &'static str) {
+    if ipv6 {
+        ("-6", "::/0")
+    } else {
+        ("-4", "0.0.0.0/0")
+    }
+}
+
+/// 带有 TProxy 标记的数据包目标地址并非本机, 需要策略路由将其投递到本机;
+/// 根据 Flow 出口的变化添加策略路由, 没有 Flow 使用 TProxy 出口时将其移除
+pub async fn update_tproxy_policy_route(
+    ipv6: bool,
+    flows: impl IntoIterator<Item = (FlowId, bool)>,
+) {
+    let mut state =
+        if ipv6 { IPV6_POLICY_ROUTE.lock().await } else { IPV4_POLICY_ROUTE.lock().await };
+    for (flow_id, use_tproxy) in flows {
+        if use_tproxy {
+            state.flows.insert(flow_id);
+        } else {
+            state.flows.remove(&flow_id);
+        }
+    }
+
+    let (family, default) = family(ipv6);
+    let mark = format!("{LANDSCAPE_TPROXY_MARK:#x}/{LANDSCAPE_TPROXY_MARK:#x}");
+    let table = LANDSCAPE_TPROXY_ROUTE_TABLE.to_string();
+    let del_rule = [family, "rule", "del", "fwmark", &mark, "lookup", &table];
+    if !state.flows.is_empty() && !state.installed {
+        // 先删除上次运行遗留的规则, 避免重复
+        let _ = Command::new("ip").args(del_rule).output().await;
+        let added = run_ip(&[family, "rule", "add", "fwmark", &mark, "lookup", &table]).await;
+        let routed =
+            run_ip(&[family, "route", "replace", "local", default, "dev", "lo", "table", &table])
+                .await;
+        state.installed = added && routed;
+    } else if state.flows.is_empty() && state.installed {
+        run_ip(&del_rule).await;
+        run_ip(&[family, "route", "flush", "table", &table]).await;
+        state.installed = false;
+    }
+}
+
+async fn run_ip(args: &[&str]) -> bool {
+    match Command::new("ip").args(args).output().await {
+        Ok(output) if output.status.success() => true,
+        Ok(output) => {
+            tracing::error!(
+                "ip {} failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            false
+        }
+        Err(e) => {
+            tracing::error!("run ip {} failed: {e}", args.join(" "));
+            false
+        }
+    }
+}
@@ -1,11 +1,11 @@
-use std::net::IpAddr;
+use std::net::{IpAddr, Ipv6Addr};
 
 use landscape_common::database::LandscapeStore;
-use landscape_common::route::{LanRouteInfo, LanRouteMode, RouteTargetInfo};
+use landscape_common::route::{LanIPv6RouteKey, LanRouteInfo, LanRouteMode, RouteTargetInfo};
 use landscape_common::LANDSCAPE_DEFAULE_DHCP_V4_CLIENT_PORT;
 use landscape_common::{
     args::LAND_HOSTNAME,
-    config::iface_ip::{IfaceIpModelConfig, IfaceIpServiceConfig},
+    config::iface_ip::{IfaceIpModelConfig, IfaceIpServiceConfig, StaticIpv6Addr},
     global_const::default_router::{RouteInfo, RouteType, LD_ALL_ROUTERS},
     observer::IfaceObserverAction,
     service::{
@@ -64,15 +64,23 @@ async fn init_service_from_config(
     service_status: WatchService,
     route_service: IpRouteService,
 ) {
+    let ipv6_addrs = service_config.static_ipv6_addrs();
     match service_config {
         IfaceIpModelConfig::Nothing => {}
         IfaceIpModelConfig::Static {
-            default_router, default_router_ip, ipv4, ipv4_mask, ..
+            default_router,
+            default_router_ip,
+            ipv4,
+            ipv4_mask,
+            ipv6_gateway,
+            ..
         } => {
-            // TODO: IPV6 的设置
+            if ipv4.is_none() && ipv6_addrs.is_empty() {
+                return;
+            }
+            service_status.just_change_status(ServiceStatus::Staring);
+            let iface_name = iface.name.clone();
             if let Some(ipv4) = ipv4 {
-                service_status.just_change_status(ServiceStatus::Staring);
-                let iface_name = iface.name;
                 tracing::info!("set ipv4 is: {}", ipv4);
                 let _ = std::process::Command::new("ip")
                     .args(&["addr", "add", &format!("{}/{}", ipv4, ipv4_mask), "dev", &iface_name])
@@ -90,7 +98,7 @@ async fn init_service_from_config(
                     ifindex: iface.index,
                     iface_name: iface_name.clone(),
                     iface_ip: IpAddr::V4(ipv4),
-                    mac: iface.mac,
+                    mac: iface.mac.clone(),
                     prefix: ipv4_mask,
                     mode: LanRouteMode::Reachable,
                 };
@@ -119,6 +127,8 @@ async fn init_service_from_config(
                             weight: 1,
                             mac: iface.mac.clone(),
                             is_docker: false,
+                            is_namespace: false,
+                            tproxy: None,
                             iface_name: iface_name.clone(),
                             iface_ip: IpAddr::V4(ipv4),
                             default_route: default_router,
@@ -127,21 +137,38 @@ async fn init_service_from_config(
                         route_service.insert_ipv4_wan_route(&iface_name, info).await;
                     }
                 }
+            }
+
+            if !ipv6_addrs.is_empty() {
+                set_static_ipv6(&iface, &ipv6_addrs, ipv6_gateway, default_router, &route_service)
+                    .await;
+            }
 
-                service_status.just_change_status(ServiceStatus::Running);
-                service_status.wait_to_stopping().await;
+            service_status.just_change_status(ServiceStatus::Running);
+            service_status.wait_to_stopping().await;
+
+            if let Some(ipv4) = ipv4 {
                 let _ = std::process::Command::new("ip")
                     .args(&["addr", "del", &format!("{}/{}", ipv4, ipv4_mask), "dev", &iface_name])
                     .output();
-
-                if default_router {
-                    LD_ALL_ROUTERS.del_route_by_iface(&iface_name).await;
-                }
                 route_service.remove_ipv4_wan_route(&iface_name).await;
                 route_service.remove_ipv4_lan_route(&iface_name).await;
                 landscape_ebpf::map_setting::del_ipv4_wan_ip(iface.index);
-                service_status.just_change_status(ServiceStatus::Stop);
             }
+
+            if !ipv6_addrs.is_empty() {
+                for addr in ipv6_addrs.iter() {
+                    crate::icmp::v6::del_iface_ip(addr.ip, addr.prefix, &iface_name);
+                }
+                route_service.remove_ipv6_wan_route(&iface_name).await;
+                route_service.remove_ipv6_lan_route(&iface_name).await;
+                landscape_ebpf::map_setting::del_ipv6_wan_ip(iface.index);
+            }
+
+            if default_router {
+                LD_ALL_ROUTERS.del_route_by_iface(&iface_name).await;
+            }
+            service_status.just_change_status(ServiceStatus::Stop);
         }
         IfaceIpModelConfig::PPPoE { username: _, password: _, mtu: _, .. } => {
             // TODO： 重构 PPPoE ebpf 版本
@@ -185,6 +212,73 @@ async fn init_service_from_config(
     };
 }
 
+/// 设置静态 IPv6 地址及网关
+async fn set_static_ipv6(
+    iface: &LandscapeInterface,
+    ipv6_addrs: &[StaticIpv6Addr],
+    ipv6_gateway: Option<Ipv6Addr>,
+    default_router: bool,
+    route_service: &IpRouteService,
+) {
+    let iface_name = &iface.name;
+    for (index, addr) in ipv6_addrs.iter().enumerate() {
+        tracing::info!("set ipv6 is: {}/{}", addr.ip, addr.prefix);
+        crate::icmp::v6::set_iface_ip(addr.ip, addr.prefix, iface_name, None, None);
+
+        let lan_info = LanRouteInfo {
+            ifindex: iface.index,
+            iface_name: iface_name.clone(),
+            iface_ip: IpAddr::V6(addr.ip),
+            mac: iface.mac.clone(),
+            prefix: addr.prefix,
+            mode: LanRouteMode::Reachable,
+        };
+        let key = LanIPv6RouteKey {
+            iface_name: iface_name.clone(),
+            subnet_index: index as u32,
+        };
+        route_service.insert_ipv6_lan_route(key, lan_info).await;
+    }
+
+    // 第一个地址作为 WAN 地址
+    let wan_addr = &ipv6_addrs[0];
+    landscape_ebpf::map_setting::add_ipv6_wan_ip(
+        iface.index,
+        wan_addr.ip,
+        ipv6_gateway,
+        wan_addr.prefix,
+        iface.mac.clone(),
+    );
+
+    let Some(gateway) = ipv6_gateway else {
+        return;
+    };
+    if default_router {
+        tracing::info!("setting ipv6 default route: {:?}", gateway);
+        LD_ALL_ROUTERS
+            .add_route(RouteInfo {
+                iface_name: iface_name.clone(),
+                weight: 1,
+                route: RouteType::Ipv6(gateway),
+            })
+            .await;
+    }
+
+    let info = RouteTargetInfo {
+        ifindex: iface.index,
+        weight: 1,
+        mac: iface.mac.clone(),
+        is_docker: false,
+        is_namespace: false,
+        tproxy: None,
+        iface_name: iface_name.clone(),
+        iface_ip: IpAddr::V6(wan_addr.ip),
+        default_route: default_router,
+        gateway_ip: IpAddr::V6(gateway),
+    };
+    route_service.insert_ipv6_wan_route(iface_name, info).await;
+}
+
 #[derive(Clone)]
 pub struct IfaceIpServiceManagerService {
     store: IfaceIpServiceRepository,
@@ -4,15 +4,20 @@ use std::net::Ipv6Addr;
 
 use landscape_common::ipv6_pd::IAPrefixMap;
 use landscape_common::ipv6_pd::LDIAPrefix;
+use landscape_common::ipv6_pd::LDWanIpv6Info;
+use landscape_common::ipv6_pd::WanIpv6InfoMap;
 use landscape_common::route::RouteTargetInfo;
 use landscape_common::service::manager::ServiceStarterTrait;
 use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
 
 use landscape_common::database::LandscapeStore;
 use landscape_common::{
     dhcp::v6_client::config::IPV6PDServiceConfig,
     observer::IfaceObserverAction,
-    service::{controller::ControllerService, manager::ServiceManager, WatchService},
+    service::{
+        controller::ControllerService, manager::ServiceManager, ServiceStatus, WatchService,
+    },
     LANDSCAPE_DEFAULE_DHCP_V6_CLIENT_PORT,
 };
 use landscape_database::{
@@ -26,11 +31,16 @@ use crate::route::IpRouteService;
 pub struct IPV6PDService {
     route_service: IpRouteService,
     prefix_map: IAPrefixMap,
+    wan_ipv6_info: WanIpv6InfoMap,
 }
 
 impl IPV6PDService {
-    pub fn new(route_service: IpRouteService, prefix_map: IAPrefixMap) -> Self {
-        Self { route_service, prefix_map }
+    pub fn new(
+        route_service: IpRouteService,
+        prefix_map: IAPrefixMap,
+        wan_ipv6_info: WanIpv6InfoMap,
+    ) -> Self {
+        Self { route_service, prefix_map, wan_ipv6_info }
     }
 }
 
@@ -43,12 +53,15 @@ impl ServiceStarterTrait for IPV6PDService {
         if config.enable {
             let route_service = self.route_service.clone();
             let prefix_map = self.prefix_map.clone();
+            let wan_ipv6_info = self.wan_ipv6_info.clone();
             if let Some(iface) = get_iface_by_name(&config.iface_name).await {
                 let route_info = RouteTargetInfo {
                     ifindex: iface.index,
                     weight: 1,
                     mac: iface.mac.clone(),
                     is_docker: false,
+                    is_namespace: false,
+                    tproxy: None,
                     iface_name: iface.name.clone(),
                     iface_ip: IpAddr::V6(Ipv6Addr::UNSPECIFIED),
                     default_route: true,
@@ -56,18 +69,55 @@ impl ServiceStarterTrait for IPV6PDService {
                 };
                 let status_clone = service_status.clone();
                 tokio::spawn(async move {
-                    crate::dhcp_client::v6::dhcp_v6_pd_client(
-                        config.iface_name,
-                        iface.index,
-                        iface.mac,
-                        config.config.mac,
-                        LANDSCAPE_DEFAULE_DHCP_V6_CLIENT_PORT,
-                        status_clone,
-                        route_info,
-                        route_service,
-                        prefix_map,
-                    )
-                    .await;
+                    let cancel = CancellationToken::new();
+                    let ra_client = match (config.config.accept_ra, iface.mac) {
+                        (true, Some(mac)) => {
+                            Some(tokio::spawn(crate::icmp::ra_client::icmp_ra_client(
+                                config.iface_name.clone(),
+                                iface.index,
+                                mac,
+                                cancel.clone(),
+                                route_info.clone(),
+                                route_service.clone(),
+                                wan_ipv6_info.clone(),
+                            )))
+                        }
+                        (true, None) => {
+                            tracing::error!("{} has no mac, can not accept RA", config.iface_name);
+                            None
+                        }
+                        _ => None,
+                    };
+
+                    let need_dhcp_client = config.config.need_dhcp_client();
+                    if need_dhcp_client {
+                        crate::dhcp_client::v6::dhcp_v6_pd_client(
+                            config.iface_name,
+                            iface.index,
+                            iface.mac,
+                            config.config,
+                            LANDSCAPE_DEFAULE_DHCP_V6_CLIENT_PORT,
+                            status_clone.clone(),
+                            route_info,
+                            route_service,
+                            prefix_map,
+                            wan_ipv6_info,
+                        )
+                        .await;
+                    } else {
+                        // 仅处理 RA 时由此处维护服务状态
+                        status_clone.just_change_status(ServiceStatus::Staring);
+                        status_clone.just_change_status(ServiceStatus::Running);
+                        status_clone.wait_to_stopping().await;
+                    }
+
+                    cancel.cancel();
+                    if let Some(ra_client) = ra_client {
+                        let _ = ra_client.await;
+                    }
+                    if !need_dhcp_client {
+                        status_clone.just_change_status(ServiceStatus::Stop);
+                    }
                 });
             } else {
                 tracing::error!("Interface {} not found", config.iface_name);
@@ -83,6 +133,7 @@ pub struct DHCPv6ClientManagerService {
     store: DHCPv6ClientRepository,
     service: ServiceManager<IPV6PDService>,
     prefix_map: IAPrefixMap,
+    wan_ipv6_info: WanIpv6InfoMap,
 }
 
 impl ControllerService for DHCPv6ClientManagerService {
@@ -108,7 +159,9 @@ impl DHCPv6ClientManagerService {
         prefix_map: IAPrefixMap,
     ) -> Self {
         let store = store_service.dhcp_v6_client_store();
-        let server_starter = IPV6PDService::new(route_service, prefix_map.clone());
+        let wan_ipv6_info = WanIpv6InfoMap::new();
+        let server_starter =
+            IPV6PDService::new(route_service, prefix_map.clone(), wan_ipv6_info.clone());
         let service = ServiceManager::init(store.list().await.unwrap(), server_starter).await;
 
         let service_clone = service.clone();
@@ -133,10 +186,14 @@ impl DHCPv6ClientManagerService {
         });
 
         let store = store_service.dhcp_v6_client_store();
-        Self { service, store, prefix_map }
+        Self { service, store, prefix_map, wan_ipv6_info }
     }
 
     pub async fn get_ipv6_prefix_infos(&self) -> HashMap<String, Option<LDIAPrefix>> {
         self.prefix_map.get_info().await
     }
+
+    pub async fn get_wan_ipv6_infos(&self) -> HashMap<String, LDWanIpv6Info> {
+        self.wan_ipv6_info.get_info().await
+    }
 }
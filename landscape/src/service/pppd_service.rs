@@ -125,6 +125,8 @@ pub async fn create_pppd_thread(
                             weight: 1,
                             mac: None,
                             is_docker: false,
+                            is_namespace: false,
+                            tproxy: None,
                             iface_name: ppp_iface_name_clone.clone(),
                             iface_ip: IpAddr::V4(ip.clone()),
                             default_route: as_router,
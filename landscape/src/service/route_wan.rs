@@ -68,6 +68,9 @@ pub async fn create_route_wan_service(ifindex: u32, has_mac: bool, service_statu
     });
     let _ = other_rx.await;
     tracing::info!("End external thread blocking");
+    // 路由程序卸载后, 回程记录指向的 WAN 不再生效
+    landscape_ebpf::map_setting::route::del_ipv4_route_reply_by_wan(ifindex);
+    landscape_ebpf::map_setting::route::del_ipv6_route_reply_by_wan(ifindex);
     service_status.just_change_status(ServiceStatus::Stop);
 }
 
@@ -0,0 +1,122 @@
+use landscape_common::config::sqm::{SqmServiceConfig, SqmStats};
+use landscape_common::database::LandscapeStore;
+use landscape_common::observer::IfaceObserverAction;
+use landscape_common::service::{
+    controller::ControllerService,
+    manager::{ServiceManager, ServiceStarterTrait},
+    ServiceStatus, WatchService,
+};
+use landscape_database::provider::LandscapeDBServiceProvider;
+use landscape_database::sqm::repository::SqmServiceRepository;
+use tokio::sync::broadcast;
+
+use crate::iface::get_iface_by_name;
+
+pub mod tc;
+
+#[derive(Clone, Default)]
+pub struct SqmService;
+
+#[async_trait::async_trait]
+impl ServiceStarterTrait for SqmService {
+    type Config = SqmServiceConfig;
+
+    async fn start(&self, config: SqmServiceConfig) -> WatchService {
+        let service_status = WatchService::new();
+
+        if config.enable {
+            if let Some(iface) = get_iface_by_name(&config.iface_name).await {
+                let status_clone = service_status.clone();
+                tokio::spawn(async move { run_sqm(config, iface.index, status_clone).await });
+            } else {
+                tracing::error!("Interface {} not found", config.iface_name);
+            }
+        }
+
+        service_status
+    }
+}
+
+async fn run_sqm(config: SqmServiceConfig, ifindex: u32, service_status: WatchService) {
+    service_status.just_change_status(ServiceStatus::Staring);
+    let iface_name = config.iface_name.clone();
+
+    if let Err(e) = tc::setup_sqm(&config, ifindex).await {
+        tracing::error!("setting sqm of {iface_name} error: {e}");
+        tc::clear_sqm(&iface_name, ifindex).await;
+        service_status.just_change_status(ServiceStatus::Stop);
+        return;
+    }
+
+    service_status.just_change_status(ServiceStatus::Running);
+    service_status.wait_to_stopping().await;
+
+    tc::clear_sqm(&iface_name, ifindex).await;
+    service_status.just_change_status(ServiceStatus::Stop);
+}
+
+#[derive(Clone)]
+pub struct SqmServiceManagerService {
+    store: SqmServiceRepository,
+    service: ServiceManager<SqmService>,
+}
+
+impl ControllerService for SqmServiceManagerService {
+    type Id = String;
+    type Config = SqmServiceConfig;
+    type DatabseAction = SqmServiceRepository;
+    type H = SqmService;
+
+    fn get_service(&self) -> &ServiceManager<Self::H> {
+        &self.service
+    }
+
+    fn get_repository(&self) -> &Self::DatabseAction {
+        &self.store
+    }
+}
+
+impl SqmServiceManagerService {
+    pub async fn new(
+        store_service: LandscapeDBServiceProvider,
+        mut dev_observer: broadcast::Receiver<IfaceObserverAction>,
+    ) -> Self {
+        let store = store_service.sqm_service_store();
+        let service = ServiceManager::init(store.list().await.unwrap(), Default::default()).await;
+
+        let service_clone = service.clone();
+        tokio::spawn(async move {
+            while let Ok(msg) = dev_observer.recv().await {
+                match msg {
+                    IfaceObserverAction::Up(iface_name) => {
+                        tracing::info!("restart {iface_name} SQM service");
+                        let service_config = if let Some(service_config) =
+                            store.find_by_id(iface_name.clone()).await.unwrap()
+                        {
+                            service_config
+                        } else {
+                            continue;
+                        };
+
+                        let _ = service_clone.update_service(service_config).await;
+                    }
+                    IfaceObserverAction::Down(_) => {}
+                }
+            }
+        });
+
+        let store = store_service.sqm_service_store();
+        Self { service, store }
+    }
+
+    /// 当前的队列统计, 网卡不存在时返回 None
+    pub async fn get_stats(&self, iface_name: String) -> Option<SqmStats> {
+        let iface = get_iface_by_name(&iface_name).await?;
+        let egress = tc::get_root_qdisc_stats(iface.index).await;
+        let ingress = match get_iface_by_name(&tc::ifb_name(iface.index)).await {
+            Some(ifb) => tc::get_root_qdisc_stats(ifb.index).await,
+            None => None,
+        };
+        Some(SqmStats { iface_name, egress, ingress })
+    }
+}
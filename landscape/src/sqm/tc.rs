@@ -0,0 +1,348 @@
+use futures::stream::TryStreamExt;
+use landscape_common::config::sqm::{SqmLinkLayer, SqmQdiscKind, SqmQdiscStats, SqmServiceConfig};
+use netlink_packet_route::tc::{TcAttribute, TcHandle};
+use rtnetlink::new_connection;
+use tokio::process::Command;
+
+/// 下行整形使用的 IFB 设备名, 按 ifindex 命名以免截断后与其他网卡重名
+pub fn ifb_name(ifindex: u32) -> String {
+    format!("ifb-{ifindex}")
+}
+
+/// 整形队列的根 handle, eBPF 按 QoS 分类将 skb->priority 设为 1:minor
+const SQM_QDISC_HANDLE: &str = "1:";
+
+/// HTB 中承载总带宽的父分类
+const HTB_ROOT_CLASS: &str = "1:10";
+
+/// 未设置分类的流量归入 Best Effort
+const HTB_DEFAULT_CLASS: &str = "2";
+
+/// QoS 分类对应的子分类 (minor, 保证带宽占比 / 16, HTB prio)
+///
+/// minor 与 eBPF 中的 SQM_CLASS_* 一致, cake diffserv4 以 minor - 1 选择 tin,
+/// 带宽占比参考 diffserv4 各 tin 的阈值
+const SQM_CLASSES: [(u16, u32, u8); 4] = [
+    // Bulk
+    (1, 1, 3),
+    // Best Effort
+    (2, 7, 2),
+    // Video
+    (3, 4, 1),
+    // Voice
+    (4, 4, 0),
+];
+
+async fn run_cmd(program: &str, args: &[&str]) -> Result<(), String> {
+    let output = Command::new(program).args(args).output().await.map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{program} {}: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// 整形队列的 tc 参数, `ingress` 为 IFB 上的下行方向
+fn shaper_args(config: &SqmServiceConfig, bandwidth: u32, ingress: bool) -> Vec<String> {
+    match config.qdisc {
+        SqmQdiscKind::Cake => {
+            let mut args = vec![
+                "cake".to_string(),
+                "bandwidth".to_string(),
+                format!("{bandwidth}kbit"),
+                "diffserv4".to_string(),
+            ];
+            args.push(
+                match config.link_layer {
+                    SqmLinkLayer::Ethernet => "noatm",
+                    SqmLinkLayer::Atm => "atm",
+                    SqmLinkLayer::Ptm => "ptm",
+                }
+                .to_string(),
+            );
+            args.extend(["overhead".to_string(), config.overhead.to_string()]);
+            if ingress {
+                args.push("ingress".to_string());
+            }
+            args
+        }
+        SqmQdiscKind::FqCodel => htb_class_args(config, bandwidth, bandwidth),
+    }
+}
+
+/// HTB 分类的 tc 参数
+fn htb_class_args(config: &SqmServiceConfig, rate: u32, ceil: u32) -> Vec<String> {
+    let link_layer = match config.link_layer {
+        SqmLinkLayer::Atm => "atm",
+        // HTB 不支持 PTM, 按以太网计算
+        SqmLinkLayer::Ethernet | SqmLinkLayer::Ptm => "ethernet",
+    };
+    [
+        "htb",
+        "rate",
+        &format!("{rate}kbit"),
+        "ceil",
+        &format!("{ceil}kbit"),
+        "overhead",
+        &config.overhead.to_string(),
+        "linklayer",
+        link_layer,
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+/// HTB 中各 QoS 分类的子分类, 保证各自的带宽占比并可借用至总带宽
+fn htb_sub_classes(config: &SqmServiceConfig, bandwidth: u32) -> Vec<(String, Vec<String>)> {
+    SQM_CLASSES
+        .iter()
+        .map(|(minor, share, prio)| {
+            let rate = (bandwidth as u64 * *share as u64 / 16).max(1) as u32;
+            let mut args = htb_class_args(config, rate, bandwidth);
+            args.extend(["prio".to_string(), prio.to_string()]);
+            (format!("1:{minor}"), args)
+        })
+        .collect()
+}
+
+/// 在网卡的根上替换为整形队列
+async fn set_root_shaper(
+    dev: &str,
+    config: &SqmServiceConfig,
+    bandwidth: u32,
+    ingress: bool,
+) -> Result<(), String> {
+    let args = shaper_args(config, bandwidth, ingress);
+    match config.qdisc {
+        SqmQdiscKind::Cake => {
+            let mut cmd = vec!["qdisc", "replace", "dev", dev, "root", "handle", SQM_QDISC_HANDLE];
+            cmd.extend(args.iter().map(String::as_str));
+            run_cmd("tc", &cmd).await
+        }
+        SqmQdiscKind::FqCodel => {
+            run_cmd(
+                "tc",
+                &[
+                    "qdisc",
+                    "replace",
+                    "dev",
+                    dev,
+                    "root",
+                    "handle",
+                    SQM_QDISC_HANDLE,
+                    "htb",
+                    "default",
+                    HTB_DEFAULT_CLASS,
+                ],
+            )
+            .await?;
+            let mut cmd =
+                vec!["class", "replace", "dev", dev, "parent", SQM_QDISC_HANDLE, "classid"];
+            cmd.push(HTB_ROOT_CLASS);
+            cmd.extend(args.iter().map(String::as_str));
+            run_cmd("tc", &cmd).await?;
+
+            for (classid, class_args) in htb_sub_classes(config, bandwidth) {
+                let mut cmd =
+                    vec!["class", "replace", "dev", dev, "parent", HTB_ROOT_CLASS, "classid"];
+                cmd.push(&classid);
+                cmd.extend(class_args.iter().map(String::as_str));
+                run_cmd("tc", &cmd).await?;
+                run_cmd("tc", &["qdisc", "replace", "dev", dev, "parent", &classid, "fq_codel"])
+                    .await?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// 按配置设置上行与下行的整形队列
+///
+/// netlink-packet-route 未提供 cake / htb 的参数编码, 队列的创建通过 tc 完成
+pub async fn setup_sqm(config: &SqmServiceConfig, ifindex: u32) -> Result<(), String> {
+    let iface_name = config.iface_name.as_str();
+    if config.egress_bandwidth > 0 {
+        set_root_shaper(iface_name, config, config.egress_bandwidth, false).await?;
+    }
+
+    if config.ingress_bandwidth > 0 {
+        let ifb = ifb_name(ifindex);
+        if let Err(e) = run_cmd("ip", &["link", "add", &ifb, "type", "ifb"]).await {
+            tracing::debug!("create ifb device: {e}");
+        }
+        run_cmd("ip", &["link", "set", &ifb, "up"]).await?;
+        set_root_shaper(&ifb, config, config.ingress_bandwidth, true).await?;
+
+        // eBPF 程序已挂载时 clsact 已存在
+        let _ = run_cmd("tc", &["qdisc", "add", "dev", iface_name, "clsact"]).await;
+        let prio = landscape_ebpf::SQM_INGRESS_PRIORITY.to_string();
+        run_cmd(
+            "tc",
+            &[
+                "filter", "replace", "dev", iface_name, "ingress", "prio", &prio, "protocol",
+                "all", "matchall", "action", "mirred", "egress", "redirect", "dev", &ifb,
+            ],
+        )
+        .await?;
+        landscape_ebpf::map_setting::sqm::add_sqm_ingress(ifindex);
+    }
+    Ok(())
+}
+
+/// 移除整形队列与 IFB 设备, 保留 eBPF 使用的 clsact
+pub async fn clear_sqm(iface_name: &str, ifindex: u32) {
+    landscape_ebpf::map_setting::sqm::del_sqm_ingress(ifindex);
+    let prio = landscape_ebpf::SQM_INGRESS_PRIORITY.to_string();
+    let _ = run_cmd("tc", &["filter", "del", "dev", iface_name, "ingress", "prio", &prio]).await;
+    let _ = run_cmd("tc", &["qdisc", "del", "dev", iface_name, "root"]).await;
+    let _ = run_cmd("ip", &["link", "del", &ifb_name(ifindex)]).await;
+}
+
+/// EDT 限速依赖 fq 按 skb->tstamp 发送, 根队列不是 fq 时替换为 fq, 返回被替换的队列类型
+///
+/// 已配置 SQM 的网卡 (cake / htb) 保持不变, 此时网卡上的 EDT 限速不生效
+pub async fn ensure_fq_root(iface_name: &str, ifindex: u32) -> Result<Option<String>, String> {
+    let kind = get_root_qdisc_kind(ifindex).await;
+    match kind.as_str() {
+        "fq" => Ok(None),
+        "cake" | "htb" => Err(format!("{iface_name} root qdisc is {kind}, skip fq for rate limit")),
+        _ => {
+            run_cmd("tc", &["qdisc", "replace", "dev", iface_name, "root", "fq"]).await?;
+            Ok(Some(kind))
+        }
+    }
+}
+
+/// 撤销 `ensure_fq_root` 的替换: 删除 fq 后网卡恢复默认队列 (如 mq / noqueue),
+/// 原队列不是默认队列时再按类型重新创建
+pub async fn restore_root_qdisc(iface_name: &str, ifindex: u32, kind: &str) {
+    // 根队列已被其他服务 (如 SQM) 替换时不做处理
+    if get_root_qdisc_kind(ifindex).await != "fq" {
+        return;
+    }
+    if let Err(e) = run_cmd("tc", &["qdisc", "del", "dev", iface_name, "root"]).await {
+        tracing::warn!("restore root qdisc: {e}");
+        return;
+    }
+    if !kind.is_empty() && get_root_qdisc_kind(ifindex).await != kind {
+        if let Err(e) = run_cmd("tc", &["qdisc", "replace", "dev", iface_name, "root", kind]).await
+        {
+            tracing::warn!("restore root qdisc: {e}");
+        }
+    }
+}
+
+async fn get_root_qdisc_kind(ifindex: u32) -> String {
+    get_root_qdisc_stats(ifindex).await.map(|stats| stats.kind).unwrap_or_default()
+}
+
+/// 读取网卡根队列的统计
+pub async fn get_root_qdisc_stats(ifindex: u32) -> Option<SqmQdiscStats> {
+    let (connection, handle, _) = new_connection().unwrap();
+    tokio::spawn(connection);
+    let mut qdiscs = handle.qdisc().get().execute();
+
+    while let Ok(Some(msg)) = qdiscs.try_next().await {
+        if msg.header.index != ifindex as i32 || msg.header.parent != TcHandle::ROOT {
+            continue;
+        }
+        let mut result = SqmQdiscStats::default();
+        for attr in msg.attributes {
+            match attr {
+                TcAttribute::Kind(kind) => result.kind = kind,
+                TcAttribute::Stats(stats) => {
+                    result.bytes = stats.bytes;
+                    result.packets = stats.packets;
+                    result.drops = stats.drops;
+                    result.overlimits = stats.overlimits;
+                    result.backlog = stats.backlog;
+                    result.qlen = stats.qlen;
+                }
+                _ => {}
+            }
+        }
+        return Some(result);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(qdisc: SqmQdiscKind, link_layer: SqmLinkLayer) -> SqmServiceConfig {
+        SqmServiceConfig {
+            iface_name: "eth0".to_string(),
+            enable: true,
+            qdisc,
+            egress_bandwidth: 20000,
+            ingress_bandwidth: 100000,
+            overhead: 34,
+            link_layer,
+            update_at: 0.0,
+        }
+    }
+
+    #[test]
+    fn cake_shaper_args() {
+        let config = config(SqmQdiscKind::Cake, SqmLinkLayer::Ptm);
+        assert_eq!(
+            shaper_args(&config, 20000, false),
+            ["cake", "bandwidth", "20000kbit", "diffserv4", "ptm", "overhead", "34"]
+        );
+        assert_eq!(
+            shaper_args(&config, 100000, true),
+            ["cake", "bandwidth", "100000kbit", "diffserv4", "ptm", "overhead", "34", "ingress"]
+        );
+    }
+
+    #[test]
+    fn htb_shaper_args() {
+        let expected = |link_layer: &str| {
+            [
+                "htb",
+                "rate",
+                "20000kbit",
+                "ceil",
+                "20000kbit",
+                "overhead",
+                "34",
+                "linklayer",
+                link_layer,
+            ]
+            .map(String::from)
+        };
+        let atm = config(SqmQdiscKind::FqCodel, SqmLinkLayer::Atm);
+        assert_eq!(shaper_args(&atm, 20000, true), expected("atm"));
+        // HTB 不支持 PTM, 按以太网计算
+        let ptm = config(SqmQdiscKind::FqCodel, SqmLinkLayer::Ptm);
+        assert_eq!(shaper_args(&ptm, 20000, false), expected("ethernet"));
+    }
+
+    #[test]
+    fn htb_sub_classes_match_qos_classids() {
+        let config = config(SqmQdiscKind::FqCodel, SqmLinkLayer::Ethernet);
+        let classes = htb_sub_classes(&config, 16000);
+        let classids: Vec<&str> = classes.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(classids, ["1:1", "1:2", "1:3", "1:4"]);
+        // 保证带宽之和不超过总带宽
+        assert_eq!(SQM_CLASSES.iter().map(|(_, share, _)| share).sum::<u32>(), 16);
+        assert_eq!(classes[3].1[2], "4000kbit");
+        assert_eq!(classes[3].1[4], "16000kbit");
+        assert_eq!(classes[3].1[9..], ["prio", "0"]);
+        assert!(classids.contains(&format!("1:{HTB_DEFAULT_CLASS}").as_str()));
+    }
+
+    #[test]
+    fn ifb_name_is_unique_and_fits_ifnamsiz() {
+        assert_eq!(ifb_name(12), "ifb-12");
+        assert_ne!(ifb_name(12), ifb_name(13));
+        // IFNAMSIZ - 1
+        assert!(ifb_name(u32::MAX).len() <= 15);
+    }
+}
@@ -0,0 +1,42 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use landscape_common::auth::audit::{AuditLog, AuditLogQuery, MAX_AUDIT_LOGS};
+use landscape_database::audit_log::repository::AuditLogRepository;
+use landscape_database::provider::LandscapeDBServiceProvider;
+use landscape_database::repository::Repository;
+
+/// 每写入多少条记录清理一次过期日志
+const PRUNE_INTERVAL: u64 = 100;
+
+/// 登录与修改类 API 调用的审计日志
+#[derive(Clone)]
+pub struct AuditLogService {
+    store: AuditLogRepository,
+    written: Arc<AtomicU64>,
+}
+
+impl AuditLogService {
+    pub fn new(store_provider: LandscapeDBServiceProvider) -> Self {
+        Self {
+            store: store_provider.audit_log_store(),
+            written: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    pub async fn record(&self, log: AuditLog) {
+        if let Err(e) = self.store.set_model(log).await {
+            tracing::error!("record audit log error: {e:?}");
+            return;
+        }
+        if self.written.fetch_add(1, Ordering::Relaxed) % PRUNE_INTERVAL == 0 {
+            if let Err(e) = self.store.prune(MAX_AUDIT_LOGS).await {
+                tracing::error!("prune audit logs error: {e:?}");
+            }
+        }
+    }
+
+    pub async fn query(&self, query: AuditLogQuery) -> Vec<AuditLog> {
+        self.store.query(query).await.unwrap_or_default()
+    }
+}
@@ -1,5 +1,6 @@
 use arc_swap::ArcSwap;
 use fs2::FileExt;
+use landscape_common::config::import::ConfigImportMode;
 use landscape_common::config::{
     InitConfig, LandscapeConfig, LandscapeDnsConfig, LandscapeMetricConfig, LandscapeUIConfig,
     RuntimeConfig,
@@ -45,8 +46,11 @@ impl LandscapeConfigService {
             firewall_rules: self.store.firewall_rule_store().list().await.unwrap(),
             firewall_blacklists: self.store.firewall_blacklist_store().list().await.unwrap(),
             wifi_configs: self.store.wifi_service_store().list().await.unwrap(),
+            wifi_stations: self.store.wifi_station_service_store().list().await.unwrap(),
+            wireguards: self.store.wireguard_service_store().list().await.unwrap(),
             dhcpv4_services: self.store.dhcp_v4_server_store().list().await.unwrap(),
             mss_clamps: self.store.mss_clamp_service_store().list().await.unwrap(),
+            sqms: self.store.sqm_service_store().list().await.unwrap(),
             geo_ips: self.store.geo_ip_rule_store().list().await.unwrap(),
             geo_sites: self.store.geo_site_rule_store().list().await.unwrap(),
             route_lans: self.store.route_lan_service_store().list().await.unwrap(),
@@ -55,9 +59,22 @@ impl LandscapeConfigService {
             dns_redirects: self.store.dns_redirect_rule_store().list().await.unwrap(),
             dns_upstream_configs: self.store.dns_upstream_config_store().list().await.unwrap(),
             enrolled_devices: self.store.enrolled_device_store().list().await.unwrap(),
+            traffic_quotas: self.store.traffic_quota_store().list().await.unwrap(),
+            rate_limits: self.store.rate_limit_store().list().await.unwrap(),
+            netns_targets: self.store.netns_target_store().list().await.unwrap(),
+            managed_containers: self.store.managed_container_store().list().await.unwrap(),
         }
     }
 
+    /// 在事务中写入导入的配置, 调用方需先完成校验
+    pub async fn import_init_config(
+        &self,
+        config: InitConfig,
+        mode: ConfigImportMode,
+    ) -> LdResult<()> {
+        self.store.import_init_config(config, mode).await
+    }
+
     pub fn get_ui_config_from_memory(&self) -> LandscapeUIConfig {
         self.config.load().ui.clone()
     }
@@ -66,6 +83,10 @@ impl LandscapeConfigService {
         self.config.load().file_config.metric.clone()
     }
 
+    pub fn get_prometheus_token(&self) -> Option<String> {
+        self.config.load().metric.prometheus_token.clone()
+    }
+
     pub fn get_dns_config(&self) -> (LandscapeDnsConfig, String) {
         let config = self.config.load();
         let dns = config.file_config.dns.clone();
@@ -1,5 +1,9 @@
+pub mod audit_service;
 pub mod config_service;
 pub mod dns_service;
 pub mod ebpf_service;
 pub mod routerstatus;
+pub mod snapshot_service;
+pub mod totp;
+pub mod user_service;
 pub mod web_pty;
@@ -0,0 +1,247 @@
+use std::sync::Arc;
+
+use landscape_common::config::import::{ConfigImportMode, ConfigSectionDiff};
+use landscape_common::config::snapshot::{
+    ConfigSnapshot, ConfigSnapshotInfo, PendingConfigConfirm, MAX_CONFIG_SNAPSHOTS,
+};
+use landscape_common::config::{InitConfig, LandscapeConfig};
+use landscape_common::error::LdResult;
+use landscape_common::utils::id::gen_database_uuid;
+use landscape_common::utils::time::{get_current_time_ms, get_f64_timestamp};
+use landscape_database::config_pending_confirm::repository::ConfigPendingConfirmRepository;
+use landscape_database::config_snapshot::repository::ConfigSnapshotRepository;
+use landscape_database::provider::LandscapeDBServiceProvider;
+use landscape_database::repository::Repository;
+use tokio::sync::{Mutex, OwnedMutexGuard};
+use uuid::Uuid;
+
+use super::config_service::LandscapeConfigService;
+
+/// 非用户触发的快照 (基线补记与自动回滚) 的作者
+pub const SYSTEM_AUTHOR: &str = "system";
+
+/// 配置快照与待确认修改
+#[derive(Clone)]
+pub struct ConfigSnapshotService {
+    config_service: LandscapeConfigService,
+    store: ConfigSnapshotRepository,
+    /// 串行化配置修改, 使每个快照只包含对应请求的改动
+    write_lock: Arc<Mutex<()>>,
+    /// 待确认修改保存在数据库中, 重启后继续等待确认
+    pending: ConfigPendingConfirmRepository,
+}
+
+impl ConfigSnapshotService {
+    pub async fn new(
+        config_service: LandscapeConfigService,
+        store_provider: LandscapeDBServiceProvider,
+    ) -> Self {
+        Self {
+            config_service,
+            store: store_provider.config_snapshot_store(),
+            write_lock: Arc::new(Mutex::new(())),
+            pending: store_provider.config_pending_confirm_store(),
+        }
+    }
+
+    /// 修改配置及记录快照前需持有该锁, 直到修改后的快照记录完成
+    pub async fn lock_writes(&self) -> OwnedMutexGuard<()> {
+        self.write_lock.clone().lock_owned().await
+    }
+
+    /// 当前配置与最新快照存在差异时记录新快照, 返回最新快照的 ID
+    pub async fn record(&self, author: &str, summary: &str) -> LdResult<Uuid> {
+        let mut current = self.config_service.export_init_config().await;
+        // 配置文件部分 (含管理员密码) 不参与导入与回滚, 不保存到快照
+        current.config = LandscapeConfig::default();
+        let latest = self.store.latest().await?;
+
+        let diff = match &latest {
+            Some(latest) => {
+                let previous = InitConfig::from_toml(&latest.content).unwrap_or_default();
+                let diff = previous.diff(&current, ConfigImportMode::Replace);
+                if diff.is_empty() {
+                    return Ok(latest.id);
+                }
+                diff
+            }
+            None => vec![],
+        };
+
+        let snapshot = ConfigSnapshot {
+            id: gen_database_uuid(),
+            author: author.to_string(),
+            summary: summary.to_string(),
+            diff,
+            content: toml::to_string(&current).unwrap_or_default(),
+            update_at: get_f64_timestamp(),
+        };
+        let snapshot = self.store.set_model(snapshot).await?;
+        if let Err(e) = self.store.prune(MAX_CONFIG_SNAPSHOTS).await {
+            tracing::error!("prune config snapshots error: {e:?}");
+        }
+        Ok(snapshot.id)
+    }
+
+    /// 修改前的快照 ID. 仅在确认窗口的首次修改时补记基线快照, 以便回滚到窗口开始前的配置;
+    /// 其余修改直接使用最新快照, 数据库被其他途径修改的部分会计入本次修改的快照
+    pub async fn baseline(&self, confirm: bool) -> LdResult<Uuid> {
+        if confirm && self.list_pending().await.is_empty() {
+            return self.record(SYSTEM_AUTHOR, "baseline").await;
+        }
+        match self.store.latest_id().await? {
+            Some(id) => Ok(id),
+            None => self.record(SYSTEM_AUTHOR, "baseline").await,
+        }
+    }
+
+    pub async fn list(&self) -> Vec<ConfigSnapshotInfo> {
+        self.store.list_infos().await.unwrap_or_default()
+    }
+
+    pub async fn get(&self, id: Uuid) -> Option<ConfigSnapshot> {
+        self.store.find_by_id(id).await.ok().flatten()
+    }
+
+    /// 比较两个快照, `from` 视为旧配置
+    pub async fn compare(&self, from: Uuid, to: Uuid) -> Option<Vec<ConfigSectionDiff>> {
+        let from = InitConfig::from_toml(&self.get(from).await?.content).ok()?;
+        let to = InitConfig::from_toml(&self.get(to).await?.content).ok()?;
+        Some(from.diff(&to, ConfigImportMode::Replace))
+    }
+
+    pub async fn add_pending(
+        &self,
+        revert_to: Uuid,
+        applied: Uuid,
+        author: &str,
+        summary: &str,
+        timeout_secs: u64,
+    ) -> LdResult<PendingConfigConfirm> {
+        let pending = PendingConfigConfirm {
+            id: gen_database_uuid(),
+            revert_to,
+            applied,
+            author: author.to_string(),
+            summary: summary.to_string(),
+            deadline: get_current_time_ms().unwrap_or_default() + timeout_secs * 1000,
+            update_at: get_f64_timestamp(),
+        };
+        self.pending.set_model(pending).await
+    }
+
+    /// 确认或超时时移除, 返回 None 说明已被处理
+    pub async fn take_pending(&self, id: Uuid) -> Option<PendingConfigConfirm> {
+        match self.pending.take(id).await {
+            Ok(pending) => pending,
+            Err(e) => {
+                tracing::error!("take pending config confirm error: {e:?}");
+                None
+            }
+        }
+    }
+
+    /// 按截止时间排序, 启动时据此恢复自动回滚
+    pub async fn list_pending(&self) -> Vec<PendingConfigConfirm> {
+        let mut pending = self.pending.list_all().await.unwrap_or_default();
+        pending.sort_by_key(|p| p.deadline);
+        pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use clap::Parser;
+    use landscape_common::args::WebCommArgs;
+    use landscape_common::config::RuntimeConfig;
+    use landscape_common::database::LandscapeStore;
+    use landscape_common::netns_target::{NetnsSource, NetnsTargetConfig};
+
+    use super::*;
+
+    async fn new_service(provider: &LandscapeDBServiceProvider) -> ConfigSnapshotService {
+        let mut args = WebCommArgs::parse_from(["landscape"]);
+        args.config_dir = Some(std::env::temp_dir().join("landscape-snapshot-test"));
+        let config_service =
+            LandscapeConfigService::new(RuntimeConfig::new(args), provider.clone()).await;
+        ConfigSnapshotService::new(config_service, provider.clone()).await
+    }
+
+    async fn add_netns(provider: &LandscapeDBServiceProvider, name: &str) {
+        let config = NetnsTargetConfig {
+            id: gen_database_uuid(),
+            enable: true,
+            name: name.to_string(),
+            source: NetnsSource::Named { netns: name.to_string() },
+            remark: String::new(),
+            update_at: 0.0,
+        };
+        provider.netns_target_store().set(config).await.unwrap();
+        // 快照按毫秒时间戳排序
+        tokio::time::sleep(Duration::from_millis(2)).await;
+    }
+
+    #[tokio::test]
+    async fn pending_confirm_survives_restart() {
+        let provider = LandscapeDBServiceProvider::mem_test_db().await;
+        let service = new_service(&provider).await;
+        let revert_to = gen_database_uuid();
+        let applied = gen_database_uuid();
+        let pending =
+            service.add_pending(revert_to, applied, "admin", "PUT /config", 60).await.unwrap();
+        let now = get_current_time_ms().unwrap();
+        assert!(pending.deadline > now && pending.deadline <= now + 60_000);
+
+        // 重启后从数据库恢复
+        let restarted = new_service(&provider).await;
+        let resumed = restarted.list_pending().await;
+        assert_eq!(resumed.len(), 1);
+        assert_eq!(resumed[0].id, pending.id);
+        assert_eq!(resumed[0].revert_to, revert_to);
+        assert_eq!(resumed[0].applied, applied);
+        assert_eq!(resumed[0].deadline, pending.deadline);
+
+        // 确认与超时回滚只有一方能取到
+        assert!(restarted.take_pending(pending.id).await.is_some());
+        assert!(service.take_pending(pending.id).await.is_none());
+        assert!(restarted.list_pending().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn baseline_records_only_on_first_confirm_mutation() {
+        let provider = LandscapeDBServiceProvider::mem_test_db().await;
+        let service = new_service(&provider).await;
+
+        // 没有任何快照时补记基线
+        let first = service.baseline(false).await.unwrap();
+        assert_eq!(service.list().await.len(), 1);
+        tokio::time::sleep(Duration::from_millis(2)).await;
+
+        // 普通修改直接使用最新快照
+        add_netns(&provider, "ns1").await;
+        assert_eq!(service.baseline(false).await.unwrap(), first);
+        assert_eq!(service.list().await.len(), 1);
+
+        // 确认窗口的首次修改补记基线
+        let window = service.baseline(true).await.unwrap();
+        assert_ne!(window, first);
+        assert_eq!(service.list().await.len(), 2);
+        tokio::time::sleep(Duration::from_millis(2)).await;
+
+        add_netns(&provider, "ns2").await;
+        let applied = service.record("admin", "POST /netns").await.unwrap();
+        assert_ne!(applied, window);
+        assert_eq!(service.record("admin", "POST /netns").await.unwrap(), applied);
+        service.add_pending(window, applied, "admin", "POST /netns", 60).await.unwrap();
+
+        // 窗口内的后续修改不再补记
+        assert_eq!(service.baseline(true).await.unwrap(), applied);
+        assert_eq!(service.list().await.len(), 3);
+
+        let diff = service.compare(first, applied).await.unwrap();
+        assert!(!diff.is_empty());
+        assert!(service.compare(window, window).await.unwrap().is_empty());
+    }
+}
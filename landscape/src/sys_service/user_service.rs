@@ -0,0 +1,381 @@
+use std::collections::HashMap;
+use std::fs::Permissions;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use landscape_common::args::LAND_HOME_PATH;
+use landscape_common::auth::user::{
+    ApiToken, CreateApiTokenResult, LandscapeUser, TotpEnrollment, UserRole, API_TOKEN_PREFIX,
+};
+use landscape_common::config::AuthRuntimeConfig;
+use landscape_common::error::LdResult;
+use landscape_common::utils::id::gen_database_uuid;
+use landscape_common::utils::time::{get_current_time_ms, get_f64_timestamp};
+use landscape_common::LANDSCAPE_ADMIN_SEED_FILE_NAME;
+use landscape_database::api_token::repository::ApiTokenRepository;
+use landscape_database::provider::LandscapeDBServiceProvider;
+use landscape_database::repository::Repository;
+use landscape_database::user::repository::UserRepository;
+use pbkdf2::pbkdf2_hmac;
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use super::totp;
+
+const TOTP_ISSUER: &str = "Landscape";
+const PASSWORD_HASH_SCHEME: &str = "pbkdf2_sha256";
+const PASSWORD_HASH_ROUNDS: u32 = 100_000;
+const PASSWORD_SALT_LENGTH: usize = 16;
+const API_TOKEN_LENGTH: usize = 40;
+
+/// 用户账号与 API Token
+#[derive(Clone)]
+pub struct LandscapeUserService {
+    users: UserRepository,
+    tokens: ApiTokenRepository,
+    /// 用户不存在时用于校验的哈希, 使耗时与用户存在时一致
+    dummy_hash: Arc<String>,
+    /// 待激活的 TOTP 密钥, 激活前原密钥保持有效
+    pending_totp: Arc<Mutex<HashMap<Uuid, String>>>,
+    /// 每个用户最近一次通过校验的时间步, 同一验证码不能重复使用
+    totp_last_step: Arc<Mutex<HashMap<Uuid, u64>>>,
+}
+
+impl LandscapeUserService {
+    pub async fn new(store_provider: LandscapeDBServiceProvider, auth: &AuthRuntimeConfig) -> Self {
+        let service = Self {
+            users: store_provider.user_store(),
+            tokens: store_provider.api_token_store(),
+            dummy_hash: Arc::new(hash_password_blocking("landscape").await),
+            pending_totp: Arc::default(),
+            totp_last_step: Arc::default(),
+        };
+        if let Err(e) = service.seed_config_admin(auth).await {
+            tracing::error!("seed admin user from config error: {e:?}");
+        }
+        service
+    }
+
+    /// 配置文件中的管理员账号不存在时创建, 之后以数据库为准,
+    /// 通过 API 修改的密码不会在重启后被配置文件覆盖;
+    /// 配置文件中的密码与上次启动时不同则重置该账号, 用于找回无法登录的管理员
+    async fn seed_config_admin(&self, auth: &AuthRuntimeConfig) -> LdResult<()> {
+        let seed_path = LAND_HOME_PATH.join(LANDSCAPE_ADMIN_SEED_FILE_NAME);
+        let seeded = tokio::fs::read_to_string(&seed_path).await.ok();
+        let password_hash = hash_password_blocking(&auth.admin_pass).await;
+
+        match self.users.find_by_username(&auth.admin_user).await? {
+            None => {
+                let user = LandscapeUser {
+                    id: gen_database_uuid(),
+                    username: auth.admin_user.clone(),
+                    password_hash: password_hash.clone(),
+                    role: UserRole::Admin,
+                    enable: true,
+                    totp_secret: None,
+                    totp_enable: false,
+                    token_version: 0,
+                    update_at: get_f64_timestamp(),
+                };
+                self.users.set_model(user).await?;
+            }
+            Some(mut user) => {
+                // 首次记录时无法判断配置是否变化, 保持数据库中的账号不变
+                let Some(seeded) = seeded else {
+                    write_admin_seed(&seed_path, &password_hash).await;
+                    return Ok(());
+                };
+                if verify_password_blocking(&auth.admin_pass, seeded.trim()).await {
+                    return Ok(());
+                }
+                tracing::warn!("admin password in config changed, reset user {}", user.username);
+                user.password_hash = password_hash.clone();
+                user.role = UserRole::Admin;
+                user.enable = true;
+                user.totp_secret = None;
+                user.totp_enable = false;
+                user.token_version = user.token_version.wrapping_add(1);
+                user.update_at = get_f64_timestamp();
+                self.pending_totp.lock().unwrap().remove(&user.id);
+                self.users.set_or_update_model(user.id, user).await?;
+            }
+        }
+        write_admin_seed(&seed_path, &password_hash).await;
+        Ok(())
+    }
+
+    /// 校验用户名与密码, 仅返回已启用的用户;
+    /// 用户不存在或已禁用时同样计算一次哈希, 避免通过耗时枚举用户名
+    pub async fn verify_password(&self, username: &str, password: &str) -> Option<LandscapeUser> {
+        let Some(user) = self.users.find_by_username(username).await.ok().flatten() else {
+            verify_password_blocking(password, &self.dummy_hash).await;
+            return None;
+        };
+        let verified = verify_password_blocking(password, &user.password_hash).await;
+        (verified && user.enable).then_some(user)
+    }
+
+    /// 校验 API Token, 返回所属用户与生效的角色
+    pub async fn verify_token(&self, secret: &str) -> Option<(LandscapeUser, UserRole)> {
+        let token = self.tokens.find_by_token_hash(&hash_token(secret)).await.ok().flatten()?;
+        if !token.is_valid(get_current_time_ms().unwrap_or_default()) {
+            return None;
+        }
+        let user = self.find_user(token.user_id).await.filter(|u| u.enable)?;
+        let role = token.role.min(user.role);
+        Some((user, role))
+    }
+
+    pub async fn find_user(&self, id: Uuid) -> Option<LandscapeUser> {
+        self.users.find_by_id(id).await.ok().flatten()
+    }
+
+    pub async fn find_user_by_name(&self, username: &str) -> Option<LandscapeUser> {
+        self.users.find_by_username(username).await.ok().flatten()
+    }
+
+    pub async fn list_users(&self) -> Vec<LandscapeUser> {
+        self.users.list_all().await.unwrap_or_default()
+    }
+
+    /// 除 `exclude` 之外已启用的管理员数量
+    pub async fn count_other_admins(&self, exclude: Uuid) -> usize {
+        self.list_users()
+            .await
+            .iter()
+            .filter(|u| u.id != exclude && u.enable && u.role.is_admin())
+            .count()
+    }
+
+    pub async fn create_user(
+        &self,
+        username: String,
+        password: &str,
+        role: UserRole,
+    ) -> LdResult<LandscapeUser> {
+        let user = LandscapeUser {
+            id: gen_database_uuid(),
+            username,
+            password_hash: hash_password_blocking(password).await,
+            role,
+            enable: true,
+            totp_secret: None,
+            totp_enable: false,
+            token_version: 0,
+            update_at: get_f64_timestamp(),
+        };
+        self.users.set_model(user).await
+    }
+
+    pub async fn update_user(
+        &self,
+        mut user: LandscapeUser,
+        role: Option<UserRole>,
+        enable: Option<bool>,
+        password: Option<&str>,
+    ) -> LdResult<LandscapeUser> {
+        if let Some(role) = role {
+            user.role = role;
+        }
+        if let Some(enable) = enable {
+            user.enable = enable;
+        }
+        if let Some(password) = password {
+            user.password_hash = hash_password_blocking(password).await;
+            user.token_version = user.token_version.wrapping_add(1);
+        }
+        user.update_at = get_f64_timestamp();
+        self.users.set_or_update_model(user.id, user).await
+    }
+
+    pub async fn delete_user(&self, id: Uuid) -> LdResult<()> {
+        self.tokens.delete_by_user(id).await?;
+        self.users.delete_model(id).await
+    }
+
+    /// 生成新的 TOTP 密钥, 需调用 `activate_totp` 验证后才会替换原密钥
+    pub fn enroll_totp(&self, user: &LandscapeUser) -> Option<TotpEnrollment> {
+        let secret = totp::generate_secret();
+        let uri = totp::otpauth_uri(TOTP_ISSUER, &user.username, &secret)?;
+        self.pending_totp.lock().unwrap().insert(user.id, secret.clone());
+        Some(TotpEnrollment { secret, uri })
+    }
+
+    pub fn pending_totp_secret(&self, user_id: Uuid) -> Option<String> {
+        self.pending_totp.lock().unwrap().get(&user_id).cloned()
+    }
+
+    pub fn verify_totp(&self, user: &LandscapeUser, code: &str) -> bool {
+        user.totp_secret.as_deref().is_some_and(|secret| self.accept_totp(user.id, secret, code))
+    }
+
+    /// 校验验证码, 拒绝不晚于上次通过时间步的验证码
+    pub fn accept_totp(&self, user_id: Uuid, secret: &str, code: &str) -> bool {
+        let now = get_current_time_ms().unwrap_or_default() / 1000;
+        let Some(step) = totp::verify(secret, code, now) else {
+            return false;
+        };
+        let mut last_steps = self.totp_last_step.lock().unwrap();
+        if last_steps.get(&user_id).is_some_and(|last| step <= *last) {
+            return false;
+        }
+        last_steps.insert(user_id, step);
+        true
+    }
+
+    /// 启用新密钥, 调用前需已用该密钥校验过验证码
+    pub async fn activate_totp(
+        &self,
+        mut user: LandscapeUser,
+        secret: String,
+    ) -> LdResult<LandscapeUser> {
+        self.pending_totp.lock().unwrap().remove(&user.id);
+        user.totp_secret = Some(secret);
+        user.totp_enable = true;
+        user.update_at = get_f64_timestamp();
+        self.users.set_or_update_model(user.id, user).await
+    }
+
+    pub async fn disable_totp(&self, mut user: LandscapeUser) -> LdResult<LandscapeUser> {
+        self.pending_totp.lock().unwrap().remove(&user.id);
+        user.totp_secret = None;
+        user.totp_enable = false;
+        user.update_at = get_f64_timestamp();
+        self.users.set_or_update_model(user.id, user).await
+    }
+
+    pub async fn list_tokens(&self, user_id: Uuid) -> Vec<ApiToken> {
+        self.tokens.list_by_user(user_id).await.unwrap_or_default()
+    }
+
+    pub async fn find_token(&self, id: Uuid) -> Option<ApiToken> {
+        self.tokens.find_by_id(id).await.ok().flatten()
+    }
+
+    pub async fn create_token(
+        &self,
+        user_id: Uuid,
+        name: String,
+        role: UserRole,
+        expire_secs: Option<u64>,
+    ) -> LdResult<CreateApiTokenResult> {
+        let secret: String = rand::rng()
+            .sample_iter(rand::distr::Alphanumeric)
+            .take(API_TOKEN_LENGTH)
+            .map(char::from)
+            .collect();
+        let secret = format!("{API_TOKEN_PREFIX}{secret}");
+        let now = get_current_time_ms().unwrap_or_default();
+        let token = ApiToken {
+            id: gen_database_uuid(),
+            user_id,
+            name,
+            role,
+            token_hash: hash_token(&secret),
+            expire_at: expire_secs.map(|secs| now + secs * 1000),
+            revoked: false,
+            update_at: get_f64_timestamp(),
+        };
+        let token = self.tokens.set_model(token).await?;
+        Ok(CreateApiTokenResult { token, secret })
+    }
+
+    pub async fn revoke_token(&self, mut token: ApiToken) -> LdResult<ApiToken> {
+        token.revoked = true;
+        token.update_at = get_f64_timestamp();
+        self.tokens.set_or_update_model(token.id, token).await
+    }
+}
+
+async fn write_admin_seed(path: &Path, password_hash: &str) {
+    if let Err(e) = tokio::fs::write(path, password_hash).await {
+        tracing::error!("write admin seed file error: {e:?}");
+        return;
+    }
+    if let Err(e) = tokio::fs::set_permissions(path, Permissions::from_mode(0o600)).await {
+        tracing::error!("set admin seed file permissions error: {e:?}");
+    }
+}
+
+fn hash_token(secret: &str) -> String {
+    format!("{:x}", Sha256::digest(secret.as_bytes()))
+}
+
+/// 哈希计算耗时较长, 放到阻塞线程池中执行, 避免占用异步工作线程
+async fn hash_password_blocking(password: &str) -> String {
+    let password = password.to_string();
+    tokio::task::spawn_blocking(move || hash_password(&password))
+        .await
+        .expect("password hash task panicked")
+}
+
+async fn verify_password_blocking(password: &str, password_hash: &str) -> bool {
+    let (password, password_hash) = (password.to_string(), password_hash.to_string());
+    tokio::task::spawn_blocking(move || verify_password(&password, &password_hash))
+        .await
+        .unwrap_or(false)
+}
+
+/// 生成 `pbkdf2_sha256$<轮数>$<盐>$<哈希>` 格式的密码哈希
+fn hash_password(password: &str) -> String {
+    let salt: String = rand::rng()
+        .sample_iter(rand::distr::Alphanumeric)
+        .take(PASSWORD_SALT_LENGTH)
+        .map(char::from)
+        .collect();
+    let hash = pbkdf2_sha256(password.as_bytes(), salt.as_bytes(), PASSWORD_HASH_ROUNDS);
+    format!("{PASSWORD_HASH_SCHEME}${PASSWORD_HASH_ROUNDS}${salt}${}", to_hex(&hash))
+}
+
+fn verify_password(password: &str, password_hash: &str) -> bool {
+    let mut parts = password_hash.split('$');
+    let (Some(PASSWORD_HASH_SCHEME), Some(rounds), Some(salt), Some(expected), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return false;
+    };
+    let Ok(rounds) = rounds.parse::<u32>() else {
+        return false;
+    };
+    let hash = to_hex(&pbkdf2_sha256(password.as_bytes(), salt.as_bytes(), rounds));
+    // 定长比较, 避免时序泄露
+    hash.len() == expected.len()
+        && hash.bytes().zip(expected.bytes()).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+fn pbkdf2_sha256(password: &[u8], salt: &[u8], rounds: u32) -> [u8; 32] {
+    let mut result = [0u8; 32];
+    pbkdf2_hmac::<Sha256>(password, salt, rounds, &mut result);
+    result
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pbkdf2_sha256_vectors() {
+        assert_eq!(
+            to_hex(&pbkdf2_sha256(b"password", b"salt", 1)),
+            "120fb6cffcf8b32c43e7225256c4f837a86548c92ccc35480805987cb70be17b"
+        );
+        assert_eq!(
+            to_hex(&pbkdf2_sha256(b"password", b"salt", 4096)),
+            "c5e478d59288c841aa530db6845c4c8d962893a001ce4e11a4963873aa98134a"
+        );
+    }
+
+    #[test]
+    fn password_hash_roundtrip() {
+        let hash = hash_password("root");
+        assert!(verify_password("root", &hash));
+        assert!(!verify_password("toor", &hash));
+        assert!(!verify_password("root", "root"));
+    }
+}
@@ -0,0 +1,177 @@
+use landscape_common::config::wifi::{
+    WifiBand, WifiBssConfig, WifiChannelWidth, WifiSecurity, WifiServiceConfig,
+};
+
+/// 将 AP 配置转换为 hostapd 配置文件内容
+pub fn render_hostapd_config(config: &WifiServiceConfig) -> String {
+    let ap = &config.ap;
+    let mut lines = vec![
+        "driver=nl80211".to_string(),
+        format!("country_code={}", ap.country),
+        "ieee80211d=1".to_string(),
+    ];
+
+    let hw_mode = if ap.band == WifiBand::Band2G { "g" } else { "a" };
+    lines.push(format!("hw_mode={hw_mode}"));
+    lines.push(format!("channel={}", ap.channel));
+
+    match ap.band {
+        WifiBand::Band2G => {
+            lines.push("ieee80211n=1".to_string());
+            lines.push("ieee80211ax=1".to_string());
+            if ap.width == WifiChannelWidth::Mhz40 {
+                lines.push(format!("ht_capab={}", ht40_capab(ap.band, ap.channel)));
+            }
+        }
+        WifiBand::Band5G => {
+            lines.push("ieee80211h=1".to_string());
+            lines.push("ieee80211n=1".to_string());
+            lines.push("ieee80211ac=1".to_string());
+            lines.push("ieee80211ax=1".to_string());
+            if ap.width >= WifiChannelWidth::Mhz40 {
+                lines.push(format!("ht_capab={}", ht40_capab(ap.band, ap.channel)));
+            }
+            let chwidth = oper_chwidth(ap.width);
+            lines.push(format!("vht_oper_chwidth={chwidth}"));
+            lines.push(format!("he_oper_chwidth={chwidth}"));
+            if let Some(center) = center_channel(ap.band, ap.channel, ap.width) {
+                lines.push(format!("vht_oper_centr_freq_seg0_idx={center}"));
+                lines.push(format!("he_oper_centr_freq_seg0_idx={center}"));
+            }
+        }
+        WifiBand::Band6G => {
+            lines.push("ieee80211ax=1".to_string());
+            lines.push(format!("op_class={}", op_class_6g(ap.width)));
+            lines.push(format!("he_oper_chwidth={}", oper_chwidth(ap.width)));
+            if let Some(center) = center_channel(ap.band, ap.channel, ap.width) {
+                lines.push(format!("he_oper_centr_freq_seg0_idx={center}"));
+            }
+        }
+    }
+
+    for (index, bss) in ap.bss.iter().enumerate() {
+        lines.push(String::new());
+        let iface_name = config.bss_iface_name(index);
+        if index == 0 {
+            lines.push(format!("interface={iface_name}"));
+        } else {
+            lines.push(format!("bss={iface_name}"));
+        }
+        render_bss(&mut lines, ap.band, bss);
+    }
+    lines.push(String::new());
+
+    lines.join("\n")
+}
+
+fn render_bss(lines: &mut Vec<String>, band: WifiBand, bss: &WifiBssConfig) {
+    lines.push("utf8_ssid=1".to_string());
+    lines.push(format!("ssid={}", bss.ssid));
+    if let Some(bridge) = &bss.bridge {
+        lines.push(format!("bridge={bridge}"));
+    }
+    if bss.hidden {
+        lines.push("ignore_broadcast_ssid=1".to_string());
+    }
+    if bss.isolate {
+        lines.push("ap_isolate=1".to_string());
+    }
+    lines.push("wmm_enabled=1".to_string());
+
+    let passphrase = match &bss.security {
+        WifiSecurity::Open => return,
+        WifiSecurity::Wpa2Psk { passphrase } => {
+            lines.push("wpa_key_mgmt=WPA-PSK".to_string());
+            passphrase
+        }
+        WifiSecurity::Wpa3Sae { passphrase } => {
+            lines.push("wpa_key_mgmt=SAE".to_string());
+            lines.push("ieee80211w=2".to_string());
+            // 6GHz 仅支持 hash-to-element
+            let sae_pwe = if band == WifiBand::Band6G { 1 } else { 2 };
+            lines.push(format!("sae_pwe={sae_pwe}"));
+            passphrase
+        }
+        WifiSecurity::Wpa2Wpa3 { passphrase } => {
+            lines.push("wpa_key_mgmt=WPA-PSK SAE".to_string());
+            lines.push("ieee80211w=1".to_string());
+            lines.push("sae_pwe=2".to_string());
+            passphrase
+        }
+    };
+    lines.push("wpa=2".to_string());
+    lines.push("rsn_pairwise=CCMP".to_string());
+    if passphrase.len() == 64 {
+        lines.push(format!("wpa_psk={passphrase}"));
+    } else {
+        lines.push(format!("wpa_passphrase={passphrase}"));
+    }
+}
+
+/// 40MHz 时辅信道的方向
+fn ht40_capab(band: WifiBand, channel: u8) -> &'static str {
+    match band {
+        WifiBand::Band2G if channel == 0 || channel <= 7 => "[HT40+]",
+        WifiBand::Band2G => "[HT40-]",
+        _ if channel == 0 || (channel / 4) % 2 == 1 => "[HT40+]",
+        _ => "[HT40-]",
+    }
+}
+
+fn oper_chwidth(width: WifiChannelWidth) -> u8 {
+    match width {
+        WifiChannelWidth::Mhz20 | WifiChannelWidth::Mhz40 => 0,
+        WifiChannelWidth::Mhz80 => 1,
+        WifiChannelWidth::Mhz160 => 2,
+    }
+}
+
+fn op_class_6g(width: WifiChannelWidth) -> u8 {
+    match width {
+        WifiChannelWidth::Mhz20 => 131,
+        WifiChannelWidth::Mhz40 => 132,
+        WifiChannelWidth::Mhz80 => 133,
+        WifiChannelWidth::Mhz160 => 134,
+    }
+}
+
+/// 80/160MHz 的中心信道, 自动选择信道时交由 hostapd 计算
+fn center_channel(band: WifiBand, channel: u8, width: WifiChannelWidth) -> Option<u8> {
+    if channel == 0 {
+        return None;
+    }
+    let span = match width {
+        WifiChannelWidth::Mhz20 => return (band == WifiBand::Band6G).then_some(channel),
+        WifiChannelWidth::Mhz40 => 8,
+        WifiChannelWidth::Mhz80 => 16,
+        WifiChannelWidth::Mhz160 => 32,
+    };
+    let first = match band {
+        WifiBand::Band2G => return None,
+        WifiBand::Band5G if channel >= 149 => 149,
+        WifiBand::Band5G => 36,
+        WifiBand::Band6G => 1,
+    };
+    if band == WifiBand::Band5G && width == WifiChannelWidth::Mhz40 {
+        return None;
+    }
+    let start = (channel - first) / span * span + first;
+    Some(start + span / 2 - 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::center_channel;
+    use landscape_common::config::wifi::{WifiBand, WifiChannelWidth};
+
+    #[test]
+    fn test_center_channel() {
+        assert_eq!(center_channel(WifiBand::Band5G, 36, WifiChannelWidth::Mhz80), Some(42));
+        assert_eq!(center_channel(WifiBand::Band5G, 112, WifiChannelWidth::Mhz80), Some(106));
+        assert_eq!(center_channel(WifiBand::Band5G, 157, WifiChannelWidth::Mhz80), Some(155));
+        assert_eq!(center_channel(WifiBand::Band5G, 44, WifiChannelWidth::Mhz160), Some(50));
+        assert_eq!(center_channel(WifiBand::Band5G, 120, WifiChannelWidth::Mhz160), Some(114));
+        assert_eq!(center_channel(WifiBand::Band6G, 37, WifiChannelWidth::Mhz80), Some(39));
+        assert_eq!(center_channel(WifiBand::Band6G, 5, WifiChannelWidth::Mhz40), Some(3));
+    }
+}
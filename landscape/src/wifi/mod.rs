@@ -1,4 +1,5 @@
 use landscape_common::database::LandscapeStore;
+use landscape_common::iface::dev_wifi::{WifiPhyCapability, WifiStationInfo};
 use landscape_common::{
     args::LAND_HOME_PATH,
     config::wifi::WifiServiceConfig,
@@ -19,8 +20,13 @@ use std::{
 };
 use tokio::sync::oneshot;
 
+use crate::iface::dev_wifi::{get_wifi_phy_capability, get_wifi_stations};
 use crate::iface::get_iface_by_name;
 
+pub mod hostapd;
+pub mod station;
+pub mod wpa_ctrl;
+
 #[derive(Clone, Default)]
 pub struct WifiService;
 
@@ -32,10 +38,13 @@ impl ServiceStarterTrait for WifiService {
         let service_status = WatchService::new();
 
         if config.enable {
-            if let Some(_) = get_iface_by_name(&config.iface_name).await {
+            if let Err(e) = config.validate() {
+                tracing::error!("invalid wifi config of {}: {e}", config.iface_name);
+            } else if let Some(_) = get_iface_by_name(&config.iface_name).await {
+                let hostapd_config = hostapd::render_hostapd_config(&config);
                 let status_clone = service_status.clone();
                 tokio::spawn(async move {
-                    create_wifi_service(config.iface_name, config.config, status_clone).await
+                    create_wifi_service(config.iface_name, hostapd_config, status_clone).await
                 });
             } else {
                 tracing::error!("Interface {} not found", config.iface_name);
@@ -217,4 +226,25 @@ impl WifiServiceManagerService {
         let store = store_service.wifi_service_store();
         Self { service, store }
     }
+
+    /// 读取接口所在物理网卡的能力
+    pub async fn get_capability(&self, iface_name: &str) -> Option<WifiPhyCapability> {
+        let wifi_iface = crate::get_all_wifi_devices().await.remove(iface_name)?;
+        get_wifi_phy_capability(wifi_iface.wiphy?).await
+    }
+
+    /// 列出所有 BSS 上已连接的客户端
+    pub async fn get_stations(&self, iface_name: String) -> Vec<WifiStationInfo> {
+        let Some(config) = self.get_config_by_name(iface_name).await else {
+            return vec![];
+        };
+        let mut result = vec![];
+        for index in 0..config.ap.bss.len().max(1) {
+            let bss_iface = config.bss_iface_name(index);
+            if let Some(iface) = get_iface_by_name(&bss_iface).await {
+                result.extend(get_wifi_stations(&bss_iface, iface.index).await);
+            }
+        }
+        result
+    }
 }
@@ -0,0 +1,399 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+
+use landscape_common::config::wifi::WifiSecurity;
+use landscape_common::config::wifi_station::{
+    WifiScanResult, WifiStationEvent, WifiStationEventKind, WifiStationNetwork,
+    WifiStationServiceConfig, WifiStationStatus,
+};
+use landscape_common::database::LandscapeStore;
+use landscape_common::service::{
+    controller::ControllerService,
+    manager::{ServiceManager, ServiceStarterTrait},
+    ServiceStatus, WatchService,
+};
+use landscape_common::utils::time::get_f64_timestamp;
+use landscape_common::{args::LAND_HOME_PATH, LANDSCAPE_WPA_SUPPLICANT_TMP_DIR};
+use landscape_database::provider::LandscapeDBServiceProvider;
+use landscape_database::wifi_station::repository::WifiStationServiceRepository;
+use tokio::sync::RwLock;
+
+use crate::iface::dev_wifi::{get_wifi_link, get_wifi_scan_results, get_wifi_stations};
+use crate::iface::get_iface_by_name;
+use crate::service::ipconfig::IfaceIpServiceManagerService;
+
+use super::wpa_ctrl::{WpaCtrl, SCAN_RESULTS_EVENT};
+
+/// 保留的连接事件数量
+const MAX_STATION_EVENTS: usize = 50;
+/// 连接状态检查间隔
+const LINK_CHECK_INTERVAL_SECS: u64 = 2;
+/// 等待扫描完成的超时
+const SCAN_TIMEOUT_SECS: u64 = 15;
+
+type StationStatusMap = Arc<RwLock<HashMap<String, WifiStationStatus>>>;
+
+#[derive(Clone)]
+pub struct WifiStationService {
+    ip_service: IfaceIpServiceManagerService,
+    status: StationStatusMap,
+}
+
+#[async_trait::async_trait]
+impl ServiceStarterTrait for WifiStationService {
+    type Config = WifiStationServiceConfig;
+
+    async fn start(&self, config: WifiStationServiceConfig) -> WatchService {
+        let service_status = WatchService::new();
+
+        if config.enable {
+            if let Some(iface) = get_iface_by_name(&config.iface_name).await {
+                let status_clone = service_status.clone();
+                let ip_service = self.ip_service.clone();
+                let status = self.status.clone();
+                tokio::spawn(async move {
+                    create_station_service(iface.index, config, status_clone, ip_service, status)
+                        .await
+                });
+            } else {
+                tracing::error!("Interface {} not found", config.iface_name);
+            }
+        }
+
+        service_status
+    }
+}
+
+async fn create_station_service(
+    ifindex: u32,
+    config: WifiStationServiceConfig,
+    service_status: WatchService,
+    ip_service: IfaceIpServiceManagerService,
+    status: StationStatusMap,
+) {
+    service_status.just_change_status(ServiceStatus::Staring);
+    let iface_name = config.iface_name.clone();
+
+    let ctrl_dir = supplicant_ctrl_dir();
+    let config_path =
+        LAND_HOME_PATH.join(LANDSCAPE_WPA_SUPPLICANT_TMP_DIR).join(format!("{}.conf", &iface_name));
+    if let Err(e) = std::fs::create_dir_all(&ctrl_dir).and_then(|_| {
+        std::fs::write(&config_path, render_supplicant_config(&ctrl_dir, &config.networks))
+    }) {
+        tracing::error!("wpa_supplicant 配置写入失败: {e:?}");
+        service_status.just_change_status(ServiceStatus::Stop);
+        return;
+    }
+
+    // 接口上已有 wpa_supplicant (如上次异常退出后残留) 时让其重新加载配置, 否则启动新进程
+    let mut child = None;
+    if let Err(e) = reconfigure_supplicant(&ctrl_dir, &iface_name).await {
+        tracing::debug!("no running wpa_supplicant on {iface_name}: {e:?}");
+        match tokio::process::Command::new("wpa_supplicant")
+            .args(["-D", "nl80211", "-i", &iface_name, "-c"])
+            .arg(&config_path)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+        {
+            Ok(c) => child = Some(c),
+            Err(e) => {
+                tracing::error!("启动 wpa_supplicant 失败: {e:?}");
+                let _ = std::fs::remove_file(&config_path);
+                service_status.just_change_status(ServiceStatus::Stop);
+                return;
+            }
+        }
+    } else {
+        tracing::info!("reuse running wpa_supplicant on {iface_name}");
+    }
+
+    let adopted = child.is_none();
+    status.write().await.insert(iface_name.clone(), WifiStationStatus::default());
+    service_status.just_change_status(ServiceStatus::Running);
+
+    let mut interval =
+        tokio::time::interval(tokio::time::Duration::from_secs(LINK_CHECK_INTERVAL_SECS));
+    let mut current_ssid: Option<String> = None;
+    let stop_wait = service_status.wait_to_stopping();
+    tokio::pin!(stop_wait);
+    loop {
+        tokio::select! {
+            _ = &mut stop_wait => break,
+            result = wait_child(&mut child) => {
+                tracing::error!("wpa_supplicant of {iface_name} exit: {result:?}");
+                push_event(&status, &iface_name, WifiStationEventKind::SupplicantExit).await;
+                break;
+            }
+            _ = interval.tick() => {
+                if adopted && !supplicant_alive(&ctrl_dir, &iface_name).await {
+                    tracing::error!("wpa_supplicant of {iface_name} exit");
+                    push_event(&status, &iface_name, WifiStationEventKind::SupplicantExit).await;
+                    break;
+                }
+                let link = get_wifi_link(ifindex).await;
+                let ap = get_wifi_stations(&iface_name, ifindex).await.into_iter().next();
+                let ssid = link.as_ref().map(|(ssid, _)| ssid.clone());
+
+                if let Some(info) = status.write().await.get_mut(&iface_name) {
+                    info.connected = link.is_some();
+                    info.ssid = ssid.clone();
+                    info.freq = link.and_then(|(_, freq)| freq);
+                    info.bssid = ap.as_ref().map(|ap| ap.mac);
+                    info.signal = ap.as_ref().and_then(|ap| ap.signal);
+                }
+
+                if ssid == current_ssid {
+                    continue;
+                }
+                if let Some(old_ssid) = current_ssid.take() {
+                    tracing::info!("{iface_name} disconnected from {old_ssid}");
+                    let event = WifiStationEventKind::Disconnected { ssid: old_ssid };
+                    push_event(&status, &iface_name, event).await;
+                }
+                if let Some(new_ssid) = ssid {
+                    tracing::info!("{iface_name} connected to {new_ssid}");
+                    let bssid = ap.map(|ap| ap.mac);
+                    let event = WifiStationEventKind::Connected { ssid: new_ssid.clone(), bssid };
+                    push_event(&status, &iface_name, event).await;
+                    current_ssid = Some(new_ssid);
+                    // 关联成功后重新启动 IP 配置服务, 以便 DHCP 客户端立即获取地址
+                    restart_ip_service(&ip_service, &iface_name).await;
+                }
+            }
+        }
+    }
+
+    match child.as_mut() {
+        Some(child) => {
+            let _ = child.kill().await;
+        }
+        None => {
+            if let Ok(ctrl) = WpaCtrl::open(&ctrl_dir, &iface_name) {
+                let _ = ctrl.request("TERMINATE").await;
+            }
+        }
+    }
+    let _ = std::fs::remove_file(&config_path);
+    if let Some(info) = status.write().await.get_mut(&iface_name) {
+        info.connected = false;
+        info.ssid = None;
+        info.bssid = None;
+        info.freq = None;
+        info.signal = None;
+    }
+    service_status.just_change_status(ServiceStatus::Stop);
+}
+
+fn supplicant_ctrl_dir() -> PathBuf {
+    LAND_HOME_PATH.join(LANDSCAPE_WPA_SUPPLICANT_TMP_DIR).join("ctrl")
+}
+
+async fn reconfigure_supplicant(ctrl_dir: &Path, iface_name: &str) -> std::io::Result<()> {
+    WpaCtrl::open(ctrl_dir, iface_name)?.command("RECONFIGURE").await
+}
+
+/// 等待自行启动的进程退出, 复用的进程没有句柄, 由连接状态检查负责
+async fn wait_child(
+    child: &mut Option<tokio::process::Child>,
+) -> std::io::Result<std::process::ExitStatus> {
+    match child {
+        Some(child) => child.wait().await,
+        None => std::future::pending().await,
+    }
+}
+
+async fn supplicant_alive(ctrl_dir: &Path, iface_name: &str) -> bool {
+    match WpaCtrl::open(ctrl_dir, iface_name) {
+        Ok(ctrl) => matches!(ctrl.request("PING").await.as_deref(), Ok("PONG")),
+        Err(_) => false,
+    }
+}
+
+/// 通过 wpa_supplicant 发起扫描并等待完成
+async fn trigger_scan(ctrl_dir: &Path, iface_name: &str) -> std::io::Result<()> {
+    let ctrl = WpaCtrl::open(ctrl_dir, iface_name)?;
+    ctrl.command("ATTACH").await?;
+    // 正在扫描时返回 FAIL-BUSY, 同样等待本轮结果
+    let result = match ctrl.request("SCAN").await? {
+        reply if reply == "OK" || reply == "FAIL-BUSY" => {
+            ctrl.wait_event(SCAN_RESULTS_EVENT, Duration::from_secs(SCAN_TIMEOUT_SECS)).await
+        }
+        reply => Err(std::io::Error::other(format!("SCAN: {reply}"))),
+    };
+    let _ = ctrl.request("DETACH").await;
+    result
+}
+
+async fn push_event(status: &StationStatusMap, iface_name: &str, kind: WifiStationEventKind) {
+    if let Some(info) = status.write().await.get_mut(iface_name) {
+        if info.events.len() >= MAX_STATION_EVENTS {
+            info.events.remove(0);
+        }
+        info.events.push(WifiStationEvent { time: get_f64_timestamp(), kind });
+    }
+}
+
+async fn restart_ip_service(ip_service: &IfaceIpServiceManagerService, iface_name: &str) {
+    if let Some(config) = ip_service.get_config_by_name(iface_name.to_string()).await {
+        tracing::info!("restart {iface_name} IfaceIp service after association");
+        let _ = ip_service.get_service().update_service(config).await;
+    }
+}
+
+/// 生成 wpa_supplicant 配置, SSID 使用十六进制避免转义问题
+pub fn render_supplicant_config(ctrl_dir: &Path, networks: &[WifiStationNetwork]) -> String {
+    let mut lines =
+        vec![format!("ctrl_interface={}", ctrl_dir.display()), "update_config=0".to_string()];
+    for network in networks.iter() {
+        lines.push(String::new());
+        lines.push("network={".to_string());
+        let ssid_hex: String = network.ssid.bytes().map(|b| format!("{b:02x}")).collect();
+        lines.push(format!("\tssid={ssid_hex}"));
+        if network.hidden {
+            lines.push("\tscan_ssid=1".to_string());
+        }
+        if let Some(bssid) = &network.bssid {
+            lines.push(format!("\tbssid={bssid}"));
+        }
+        lines.push(format!("\tpriority={}", network.priority));
+        match &network.security {
+            WifiSecurity::Open => lines.push("\tkey_mgmt=NONE".to_string()),
+            WifiSecurity::Wpa2Psk { .. } => lines.push("\tkey_mgmt=WPA-PSK".to_string()),
+            WifiSecurity::Wpa3Sae { .. } => {
+                lines.push("\tkey_mgmt=SAE".to_string());
+                lines.push("\tieee80211w=2".to_string());
+            }
+            WifiSecurity::Wpa2Wpa3 { .. } => {
+                lines.push("\tkey_mgmt=WPA-PSK SAE".to_string());
+                lines.push("\tieee80211w=1".to_string());
+            }
+        }
+        if let Some(passphrase) = network.security.passphrase() {
+            if passphrase.len() == 64 {
+                lines.push(format!("\tpsk={passphrase}"));
+            } else {
+                lines.push(format!("\tpsk=\"{passphrase}\""));
+            }
+        }
+        lines.push("}".to_string());
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+#[derive(Clone)]
+pub struct WifiStationServiceManagerService {
+    store: WifiStationServiceRepository,
+    service: ServiceManager<WifiStationService>,
+    status: StationStatusMap,
+}
+
+impl ControllerService for WifiStationServiceManagerService {
+    type Id = String;
+    type Config = WifiStationServiceConfig;
+    type DatabseAction = WifiStationServiceRepository;
+    type H = WifiStationService;
+
+    fn get_service(&self) -> &ServiceManager<Self::H> {
+        &self.service
+    }
+
+    fn get_repository(&self) -> &Self::DatabseAction {
+        &self.store
+    }
+}
+
+impl WifiStationServiceManagerService {
+    pub async fn new(
+        ip_service: IfaceIpServiceManagerService,
+        store_service: LandscapeDBServiceProvider,
+    ) -> Self {
+        let store = store_service.wifi_station_service_store();
+        let status: StationStatusMap = Arc::new(RwLock::new(HashMap::new()));
+        let server_starter = WifiStationService { ip_service, status: status.clone() };
+        let service = ServiceManager::init(store.list().await.unwrap(), server_starter).await;
+
+        // wpa_supplicant 会自行跟踪接口状态, 不跟随接口 Up 事件重启
+        let store = store_service.wifi_station_service_store();
+        Self { service, store, status }
+    }
+
+    pub async fn get_station_status(&self, iface_name: &str) -> Option<WifiStationStatus> {
+        self.status.read().await.get(iface_name).cloned()
+    }
+
+    /// 扫描需由 wpa_supplicant 发起, 未启用客户端服务时只能返回内核缓存的结果
+    pub async fn scan(&self, iface_name: &str) -> Option<Vec<WifiScanResult>> {
+        let iface = get_iface_by_name(iface_name).await?;
+        if let Err(e) = trigger_scan(&supplicant_ctrl_dir(), iface_name).await {
+            tracing::warn!("trigger scan on {iface_name} error: {e:?}, return cached results");
+        }
+        Some(get_wifi_scan_results(iface.index).await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use landscape_common::net::MacAddr;
+
+    use super::*;
+
+    fn network(ssid: &str, security: WifiSecurity) -> WifiStationNetwork {
+        WifiStationNetwork {
+            ssid: ssid.to_string(),
+            security,
+            hidden: false,
+            priority: 0,
+            bssid: None,
+        }
+    }
+
+    #[test]
+    fn test_render_supplicant_config() {
+        let networks = [
+            WifiStationNetwork {
+                hidden: true,
+                priority: 10,
+                bssid: Some(MacAddr(0, 0x11, 0x22, 0x33, 0x44, 0x55)),
+                ..network("Home \"5G\"", WifiSecurity::Wpa3Sae { passphrase: "password".into() })
+            },
+            network("Cafe", WifiSecurity::Open),
+            network("Office", WifiSecurity::Wpa2Psk { passphrase: "a".repeat(64) }),
+        ];
+        let expected = [
+            "ctrl_interface=/run/wpa_supplicant",
+            "update_config=0",
+            "",
+            "network={",
+            "\tssid=486f6d652022354722",
+            "\tscan_ssid=1",
+            "\tbssid=00:11:22:33:44:55",
+            "\tpriority=10",
+            "\tkey_mgmt=SAE",
+            "\tieee80211w=2",
+            "\tpsk=\"password\"",
+            "}",
+            "",
+            "network={",
+            "\tssid=43616665",
+            "\tpriority=0",
+            "\tkey_mgmt=NONE",
+            "}",
+            "",
+            "network={",
+            "\tssid=4f6666696365",
+            "\tpriority=0",
+            "\tkey_mgmt=WPA-PSK",
+            &format!("\tpsk={}", "a".repeat(64)),
+            "}",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(render_supplicant_config(Path::new("/run/wpa_supplicant"), &networks), expected);
+    }
+}
@@ -0,0 +1,75 @@
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use landscape_common::utils::id::gen_database_uuid;
+use tokio::net::UnixDatagram;
+
+/// 控制命令的应答超时
+const CTRL_REPLY_TIMEOUT_SECS: u64 = 5;
+/// 扫描完成事件
+pub const SCAN_RESULTS_EVENT: &str = "CTRL-EVENT-SCAN-RESULTS";
+
+/// wpa_supplicant 控制接口客户端, 对应 `ctrl_interface` 目录下以接口名命名的 socket
+pub struct WpaCtrl {
+    socket: UnixDatagram,
+    local: PathBuf,
+}
+
+impl WpaCtrl {
+    pub fn open(ctrl_dir: &Path, iface_name: &str) -> std::io::Result<Self> {
+        // 应答按发送方地址返回, 本端需绑定一个路径
+        let local = ctrl_dir.join(format!("landscape-{}", gen_database_uuid().simple()));
+        let socket = UnixDatagram::bind(&local)?;
+        let ctrl = WpaCtrl { socket, local };
+        ctrl.socket.connect(ctrl_dir.join(iface_name))?;
+        Ok(ctrl)
+    }
+
+    /// 发送命令并返回应答, 跳过 ATTACH 后收到的以 `<` 开头的事件消息
+    pub async fn request(&self, cmd: &str) -> std::io::Result<String> {
+        self.socket.send(cmd.as_bytes()).await?;
+        let mut buf = vec![0u8; 4096];
+        let reply = async {
+            loop {
+                let len = self.socket.recv(&mut buf).await?;
+                if buf.first() != Some(&b'<') {
+                    return Ok(String::from_utf8_lossy(&buf[..len]).trim_end().to_string());
+                }
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(CTRL_REPLY_TIMEOUT_SECS), reply)
+            .await
+            .map_err(|_| Error::new(ErrorKind::TimedOut, format!("{cmd} timeout")))?
+    }
+
+    /// 发送需应答 `OK` 的命令
+    pub async fn command(&self, cmd: &str) -> std::io::Result<()> {
+        match self.request(cmd).await? {
+            reply if reply == "OK" => Ok(()),
+            reply => Err(Error::other(format!("{cmd}: {reply}"))),
+        }
+    }
+
+    /// 等待指定事件, 需先 ATTACH
+    pub async fn wait_event(&self, event: &str, timeout: Duration) -> std::io::Result<()> {
+        let mut buf = vec![0u8; 4096];
+        let wait = async {
+            loop {
+                let len = self.socket.recv(&mut buf).await?;
+                if String::from_utf8_lossy(&buf[..len]).contains(event) {
+                    return Ok(());
+                }
+            }
+        };
+        tokio::time::timeout(timeout, wait)
+            .await
+            .map_err(|_| Error::new(ErrorKind::TimedOut, format!("wait {event} timeout")))?
+    }
+}
+
+impl Drop for WpaCtrl {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.local);
+    }
+}
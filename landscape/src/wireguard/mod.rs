@@ -0,0 +1,465 @@
+use std::net::IpAddr;
+
+use landscape_common::config::iface::IfaceZoneType;
+use landscape_common::config::wireguard::{WireGuardCidr, WireGuardServiceConfig};
+use landscape_common::database::LandscapeStore;
+use landscape_common::observer::IfaceObserverAction;
+use landscape_common::route::{LanRouteInfo, LanRouteMode, RouteTargetInfo};
+use landscape_common::service::{
+    controller::ControllerService,
+    manager::{ServiceManager, ServiceStarterTrait},
+    ServiceStatus, WatchService,
+};
+use landscape_database::iface::repository::NetIfaceRepository;
+use landscape_database::provider::LandscapeDBServiceProvider;
+use landscape_database::repository::Repository;
+use landscape_database::wireguard::repository::WireGuardServiceRepository;
+use tokio::sync::broadcast;
+
+use crate::dev::LandscapeInterface;
+use crate::iface::get_iface_by_name;
+use crate::route::IpRouteService;
+
+pub mod netlink;
+
+#[derive(Clone)]
+pub struct WireGuardService {
+    route_service: IpRouteService,
+    iface_store: NetIfaceRepository,
+}
+
+impl WireGuardService {
+    pub fn new(route_service: IpRouteService, iface_store: NetIfaceRepository) -> Self {
+        WireGuardService { route_service, iface_store }
+    }
+}
+
+#[async_trait::async_trait]
+impl ServiceStarterTrait for WireGuardService {
+    type Config = WireGuardServiceConfig;
+
+    async fn start(&self, config: WireGuardServiceConfig) -> WatchService {
+        let service_status = WatchService::new();
+
+        if config.enable {
+            if let Some(iface) = get_iface_by_name(&config.iface_name).await {
+                // 仅 WAN 区域的接口作为 Flow 的出口
+                let as_wan_target = self
+                    .iface_store
+                    .find_by_id(config.iface_name.clone())
+                    .await
+                    .ok()
+                    .flatten()
+                    .is_some_and(|iface| matches!(iface.zone_type, IfaceZoneType::Wan));
+                let status_clone = service_status.clone();
+                let route_service = self.route_service.clone();
+                tokio::spawn(async move {
+                    create_wireguard_service(
+                        iface,
+                        config,
+                        as_wan_target,
+                        status_clone,
+                        route_service,
+                    )
+                    .await
+                });
+            } else {
+                tracing::error!("Interface {} not found", config.iface_name);
+            }
+        }
+
+        service_status
+    }
+}
+
+async fn create_wireguard_service(
+    iface: LandscapeInterface,
+    config: WireGuardServiceConfig,
+    as_wan_target: bool,
+    service_status: WatchService,
+    route_service: IpRouteService,
+) {
+    service_status.just_change_status(ServiceStatus::Staring);
+    let iface_name = iface.name.clone();
+
+    if let Err(e) = netlink::set_device(&config).await {
+        tracing::error!("setting wireguard device {iface_name} error: {e}");
+        service_status.just_change_status(ServiceStatus::Stop);
+        return;
+    }
+
+    // 对端 AllowedIPs 中的网段需要路由到隧道, 默认路由交由 Flow 处理
+    let peer_routes: Vec<WireGuardCidr> = config
+        .peers
+        .iter()
+        .flat_map(|peer| peer.allowed_ips.iter())
+        .filter(|cidr| !cidr.is_default_route())
+        .cloned()
+        .collect();
+
+    if let Err(e) = setup_tunnel(&config, &iface_name, &peer_routes).await {
+        tracing::error!("setting wireguard tunnel {iface_name} error: {e}");
+        teardown_tunnel(&config, &iface_name, &peer_routes).await;
+        if let Err(e) = netlink::clear_peers(&iface_name).await {
+            tracing::error!("clear wireguard peers of {iface_name} error: {e}");
+        }
+        service_status.just_change_status(ServiceStatus::Stop);
+        return;
+    }
+
+    let ipv4_addr = config.addresses.iter().find(|addr| addr.ip.is_ipv4()).cloned();
+    let ipv6_addr = config.addresses.iter().find(|addr| addr.ip.is_ipv6()).cloned();
+    for addr in [ipv4_addr, ipv6_addr].into_iter().flatten() {
+        register_route(&iface, addr, as_wan_target, &route_service).await;
+    }
+
+    service_status.just_change_status(ServiceStatus::Running);
+    service_status.wait_to_stopping().await;
+
+    if ipv4_addr.is_some() {
+        if as_wan_target {
+            route_service.remove_ipv4_wan_route(&iface_name).await;
+            landscape_ebpf::map_setting::del_ipv4_wan_ip(iface.index);
+        }
+        route_service.remove_ipv4_lan_route(&iface_name).await;
+    }
+    if ipv6_addr.is_some() {
+        if as_wan_target {
+            route_service.remove_ipv6_wan_route(&iface_name).await;
+            landscape_ebpf::map_setting::del_ipv6_wan_ip(iface.index);
+        }
+        route_service.remove_ipv6_lan_route(&iface_name).await;
+    }
+
+    teardown_tunnel(&config, &iface_name, &peer_routes).await;
+    if let Err(e) = netlink::clear_peers(&iface_name).await {
+        tracing::error!("clear wireguard peers of {iface_name} error: {e}");
+    }
+    service_status.just_change_status(ServiceStatus::Stop);
+}
+
+/// 隧道为三层设备, 没有 MAC, 网关使用本端地址
+async fn register_route(
+    iface: &LandscapeInterface,
+    addr: WireGuardCidr,
+    as_wan_target: bool,
+    route_service: &IpRouteService,
+) {
+    let iface_name = iface.name.clone();
+    let lan_info = LanRouteInfo {
+        ifindex: iface.index,
+        iface_name: iface_name.clone(),
+        iface_ip: addr.ip,
+        mac: None,
+        prefix: addr.prefix,
+        mode: LanRouteMode::Reachable,
+    };
+    let target_info = RouteTargetInfo {
+        ifindex: iface.index,
+        weight: 1,
+        mac: None,
+        is_docker: false,
+        is_namespace: false,
+        tproxy: None,
+        iface_name: iface_name.clone(),
+        iface_ip: addr.ip,
+        default_route: false,
+        gateway_ip: addr.ip,
+    };
+
+    match addr.ip {
+        IpAddr::V4(ip) => {
+            route_service.insert_ipv4_lan_route(&iface_name, lan_info).await;
+            if as_wan_target {
+                landscape_ebpf::map_setting::add_ipv4_wan_ip(
+                    iface.index,
+                    ip,
+                    None,
+                    addr.prefix,
+                    None,
+                );
+                route_service.insert_ipv4_wan_route(&iface_name, target_info).await;
+            }
+        }
+        IpAddr::V6(ip) => {
+            let key = landscape_common::route::LanIPv6RouteKey {
+                iface_name: iface_name.clone(),
+                subnet_index: 0,
+            };
+            route_service.insert_ipv6_lan_route(key, lan_info).await;
+            if as_wan_target {
+                landscape_ebpf::map_setting::add_ipv6_wan_ip(
+                    iface.index,
+                    ip,
+                    None,
+                    addr.prefix,
+                    None,
+                );
+                route_service.insert_ipv6_wan_route(&iface_name, target_info).await;
+            }
+        }
+    }
+}
+
+async fn run_ip(args: &[&str]) -> Result<(), String> {
+    let output =
+        tokio::process::Command::new("ip").args(args).output().await.map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!("ip {}: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+/// 设置隧道的 MTU、地址与对端网段的路由
+async fn setup_tunnel(
+    config: &WireGuardServiceConfig,
+    iface_name: &str,
+    peer_routes: &[WireGuardCidr],
+) -> Result<(), String> {
+    if let Some(mtu) = config.mtu {
+        run_ip(&["link", "set", iface_name, "mtu", &mtu.to_string()]).await?;
+    }
+    for addr in config.addresses.iter() {
+        run_ip(&["addr", "replace", &addr.to_string(), "dev", iface_name]).await?;
+    }
+    for cidr in peer_routes.iter() {
+        set_kernel_route(cidr, iface_name, true).await?;
+    }
+    Ok(())
+}
+
+/// 移除 `setup_tunnel` 添加的路由与地址, 失败时继续清理其余项
+async fn teardown_tunnel(
+    config: &WireGuardServiceConfig,
+    iface_name: &str,
+    peer_routes: &[WireGuardCidr],
+) {
+    for cidr in peer_routes.iter() {
+        if let Err(e) = set_kernel_route(cidr, iface_name, false).await {
+            tracing::warn!("{e}");
+        }
+    }
+    for addr in config.addresses.iter() {
+        if let Err(e) = run_ip(&["addr", "del", &addr.to_string(), "dev", iface_name]).await {
+            tracing::warn!("{e}");
+        }
+    }
+}
+
+async fn set_kernel_route(cidr: &WireGuardCidr, iface_name: &str, add: bool) -> Result<(), String> {
+    let family = if cidr.ip.is_ipv4() { "-4" } else { "-6" };
+    let action = if add { "replace" } else { "del" };
+    run_ip(&[family, "route", action, &cidr.to_string(), "dev", iface_name]).await
+}
+
+/// 生成对端使用的 wg-quick 配置, 可直接转为二维码
+pub fn gen_peer_config(config: &WireGuardServiceConfig, peer_name: &str) -> Option<String> {
+    let peer = config.get_peer(peer_name)?;
+    let server_public_key = netlink::public_key_of(&config.private_key)?;
+
+    let join = |cidrs: &[WireGuardCidr]| {
+        cidrs.iter().map(|cidr| cidr.to_string()).collect::<Vec<_>>().join(", ")
+    };
+
+    let mut lines = vec!["[Interface]".to_string()];
+    lines.push(format!("PrivateKey = {}", peer.private_key.as_deref().unwrap_or("<PRIVATE_KEY>")));
+    if !peer.allowed_ips.is_empty() {
+        lines.push(format!("Address = {}", join(&peer.allowed_ips)));
+    }
+    if !peer.client_dns.is_empty() {
+        let dns: Vec<String> = peer.client_dns.iter().map(|ip| ip.to_string()).collect();
+        lines.push(format!("DNS = {}", dns.join(", ")));
+    }
+    if let Some(mtu) = config.mtu {
+        lines.push(format!("MTU = {mtu}"));
+    }
+
+    lines.push(String::new());
+    lines.push("[Peer]".to_string());
+    lines.push(format!("PublicKey = {server_public_key}"));
+    if let Some(psk) = &peer.preshared_key {
+        lines.push(format!("PresharedKey = {psk}"));
+    }
+    let allowed_ips = if peer.client_allowed_ips.is_empty() {
+        "0.0.0.0/0, ::/0".to_string()
+    } else {
+        join(&peer.client_allowed_ips)
+    };
+    lines.push(format!("AllowedIPs = {allowed_ips}"));
+    if let (Some(host), Some(port)) = (&config.public_endpoint, config.listen_port) {
+        if host.contains(':') && !host.starts_with('[') {
+            lines.push(format!("Endpoint = [{host}]:{port}"));
+        } else {
+            lines.push(format!("Endpoint = {host}:{port}"));
+        }
+    }
+    if let Some(keepalive) = peer.persistent_keepalive {
+        lines.push(format!("PersistentKeepalive = {keepalive}"));
+    }
+    lines.push(String::new());
+
+    Some(lines.join("\n"))
+}
+
+#[derive(Clone)]
+pub struct WireGuardServiceManagerService {
+    store: WireGuardServiceRepository,
+    service: ServiceManager<WireGuardService>,
+}
+
+impl ControllerService for WireGuardServiceManagerService {
+    type Id = String;
+    type Config = WireGuardServiceConfig;
+    type DatabseAction = WireGuardServiceRepository;
+    type H = WireGuardService;
+
+    fn get_service(&self) -> &ServiceManager<Self::H> {
+        &self.service
+    }
+
+    fn get_repository(&self) -> &Self::DatabseAction {
+        &self.store
+    }
+}
+
+impl WireGuardServiceManagerService {
+    pub async fn new(
+        route_service: IpRouteService,
+        store_service: LandscapeDBServiceProvider,
+        mut dev_observer: broadcast::Receiver<IfaceObserverAction>,
+    ) -> Self {
+        let store = store_service.wireguard_service_store();
+        let server_starter = WireGuardService::new(route_service, store_service.iface_store());
+        let service = ServiceManager::init(store.list().await.unwrap(), server_starter).await;
+
+        let service_clone = service.clone();
+        tokio::spawn(async move {
+            while let Ok(msg) = dev_observer.recv().await {
+                match msg {
+                    IfaceObserverAction::Up(iface_name) => {
+                        let Some(service_config) =
+                            store.find_by_id(iface_name.clone()).await.unwrap()
+                        else {
+                            continue;
+                        };
+                        tracing::info!("restart {iface_name} WireGuard service");
+                        let _ = service_clone.update_service(service_config).await;
+                    }
+                    IfaceObserverAction::Down(_) => {}
+                }
+            }
+        });
+
+        let store = store_service.wireguard_service_store();
+        Self { service, store }
+    }
+
+    /// 接口区域变化后重新启动, 以更新是否作为 Flow 出口
+    pub async fn restart_iface_service(&self, iface_name: String) {
+        if let Some(config) = self.get_config_by_name(iface_name).await {
+            let _ = self.service.update_service(config).await;
+        }
+    }
+
+    pub async fn get_peer_config(&self, iface_name: String, peer_name: String) -> Option<String> {
+        let config = self.get_config_by_name(iface_name).await?;
+        gen_peer_config(&config, &peer_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use landscape_common::config::wireguard::WireGuardPeerConfig;
+
+    use super::*;
+
+    /// RFC 7748 6.1 中 Alice 的密钥对
+    const PRIVATE_KEY: &str = "dwdtCnMYpX08FsFyUbJmRd9ML4frwJkqsXf7pR25LCo=";
+    const PUBLIC_KEY: &str = "hSDwCYkwp1R0i33ctD73Wg2/Og0mOBr066SpjqqbTmo=";
+
+    fn cidr(ip: &str, prefix: u8) -> WireGuardCidr {
+        WireGuardCidr { ip: ip.parse().unwrap(), prefix }
+    }
+
+    fn config(peer: WireGuardPeerConfig) -> WireGuardServiceConfig {
+        WireGuardServiceConfig {
+            iface_name: "wg0".to_string(),
+            enable: true,
+            private_key: PRIVATE_KEY.to_string(),
+            listen_port: Some(51820),
+            addresses: vec![cidr("10.8.0.1", 24)],
+            mtu: Some(1420),
+            public_endpoint: Some("2001:db8::1".to_string()),
+            peers: vec![peer],
+            update_at: 0.0,
+        }
+    }
+
+    fn peer() -> WireGuardPeerConfig {
+        WireGuardPeerConfig {
+            name: "phone".to_string(),
+            public_key: "AQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQE=".to_string(),
+            private_key: None,
+            preshared_key: None,
+            endpoint: None,
+            allowed_ips: vec![cidr("10.8.0.2", 32)],
+            persistent_keepalive: Some(25),
+            client_allowed_ips: vec![],
+            client_dns: vec!["10.8.0.1".parse().unwrap()],
+        }
+    }
+
+    #[test]
+    fn public_key_matches_x25519() {
+        assert_eq!(netlink::public_key_of(PRIVATE_KEY).as_deref(), Some(PUBLIC_KEY));
+
+        let pair = netlink::gen_key_pair();
+        assert_eq!(netlink::public_key_of(&pair.private_key), Some(pair.public_key));
+    }
+
+    #[test]
+    fn peer_config_render() {
+        let config = config(peer());
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            gen_peer_config(&config, "phone").unwrap(),
+            [
+                "[Interface]",
+                "PrivateKey = <PRIVATE_KEY>",
+                "Address = 10.8.0.2/32",
+                "DNS = 10.8.0.1",
+                "MTU = 1420",
+                "",
+                "[Peer]",
+                &format!("PublicKey = {PUBLIC_KEY}"),
+                "AllowedIPs = 0.0.0.0/0, ::/0",
+                "Endpoint = [2001:db8::1]:51820",
+                "PersistentKeepalive = 25",
+                "",
+            ]
+            .join("\n")
+        );
+        assert_eq!(gen_peer_config(&config, "laptop"), None);
+    }
+
+    #[test]
+    fn invalid_configs_are_rejected() {
+        let mut duplicated = config(peer());
+        duplicated.peers.push(WireGuardPeerConfig { name: "tablet".to_string(), ..peer() });
+        assert!(duplicated.validate().is_err());
+
+        let endpoint = WireGuardPeerConfig {
+            endpoint: Some("vpn.example.com".to_string()),
+            ..peer()
+        };
+        assert!(config(endpoint).validate().is_err());
+
+        let allowed_ips = WireGuardPeerConfig { allowed_ips: vec![cidr("10.8.0.2", 33)], ..peer() };
+        assert!(config(allowed_ips).validate().is_err());
+
+        let mut default_address = config(peer());
+        default_address.addresses = vec![cidr("0.0.0.0", 0)];
+        assert!(default_address.validate().is_err());
+    }
+}
@@ -0,0 +1,174 @@
+use std::net::SocketAddr;
+use std::time::UNIX_EPOCH;
+
+use futures::StreamExt;
+use genetlink::new_connection;
+use landscape_common::config::wireguard::{
+    decode_wg_key, encode_wg_key, WireGuardCidr, WireGuardKeyPair, WireGuardPeerRuntimeInfo,
+    WireGuardRuntimeInfo, WireGuardServiceConfig, WG_KEY_LEN,
+};
+use netlink_packet_core::{NetlinkMessage, NetlinkPayload, NLM_F_ACK, NLM_F_DUMP, NLM_F_REQUEST};
+use netlink_packet_generic::GenlMessage;
+use netlink_packet_wireguard::{
+    constants::{AF_INET, AF_INET6, WGDEVICE_F_REPLACE_PEERS, WGPEER_F_REPLACE_ALLOWEDIPS},
+    nlas::{WgAllowedIp, WgAllowedIpAttrs, WgDeviceAttrs, WgPeer, WgPeerAttrs},
+    Wireguard, WireguardCmd,
+};
+use x25519_dalek::{PublicKey, StaticSecret};
+
+/// 生成新的密钥对
+pub fn gen_key_pair() -> WireGuardKeyPair {
+    let private_key: [u8; WG_KEY_LEN] = rand::random();
+    let secret = StaticSecret::from(private_key);
+    let public_key = PublicKey::from(&secret);
+    WireGuardKeyPair {
+        private_key: encode_wg_key(&secret.to_bytes()),
+        public_key: encode_wg_key(public_key.as_bytes()),
+    }
+}
+
+/// 由私钥计算公钥
+pub fn public_key_of(private_key: &str) -> Option<String> {
+    let secret = StaticSecret::from(decode_wg_key(private_key)?);
+    Some(encode_wg_key(PublicKey::from(&secret).as_bytes()))
+}
+
+fn to_allowed_ip(cidr: &WireGuardCidr) -> WgAllowedIp {
+    let family = if cidr.ip.is_ipv4() { AF_INET } else { AF_INET6 };
+    WgAllowedIp(vec![
+        WgAllowedIpAttrs::Family(family),
+        WgAllowedIpAttrs::IpAddr(cidr.ip),
+        WgAllowedIpAttrs::Cidr(cidr.prefix),
+    ])
+}
+
+async fn resolve_endpoint(endpoint: &str) -> Option<SocketAddr> {
+    match tokio::net::lookup_host(endpoint).await {
+        Ok(mut addrs) => addrs.next(),
+        Err(e) => {
+            tracing::error!("resolve wireguard endpoint {endpoint} error: {e:?}");
+            None
+        }
+    }
+}
+
+async fn send_request(
+    nlas: Vec<WgDeviceAttrs>,
+    cmd: WireguardCmd,
+) -> Result<Vec<Wireguard>, String> {
+    let (connection, mut handle, _) = new_connection().map_err(|e| e.to_string())?;
+    tokio::spawn(connection);
+
+    let flags = match cmd {
+        WireguardCmd::GetDevice => NLM_F_REQUEST | NLM_F_DUMP,
+        WireguardCmd::SetDevice => NLM_F_REQUEST | NLM_F_ACK,
+    };
+    let mut nl_msg = NetlinkMessage::from(GenlMessage::from_payload(Wireguard { cmd, nlas }));
+    nl_msg.header.flags = flags;
+
+    let mut responses = handle.request(nl_msg).await.map_err(|e| e.to_string())?;
+    let mut result = vec![];
+    while let Some(msg) = responses.next().await {
+        let msg = msg.map_err(|e| e.to_string())?;
+        match msg.payload {
+            NetlinkPayload::InnerMessage(genl_msg) => result.push(genl_msg.payload),
+            NetlinkPayload::Error(e) if e.code.is_some() => return Err(e.to_io().to_string()),
+            _ => {}
+        }
+    }
+    Ok(result)
+}
+
+/// 依据配置设置 WireGuard 设备, 会替换已有的对端
+pub async fn set_device(config: &WireGuardServiceConfig) -> Result<(), String> {
+    let Some(private_key) = decode_wg_key(&config.private_key) else {
+        return Err("invalid private key".to_string());
+    };
+
+    let mut peers = Vec::with_capacity(config.peers.len());
+    for peer in config.peers.iter() {
+        let Some(public_key) = decode_wg_key(&peer.public_key) else {
+            tracing::error!("invalid public key of peer: {}", peer.name);
+            continue;
+        };
+        let mut attrs = vec![
+            WgPeerAttrs::PublicKey(public_key),
+            WgPeerAttrs::Flags(WGPEER_F_REPLACE_ALLOWEDIPS),
+            WgPeerAttrs::PersistentKeepalive(peer.persistent_keepalive.unwrap_or(0)),
+            WgPeerAttrs::AllowedIps(peer.allowed_ips.iter().map(to_allowed_ip).collect()),
+        ];
+        if let Some(psk) = peer.preshared_key.as_deref().and_then(decode_wg_key) {
+            attrs.push(WgPeerAttrs::PresharedKey(psk));
+        }
+        if let Some(endpoint) = &peer.endpoint {
+            if let Some(addr) = resolve_endpoint(endpoint).await {
+                attrs.push(WgPeerAttrs::Endpoint(addr));
+            }
+        }
+        peers.push(WgPeer(attrs));
+    }
+
+    let nlas = vec![
+        WgDeviceAttrs::IfName(config.iface_name.clone()),
+        WgDeviceAttrs::PrivateKey(private_key),
+        WgDeviceAttrs::ListenPort(config.listen_port.unwrap_or(0)),
+        WgDeviceAttrs::Flags(WGDEVICE_F_REPLACE_PEERS),
+        WgDeviceAttrs::Peers(peers),
+    ];
+    send_request(nlas, WireguardCmd::SetDevice).await.map(|_| ())
+}
+
+/// 清空对端
+pub async fn clear_peers(iface_name: &str) -> Result<(), String> {
+    let nlas = vec![
+        WgDeviceAttrs::IfName(iface_name.to_string()),
+        WgDeviceAttrs::Flags(WGDEVICE_F_REPLACE_PEERS),
+        WgDeviceAttrs::Peers(vec![]),
+    ];
+    send_request(nlas, WireguardCmd::SetDevice).await.map(|_| ())
+}
+
+/// 读取设备当前状态
+pub async fn get_device(iface_name: &str) -> Result<WireGuardRuntimeInfo, String> {
+    let nlas = vec![WgDeviceAttrs::IfName(iface_name.to_string())];
+    let msgs = send_request(nlas, WireguardCmd::GetDevice).await?;
+
+    let mut info = WireGuardRuntimeInfo::default();
+    // 对端较多时内核会分多条消息返回
+    for msg in msgs {
+        for nla in msg.nlas {
+            match nla {
+                WgDeviceAttrs::PublicKey(key) => info.public_key = Some(encode_wg_key(&key)),
+                WgDeviceAttrs::ListenPort(port) => info.listen_port = Some(port),
+                WgDeviceAttrs::Peers(peers) => {
+                    for WgPeer(attrs) in peers {
+                        let mut peer_info = WireGuardPeerRuntimeInfo::default();
+                        for attr in attrs {
+                            match attr {
+                                WgPeerAttrs::PublicKey(key) => {
+                                    peer_info.public_key = encode_wg_key(&key)
+                                }
+                                WgPeerAttrs::Endpoint(addr) => {
+                                    peer_info.endpoint = Some(addr.to_string())
+                                }
+                                WgPeerAttrs::LastHandshake(time) => {
+                                    peer_info.last_handshake = time
+                                        .duration_since(UNIX_EPOCH)
+                                        .ok()
+                                        .filter(|d| !d.is_zero())
+                                        .map(|d| d.as_secs_f64());
+                                }
+                                WgPeerAttrs::RxBytes(bytes) => peer_info.rx_bytes = bytes,
+                                WgPeerAttrs::TxBytes(bytes) => peer_info.tx_bytes = bytes,
+                                _ => {}
+                            }
+                        }
+                        info.peers.push(peer_info);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(info)
+}